@@ -0,0 +1,255 @@
+//! Monophonic audio-to-MIDI: pitch-track a WAV file and segment the result
+//! into notes for the piano roll.
+//!
+//! Pitch detection uses the YIN difference function on a downsampled mono
+//! signal, which is plenty for bass lines, vocals and single-note leads.
+
+use std::path::Path;
+
+use crate::action::{Action, PianoRollAction};
+use crate::state::ClipboardNote;
+
+/// Analysis sample rate; input is averaged down to roughly this
+const ANALYSIS_RATE: u32 = 11025;
+const WINDOW: usize = 1024;
+const HOP: usize = 256;
+/// Lowest and highest tracked pitches in Hz
+const MIN_FREQ: f32 = 50.0;
+const MAX_FREQ: f32 = 1500.0;
+/// YIN aperiodicity threshold
+const YIN_THRESHOLD: f32 = 0.15;
+/// Frames quieter than this RMS are treated as silence
+const GATE_RMS: f32 = 0.01;
+/// Notes shorter than this are dropped as glitches
+const MIN_NOTE_SECS: f32 = 0.06;
+/// Note positions snap to 1/32 notes
+const SNAPS_PER_BEAT: u32 = 8;
+
+/// A detected note, in seconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DetectedNote {
+    pub pitch: u8,
+    pub start: f32,
+    pub length: f32,
+    pub velocity: u8,
+}
+
+/// Read a WAV file as mono f32 samples plus its sample rate
+pub(crate) fn read_mono(path: &Path) -> Result<(Vec<f32>, u32), String> {
+    let mut reader = hound::WavReader::open(path).map_err(|e| e.to_string())?;
+    let spec = reader.spec();
+    let channels = spec.channels.max(1) as usize;
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>().map_err(|e| e.to_string())?,
+        hound::SampleFormat::Int => {
+            let scale = 1.0 / (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader.samples::<i32>()
+                .map(|s| s.map(|v| v as f32 * scale))
+                .collect::<Result<_, _>>()
+                .map_err(|e| e.to_string())?
+        }
+    };
+    let mono = interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect();
+    Ok((mono, spec.sample_rate))
+}
+
+/// Average blocks of samples to bring the rate near `ANALYSIS_RATE`
+fn downsample(samples: &[f32], rate: u32) -> (Vec<f32>, u32) {
+    let factor = (rate / ANALYSIS_RATE).max(1) as usize;
+    if factor == 1 {
+        return (samples.to_vec(), rate);
+    }
+    let out = samples.chunks(factor).map(|c| c.iter().sum::<f32>() / c.len() as f32).collect();
+    (out, rate / factor as u32)
+}
+
+/// YIN fundamental estimate for one window, in Hz
+fn yin_pitch(frame: &[f32], rate: u32) -> Option<f32> {
+    let max_lag = ((rate as f32 / MIN_FREQ) as usize).min(frame.len() / 2);
+    let min_lag = ((rate as f32 / MAX_FREQ) as usize).max(2);
+    if max_lag <= min_lag + 1 {
+        return None;
+    }
+    let n = frame.len() - max_lag;
+
+    let mut diff = vec![0.0f32; max_lag + 1];
+    for (lag, d) in diff.iter_mut().enumerate().skip(1) {
+        *d = (0..n).map(|i| {
+            let delta = frame[i] - frame[i + lag];
+            delta * delta
+        }).sum();
+    }
+
+    // Cumulative mean normalized difference
+    let mut cmnd = vec![1.0f32; max_lag + 1];
+    let mut running = 0.0;
+    for lag in 1..=max_lag {
+        running += diff[lag];
+        cmnd[lag] = if running > 0.0 { diff[lag] * lag as f32 / running } else { 1.0 };
+    }
+
+    let mut lag = min_lag;
+    while lag < max_lag {
+        if cmnd[lag] < YIN_THRESHOLD {
+            while lag + 1 < max_lag && cmnd[lag + 1] < cmnd[lag] {
+                lag += 1;
+            }
+            // Parabolic interpolation around the minimum
+            let (a, b, c) = (cmnd[lag - 1], cmnd[lag], cmnd[lag + 1]);
+            let denom = a - 2.0 * b + c;
+            let offset = if denom.abs() > f32::EPSILON { 0.5 * (a - c) / denom } else { 0.0 };
+            return Some(rate as f32 / (lag as f32 + offset));
+        }
+        lag += 1;
+    }
+    None
+}
+
+fn freq_to_midi(freq: f32) -> u8 {
+    (69.0 + 12.0 * (freq / 440.0).log2()).round().clamp(0.0, 127.0) as u8
+}
+
+/// Track pitch over `samples` and segment it into notes
+pub fn detect_notes(samples: &[f32], rate: u32) -> Vec<DetectedNote> {
+    let (signal, rate) = downsample(samples, rate);
+    let hop_secs = HOP as f32 / rate as f32;
+
+    // Per-frame (pitch, rms), None when silent or unpitched
+    let frames: Vec<Option<(u8, f32)>> = (0..signal.len().saturating_sub(WINDOW) / HOP + 1)
+        .map(|i| {
+            let frame = signal.get(i * HOP..i * HOP + WINDOW)?;
+            let rms = (frame.iter().map(|s| s * s).sum::<f32>() / WINDOW as f32).sqrt();
+            if rms < GATE_RMS {
+                return None;
+            }
+            yin_pitch(frame, rate).map(|f| (freq_to_midi(f), rms))
+        })
+        .collect();
+
+    let mut notes = Vec::new();
+    let mut current: Option<(u8, usize, f32)> = None; // (pitch, start frame, peak rms)
+    let flush = |pitch: u8, start: usize, end: usize, peak: f32, notes: &mut Vec<DetectedNote>| {
+        let length = (end - start) as f32 * hop_secs;
+        if length >= MIN_NOTE_SECS {
+            let velocity = (peak.sqrt() * 127.0).round().clamp(1.0, 127.0) as u8;
+            notes.push(DetectedNote { pitch, start: start as f32 * hop_secs, length, velocity });
+        }
+    };
+
+    for (i, frame) in frames.iter().enumerate() {
+        match (*frame, current) {
+            (Some((pitch, rms)), Some((cur, start, peak))) if pitch == cur => {
+                current = Some((cur, start, peak.max(rms)));
+            }
+            (Some((pitch, rms)), prev) => {
+                if let Some((cur, start, peak)) = prev {
+                    flush(cur, start, i, peak, &mut notes);
+                }
+                current = Some((pitch, i, rms));
+            }
+            (None, Some((cur, start, peak))) => {
+                flush(cur, start, i, peak, &mut notes);
+                current = None;
+            }
+            (None, None) => {}
+        }
+    }
+    if let Some((cur, start, peak)) = current {
+        flush(cur, start, frames.len(), peak, &mut notes);
+    }
+    notes
+}
+
+/// Analyze a WAV file into notes
+pub fn analyze_file(path: &Path) -> Result<Vec<DetectedNote>, String> {
+    let (samples, rate) = read_mono(path)?;
+    Ok(detect_notes(&samples, rate))
+}
+
+/// Convert detected notes to `(pitch, tick, duration, velocity)` at `bpm`,
+/// with ticks from the start of the file, snapped to 1/32 notes
+pub fn notes_to_ticks(notes: &[DetectedNote], bpm: f32, ticks_per_beat: u32) -> Vec<(u8, u32, u32, u8)> {
+    let snap_ticks = (ticks_per_beat / SNAPS_PER_BEAT).max(1);
+    let ticks_per_sec = bpm / 60.0 * ticks_per_beat as f32;
+    let snap = |t: f32| ((t * ticks_per_sec / snap_ticks as f32).round() as u32) * snap_ticks;
+    notes.iter()
+        .map(|n| {
+            let tick = snap(n.start);
+            let end = snap(n.start + n.length).max(tick + snap_ticks);
+            (n.pitch, tick, end - tick, n.velocity)
+        })
+        .collect()
+}
+
+/// One paste adding the notes to `track` from `start_tick`, leaving the notes
+/// already there alone; None when nothing was detected
+pub fn add_notes(notes: &[(u8, u32, u32, u8)], track: usize, start_tick: u32) -> Option<Action> {
+    let lowest = notes.iter().map(|&(pitch, ..)| pitch).min()?;
+    let notes = notes.iter()
+        .map(|&(pitch, tick, duration, velocity)| ClipboardNote {
+            tick_offset: tick,
+            pitch_offset: (pitch - lowest) as _,
+            duration,
+            velocity,
+        })
+        .collect();
+    Some(Action::PianoRoll(PianoRollAction::PasteNotes { track, anchor_tick: start_tick, anchor_pitch: lowest, notes }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(freq: f32, secs: f32, rate: u32) -> Vec<f32> {
+        (0..(secs * rate as f32) as usize)
+            .map(|i| 0.5 * (std::f32::consts::TAU * freq * i as f32 / rate as f32).sin())
+            .collect()
+    }
+
+    #[test]
+    fn detects_a440() {
+        let notes = detect_notes(&sine(440.0, 0.5, 44100), 44100);
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].pitch, 69);
+    }
+
+    #[test]
+    fn segments_notes_separated_by_silence() {
+        let rate = 44100;
+        let mut samples = sine(261.63, 0.3, rate);
+        samples.extend(vec![0.0; (0.2 * rate as f32) as usize]);
+        samples.extend(sine(392.0, 0.3, rate));
+        let pitches: Vec<u8> = detect_notes(&samples, rate).iter().map(|n| n.pitch).collect();
+        assert_eq!(pitches, vec![60, 67]);
+    }
+
+    #[test]
+    fn silence_yields_no_notes() {
+        assert!(detect_notes(&vec![0.0; 44100], 44100).is_empty());
+    }
+
+    #[test]
+    fn ticks_follow_tempo() {
+        let notes = [DetectedNote { pitch: 60, start: 0.5, length: 0.5, velocity: 100 }];
+        // At 120 bpm half a second is one beat
+        assert_eq!(notes_to_ticks(&notes, 120.0, 480), vec![(60, 480, 480, 100)]);
+        assert_eq!(notes_to_ticks(&notes, 120.0, 960), vec![(60, 960, 960, 100)]);
+    }
+
+    #[test]
+    fn detected_notes_are_added_not_toggled() {
+        assert!(add_notes(&[], 0, 0).is_none());
+        let action = add_notes(&[(64, 0, 240, 100), (60, 480, 240, 90)], 2, 1920);
+        match action {
+            Some(Action::PianoRoll(PianoRollAction::PasteNotes { track, anchor_tick, anchor_pitch, notes })) => {
+                assert_eq!((track, anchor_tick, anchor_pitch), (2, 1920, 60));
+                assert_eq!(notes[0].pitch_offset, 4);
+                assert_eq!(notes[1].tick_offset, 480);
+            }
+            _ => panic!("Expected PasteNotes"),
+        }
+    }
+}
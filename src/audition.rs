@@ -0,0 +1,149 @@
+//! Playing notes from the piano roll without running the transport.
+//!
+//! An `Audition` holds notes relative to its start and releases them as
+//! wall-clock time passes; main.rs polls it and dispatches the results.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::action::{Action, PianoRollAction};
+use crate::state::InstrumentId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuditionNote {
+    /// Ticks after the audition starts
+    pub offset_ticks: u32,
+    pub pitch: u8,
+    pub velocity: u8,
+    pub duration_ticks: u32,
+}
+
+/// Notes as (tick, duration, pitch, velocity), offset so the earliest
+/// starts at zero and sorted by start
+pub fn relative_notes(notes: impl IntoIterator<Item = (u32, u32, u8, u8)>) -> Vec<AuditionNote> {
+    let mut notes: Vec<_> = notes.into_iter().collect();
+    notes.sort_by_key(|n| n.0);
+    let start = notes.first().map(|n| n.0).unwrap_or(0);
+    notes.into_iter()
+        .map(|(tick, duration, pitch, velocity)| AuditionNote {
+            offset_ticks: tick - start,
+            pitch,
+            velocity,
+            duration_ticks: duration,
+        })
+        .collect()
+}
+
+/// Notes as (tick, duration, pitch, velocity) that sound at `tick`, cut
+/// to at most `window` ticks so scrubbing stays short
+pub fn scrub_notes(notes: impl IntoIterator<Item = (u32, u32, u8, u8)>, tick: u32, window: u32) -> Vec<AuditionNote> {
+    notes.into_iter()
+        .filter(|&(start, duration, _, _)| start <= tick && tick < start + duration)
+        .map(|(start, duration, pitch, velocity)| AuditionNote {
+            offset_ticks: 0,
+            pitch,
+            velocity,
+            duration_ticks: (start + duration - tick).min(window),
+        })
+        .collect()
+}
+
+pub struct Audition {
+    started: Instant,
+    secs_per_tick: f64,
+    instrument_id: InstrumentId,
+    track: usize,
+    pending: VecDeque<AuditionNote>,
+}
+
+impl Audition {
+    pub fn new(
+        notes: Vec<AuditionNote>,
+        bpm: f32,
+        ticks_per_beat: u32,
+        instrument_id: InstrumentId,
+        track: usize,
+        now: Instant,
+    ) -> Self {
+        Self {
+            started: now,
+            secs_per_tick: 60.0 / (bpm.max(1.0) as f64 * ticks_per_beat.max(1) as f64),
+            instrument_id,
+            track,
+            pending: notes.into(),
+        }
+    }
+
+    fn at(&self, ticks: u32) -> Duration {
+        Duration::from_secs_f64(ticks as f64 * self.secs_per_tick)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Notes due by `now`, as actions to dispatch
+    pub fn poll(&mut self, now: Instant) -> Vec<Action> {
+        let elapsed = now.saturating_duration_since(self.started);
+        let mut due = Vec::new();
+        while let Some(note) = self.pending.front().copied() {
+            if self.at(note.offset_ticks) > elapsed {
+                break;
+            }
+            self.pending.pop_front();
+            due.push(Action::PianoRoll(PianoRollAction::AuditionNote {
+                pitch: note.pitch,
+                velocity: note.velocity,
+                duration_secs: self.at(note.duration_ticks).as_secs_f32(),
+                instrument_id: self.instrument_id,
+                track: self.track,
+            }));
+        }
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notes_are_made_relative_and_sorted() {
+        let notes = relative_notes([(960, 240, 64, 90), (480, 480, 60, 100)]);
+        assert_eq!(notes[0], AuditionNote { offset_ticks: 0, pitch: 60, velocity: 100, duration_ticks: 480 });
+        assert_eq!(notes[1].offset_ticks, 480);
+    }
+
+    #[test]
+    fn scrub_plays_only_sounding_notes_briefly() {
+        let notes = scrub_notes([(0, 960, 60, 100), (480, 120, 64, 80), (960, 480, 67, 90)], 500, 120);
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[0].duration_ticks, 120);
+        // Cut to what is left of the note
+        assert_eq!(notes[1].duration_ticks, 100);
+        assert!(notes.iter().all(|n| n.offset_ticks == 0));
+    }
+
+    #[test]
+    fn notes_are_released_on_time() {
+        let start = Instant::now();
+        // 120 BPM: one beat (480 ticks) is half a second
+        let notes = relative_notes([(0, 480, 60, 100), (480, 480, 62, 100)]);
+        let mut audition = Audition::new(notes, 120.0, 480, 1, 0, start);
+        assert_eq!(audition.poll(start).len(), 1);
+        assert!(audition.poll(start + Duration::from_millis(400)).is_empty());
+        assert_eq!(audition.poll(start + Duration::from_millis(500)).len(), 1);
+        assert!(audition.is_done());
+    }
+
+    #[test]
+    fn timing_follows_the_project_resolution() {
+        let start = Instant::now();
+        // 120 BPM at 960 ticks per beat: 480 ticks is a quarter second
+        let notes = relative_notes([(0, 480, 60, 100), (480, 480, 62, 100)]);
+        let mut audition = Audition::new(notes, 120.0, 960, 1, 0, start);
+        assert_eq!(audition.poll(start).len(), 1);
+        assert!(audition.poll(start + Duration::from_millis(200)).is_empty());
+        assert_eq!(audition.poll(start + Duration::from_millis(250)).len(), 1);
+    }
+}
@@ -0,0 +1,81 @@
+//! Automation pickup at playhead jumps.
+//!
+//! The engine applies a lane when playback passes one of its points, so
+//! after starting mid-song, seeking, or wrapping a loop, a filter or level
+//! keeps whatever value it had last until the next point comes along.
+//! Watching the playhead from frame to frame catches those jumps, and the
+//! value of every enabled lane at the new tick is sent right away.
+
+use std::time::Instant;
+
+use crate::state::automation::AutomationTarget;
+use crate::state::AppState;
+
+/// Beats the playhead may run past what the elapsed time allows before it
+/// counts as a jump, covering frame hitches and feedback arriving in bursts
+const SLACK_BEATS: f64 = 0.5;
+
+#[derive(Debug, Default)]
+pub struct PlayheadWatch {
+    /// Playhead and time as of the last frame, while playing
+    last: Option<(u32, Instant)>,
+}
+
+impl PlayheadWatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tick to pick automation up from, when playback just started or the
+    /// playhead moved further than playing alone would take it
+    pub fn check(&mut self, playing: bool, playhead: u32, bpm: f32, ticks_per_beat: u32, now: Instant) -> Option<u32> {
+        if !playing {
+            self.last = None;
+            return None;
+        }
+        let jumped = match self.last {
+            None => true,
+            Some((last, at)) => {
+                let beats = now.duration_since(at).as_secs_f64() * bpm as f64 / 60.0;
+                let max_ahead = ((beats + SLACK_BEATS) * ticks_per_beat as f64).ceil() as u32;
+                playhead < last || playhead - last > max_ahead
+            }
+        };
+        self.last = Some((playhead, now));
+        jumped.then_some(playhead)
+    }
+}
+
+/// Value of every enabled lane at `tick`, in the lane's own units
+pub fn values_at(state: &AppState, tick: u32) -> Vec<(AutomationTarget, f32)> {
+    state.session.automation.lanes.iter()
+        .filter(|lane| lane.enabled)
+        .filter_map(|lane| lane.value_at(tick).map(|value| (lane.target.clone(), value)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn jumps_are_told_from_playing_on() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut watch = PlayheadWatch::new();
+
+        assert_eq!(watch.check(false, 0, 120.0, 480, at(0)), None);
+        // Starting mid-song picks up where it starts
+        assert_eq!(watch.check(true, 1920, 120.0, 480, at(0)), Some(1920));
+        // Playing on, a little ahead of the clock, is not a jump
+        assert_eq!(watch.check(true, 1920 + 32, 120.0, 480, at(16)), None);
+        assert_eq!(watch.check(true, 1920 + 200, 120.0, 480, at(32)), None);
+        // Seeking forward, and back
+        assert_eq!(watch.check(true, 7680, 120.0, 480, at(48)), Some(7680));
+        assert_eq!(watch.check(true, 480, 120.0, 480, at(64)), Some(480));
+        // Stopping forgets the playhead
+        assert_eq!(watch.check(false, 480, 120.0, 480, at(80)), None);
+        assert_eq!(watch.check(true, 480, 120.0, 480, at(96)), Some(480));
+    }
+}
@@ -0,0 +1,101 @@
+//! Visual offset for the playhead.
+//!
+//! On slow terminals the drawn playhead trails what is heard. The offset
+//! set in preferences is handed to the piano roll, tracker, arrangement,
+//! drum sequencer and keyboard strip, which draw their playhead or step
+//! highlight that far ahead. The transport itself is untouched.
+
+use crate::panes::pattern_secs;
+use crate::state::drum_sequencer::DrumSequencerState;
+use crate::state::AppState;
+
+/// Largest offset offered in preferences
+pub const MAX_OFFSET_MS: u32 = 200;
+
+/// Preferences adjust the offset by this much per press
+pub const OFFSET_STEP_MS: u32 = 5;
+
+fn beats_ahead(offset_ms: u32, bpm: f64) -> f64 {
+    offset_ms as f64 / 1000.0 * bpm / 60.0
+}
+
+/// `playhead` moved `ahead` ticks, wrapping inside the loop it is playing
+fn advance_tick(playhead: u32, ahead: u32, looped: Option<(u32, u32)>) -> u32 {
+    match looped {
+        Some((start, end)) if start <= playhead && playhead < end => {
+            start + (playhead - start + ahead) % (end - start)
+        }
+        _ => playhead + ahead,
+    }
+}
+
+/// How far ahead of the transport positions are drawn
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VisualOffset {
+    pub ms: u32,
+}
+
+impl VisualOffset {
+    pub fn new(ms: u32) -> Self {
+        Self { ms }
+    }
+
+    /// The playhead where it is drawn
+    pub fn playhead(self, state: &AppState) -> u32 {
+        let piano_roll = &state.session.piano_roll;
+        if self.ms == 0 || !piano_roll.playing {
+            return state.audio.playhead;
+        }
+        let beats = beats_ahead(self.ms, state.audio.bpm as f64);
+        let ahead = (beats * piano_roll.ticks_per_beat as f64).round() as u32;
+        let looped = (piano_roll.looping && piano_roll.loop_end > piano_roll.loop_start)
+            .then_some((piano_roll.loop_start, piano_roll.loop_end));
+        advance_tick(state.audio.playhead, ahead, looped)
+    }
+
+    /// A drum sequencer's current step where it is drawn
+    pub fn step(self, seq: &DrumSequencerState, state: &AppState) -> usize {
+        let length = seq.pattern().length;
+        if self.ms == 0 || !seq.playing || length == 0 {
+            return seq.current_step;
+        }
+        let step_secs = pattern_secs(1, state.audio.bpm as f32);
+        let ahead = (self.ms as f32 / 1000.0 / step_secs).round() as usize;
+        (seq.current_step + ahead) % length
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn playhead_wraps_inside_the_loop() {
+        // 50 ms at 120 BPM is a tenth of a beat
+        assert!((beats_ahead(50, 120.0) - 0.1).abs() < 1e-9);
+        assert_eq!(advance_tick(100, 48, None), 148);
+        assert_eq!(advance_tick(1900, 48, Some((0, 1920))), 28);
+        assert_eq!(advance_tick(2000, 48, Some((0, 1920))), 2048);
+    }
+
+    #[test]
+    fn drawn_positions_leave_the_state_alone() {
+        let mut state = AppState::new();
+        state.add_instrument(crate::state::SourceType::Kit);
+        state.audio.bpm = 120.0;
+        state.audio.playhead = 100;
+        state.session.piano_roll.playing = true;
+        let seq = state.instruments.instruments[0].drum_sequencer.as_mut().unwrap();
+        let length = seq.pattern().length;
+        seq.playing = true;
+        seq.current_step = length - 1;
+
+        // A sixteenth at 120 BPM lasts 125 ms
+        let offset = VisualOffset::new(125);
+        let seq = state.instruments.instruments[0].drum_sequencer.as_ref().unwrap();
+        assert_eq!(offset.step(seq, &state), 0);
+        assert!(offset.playhead(&state) > 100);
+        assert_eq!(VisualOffset::default().playhead(&state), 100);
+        assert_eq!(state.audio.playhead, 100);
+    }
+}
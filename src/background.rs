@@ -0,0 +1,158 @@
+//! What the main loop keeps running between key presses: note auditions
+//! from the piano roll and tracker, the drum sequencer's performance
+//! macros, the sclang process behind the live-coding console, the
+//! sample library's indexer and sample rate conversions.
+
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::action::Action;
+use crate::audition::Audition;
+use crate::panes::{PianoRollPane, SampleLibraryPane, SclangPane, SequencerPane, TrackerPane};
+use crate::perf_macros::PerformanceMacro;
+use crate::preferences::Preferences;
+use crate::sample_import::Conversion;
+use crate::sample_library::{self, IndexEvent, Indexer};
+use crate::sclang::Sclang;
+use crate::ui::PaneManager;
+
+pub struct Background {
+    audition: Option<Audition>,
+    perf_macro: Option<PerformanceMacro>,
+    /// Started the first time code is sent from the sclang pane
+    sclang: Option<Sclang>,
+    library_db: Option<PathBuf>,
+    library_indexer: Option<Indexer>,
+    conversions: Vec<Conversion>,
+}
+
+impl Background {
+    /// Show what the sample library has indexed, then refresh it in the background
+    pub fn start(prefs: &Preferences, panes: &mut PaneManager) -> Self {
+        let library_db = sample_library::db_path();
+        let mut library_indexer = None;
+        if let Some(db) = &library_db {
+            if let (Ok(samples), Some(library)) = (sample_library::load_all(db), panes.get_pane_mut::<SampleLibraryPane>("sample_library")) {
+                library.set_samples(samples);
+            }
+            let roots = prefs.sample_library_roots();
+            if !roots.is_empty() {
+                library_indexer = Some(Indexer::start(roots, db.clone()));
+            }
+        }
+        Self {
+            audition: None,
+            perf_macro: None,
+            sclang: None,
+            library_db,
+            library_indexer,
+            conversions: Vec::new(),
+        }
+    }
+
+    /// Cut off the audition and performance macro, as a panic does
+    pub fn stop_playback(&mut self) {
+        self.audition = None;
+        self.perf_macro = None;
+    }
+
+    /// Take auditions and macros the panes started, a new one replacing the old, and
+    /// return the actions due now
+    pub fn poll_playback(&mut self, panes: &mut PaneManager, now: Instant) -> Vec<Action> {
+        if let Some(started) = panes.get_pane_mut::<PianoRollPane>("piano_roll").and_then(|p| p.take_audition()) {
+            self.audition = Some(started);
+        }
+        if let Some(started) = panes.get_pane_mut::<TrackerPane>("tracker").and_then(|p| p.take_audition()) {
+            self.audition = Some(started);
+        }
+        if let Some(started) = panes.get_pane_mut::<SequencerPane>("sequencer").and_then(|p| p.take_macro()) {
+            self.perf_macro = Some(started);
+        }
+
+        let mut actions = Vec::new();
+        if let Some(current) = self.audition.as_mut() {
+            actions.extend(current.poll(now));
+            if current.is_done() {
+                self.audition = None;
+            }
+        }
+        // Sweeps land in automation when recording
+        if let Some(current) = self.perf_macro.as_mut() {
+            actions.extend(current.poll(now));
+            if current.is_done() {
+                self.perf_macro = None;
+            }
+        }
+        actions
+    }
+
+    /// Live-coding console: send entered code and show sclang's replies
+    pub fn serve_sclang(&mut self, panes: &mut PaneManager, server_address: &str) {
+        let Some(pane) = panes.get_pane_mut::<SclangPane>("sclang") else { return };
+        if let Some(code) = pane.take_code() {
+            if self.sclang.as_mut().map_or(true, |lang| !lang.is_running()) {
+                self.sclang = match Sclang::start(server_address) {
+                    Ok(lang) => Some(lang),
+                    Err(e) => {
+                        pane.push_output(&format!("ERROR: {}", e));
+                        None
+                    }
+                };
+            }
+            if let Some(Err(e)) = self.sclang.as_mut().map(|lang| lang.send(&code)) {
+                pane.push_output(&format!("ERROR: {}", e));
+            }
+        }
+        if let Some(lang) = self.sclang.as_mut() {
+            for line in lang.poll() {
+                pane.push_output(&line);
+            }
+        }
+        pane.set_running(self.sclang.as_mut().map_or(false, |lang| lang.is_running()));
+    }
+
+    /// Sample library: indexing progress and rescans
+    pub fn serve_library(&mut self, panes: &mut PaneManager, prefs: &Preferences) {
+        let Some(library) = panes.get_pane_mut::<SampleLibraryPane>("sample_library") else { return };
+        if let Some(indexer) = self.library_indexer.as_ref() {
+            for event in indexer.poll() {
+                library.index_event(&event);
+                if !matches!(event, IndexEvent::Progress { .. }) {
+                    self.library_indexer = None;
+                    if let Some(Ok(samples)) = self.library_db.as_deref().map(sample_library::load_all) {
+                        library.set_samples(samples);
+                    }
+                }
+            }
+        }
+        if library.take_rescan() {
+            match &self.library_db {
+                Some(db) => self.library_indexer = Some(Indexer::start(prefs.sample_library_roots(), db.clone())),
+                None => library.index_event(&IndexEvent::Failed("no config directory".to_string())),
+            }
+        }
+    }
+
+    /// Hold back a sample load while its file is converted to the server's
+    /// `rate`, replacing it with `Action::None`. Returns a status line.
+    pub fn convert_sample(&mut self, action: &mut Action, project: Option<&Path>, rate: u32, prefs: &Preferences) -> Option<String> {
+        let conversion = Conversion::start(action, project, rate, prefs.resample_quality)?;
+        let status = conversion.status.clone();
+        *action = Action::None;
+        self.conversions.push(conversion);
+        Some(status)
+    }
+
+    /// Sample loads whose conversion finished, with their status lines
+    pub fn poll_conversions(&mut self) -> Vec<(Action, Option<String>)> {
+        let mut done = Vec::new();
+        self.conversions.retain(|conversion| match conversion.poll() {
+            Some(finished) => {
+                done.push(finished);
+                false
+            }
+            None => true,
+        });
+        done
+    }
+}
@@ -0,0 +1,266 @@
+//! Batch export of drum patterns for sample packs.
+//!
+//! Each pattern of the selected drum machine that has steps is bounced in
+//! turn with the same render as the sequencer's bounce key, one pass at
+//! the current BPM, and the result is copied to a WAV named after the
+//! project, instrument and pattern. The pattern that was showing is
+//! selected again at the end.
+
+use std::path::{Path, PathBuf};
+
+use crate::action::{Action, SequencerAction};
+use crate::panes::pattern_secs;
+use crate::state::{AppState, InstrumentId};
+
+/// Pattern letters as the sequencer header shows them
+const PATTERN_NAMES: [&str; 4] = ["A", "B", "C", "D"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobState {
+    Waiting,
+    Rendering,
+    Done(PathBuf),
+    /// Nothing to render
+    Empty,
+    Failed(String),
+    Cancelled,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Job {
+    pub pattern: usize,
+    pub name: String,
+    pub state: JobState,
+}
+
+/// Where the running job is
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Phase {
+    /// Switching to the job's pattern
+    Select,
+    /// Bounce dispatched, waiting for the render to show up
+    Started,
+    /// Render running; its file is copied when it ends
+    Rendering,
+    /// All jobs ended; switching back to the original pattern
+    Restore,
+    Finished,
+}
+
+pub struct BatchExport {
+    instrument: InstrumentId,
+    jobs: Vec<Job>,
+    dir: PathBuf,
+    original_pattern: usize,
+    current: usize,
+    phase: Phase,
+    render_path: Option<PathBuf>,
+}
+
+/// Keep names filesystem-safe
+fn file_part(name: &str) -> String {
+    let cleaned: String = name.chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    if cleaned.is_empty() { "untitled".to_string() } else { cleaned }
+}
+
+/// Where pattern exports for a project go
+pub fn export_dir(project: Option<&Path>) -> Option<PathBuf> {
+    match project {
+        Some(project) => {
+            let stem = project.file_stem()?.to_string_lossy();
+            Some(project.with_file_name(format!("{}_patterns", stem)))
+        }
+        None => Some(dirs::data_dir()?.join("imbolc").join("patterns")),
+    }
+}
+
+impl BatchExport {
+    /// Jobs for every pattern of the selected drum machine. None when no
+    /// drum machine is selected.
+    pub fn new(state: &AppState) -> Option<Self> {
+        let inst = state.instruments.selected_instrument()?;
+        let seq = inst.drum_sequencer.as_ref()?;
+        let project = state.project.path.as_deref();
+        let dir = export_dir(project)?;
+        let prefix = format!(
+            "{}_{}",
+            file_part(&project.and_then(|p| p.file_stem()).map(|s| s.to_string_lossy().into_owned()).unwrap_or_default()),
+            file_part(&inst.name),
+        );
+        let jobs = seq.patterns.iter().enumerate()
+            .map(|(i, pattern)| {
+                let letter = PATTERN_NAMES.get(i).copied().unwrap_or("?");
+                let has_steps = pattern.steps.iter().any(|pad| pad.iter().take(pattern.length).any(|s| s.active));
+                Job {
+                    pattern: i,
+                    name: format!("{}_{}.wav", prefix, letter),
+                    state: if has_steps { JobState::Waiting } else { JobState::Empty },
+                }
+            })
+            .collect();
+        Some(Self {
+            instrument: inst.id,
+            jobs,
+            dir,
+            original_pattern: seq.current_pattern,
+            current: 0,
+            phase: Phase::Select,
+            render_path: None,
+        })
+    }
+
+    pub fn jobs(&self) -> &[Job] {
+        &self.jobs
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.phase == Phase::Finished
+    }
+
+    /// Stop after the render in progress; waiting jobs are dropped
+    pub fn cancel(&mut self) {
+        for job in &mut self.jobs {
+            if job.state == JobState::Waiting {
+                job.state = JobState::Cancelled;
+            }
+        }
+    }
+
+    /// Call every frame; returns the next action to dispatch, if any
+    pub fn poll(&mut self, state: &AppState) -> Option<Action> {
+        let seq = state.instruments.selected_instrument()
+            .filter(|inst| inst.id == self.instrument)
+            .and_then(|inst| inst.drum_sequencer.as_ref());
+        let Some(seq) = seq else {
+            // Pattern actions follow the selection, so a different one ends the batch
+            if self.phase == Phase::Rendering {
+                return None;
+            }
+            self.fail_rest("instrument deselected");
+            self.phase = Phase::Finished;
+            return None;
+        };
+
+        loop {
+            match self.phase {
+                Phase::Select => {
+                    let Some(idx) = self.jobs.iter().position(|j| j.state == JobState::Waiting) else {
+                        self.phase = Phase::Restore;
+                        continue;
+                    };
+                    self.current = idx;
+                    let pattern = self.jobs[idx].pattern;
+                    if let Some(step) = Self::step_toward(seq.current_pattern, pattern) {
+                        return Some(step);
+                    }
+                    if state.io.pending_render.is_some() {
+                        // Someone else's bounce; wait for it
+                        return None;
+                    }
+                    self.jobs[idx].state = JobState::Rendering;
+                    self.phase = Phase::Started;
+                    let length = seq.patterns[pattern].length;
+                    return Some(Action::Sequencer(SequencerAction::BouncePattern {
+                        instrument_id: self.instrument,
+                        duration_secs: pattern_secs(length, state.session.bpm as f32),
+                    }));
+                }
+                Phase::Started => {
+                    match &state.io.pending_render {
+                        Some(render) => {
+                            self.render_path = Some(render.path.clone());
+                            self.phase = Phase::Rendering;
+                        }
+                        None => {
+                            self.jobs[self.current].state = JobState::Failed("render did not start".into());
+                            self.phase = Phase::Select;
+                        }
+                    }
+                    return None;
+                }
+                Phase::Rendering => {
+                    if state.io.pending_render.is_some() {
+                        return None;
+                    }
+                    let rendered = self.render_path.take();
+                    self.jobs[self.current].state = match rendered.filter(|p| p.is_file()) {
+                        Some(path) => self.copy_out(&path, self.current),
+                        None => JobState::Failed("render was cancelled".into()),
+                    };
+                    self.phase = Phase::Select;
+                }
+                Phase::Restore => {
+                    if let Some(step) = Self::step_toward(seq.current_pattern, self.original_pattern) {
+                        return Some(step);
+                    }
+                    self.phase = Phase::Finished;
+                }
+                Phase::Finished => return None,
+            }
+        }
+    }
+
+    fn step_toward(from: usize, to: usize) -> Option<Action> {
+        match from.cmp(&to) {
+            std::cmp::Ordering::Less => Some(Action::Sequencer(SequencerAction::NextPattern)),
+            std::cmp::Ordering::Greater => Some(Action::Sequencer(SequencerAction::PrevPattern)),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    fn copy_out(&self, rendered: &Path, idx: usize) -> JobState {
+        let dest = self.dir.join(&self.jobs[idx].name);
+        let copied = std::fs::create_dir_all(&self.dir).and_then(|()| std::fs::copy(rendered, &dest));
+        match copied {
+            Ok(_) => JobState::Done(dest),
+            Err(e) => JobState::Failed(e.to_string()),
+        }
+    }
+
+    fn fail_rest(&mut self, reason: &str) {
+        for job in &mut self.jobs {
+            if matches!(job.state, JobState::Waiting | JobState::Rendering) {
+                job.state = JobState::Failed(reason.to_string());
+            }
+        }
+    }
+
+    /// One-line result for the status bar
+    pub fn summary(&self) -> String {
+        let done = self.jobs.iter().filter(|j| matches!(j.state, JobState::Done(_))).count();
+        let failed = self.jobs.iter().filter(|j| matches!(j.state, JobState::Failed(_))).count();
+        match failed {
+            0 => format!("Exported {} pattern(s) to {}", done, self.dir.display()),
+            n => format!("Exported {} pattern(s) to {}, {} failed", done, self.dir.display(), n),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::SourceType;
+
+    #[test]
+    fn empty_patterns_are_skipped() {
+        let mut state = AppState::new();
+        state.add_instrument(SourceType::Kit);
+        let batch = BatchExport::new(&state).unwrap();
+        assert!(!batch.jobs().is_empty());
+        assert!(batch.jobs().iter().all(|j| j.state == JobState::Empty));
+        assert!(batch.jobs()[0].name.ends_with("_A.wav"));
+    }
+
+    #[test]
+    fn steps_toward_the_target_pattern() {
+        assert!(matches!(BatchExport::step_toward(0, 2), Some(Action::Sequencer(SequencerAction::NextPattern))));
+        assert!(matches!(BatchExport::step_toward(3, 1), Some(Action::Sequencer(SequencerAction::PrevPattern))));
+        assert!(BatchExport::step_toward(1, 1).is_none());
+    }
+}
@@ -0,0 +1,85 @@
+//! Renaming many instruments or buses at once with find/replace, a prefix
+//! and numbering.
+//!
+//! With a find string, only names containing it are renamed; without one,
+//! every name is. Numbers count the renamed names in order, padded to the
+//! width of the start number as typed (`01` gives `01, 02, ...`).
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RenameRule {
+    pub find: String,
+    pub replace: String,
+    pub prefix: String,
+    /// First number and its zero-padded width
+    pub number_from: Option<(u32, usize)>,
+}
+
+impl RenameRule {
+    /// Start number from the numbering field; blank or invalid turns it off
+    pub fn parse_number(text: &str) -> Option<(u32, usize)> {
+        let text = text.trim();
+        text.parse().ok().map(|n| (n, text.len()))
+    }
+
+    pub fn is_noop(&self) -> bool {
+        self.find.is_empty() && self.prefix.is_empty() && self.number_from.is_none()
+    }
+
+    /// New name for each of `names`, or None where it is left alone
+    pub fn apply(&self, names: &[&str]) -> Vec<Option<String>> {
+        if self.is_noop() {
+            return vec![None; names.len()];
+        }
+        let mut renamed = 0;
+        names.iter().map(|name| {
+            if !self.find.is_empty() && !name.contains(self.find.as_str()) {
+                return None;
+            }
+            let base = if self.find.is_empty() { name.to_string() } else { name.replace(&self.find, &self.replace) };
+            let mut new_name = format!("{}{}", self.prefix, base);
+            if let Some((start, width)) = self.number_from {
+                new_name = format!("{} {:0width$}", new_name.trim_end(), start + renamed, width = width);
+            }
+            renamed += 1;
+            let new_name = new_name.trim().to_string();
+            (!new_name.is_empty() && new_name != *name).then_some(new_name)
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_replace_only_touches_matches() {
+        let rule = RenameRule { find: "Audio".into(), replace: "Vox".into(), ..Default::default() };
+        assert_eq!(
+            rule.apply(&["Audio 1", "Bass", "Audio 2"]),
+            vec![Some("Vox 1".to_string()), None, Some("Vox 2".to_string())],
+        );
+    }
+
+    #[test]
+    fn prefix_and_numbering_apply_to_all_without_find() {
+        let rule = RenameRule {
+            prefix: "Str ".into(),
+            number_from: RenameRule::parse_number("01"),
+            ..Default::default()
+        };
+        assert_eq!(
+            rule.apply(&["Violin", "Cello"]),
+            vec![Some("Str Violin 01".to_string()), Some("Str Cello 02".to_string())],
+        );
+    }
+
+    #[test]
+    fn empty_rule_and_unchanged_names_are_skipped() {
+        assert_eq!(RenameRule::default().apply(&["Lead"]), vec![None]);
+        let rule = RenameRule { find: "Lead".into(), replace: "Lead".into(), ..Default::default() };
+        assert_eq!(rule.apply(&["Lead"]), vec![None]);
+        let rule = RenameRule { find: "Lead".into(), ..Default::default() };
+        assert_eq!(rule.apply(&["Lead"]), vec![None]);
+        assert_eq!(RenameRule::parse_number("x"), None);
+    }
+}
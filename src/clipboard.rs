@@ -0,0 +1,211 @@
+//! Copying text to and from the system clipboard.
+//!
+//! An external tool (wl-copy, xclip, xsel or pbcopy) is used when one is
+//! installed. Otherwise copied text is sent to the terminal as an OSC 52
+//! escape, which most modern terminals, and tmux with `set-clipboard on`,
+//! pass on to the clipboard, including over SSH. Terminals rarely allow
+//! reading the clipboard back, so pasting needs one of the tools.
+//!
+//! Piano roll notes travel as plain text, one note per line, so they can
+//! be pasted between imbolc instances or edited in another program.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+
+use rat_widget::text::clipboard::{set_global_clipboard, Clipboard, ClipboardError};
+
+use crate::state::ClipboardNote;
+
+/// Clipboard tools tried in order, with their arguments
+const TOOLS: &[(&str, &[&str])] = &[
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+    ("pbcopy", &[]),
+];
+
+/// Tools that print the clipboard, in the same order
+const PASTE_TOOLS: &[(&str, &[&str])] = &[
+    ("wl-paste", &["--no-newline"]),
+    ("xclip", &["-selection", "clipboard", "-o"]),
+    ("xsel", &["--clipboard", "--output"]),
+    ("pbpaste", &[]),
+];
+
+/// First line of copied notes
+const NOTES_HEADER: &str = "# imbolc notes: tick pitch duration velocity";
+
+/// How the text reached the clipboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Tool(&'static str),
+    Osc52,
+}
+
+impl Method {
+    pub fn name(self) -> &'static str {
+        match self {
+            Method::Tool(name) => name,
+            Method::Osc52 => "terminal",
+        }
+    }
+}
+
+/// Copy `text` to the system clipboard
+pub fn copy(text: &str) -> std::io::Result<Method> {
+    for (tool, args) in TOOLS {
+        if copy_with_tool(tool, args, text) {
+            return Ok(Method::Tool(tool));
+        }
+    }
+    let mut stdout = std::io::stdout();
+    stdout.write_all(osc52(text).as_bytes())?;
+    stdout.flush()?;
+    Ok(Method::Osc52)
+}
+
+/// Text on the system clipboard, if a tool can read it
+pub fn paste() -> Option<String> {
+    PASTE_TOOLS.iter().find_map(|(tool, args)| {
+        let output = Command::new(tool).args(*args).stderr(Stdio::null()).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        String::from_utf8(output.stdout).ok()
+    })
+}
+
+/// Route Ctrl+C, Ctrl+X and Ctrl+V in every text field through the system clipboard
+pub fn install_for_text_fields() {
+    set_global_clipboard(TextFieldClipboard::default());
+}
+
+#[derive(Debug, Default)]
+struct TextFieldClipboard {
+    /// Last text copied, for pasting when no tool can read the clipboard
+    last: Mutex<String>,
+}
+
+impl Clipboard for TextFieldClipboard {
+    fn get_string(&self) -> Result<String, ClipboardError> {
+        match paste() {
+            // Text fields are single line
+            Some(text) => Ok(text.lines().next().unwrap_or_default().to_string()),
+            None => self.last.lock().map(|last| last.clone()).map_err(|_| ClipboardError),
+        }
+    }
+
+    fn set_string(&self, s: &str) -> Result<(), ClipboardError> {
+        if let Ok(mut last) = self.last.lock() {
+            *last = s.to_string();
+        }
+        copy(s).map(|_| ()).map_err(|_| ClipboardError)
+    }
+}
+
+/// Copied notes as text, one `tick pitch duration velocity` line per note.
+/// Tick and pitch are offsets from where the notes are pasted.
+pub fn notes_to_text(notes: &[ClipboardNote]) -> String {
+    let mut text = String::from(NOTES_HEADER);
+    for note in notes {
+        text.push_str(&format!(
+            "\n{} {:+} {} {}",
+            note.tick_offset, note.pitch_offset, note.duration, note.velocity,
+        ));
+    }
+    text.push('\n');
+    text
+}
+
+/// Notes from text written by `notes_to_text`; None for any other text
+pub fn notes_from_text(text: &str) -> Option<Vec<ClipboardNote>> {
+    let mut lines = text.lines();
+    if lines.next()?.trim() != NOTES_HEADER {
+        return None;
+    }
+    lines
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let mut fields = line.split_whitespace();
+            let tick_offset = fields.next()?.parse().ok()?;
+            let pitch_offset = fields.next()?.trim_start_matches('+').parse().ok()?;
+            let duration = fields.next()?.parse().ok()?;
+            let velocity = fields.next()?.parse().ok()?;
+            Some(ClipboardNote { tick_offset, pitch_offset, duration, velocity })
+        })
+        .collect()
+}
+
+fn copy_with_tool(tool: &str, args: &[&str], text: &str) -> bool {
+    let Ok(mut child) = Command::new(tool)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    else {
+        return false;
+    };
+    let written = child.stdin.take().map_or(false, |mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+    child.wait().map_or(false, |status| status.success()) && written
+}
+
+/// OSC 52 escape setting the clipboard to `text`
+fn osc52(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64(text.as_bytes()))
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = ((bytes[0] as u32) << 16) | ((bytes[1] as u32) << 8) | bytes[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_pads_partial_chunks() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn osc52_wraps_encoded_text() {
+        assert_eq!(osc52("hi"), "\x1b]52;c;aGk=\x07");
+    }
+
+    #[test]
+    fn notes_round_trip_as_text() {
+        let notes = vec![
+            ClipboardNote { tick_offset: 0, pitch_offset: 0, duration: 480, velocity: 100 },
+            ClipboardNote { tick_offset: 240, pitch_offset: -5, duration: 120, velocity: 64 },
+        ];
+        let text = notes_to_text(&notes);
+        assert!(text.contains("240 -5 120 64"));
+        let parsed = notes_from_text(&text).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[1].pitch_offset, -5);
+        assert_eq!(parsed[0].duration, 480);
+
+        assert!(notes_from_text("just some text").is_none());
+        assert!(notes_from_text(&format!("{}\n1 2", NOTES_HEADER)).is_none());
+    }
+}
@@ -0,0 +1,306 @@
+use crate::action::{Action, MixerAction, PianoRollAction};
+use crate::midi::{MidiEvent, MidiOutputManager};
+use crate::state::{AppState, MixerSelection};
+
+/// Number of channel strips on a Mackie Control unit (faders 0-7, master on 8)
+const STRIPS: usize = 8;
+const MASTER_FADER_CHANNEL: u8 = 8;
+
+/// Maximum value of a 14-bit pitch bend message (used for fader positions)
+const FADER_MAX: u16 = 16383;
+
+// Button note numbers from the Mackie Control protocol
+const NOTE_SOLO_BASE: u8 = 0x08;
+const NOTE_MUTE_BASE: u8 = 0x10;
+const NOTE_SELECT_BASE: u8 = 0x18;
+const NOTE_BANK_LEFT: u8 = 0x2E;
+const NOTE_BANK_RIGHT: u8 = 0x2F;
+const NOTE_CHANNEL_LEFT: u8 = 0x30;
+const NOTE_CHANNEL_RIGHT: u8 = 0x31;
+const NOTE_CYCLE: u8 = 0x56;
+const NOTE_STOP: u8 = 0x5D;
+const NOTE_PLAY: u8 = 0x5E;
+const NOTE_RECORD: u8 = 0x5F;
+
+/// Jog wheel relative CC
+const CC_JOG: u8 = 0x3C;
+/// Jog wheel detents per beat (each detent moves a 1/16 note)
+const JOG_STEPS_PER_BEAT: u32 = 4;
+
+/// Returns true if a MIDI port name looks like a Mackie Control / HUI surface.
+/// Other controllers keep going through the regular CC mapping path.
+pub fn is_mackie_port(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    ["mackie", "mcu", "x-touch", "hui", "qcon", "platform m"]
+        .iter()
+        .any(|pattern| lower.contains(pattern))
+}
+
+/// Translates MCU-style MIDI into mixer/transport actions and produces
+/// motorized-fader feedback for the current bank.
+pub struct ControlSurface {
+    pub enabled: bool,
+    /// First instrument index mapped to strip 0
+    bank_offset: usize,
+    /// Last fader positions sent to the surface (strips + master)
+    sent_faders: [Option<u16>; STRIPS + 1],
+}
+
+impl ControlSurface {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            bank_offset: 0,
+            sent_faders: [None; STRIPS + 1],
+        }
+    }
+
+    /// Enable or disable translation, e.g. after the connected port changes
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.bank_offset = 0;
+        self.invalidate_feedback();
+    }
+
+    /// Enable the surface when the connected input is a Mackie-style surface,
+    /// and open the output port with the same name for fader feedback
+    pub fn sync_port(&mut self, midi_output: &mut MidiOutputManager, state: &AppState) {
+        let port = state.midi.connected_port.as_deref().filter(|name| is_mackie_port(name));
+        match port {
+            Some(name) => {
+                midi_output.refresh_ports();
+                let idx = midi_output.list_ports().iter().position(|p| p.name == name);
+                if let Some(idx) = idx {
+                    let _ = midi_output.connect(idx);
+                }
+                self.set_enabled(true);
+            }
+            None => {
+                midi_output.disconnect();
+                self.set_enabled(false);
+            }
+        }
+    }
+
+    /// Force every fader to be re-sent on the next feedback pass
+    pub fn invalidate_feedback(&mut self) {
+        self.sent_faders = [None; STRIPS + 1];
+    }
+
+    fn strip_selection(&self, strip: usize, state: &AppState) -> Option<MixerSelection> {
+        let idx = self.bank_offset + strip;
+        (idx < state.instruments.instruments.len()).then_some(MixerSelection::Instrument(idx))
+    }
+
+    fn shift_bank(&mut self, delta: isize, state: &AppState) {
+        let count = state.instruments.instruments.len();
+        let max_offset = count.saturating_sub(1);
+        self.bank_offset = (self.bank_offset as isize + delta).clamp(0, max_offset as isize) as usize;
+        self.invalidate_feedback();
+    }
+
+    /// Translate a surface event. Returns `None` if the event isn't part of the
+    /// surface protocol and should fall through to regular MIDI handling.
+    pub fn translate(&mut self, event: &MidiEvent, state: &AppState) -> Option<Vec<Action>> {
+        if !self.enabled {
+            return None;
+        }
+
+        match event {
+            MidiEvent::PitchBend { channel, value } => {
+                let level = (*value as f32 / FADER_MAX as f32).clamp(0.0, 1.0);
+                let selection = if *channel == MASTER_FADER_CHANNEL {
+                    MixerSelection::Master
+                } else {
+                    match self.strip_selection(*channel as usize, state) {
+                        Some(sel) => sel,
+                        None => return Some(Vec::new()),
+                    }
+                };
+                // The fader already sits where the user put it; don't echo it back
+                let slot = if *channel == MASTER_FADER_CHANNEL { STRIPS } else { *channel as usize };
+                if slot <= STRIPS {
+                    self.sent_faders[slot] = Some(*value);
+                }
+                Some(vec![Action::Mixer(MixerAction::SetLevelAt(selection, level))])
+            }
+
+            MidiEvent::NoteOn { note, velocity, .. } => {
+                // Buttons send velocity 0 on release
+                if *velocity == 0 {
+                    return Some(Vec::new());
+                }
+                Some(self.translate_button(*note, state))
+            }
+
+            MidiEvent::NoteOff { .. } => Some(Vec::new()),
+
+            MidiEvent::ControlChange { controller, value, .. } if *controller == CC_JOG => {
+                // Relative encoding: bit 6 set means counter-clockwise
+                let steps = (*value & 0x3F) as i32;
+                let delta = if *value & 0x40 != 0 { -steps } else { steps };
+                let jog_ticks = (state.session.piano_roll.ticks_per_beat / JOG_STEPS_PER_BEAT) as i32;
+                Some(vec![Action::PianoRoll(PianoRollAction::SeekRelative(delta * jog_ticks))])
+            }
+
+            _ => None,
+        }
+    }
+
+    fn translate_button(&mut self, note: u8, state: &AppState) -> Vec<Action> {
+        let strip_button = |base: u8| (base..base + STRIPS as u8).contains(&note).then(|| (note - base) as usize);
+
+        if let Some(strip) = strip_button(NOTE_SOLO_BASE) {
+            return self.strip_action(strip, state, Some(MixerAction::ToggleSolo));
+        }
+        if let Some(strip) = strip_button(NOTE_MUTE_BASE) {
+            return self.strip_action(strip, state, Some(MixerAction::ToggleMute));
+        }
+        if let Some(strip) = strip_button(NOTE_SELECT_BASE) {
+            return self.strip_action(strip, state, None);
+        }
+
+        match note {
+            NOTE_BANK_LEFT => self.shift_bank(-(STRIPS as isize), state),
+            NOTE_BANK_RIGHT => self.shift_bank(STRIPS as isize, state),
+            NOTE_CHANNEL_LEFT => self.shift_bank(-1, state),
+            NOTE_CHANNEL_RIGHT => self.shift_bank(1, state),
+            NOTE_PLAY => {
+                if !state.session.piano_roll.playing {
+                    return vec![Action::PianoRoll(PianoRollAction::PlayStop)];
+                }
+            }
+            NOTE_STOP => {
+                if state.session.piano_roll.playing {
+                    return vec![Action::PianoRoll(PianoRollAction::PlayStop)];
+                }
+            }
+            NOTE_RECORD => return vec![Action::PianoRoll(PianoRollAction::PlayStopRecord)],
+            NOTE_CYCLE => return vec![Action::PianoRoll(PianoRollAction::ToggleLoop)],
+            _ => {}
+        }
+        Vec::new()
+    }
+
+    fn strip_action(&self, strip: usize, state: &AppState, then: Option<MixerAction>) -> Vec<Action> {
+        let Some(selection) = self.strip_selection(strip, state) else {
+            return Vec::new();
+        };
+        let mut actions = vec![Action::Mixer(MixerAction::SelectAt(selection))];
+        if let Some(action) = then {
+            actions.push(Action::Mixer(action));
+        }
+        actions
+    }
+
+    /// Pitch bend messages moving the surface faders to the current levels.
+    /// Only faders whose position changed since the last call are included.
+    pub fn fader_feedback(&mut self, state: &AppState) -> Vec<[u8; 3]> {
+        let mut messages = Vec::new();
+        if !self.enabled {
+            return messages;
+        }
+
+        for slot in 0..=STRIPS {
+            let level = if slot == STRIPS {
+                Some(state.session.mixer.master_level)
+            } else {
+                state.instruments.instruments.get(self.bank_offset + slot).map(|i| i.level)
+            };
+            // Strips past the last instrument park at the bottom
+            let value = (level.unwrap_or(0.0).clamp(0.0, 1.0) * FADER_MAX as f32).round() as u16;
+            if self.sent_faders[slot] == Some(value) {
+                continue;
+            }
+            self.sent_faders[slot] = Some(value);
+            let channel = if slot == STRIPS { MASTER_FADER_CHANNEL } else { slot as u8 };
+            messages.push([0xE0 | channel, (value & 0x7F) as u8, (value >> 7) as u8]);
+        }
+        messages
+    }
+}
+
+impl Default for ControlSurface {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn surface() -> ControlSurface {
+        let mut surface = ControlSurface::new();
+        surface.set_enabled(true);
+        surface
+    }
+
+    #[test]
+    fn detects_mackie_ports() {
+        assert!(is_mackie_port("Behringer X-Touch"));
+        assert!(is_mackie_port("MCU Pro Port 1"));
+        assert!(!is_mackie_port("Arturia KeyStep"));
+    }
+
+    #[test]
+    fn disabled_surface_passes_events_through() {
+        let mut surface = ControlSurface::new();
+        let state = AppState::new();
+        let event = MidiEvent::NoteOn { channel: 0, note: NOTE_PLAY, velocity: 127 };
+        assert!(surface.translate(&event, &state).is_none());
+    }
+
+    #[test]
+    fn master_fader_sets_master_level() {
+        let mut surface = surface();
+        let state = AppState::new();
+        let event = MidiEvent::PitchBend { channel: MASTER_FADER_CHANNEL, value: FADER_MAX };
+        let actions = surface.translate(&event, &state).unwrap();
+        assert!(matches!(
+            actions.as_slice(),
+            [Action::Mixer(MixerAction::SetLevelAt(MixerSelection::Master, level))] if (*level - 1.0).abs() < f32::EPSILON
+        ));
+    }
+
+    #[test]
+    fn play_button_starts_transport() {
+        let mut surface = surface();
+        let state = AppState::new();
+        let event = MidiEvent::NoteOn { channel: 0, note: NOTE_PLAY, velocity: 127 };
+        let actions = surface.translate(&event, &state).unwrap();
+        assert!(matches!(actions.as_slice(), [Action::PianoRoll(PianoRollAction::PlayStop)]));
+
+        // Release is consumed without producing an action
+        let release = MidiEvent::NoteOn { channel: 0, note: NOTE_PLAY, velocity: 0 };
+        assert!(surface.translate(&release, &state).unwrap().is_empty());
+    }
+
+    #[test]
+    fn jog_wheel_is_relative() {
+        let mut surface = surface();
+        let mut state = AppState::new();
+        let ticks_per_beat = state.session.piano_roll.ticks_per_beat as i32;
+        let ccw = MidiEvent::ControlChange { channel: 0, controller: CC_JOG, value: 0x41 };
+        let actions = surface.translate(&ccw, &state).unwrap();
+        assert!(matches!(actions.as_slice(), [Action::PianoRoll(PianoRollAction::SeekRelative(d))] if *d == -ticks_per_beat / 4));
+
+        // Detents are sixteenths at the project's resolution
+        state.session.piano_roll.ticks_per_beat = 960;
+        let cw = MidiEvent::ControlChange { channel: 0, controller: CC_JOG, value: 0x02 };
+        let actions = surface.translate(&cw, &state).unwrap();
+        assert!(matches!(actions.as_slice(), [Action::PianoRoll(PianoRollAction::SeekRelative(480))]));
+    }
+
+    #[test]
+    fn feedback_only_sends_changes() {
+        let mut surface = surface();
+        let state = AppState::new();
+        let first = surface.fader_feedback(&state);
+        assert_eq!(first.len(), STRIPS + 1);
+        assert!(surface.fader_feedback(&state).is_empty());
+
+        surface.invalidate_feedback();
+        assert_eq!(surface.fader_feedback(&state).len(), STRIPS + 1);
+    }
+}
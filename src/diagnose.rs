@@ -0,0 +1,135 @@
+//! "Why is this not sounding?" diagnostics for one instrument.
+//!
+//! `checks` walks the signal path from the server down to the master
+//! output and reports each link in order. The first failing link is
+//! usually the answer.
+
+use crate::state::{AppState, Instrument, OutputTarget};
+
+/// Engine-side facts the state alone can't tell us
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EngineFacts {
+    pub server_running: bool,
+    /// The instrument has a synth node in the engine's node map
+    pub node_exists: bool,
+    /// The instrument's sample buffer is loaded (true when it has none)
+    pub buffer_loaded: bool,
+}
+
+pub struct Check {
+    pub label: &'static str,
+    /// Plain-language explanation when this link is broken
+    pub problem: Option<String>,
+}
+
+impl Check {
+    fn new(label: &'static str, problem: Option<String>) -> Self {
+        Self { label, problem }
+    }
+}
+
+fn output_check(state: &AppState, inst: &Instrument) -> Option<String> {
+    let has_send = inst.sends.iter().any(|s| s.enabled && s.level > 0.0);
+    if inst.level <= 0.0 && !has_send {
+        return Some(format!("{}'s level is at zero and it has no active sends.", inst.name));
+    }
+    if let OutputTarget::Bus(id) = inst.output_target {
+        match state.session.mixer.buses.iter().find(|b| b.id == id) {
+            None => return Some(format!("{} outputs to bus {}, which doesn't exist.", inst.name, id)),
+            Some(bus) if bus.mute => return Some(format!("{} outputs to bus {} ({}), which is muted.", inst.name, id, bus.name)),
+            Some(bus) if bus.level <= 0.0 => return Some(format!("Bus {} ({}) has its level at zero.", id, bus.name)),
+            Some(_) => {}
+        }
+    }
+    None
+}
+
+fn solo_check(state: &AppState, inst: &Instrument) -> Option<String> {
+    let soloed_bus = |id: u8| state.session.mixer.buses.iter().any(|b| b.id == id && b.solo);
+    let any_solo = state.instruments.instruments.iter().any(|i| i.solo)
+        || state.session.mixer.buses.iter().any(|b| b.solo);
+    let heard = inst.solo
+        || inst.solo_safe
+        || matches!(inst.output_target, OutputTarget::Bus(id) if soloed_bus(id));
+    if any_solo && !heard {
+        Some(format!("Another channel is soloed, so {} is silenced.", inst.name))
+    } else {
+        None
+    }
+}
+
+/// Every link in the instrument's signal path, in order
+pub fn checks(state: &AppState, inst: &Instrument, facts: EngineFacts) -> Vec<Check> {
+    let mixer = &state.session.mixer;
+    let has_sample = match &inst.sampler_config {
+        Some(config) => config.sample_path.is_some(),
+        None => true,
+    };
+    vec![
+        Check::new("Audio server", (!facts.server_running).then(|| {
+            "The audio server is not running. Start it from the server pane (F5).".to_string()
+        })),
+        Check::new("Instrument active", (!inst.active).then(|| {
+            format!("{} is inactive. Enable it in the instrument editor.", inst.name)
+        })),
+        Check::new("Synth node", (facts.server_running && !facts.node_exists).then(|| {
+            format!("{} has no synth on the server. Try reloading the project or restarting the server.", inst.name)
+        })),
+        Check::new("Sample", if !has_sample {
+            Some(format!("{} is a sampler with no sample loaded.", inst.name))
+        } else if facts.server_running && !facts.buffer_loaded {
+            Some(format!("The sample for {} is not loaded on the server.", inst.name))
+        } else {
+            None
+        }),
+        Check::new("Mute", inst.mute.then(|| format!("{} is muted.", inst.name))),
+        Check::new("Solo", solo_check(state, inst)),
+        Check::new("Level and output", output_check(state, inst)),
+        Check::new("Master", if mixer.master_mute {
+            Some("The master output is muted.".to_string())
+        } else if mixer.master_level <= 0.0 {
+            Some("The master level is at zero.".to_string())
+        } else {
+            None
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::SourceType;
+
+    const RUNNING: EngineFacts = EngineFacts { server_running: true, node_exists: true, buffer_loaded: true };
+
+    fn first_problem(checks: &[Check]) -> Option<&'static str> {
+        checks.iter().find(|c| c.problem.is_some()).map(|c| c.label)
+    }
+
+    #[test]
+    fn server_down_is_reported_first() {
+        let mut state = AppState::new();
+        let id = state.add_instrument(SourceType::Saw);
+        let inst = state.instruments.instrument(id).unwrap();
+        let facts = EngineFacts { server_running: false, ..RUNNING };
+        assert_eq!(first_problem(&checks(&state, inst, facts)), Some("Audio server"));
+    }
+
+    #[test]
+    fn mute_and_solo_are_caught() {
+        let mut state = AppState::new();
+        let a = state.add_instrument(SourceType::Saw);
+        state.add_instrument(SourceType::Sin);
+        state.instruments.instruments[1].solo = true;
+        let inst = state.instruments.instrument(a).unwrap();
+        assert_eq!(first_problem(&checks(&state, inst, RUNNING)), Some("Solo"));
+
+        state.instruments.instruments[0].solo_safe = true;
+        let inst = state.instruments.instrument(a).unwrap();
+        assert_eq!(first_problem(&checks(&state, inst, RUNNING)), None);
+
+        state.instruments.instruments[0].mute = true;
+        let inst = state.instruments.instrument(a).unwrap();
+        assert_eq!(first_problem(&checks(&state, inst, RUNNING)), Some("Mute"));
+    }
+}
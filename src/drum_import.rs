@@ -0,0 +1,369 @@
+//! Drum pattern import from Hydrogen songs (.h2song) and LMMS
+//! beat/bassline tracks (.mmp).
+//!
+//! Both formats count 48 ticks to a quarter note, so a sixteenth step is
+//! 12 ticks. Instruments map to pads in file order and patterns fill the
+//! kit's pattern slots from the current one on. A pad's sample is loaded
+//! when its path resolves to a file, either as written, next to the song,
+//! or in the usual Hydrogen drumkit and LMMS sample folders. Velocities
+//! aren't carried over.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use roxmltree::{Document, Node};
+
+use crate::action::{Action, SequencerAction};
+use crate::state::drum_sequencer::{DrumSequencerState, NUM_PADS};
+
+/// Ticks per sixteenth step in both formats
+const TICKS_PER_STEP: u32 = 12;
+/// Ticks per bar; LMMS places beat/bassline patterns one bar apart
+const TICKS_PER_BAR: u32 = 192;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedPad {
+    pub name: String,
+    /// Sample file, when its path resolved
+    pub sample: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedPattern {
+    pub name: String,
+    /// (pad, step) hits
+    pub hits: BTreeSet<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DrumImport {
+    pub pads: Vec<ImportedPad>,
+    pub patterns: Vec<ImportedPattern>,
+}
+
+/// Actions building an import into a kit, with what was left out
+pub struct ImportPlan {
+    pub actions: Vec<Action>,
+    /// Names of the patterns imported, in slot order
+    pub patterns: Vec<String>,
+    pub samples: usize,
+    /// Instruments past the last pad
+    pub dropped_pads: Vec<String>,
+    pub dropped_patterns: usize,
+}
+
+impl ImportPlan {
+    pub fn summary(&self) -> String {
+        let mut text = format!("Imported {} ({} samples)", self.patterns.join(", "), self.samples);
+        if !self.dropped_pads.is_empty() {
+            text.push_str(&format!("; no pads left for {}", self.dropped_pads.join(", ")));
+        }
+        if self.dropped_patterns > 0 {
+            text.push_str(&format!("; {} patterns didn't fit", self.dropped_patterns));
+        }
+        text
+    }
+}
+
+pub fn load(path: &Path) -> Result<DrumImport, String> {
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+    if ext == "mmpz" {
+        return Err("compressed LMMS projects aren't supported; save as .mmp".to_string());
+    }
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let base = path.parent().unwrap_or(Path::new("."));
+    match ext.as_str() {
+        "h2song" => parse_hydrogen(&text, base),
+        "mmp" => parse_lmms(&text, base),
+        _ => Err("expected a .h2song or .mmp file".to_string()),
+    }
+}
+
+fn child<'a, 'i>(node: Node<'a, 'i>, name: &str) -> Option<Node<'a, 'i>> {
+    node.children().find(|n| n.has_tag_name(name))
+}
+
+fn child_text<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    child(node, name).and_then(|n| n.text()).map(str::trim)
+}
+
+fn tick_to_step(tick: f64) -> usize {
+    (tick.max(0.0) / TICKS_PER_STEP as f64).round() as usize
+}
+
+fn first_existing(candidates: impl IntoIterator<Item = PathBuf>) -> Option<PathBuf> {
+    candidates.into_iter().find(|p| p.is_file())
+}
+
+fn parse_hydrogen(text: &str, base: &Path) -> Result<DrumImport, String> {
+    let doc = Document::parse(text).map_err(|e| e.to_string())?;
+    let song = doc.root_element();
+    if !song.has_tag_name("song") {
+        return Err("not a Hydrogen song".to_string());
+    }
+
+    let song_kit = child_text(song, "drumkit_name");
+    let mut pad_for_id = HashMap::new();
+    let mut pads = Vec::new();
+    for inst in child(song, "instrumentList").into_iter().flat_map(|l| l.children()).filter(|n| n.has_tag_name("instrument")) {
+        let id = child_text(inst, "id").unwrap_or_default().to_string();
+        pad_for_id.insert(id, pads.len());
+        // Older songs keep the file on the instrument, newer ones on its layers
+        let filename = inst.descendants()
+            .find(|n| n.has_tag_name("filename"))
+            .and_then(|n| n.text())
+            .map(str::trim)
+            .filter(|f| !f.is_empty());
+        let kit_dir = child_text(inst, "drumkitPath").map(PathBuf::from);
+        let kit = child_text(inst, "drumkit").or(song_kit);
+        pads.push(ImportedPad {
+            name: child_text(inst, "name").unwrap_or_default().to_string(),
+            sample: filename.and_then(|f| resolve_hydrogen_sample(f, base, kit_dir.as_deref(), kit)),
+        });
+    }
+
+    let mut patterns = Vec::new();
+    for pattern in child(song, "patternList").into_iter().flat_map(|l| l.children()).filter(|n| n.has_tag_name("pattern")) {
+        let mut hits = BTreeSet::new();
+        for note in child(pattern, "noteList").into_iter().flat_map(|l| l.children()).filter(|n| n.has_tag_name("note")) {
+            let pad = child_text(note, "instrument").and_then(|id| pad_for_id.get(id));
+            let tick = child_text(note, "position").and_then(|p| p.parse::<f64>().ok());
+            if let (Some(&pad), Some(tick)) = (pad, tick) {
+                hits.insert((pad, tick_to_step(tick)));
+            }
+        }
+        patterns.push(ImportedPattern {
+            name: child_text(pattern, "name").unwrap_or_default().to_string(),
+            hits,
+        });
+    }
+    Ok(DrumImport { pads, patterns })
+}
+
+fn resolve_hydrogen_sample(filename: &str, base: &Path, kit_dir: Option<&Path>, kit: Option<&str>) -> Option<PathBuf> {
+    let file = Path::new(filename);
+    if file.is_absolute() {
+        return first_existing([file.to_path_buf()]);
+    }
+    let mut candidates = vec![base.join(file)];
+    if let Some(dir) = kit_dir {
+        candidates.push(dir.join(file));
+    }
+    if let Some(kit) = kit {
+        let mut roots = Vec::new();
+        if let Some(home) = dirs::home_dir() {
+            roots.push(home.join(".hydrogen/data/drumkits"));
+        }
+        roots.push(PathBuf::from("/usr/share/hydrogen/data/drumkits"));
+        roots.push(PathBuf::from("/usr/local/share/hydrogen/data/drumkits"));
+        candidates.extend(roots.into_iter().map(|root| root.join(kit).join(file)));
+    }
+    first_existing(candidates)
+}
+
+fn parse_lmms(text: &str, base: &Path) -> Result<DrumImport, String> {
+    let doc = Document::parse(text).map_err(|e| e.to_string())?;
+    let root = doc.root_element();
+    if !root.has_tag_name("lmms-project") {
+        return Err("not an LMMS project".to_string());
+    }
+    let song_tracks = child(root, "song")
+        .and_then(|song| child(song, "trackcontainer"))
+        .ok_or("LMMS project has no song")?;
+
+    // Beat/bassline tracks in the song name the patterns; their
+    // instruments all live in the first one's container
+    let bb_tracks: Vec<Node> = song_tracks.children()
+        .filter(|n| n.has_tag_name("track") && n.attribute("type") == Some("1"))
+        .collect();
+    let Some(container) = bb_tracks.iter()
+        .filter_map(|t| child(*t, "bbtrack"))
+        .find_map(|bb| child(bb, "trackcontainer"))
+    else {
+        return Err("no beat/bassline patterns in this project".to_string());
+    };
+
+    let mut patterns: Vec<ImportedPattern> = bb_tracks.iter()
+        .map(|t| ImportedPattern { name: t.attribute("name").unwrap_or_default().to_string(), hits: BTreeSet::new() })
+        .collect();
+    let mut pads = Vec::new();
+    for track in container.children().filter(|n| n.has_tag_name("track") && n.attribute("type") == Some("0")) {
+        let pad = pads.len();
+        let sample = track.descendants()
+            .find(|n| n.has_tag_name("audiofileprocessor"))
+            .and_then(|n| n.attribute("src"))
+            .filter(|src| !src.is_empty())
+            .and_then(|src| resolve_lmms_sample(src, base));
+        pads.push(ImportedPad { name: track.attribute("name").unwrap_or_default().to_string(), sample });
+
+        for pattern in track.children().filter(|n| n.has_tag_name("pattern")) {
+            let pos: u32 = pattern.attribute("pos").and_then(|p| p.parse().ok()).unwrap_or(0);
+            let idx = (pos / TICKS_PER_BAR) as usize;
+            while patterns.len() <= idx {
+                patterns.push(ImportedPattern { name: format!("Beat/Bassline {}", patterns.len()), hits: BTreeSet::new() });
+            }
+            for note in pattern.children().filter(|n| n.has_tag_name("note")) {
+                if let Some(tick) = note.attribute("pos").and_then(|p| p.parse::<f64>().ok()) {
+                    patterns[idx].hits.insert((pad, tick_to_step(tick)));
+                }
+            }
+        }
+    }
+    Ok(DrumImport { pads, patterns })
+}
+
+fn resolve_lmms_sample(src: &str, base: &Path) -> Option<PathBuf> {
+    // Newer projects prefix where the sample came from
+    let src = src.strip_prefix("factorysample:").or_else(|| src.strip_prefix("userfile:")).unwrap_or(src);
+    let file = Path::new(src);
+    if file.is_absolute() {
+        return first_existing([file.to_path_buf()]);
+    }
+    let mut candidates = vec![base.join(file)];
+    if let Some(home) = dirs::home_dir() {
+        candidates.push(home.join("lmms/samples").join(file));
+        candidates.push(home.join("Documents/lmms/samples").join(file));
+    }
+    candidates.push(PathBuf::from("/usr/share/lmms/samples").join(file));
+    candidates.push(PathBuf::from("/usr/local/share/lmms/samples").join(file));
+    first_existing(candidates)
+}
+
+/// Actions writing `import` into `seq`: samples onto pads, then each
+/// pattern into a slot from the current one on, returning to it after.
+/// Hits past a slot's length are dropped.
+pub fn plan(import: &DrumImport, seq: &DrumSequencerState) -> ImportPlan {
+    let mut actions = Vec::new();
+    let mut samples = 0;
+    for (pad, imported) in import.pads.iter().enumerate().take(NUM_PADS) {
+        if let Some(path) = &imported.sample {
+            actions.push(Action::Sequencer(SequencerAction::LoadSampleResult(pad, path.clone())));
+            samples += 1;
+        }
+    }
+
+    let start = seq.current_pattern;
+    let slots = seq.patterns.len().saturating_sub(start);
+    let count = import.patterns.len().min(slots);
+    for (i, pattern) in import.patterns.iter().take(count).enumerate() {
+        if i > 0 {
+            actions.push(Action::Sequencer(SequencerAction::NextPattern));
+        }
+        actions.push(Action::Sequencer(SequencerAction::ClearPattern));
+        let length = seq.patterns[start + i].length;
+        actions.extend(pattern.hits.iter()
+            .filter(|(pad, step)| *pad < NUM_PADS && *step < length)
+            .map(|&(pad, step)| Action::Sequencer(SequencerAction::ToggleStep(pad, step))));
+    }
+    for _ in 1..count {
+        actions.push(Action::Sequencer(SequencerAction::PrevPattern));
+    }
+
+    ImportPlan {
+        actions,
+        patterns: import.patterns.iter().take(count).map(|p| p.name.clone()).collect(),
+        samples,
+        dropped_pads: import.pads.iter().skip(NUM_PADS).map(|p| p.name.clone()).collect(),
+        dropped_patterns: import.patterns.len() - count,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{AppState, SourceType};
+
+    const H2SONG: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<song>
+  <drumkit_name>GMRockKit</drumkit_name>
+  <instrumentList>
+    <instrument><id>0</id><name>Kick</name><filename>kick.wav</filename></instrument>
+    <instrument><id>3</id><name>Snare</name>
+      <instrumentComponent><layer><filename>snare.wav</filename></layer></instrumentComponent>
+    </instrument>
+  </instrumentList>
+  <patternList>
+    <pattern>
+      <name>Verse</name>
+      <size>192</size>
+      <noteList>
+        <note><position>0</position><instrument>0</instrument><velocity>0.8</velocity></note>
+        <note><position>48</position><instrument>3</instrument><velocity>1</velocity></note>
+        <note><position>96</position><instrument>0</instrument><velocity>0.8</velocity></note>
+      </noteList>
+    </pattern>
+  </patternList>
+</song>"#;
+
+    const MMP: &str = r#"<?xml version="1.0"?>
+<lmms-project version="1.0">
+  <song>
+    <trackcontainer>
+      <track type="1" name="Beat A">
+        <bbtrack>
+          <trackcontainer>
+            <track type="0" name="Kick">
+              <instrumenttrack><instrument name="audiofileprocessor"><audiofileprocessor src="kick.wav"/></instrument></instrumenttrack>
+              <pattern pos="0" steps="16"><note pos="0" key="57" vol="100"/><note pos="96" key="57" vol="100"/></pattern>
+              <pattern pos="192" steps="16"><note pos="12" key="57" vol="100"/></pattern>
+            </track>
+            <track type="0" name="Hat">
+              <pattern pos="0" steps="16"><note pos="24" key="57" vol="100"/></pattern>
+            </track>
+          </trackcontainer>
+        </bbtrack>
+      </track>
+      <track type="1" name="Beat B"><bbtrack/></track>
+    </trackcontainer>
+  </song>
+</lmms-project>"#;
+
+    #[test]
+    fn hydrogen_notes_map_to_pads_by_instrument() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("snare.wav"), b"").unwrap();
+        let import = parse_hydrogen(H2SONG, dir.path()).unwrap();
+
+        assert_eq!(import.pads.len(), 2);
+        assert_eq!(import.pads[0].sample, None);
+        assert_eq!(import.pads[1].sample, Some(dir.path().join("snare.wav")));
+        assert_eq!(import.patterns[0].name, "Verse");
+        assert_eq!(import.patterns[0].hits.iter().copied().collect::<Vec<_>>(), vec![(0, 0), (0, 8), (1, 4)]);
+    }
+
+    #[test]
+    fn lmms_patterns_follow_bar_positions() {
+        let dir = tempfile::tempdir().unwrap();
+        let import = parse_lmms(MMP, dir.path()).unwrap();
+
+        assert_eq!(import.pads.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), vec!["Kick", "Hat"]);
+        assert_eq!(import.patterns.len(), 2);
+        assert_eq!(import.patterns[1].name, "Beat B");
+        assert!(import.patterns[0].hits.contains(&(1, 2)));
+        assert_eq!(import.patterns[1].hits.iter().copied().collect::<Vec<_>>(), vec![(0, 1)]);
+    }
+
+    #[test]
+    fn plan_fills_slots_and_returns_to_the_current_pattern() {
+        let mut state = AppState::new();
+        state.add_instrument(SourceType::Kit);
+        let seq = state.instruments.selected_drum_sequencer().unwrap();
+        let import = parse_lmms(MMP, Path::new(".")).unwrap();
+
+        let plan = plan(&import, seq);
+        assert_eq!(plan.patterns, vec!["Beat A", "Beat B"]);
+        assert!(matches!(plan.actions[0], Action::Sequencer(SequencerAction::ClearPattern)));
+        assert!(matches!(plan.actions.last(), Some(Action::Sequencer(SequencerAction::PrevPattern))));
+        let toggles = plan.actions.iter()
+            .filter(|a| matches!(a, Action::Sequencer(SequencerAction::ToggleStep(..))))
+            .count();
+        assert_eq!(toggles, 4);
+    }
+
+    #[test]
+    fn other_xml_is_rejected() {
+        assert!(parse_hydrogen("<lmms-project/>", Path::new(".")).is_err());
+        assert!(parse_lmms("<song/>", Path::new(".")).is_err());
+        assert!(parse_lmms("not xml", Path::new(".")).is_err());
+    }
+}
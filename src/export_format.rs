@@ -0,0 +1,166 @@
+//! Bit depth and dither for exported audio.
+//!
+//! The engine renders bounces and stems as it always has; once an export
+//! finishes, each file is rewritten at the bit depth chosen in the export
+//! dialog. Integer depths can be TPDF-dithered: two uniform random values
+//! of one LSB each are summed and added before rounding, which trades the
+//! correlated distortion of plain truncation for a flat noise floor.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BitDepth {
+    Int16,
+    #[default]
+    Int24,
+    Float32,
+}
+
+impl BitDepth {
+    pub const ALL: [BitDepth; 3] = [BitDepth::Int16, BitDepth::Int24, BitDepth::Float32];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BitDepth::Int16 => "16-bit",
+            BitDepth::Int24 => "24-bit",
+            BitDepth::Float32 => "32-bit float",
+        }
+    }
+
+    fn spec(self) -> (u16, hound::SampleFormat) {
+        match self {
+            BitDepth::Int16 => (16, hound::SampleFormat::Int),
+            BitDepth::Int24 => (24, hound::SampleFormat::Int),
+            BitDepth::Float32 => (32, hound::SampleFormat::Float),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportFormat {
+    pub depth: BitDepth,
+    /// TPDF dither when reducing to an integer depth
+    pub dither: bool,
+}
+
+impl Default for ExportFormat {
+    fn default() -> Self {
+        Self { depth: BitDepth::Int24, dither: true }
+    }
+}
+
+impl ExportFormat {
+    /// Dither only means something for integer output
+    pub fn dithers(self) -> bool {
+        self.dither && self.depth != BitDepth::Float32
+    }
+}
+
+/// Small xorshift generator; dither needs noise, not cryptography
+struct Noise(u64);
+
+impl Noise {
+    fn new() -> Self {
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0x9E37_79B9_7F4A_7C15);
+        Self(seed | 1)
+    }
+
+    /// Uniform in [-0.5, 0.5)
+    fn uniform(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+    }
+
+    /// Triangular in (-1, 1), in LSBs
+    fn tpdf(&mut self) -> f64 {
+        self.uniform() + self.uniform()
+    }
+}
+
+/// Quantize one sample in [-1, 1] to a signed integer of `bits`
+fn quantize(sample: f32, bits: u16, noise: Option<&mut Noise>) -> i32 {
+    let max = ((1i64 << (bits - 1)) - 1) as f64;
+    let scaled = sample as f64 * max + noise.map_or(0.0, |n| n.tpdf());
+    scaled.round().clamp(-max - 1.0, max) as i32
+}
+
+/// Rewrite the WAV at `path` in `format`. Files already in that format are
+/// left alone.
+pub fn reencode(path: &Path, format: ExportFormat) -> Result<(), String> {
+    let mut reader = hound::WavReader::open(path).map_err(|e| e.to_string())?;
+    let spec = reader.spec();
+    let (bits, sample_format) = format.depth.spec();
+    if spec.bits_per_sample == bits && spec.sample_format == sample_format {
+        return Ok(());
+    }
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>().map_err(|e| e.to_string())?,
+        hound::SampleFormat::Int => {
+            let scale = 1.0 / (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader.samples::<i32>()
+                .map(|s| s.map(|v| v as f32 * scale))
+                .collect::<Result<_, _>>()
+                .map_err(|e| e.to_string())?
+        }
+    };
+    drop(reader);
+
+    // Write beside the original and swap, so a failure leaves the export intact
+    let tmp = path.with_extension("wav.tmp");
+    let out_spec = hound::WavSpec { bits_per_sample: bits, sample_format, ..spec };
+    let mut writer = hound::WavWriter::create(&tmp, out_spec).map_err(|e| e.to_string())?;
+    let mut noise = format.dithers().then(Noise::new);
+    for sample in samples {
+        let written = match sample_format {
+            hound::SampleFormat::Float => writer.write_sample(sample),
+            hound::SampleFormat::Int => writer.write_sample(quantize(sample, bits, noise.as_mut())),
+        };
+        written.map_err(|e| e.to_string())?;
+    }
+    writer.finalize().map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, path).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quantize_clamps_and_rounds() {
+        assert_eq!(quantize(1.5, 16, None), 32767);
+        assert_eq!(quantize(-1.5, 16, None), -32768);
+        assert_eq!(quantize(0.5, 16, None), 16384);
+        let mut noise = Noise::new();
+        for _ in 0..1000 {
+            assert!(quantize(0.0, 16, Some(&mut noise)).abs() <= 1);
+        }
+    }
+
+    #[test]
+    fn reencodes_float_to_dithered_16_bit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bounce.wav");
+        let spec = hound::WavSpec { channels: 2, sample_rate: 48000, bits_per_sample: 32, sample_format: hound::SampleFormat::Float };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for i in 0..960 {
+            writer.write_sample((i as f32 / 960.0) - 0.5).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        reencode(&path, ExportFormat { depth: BitDepth::Int16, dither: true }).unwrap();
+        let mut reader = hound::WavReader::open(&path).unwrap();
+        let out = reader.spec();
+        assert_eq!((out.bits_per_sample, out.sample_format, out.channels), (16, hound::SampleFormat::Int, 2));
+        let samples: Vec<i32> = reader.samples::<i32>().map(Result::unwrap).collect();
+        assert_eq!(samples.len(), 960);
+        assert!((samples[0] + 16384).abs() <= 1);
+    }
+}
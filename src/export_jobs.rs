@@ -0,0 +1,276 @@
+//! Exports as the main loop runs them.
+//!
+//! Bounces and stem exports started from the export dialog wait in the
+//! render queue and run one at a time. A region render narrows the engine
+//! to the region first; once the engine has written the files, the full
+//! arrangement is given back and the files are cut to the region and
+//! rewritten at the chosen depth. A stem export also gets a session
+//! manifest next to its stems. The sequencer's batch pattern export runs
+//! here too, one bounce at a time.
+
+use std::path::PathBuf;
+use std::sync::mpsc::Sender;
+
+use crate::action::{Action, AudioDirty, IoFeedback, PianoRollAction};
+use crate::audio::AudioHandle;
+use crate::audio::commands::ExportKind;
+use crate::batch_export::BatchExport;
+use crate::export_format::{self, ExportFormat};
+use crate::export_region::{self, Finish, RegionSettings};
+use crate::global_actions::{dispatch_and_apply, show_status, sync_pane_layer};
+use crate::panes::{BatchExportPane, ExportPane, ExportTarget, PianoRollPane, PreferencesPane, QueueRequest, RenderQueuePane, SequencerPane};
+use crate::preferences::Preferences;
+use crate::render_queue::{QueueState, RenderQueue};
+use crate::session_manifest;
+use crate::state::{AppState, InstrumentId};
+use crate::ui::{Frame, LayerStack, PaneManager};
+
+pub struct ExportJobs {
+    queue: RenderQueue,
+    /// Post-processing for the export being started, then the files it writes
+    finish_pending: Option<Finish>,
+    files: Option<(Vec<PathBuf>, Finish)>,
+    /// Stems of the running stem export, for the session manifest
+    stems: Option<Vec<(InstrumentId, PathBuf)>>,
+    patterns: Option<BatchExport>,
+}
+
+impl ExportJobs {
+    pub fn new() -> Self {
+        Self {
+            queue: RenderQueue::new(),
+            finish_pending: None,
+            files: None,
+            stems: None,
+            patterns: None,
+        }
+    }
+
+    /// A region render keeps the engine's region-only arrangement until it
+    /// ends, so routing rebuilds wait for it
+    pub fn region_render(&self) -> bool {
+        self.finish_pending.iter()
+            .chain(self.files.iter().map(|(_, finish)| finish))
+            .any(|finish| finish.resync)
+    }
+
+    /// Once an export finishes, give the engine back the full arrangement,
+    /// then cut the files to the region, rewrite them at the chosen depth
+    /// and write the session manifest next to any stems
+    pub(crate) fn poll_finished(
+        &mut self,
+        state: &AppState,
+        panes: &mut PaneManager,
+        audio: &AudioHandle,
+        pending_audio_dirty: &mut AudioDirty,
+    ) {
+        match &state.io.pending_export {
+            Some(export) => {
+                if let Some(finish) = self.finish_pending.take() {
+                    let files = match export.kind {
+                        ExportKind::MasterBounce => vec![export.path.clone()],
+                        ExportKind::StemExport => export.stems.iter().map(|(_, path)| path.clone()).collect(),
+                    };
+                    self.files = Some((files, finish));
+                }
+                if matches!(export.kind, ExportKind::StemExport) {
+                    self.stems = Some(export.stems.clone());
+                }
+            }
+            None => {
+                // An export that never started still has the engine to resync
+                let ended = self.files.take().or_else(|| self.finish_pending.take().map(|f| (Vec::new(), f)));
+                if let Some((files, finish)) = ended {
+                    if finish.resync {
+                        pending_audio_dirty.merge(AudioDirty::all());
+                    }
+                    self.convert(files, finish, panes, audio);
+                }
+                if let Some(stems) = self.stems.take().filter(|s| s.iter().all(|(_, path)| path.is_file())) {
+                    let status = match session_manifest::write(state, &stems) {
+                        Ok(dir) => format!("Wrote session.rpp and session.json to {}", dir.display()),
+                        Err(e) => format!("Session manifest failed: {}", e),
+                    };
+                    show_status(panes, audio, &status);
+                }
+            }
+        }
+    }
+
+    /// Trim and re-encode the files a finished export wrote, and record how
+    /// it went in the queue
+    fn convert(&mut self, files: Vec<PathBuf>, finish: Finish, panes: &mut PaneManager, audio: &AudioHandle) {
+        if files.is_empty() {
+            self.queue.finish(QueueState::Failed("export did not start".into()));
+            return;
+        }
+        if self.queue.stopping() || !files.iter().all(|p| p.is_file()) {
+            self.queue.finish(QueueState::Cancelled);
+            return;
+        }
+        let failed: Vec<String> = files.iter()
+            .filter_map(|path| {
+                let trimmed = match finish.span {
+                    Some((start, length)) => export_region::trim(path, start, length),
+                    None => Ok(()),
+                };
+                trimmed
+                    .and_then(|()| export_format::reencode(path, finish.format))
+                    .err()
+                    .map(|e| format!("{}: {}", path.display(), e))
+            })
+            .collect();
+        let status = if failed.is_empty() {
+            let format = finish.format;
+            let dither = if format.dithers() { ", TPDF dither" } else { "" };
+            format!("Wrote {} file(s) as {}{}", files.len(), format.depth.name(), dither)
+        } else {
+            format!("Export conversion failed: {}", failed.join("; "))
+        };
+        self.queue.finish(if failed.is_empty() {
+            QueueState::Done(files)
+        } else {
+            QueueState::Failed(failed.join("; "))
+        });
+        show_status(panes, audio, &status);
+    }
+
+    /// Open the export dialog, queue what it starts, take cancels from the
+    /// render queue and start the next export once the last is finished off
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn poll_requests(
+        &mut self,
+        prefs: &mut Preferences,
+        state: &mut AppState,
+        panes: &mut PaneManager,
+        audio: &mut AudioHandle,
+        app_frame: &mut Frame,
+        pending_audio_dirty: &mut AudioDirty,
+        layer_stack: &mut LayerStack,
+        io_tx: &Sender<IoFeedback>,
+    ) {
+        // Bounce and stem keys open the export dialog with the last used format
+        if let Some(target) = panes.get_pane_mut::<PianoRollPane>("piano_roll").and_then(|p| p.take_export_dialog()) {
+            if let Some(export) = panes.get_pane_mut::<ExportPane>("export") {
+                export.open(target, prefs.export_region, prefs.export_format, state);
+            }
+            panes.push_to("export", state);
+            sync_pane_layer(panes, layer_stack);
+        }
+        if let Some(job) = panes.get_pane_mut::<ExportPane>("export").and_then(|p| p.take_start()) {
+            remember_export_settings(prefs, panes, job.format, job.region);
+            self.queue.push(job);
+            if self.queue.running().is_some() {
+                let status = format!("Export queued ({} waiting, F10 to view)", self.queue.waiting());
+                show_status(panes, audio, &status);
+            }
+        }
+        match panes.get_pane_mut::<RenderQueuePane>("render_queue").and_then(|p| p.take_request()) {
+            Some(QueueRequest::Cancel(id)) => {
+                if self.queue.cancel(id) && state.io.pending_export.is_some() {
+                    let cancel = Action::PianoRoll(PianoRollAction::CancelExport);
+                    dispatch_and_apply(&cancel, state, panes, audio, app_frame, pending_audio_dirty, io_tx);
+                }
+            }
+            Some(QueueRequest::ClearFinished) => self.queue.clear_finished(),
+            None => {}
+        }
+
+        let idle = state.io.pending_export.is_none()
+            && state.io.pending_render.is_none()
+            && self.finish_pending.is_none()
+            && self.files.is_none();
+        let next = if idle { self.queue.start_next() } else { None };
+        if let Some(job) = next {
+            match export_region::resolve(job.range, state) {
+                Ok(region) => {
+                    let mut finish = Finish { format: job.format, span: None, resync: false };
+                    // A region render hears only the region and its pre-roll
+                    if let Some(region) = region {
+                        finish.span = Some(export_region::span_secs(state, region, job.region));
+                        finish.resync = export_region::sync_region(state, audio, region, job.region);
+                    }
+                    let action = match job.target {
+                        ExportTarget::Master => PianoRollAction::BounceToWav,
+                        ExportTarget::Stems => PianoRollAction::ExportStems,
+                    };
+                    dispatch_and_apply(&Action::PianoRoll(action), state, panes, audio, app_frame, pending_audio_dirty, io_tx);
+                    self.finish_pending = Some(finish);
+                }
+                Err(e) => {
+                    self.queue.finish(QueueState::Failed(e.clone()));
+                    show_status(panes, audio, &format!("Export: {}", e));
+                }
+            }
+        }
+        if let Some(pane) = panes.get_pane_mut::<RenderQueuePane>("render_queue") {
+            pane.set_jobs(self.queue.jobs());
+        }
+    }
+
+    /// Export every drum pattern, one bounce at a time
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn poll_patterns(
+        &mut self,
+        state: &mut AppState,
+        panes: &mut PaneManager,
+        audio: &mut AudioHandle,
+        app_frame: &mut Frame,
+        pending_audio_dirty: &mut AudioDirty,
+        layer_stack: &mut LayerStack,
+        io_tx: &Sender<IoFeedback>,
+    ) {
+        if panes.get_pane_mut::<SequencerPane>("sequencer").is_some_and(|p| p.take_batch_export()) && self.patterns.is_none() {
+            match BatchExport::new(state) {
+                Some(batch) => {
+                    self.patterns = Some(batch);
+                    panes.push_to("batch_export", state);
+                    sync_pane_layer(panes, layer_stack);
+                }
+                None => show_status(panes, audio, "Pattern export: select a drum machine first"),
+            }
+        }
+        let Some(batch) = self.patterns.as_mut() else { return };
+        if panes.get_pane_mut::<BatchExportPane>("batch_export").is_some_and(|p| p.take_cancel()) {
+            batch.cancel();
+        }
+        if let Some(action) = batch.poll(state) {
+            dispatch_and_apply(&action, state, panes, audio, app_frame, pending_audio_dirty, io_tx);
+        }
+        let done = batch.is_done();
+        if let Some(pane) = panes.get_pane_mut::<BatchExportPane>("batch_export") {
+            pane.set_progress(batch.jobs(), batch.dir(), !done);
+        }
+        if done {
+            let status = batch.summary();
+            show_status(panes, audio, &status);
+            self.patterns = None;
+        }
+    }
+}
+
+impl Default for ExportJobs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Remember the export dialog's format, pre-roll and tail for next time
+fn remember_export_settings(
+    prefs: &mut Preferences,
+    panes: &mut PaneManager,
+    format: ExportFormat,
+    region: RegionSettings,
+) {
+    if prefs.export_format == format && prefs.export_region == region {
+        return;
+    }
+    prefs.export_format = format;
+    prefs.export_region = region;
+    if let Err(e) = prefs.save() {
+        log::error!("preferences: could not save: {}", e);
+    }
+    if let Some(pane) = panes.get_pane_mut::<PreferencesPane>("preferences") {
+        pane.set_export_settings(format, region);
+    }
+}
@@ -0,0 +1,201 @@
+//! Exporting part of the arrangement: the loop, or a range of bars.
+//!
+//! The engine always bounces from the top, so a region export works
+//! around it. For the render, the audio thread is handed a copy of the
+//! piano roll without the notes starting after the region or before its
+//! pre-roll; the project itself is left alone, and the engine gets the
+//! full arrangement back once the render finishes. The files are then cut to start at the
+//! region and run for the tail past its end. Notes in the pre-roll still
+//! play, so reverbs and delays are already ringing when the region begins,
+//! and the tail holds only their decay.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::commands::AudioCmd;
+use crate::audio::AudioHandle;
+use crate::export_format::ExportFormat;
+use crate::state::AppState;
+
+/// Longest pre-roll and tail offered in the export dialog
+pub const MAX_PRE_ROLL_BARS: u32 = 8;
+pub const MAX_TAIL_SECS: u32 = 30;
+
+/// What part of the arrangement an export covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportRange {
+    Song,
+    Loop,
+    /// Bars `from` to `to`, inclusive, counting from 1
+    Bars { from: u32, to: u32 },
+}
+
+/// Pre-roll and tail around a region, remembered between exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RegionSettings {
+    pub pre_roll_bars: u32,
+    pub tail_secs: u32,
+}
+
+impl Default for RegionSettings {
+    fn default() -> Self {
+        Self { pre_roll_bars: 1, tail_secs: 2 }
+    }
+}
+
+/// A region in ticks, end exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    pub start: u32,
+    pub end: u32,
+}
+
+/// Post-processing owed to an export once its render finishes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Finish {
+    pub format: ExportFormat,
+    /// Start and length in seconds to keep; None keeps everything
+    pub span: Option<(f64, f64)>,
+    /// The engine is playing a region-only arrangement and needs the full
+    /// one back; syncs to it are held until then
+    pub resync: bool,
+}
+
+/// The region `range` covers; None for the whole song
+pub fn resolve(range: ExportRange, state: &AppState) -> Result<Option<Region>, String> {
+    let piano_roll = &state.session.piano_roll;
+    match range {
+        ExportRange::Song => Ok(None),
+        ExportRange::Loop if piano_roll.loop_end > piano_roll.loop_start => {
+            Ok(Some(Region { start: piano_roll.loop_start, end: piano_roll.loop_end }))
+        }
+        ExportRange::Loop => Err("No loop region set".to_string()),
+        ExportRange::Bars { from, to } => {
+            let ticks_per_bar = piano_roll.ticks_per_bar().max(1);
+            let from = from.max(1);
+            Ok(Some(Region { start: (from - 1) * ticks_per_bar, end: to.max(from) * ticks_per_bar }))
+        }
+    }
+}
+
+/// Whether a note starting at `tick` is heard in a render of `region`
+/// whose pre-roll starts at `keep_from`
+fn heard(tick: u32, keep_from: u32, region: Region) -> bool {
+    keep_from <= tick && tick < region.end
+}
+
+/// Send the audio thread a copy of the piano roll holding only the notes a
+/// region export should hear; the project's notes are not touched. Returns
+/// false when every note is heard and nothing was sent.
+pub fn sync_region(state: &AppState, audio: &mut AudioHandle, region: Region, settings: RegionSettings) -> bool {
+    let mut piano_roll = state.session.piano_roll.clone();
+    let keep_from = region.start.saturating_sub(settings.pre_roll_bars * piano_roll.ticks_per_bar());
+    let mut filtered = false;
+    for track in piano_roll.tracks.values_mut() {
+        let before = track.notes.len();
+        track.notes.retain(|n| heard(n.tick, keep_from, region));
+        filtered |= track.notes.len() != before;
+    }
+    if !filtered {
+        return false;
+    }
+    match audio.send_cmd(AudioCmd::UpdatePianoRollData { piano_roll }) {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("region export: sending the region's notes failed: {}", e);
+            false
+        }
+    }
+}
+
+/// Region start and length in seconds at the session tempo, tail included
+pub fn span_secs(state: &AppState, region: Region, settings: RegionSettings) -> (f64, f64) {
+    let ticks_per_beat = state.session.piano_roll.ticks_per_beat as f64;
+    let ticks_per_sec = state.session.bpm as f64 / 60.0 * ticks_per_beat;
+    let start = region.start as f64 / ticks_per_sec.max(1.0);
+    let length = region.end.saturating_sub(region.start) as f64 / ticks_per_sec.max(1.0);
+    (start, length + settings.tail_secs as f64)
+}
+
+/// Cut the WAV at `path` to `length` seconds from `start`, padding with
+/// silence if the render ended early
+pub fn trim(path: &Path, start: f64, length: f64) -> Result<(), String> {
+    let mut reader = hound::WavReader::open(path).map_err(|e| e.to_string())?;
+    let spec = reader.spec();
+    let channels = spec.channels.max(1) as usize;
+    let skip = (start * spec.sample_rate as f64).round() as usize * channels;
+    let keep = (length * spec.sample_rate as f64).round() as usize * channels;
+
+    let tmp = path.with_extension("wav.tmp");
+    let mut writer = hound::WavWriter::create(&tmp, spec).map_err(|e| e.to_string())?;
+    match spec.sample_format {
+        hound::SampleFormat::Float => {
+            let samples = reader.samples::<f32>().skip(skip).chain(std::iter::repeat(Ok(0.0))).take(keep);
+            for sample in samples {
+                writer.write_sample(sample.map_err(|e| e.to_string())?).map_err(|e| e.to_string())?;
+            }
+        }
+        hound::SampleFormat::Int => {
+            let samples = reader.samples::<i32>().skip(skip).chain(std::iter::repeat(Ok(0))).take(keep);
+            for sample in samples {
+                writer.write_sample(sample.map_err(|e| e.to_string())?).map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    writer.finalize().map_err(|e| e.to_string())?;
+    drop(reader);
+    std::fs::rename(&tmp, path).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bars_resolve_to_ticks() {
+        let state = AppState::new();
+        let ticks_per_bar = state.session.piano_roll.ticks_per_bar();
+        let region = resolve(ExportRange::Bars { from: 3, to: 4 }, &state).unwrap().unwrap();
+        assert_eq!(region, Region { start: 2 * ticks_per_bar, end: 4 * ticks_per_bar });
+        assert_eq!(resolve(ExportRange::Song, &state), Ok(None));
+    }
+
+    #[test]
+    fn region_hears_its_pre_roll_but_not_past_its_end() {
+        let region = Region { start: 1920, end: 3840 };
+        assert!(heard(960, 960, region));
+        assert!(heard(3839, 960, region));
+        assert!(!heard(959, 960, region));
+        assert!(!heard(3840, 960, region));
+    }
+
+    #[test]
+    fn span_follows_the_tick_resolution() {
+        let state = AppState::new();
+        let ticks_per_beat = state.session.piano_roll.ticks_per_beat;
+        let secs_per_beat = 60.0 / state.session.bpm as f64;
+        let region = Region { start: 4 * ticks_per_beat, end: 8 * ticks_per_beat };
+        let (start, length) = span_secs(&state, region, RegionSettings { pre_roll_bars: 0, tail_secs: 1 });
+        assert!((start - 4.0 * secs_per_beat).abs() < 1e-9);
+        assert!((length - (4.0 * secs_per_beat + 1.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn trim_cuts_and_pads() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bounce.wav");
+        let spec = hound::WavSpec { channels: 1, sample_rate: 1000, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for i in 0..1000 {
+            writer.write_sample(i as i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        trim(&path, 0.5, 1.0).unwrap();
+        let samples: Vec<i32> = hound::WavReader::open(&path).unwrap().samples::<i32>().map(Result::unwrap).collect();
+        assert_eq!(samples.len(), 1000);
+        assert_eq!((samples[0], samples[499], samples[500]), (500, 999, 0));
+    }
+}
@@ -0,0 +1,163 @@
+//! Star ratings for browsable assets, and the favorites filter in the
+//! file browser, sample library and add menus.
+//!
+//! Ratings are kept in `~/.config/imbolc/favorites.toml`, keyed by asset
+//! kind and a name that survives across projects: the file path for
+//! samples, the display name for sources, synthdefs, plugins and effects.
+//! Any rating from one to five stars makes an asset a favorite.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+pub const MAX_RATING: u8 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetKind {
+    /// Sample or other file picked in a browser, by path
+    File,
+    /// Built-in instrument source
+    Source,
+    /// Imported custom synthdef
+    Synthdef,
+    Vst,
+    /// Built-in effect
+    Effect,
+    /// Multi-instrument template
+    Group,
+}
+
+impl AssetKind {
+    fn prefix(self) -> &'static str {
+        match self {
+            AssetKind::File => "file",
+            AssetKind::Source => "source",
+            AssetKind::Synthdef => "synthdef",
+            AssetKind::Vst => "vst",
+            AssetKind::Effect => "effect",
+            AssetKind::Group => "group",
+        }
+    }
+}
+
+/// Store key for an asset
+pub fn key(kind: AssetKind, name: &str) -> String {
+    format!("{}:{}", kind.prefix(), name)
+}
+
+pub fn file_key(path: &Path) -> String {
+    key(AssetKind::File, &path.display().to_string())
+}
+
+/// Rating as a fixed-width star column, blank when unrated
+pub fn stars(rating: u8) -> String {
+    let rating = rating.min(MAX_RATING) as usize;
+    format!("{}{}", "★".repeat(rating), " ".repeat(MAX_RATING as usize - rating))
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct Store {
+    #[serde(default)]
+    ratings: BTreeMap<String, u8>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Favorites {
+    store: Store,
+}
+
+impl Favorites {
+    pub fn load() -> Self {
+        let store = store_path()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|s| toml::from_str(&s).ok())
+            .unwrap_or_default();
+        Self { store }
+    }
+
+    pub fn rating(&self, key: &str) -> u8 {
+        self.store.ratings.get(key).copied().unwrap_or(0)
+    }
+
+    pub fn is_favorite(&self, key: &str) -> bool {
+        self.rating(key) > 0
+    }
+
+    /// Star an unrated asset with one star, or clear its rating
+    pub fn toggle(&mut self, key: &str) {
+        let rating = if self.is_favorite(key) { 0 } else { 1 };
+        self.set_rating(key, rating);
+    }
+
+    /// Add or remove stars, keeping within 0..=MAX_RATING
+    pub fn adjust(&mut self, key: &str, delta: i8) {
+        let rating = (self.rating(key) as i8 + delta).clamp(0, MAX_RATING as i8) as u8;
+        self.set_rating(key, rating);
+    }
+
+    fn set_rating(&mut self, key: &str, rating: u8) {
+        if rating == 0 {
+            self.store.ratings.remove(key);
+        } else {
+            self.store.ratings.insert(key.to_string(), rating.min(MAX_RATING));
+        }
+    }
+
+    pub fn save(&self) {
+        let Some(path) = store_path() else { return };
+        let result = toml::to_string_pretty(&self.store)
+            .map_err(|e| e.to_string())
+            .and_then(|text| {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                }
+                std::fs::write(&path, text).map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            log::error!("favorites: could not save: {}", e);
+        }
+    }
+}
+
+fn store_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("imbolc").join("favorites.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toggle_and_adjust_stay_in_range() {
+        let mut favs = Favorites::default();
+        let kick = file_key(Path::new("/lib/kick.wav"));
+        assert!(!favs.is_favorite(&kick));
+
+        favs.toggle(&kick);
+        assert_eq!(favs.rating(&kick), 1);
+        favs.adjust(&kick, 10);
+        assert_eq!(favs.rating(&kick), MAX_RATING);
+        favs.toggle(&kick);
+        assert!(!favs.is_favorite(&kick));
+        favs.adjust(&kick, -1);
+        assert_eq!(favs.rating(&kick), 0);
+    }
+
+    #[test]
+    fn kinds_do_not_collide() {
+        let mut favs = Favorites::default();
+        favs.toggle(&key(AssetKind::Vst, "Reverb"));
+        assert!(!favs.is_favorite(&key(AssetKind::Effect, "Reverb")));
+    }
+
+    #[test]
+    fn round_trips_through_toml() {
+        let mut favs = Favorites::default();
+        favs.adjust(&key(AssetKind::Synthdef, "fm_bell"), 3);
+        let text = toml::to_string_pretty(&favs.store).unwrap();
+        let store: Store = toml::from_str(&text).unwrap();
+        assert_eq!(store.ratings.get("synthdef:fm_bell"), Some(&3));
+        assert_eq!(stars(3), "★★★  ");
+    }
+}
@@ -0,0 +1,294 @@
+//! File actions the main loop handles itself instead of dispatching.
+//!
+//! Imports, exports and scripts picked in the file browser are parsed or
+//! run here, in the UI, and whatever they change in the project is then
+//! dispatched as ordinary actions so it lands in undo history. Each
+//! handler closes the browser and leaves a status line saying what
+//! happened.
+
+use std::path::Path;
+use std::sync::mpsc::Sender;
+
+use crate::action::{self, Action, AudioDirty, IoFeedback, InstrumentAction, SessionAction};
+use crate::audio::AudioHandle;
+use crate::audio::commands::AudioCmd;
+use crate::dispatch::LocalDispatcher;
+use crate::global_actions::{apply_dispatch_result, dispatch_and_apply, show_status};
+use crate::panes::FrameEditPane;
+use crate::state::{self, AppState, InstrumentId};
+use crate::ui::{Frame, PaneManager};
+use crate::{audio_to_midi, drum_import, midi_import, project_json, safe_mode, scripting, tempo_detect};
+use imbolc_types::Dispatcher;
+
+/// Handle `action` if it is one of these file actions. Returns false,
+/// without touching anything, for everything else. `safe` routes project
+/// loads through safe mode's recovery path.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn handle(
+    action: &Action,
+    safe: bool,
+    state: &mut AppState,
+    panes: &mut PaneManager,
+    audio: &mut AudioHandle,
+    app_frame: &mut Frame,
+    pending_audio_dirty: &mut AudioDirty,
+    io_tx: &Sender<IoFeedback>,
+) -> bool {
+    let status = match action {
+        Action::PianoRoll(action::PianoRollAction::AudioToMidi { track, start_tick, path }) => {
+            panes.pop(state);
+            // Pitch tracking runs here; the resulting notes are added as one undoable paste
+            match audio_to_midi::analyze_file(path) {
+                Ok(detected) => {
+                    let notes = audio_to_midi::notes_to_ticks(&detected, state.session.bpm as f32, state.session.piano_roll.ticks_per_beat);
+                    if let Some(add) = audio_to_midi::add_notes(&notes, *track, *start_tick) {
+                        dispatch_and_apply(&add, state, panes, audio, app_frame, pending_audio_dirty, io_tx);
+                    }
+                    Some(format!("Extracted {} notes", notes.len()))
+                }
+                Err(e) => Some(format!("Audio-to-MIDI failed: {}", e)),
+            }
+        }
+        Action::Sequencer(action::SequencerAction::ImportPattern(path)) => {
+            panes.pop(state);
+            Some(import_drum_pattern(path, state, panes, audio, app_frame, pending_audio_dirty, io_tx))
+        }
+        Action::Session(SessionAction::ImportMidiFile(path)) => {
+            panes.pop(state);
+            Some(import_midi_file(path, state, panes, audio, app_frame, pending_audio_dirty, io_tx))
+        }
+        Action::Session(SessionAction::DetectTempo(path)) => {
+            // Back to the session settings with the estimate loaded into the BPM field
+            panes.pop(state);
+            match tempo_detect::analyze_file(path, state.session.time_signature.0 as usize) {
+                Ok(estimate) => {
+                    let live = panes.get_pane_mut::<FrameEditPane>("frame_edit")
+                        .map(|fe| fe.apply_tempo_estimate(estimate));
+                    if let Some(live) = live {
+                        dispatch_and_apply(&live, state, panes, audio, app_frame, pending_audio_dirty, io_tx);
+                    }
+                    None
+                }
+                Err(e) => Some(format!("Tempo detection failed: {}", e)),
+            }
+        }
+        Action::Session(SessionAction::SaveAs(path)) if project_json::is_json_path(path) => {
+            // JSON export writes a copy; the project keeps saving to SQLite
+            panes.pop(state);
+            match project_json::export(state, path) {
+                Ok(()) => Some(format!("Exported JSON to {}", path.display())),
+                Err(e) => Some(format!("JSON export failed: {}", e)),
+            }
+        }
+        Action::Session(SessionAction::LoadFrom(path)) if project_json::is_json_path(path) => {
+            panes.pop(state);
+            Some(import_json(path, state, panes, audio, app_frame, pending_audio_dirty, io_tx))
+        }
+        Action::Session(SessionAction::LoadFrom(path)) if safe => {
+            panes.pop(state);
+            let status = load_in_safe_mode(path, state, panes, app_frame, audio, io_tx);
+            pending_audio_dirty.merge(AudioDirty::all());
+            Some(status)
+        }
+        Action::Session(SessionAction::RunScript(path)) => {
+            // Scripts run in the UI against a snapshot; their edits dispatch as one undoable batch
+            panes.pop(state);
+            match scripting::run_script(path, state) {
+                Ok(run) => {
+                    for line in &run.log {
+                        log::info!("script: {}", line);
+                    }
+                    dispatch_and_apply(&run.action, state, panes, audio, app_frame, pending_audio_dirty, io_tx);
+                    Some(format!("Script applied {} edits", run.edit_count))
+                }
+                Err(e) => Some(format!("Script error: {}", e)),
+            }
+        }
+        _ => return false,
+    };
+    if let Some(status) = status {
+        show_status(panes, audio, &status);
+    }
+    true
+}
+
+/// Files are parsed here; the samples and steps dispatch as one undoable batch
+fn import_drum_pattern(
+    path: &Path,
+    state: &mut AppState,
+    panes: &mut PaneManager,
+    audio: &mut AudioHandle,
+    app_frame: &mut Frame,
+    pending_audio_dirty: &mut AudioDirty,
+    io_tx: &Sender<IoFeedback>,
+) -> String {
+    let import = match drum_import::load(path) {
+        Ok(import) => import,
+        Err(e) => return format!("Drum pattern import failed: {}", e),
+    };
+    match state.instruments.selected_drum_sequencer().map(|seq| drum_import::plan(&import, seq)) {
+        Some(plan) => {
+            let status = plan.summary();
+            dispatch_and_apply(&Action::Batch(plan.actions), state, panes, audio, app_frame, pending_audio_dirty, io_tx);
+            status
+        }
+        None => "Select a kit instrument to import drum patterns into".to_string(),
+    }
+}
+
+/// New instruments dispatch first so their tracks exist; the notes follow as one batch
+fn import_midi_file(
+    path: &Path,
+    state: &mut AppState,
+    panes: &mut PaneManager,
+    audio: &mut AudioHandle,
+    app_frame: &mut Frame,
+    pending_audio_dirty: &mut AudioDirty,
+    io_tx: &Sender<IoFeedback>,
+) -> String {
+    let import = match midi_import::load(path) {
+        Ok(import) => import,
+        Err(e) => return format!("MIDI import failed: {}", e),
+    };
+    let mut created = 0;
+    let mut notes = Vec::new();
+    for part in &import.parts {
+        let id = match midi_import::existing_instrument(part, state) {
+            Some(id) => id,
+            None => {
+                // Skip the part if the add was refused rather than filling someone else's instrument
+                let add = Action::Instrument(InstrumentAction::Add(part.source()));
+                let Some(id) = add_instrument(&add, state, panes, audio, app_frame, pending_audio_dirty, io_tx) else { continue };
+                let rename = Action::Instrument(InstrumentAction::Rename(id, part.name.clone()));
+                dispatch_and_apply(&rename, state, panes, audio, app_frame, pending_audio_dirty, io_tx);
+                created += 1;
+                id
+            }
+        };
+        if let Some(track) = state.session.piano_roll.track_order.iter().position(|t| *t == id) {
+            notes.extend(midi_import::note_actions(part, track, import.ppq, state));
+        }
+    }
+    let count = notes.len();
+    dispatch_and_apply(&Action::Batch(notes), state, panes, audio, app_frame, pending_audio_dirty, io_tx);
+    let mut status = format!("Imported {} notes from {} parts ({} new instruments)", count, import.parts.len(), created);
+    if let Some(bpm) = import.bpm.filter(|bpm| (bpm - state.session.bpm as f32).abs() >= 0.5) {
+        status.push_str(&format!("; the file's tempo is {:.0} BPM", bpm));
+    }
+    status
+}
+
+/// JSON import builds a new untitled project from the file
+fn import_json(
+    path: &Path,
+    state: &mut AppState,
+    panes: &mut PaneManager,
+    audio: &mut AudioHandle,
+    app_frame: &mut Frame,
+    pending_audio_dirty: &mut AudioDirty,
+    io_tx: &Sender<IoFeedback>,
+) -> String {
+    let project = match project_json::load(path) {
+        Ok(project) => project,
+        Err(e) => return format!("JSON import failed: {}", e),
+    };
+    dispatch_and_apply(&Action::Session(SessionAction::NewProject), state, panes, audio, app_frame, pending_audio_dirty, io_tx);
+    let session = Action::Batch(project.session_actions(state));
+    dispatch_and_apply(&session, state, panes, audio, app_frame, pending_audio_dirty, io_tx);
+    let mut skipped = Vec::new();
+    for inst_json in &project.instruments {
+        let Some(source) = inst_json.source_type() else {
+            skipped.push(inst_json.name.clone());
+            continue;
+        };
+        let add = Action::Instrument(InstrumentAction::Add(source));
+        let Some(id) = add_instrument(&add, state, panes, audio, app_frame, pending_audio_dirty, io_tx) else {
+            skipped.push(inst_json.name.clone());
+            continue;
+        };
+        let settings = Action::Batch(inst_json.actions(state, id));
+        dispatch_and_apply(&settings, state, panes, audio, app_frame, pending_audio_dirty, io_tx);
+    }
+    pending_audio_dirty.merge(AudioDirty::all());
+    let imported = project.instruments.len() - skipped.len();
+    if skipped.is_empty() {
+        format!("Imported {} instruments from JSON", imported)
+    } else {
+        format!("Imported {} instruments from JSON; skipped {}", imported, skipped.join(", "))
+    }
+}
+
+/// Dispatch an instrument add and return the new instrument's id, or None
+/// if the add was refused
+fn add_instrument(
+    add: &Action,
+    state: &mut AppState,
+    panes: &mut PaneManager,
+    audio: &mut AudioHandle,
+    app_frame: &mut Frame,
+    pending_audio_dirty: &mut AudioDirty,
+    io_tx: &Sender<IoFeedback>,
+) -> Option<InstrumentId> {
+    let before: Vec<_> = state.instruments.instruments.iter().map(|inst| inst.id).collect();
+    dispatch_and_apply(add, state, panes, audio, app_frame, pending_audio_dirty, io_tx);
+    state.instruments.instruments.iter()
+        .map(|inst| inst.id)
+        .find(|id| !before.contains(id))
+}
+
+/// Ask the audio thread to restore saved VST plugin state after a load
+pub(crate) fn queue_vst_state_restores(state: &AppState, audio: &mut AudioHandle) {
+    for inst in &state.instruments.instruments {
+        if let (state::SourceType::Vst(_), Some(ref path)) = (&inst.source, &inst.vst_state_path) {
+            let _ = audio.send_cmd(AudioCmd::LoadVstState {
+                instrument_id: inst.id,
+                target: action::VstTarget::Source,
+                path: path.clone(),
+            });
+        }
+        for effect in &inst.effects {
+            if let (state::EffectType::Vst(_), Some(ref path)) = (&effect.effect_type, &effect.vst_state_path) {
+                let _ = audio.send_cmd(AudioCmd::LoadVstState {
+                    instrument_id: inst.id,
+                    target: action::VstTarget::Effect(effect.id),
+                    path: path.clone(),
+                });
+            }
+        }
+    }
+}
+
+/// Load a project through safe mode's recovery path. Returns the status
+/// line, which lists anything that was skipped.
+pub(crate) fn load_in_safe_mode(
+    path: &Path,
+    state: &mut AppState,
+    panes: &mut PaneManager,
+    app_frame: &mut Frame,
+    audio: &mut AudioHandle,
+    io_tx: &Sender<IoFeedback>,
+) -> String {
+    let recovered = match safe_mode::load(path, state) {
+        Ok(recovered) => recovered,
+        Err(e) => return format!("Safe mode: could not load {}: {}", path.display(), e),
+    };
+    let name = path.file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("untitled")
+        .to_string();
+    state.undo_history.clear();
+    state.project.path = Some(path.to_path_buf());
+    app_frame.set_project_name(name);
+    // Removals stay undoable; the project is left dirty so the repair can be saved
+    let r = LocalDispatcher::new(state, audio, io_tx).dispatch(&recovered.repairs);
+    apply_dispatch_result(r, state, panes, app_frame, audio);
+    state.project.dirty = !recovered.skipped.is_empty();
+    queue_vst_state_restores(state, audio);
+
+    if state.instruments.instruments.is_empty() {
+        panes.switch_to("add", state);
+    } else {
+        panes.switch_to("instrument_edit", state);
+    }
+    recovered.summary()
+}
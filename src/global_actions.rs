@@ -4,18 +4,22 @@ use crate::action::{
     AutomationAction, Action
 };
 use crate::state::MixerSelection;
+use crate::clipboard;
+use crate::diagnose::{self, EngineFacts};
 use crate::dispatch;
+use crate::project_search::Target;
 use crate::state::{AppState, ClipboardContents};
 use crate::panes::{
     CommandPalettePane, InstrumentEditPane, PianoRollPane, SequencerPane,
     AutomationPane, ServerPane, HelpPane, FileBrowserPane, VstParamPane,
-    ConfirmPane, SaveAsPane, PendingAction,
+    ConfirmPane, DiagnosePane, SaveAsPane, PendingAction, BatchRenamePane,
 };
 use crate::ui::{
     self, DispatchResult, Frame, LayerStack, NavIntent, PaneManager,
     SessionAction, StatusEvent, ToggleResult, ViewState
 };
 use crate::ui::action_id::{ActionId, GlobalActionId, PaneId};
+use imbolc_types::Dispatcher;
 
 /// Two-digit instrument selection state machine
 pub(crate) enum InstrumentSelectMode {
@@ -27,6 +31,12 @@ pub(crate) enum InstrumentSelectMode {
 pub(crate) enum GlobalResult {
     Quit,
     RefreshScreen,
+    ToggleTutorial,
+    /// Silence everything; main.rs owns the MIDI output it also resets
+    Panic,
+    /// Switch to a workspace (1-based); they live in main.rs's preferences
+    Workspace(u8),
+    SaveWorkspace,
     Handled,
     NotHandled,
 }
@@ -50,6 +60,47 @@ pub(crate) fn select_instrument(
     }
 }
 
+/// Move the UI to a project search hit: select its instrument and open the
+/// instrument list, automation lane or piano roll note
+pub(crate) fn jump_to_search_hit(
+    target: Target,
+    state: &mut AppState,
+    panes: &mut PaneManager,
+    audio: &mut AudioHandle,
+    app_frame: &mut Frame,
+    pending_audio_dirty: &mut AudioDirty,
+    io_tx: &std::sync::mpsc::Sender<IoFeedback>,
+) {
+    match target {
+        Target::Instrument(idx) => {
+            select_instrument(idx + 1, state, panes, audio, io_tx);
+            panes.switch_to("instrument", &*state);
+        }
+        Target::AutomationLane { lane, instrument } => {
+            if let Some(idx) = instrument {
+                select_instrument(idx + 1, state, panes, audio, io_tx);
+            }
+            dispatch_and_apply(
+                &Action::Automation(AutomationAction::SelectLaneAt(lane)),
+                state,
+                panes,
+                audio,
+                app_frame,
+                pending_audio_dirty,
+                io_tx,
+            );
+            panes.switch_to("automation", &*state);
+        }
+        Target::Note { instrument, tick, pitch } => {
+            select_instrument(instrument + 1, state, panes, audio, io_tx);
+            if let Some(pr_pane) = panes.get_pane_mut::<PianoRollPane>("piano_roll") {
+                pr_pane.jump_to(tick, pitch);
+            }
+            panes.switch_to("piano_roll", &*state);
+        }
+    }
+}
+
 /// Sync piano roll's current track to match the globally selected instrument,
 /// and re-route the active pane if on a F2-family pane (piano_roll/sequencer/waveform).
 pub(crate) fn sync_piano_roll_to_selection(
@@ -267,6 +318,88 @@ pub(crate) fn handle_global_action(
                 pending_audio_dirty.merge(r.audio_dirty);
                 apply_dispatch_result(r, state, panes, app_frame, audio);
             }
+            GlobalActionId::RunScript => {
+                let start_dir = crate::scripting::scripts_dir().filter(|d| d.is_dir());
+                if let Some(fb) = panes.get_pane_mut::<FileBrowserPane>("file_browser") {
+                    fb.open_for(ui::FileSelectAction::RunScript, start_dir);
+                }
+                panes.push_to("file_browser", &*state);
+                sync_pane_layer(panes, layer_stack);
+            }
+            GlobalActionId::ImportMidi => {
+                if let Some(fb) = panes.get_pane_mut::<FileBrowserPane>("file_browser") {
+                    fb.open_for(ui::FileSelectAction::ImportMidiFile, None);
+                }
+                panes.push_to("file_browser", &*state);
+                sync_pane_layer(panes, layer_stack);
+            }
+            GlobalActionId::ExportMidi => {
+                let dir = crate::midi_export::export_dir(state.project.path.as_deref());
+                let status = match crate::midi_export::write(state, &dir) {
+                    Ok(path) => format!("Exported MIDI to {}", path.display()),
+                    Err(e) => format!("MIDI export failed: {}", e),
+                };
+                if let Some(server) = panes.get_pane_mut::<ServerPane>("server") {
+                    server.set_status(audio.status(), &status);
+                }
+            }
+            GlobalActionId::Preferences => {
+                panes.push_to("preferences", &*state);
+                sync_pane_layer(panes, layer_stack);
+            }
+            GlobalActionId::UndoHistory => {
+                panes.push_to("undo_history", &*state);
+                sync_pane_layer(panes, layer_stack);
+            }
+            GlobalActionId::Console => {
+                panes.push_to("console", &*state);
+                sync_pane_layer(panes, layer_stack);
+            }
+            GlobalActionId::Sclang => {
+                panes.push_to("sclang", &*state);
+                sync_pane_layer(panes, layer_stack);
+            }
+            GlobalActionId::ProjectSearch => {
+                panes.push_to("project_search", &*state);
+                sync_pane_layer(panes, layer_stack);
+            }
+            GlobalActionId::BatchRename => {
+                let buses = panes.active().id() == "mixer"
+                    && matches!(state.session.mixer.selection, MixerSelection::Bus(_));
+                if let Some(rename) = panes.get_pane_mut::<BatchRenamePane>("batch_rename") {
+                    rename.open(buses);
+                }
+                panes.push_to("batch_rename", &*state);
+                sync_pane_layer(panes, layer_stack);
+            }
+            GlobalActionId::RenderQueue => {
+                panes.push_to("render_queue", &*state);
+                sync_pane_layer(panes, layer_stack);
+            }
+            GlobalActionId::ProjectCheck => {
+                panes.push_to("project_check", &*state);
+                sync_pane_layer(panes, layer_stack);
+            }
+            GlobalActionId::WhySilent => {
+                if let Some(inst) = state.instruments.selected_instrument() {
+                    let facts = EngineFacts {
+                        server_running: audio.is_running(),
+                        node_exists: audio.instrument_node_exists(inst.id),
+                        buffer_loaded: audio.instrument_buffer_loaded(inst.id),
+                    };
+                    let checks = diagnose::checks(state, inst, facts);
+                    let name = inst.name.clone();
+                    if let Some(pane) = panes.get_pane_mut::<DiagnosePane>("diagnose") {
+                        pane.set_report(&name, checks);
+                    }
+                    panes.push_to("diagnose", &*state);
+                    sync_pane_layer(panes, layer_stack);
+                }
+            }
+            GlobalActionId::Latency => {
+                panes.push_to("latency", &*state);
+                sync_pane_layer(panes, layer_stack);
+            }
             GlobalActionId::Copy => {
                 copy_from_active_pane(state, panes, audio, io_tx);
             }
@@ -286,6 +419,23 @@ pub(crate) fn handle_global_action(
                     apply_dispatch_result(r, state, panes, app_frame, audio);
                 }
             }
+            GlobalActionId::PasteSystem => {
+                // Notes copied in another program or imbolc instance. Reading
+                // runs an external tool, so only on request.
+                if panes.active().id() == "piano_roll" {
+                    match clipboard::paste().and_then(|text| clipboard::notes_from_text(&text)) {
+                        Some(notes) => {
+                            state.clipboard.contents = Some(ClipboardContents::PianoRollNotes(notes));
+                            if let Some(action) = paste_to_active_pane(state, panes) {
+                                let r = dispatch::dispatch_action(&action, state, audio, io_tx);
+                                pending_audio_dirty.merge(r.audio_dirty);
+                                apply_dispatch_result(r, state, panes, app_frame, audio);
+                            }
+                        }
+                        None => show_status(panes, audio, "No notes on the system clipboard"),
+                    }
+                }
+            }
             GlobalActionId::SelectAll => {
                 select_all_in_active_pane(state, panes);
             }
@@ -505,6 +655,18 @@ pub(crate) fn handle_global_action(
             GlobalActionId::RefreshScreen => {
                 return GlobalResult::RefreshScreen;
             }
+            GlobalActionId::Tutorial => {
+                return GlobalResult::ToggleTutorial;
+            }
+            GlobalActionId::Panic => {
+                return GlobalResult::Panic;
+            }
+            GlobalActionId::Workspace(n) => {
+                return GlobalResult::Workspace(n);
+            }
+            GlobalActionId::SaveWorkspace => {
+                return GlobalResult::SaveWorkspace;
+            }
         },
         _ => return GlobalResult::NotHandled,
     }
@@ -569,6 +731,31 @@ pub(crate) fn apply_dispatch_result(
     }
 }
 
+/// Dispatch an action from the main loop and apply its result to the UI.
+/// Returns true when the action asked to quit.
+pub(crate) fn dispatch_and_apply(
+    action: &Action,
+    state: &mut AppState,
+    panes: &mut PaneManager,
+    audio: &mut AudioHandle,
+    app_frame: &mut Frame,
+    pending_audio_dirty: &mut AudioDirty,
+    io_tx: &std::sync::mpsc::Sender<IoFeedback>,
+) -> bool {
+    let result = dispatch::LocalDispatcher::new(state, audio, io_tx).dispatch(action);
+    let quit = result.quit;
+    pending_audio_dirty.merge(result.audio_dirty);
+    apply_dispatch_result(result, state, panes, app_frame, audio);
+    quit
+}
+
+/// Show a one-line message in the server pane's status
+pub(crate) fn show_status(panes: &mut PaneManager, audio: &AudioHandle, message: &str) {
+    if let Some(server) = panes.get_pane_mut::<ServerPane>("server") {
+        server.set_status(audio.status(), message);
+    }
+}
+
 fn copy_from_active_pane(
     state: &mut AppState,
     panes: &mut PaneManager,
@@ -580,12 +767,19 @@ fn copy_from_active_pane(
         "piano_roll" => {
             if let Some(pane) = panes.get_pane_mut::<PianoRollPane>("piano_roll") {
                 let (track, start_tick, end_tick, start_pitch, end_pitch) = pane.selection_region();
+                pane.set_copied_span(end_tick - start_tick);
                 dispatch::dispatch_action(
                     &Action::PianoRoll(PianoRollAction::CopyNotes {
                         track, start_tick, end_tick, start_pitch, end_pitch,
                     }),
                     state, audio, io_tx,
                 );
+                // Share with other programs and imbolc instances
+                if let Some(ClipboardContents::PianoRollNotes(notes)) = &state.clipboard.contents {
+                    if let Err(e) = clipboard::copy(&clipboard::notes_to_text(notes)) {
+                        log::warn!("Copy to system clipboard failed: {}", e);
+                    }
+                }
             }
         }
         "sequencer" => {
@@ -777,6 +971,16 @@ fn select_all_in_active_pane(state: &mut AppState, panes: &mut PaneManager) {
                 }
             }
         }
+        "automation" => {
+            if let Some(pane) = panes.get_pane_mut::<AutomationPane>("automation") {
+                if let Some(lane) = state.session.automation.selected() {
+                    if let (Some(first), Some(last)) = (lane.points.first(), lane.points.last()) {
+                        pane.selection_anchor_tick = Some(first.tick);
+                        pane.cursor_tick = last.tick;
+                    }
+                }
+            }
+        }
         _ => {}
     }
 }
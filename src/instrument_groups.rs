@@ -0,0 +1,94 @@
+//! Multi-instrument templates for the Add menu.
+//!
+//! A template adds several instruments in one undo step, already wired
+//! together: layer groups, sidechain keys and bus sends refer to other
+//! members of the same template by index.
+
+use crate::action::{Action, GroupMember, InstrumentAction};
+use crate::state::SourceType;
+
+pub struct MemberTemplate {
+    pub name: &'static str,
+    pub source: SourceType,
+    /// Join the layer group of this earlier member
+    pub layer_with: Option<usize>,
+    /// Insert a sidechain compressor keyed from this earlier member
+    pub sidechain_from: Option<usize>,
+    /// Send to a bus as (bus id, level)
+    pub send: Option<(u8, f32)>,
+}
+
+pub struct GroupTemplate {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub members: &'static [MemberTemplate],
+}
+
+impl GroupTemplate {
+    /// The action that adds every member of the template
+    pub fn add_action(&self) -> Action {
+        let members = self.members.iter().map(|m| GroupMember {
+            name: m.name.to_string(),
+            source: m.source,
+            layer_with: m.layer_with,
+            sidechain_from: m.sidechain_from,
+            send: m.send,
+        }).collect();
+        Action::Instrument(InstrumentAction::AddGroup(members))
+    }
+}
+
+pub const TEMPLATES: &[GroupTemplate] = &[
+    GroupTemplate {
+        name: "Kick + Bass",
+        description: "Drum kit with a bass ducked by the kick",
+        members: &[
+            MemberTemplate { name: "Kick", source: SourceType::Kit, layer_with: None, sidechain_from: None, send: None },
+            MemberTemplate { name: "Bass", source: SourceType::Saw, layer_with: None, sidechain_from: Some(0), send: None },
+        ],
+    },
+    GroupTemplate {
+        name: "Lead + Sub",
+        description: "Layered supersaw lead with a sine sub, sent to bus 1",
+        members: &[
+            MemberTemplate { name: "Lead", source: SourceType::SuperSaw, layer_with: None, sidechain_from: None, send: Some((1, 0.3)) },
+            MemberTemplate { name: "Sub", source: SourceType::Sin, layer_with: Some(0), sidechain_from: None, send: None },
+        ],
+    },
+    GroupTemplate {
+        name: "Pad Stack",
+        description: "Wavetable and saw pads layered, sent to bus 2",
+        members: &[
+            MemberTemplate { name: "Pad A", source: SourceType::Wavetable, layer_with: None, sidechain_from: None, send: Some((2, 0.4)) },
+            MemberTemplate { name: "Pad B", source: SourceType::Saw, layer_with: Some(0), sidechain_from: None, send: Some((2, 0.4)) },
+        ],
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn members_only_refer_to_earlier_members() {
+        for template in TEMPLATES {
+            for (i, member) in template.members.iter().enumerate() {
+                for j in member.layer_with.iter().chain(member.sidechain_from.iter()) {
+                    assert!(*j < i, "{}: {}", template.name, member.name);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn add_action_includes_every_member() {
+        let template = &TEMPLATES[0];
+        match template.add_action() {
+            Action::Instrument(InstrumentAction::AddGroup(members)) => {
+                assert_eq!(members.len(), template.members.len());
+                assert_eq!(members[1].sidechain_from, Some(0));
+            }
+            _ => panic!("expected AddGroup"),
+        }
+    }
+}
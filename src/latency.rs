@@ -0,0 +1,153 @@
+//! Timing diagnostics: how long the server takes to answer, how far ahead
+//! bundles are scheduled, which instruments play early or late, and what
+//! feedback the engine had to drop.
+//!
+//! Round trips come from the engine's /sync pings and are sampled a few
+//! times a second into a short history, so the panel can tell a steady
+//! connection from a drifting one.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::state::AppState;
+
+/// How often a round trip is recorded
+const SAMPLE_EVERY: Duration = Duration::from_millis(250);
+
+/// Samples kept: half a minute
+const HISTORY: usize = 120;
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    round_trip_ms: f32,
+    /// Feedback messages dropped since the engine started
+    dropped: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct LatencyMonitor {
+    samples: VecDeque<Sample>,
+    last_sample: Option<Instant>,
+}
+
+/// What the latency panel shows
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TimingReport {
+    pub last_ms: f32,
+    pub min_ms: f32,
+    pub avg_ms: f32,
+    pub max_ms: f32,
+    /// Spread between the fastest and slowest recent round trip
+    pub jitter_ms: f32,
+    /// How far ahead of their time bundles are sent
+    pub lead_ms: f32,
+    /// Instruments with a playback offset, and the offset
+    pub offsets: Vec<(String, f32)>,
+    pub dropped_total: u64,
+    /// Dropped within the history window
+    pub dropped_recent: u64,
+    pub suggestions: Vec<String>,
+}
+
+impl LatencyMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a round trip, at most once per `SAMPLE_EVERY`
+    pub fn sample(&mut self, round_trip_ms: f32, dropped: u64, now: Instant) {
+        if self.last_sample.is_some_and(|at| now.duration_since(at) < SAMPLE_EVERY) {
+            return;
+        }
+        self.last_sample = Some(now);
+        if self.samples.len() == HISTORY {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(Sample { round_trip_ms, dropped });
+    }
+
+    /// Start the history over, e.g. after changing server settings
+    pub fn reset(&mut self) {
+        self.samples.clear();
+        self.last_sample = None;
+    }
+
+    pub fn report(&self, state: &AppState, lead_ms: f32) -> TimingReport {
+        let offsets = state.instruments.instruments.iter()
+            .filter(|inst| inst.playback_offset_ms != 0.0)
+            .map(|inst| (inst.name.clone(), inst.playback_offset_ms))
+            .collect();
+        let mut report = TimingReport { lead_ms, offsets, ..Default::default() };
+        if let (Some(first), Some(last)) = (self.samples.front(), self.samples.back()) {
+            let times = self.samples.iter().map(|s| s.round_trip_ms);
+            report.last_ms = last.round_trip_ms;
+            report.min_ms = times.clone().fold(f32::INFINITY, f32::min);
+            report.max_ms = times.clone().fold(0.0, f32::max);
+            report.avg_ms = times.sum::<f32>() / self.samples.len() as f32;
+            report.jitter_ms = report.max_ms - report.min_ms;
+            report.dropped_total = last.dropped;
+            report.dropped_recent = last.dropped.saturating_sub(first.dropped);
+        }
+        report.suggestions = suggestions(&report);
+        report
+    }
+}
+
+/// Plain-language advice for whatever looks off
+fn suggestions(report: &TimingReport) -> Vec<String> {
+    let mut out = Vec::new();
+    if report.lead_ms > 0.0 && report.max_ms > report.lead_ms {
+        out.push(format!(
+            "Round trips reach {:.0} ms, past the {:.0} ms bundle lead: notes can arrive late. Lower the server's load or raise its latency.",
+            report.max_ms, report.lead_ms,
+        ));
+    }
+    if report.jitter_ms > 5.0 && report.jitter_ms > report.avg_ms {
+        out.push(format!(
+            "Latency swings by {:.0} ms, so timing is drifting. Close other audio apps or raise the server's block size.",
+            report.jitter_ms,
+        ));
+    }
+    if report.dropped_recent > 0 {
+        out.push(format!(
+            "{} feedback messages were dropped recently; meters and the playhead may stutter. Try a lower frame rate.",
+            report.dropped_recent,
+        ));
+    }
+    for (name, offset) in &report.offsets {
+        if report.lead_ms > 0.0 && -offset > report.lead_ms {
+            out.push(format!(
+                "{} plays {:.0} ms early, more than the bundle lead allows, so it can't be early enough.",
+                name, -offset,
+            ));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_spots_drift_and_drops() {
+        let state = AppState::new();
+        let start = Instant::now();
+        let mut monitor = LatencyMonitor::new();
+        for (i, (ms, dropped)) in [(2.0, 4), (3.0, 4), (40.0, 9), (2.5, 9)].into_iter().enumerate() {
+            monitor.sample(ms, dropped, start + SAMPLE_EVERY * i as u32);
+        }
+        // Too soon after the last sample
+        monitor.sample(100.0, 9, start + SAMPLE_EVERY * 3 + Duration::from_millis(10));
+
+        let report = monitor.report(&state, 20.0);
+        assert_eq!(report.last_ms, 2.5);
+        assert_eq!(report.max_ms, 40.0);
+        assert_eq!(report.jitter_ms, 38.0);
+        assert_eq!(report.dropped_recent, 5);
+        assert_eq!(report.suggestions.len(), 3);
+
+        monitor.reset();
+        assert!(monitor.report(&state, 20.0).suggestions.is_empty());
+    }
+}
@@ -0,0 +1,89 @@
+//! Frame rate and low-power mode.
+//!
+//! The screen redraws at the frame rate chosen in preferences. In
+//! low-power mode, meters, scopes and spectra stop updating while the
+//! terminal is unfocused or the session is idle (transport stopped and no
+//! key, mouse or MIDI input for a minute), and the screen redraws twice a
+//! second. While idle, input is also polled less often.
+
+use std::time::{Duration, Instant};
+
+/// Frame rates offered in preferences
+pub const FRAME_RATES: [u32; 4] = [60, 30, 20, 10];
+
+/// No input for this long, with the transport stopped, counts as idle
+const IDLE_AFTER: Duration = Duration::from_secs(60);
+
+/// Redraw interval while visualizations are paused
+const PAUSED_FRAME: Duration = Duration::from_millis(500);
+
+const POLL: Duration = Duration::from_millis(2);
+const IDLE_POLL: Duration = Duration::from_millis(20);
+
+#[derive(Debug)]
+pub struct PowerSaver {
+    last_input: Instant,
+    idle: bool,
+    paused: bool,
+}
+
+impl PowerSaver {
+    pub fn new(now: Instant) -> Self {
+        Self { last_input: now, idle: false, paused: false }
+    }
+
+    /// Key, mouse or MIDI input arrived
+    pub fn input(&mut self, now: Instant) {
+        self.last_input = now;
+        self.idle = false;
+        self.paused = false;
+    }
+
+    /// Recompute once per loop from the preference and current state
+    pub fn update(&mut self, low_power: bool, focused: bool, playing: bool, now: Instant) {
+        self.idle = low_power && !playing && now.duration_since(self.last_input) >= IDLE_AFTER;
+        self.paused = low_power && (!focused || self.idle);
+    }
+
+    /// Visualizations should not update
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn frame_interval(&self, frame_rate: u32) -> Duration {
+        if self.paused {
+            PAUSED_FRAME
+        } else {
+            Duration::from_millis(1000 / frame_rate.max(1) as u64)
+        }
+    }
+
+    pub fn poll_timeout(&self) -> Duration {
+        if self.idle { IDLE_POLL } else { POLL }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pauses_when_unfocused_or_idle() {
+        let start = Instant::now();
+        let mut power = PowerSaver::new(start);
+        power.update(true, false, true, start);
+        assert!(power.paused());
+        assert_eq!(power.poll_timeout(), POLL);
+
+        let later = start + IDLE_AFTER;
+        power.update(true, true, false, later);
+        assert!(power.paused());
+        assert_eq!(power.poll_timeout(), IDLE_POLL);
+        power.input(later);
+        assert!(!power.paused());
+
+        power.update(false, false, false, later + IDLE_AFTER);
+        assert!(!power.paused());
+        assert_eq!(power.frame_interval(30), Duration::from_millis(33));
+    }
+}
@@ -12,16 +12,67 @@ mod setup;
 mod ui;
 mod global_actions;
 mod midi_dispatch;
+mod control_surface;
+mod session_share;
+mod scripting;
+mod audio_to_midi;
+mod tempo_detect;
+mod preferences;
+mod project_check;
+mod diagnose;
+mod practice;
+mod velocity;
+mod instrument_groups;
+mod audition;
+mod perf_macros;
+mod value_entry;
+mod param_units;
+mod param_help;
+mod tutorial;
+mod clipboard;
+mod project_json;
+mod drum_import;
+mod midi_import;
+mod midi_export;
+mod session_manifest;
+mod scd_export;
+mod sclang;
+mod web_remote;
+mod sample_library;
+mod favorites;
+mod usage;
+mod project_search;
+mod batch_rename;
+mod safe_mode;
+mod vst_guard;
+mod sample_import;
+mod export_format;
+mod export_region;
+mod batch_export;
+mod render_queue;
+mod note_panic;
+mod voice_activity;
+mod rebuild_guard;
+mod low_power;
+mod view_state;
+mod workspace;
+mod av_sync;
+mod latency;
+mod automation_pickup;
+mod file_actions;
+mod pane_requests;
+mod background;
+mod export_jobs;
 
 use std::fs::File;
-use std::time::{Duration, Instant};
+use std::time::Instant;
 
 use audio::AudioHandle;
 use audio::commands::AudioCmd;
 use action::{AudioDirty, IoFeedback};
 use dispatch::LocalDispatcher;
 use imbolc_types::Dispatcher;
-use panes::{AddEffectPane, AddPane, AutomationPane, CommandPalettePane, ConfirmPane, EqPane, FileBrowserPane, FrameEditPane, HelpPane, HomePane, InstrumentEditPane, InstrumentPane, MidiSettingsPane, MixerPane, PianoRollPane, ProjectBrowserPane, QuitPromptPane, SaveAsPane, SampleChopperPane, SequencerPane, ServerPane, TrackPane, VstParamPane, WaveformPane};
+use panes::{AddEffectPane, AddPane, AutomationPane, BatchExportPane, BatchRenamePane, ChannelPastePane, ClipInspectorPane, CommandPalettePane, CompPane, ConfirmPane, ConsolePane, DiagnosePane, EqPane, ExportPane, FileBrowserPane, FrameEditPane, HelpPane, HomePane, InstrumentEditPane, InstrumentPane, LatencyPane, MidiMonitorPane, MidiSettingsPane, MixerPane, NoteGeneratorPane, PianoRollPane, PreferencesPane, ProjectBrowserPane, ProjectCheckPane, ProjectSearchPane, QuantizePane, QuitPromptPane, RandomLooperPane, RecordSettingsPane, RenderQueuePane, RoutingPane, SaveAsPane, SampleChopperPane, SampleLibraryPane, SclangPane, SequencerPane, ServerPane, TimeEditPane, TrackPane, TrackerPane, UndoHistoryPane, VstParamPane, WaveformPane};
 use state::AppState;
 use ui::{
     Action, AppEvent, Frame, InputSource, KeyCode, Keymap, LayerResult,
@@ -76,6 +127,9 @@ fn run(backend: &mut RatatuiBackend) -> std::io::Result<()> {
     let config = config::Config::load();
     let mut state = AppState::new_with_defaults(config.defaults());
     state.keyboard_layout = config.keyboard_layout();
+    let mut prefs = preferences::Preferences::load(state.keyboard_layout);
+    state.keyboard_layout = prefs.keyboard_layout();
+    clipboard::install_for_text_fields();
 
     // Load keybindings from embedded TOML (with optional user override)
     let (layers, mut keymaps) = keybindings::load_keybindings();
@@ -93,6 +147,21 @@ fn run(backend: &mut RatatuiBackend) -> std::io::Result<()> {
     panes.add_pane(Box::new(PianoRollPane::new(pane_keymap(&mut keymaps, "piano_roll"))));
     panes.add_pane(Box::new(SequencerPane::new(pane_keymap(&mut keymaps, "sequencer"))));
     panes.add_pane(Box::new(FrameEditPane::new(pane_keymap(&mut keymaps, "frame_edit"))));
+    panes.add_pane(Box::new(RecordSettingsPane::new(pane_keymap(&mut keymaps, "record_settings"))));
+    panes.add_pane(Box::new(NoteGeneratorPane::new(pane_keymap(&mut keymaps, "note_generator"))));
+    panes.add_pane(Box::new(QuantizePane::new(pane_keymap(&mut keymaps, "quantize"))));
+    panes.add_pane(Box::new(RandomLooperPane::new(pane_keymap(&mut keymaps, "random_looper"))));
+    panes.add_pane(Box::new(ClipInspectorPane::new(pane_keymap(&mut keymaps, "clip_inspector"))));
+    panes.add_pane(Box::new(TimeEditPane::new(pane_keymap(&mut keymaps, "time_edit"))));
+    panes.add_pane(Box::new(PreferencesPane::new(pane_keymap(&mut keymaps, "preferences"), prefs.clone())));
+    panes.add_pane(Box::new(UndoHistoryPane::new(pane_keymap(&mut keymaps, "undo_history"))));
+    panes.add_pane(Box::new(ProjectCheckPane::new(pane_keymap(&mut keymaps, "project_check"))));
+    panes.add_pane(Box::new(ChannelPastePane::new(pane_keymap(&mut keymaps, "channel_paste"))));
+    panes.add_pane(Box::new(RoutingPane::new(pane_keymap(&mut keymaps, "routing"))));
+    panes.add_pane(Box::new(DiagnosePane::new(pane_keymap(&mut keymaps, "diagnose"))));
+    panes.add_pane(Box::new(LatencyPane::new(pane_keymap(&mut keymaps, "latency"))));
+    panes.add_pane(Box::new(TrackerPane::new(pane_keymap(&mut keymaps, "tracker"))));
+    panes.add_pane(Box::new(CompPane::new(pane_keymap(&mut keymaps, "comp"))));
     panes.add_pane(Box::new(SampleChopperPane::new(pane_keymap(&mut keymaps, "sample_chopper"), file_browser_km)));
     panes.add_pane(Box::new(AddEffectPane::new(pane_keymap(&mut keymaps, "add_effect"))));
     panes.add_pane(Box::new(FileBrowserPane::new(pane_keymap(&mut keymaps, "file_browser"))));
@@ -107,6 +176,15 @@ fn run(backend: &mut RatatuiBackend) -> std::io::Result<()> {
     panes.add_pane(Box::new(SaveAsPane::new(pane_keymap(&mut keymaps, "save_as"))));
     panes.add_pane(Box::new(CommandPalettePane::new(pane_keymap(&mut keymaps, "command_palette"))));
     panes.add_pane(Box::new(MidiSettingsPane::new(pane_keymap(&mut keymaps, "midi_settings"))));
+    panes.add_pane(Box::new(MidiMonitorPane::new(pane_keymap(&mut keymaps, "midi_monitor"))));
+    panes.add_pane(Box::new(ConsolePane::new(pane_keymap(&mut keymaps, "console"))));
+    panes.add_pane(Box::new(SclangPane::new(pane_keymap(&mut keymaps, "sclang"))));
+    panes.add_pane(Box::new(SampleLibraryPane::new(pane_keymap(&mut keymaps, "sample_library"))));
+    panes.add_pane(Box::new(ProjectSearchPane::new(pane_keymap(&mut keymaps, "project_search"))));
+    panes.add_pane(Box::new(BatchRenamePane::new(pane_keymap(&mut keymaps, "batch_rename"))));
+    panes.add_pane(Box::new(ExportPane::new(pane_keymap(&mut keymaps, "export"))));
+    panes.add_pane(Box::new(BatchExportPane::new(pane_keymap(&mut keymaps, "batch_export"))));
+    panes.add_pane(Box::new(RenderQueuePane::new(pane_keymap(&mut keymaps, "render_queue"))));
 
     // Create layer stack
     let mut layer_stack = LayerStack::new(layers);
@@ -129,11 +207,64 @@ fn run(backend: &mut RatatuiBackend) -> std::io::Result<()> {
     }
     state.midi.port_names = midi_input.list_ports().iter().map(|p| p.name.clone()).collect();
     state.midi.connected_port = midi_input.connected_port_name().map(|s| s.to_string());
+    // Mackie Control surfaces get fader feedback on the matching output port
+    let mut midi_output = midi::MidiOutputManager::new();
+    let mut control_surface = control_surface::ControlSurface::new();
+    control_surface.sync_port(&mut midi_output, &state);
     let mut recent_projects = state::recent_projects::RecentProjects::load();
     let mut last_render_time = Instant::now();
     let mut select_mode = InstrumentSelectMode::Normal;
     let mut pending_audio_dirty = AudioDirty::default();
     let mut quit_after_save = false;
+    let mut autosave_interval = None;
+    let mut last_autosave = Instant::now();
+    let mut keyboard_strip = ui::widgets::KeyboardStrip::new();
+    let mut practice = practice::PracticeTracker::load();
+    let mut views = view_state::ViewStore::load();
+    let mut workspace_idx = 0;
+    let mut latency_monitor = latency::LatencyMonitor::new();
+    let mut playhead_watch = automation_pickup::PlayheadWatch::new();
+    let mut export_jobs = export_jobs::ExportJobs::new();
+    let mut escape_watch = note_panic::EscapeWatch::new();
+    let mut voice_activity = voice_activity::VoiceActivity::new();
+    let mut rebuild_guard = rebuild_guard::RebuildGuard::new();
+    let mut power = low_power::PowerSaver::new(Instant::now());
+    // Ballistics for the level meter, spectrum and scope: [left, right]
+    let mut peak_meters = [ui::ballistics::Meter::new(); 2];
+    let mut rms_meters = [ui::ballistics::Meter::new(); 2];
+    let mut band_meters = ui::ballistics::BandMeters::new();
+    let mut scope_meter = ui::ballistics::Meter::new();
+    let mut plugin_guard = vst_guard::VstGuard::new();
+    let mut background = background::Background::start(&prefs, &mut panes);
+    prefs.apply(&mut state, &mut panes, &mut autosave_interval);
+
+    // Experimental session sharing (--host[=port] / --join=addr, --share-key=key)
+    let cli_args: Vec<String> = std::env::args().collect();
+    let mut session_share = match session_share::from_args(&cli_args) {
+        Some(Ok(share)) => Some(share),
+        Some(Err(e)) => {
+            log::error!("session share: {}", e);
+            show_status(&mut panes, &audio, &format!("Session share: {}", e));
+            None
+        }
+        None => None,
+    };
+
+    // Recover projects instead of refusing them (--safe-mode)
+    let safe = safe_mode::requested(&cli_args);
+
+    // Read-only web view of transport and mixer (--web[=port])
+    let mut web_remote = match web_remote::from_args(&cli_args) {
+        Some(Ok(remote)) => {
+            log::info!("web remote: serving on port {}", remote.port());
+            Some(remote)
+        }
+        Some(Err(e)) => {
+            log::error!("web remote: could not listen: {}", e);
+            None
+        }
+        None => None,
+    };
 
     // CLI argument: optional project path (skip flags like --verbose)
     let project_arg = std::env::args()
@@ -141,7 +272,14 @@ fn run(backend: &mut RatatuiBackend) -> std::io::Result<()> {
         .find(|a| !a.starts_with('-'));
     if let Some(arg) = project_arg {
         let load_path = std::path::PathBuf::from(&arg);
-        if load_path.exists() {
+        if load_path.exists() && safe {
+            let status = file_actions::load_in_safe_mode(&load_path, &mut state, &mut panes, &mut app_frame, &mut audio, &io_tx);
+            pending_audio_dirty.merge(AudioDirty::all());
+            layer_stack.set_pane_layer(panes.active().id());
+            if let Some(server) = panes.get_pane_mut::<ServerPane>("server") {
+                server.set_status(audio.status(), &status);
+            }
+        } else if load_path.exists() {
             // Load existing project
             if let Ok((session, instruments)) = state::persistence::load_project(&load_path) {
                 let name = load_path.file_stem()
@@ -160,6 +298,9 @@ fn run(backend: &mut RatatuiBackend) -> std::io::Result<()> {
                 } else {
                     panes.switch_to("instrument_edit", &state);
                 }
+                if let Some(view) = state.project.path.as_deref().and_then(|p| views.get(p)).cloned() {
+                    view_state::restore(&view, &mut panes, &mut state);
+                }
                 layer_stack.set_pane_layer(panes.active().id());
             }
         } else {
@@ -175,10 +316,15 @@ fn run(backend: &mut RatatuiBackend) -> std::io::Result<()> {
 
     // Auto-start SuperCollider and apply status events
     {
-        let startup_events = setup::auto_start_sc(&mut audio);
-        apply_status_events(&startup_events, &mut panes);
+        if prefs.auto_start_server {
+            let startup_events = setup::auto_start_sc(&mut audio, &prefs.server_address);
+            apply_status_events(&startup_events, &mut panes);
+        }
     }
 
+    // First run: walk through the basics
+    let mut tutorial = (!prefs.tutorial_seen).then(|| tutorial::Tutorial::new(&state));
+
     // Track last render area for mouse hit-testing
     let mut last_area = ratatui::layout::Rect::new(0, 0, 80, 24);
 
@@ -186,7 +332,22 @@ fn run(backend: &mut RatatuiBackend) -> std::io::Result<()> {
         // Sync layer stack in case dispatch switched panes last iteration
         layer_stack.set_pane_layer(panes.active().id());
 
-        if let Some(app_event) = backend.poll_event(Duration::from_millis(2)) {
+        if let Some(app_event) = backend.poll_event(power.poll_timeout()) {
+            practice.note_input(Instant::now());
+            power.input(Instant::now());
+            if matches!(app_event, AppEvent::Key(_)) {
+                app_frame.alert = None;
+            }
+            // Key behind a note action, for computer-keyboard velocity
+            let note_key = match &app_event {
+                AppEvent::Key(event) if layer_stack.has_layer("piano_mode") => match event.key {
+                    KeyCode::Char(c) => Some(ui::translate_key(c, state.keyboard_layout)),
+                    _ => None,
+                },
+                _ => None,
+            };
+
+            let escape = matches!(&app_event, AppEvent::Key(event) if matches!(event.key, KeyCode::Escape));
             let pane_action = match app_event {
                 AppEvent::Mouse(mouse_event) => {
                     panes.active_mut().handle_mouse(&mouse_event, last_area, &state)
@@ -223,6 +384,10 @@ fn run(backend: &mut RatatuiBackend) -> std::io::Result<()> {
                         InstrumentSelectMode::Normal => {}
                     }
 
+                    if !matches!(event.key, KeyCode::Escape) {
+                        escape_watch.other_key();
+                    }
+
                     // Layer resolution
                     match layer_stack.resolve(&event) {
                         LayerResult::Action(action) => {
@@ -242,6 +407,27 @@ fn run(backend: &mut RatatuiBackend) -> std::io::Result<()> {
                                     backend.clear()?;
                                     continue;
                                 }
+                                GlobalResult::Panic => {
+                                    note_panic::all_notes_off(&mut audio, &mut midi_output, &prefs, &mut panes);
+                                    background.stop_playback();
+                                    continue;
+                                }
+                                GlobalResult::Workspace(n) => {
+                                    workspace::switch(n, &mut workspace_idx, &prefs, &mut panes, &mut layer_stack, &state, &audio);
+                                    continue;
+                                }
+                                GlobalResult::SaveWorkspace => {
+                                    workspace::save_current(workspace_idx, &mut prefs, &mut panes, &layer_stack, &audio);
+                                    continue;
+                                }
+                                GlobalResult::ToggleTutorial => {
+                                    if tutorial.take().is_some() {
+                                        tutorial::mark_seen(&mut prefs, &mut panes);
+                                    } else {
+                                        tutorial = Some(tutorial::Tutorial::new(&state));
+                                    }
+                                    continue;
+                                }
                                 GlobalResult::Handled => continue,
                                 GlobalResult::NotHandled => {
                                     panes.active_mut().handle_action(action, &event, &state)
@@ -255,6 +441,45 @@ fn run(backend: &mut RatatuiBackend) -> std::io::Result<()> {
                 }
             };
 
+            let mut pane_action = match note_key {
+                Some(key) => velocity::map_note_velocity(pane_action, |_| prefs.key_velocity_for(key)),
+                None => pane_action,
+            };
+
+            // Escape twice quickly is a panic; the second one still does its usual job.
+            // An Escape that closed or cleared something doesn't start one.
+            if escape && escape_watch.press(Instant::now(), matches!(pane_action, Action::None)) {
+                note_panic::all_notes_off(&mut audio, &mut midi_output, &prefs, &mut panes);
+                background.stop_playback();
+            }
+
+            // Samples at another rate than the running server's are converted
+            // first; the load is dispatched when the conversion finishes
+            if let Some(rate) = audio.server_sample_rate() {
+                if let Some(status) = background.convert_sample(&mut pane_action, state.project.path.as_deref(), rate, &prefs) {
+                    show_status(&mut panes, &audio, &status);
+                }
+            }
+
+            if let Some(t) = tutorial.as_mut() {
+                t.observe(&pane_action);
+            }
+
+            // Light up notes played from the computer keyboard
+            match &pane_action {
+                Action::Instrument(action::InstrumentAction::PlayNote(pitch, _))
+                | Action::PianoRoll(action::PianoRollAction::PlayNote { pitch, .. }) => {
+                    keyboard_strip.key_pressed(*pitch);
+                }
+                Action::Instrument(action::InstrumentAction::PlayNotes(pitches, _))
+                | Action::PianoRoll(action::PianoRollAction::PlayNotes { pitches, .. }) => {
+                    for pitch in pitches {
+                        keyboard_strip.key_pressed(*pitch);
+                    }
+                }
+                _ => {}
+            }
+
             // Process layer management actions
             match &pane_action {
                 Action::PushLayer(name) => {
@@ -282,6 +507,26 @@ fn run(backend: &mut RatatuiBackend) -> std::io::Result<()> {
                         panes.get_pane_mut::<FrameEditPane>("frame_edit")
                             .map_or(false, |p| p.is_editing())
                     }
+                    "preferences" => {
+                        panes.get_pane_mut::<PreferencesPane>("preferences")
+                            .map_or(false, |p| p.is_editing())
+                    }
+                    "instrument" => {
+                        panes.get_pane_mut::<InstrumentPane>("instrument")
+                            .map_or(false, |p| p.is_editing())
+                    }
+                    "mixer" => {
+                        panes.get_pane_mut::<MixerPane>("mixer")
+                            .map_or(false, |p| p.is_editing())
+                    }
+                    "help" => {
+                        panes.get_pane_mut::<HelpPane>("help")
+                            .map_or(false, |p| p.is_editing())
+                    }
+                    "piano_roll" => {
+                        panes.get_pane_mut::<PianoRollPane>("piano_roll")
+                            .map_or(false, |p| p.is_editing())
+                    }
                     _ => false,
                 };
                 if !still_editing {
@@ -326,6 +571,16 @@ fn run(backend: &mut RatatuiBackend) -> std::io::Result<()> {
                             &mut select_mode, &mut pending_audio_dirty, &mut layer_stack, &io_tx,
                         );
                         if matches!(global_result, GlobalResult::Quit) { break; }
+                        if matches!(global_result, GlobalResult::Panic) {
+                            note_panic::all_notes_off(&mut audio, &mut midi_output, &prefs, &mut panes);
+                            background.stop_playback();
+                        }
+                        if let GlobalResult::Workspace(n) = global_result {
+                            workspace::switch(n, &mut workspace_idx, &prefs, &mut panes, &mut layer_stack, &state, &audio);
+                        }
+                        if matches!(global_result, GlobalResult::SaveWorkspace) {
+                            workspace::save_current(workspace_idx, &mut prefs, &mut panes, &layer_stack, &audio);
+                        }
                         if matches!(global_result, GlobalResult::NotHandled) {
                             let dummy_event = ui::InputEvent::new(KeyCode::Enter, ui::Modifiers::none());
                             let re_action = panes.active_mut().handle_action(cmd, &dummy_event, &state);
@@ -356,9 +611,11 @@ fn run(backend: &mut RatatuiBackend) -> std::io::Result<()> {
                     }
                 }
                 state.midi.port_names = midi_input.list_ports().iter().map(|p| p.name.clone()).collect();
+                control_surface.sync_port(&mut midi_output, &state);
             } else if let Action::Midi(action::MidiAction::DisconnectPort) = &pane_action {
                 midi_input.disconnect();
                 state.midi.connected_port = None;
+                control_surface.sync_port(&mut midi_output, &state);
             }
 
             // Intercept SaveAndQuit — handle in main.rs, not dispatch
@@ -381,7 +638,17 @@ fn run(backend: &mut RatatuiBackend) -> std::io::Result<()> {
                     sync_pane_layer(&mut panes, &mut layer_stack);
                     quit_after_save = true;
                 }
+            } else if file_actions::handle(
+                &pane_action, safe, &mut state, &mut panes, &mut audio, &mut app_frame, &mut pending_audio_dirty, &io_tx,
+            ) {
+                // Imports, exports and scripts picked in the file browser
+                sync_pane_layer(&mut panes, &mut layer_stack);
             } else {
+                if matches!(&pane_action, Action::Server(
+                    action::ServerAction::Stop | action::ServerAction::Disconnect | action::ServerAction::Restart { .. }
+                )) {
+                    plugin_guard.expect_stop();
+                }
                 let dispatch_result = LocalDispatcher::new(&mut state, &mut audio, &io_tx).dispatch(&pane_action);
                 if dispatch_result.quit {
                     break;
@@ -391,9 +658,20 @@ fn run(backend: &mut RatatuiBackend) -> std::io::Result<()> {
             }
         }
 
-        if pending_audio_dirty.any() {
-            audio.flush_dirty(&state, pending_audio_dirty);
-            pending_audio_dirty.clear();
+        // Rebuilding routing under held notes waits for them to release.
+        // A region render keeps the engine's region-only arrangement until it ends.
+        if pending_audio_dirty.any() && !export_jobs.region_render() {
+            match rebuild_guard.step(pending_audio_dirty.routing, voice_activity.sounding(), Instant::now()) {
+                rebuild_guard::Step::Release => audio.release_all_voices(),
+                rebuild_guard::Step::Wait => {}
+                rebuild_guard::Step::Flush { released } => {
+                    audio.flush_dirty(&state, pending_audio_dirty);
+                    pending_audio_dirty.clear();
+                    if released {
+                        audio.clear_active_notes();
+                    }
+                }
+            }
         }
 
         // Drain I/O feedback
@@ -410,6 +688,7 @@ fn run(backend: &mut RatatuiBackend) -> std::io::Result<()> {
                             recent_projects.add(&path, &name);
                             recent_projects.save();
                             app_frame.set_project_name(name);
+                            views.remember(&mut panes, &state);
                             "Saved project".to_string()
                         }
                         Err(e) => format!("Save failed: {}", e),
@@ -424,6 +703,8 @@ fn run(backend: &mut RatatuiBackend) -> std::io::Result<()> {
                     }
                      match result {
                          Ok((new_session, new_instruments, name)) => {
+                             // Leave the outgoing project where it was
+                             views.remember(&mut panes, &state);
                              state.undo_history.clear();
                              state.session = new_session;
                              state.instruments = new_instruments;
@@ -435,30 +716,14 @@ fn run(backend: &mut RatatuiBackend) -> std::io::Result<()> {
                              
                              if state.instruments.instruments.is_empty() {
                                  panes.switch_to("add", &state);
+                             } else if let Some(view) = views.get(&path).cloned() {
+                                 view_state::restore(&view, &mut panes, &mut state);
                              }
 
                              let dirty = AudioDirty::all();
                              pending_audio_dirty.merge(dirty);
                              
-                             // Queue VST state restores
-                             for inst in &state.instruments.instruments {
-                                if let (state::SourceType::Vst(_), Some(ref path)) = (&inst.source, &inst.vst_state_path) {
-                                    let _ = audio.send_cmd(audio::commands::AudioCmd::LoadVstState {
-                                        instrument_id: inst.id,
-                                        target: action::VstTarget::Source,
-                                        path: path.clone(),
-                                    });
-                                }
-                                for effect in &inst.effects {
-                                    if let (state::EffectType::Vst(_), Some(ref path)) = (&effect.effect_type, &effect.vst_state_path) {
-                                        let _ = audio.send_cmd(audio::commands::AudioCmd::LoadVstState {
-                                            instrument_id: inst.id,
-                                            target: action::VstTarget::Effect(effect.id),
-                                            path: path.clone(),
-                                        });
-                                    }
-                                }
-                             }
+                             file_actions::queue_vst_state_restores(&state, &mut audio);
 
                              if let Some(server) = panes.get_pane_mut::<ServerPane>("server") {
                                  server.set_status(audio.status(), "Project loaded");
@@ -544,27 +809,152 @@ fn run(backend: &mut RatatuiBackend) -> std::io::Result<()> {
 
         // Drain audio feedback
         for feedback in audio.drain_feedback() {
+            // Voice counts only feed the mixer and instrument list
+            if let audio::commands::AudioFeedback::VoiceCount { instrument_id, active, stolen } = feedback {
+                voice_activity.report(instrument_id, active, stolen, Instant::now());
+                continue;
+            }
             let action = Action::AudioFeedback(feedback);
             let r = LocalDispatcher::new(&mut state, &mut audio, &io_tx).dispatch(&action);
             pending_audio_dirty.merge(r.audio_dirty);
             apply_dispatch_result(r, &mut state, &mut panes, &mut app_frame, &mut audio);
         }
 
+        export_jobs.poll_finished(&state, &mut panes, &audio, &mut pending_audio_dirty);
+
         // Poll MIDI events
         for event in midi_input.poll_events() {
-            if let Some(action) = midi_dispatch::process_midi_event(&event, &state) {
+            power.input(Instant::now());
+            match &event {
+                midi::MidiEvent::NoteOn { note, velocity, .. } => keyboard_strip.midi_note(*note, *velocity > 0),
+                midi::MidiEvent::NoteOff { note, .. } => keyboard_strip.midi_note(*note, false),
+                _ => {}
+            }
+            if let Some(monitor) = panes.get_pane_mut::<MidiMonitorPane>("midi_monitor") {
+                monitor.push(&event, midi_input.connected_port_name());
+            }
+            if let Some(actions) = control_surface.translate(&event, &state) {
+                for action in actions {
+                    let r = LocalDispatcher::new(&mut state, &mut audio, &io_tx).dispatch(&action);
+                    pending_audio_dirty.merge(r.audio_dirty);
+                }
+            } else if let Some(action) = midi_dispatch::process_midi_event(&event, &state) {
+                let curve = prefs.midi_velocity_curve;
+                let action = velocity::map_note_velocity(action, |v| curve.apply(v));
                 let r = LocalDispatcher::new(&mut state, &mut audio, &io_tx).dispatch(&action);
                 pending_audio_dirty.merge(r.audio_dirty);
             }
         }
 
-        // Visual updates and rendering at ~60fps
+        // Send a shared session peer what this pass changed, then apply its edits
+        if let Some(share) = session_share.as_mut() {
+            share.send_changes(&state);
+            for op in share.poll() {
+                if let Some(action) = op.to_action(&state) {
+                    let r = LocalDispatcher::new(&mut state, &mut audio, &io_tx).dispatch(&action);
+                    pending_audio_dirty.merge(r.audio_dirty);
+                    apply_dispatch_result(r, &mut state, &mut panes, &mut app_frame, &mut audio);
+                }
+            }
+            share.applied(&state);
+            if let Some(status) = share.take_status() {
+                show_status(&mut panes, &audio, &status);
+            }
+        }
+
+        // Move new status messages into the console, and copy from it
+        let messages = panes.get_pane_mut::<ServerPane>("server").map(|p| p.take_messages()).unwrap_or_default();
+        if let Some(console) = panes.get_pane_mut::<ConsolePane>("console") {
+            for message in &messages {
+                console.push(message);
+            }
+            if let Some(text) = console.take_copy() {
+                let notice = match clipboard::copy(&text) {
+                    Ok(method) => format!("Copied ({})", method.name()),
+                    Err(e) => format!("Copy failed: {}", e),
+                };
+                console.set_notice(notice);
+            }
+        }
+
+        background.serve_sclang(&mut panes, &prefs.server_address);
+        background.serve_library(&mut panes, &prefs);
+
+        pane_requests::poll(
+            &mut state,
+            &mut panes,
+            &mut audio,
+            &mut app_frame,
+            &mut select_mode,
+            &mut pending_audio_dirty,
+            &mut layer_stack,
+            &io_tx,
+        );
+
+        export_jobs.poll_requests(
+            &mut prefs,
+            &mut state,
+            &mut panes,
+            &mut audio,
+            &mut app_frame,
+            &mut pending_audio_dirty,
+            &mut layer_stack,
+            &io_tx,
+        );
+
+        // Play notes auditioned from the piano roll or tracker, and drum sequencer performance macros
+        for action in background.poll_playback(&mut panes, Instant::now()) {
+            let r = LocalDispatcher::new(&mut state, &mut audio, &io_tx).dispatch(&action);
+            pending_audio_dirty.merge(r.audio_dirty);
+        }
+        for (action, status) in background.poll_conversions() {
+            if let Some(status) = status {
+                show_status(&mut panes, &audio, &status);
+            }
+            dispatch_and_apply(&action, &mut state, &mut panes, &mut audio, &mut app_frame, &mut pending_audio_dirty, &io_tx);
+        }
+
+        export_jobs.poll_patterns(
+            &mut state, &mut panes, &mut audio, &mut app_frame, &mut pending_audio_dirty, &mut layer_stack, &io_tx,
+        );
+
+        // Save and hot-apply edited preferences
+        if let Some(changed) = panes.get_pane_mut::<PreferencesPane>("preferences").and_then(|p| p.take_changed()) {
+            if let Err(e) = changed.save() {
+                log::error!("preferences: could not save: {}", e);
+            }
+            changed.apply(&mut state, &mut panes, &mut autosave_interval);
+            prefs = changed;
+        }
+
+        // Periodic autosave of projects that already have a path
+        if let Some(interval) = autosave_interval {
+            if last_autosave.elapsed() >= interval {
+                last_autosave = Instant::now();
+                if state.project.dirty && state.project.path.is_some() {
+                    let r = LocalDispatcher::new(&mut state, &mut audio, &io_tx)
+                        .dispatch(&Action::Session(action::SessionAction::Save));
+                    pending_audio_dirty.merge(r.audio_dirty);
+                    apply_dispatch_result(r, &mut state, &mut panes, &mut app_frame, &mut audio);
+                }
+            }
+        }
+
+        // Motorized fader feedback
+        for message in control_surface.fader_feedback(&state) {
+            let _ = midi_output.send(&message);
+        }
+
+        // Visual updates and rendering at the preferred frame rate
         let now_render = Instant::now();
-        if now_render.duration_since(last_render_time).as_millis() >= 16 {
+        power.update(prefs.low_power, backend.is_focused(), state.session.piano_roll.playing, now_render);
+        if now_render.duration_since(last_render_time) >= power.frame_interval(prefs.frame_rate) {
             last_render_time = now_render;
+            // Low-power mode leaves meters, scopes and spectra as they were
+            let visualize = !power.paused();
 
             // Update master meter from real audio peak
-            {
+            if visualize {
                 let peak = if audio.is_running() {
                     audio.master_peak()
                 } else {
@@ -574,11 +964,38 @@ fn run(backend: &mut RatatuiBackend) -> std::io::Result<()> {
                 app_frame.set_master_peak(peak, mute);
             }
 
+            // Credit time to the project and refresh the session timer
+            practice.tick(now_render, state.session.piano_roll.playing, state.project.path.as_deref());
+            app_frame.session_timer = practice.status_text(prefs.session_timer, now_render, state.project.path.as_deref());
+
+            // A VST plugin that crashes takes scsynth with it: disable it and restart
+            let server_up = matches!(audio.status(), audio::ServerStatus::Connected);
+            if let Some(crash) = plugin_guard.poll(&state, server_up, now_render) {
+                let message = vst_guard::recover(
+                    crash, &mut state, &mut panes, &mut audio, &mut app_frame, &mut pending_audio_dirty, &io_tx,
+                );
+                app_frame.alert = Some(message);
+            }
+
             // Update SC CPU and latency indicators
             {
                 let cpu = if audio.is_running() { audio.sc_cpu() } else { 0.0 };
                 let latency = if audio.is_running() { audio.osc_latency_ms() } else { 0.0 };
                 app_frame.set_sc_metrics(cpu, latency);
+                if audio.is_running() {
+                    latency_monitor.sample(latency, audio.dropped_feedback(), now_render);
+                }
+                if let Some(pane) = panes.get_pane_mut::<LatencyPane>("latency") {
+                    if pane.take_reset_requested() {
+                        latency_monitor.reset();
+                    }
+                }
+                if panes.active().id() == "latency" {
+                    let report = latency_monitor.report(&state, audio.bundle_lead_ms());
+                    if let Some(pane) = panes.get_pane_mut::<LatencyPane>("latency") {
+                        pane.set_report(report, audio.is_running());
+                    }
+                }
             }
 
             // Update recording state
@@ -589,20 +1006,66 @@ fn run(backend: &mut RatatuiBackend) -> std::io::Result<()> {
             app_frame.recording_secs = state.recording.recording_secs;
 
             // Update visualization data from audio analysis synths
-            state.audio.visualization.spectrum_bands = audio.spectrum_bands();
-            let (peak_l, peak_r, rms_l, rms_r) = audio.lufs_data();
-            state.audio.visualization.peak_l = peak_l;
-            state.audio.visualization.peak_r = peak_r;
-            state.audio.visualization.rms_l = rms_l;
-            state.audio.visualization.rms_r = rms_r;
-            let scope = audio.scope_buffer();
-            state.audio.visualization.scope_buffer.clear();
-            state.audio.visualization.scope_buffer.extend(scope);
+            // Meters and spectrum get ballistics; peaks show the held value
+            if visualize {
+                let mut bands = audio.spectrum_bands();
+                band_meters.apply(&mut bands, now_render);
+                state.audio.visualization.spectrum_bands = bands;
+                let (peak_l, peak_r, rms_l, rms_r) = audio.lufs_data();
+                peak_meters[0].update(peak_l, now_render);
+                peak_meters[1].update(peak_r, now_render);
+                rms_meters[0].update(rms_l, now_render);
+                rms_meters[1].update(rms_r, now_render);
+                state.audio.visualization.peak_l = peak_meters[0].peak();
+                state.audio.visualization.peak_r = peak_meters[1].peak();
+                state.audio.visualization.rms_l = rms_meters[0].level();
+                state.audio.visualization.rms_r = rms_meters[1].level();
+                let scope = audio.scope_buffer();
+                state.audio.visualization.scope_buffer.clear();
+                state.audio.visualization.scope_buffer.extend(scope);
+                let scope_max = state.audio.visualization.scope_buffer.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+                scope_meter.update(scope_max, now_render);
+                if panes.active().id() == "waveform" {
+                    if let Some(wf) = panes.get_pane_mut::<WaveformPane>("waveform") {
+                        wf.set_peak_holds(band_meters.peaks(), scope_meter.peak());
+                    }
+                }
+            }
+            if let Some(remote) = web_remote.as_mut() {
+                remote.publish(&state);
+            }
+
+            // Per-bus spectrum for the mixer's tilt meters
+            if visualize && panes.active().id() == "mixer" && audio.is_running() {
+                let bands = state.session.mixer.buses.iter()
+                    .map(|bus| (bus.id, audio.bus_spectrum_bands(bus.id)))
+                    .collect();
+                if let Some(mixer) = panes.get_pane_mut::<MixerPane>("mixer") {
+                    mixer.set_bus_spectrum(bands);
+                }
+            }
+
+            // Voice counts for the mixer strips and instrument list
+            match panes.active().id() {
+                "mixer" => {
+                    let voices = voice_activity.snapshot(Instant::now());
+                    if let Some(mixer) = panes.get_pane_mut::<MixerPane>("mixer") {
+                        mixer.set_voice_activity(voices);
+                    }
+                }
+                "instrument" => {
+                    let voices = voice_activity.snapshot(Instant::now());
+                    if let Some(list) = panes.get_pane_mut::<InstrumentPane>("instrument") {
+                        list.set_voice_activity(voices);
+                    }
+                }
+                _ => {}
+            }
 
             // Update waveform cache for waveform pane
             if panes.active().id() == "waveform" {
                 if let Some(wf) = panes.get_pane_mut::<WaveformPane>("waveform") {
-                    if state.recorded_waveform_peaks.is_none() {
+                    if visualize && state.recorded_waveform_peaks.is_none() {
                         wf.audio_in_waveform = state.instruments.selected_instrument()
                             .filter(|s| s.source.is_audio_input() || s.source.is_bus_in())
                             .map(|s| audio.audio_in_waveform(s.id));
@@ -623,17 +1086,49 @@ fn run(backend: &mut RatatuiBackend) -> std::io::Result<()> {
                 state.audio.server_status = ars.server_status;
             }
 
-            // Render
+            // Started mid-song or jumped: bring automated parameters to
+            // their values here rather than waiting for the next point
+            let piano_roll = &state.session.piano_roll;
+            if let Some(tick) = playhead_watch.check(
+                piano_roll.playing,
+                state.audio.playhead,
+                state.audio.bpm,
+                piano_roll.ticks_per_beat,
+                Instant::now(),
+            ) {
+                for (target, value) in automation_pickup::values_at(&state, tick) {
+                    audio.apply_automation(&target, value);
+                }
+            }
+
+            if let Some(t) = tutorial.as_mut() {
+                t.tick(&state);
+                if t.is_done() {
+                    tutorial::mark_seen(&mut prefs, &mut panes);
+                }
+            }
+
+            // Render; panes draw the playhead ahead to match what is heard
             let mut frame = backend.begin_frame()?;
             let area = frame.area();
             last_area = area;
             let mut rbuf = ui::RenderBuf::new(frame.buffer_mut());
             app_frame.render_buf(area, &mut rbuf, &state);
             panes.render(area, &mut rbuf, &state);
+            if layer_stack.has_layer("piano_mode") && area.height > 8 {
+                let sounding = ui::widgets::playing_on_selected_track(&state, av_sync::VisualOffset::new(prefs.visual_offset_ms));
+                let strip_area = ui::Rect::new(area.x + 1, area.y + area.height - 4, area.width.saturating_sub(2), 2);
+                keyboard_strip.render(strip_area, &mut rbuf, &sounding);
+            }
+            if let Some(t) = tutorial.as_ref() {
+                t.render(area, &mut rbuf);
+            }
             backend.end_frame(frame)?;
         }
     }
 
+    views.remember(&mut panes, &state);
+    practice.save();
     Ok(())
 }
 
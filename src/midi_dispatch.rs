@@ -1,7 +1,19 @@
 use crate::action::{Action, AutomationAction, InstrumentAction};
 use crate::midi::MidiEvent;
+use crate::state::automation::AutomationTarget;
+use crate::state::drum_sequencer::NUM_PADS;
 use crate::state::AppState;
 
+/// Note of the first kit pad (C1, the General MIDI kick); the other pads
+/// follow chromatically
+const FIRST_PAD_NOTE: u8 = 36;
+
+/// Kit pad triggered by a MIDI note
+fn pad_for_note(note: u8) -> Option<usize> {
+    let pad = note.checked_sub(FIRST_PAD_NOTE)? as usize;
+    (pad < NUM_PADS).then_some(pad)
+}
+
 /// Process a MIDI event and return an Action if one should be dispatched.
 pub fn process_midi_event(event: &MidiEvent, state: &AppState) -> Option<Action> {
     let midi_rec = &state.session.midi_recording;
@@ -32,6 +44,12 @@ pub fn process_midi_event(event: &MidiEvent, state: &AppState) -> Option<Action>
                 return None;
             }
 
+            // Kits hit the pad for the note at the played velocity
+            if state.instruments.selected_instrument().map_or(false, |i| i.source.is_kit()) {
+                let pad = pad_for_note(*note)?;
+                return Some(Action::Instrument(InstrumentAction::PlayDrumPadVelocity(pad, *velocity)));
+            }
+
             // PlayNote uses the selected instrument
             Some(Action::Instrument(InstrumentAction::PlayNote(*note, *velocity)))
         }
@@ -61,6 +79,17 @@ pub fn process_midi_event(event: &MidiEvent, state: &AppState) -> Option<Action>
             Some(Action::Automation(AutomationAction::RecordValue(target, normalized)))
         }
 
+        MidiEvent::ChannelPressure { channel, value } => {
+            if !midi_rec.should_process_channel(*channel) {
+                return None;
+            }
+
+            // Pressing into a kit's pads opens its filter
+            let inst = state.instruments.selected_instrument().filter(|i| i.source.is_kit())?;
+            let normalized = *value as f32 / 127.0;
+            Some(Action::Automation(AutomationAction::RecordValue(AutomationTarget::FilterCutoff(inst.id), normalized)))
+        }
+
         _ => None,
     }
 }
@@ -68,8 +97,8 @@ pub fn process_midi_event(event: &MidiEvent, state: &AppState) -> Option<Action>
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::state::automation::AutomationTarget;
     use crate::state::midi_recording::MidiCcMapping;
+    use crate::state::SourceType;
 
     fn test_state() -> AppState {
         let mut state = AppState::new();
@@ -123,4 +152,28 @@ mod tests {
         let action = process_midi_event(&event, &state);
         assert!(action.is_none());
     }
+
+    #[test]
+    fn test_kit_notes_hit_pads_with_velocity() {
+        let mut state = test_state();
+        state.add_instrument(SourceType::Kit);
+        let event = MidiEvent::NoteOn { channel: 0, note: 38, velocity: 57 };
+        let action = process_midi_event(&event, &state);
+        assert!(matches!(action, Some(Action::Instrument(InstrumentAction::PlayDrumPadVelocity(2, 57)))));
+
+        // Notes outside the pad range do nothing
+        let event = MidiEvent::NoteOn { channel: 0, note: 30, velocity: 57 };
+        assert!(process_midi_event(&event, &state).is_none());
+    }
+
+    #[test]
+    fn test_channel_pressure_only_for_kits() {
+        let mut state = test_state();
+        let event = MidiEvent::ChannelPressure { channel: 0, value: 127 };
+        assert!(process_midi_event(&event, &state).is_none());
+
+        state.add_instrument(SourceType::Kit);
+        let action = process_midi_event(&event, &state);
+        assert!(matches!(action, Some(Action::Automation(AutomationAction::RecordValue(AutomationTarget::FilterCutoff(_), _)))));
+    }
 }
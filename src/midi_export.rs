@@ -0,0 +1,195 @@
+//! Standard MIDI file (.mid) export of the piano roll.
+//!
+//! The file is type 1: a tempo track carrying the session's tempo and time
+//! signature, then one track per piano roll track, named after its
+//! instrument. Tracks take channels in order, skipping channel 10, which
+//! goes to kits. Pulses per quarter note are the session's ticks_per_beat,
+//! so note positions are written as they are, unless the resolution is too
+//! fine for the header and gets scaled to 960.
+
+use std::path::{Path, PathBuf};
+
+use crate::state::AppState;
+
+/// General MIDI percussion, channel 10 counted from zero
+const DRUM_CHANNEL: u8 = 9;
+
+/// Largest PPQ the header can hold with metrical timing
+const MAX_PPQ: u32 = 0x7fff;
+
+fn vlq(mut value: u32, out: &mut Vec<u8>) {
+    let mut bytes = vec![(value & 0x7f) as u8];
+    value >>= 7;
+    while value > 0 {
+        bytes.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+    out.extend(bytes.iter().rev());
+}
+
+fn chunk(id: &[u8; 4], data: &[u8], out: &mut Vec<u8>) {
+    out.extend(id);
+    out.extend((data.len() as u32).to_be_bytes());
+    out.extend(data);
+}
+
+/// Track data from `(tick, event bytes)` pairs, already in order
+fn track_data(events: &[(u32, Vec<u8>)]) -> Vec<u8> {
+    let mut data = Vec::new();
+    let mut last = 0;
+    for (tick, bytes) in events {
+        vlq(tick - last, &mut data);
+        data.extend(bytes);
+        last = *tick;
+    }
+    vlq(0, &mut data);
+    data.extend([0xff, 0x2f, 0x00]);
+    data
+}
+
+fn track_name(name: &str) -> Vec<u8> {
+    let mut bytes = vec![0xff, 0x03];
+    vlq(name.len() as u32, &mut bytes);
+    bytes.extend(name.as_bytes());
+    bytes
+}
+
+/// Channels for piano roll tracks in order; kits share the drum channel
+fn channels(kits: &[bool]) -> Vec<u8> {
+    let melodic: Vec<u8> = (0..16).filter(|c| *c != DRUM_CHANNEL).collect();
+    let mut next = 0;
+    kits.iter()
+        .map(|&kit| {
+            if kit {
+                return DRUM_CHANNEL;
+            }
+            let channel = melodic[next % melodic.len()];
+            next += 1;
+            channel
+        })
+        .collect()
+}
+
+/// Track data for `notes` as `(tick, pitch, duration, velocity)`
+fn note_track(name: &str, channel: u8, notes: impl Iterator<Item = (u32, u8, u32, u8)>) -> Vec<u8> {
+    // (tick, offs before ons at the same tick, bytes)
+    let mut events: Vec<(u32, bool, Vec<u8>)> = Vec::new();
+    for (tick, pitch, duration, velocity) in notes {
+        events.push((tick, true, vec![0x90 | channel, pitch & 0x7f, velocity.clamp(1, 127)]));
+        events.push((tick + duration.max(1), false, vec![0x80 | channel, pitch & 0x7f, 0]));
+    }
+    events.sort_by_key(|(tick, on, _)| (*tick, *on));
+    let mut ordered = vec![(0, track_name(name))];
+    ordered.extend(events.into_iter().map(|(tick, _, bytes)| (tick, bytes)));
+    track_data(&ordered)
+}
+
+/// The whole piano roll as a type-1 file
+pub fn encode(state: &AppState) -> Vec<u8> {
+    let piano_roll = &state.session.piano_roll;
+    let tracks: Vec<_> = (0..piano_roll.track_order.len())
+        .filter_map(|i| piano_roll.track_at(i).map(|t| (piano_roll.track_order[i], t)))
+        .collect();
+    let instrument = |id| state.instruments.instruments.iter().find(|inst| inst.id == id);
+
+    // Odd resolutions beyond what the header holds are scaled down
+    let tpb = piano_roll.ticks_per_beat.max(1);
+    let (ppq, scale) = if tpb > MAX_PPQ { (960, 960.0 / tpb as f64) } else { (tpb, 1.0) };
+    let at = |tick: u32| (tick as f64 * scale).round() as u32;
+
+    let mut file = Vec::new();
+    let mut header = Vec::new();
+    header.extend(1u16.to_be_bytes());
+    header.extend((tracks.len() as u16 + 1).to_be_bytes());
+    header.extend((ppq as u16).to_be_bytes());
+    chunk(b"MThd", &header, &mut file);
+
+    let us_per_beat = (60_000_000.0 / (state.session.bpm as f64).max(1.0)).round() as u32;
+    let (numerator, denominator) = state.session.time_signature;
+    let conductor = [
+        (0, track_name("Tempo")),
+        (0, vec![0xff, 0x51, 0x03, (us_per_beat >> 16) as u8, (us_per_beat >> 8) as u8, us_per_beat as u8]),
+        (0, vec![0xff, 0x58, 0x04, numerator as u8, (denominator as u32).max(1).trailing_zeros() as u8, 24, 8]),
+    ];
+    chunk(b"MTrk", &track_data(&conductor), &mut file);
+
+    let kits: Vec<bool> = tracks.iter()
+        .map(|(id, _)| instrument(*id).is_some_and(|inst| inst.source.is_kit()))
+        .collect();
+    for ((id, track), channel) in tracks.iter().zip(channels(&kits)) {
+        let name = instrument(*id).map_or_else(|| "Track".to_string(), |inst| inst.name.clone());
+        let notes = track.notes.iter().map(|n| {
+            let start = at(n.tick);
+            (start, n.pitch, at(n.tick + n.duration).saturating_sub(start), n.velocity)
+        });
+        chunk(b"MTrk", &note_track(&name, channel, notes), &mut file);
+    }
+    file
+}
+
+/// Where MIDI exports go: next to the project when it has been saved
+pub fn export_dir(project_path: Option<&Path>) -> PathBuf {
+    project_path
+        .and_then(|p| p.parent())
+        .map(Path::to_path_buf)
+        .or_else(|| dirs::config_dir().map(|d| d.join("imbolc").join("midi")))
+        .unwrap_or_else(|| PathBuf::from("midi"))
+}
+
+/// Write `<project>.mid` into `dir`. Returns the file written.
+pub fn write(state: &AppState, dir: &Path) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let stem = state.project.path.as_deref()
+        .and_then(Path::file_stem)
+        .map_or_else(|| "untitled".to_string(), |s| s.to_string_lossy().into_owned());
+    let path = dir.join(format!("{}.mid", stem));
+    std::fs::write(&path, encode(state)).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::midi_import;
+    use crate::state::SourceType;
+
+    #[test]
+    fn tracks_read_back_through_the_importer() {
+        let mut file = Vec::new();
+        chunk(b"MThd", &[0, 1, 0, 1, 0x01, 0xe0], &mut file);
+        let notes = [(480, 43, 960, 90), (0, 36, 480, 100), (480, 36, 240, 0)];
+        chunk(b"MTrk", &note_track("Bass", 2, notes.into_iter()), &mut file);
+
+        let import = midi_import::parse(&file).unwrap();
+        assert_eq!(import.ppq, 480);
+        assert_eq!(import.parts.len(), 1);
+        assert_eq!(import.parts[0].name, "Bass");
+        assert_eq!(import.parts[0].channel, 2);
+        // The note ending at 480 is off before the one starting there
+        assert_eq!(import.parts[0].notes, vec![
+            midi_import::ImportedNote { tick: 0, pitch: 36, duration: 480, velocity: 100 },
+            midi_import::ImportedNote { tick: 480, pitch: 36, duration: 240, velocity: 1 },
+            midi_import::ImportedNote { tick: 480, pitch: 43, duration: 960, velocity: 90 },
+        ]);
+    }
+
+    #[test]
+    fn project_has_a_tempo_track_and_one_per_instrument() {
+        let mut state = AppState::new();
+        state.add_instrument(SourceType::Saw);
+        state.add_instrument(SourceType::Kit);
+        let bytes = encode(&state);
+        let tracks = u16::from_be_bytes([bytes[10], bytes[11]]) as usize;
+        assert_eq!(tracks, state.session.piano_roll.track_order.len() + 1);
+        let import = midi_import::parse(&bytes).unwrap();
+        assert_eq!(import.bpm.map(f32::round), Some(state.session.bpm as f32));
+    }
+
+    #[test]
+    fn kits_share_the_drum_channel() {
+        assert_eq!(channels(&[false, true, false, true]), vec![0, 9, 1, 9]);
+        let many = channels(&[false; 10]);
+        assert_eq!(many[8], 8);
+        assert_eq!(many[9], 10);
+    }
+}
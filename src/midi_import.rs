@@ -0,0 +1,347 @@
+//! Standard MIDI file (.mid) import into piano roll tracks.
+//!
+//! Every channel used in a file track becomes a part. A part goes to the
+//! instrument with the same name when there is one, otherwise to a new
+//! instrument: a kit for channel 10, a saw synth for the rest. Ticks are
+//! scaled from the file's pulses per quarter note to the session's
+//! ticks_per_beat. Notes land where they sit in the file, and ones that
+//! would fall on an existing note of the same pitch are left out.
+//! SMPTE-timed files aren't supported.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::path::Path;
+
+use crate::action::{Action, PianoRollAction};
+use crate::state::{AppState, InstrumentId, SourceType};
+
+/// General MIDI percussion, channel 10 counted from zero
+const DRUM_CHANNEL: u8 = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImportedNote {
+    /// In the file's pulses
+    pub tick: u32,
+    pub pitch: u8,
+    pub duration: u32,
+    pub velocity: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MidiPart {
+    pub name: String,
+    pub channel: u8,
+    pub notes: Vec<ImportedNote>,
+}
+
+impl MidiPart {
+    pub fn is_drums(&self) -> bool {
+        self.channel == DRUM_CHANNEL
+    }
+
+    /// Source for a new instrument playing this part
+    pub fn source(&self) -> SourceType {
+        if self.is_drums() { SourceType::Kit } else { SourceType::Saw }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MidiImport {
+    /// Pulses per quarter note
+    pub ppq: u16,
+    /// The file's first tempo
+    pub bpm: Option<f32>,
+    pub parts: Vec<MidiPart>,
+}
+
+pub fn load(path: &Path) -> Result<MidiImport, String> {
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    parse(&bytes)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(n).filter(|end| *end <= self.bytes.len()).ok_or("file ends early")?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn byte(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        let b = self.take(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let b = self.take(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    /// Variable-length quantity, at most four bytes
+    fn vlq(&mut self) -> Result<u32, String> {
+        let mut value = 0u32;
+        for _ in 0..4 {
+            let b = self.byte()?;
+            value = (value << 7) | (b & 0x7f) as u32;
+            if b & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("bad variable-length number".to_string())
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+}
+
+pub fn parse(bytes: &[u8]) -> Result<MidiImport, String> {
+    let mut r = Reader { bytes, pos: 0 };
+    if r.take(4).ok() != Some(b"MThd".as_slice()) {
+        return Err("not a MIDI file".to_string());
+    }
+    let header_len = r.u32()? as usize;
+    let _format = r.u16()?;
+    let track_count = r.u16()?;
+    let division = r.u16()?;
+    r.take(header_len.saturating_sub(6))?;
+    if division & 0x8000 != 0 || division == 0 {
+        return Err("SMPTE-timed MIDI files aren't supported".to_string());
+    }
+
+    let mut bpm = None;
+    let mut parts = Vec::new();
+    let mut track_idx = 0;
+    while track_idx < track_count && !r.at_end() {
+        let id = r.take(4)?;
+        let len = r.u32()? as usize;
+        let chunk = r.take(len)?;
+        // Unknown chunks are skipped, as the spec asks
+        if id != b"MTrk" {
+            continue;
+        }
+        track_idx += 1;
+        let track = parse_track(chunk)?;
+        bpm = bpm.or(track.bpm);
+        let multi = track.channels.len() > 1;
+        for (channel, notes) in track.channels {
+            let name = match (&track.name, multi) {
+                (Some(name), false) => name.clone(),
+                (Some(name), true) => format!("{} ch{}", name, channel + 1),
+                (None, _) if channel == DRUM_CHANNEL => "Drums".to_string(),
+                (None, _) => format!("Track {} ch{}", track_idx, channel + 1),
+            };
+            parts.push(MidiPart { name, channel, notes });
+        }
+    }
+    Ok(MidiImport { ppq: division, bpm, parts })
+}
+
+struct ParsedTrack {
+    name: Option<String>,
+    bpm: Option<f32>,
+    channels: BTreeMap<u8, Vec<ImportedNote>>,
+}
+
+fn parse_track(chunk: &[u8]) -> Result<ParsedTrack, String> {
+    let mut r = Reader { bytes: chunk, pos: 0 };
+    let mut track = ParsedTrack { name: None, bpm: None, channels: BTreeMap::new() };
+    // Notes sounding, per channel and pitch, oldest first
+    let mut open: HashMap<(u8, u8), VecDeque<(u32, u8)>> = HashMap::new();
+    let mut tick = 0u32;
+    let mut running = None;
+
+    while !r.at_end() {
+        tick = tick.saturating_add(r.vlq()?);
+        let mut status = r.byte()?;
+        let first_data = if status < 0x80 {
+            // Running status: this byte is data for the previous message
+            let data = status;
+            status = running.ok_or("data byte without a status")?;
+            Some(data)
+        } else {
+            None
+        };
+
+        match status {
+            0xff => {
+                let kind = r.byte()?;
+                let len = r.vlq()? as usize;
+                let data = r.take(len)?;
+                match kind {
+                    0x03 if track.name.is_none() => {
+                        let name = String::from_utf8_lossy(data).trim().to_string();
+                        track.name = Some(name).filter(|n| !n.is_empty());
+                    }
+                    0x51 if data.len() == 3 && track.bpm.is_none() => {
+                        let us_per_beat = u32::from_be_bytes([0, data[0], data[1], data[2]]);
+                        if us_per_beat > 0 {
+                            track.bpm = Some(60_000_000.0 / us_per_beat as f32);
+                        }
+                    }
+                    0x2f => break,
+                    _ => {}
+                }
+            }
+            0xf0 | 0xf7 => {
+                let len = r.vlq()? as usize;
+                r.take(len)?;
+            }
+            0x80..=0xef => {
+                running = Some(status);
+                let a = match first_data {
+                    Some(data) => data,
+                    None => r.byte()?,
+                };
+                let kind = status & 0xf0;
+                let channel = status & 0x0f;
+                // Program change and channel pressure carry one data byte
+                let b = if kind == 0xc0 || kind == 0xd0 { 0 } else { r.byte()? };
+                let (pitch, velocity) = (a & 0x7f, b & 0x7f);
+                if kind == 0x90 && velocity > 0 {
+                    open.entry((channel, pitch)).or_default().push_back((tick, velocity));
+                } else if kind == 0x80 || kind == 0x90 {
+                    if let Some((start, velocity)) = open.get_mut(&(channel, pitch)).and_then(|q| q.pop_front()) {
+                        track.channels.entry(channel).or_default().push(ImportedNote {
+                            tick: start,
+                            pitch,
+                            duration: (tick - start).max(1),
+                            velocity,
+                        });
+                    }
+                }
+            }
+            _ => return Err(format!("unexpected status byte {:#04x}", status)),
+        }
+    }
+
+    // Notes never released end with the track
+    for ((channel, pitch), starts) in open {
+        for (start, velocity) in starts {
+            track.channels.entry(channel).or_default().push(ImportedNote {
+                tick: start,
+                pitch,
+                duration: (tick - start).max(1),
+                velocity,
+            });
+        }
+    }
+    for notes in track.channels.values_mut() {
+        notes.sort_by_key(|n| (n.tick, n.pitch));
+    }
+    track.channels.retain(|_, notes| !notes.is_empty());
+    Ok(track)
+}
+
+/// Instrument already named like `part`, which its notes go to
+pub fn existing_instrument(part: &MidiPart, state: &AppState) -> Option<InstrumentId> {
+    state.instruments.instruments.iter()
+        .find(|inst| inst.name.eq_ignore_ascii_case(&part.name))
+        .map(|inst| inst.id)
+}
+
+fn scale(tick: u32, ppq: u16, ticks_per_beat: u32) -> u32 {
+    (tick as u64 * ticks_per_beat as u64 + ppq as u64 / 2).checked_div(ppq as u64).unwrap_or(0) as u32
+}
+
+/// Notes of `part` placed on piano roll `track`, skipping any that would
+/// toggle off a note already there or one placed before it
+pub fn note_actions(part: &MidiPart, track: usize, ppq: u16, state: &AppState) -> Vec<Action> {
+    let tpb = state.session.piano_roll.ticks_per_beat;
+    let mut taken: BTreeSet<(u32, u8)> = state.session.piano_roll.track_at(track)
+        .map(|t| t.notes.iter().map(|n| (n.tick, n.pitch)).collect())
+        .unwrap_or_default();
+    part.notes.iter()
+        .filter_map(|n| {
+            let tick = scale(n.tick, ppq, tpb);
+            let duration = scale(n.duration, ppq, tpb).max(1);
+            taken.insert((tick, n.pitch)).then_some(Action::PianoRoll(PianoRollAction::ToggleNote {
+                pitch: n.pitch,
+                tick,
+                duration,
+                velocity: n.velocity.max(1),
+                track,
+            }))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(id: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut out = id.to_vec();
+        out.extend((data.len() as u32).to_be_bytes());
+        out.extend(data);
+        out
+    }
+
+    /// Format 1, 96 PPQ: a tempo track, then a named track with a bass on
+    /// channel 1 (using running status) and a kick on channel 10
+    fn sample_file() -> Vec<u8> {
+        let mut file = chunk(b"MThd", &[0, 1, 0, 2, 0, 96]);
+        file.extend(chunk(b"MTrk", &[
+            0x00, 0xff, 0x51, 0x03, 0x07, 0xa1, 0x20, // 120 bpm
+            0x00, 0xff, 0x2f, 0x00,
+        ]));
+        file.extend(chunk(b"MTrk", &[
+            0x00, 0xff, 0x03, 0x04, b'B', b'a', b's', b's',
+            0x00, 0x90, 36, 100,
+            0x00, 0x99, 36, 120,
+            0x30, 0x89, 36, 0,
+            0x30, 0x90, 36, 0, // velocity 0 releases
+            0x00, 43, 90, // running status
+            0x81, 0x40, 0x80, 43, 0, // delta 192
+            0x00, 0xff, 0x2f, 0x00,
+        ]));
+        file
+    }
+
+    #[test]
+    fn channels_become_parts_with_lengths() {
+        let import = parse(&sample_file()).unwrap();
+        assert_eq!(import.ppq, 96);
+        assert_eq!(import.bpm, Some(120.0));
+        assert_eq!(import.parts.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), vec!["Bass ch1", "Bass ch10"]);
+        assert_eq!(import.parts[0].notes, vec![
+            ImportedNote { tick: 0, pitch: 36, duration: 96, velocity: 100 },
+            ImportedNote { tick: 96, pitch: 43, duration: 192, velocity: 90 },
+        ]);
+        assert!(import.parts[1].is_drums());
+        assert_eq!(import.parts[1].notes[0].duration, 48);
+
+        assert!(parse(b"RIFF0000").is_err());
+        assert!(parse(&sample_file()[..30]).is_err());
+    }
+
+    #[test]
+    fn notes_scale_to_the_session_and_skip_collisions() {
+        let mut state = AppState::new();
+        state.add_instrument(SourceType::Saw);
+        let tpb = state.session.piano_roll.ticks_per_beat;
+        let part = MidiPart {
+            name: "Lead".to_string(),
+            channel: 0,
+            notes: vec![
+                ImportedNote { tick: 48, pitch: 60, duration: 48, velocity: 80 },
+                // Lands on the same tick once scaled
+                ImportedNote { tick: 48, pitch: 60, duration: 24, velocity: 80 },
+            ],
+        };
+        let actions = note_actions(&part, 0, 96, &state);
+        assert_eq!(actions.len(), 1);
+        assert!(matches!(
+            actions[0],
+            Action::PianoRoll(PianoRollAction::ToggleNote { tick, duration, .. }) if tick == tpb / 2 && duration == tpb / 2
+        ));
+    }
+}
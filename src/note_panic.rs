@@ -0,0 +1,119 @@
+//! Stuck-note panic.
+//!
+//! The panic key (or Escape twice in quick succession) releases every
+//! voice, frees whatever is still running in the server's source group,
+//! forgets the notes the engine and its arpeggiators think are held and,
+//! when enabled in preferences, sends all-notes-off on every channel of
+//! the MIDI output.
+
+use std::time::{Duration, Instant};
+
+use crate::audio::commands::AudioCmd;
+use crate::audio::AudioHandle;
+use crate::global_actions::show_status;
+use crate::midi::MidiOutputManager;
+use crate::preferences::Preferences;
+use crate::ui::PaneManager;
+
+/// Second Escape within this long of the first is a panic
+const DOUBLE_ESCAPE: Duration = Duration::from_millis(400);
+
+/// Spots two Escape presses close together
+#[derive(Debug, Default)]
+pub struct EscapeWatch {
+    last: Option<Instant>,
+}
+
+impl EscapeWatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Any other key breaks up a double press
+    pub fn other_key(&mut self) {
+        self.last = None;
+    }
+
+    /// Record an Escape; true when it completes a double press. Only an
+    /// Escape nothing else used (`unused`) can start one.
+    pub fn press(&mut self, now: Instant, unused: bool) -> bool {
+        match self.last.take() {
+            Some(last) if now.duration_since(last) <= DOUBLE_ESCAPE => true,
+            _ => {
+                self.last = unused.then_some(now);
+                false
+            }
+        }
+    }
+}
+
+/// Sustain off, all notes off and all sound off on all 16 channels
+pub fn midi_reset() -> Vec<[u8; 3]> {
+    (0..16u8)
+        .flat_map(|channel| [64, 123, 120].map(|cc| [0xB0 | channel, cc, 0]))
+        .collect()
+}
+
+/// Silence stuck notes: release every voice, free what is left in the
+/// source group, reset the arpeggiators and, if enabled, send
+/// all-notes-off to the MIDI output
+pub(crate) fn all_notes_off(
+    audio: &mut AudioHandle,
+    midi_output: &mut MidiOutputManager,
+    prefs: &Preferences,
+    panes: &mut PaneManager,
+) {
+    if audio.is_running() {
+        audio.release_all_voices();
+        // The engine frees its own source group, wherever the server is
+        if let Err(e) = audio.send_cmd(AudioCmd::FreeSourceGroup) {
+            log::warn!("panic: freeing sources failed: {}", e);
+        }
+        // Freeing the group takes persistent sources like VST hosts with it
+        let _ = audio.rebuild_instrument_routing();
+    }
+    audio.clear_active_notes();
+    // Otherwise the arpeggiators keep stepping through the notes they held
+    if let Err(e) = audio.send_cmd(AudioCmd::ResetArpeggiators) {
+        log::warn!("panic: resetting arpeggiators failed: {}", e);
+    }
+    if prefs.panic_midi_reset {
+        for message in midi_reset() {
+            let _ = midi_output.send(&message);
+        }
+    }
+    show_status(panes, audio, "Panic: all notes off");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn double_escape_needs_two_quick_presses() {
+        let mut watch = EscapeWatch::new();
+        let now = Instant::now();
+        assert!(!watch.press(now, true));
+        assert!(watch.press(now + Duration::from_millis(200), true));
+        // A third press starts over
+        assert!(!watch.press(now + Duration::from_millis(300), true));
+        assert!(!watch.press(now + Duration::from_secs(2), true));
+    }
+
+    #[test]
+    fn escape_used_by_a_pane_does_not_arm() {
+        let mut watch = EscapeWatch::new();
+        let now = Instant::now();
+        assert!(!watch.press(now, false));
+        assert!(!watch.press(now + Duration::from_millis(200), true));
+        // The second press may still be used and complete the panic
+        assert!(watch.press(now + Duration::from_millis(300), false));
+    }
+
+    #[test]
+    fn midi_reset_covers_every_channel() {
+        let messages = midi_reset();
+        assert_eq!(messages.len(), 48);
+        assert!(messages.contains(&[0xBF, 123, 0]));
+    }
+}
@@ -0,0 +1,98 @@
+//! Requests panes leave for the main loop: popups to open, jumps that
+//! move between panes, and edits that need more than the pane can see.
+
+use std::sync::mpsc::Sender;
+use std::time::Instant;
+
+use crate::action::{AudioDirty, IoFeedback};
+use crate::audio::AudioHandle;
+use crate::global_actions::{
+    dispatch_and_apply, handle_global_action, jump_to_search_hit, show_status, sync_pane_layer,
+    InstrumentSelectMode,
+};
+use crate::panes::{
+    BatchRenamePane, ChannelPastePane, FileBrowserPane, InstrumentEditPane, MixerPane, PianoRollPane, ProjectSearchPane,
+    QuantizePane, SampleLibraryPane, UndoHistoryPane,
+};
+use crate::scd_export;
+use crate::state::AppState;
+use crate::ui::action_id::{ActionId, GlobalActionId};
+use crate::ui::{Frame, LayerStack, PaneManager};
+
+/// Act on whatever the panes asked for since the last pass
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn poll(
+    state: &mut AppState,
+    panes: &mut PaneManager,
+    audio: &mut AudioHandle,
+    app_frame: &mut Frame,
+    select_mode: &mut InstrumentSelectMode,
+    pending_audio_dirty: &mut AudioDirty,
+    layer_stack: &mut LayerStack,
+    io_tx: &Sender<IoFeedback>,
+) {
+    // Samples picked in the library are handed to the file browser
+    if let Some(path) = panes.get_pane_mut::<SampleLibraryPane>("sample_library").and_then(|p| p.take_choice()) {
+        if let Some(action) = panes.get_pane_mut::<FileBrowserPane>("file_browser").map(|fb| fb.select_file(path)) {
+            dispatch_and_apply(&action, state, panes, audio, app_frame, pending_audio_dirty, io_tx);
+        }
+    }
+    if let Some(target) = panes.get_pane_mut::<ProjectSearchPane>("project_search").and_then(|p| p.take_jump()) {
+        jump_to_search_hit(target, state, panes, audio, app_frame, pending_audio_dirty, io_tx);
+        sync_pane_layer(panes, layer_stack);
+    }
+    if let Some(action) = panes.get_pane_mut::<BatchRenamePane>("batch_rename").and_then(|p| p.take_renames()) {
+        dispatch_and_apply(&action, state, panes, audio, app_frame, pending_audio_dirty, io_tx);
+    }
+
+    // Jump through undo history, one step at a time like the global keys
+    if let Some(steps) = panes.get_pane_mut::<UndoHistoryPane>("undo_history").and_then(|p| p.take_jump()) {
+        let step = if steps < 0 { GlobalActionId::Undo } else { GlobalActionId::Redo };
+        for _ in 0..steps.unsigned_abs() {
+            handle_global_action(
+                ActionId::Global(step),
+                state,
+                panes,
+                audio,
+                app_frame,
+                select_mode,
+                pending_audio_dirty,
+                layer_stack,
+                io_tx,
+            );
+        }
+    }
+    if let Some(target) = panes.get_pane_mut::<PianoRollPane>("piano_roll").and_then(|p| p.take_quantize()) {
+        if let Some(quantize) = panes.get_pane_mut::<QuantizePane>("quantize") {
+            quantize.open(target, state.session.piano_roll.ticks_per_beat);
+        }
+        panes.push_to("quantize", state);
+        sync_pane_layer(panes, layer_stack);
+    }
+
+    // Release a momentary solo once its key is no longer repeating
+    if let Some(release) = panes.get_pane_mut::<MixerPane>("mixer").and_then(|p| p.expire_momentary_solo(Instant::now())) {
+        dispatch_and_apply(&release, state, panes, audio, app_frame, pending_audio_dirty, io_tx);
+    }
+
+    // Open the paste popup for a channel paste requested in the mixer
+    if let Some((source, target)) = panes.get_pane_mut::<MixerPane>("mixer").and_then(|p| p.take_paste()) {
+        if let Some(paste) = panes.get_pane_mut::<ChannelPastePane>("channel_paste") {
+            paste.set_instruments(source, target);
+        }
+        panes.push_to("channel_paste", state);
+        sync_pane_layer(panes, layer_stack);
+    }
+
+    // Export the edited instrument as SuperCollider code
+    if let Some(id) = panes.get_pane_mut::<InstrumentEditPane>("instrument_edit").and_then(|p| p.take_export()) {
+        if let Some(inst) = state.instruments.instruments.iter().find(|i| i.id == id) {
+            let dir = scd_export::export_dir(state.project.path.as_deref());
+            let status = match scd_export::write(inst, &dir) {
+                Ok(path) => format!("Exported {} to {}", inst.name, path.display()),
+                Err(e) => format!("SuperCollider export failed: {}", e),
+            };
+            show_status(panes, audio, &status);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use std::any::Any;
 
+use crate::favorites::{self, AssetKind, Favorites};
 use crate::state::{AppState, EffectType, EffectTypeExt, VstPluginRegistry};
 use crate::ui::action_id::{ActionId, AddActionId};
 use crate::ui::layout_helpers::center_rect;
@@ -7,6 +8,7 @@ use crate::ui::{
     Rect, RenderBuf, Action, Color, FileSelectAction, InputEvent, InstrumentAction, Keymap, MouseEvent,
     MouseEventKind, MouseButton, NavAction, Pane, SessionAction, Style,
 };
+use crate::usage::Usage;
 
 /// Options available in the Add Effect menu
 #[derive(Debug, Clone)]
@@ -23,6 +25,10 @@ pub struct AddEffectPane {
     selected: usize,
     scroll_offset: usize,
     cached_options: Vec<AddEffectOption>,
+    favorites: Favorites,
+    /// List only starred effects
+    favorites_only: bool,
+    usage: Usage,
 }
 
 impl AddEffectPane {
@@ -32,6 +38,9 @@ impl AddEffectPane {
             selected: 0,
             scroll_offset: 0,
             cached_options: Self::build_options_static(),
+            favorites: Favorites::default(),
+            favorites_only: false,
+            usage: Usage::default(),
         }
     }
 
@@ -114,7 +123,41 @@ impl AddEffectPane {
             .effects()
             .map(|p| (p.id, EffectType::Vst(p.id)))
             .collect();
-        Self::build_effect_list(&vst_effects)
+        let mut options = Self::build_effect_list(&vst_effects);
+
+        // Recent and most used picks ahead of the full list
+        let keyed: Vec<(String, AddEffectOption)> = options.iter()
+            .filter_map(|option| match option {
+                AddEffectOption::Effect(effect_type) => Some((asset_key(effect_type, vst_registry), option.clone())),
+                _ => None,
+            })
+            .collect();
+        let (recent, most_used) = self.usage.quick_lists(&keyed);
+        if !recent.is_empty() {
+            let mut quick = vec![AddEffectOption::Separator("── Recent ──")];
+            quick.extend(recent);
+            quick.push(AddEffectOption::Separator("── Most used ──"));
+            quick.extend(most_used);
+            options.splice(0..0, quick);
+        }
+
+        if self.favorites_only {
+            options.retain(|option| match option {
+                AddEffectOption::Effect(effect_type) => self.favorites.is_favorite(&asset_key(effect_type, vst_registry)),
+                AddEffectOption::Separator(_) | AddEffectOption::ImportVst => true,
+            });
+            // Drop headers left with nothing under them
+            let mut kept = Vec::with_capacity(options.len());
+            for (i, option) in options.iter().enumerate() {
+                let empty = matches!(option, AddEffectOption::Separator(_))
+                    && options.get(i + 1).map_or(true, |next| matches!(next, AddEffectOption::Separator(_)));
+                if !empty {
+                    kept.push(option.clone());
+                }
+            }
+            options = kept;
+        }
+        options
     }
 
     fn update_options(&mut self, vst_registry: &VstPluginRegistry) {
@@ -129,6 +172,22 @@ impl AddEffectPane {
         self.adjust_scroll();
     }
 
+    /// Count a pick toward the recent and most used lists
+    fn record_use(&mut self, effect_type: &EffectType, state: &AppState) {
+        self.usage.record(&asset_key(effect_type, &state.session.vst_plugins));
+        self.usage.save();
+    }
+
+    fn rate_selected(&mut self, state: &AppState, change: impl FnOnce(&mut Favorites, &str)) {
+        let vst_registry = &state.session.vst_plugins;
+        let Some(AddEffectOption::Effect(effect_type)) = self.cached_options.get(self.selected) else { return };
+        change(&mut self.favorites, &asset_key(effect_type, vst_registry));
+        self.favorites.save();
+        if self.favorites_only {
+            self.update_options(vst_registry);
+        }
+    }
+
     fn select_next(&mut self) {
         let len = self.cached_options.len();
         if len == 0 {
@@ -156,6 +215,12 @@ impl AddEffectPane {
     }
 }
 
+/// Favorites and usage key for an effect
+fn asset_key(effect_type: &EffectType, vst_registry: &VstPluginRegistry) -> String {
+    let kind = if effect_type.is_vst() { AssetKind::Vst } else { AssetKind::Effect };
+    favorites::key(kind, &effect_type.display_name(vst_registry))
+}
+
 impl Default for AddEffectPane {
     fn default() -> Self {
         Self::new(Keymap::new())
@@ -170,11 +235,12 @@ impl Pane for AddEffectPane {
     fn handle_action(&mut self, action: ActionId, _event: &InputEvent, state: &AppState) -> Action {
         match action {
             ActionId::Add(AddActionId::Confirm) => {
-                if let Some(option) = self.cached_options.get(self.selected) {
+                if let Some(option) = self.cached_options.get(self.selected).cloned() {
                     match option {
                         AddEffectOption::Effect(effect_type) => {
                             if let Some(inst) = state.instruments.selected_instrument() {
-                                Action::Instrument(InstrumentAction::AddEffect(inst.id, *effect_type))
+                                self.record_use(&effect_type, state);
+                                Action::Instrument(InstrumentAction::AddEffect(inst.id, effect_type))
                             } else {
                                 Action::None
                             }
@@ -197,6 +263,25 @@ impl Pane for AddEffectPane {
                 self.select_prev();
                 Action::None
             }
+            ActionId::Add(AddActionId::ToggleStar) => {
+                self.rate_selected(state, |favs, key| favs.toggle(key));
+                Action::None
+            }
+            ActionId::Add(AddActionId::RateUp) => {
+                self.rate_selected(state, |favs, key| favs.adjust(key, 1));
+                Action::None
+            }
+            ActionId::Add(AddActionId::RateDown) => {
+                self.rate_selected(state, |favs, key| favs.adjust(key, -1));
+                Action::None
+            }
+            ActionId::Add(AddActionId::FavoritesOnly) => {
+                self.favorites_only = !self.favorites_only;
+                self.selected = 0;
+                self.scroll_offset = 0;
+                self.update_options(&state.session.vst_plugins);
+                Action::None
+            }
             _ => Action::None,
         }
     }
@@ -220,10 +305,11 @@ impl Pane for AddEffectPane {
                         self.selected = idx;
                         self.adjust_scroll();
                         // Confirm on click
-                        match &self.cached_options[idx] {
+                        match self.cached_options[idx].clone() {
                             AddEffectOption::Effect(effect_type) => {
                                 if let Some(inst) = state.instruments.selected_instrument() {
-                                    return Action::Instrument(InstrumentAction::AddEffect(inst.id, *effect_type));
+                                    self.record_use(&effect_type, state);
+                                    return Action::Instrument(InstrumentAction::AddEffect(inst.id, effect_type));
                                 }
                             }
                             AddEffectOption::ImportVst => {
@@ -252,7 +338,8 @@ impl Pane for AddEffectPane {
         let rect = center_rect(area, 40, 20);
 
         let border_style = Style::new().fg(Color::FX_COLOR);
-        let inner = buf.draw_block(rect, " Add Effect ", border_style, border_style);
+        let title = if self.favorites_only { " Add Effect (favorites) " } else { " Add Effect " };
+        let inner = buf.draw_block(rect, title, border_style, border_style);
 
         let content_x = inner.x + 1;
         let content_y = inner.y + 1;
@@ -309,6 +396,20 @@ impl Pane for AddEffectPane {
                             buf.set_cell(x, y, ' ', sel_bg);
                         }
                     }
+
+                    let rating = self.favorites.rating(&asset_key(effect_type, vst_registry));
+                    if rating > 0 {
+                        let star_style = if is_selected {
+                            Style::new().fg(Color::GOLD).bg(Color::SELECTION_BG)
+                        } else {
+                            Style::new().fg(Color::GOLD)
+                        };
+                        let stars_x = (inner.x + inner.width).saturating_sub(favorites::MAX_RATING as u16 + 1);
+                        buf.draw_line(
+                            Rect::new(stars_x, y, favorites::MAX_RATING as u16, 1),
+                            &[(&favorites::stars(rating), star_style)],
+                        );
+                    }
                 }
                 AddEffectOption::ImportVst => {
                     if is_selected {
@@ -352,6 +453,8 @@ impl Pane for AddEffectPane {
     }
 
     fn on_enter(&mut self, state: &AppState) {
+        self.favorites = Favorites::load();
+        self.usage = Usage::load();
         self.update_options(&state.session.vst_plugins);
     }
 
@@ -1,9 +1,12 @@
 use std::any::Any;
 
+use crate::favorites::{self, AssetKind, Favorites};
+use crate::instrument_groups::{GroupTemplate, TEMPLATES};
 use crate::state::{AppState, CustomSynthDefRegistry, SourceType, SourceTypeExt, VstPluginRegistry};
 use crate::ui::action_id::{ActionId, AddActionId};
 use crate::ui::layout_helpers::center_rect;
 use crate::ui::{Rect, RenderBuf, Action, Color, FileSelectAction, InputEvent, InstrumentAction, Keymap, MouseEvent, MouseEventKind, MouseButton, NavAction, Pane, SessionAction, Style};
+use crate::usage::Usage;
 
 /// Options available in the Add Instrument menu
 #[derive(Debug, Clone)]
@@ -12,6 +15,7 @@ pub enum AddOption {
     Separator(&'static str),
     ImportCustom,
     ImportVst,
+    Group(&'static GroupTemplate),
 }
 
 pub struct AddPane {
@@ -20,6 +24,10 @@ pub struct AddPane {
     scroll_offset: usize,
     /// Cached options list - rebuilt on each render_with_registry call
     cached_options: Vec<AddOption>,
+    favorites: Favorites,
+    /// List only starred sources and groups
+    favorites_only: bool,
+    usage: Usage,
 }
 
 impl AddPane {
@@ -29,6 +37,9 @@ impl AddPane {
             selected: 0,
             scroll_offset: 0,
             cached_options: Self::build_options_static(),
+            favorites: Favorites::default(),
+            favorites_only: false,
+            usage: Usage::default(),
         }
     }
 
@@ -41,6 +52,12 @@ impl AddPane {
             options.push(AddOption::Source(source));
         }
 
+        // Multi-instrument templates
+        options.push(AddOption::Separator("── Groups ──"));
+        for template in TEMPLATES {
+            options.push(AddOption::Group(template));
+        }
+
         // Custom section
         options.push(AddOption::Separator("── Custom ──"));
         options.push(AddOption::ImportCustom);
@@ -61,6 +78,12 @@ impl AddPane {
             options.push(AddOption::Source(source));
         }
 
+        // Multi-instrument templates
+        options.push(AddOption::Separator("── Groups ──"));
+        for template in TEMPLATES {
+            options.push(AddOption::Group(template));
+        }
+
         // Custom section
         options.push(AddOption::Separator("── Custom ──"));
 
@@ -83,6 +106,36 @@ impl AddPane {
         // Import VST option
         options.push(AddOption::ImportVst);
 
+        // Recent and most used picks ahead of the full list
+        let keyed: Vec<(String, AddOption)> = options.iter()
+            .filter_map(|option| asset_key(option, custom_registry, vst_registry).map(|key| (key, option.clone())))
+            .collect();
+        let (recent, most_used) = self.usage.quick_lists(&keyed);
+        if !recent.is_empty() {
+            let mut quick = vec![AddOption::Separator("── Recent ──")];
+            quick.extend(recent);
+            quick.push(AddOption::Separator("── Most used ──"));
+            quick.extend(most_used);
+            quick.push(AddOption::Separator("── Sources ──"));
+            options.splice(0..0, quick);
+        }
+
+        if self.favorites_only {
+            options.retain(|option| {
+                asset_key(option, custom_registry, vst_registry).map_or(true, |key| self.favorites.is_favorite(&key))
+            });
+            // Drop headers left with nothing under them
+            let mut kept = Vec::with_capacity(options.len());
+            for (i, option) in options.iter().enumerate() {
+                let empty = matches!(option, AddOption::Separator(_))
+                    && options.get(i + 1).map_or(true, |next| matches!(next, AddOption::Separator(_)));
+                if !empty {
+                    kept.push(option.clone());
+                }
+            }
+            options = kept;
+        }
+
         options
     }
 
@@ -94,6 +147,27 @@ impl AddPane {
         if self.selected >= self.cached_options.len() {
             self.selected = self.cached_options.len().saturating_sub(1);
         }
+        if matches!(self.cached_options.get(self.selected), Some(AddOption::Separator(_))) {
+            self.select_next();
+        }
+    }
+
+    /// Count a pick toward the recent and most used lists
+    fn record_use(&mut self, option: &AddOption, state: &AppState) {
+        if let Some(key) = asset_key(option, &state.session.custom_synthdefs, &state.session.vst_plugins) {
+            self.usage.record(&key);
+            self.usage.save();
+        }
+    }
+
+    fn rate_selected(&mut self, state: &AppState, change: impl FnOnce(&mut Favorites, &str)) {
+        let (custom, vst) = (&state.session.custom_synthdefs, &state.session.vst_plugins);
+        let Some(key) = self.cached_options.get(self.selected).and_then(|o| asset_key(o, custom, vst)) else { return };
+        change(&mut self.favorites, &key);
+        self.favorites.save();
+        if self.favorites_only {
+            self.update_options(custom, vst);
+        }
     }
 
     /// Move to next selectable item
@@ -153,7 +227,8 @@ impl AddPane {
         let rect = center_rect(area, 97, 29);
 
         let border_style = Style::new().fg(Color::LIME);
-        let inner = buf.draw_block(rect, " Add Instrument ", border_style, border_style);
+        let title = if self.favorites_only { " Add Instrument (favorites) " } else { " Add Instrument " };
+        let inner = buf.draw_block(rect, title, border_style, border_style);
 
         let content_x = inner.x + 1;
         let content_y = inner.y + 1;
@@ -185,6 +260,7 @@ impl AddPane {
             }
         }
 
+        let stars_x = (inner.x + inner.width).saturating_sub(favorites::MAX_RATING as u16 + 2);
         for (i, option) in self.cached_options.iter().skip(eff_scroll).take(visible_rows).enumerate() {
             let y = list_y + i as u16;
             let is_selected = eff_scroll + i == self.selected;
@@ -240,6 +316,31 @@ impl AddPane {
                         }
                     }
                 }
+                AddOption::Group(template) => {
+                    if is_selected {
+                        buf.set_cell(content_x, y, '>', Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG).bold());
+                    }
+
+                    let name = format!("{:12}", template.name);
+                    let description = format!("  {}", template.description);
+                    let (name_style, desc_style) = if is_selected {
+                        (Style::new().fg(Color::GOLD).bg(Color::SELECTION_BG), Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG))
+                    } else {
+                        (Style::new().fg(Color::GOLD), Style::new().fg(Color::DARK_GRAY))
+                    };
+                    buf.draw_line(
+                        Rect::new(content_x + 2, y, inner.width.saturating_sub(4), 1),
+                        &[(&name, name_style), (&description, desc_style)],
+                    );
+
+                    if is_selected {
+                        let fill_start = content_x + 2 + 14 + template.description.chars().count() as u16;
+                        let fill_end = inner.x + inner.width;
+                        for x in fill_start..fill_end {
+                            buf.set_cell(x, y, ' ', sel_bg);
+                        }
+                    }
+                }
                 AddOption::ImportCustom => {
                     if is_selected {
                         buf.set_cell(content_x, y, '>', Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG).bold());
@@ -287,6 +388,21 @@ impl AddPane {
                     }
                 }
             }
+
+            if let Some(key) = asset_key(option, registry, vst_registry) {
+                let rating = self.favorites.rating(&key);
+                if rating > 0 {
+                    let style = if is_selected {
+                        Style::new().fg(Color::GOLD).bg(Color::SELECTION_BG)
+                    } else {
+                        Style::new().fg(Color::GOLD)
+                    };
+                    buf.draw_line(
+                        Rect::new(stars_x, y, favorites::MAX_RATING as u16, 1),
+                        &[(&favorites::stars(rating), style)],
+                    );
+                }
+            }
         }
 
         // Scroll indicator: items hidden below
@@ -303,13 +419,30 @@ impl AddPane {
         if help_y < area.y + area.height {
             buf.draw_line(
                 Rect::new(content_x, help_y, inner.width.saturating_sub(2), 1),
-                &[("Enter: add | Escape: cancel | Up/Down: navigate", Style::new().fg(Color::DARK_GRAY))],
+                &[("Enter: add | Escape: cancel | Up/Down: navigate | *+-: stars | f: favorites", Style::new().fg(Color::DARK_GRAY))],
             );
         }
     }
 
 }
 
+/// Favorites and usage key for an option; separators and import entries
+/// have none
+fn asset_key(option: &AddOption, registry: &CustomSynthDefRegistry, vst_registry: &VstPluginRegistry) -> Option<String> {
+    match option {
+        AddOption::Source(source) => {
+            let kind = match source {
+                SourceType::Custom(_) => AssetKind::Synthdef,
+                SourceType::Vst(_) => AssetKind::Vst,
+                _ => AssetKind::Source,
+            };
+            Some(favorites::key(kind, &source.display_name_vst(registry, vst_registry)))
+        }
+        AddOption::Group(template) => Some(favorites::key(AssetKind::Group, template.name)),
+        AddOption::Separator(_) | AddOption::ImportCustom | AddOption::ImportVst => None,
+    }
+}
+
 impl Default for AddPane {
     fn default() -> Self {
         Self::new(Keymap::new())
@@ -324,9 +457,11 @@ impl Pane for AddPane {
     fn handle_action(&mut self, action: ActionId, _event: &InputEvent, state: &AppState) -> Action {
         match action {
             ActionId::Add(AddActionId::Confirm) => {
-                if let Some(option) = self.cached_options.get(self.selected) {
+                if let Some(option) = self.cached_options.get(self.selected).cloned() {
+                    self.record_use(&option, state);
                     match option {
-                        AddOption::Source(source) => Action::Instrument(InstrumentAction::Add(*source)),
+                        AddOption::Source(source) => Action::Instrument(InstrumentAction::Add(source)),
+                        AddOption::Group(template) => template.add_action(),
                         AddOption::ImportCustom => {
                             Action::Session(SessionAction::OpenFileBrowser(FileSelectAction::ImportCustomSynthDef))
                         }
@@ -354,11 +489,29 @@ impl Pane for AddPane {
                 self.select_prev();
                 Action::None
             }
+            ActionId::Add(AddActionId::ToggleStar) => {
+                self.rate_selected(state, |favs, key| favs.toggle(key));
+                Action::None
+            }
+            ActionId::Add(AddActionId::RateUp) => {
+                self.rate_selected(state, |favs, key| favs.adjust(key, 1));
+                Action::None
+            }
+            ActionId::Add(AddActionId::RateDown) => {
+                self.rate_selected(state, |favs, key| favs.adjust(key, -1));
+                Action::None
+            }
+            ActionId::Add(AddActionId::FavoritesOnly) => {
+                self.favorites_only = !self.favorites_only;
+                self.selected = 0;
+                self.update_options(&state.session.custom_synthdefs, &state.session.vst_plugins);
+                Action::None
+            }
             _ => Action::None,
         }
     }
 
-    fn handle_mouse(&mut self, event: &MouseEvent, area: Rect, _state: &AppState) -> Action {
+    fn handle_mouse(&mut self, event: &MouseEvent, area: Rect, state: &AppState) -> Action {
         let rect = center_rect(area, 97, 29);
         let inner_y = rect.y + 2;
         let content_y = inner_y + 1;
@@ -377,8 +530,11 @@ impl Pane for AddPane {
                         }
                         self.selected = idx;
                         // Confirm selection
-                        match &self.cached_options[idx] {
-                            AddOption::Source(source) => return Action::Instrument(InstrumentAction::Add(*source)),
+                        let option = self.cached_options[idx].clone();
+                        self.record_use(&option, state);
+                        match option {
+                            AddOption::Source(source) => return Action::Instrument(InstrumentAction::Add(source)),
+                            AddOption::Group(template) => return template.add_action(),
                             AddOption::ImportCustom => {
                                 return Action::Session(SessionAction::OpenFileBrowser(FileSelectAction::ImportCustomSynthDef));
                             }
@@ -412,6 +568,8 @@ impl Pane for AddPane {
     }
 
     fn on_enter(&mut self, state: &AppState) {
+        self.favorites = Favorites::load();
+        self.usage = Usage::load();
         self.update_options(&state.session.custom_synthdefs, &state.session.vst_plugins);
     }
 
@@ -0,0 +1,320 @@
+use crate::state::automation::CurveType;
+
+/// Periodic shapes the generator can write into a lane
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum GeneratorShape {
+    Sine,
+    Triangle,
+    SawUp,
+    SawDown,
+    Square,
+    Random,
+    RampUp,
+    RampDown,
+}
+
+impl GeneratorShape {
+    const ALL: [GeneratorShape; 8] = [
+        GeneratorShape::Sine,
+        GeneratorShape::Triangle,
+        GeneratorShape::SawUp,
+        GeneratorShape::SawDown,
+        GeneratorShape::Square,
+        GeneratorShape::Random,
+        GeneratorShape::RampUp,
+        GeneratorShape::RampDown,
+    ];
+
+    pub(super) fn name(&self) -> &'static str {
+        match self {
+            GeneratorShape::Sine => "Sine",
+            GeneratorShape::Triangle => "Triangle",
+            GeneratorShape::SawUp => "Saw Up",
+            GeneratorShape::SawDown => "Saw Down",
+            GeneratorShape::Square => "Square",
+            GeneratorShape::Random => "Random (S&H)",
+            GeneratorShape::RampUp => "Ramp Up",
+            GeneratorShape::RampDown => "Ramp Down",
+        }
+    }
+
+    fn cycle(&self, forward: bool) -> Self {
+        let idx = Self::ALL.iter().position(|s| s == self).unwrap_or(0);
+        let len = Self::ALL.len();
+        if forward {
+            Self::ALL[(idx + 1) % len]
+        } else {
+            Self::ALL[(idx + len - 1) % len]
+        }
+    }
+
+    /// Ramps span the whole range and ignore the cycle length
+    fn is_ramp(&self) -> bool {
+        matches!(self, GeneratorShape::RampUp | GeneratorShape::RampDown)
+    }
+}
+
+/// Fields editable in the generator popup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum GeneratorField {
+    Shape,
+    Rate,
+    Depth,
+    Center,
+}
+
+pub(super) const GENERATOR_FIELDS: [GeneratorField; 4] = [
+    GeneratorField::Shape,
+    GeneratorField::Rate,
+    GeneratorField::Depth,
+    GeneratorField::Center,
+];
+
+/// Cycle lengths offered by the rate field, as (numerator, denominator)
+/// fractions of a bar (1/16 .. 4 bars)
+const CYCLE_LENGTHS: [(u32, u32); 7] = [(1, 16), (1, 8), (1, 4), (1, 2), (1, 1), (2, 1), (4, 1)];
+
+/// Settings for writing a generated shape into an automation lane.
+/// Values are normalized (0.0-1.0) like the rest of the timeline editor.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct GeneratorSettings {
+    pub shape: GeneratorShape,
+    /// Length of one cycle as a fraction of a bar
+    pub cycle: (u32, u32),
+    /// Peak deviation from center
+    pub depth: f32,
+    pub center: f32,
+    pub selected: usize,
+}
+
+impl Default for GeneratorSettings {
+    fn default() -> Self {
+        Self {
+            shape: GeneratorShape::Sine,
+            cycle: (1, 1),
+            depth: 0.5,
+            center: 0.5,
+            selected: 0,
+        }
+    }
+}
+
+impl GeneratorSettings {
+    /// Length of one cycle in ticks, for bars `ticks_per_bar` long
+    pub(super) fn cycle_ticks(&self, ticks_per_bar: u32) -> u32 {
+        let (num, den) = self.cycle;
+        ticks_per_bar * num / den.max(1)
+    }
+
+    pub(super) fn current_field(&self) -> GeneratorField {
+        GENERATOR_FIELDS[self.selected]
+    }
+
+    pub(super) fn adjust(&mut self, increase: bool) {
+        match self.current_field() {
+            GeneratorField::Shape => self.shape = self.shape.cycle(increase),
+            GeneratorField::Rate => {
+                let idx = CYCLE_LENGTHS.iter().position(|c| *c == self.cycle).unwrap_or(4);
+                // Increasing the rate shortens the cycle
+                let new_idx = if increase { idx.saturating_sub(1) } else { (idx + 1).min(CYCLE_LENGTHS.len() - 1) };
+                self.cycle = CYCLE_LENGTHS[new_idx];
+            }
+            GeneratorField::Depth => {
+                let delta = if increase { 0.05 } else { -0.05 };
+                self.depth = (self.depth + delta).clamp(0.0, 0.5);
+            }
+            GeneratorField::Center => {
+                let delta = if increase { 0.05 } else { -0.05 };
+                self.center = (self.center + delta).clamp(0.0, 1.0);
+            }
+        }
+    }
+
+    pub(super) fn field_value(&self, field: GeneratorField) -> String {
+        match field {
+            GeneratorField::Shape => self.shape.name().to_string(),
+            GeneratorField::Rate => {
+                if self.shape.is_ramp() {
+                    "(whole range)".to_string()
+                } else {
+                    match self.cycle {
+                        (1, 1) => "1 bar".to_string(),
+                        (num, 1) => format!("{} bars", num),
+                        (num, den) => format!("{}/{} bar", num, den),
+                    }
+                }
+            }
+            GeneratorField::Depth => format!("{:.0}%", self.depth * 200.0),
+            GeneratorField::Center => format!("{:.2}", self.center),
+        }
+    }
+}
+
+pub(super) fn field_label(field: GeneratorField) -> &'static str {
+    match field {
+        GeneratorField::Shape => "Shape",
+        GeneratorField::Rate => "Rate",
+        GeneratorField::Depth => "Depth",
+        GeneratorField::Center => "Center",
+    }
+}
+
+/// Small deterministic PRNG so random shapes are reproducible per range
+fn hash_noise(seed: u32) -> f32 {
+    let mut x = seed.wrapping_mul(0x9E37_79B9) ^ 0x85EB_CA6B;
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x2C1B_3C6D);
+    x ^= x >> 12;
+    (x & 0xFFFF) as f32 / 65535.0
+}
+
+/// Generate `(tick, value, curve)` points for `[start, end)`, with bars
+/// `ticks_per_bar` long.
+///
+/// Linear shapes emit only their vertices; the sine is sampled every
+/// `resolution` ticks; square and random use held segments.
+pub(super) fn generate_points(
+    settings: &GeneratorSettings,
+    start: u32,
+    end: u32,
+    resolution: u32,
+    ticks_per_bar: u32,
+) -> Vec<(u32, f32, CurveType)> {
+    let mut points = Vec::new();
+    if end <= start {
+        return points;
+    }
+    let lo = (settings.center - settings.depth).clamp(0.0, 1.0);
+    let hi = (settings.center + settings.depth).clamp(0.0, 1.0);
+    let cycle = settings.cycle_ticks(ticks_per_bar).max(2);
+    let half = cycle / 2;
+
+    match settings.shape {
+        GeneratorShape::RampUp => {
+            points.push((start, lo, CurveType::Linear));
+            points.push((end, hi, CurveType::Linear));
+        }
+        GeneratorShape::RampDown => {
+            points.push((start, hi, CurveType::Linear));
+            points.push((end, lo, CurveType::Linear));
+        }
+        GeneratorShape::Sine => {
+            let step = resolution.max(1).min(cycle / 4).max(1);
+            let mut tick = start;
+            while tick <= end {
+                let phase = (tick - start) as f32 / cycle as f32;
+                let v = settings.center + settings.depth * (phase * std::f32::consts::TAU).sin();
+                points.push((tick, v.clamp(0.0, 1.0), CurveType::Linear));
+                tick += step;
+            }
+        }
+        GeneratorShape::Triangle => {
+            let mut tick = start;
+            let mut rising = true;
+            while tick <= end {
+                points.push((tick, if rising { lo } else { hi }, CurveType::Linear));
+                rising = !rising;
+                tick += half;
+            }
+        }
+        GeneratorShape::SawUp | GeneratorShape::SawDown => {
+            let (from, to) = if settings.shape == GeneratorShape::SawUp { (lo, hi) } else { (hi, lo) };
+            let mut tick = start;
+            while tick < end {
+                points.push((tick, from, CurveType::Linear));
+                let peak = (tick + cycle - 1).min(end);
+                points.push((peak, to, CurveType::Linear));
+                tick += cycle;
+            }
+        }
+        GeneratorShape::Square => {
+            let mut tick = start;
+            let mut high = true;
+            while tick < end {
+                points.push((tick, if high { hi } else { lo }, CurveType::Step));
+                high = !high;
+                tick += half;
+            }
+        }
+        GeneratorShape::Random => {
+            let mut tick = start;
+            while tick < end {
+                let v = lo + (hi - lo) * hash_noise(tick);
+                points.push((tick, v, CurveType::Step));
+                tick += cycle;
+            }
+        }
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(shape: GeneratorShape) -> GeneratorSettings {
+        GeneratorSettings { shape, cycle: (1, 4), depth: 0.5, center: 0.5, selected: 0 }
+    }
+
+    #[test]
+    fn points_stay_in_range_and_bounds() {
+        for shape in GeneratorShape::ALL {
+            let points = generate_points(&settings(shape), 960, 2880, 60, 1920);
+            assert!(!points.is_empty(), "{:?} produced no points", shape);
+            for (tick, value, _) in &points {
+                assert!(*tick >= 960 && *tick <= 2880, "{:?} tick {} out of range", shape, tick);
+                assert!((0.0..=1.0).contains(value), "{:?} value {} out of range", shape, value);
+            }
+        }
+    }
+
+    #[test]
+    fn square_alternates_with_held_segments() {
+        let points = generate_points(&settings(GeneratorShape::Square), 0, 960, 60, 1920);
+        assert_eq!(points.len(), 4);
+        assert_eq!(points[0], (0, 1.0, CurveType::Step));
+        assert_eq!(points[1], (240, 0.0, CurveType::Step));
+    }
+
+    #[test]
+    fn ramp_ignores_cycle_length() {
+        let points = generate_points(&settings(GeneratorShape::RampDown), 0, 7680, 60, 1920);
+        assert_eq!(points, vec![(0, 1.0, CurveType::Linear), (7680, 0.0, CurveType::Linear)]);
+    }
+
+    #[test]
+    fn random_is_deterministic() {
+        let a = generate_points(&settings(GeneratorShape::Random), 0, 1920, 60, 1920);
+        let b = generate_points(&settings(GeneratorShape::Random), 0, 1920, 60, 1920);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn empty_range_yields_nothing() {
+        assert!(generate_points(&settings(GeneratorShape::Sine), 480, 480, 60, 1920).is_empty());
+    }
+
+    #[test]
+    fn rate_adjust_clamps() {
+        let mut s = GeneratorSettings::default();
+        s.selected = 1;
+        for _ in 0..10 {
+            s.adjust(true);
+        }
+        assert_eq!(s.cycle, (1, 16));
+        for _ in 0..10 {
+            s.adjust(false);
+        }
+        assert_eq!(s.cycle, (4, 1));
+    }
+
+    #[test]
+    fn cycles_follow_the_bar_length() {
+        let s = GeneratorSettings::default();
+        assert_eq!(s.cycle_ticks(1920), 1920);
+        // One bar of 3/4
+        assert_eq!(s.cycle_ticks(1440), 1440);
+        let s = settings(GeneratorShape::Sine);
+        assert_eq!(s.cycle_ticks(1440), 360);
+    }
+}
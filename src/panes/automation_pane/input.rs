@@ -3,14 +3,32 @@ use crate::state::AppState;
 use crate::ui::action_id::{ActionId, AutomationActionId};
 use crate::ui::{Action, AutomationAction, InputEvent};
 
+use super::generator::{generate_points, GeneratorSettings, GENERATOR_FIELDS};
 use super::{AutomationFocus, AutomationPane, TargetPickerState};
 
+/// Max normalized deviation allowed when thinning recorded points
+const THIN_TOLERANCE: f32 = 0.01;
+
+/// Next curve type in the editor's cycle order (Linear → Exp → S-Curve → Hold)
+pub(super) fn next_curve(curve: CurveType, forward: bool) -> CurveType {
+    const ORDER: [CurveType; 4] = [CurveType::Linear, CurveType::Exponential, CurveType::SCurve, CurveType::Step];
+    let idx = ORDER.iter().position(|c| *c == curve).unwrap_or(0);
+    if forward {
+        ORDER[(idx + 1) % ORDER.len()]
+    } else {
+        ORDER[(idx + ORDER.len() - 1) % ORDER.len()]
+    }
+}
+
 impl AutomationPane {
     pub(super) fn handle_action_impl(&mut self, action: ActionId, _event: &InputEvent, state: &AppState) -> Action {
         // If target picker is active, delegate to it
         if matches!(self.target_picker, TargetPickerState::Active { .. }) {
             return self.handle_target_picker_action(action, state);
         }
+        if self.generator.is_some() {
+            return self.handle_generator_action(action, state);
+        }
 
         match action {
             // Focus switching
@@ -42,6 +60,7 @@ impl AutomationPane {
                 }
             }
             ActionId::Automation(AutomationActionId::Left) => {
+                self.selection_anchor_tick = None;
                 if self.focus == AutomationFocus::Timeline {
                     let tpc = self.ticks_per_cell();
                     self.cursor_tick = self.cursor_tick.saturating_sub(tpc);
@@ -53,12 +72,75 @@ impl AutomationPane {
                 Action::None
             }
             ActionId::Automation(AutomationActionId::Right) => {
+                self.selection_anchor_tick = None;
                 if self.focus == AutomationFocus::Timeline {
                     let tpc = self.ticks_per_cell();
                     self.cursor_tick += tpc;
                 }
                 Action::None
             }
+            ActionId::Automation(AutomationActionId::SelectLeft) => {
+                if self.focus == AutomationFocus::Timeline {
+                    if self.selection_anchor_tick.is_none() {
+                        self.selection_anchor_tick = Some(self.cursor_tick);
+                    }
+                    let tpc = self.ticks_per_cell();
+                    self.cursor_tick = self.cursor_tick.saturating_sub(tpc);
+                    if self.cursor_tick < self.view_start_tick {
+                        self.view_start_tick = self.cursor_tick;
+                    }
+                }
+                Action::None
+            }
+            ActionId::Automation(AutomationActionId::SelectRight) => {
+                if self.focus == AutomationFocus::Timeline {
+                    if self.selection_anchor_tick.is_none() {
+                        self.selection_anchor_tick = Some(self.cursor_tick);
+                    }
+                    self.cursor_tick += self.ticks_per_cell();
+                }
+                Action::None
+            }
+
+            // Lane editing tools (selection, or whole lane without one)
+            ActionId::Automation(AutomationActionId::ShiftLeft)
+            | ActionId::Automation(AutomationActionId::ShiftRight) => {
+                let Some((id, start, end)) = self.edit_range(state) else { return Action::None };
+                let tpc = self.ticks_per_cell() as i32;
+                let delta = if action == ActionId::Automation(AutomationActionId::ShiftLeft) { -tpc } else { tpc };
+                // Keep the selection on the shifted points
+                if let Some(anchor) = self.selection_anchor_tick {
+                    self.selection_anchor_tick = Some((anchor as i32 + delta).max(0) as u32);
+                    self.cursor_tick = (self.cursor_tick as i32 + delta).max(0) as u32;
+                }
+                Action::Automation(AutomationAction::ShiftPoints(id, start, end, delta))
+            }
+            ActionId::Automation(AutomationActionId::ScaleUp) => {
+                let Some((id, start, end)) = self.edit_range(state) else { return Action::None };
+                Action::Automation(AutomationAction::ScalePointValues(id, start, end, 1.1))
+            }
+            ActionId::Automation(AutomationActionId::ScaleDown) => {
+                let Some((id, start, end)) = self.edit_range(state) else { return Action::None };
+                Action::Automation(AutomationAction::ScalePointValues(id, start, end, 0.9))
+            }
+            ActionId::Automation(AutomationActionId::Stretch) => {
+                let Some((id, start, end)) = self.edit_range(state) else { return Action::None };
+                Action::Automation(AutomationAction::ScalePointTimes(id, start, end, 2.0))
+            }
+            ActionId::Automation(AutomationActionId::Compress) => {
+                let Some((id, start, end)) = self.edit_range(state) else { return Action::None };
+                Action::Automation(AutomationAction::ScalePointTimes(id, start, end, 0.5))
+            }
+            ActionId::Automation(AutomationActionId::Generate) => {
+                if self.selected_lane_id(state).is_some() {
+                    self.generator = Some(GeneratorSettings::default());
+                }
+                Action::None
+            }
+            ActionId::Automation(AutomationActionId::Thin) => {
+                let Some((id, start, end)) = self.edit_range(state) else { return Action::None };
+                Action::Automation(AutomationAction::ThinPoints(id, start, end, THIN_TOLERANCE))
+            }
 
             // Add lane
             ActionId::Automation(AutomationActionId::AddLane) => {
@@ -71,6 +153,7 @@ impl AutomationPane {
                     for (idx, _send) in inst.sends.iter().enumerate() {
                         options.push(AutomationTarget::SendLevel(id, idx));
                     }
+                    options.push(AutomationTarget::Swing(id));
                 }
                 // Add global targets (skip when editing a clip — only instrument targets apply)
                 if !editing_clip {
@@ -141,20 +224,18 @@ impl AutomationPane {
                 }
             }
 
-            // Cycle curve type at cursor
-            ActionId::Automation(AutomationActionId::CycleCurve) => {
+            // Cycle curve type of the segment under the cursor
+            ActionId::Automation(AutomationActionId::CycleCurve)
+            | ActionId::Automation(AutomationActionId::CycleCurveBack) => {
                 if self.focus == AutomationFocus::Timeline {
+                    let forward = action == ActionId::Automation(AutomationActionId::CycleCurve);
                     if let Some(id) = self.selected_lane_id(state) {
-                        let tick = self.snap_tick(self.cursor_tick);
                         if let Some(lane) = state.session.automation.lane(id) {
-                            if let Some(point) = lane.point_at(tick) {
-                                let new_curve = match point.curve {
-                                    CurveType::Linear => CurveType::Exponential,
-                                    CurveType::Exponential => CurveType::Step,
-                                    CurveType::Step => CurveType::SCurve,
-                                    CurveType::SCurve => CurveType::Linear,
-                                };
-                                return Action::Automation(AutomationAction::SetCurveType(id, tick, new_curve));
+                            // A segment's shape is owned by the point that starts it
+                            let tick = self.snap_tick(self.cursor_tick);
+                            if let Some(point) = lane.points.iter().rev().find(|p| p.tick <= tick) {
+                                let new_curve = next_curve(point.curve, forward);
+                                return Action::Automation(AutomationAction::SetCurveType(id, point.tick, new_curve));
                             }
                         }
                     }
@@ -228,6 +309,46 @@ impl AutomationPane {
         }
     }
 
+    /// Handle actions while the shape generator popup is open
+    pub(super) fn handle_generator_action(&mut self, action: ActionId, state: &AppState) -> Action {
+        let Some(settings) = self.generator.as_mut() else { return Action::None };
+        match action {
+            ActionId::Automation(AutomationActionId::Up) | ActionId::Automation(AutomationActionId::Prev) => {
+                settings.selected = settings.selected.saturating_sub(1);
+                Action::None
+            }
+            ActionId::Automation(AutomationActionId::Down) | ActionId::Automation(AutomationActionId::Next) => {
+                if settings.selected + 1 < GENERATOR_FIELDS.len() {
+                    settings.selected += 1;
+                }
+                Action::None
+            }
+            ActionId::Automation(AutomationActionId::Left) => {
+                settings.adjust(false);
+                Action::None
+            }
+            ActionId::Automation(AutomationActionId::Right) => {
+                settings.adjust(true);
+                Action::None
+            }
+            ActionId::Automation(AutomationActionId::Confirm) | ActionId::Automation(AutomationActionId::PlacePoint) => {
+                let settings = settings.clone();
+                self.generator = None;
+                let Some(id) = self.selected_lane_id(state) else { return Action::None };
+                let (start, end) = self.generator_range(state);
+                let points = generate_points(&settings, start, end, self.ticks_per_cell(), state.session.piano_roll.ticks_per_bar());
+                Action::Automation(AutomationAction::ReplacePointsInRange(id, start, end, points))
+            }
+            ActionId::Automation(AutomationActionId::Cancel)
+            | ActionId::Automation(AutomationActionId::Escape)
+            | ActionId::Automation(AutomationActionId::Generate) => {
+                self.generator = None;
+                Action::None
+            }
+            _ => Action::None,
+        }
+    }
+
     /// Handle actions while the target picker is active
     pub(super) fn handle_target_picker_action(&mut self, action: ActionId, _state: &AppState) -> Action {
         if let TargetPickerState::Active { ref options, ref mut cursor } = self.target_picker {
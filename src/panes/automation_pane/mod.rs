@@ -1,3 +1,4 @@
+mod generator;
 mod input;
 mod rendering;
 
@@ -6,6 +7,7 @@ use std::any::Any;
 use crate::state::automation::{AutomationLaneId, AutomationTarget};
 use crate::state::AppState;
 use crate::ui::action_id::ActionId;
+use generator::GeneratorSettings;
 use crate::ui::layout_helpers::center_rect;
 use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, Pane, Style};
 
@@ -35,6 +37,8 @@ pub struct AutomationPane {
     snap_to_grid: bool,
     // Target picker sub-mode
     target_picker: TargetPickerState,
+    /// Shape generator popup (open when Some)
+    generator: Option<GeneratorSettings>,
     pub(crate) selection_anchor_tick: Option<u32>,
 }
 
@@ -49,6 +53,7 @@ impl AutomationPane {
             zoom_level: 3,
             snap_to_grid: true,
             target_picker: TargetPickerState::Inactive,
+            generator: None,
             selection_anchor_tick: None,
         }
     }
@@ -81,6 +86,28 @@ impl AutomationPane {
             None
         }
     }
+
+    /// Range written by the shape generator: the selection, else the loop
+    /// region, else four bars from the bar under the cursor.
+    fn generator_range(&self, state: &AppState) -> (u32, u32) {
+        if let Some((_, start, end)) = self.selection_region(state) {
+            return (start, end);
+        }
+        let pr = &state.session.piano_roll;
+        if pr.looping && pr.loop_end > pr.loop_start {
+            return (pr.loop_start, pr.loop_end);
+        }
+        let tpbar = pr.ticks_per_bar();
+        let bar_start = self.cursor_tick - self.cursor_tick % tpbar;
+        (bar_start, bar_start + tpbar * 4)
+    }
+
+    /// Range targeted by the lane editing tools: the selection if any,
+    /// otherwise the whole selected lane.
+    pub(crate) fn edit_range(&self, state: &AppState) -> Option<(AutomationLaneId, u32, u32)> {
+        self.selection_region(state)
+            .or_else(|| self.selected_lane_id(state).map(|id| (id, 0, u32::MAX)))
+    }
 }
 
 impl Pane for AutomationPane {
@@ -146,6 +173,7 @@ impl Pane for AutomationPane {
 
         // Render target picker overlay (if active)
         self.render_target_picker(buf, rect, state);
+        self.render_generator(buf, rect, state);
     }
 
     fn keymap(&self) -> &Keymap {
@@ -202,6 +230,77 @@ mod tests {
         assert_eq!(pane.cursor_tick, start_tick);
     }
 
+    #[test]
+    fn curve_cycle_round_trips() {
+        use crate::state::automation::CurveType;
+        use super::input::next_curve;
+        let all = [CurveType::Linear, CurveType::Exponential, CurveType::SCurve, CurveType::Step];
+        for curve in all {
+            let mut c = curve;
+            for _ in 0..all.len() {
+                c = next_curve(c, true);
+            }
+            assert_eq!(c, curve);
+            assert_eq!(next_curve(next_curve(curve, true), false), curve);
+        }
+        assert_eq!(next_curve(CurveType::Step, true), CurveType::Linear);
+    }
+
+    /// Value `frac` of the way through a 0 → 1 segment of `curve`,
+    /// normalized to the lane's range the way the timeline draws it
+    fn segment_value(curve: crate::state::automation::CurveType, frac: f32) -> f32 {
+        use crate::state::automation::AutomationLane;
+        let mut lane = AutomationLane::new(0, AutomationTarget::Bpm);
+        lane.add_point(0, 0.0);
+        lane.add_point(960, 1.0);
+        lane.points[0].curve = curve;
+        let raw = lane.value_at((960.0 * frac) as u32).unwrap();
+        (raw - lane.min_value) / (lane.max_value - lane.min_value)
+    }
+
+    #[test]
+    fn segment_midpoints_follow_curve_shape() {
+        use crate::state::automation::CurveType;
+        assert!((segment_value(CurveType::Linear, 0.5) - 0.5).abs() < 1e-3);
+        // Exponential starts slow and catches up at the end
+        let exp = segment_value(CurveType::Exponential, 0.5);
+        assert!(exp > 0.0 && exp < 0.5, "exp midpoint {}", exp);
+        // S-curve eases both ends and crosses linear halfway
+        assert!((segment_value(CurveType::SCurve, 0.5) - 0.5).abs() < 1e-3);
+        assert!(segment_value(CurveType::SCurve, 0.25) < 0.25);
+        assert!(segment_value(CurveType::SCurve, 0.75) > 0.75);
+        // Hold keeps the start value until the next point
+        assert!(segment_value(CurveType::Step, 0.5).abs() < 1e-3);
+        assert!(segment_value(CurveType::Step, 0.99).abs() < 1e-3);
+    }
+
+    #[test]
+    fn shift_select_sets_and_clears_anchor() {
+        use crate::ui::action_id::{ActionId, AutomationActionId};
+        let mut pane = AutomationPane::new(Keymap::new());
+        let state = AppState::new();
+        pane.focus = AutomationFocus::Timeline;
+
+        pane.handle_action(ActionId::Automation(AutomationActionId::SelectRight), &dummy_event(), &state);
+        pane.handle_action(ActionId::Automation(AutomationActionId::SelectRight), &dummy_event(), &state);
+        assert_eq!(pane.selection_anchor_tick, Some(0));
+        assert!(pane.cursor_tick > 0);
+
+        pane.handle_action(ActionId::Automation(AutomationActionId::Left), &dummy_event(), &state);
+        assert_eq!(pane.selection_anchor_tick, None);
+    }
+
+    #[test]
+    fn edit_tools_without_lane_do_nothing() {
+        use crate::ui::action_id::{ActionId, AutomationActionId};
+        let mut pane = AutomationPane::new(Keymap::new());
+        let state = AppState::new();
+        for id in [AutomationActionId::ShiftRight, AutomationActionId::ScaleUp, AutomationActionId::Stretch, AutomationActionId::Thin] {
+            let action = pane.handle_action(ActionId::Automation(id), &dummy_event(), &state);
+            assert!(matches!(action, Action::None));
+        }
+    }
+
     #[test]
     fn add_lane_opens_target_picker() {
         use crate::ui::action_id::{ActionId, AutomationActionId};
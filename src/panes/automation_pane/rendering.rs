@@ -1,8 +1,10 @@
+use crate::param_units::Unit;
 use crate::state::automation::{AutomationTargetExt, CurveType};
 use crate::state::AppState;
 use crate::ui::layout_helpers::center_rect;
 use crate::ui::{Rect, RenderBuf, Color, Style};
 
+use super::generator::{field_label, GENERATOR_FIELDS};
 use super::{AutomationFocus, AutomationPane, TargetPickerState};
 
 /// Block characters for mini value graph (8 levels)
@@ -12,6 +14,16 @@ pub(super) const BLOCK_CHARS: [char; 8] = [
     '\u{2585}', '\u{2586}', '\u{2587}', '\u{2588}',
 ];
 
+/// Short display name for a segment curve shape
+pub(super) fn curve_label(curve: CurveType) -> &'static str {
+    match curve {
+        CurveType::Linear => "Linear",
+        CurveType::Exponential => "Exp",
+        CurveType::Step => "Hold",
+        CurveType::SCurve => "S-Curve",
+    }
+}
+
 impl AutomationPane {
     pub(super) fn render_lane_list(&self, buf: &mut RenderBuf, area: Rect, state: &AppState) {
         if area.height < 2 || area.width < 10 {
@@ -60,16 +72,13 @@ impl AutomationPane {
 
             let enabled_char = if lane.enabled { "x" } else { " " };
             let point_count = lane.points.len();
-            let curve_name = if let Some(p) = lane.points.first() {
-                match p.curve {
-                    CurveType::Linear => "Linear",
-                    CurveType::Exponential => "Exp",
-                    CurveType::Step => "Step",
-                    CurveType::SCurve => "SCurve",
-                }
+            // Selected lane shows the segment under the cursor, others their first segment
+            let segment = if is_selected {
+                lane.points.iter().rev().find(|p| p.tick <= self.cursor_tick)
             } else {
-                "Linear"
+                lane.points.first()
             };
+            let curve_name = segment.map(|p| curve_label(p.curve)).unwrap_or("Linear");
 
             let short = lane.target.short_name();
             let name = lane.target.name();
@@ -163,12 +172,28 @@ impl AutomationPane {
             }
         }
 
+        // Selection highlight
+        if let Some((_, sel_start, sel_end)) = self.selection_region(state) {
+            let sel_bg = Color::new(35, 35, 60);
+            for col in 0..graph_width {
+                let tick = self.view_start_tick + col as u32 * tpc;
+                if tick >= sel_start && tick <= sel_end {
+                    for row in 0..graph_height {
+                        if let Some(cell) = buf.raw_buf().cell_mut((area.x + col, graph_y + row)) {
+                            cell.set_bg(sel_bg.into());
+                        }
+                    }
+                }
+            }
+        }
+
         // Draw automation curve
         let curve_color = if lane.enabled { Color::CYAN } else { Color::DARK_GRAY };
         let curve_style = Style::new().fg(curve_color);
         let point_style = Style::new().fg(Color::WHITE).bg(curve_color);
 
         if !lane.points.is_empty() && graph_height > 0 {
+            let mut prev_row: Option<u16> = None;
             for col in 0..graph_width {
                 let tick = self.view_start_tick + col as u32 * tpc;
                 if let Some(raw_value) = lane.value_at(tick) {
@@ -182,6 +207,13 @@ impl AutomationPane {
                     let y = graph_y + row;
                     let x = area.x + col;
                     if y < graph_y + graph_height {
+                        // Connect to the previous column so steep curves and holds stay continuous
+                        if let Some(prev) = prev_row {
+                            let (lo, hi) = (prev.min(row), prev.max(row));
+                            for r in (lo + 1)..hi {
+                                buf.set_cell(x, graph_y + r, '│', curve_style);
+                            }
+                        }
                         // Check if there's a point exactly at this tick
                         if lane.point_at(tick).is_some() {
                             buf.set_cell(x, y, '●', point_style);
@@ -189,6 +221,9 @@ impl AutomationPane {
                             buf.set_cell(x, y, '─', curve_style);
                         }
                     }
+                    prev_row = Some(row);
+                } else {
+                    prev_row = None;
                 }
             }
         }
@@ -242,20 +277,18 @@ impl AutomationPane {
         // Status line
         let status_y = graph_y + graph_height + 1;
         if status_y < area.y + area.height {
-            let curve_at_cursor = lane.point_at(self.cursor_tick)
-                .map(|p| match p.curve {
-                    CurveType::Linear => "Linear",
-                    CurveType::Exponential => "Exp",
-                    CurveType::Step => "Step",
-                    CurveType::SCurve => "SCurve",
-                })
+            let curve_at_cursor = lane.points.iter().rev()
+                .find(|p| p.tick <= self.cursor_tick)
+                .map(|p| curve_label(p.curve))
                 .unwrap_or("—");
 
             let rec_indicator = if state.recording.automation_recording { " [REC]" } else { "" };
+            let value = lane.min_value + self.cursor_value * (lane.max_value - lane.min_value);
+            let unit = Unit::for_param(&lane.target.name(), lane.min_value, lane.max_value);
             let status = format!(
-                " Tick:{:<6} Val:{:.2}  Curve:{}{}",
+                " Tick:{:<6} Val:{}  Curve:{}{}",
                 self.cursor_tick,
-                self.cursor_value,
+                unit.format(value),
                 curve_at_cursor,
                 rec_indicator,
             );
@@ -337,4 +370,53 @@ impl AutomationPane {
             }
         }
     }
+
+    pub(super) fn render_generator(&self, buf: &mut RenderBuf, area: Rect, state: &AppState) {
+        let Some(ref settings) = self.generator else { return };
+        let rect = center_rect(area, 40, GENERATOR_FIELDS.len() as u16 + 5);
+
+        let clear_style = Style::new().bg(Color::new(20, 20, 30));
+        for y in rect.y..rect.y + rect.height {
+            for x in rect.x..rect.x + rect.width {
+                buf.set_cell(x, y, ' ', clear_style);
+            }
+        }
+
+        let border_style = Style::new().fg(Color::LFO_COLOR);
+        let inner = buf.draw_block(rect, " Generate Shape ", border_style, border_style);
+
+        for (i, field) in GENERATOR_FIELDS.iter().enumerate() {
+            let y = inner.y + i as u16;
+            if y >= inner.y + inner.height { break; }
+            let is_selected = i == settings.selected;
+            let style = if is_selected {
+                Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG).bold()
+            } else {
+                Style::new().fg(Color::GRAY)
+            };
+            let text = format!(
+                "{} {:<8} {}",
+                if is_selected { ">" } else { " " },
+                field_label(*field),
+                settings.field_value(*field),
+            );
+            let text = format!("{:<width$}", text, width = inner.width as usize);
+            buf.draw_line(Rect::new(inner.x, y, inner.width, 1), &[(&text, style)]);
+        }
+
+        let (start, end) = self.generator_range(state);
+        let tpbar = state.session.piano_roll.ticks_per_bar() as f32;
+        let range = format!(" Bars {:.1}-{:.1}", start as f32 / tpbar + 1.0, end as f32 / tpbar + 1.0);
+        let range_y = inner.y + GENERATOR_FIELDS.len() as u16 + 1;
+        if range_y < inner.y + inner.height {
+            buf.draw_line(Rect::new(inner.x, range_y, inner.width, 1), &[(&range, Style::new().fg(Color::DARK_GRAY))]);
+        }
+        let help_y = range_y + 1;
+        if help_y < inner.y + inner.height {
+            buf.draw_line(
+                Rect::new(inner.x, help_y, inner.width, 1),
+                &[(" Enter: write  Esc: cancel", Style::new().fg(Color::DARK_GRAY))],
+            );
+        }
+    }
 }
@@ -0,0 +1,144 @@
+use std::any::Any;
+use std::path::PathBuf;
+
+use crate::batch_export::{Job, JobState};
+use crate::state::AppState;
+use crate::ui::action_id::{ActionId, BatchExportActionId};
+use crate::ui::layout_helpers::center_rect;
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, NavAction, Pane, Style};
+
+/// Progress list for a batch pattern export. main.rs owns the export and
+/// copies its jobs in each frame; closing the pane leaves it running.
+pub struct BatchExportPane {
+    keymap: Keymap,
+    jobs: Vec<Job>,
+    dir: PathBuf,
+    running: bool,
+    scroll: usize,
+    pending_cancel: bool,
+}
+
+impl BatchExportPane {
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            jobs: Vec::new(),
+            dir: PathBuf::new(),
+            running: false,
+            scroll: 0,
+            pending_cancel: false,
+        }
+    }
+
+    /// Latest job states from the running export
+    pub fn set_progress(&mut self, jobs: &[Job], dir: &std::path::Path, running: bool) {
+        self.jobs = jobs.to_vec();
+        self.dir = dir.to_path_buf();
+        self.running = running;
+    }
+
+    /// Whether cancel was pressed since the last call
+    pub fn take_cancel(&mut self) -> bool {
+        std::mem::take(&mut self.pending_cancel)
+    }
+
+    fn state_label(state: &JobState) -> (String, Color) {
+        match state {
+            JobState::Waiting => ("waiting".into(), Color::DARK_GRAY),
+            JobState::Rendering => ("rendering...".into(), Color::YELLOW),
+            JobState::Done(_) => ("done".into(), Color::GREEN),
+            JobState::Empty => ("empty, skipped".into(), Color::DARK_GRAY),
+            JobState::Failed(e) => (format!("failed: {}", e), Color::RED),
+            JobState::Cancelled => ("cancelled".into(), Color::DARK_GRAY),
+        }
+    }
+}
+
+impl Default for BatchExportPane {
+    fn default() -> Self {
+        Self::new(Keymap::new())
+    }
+}
+
+impl Pane for BatchExportPane {
+    fn id(&self) -> &'static str {
+        "batch_export"
+    }
+
+    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, _state: &AppState) -> Action {
+        match action {
+            ActionId::BatchExport(BatchExportActionId::Up) => self.scroll = self.scroll.saturating_sub(1),
+            ActionId::BatchExport(BatchExportActionId::Down) => {
+                self.scroll = (self.scroll + 1).min(self.jobs.len().saturating_sub(1));
+            }
+            ActionId::BatchExport(BatchExportActionId::Cancel) => {
+                if self.running {
+                    self.pending_cancel = true;
+                }
+            }
+            ActionId::BatchExport(BatchExportActionId::Close) => return Action::Nav(NavAction::PopPane),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, _state: &AppState) {
+        let rect = center_rect(area, 64, (self.jobs.len() as u16 + 6).clamp(8, 24));
+        let border_style = Style::new().fg(Color::ORANGE);
+        let inner = buf.draw_block(rect, " Export Patterns ", border_style, border_style);
+
+        let x = inner.x + 1;
+        let w = inner.width.saturating_sub(2);
+        let finished = self.jobs.iter().filter(|j| !matches!(j.state, JobState::Waiting | JobState::Rendering)).count();
+        let header = format!("{}/{} -> {}", finished, self.jobs.len(), self.dir.display());
+        buf.draw_line(Rect::new(x, inner.y, w, 1), &[(&header, Style::new().fg(Color::CYAN))]);
+
+        let visible = inner.height.saturating_sub(3) as usize;
+        let name_width = 28usize.min(w as usize / 2);
+        for (i, job) in self.jobs.iter().skip(self.scroll).take(visible).enumerate() {
+            let y = inner.y + 2 + i as u16;
+            let name: String = job.name.chars().take(name_width).collect();
+            let (label, color) = Self::state_label(&job.state);
+            buf.draw_line(
+                Rect::new(x, y, w, 1),
+                &[
+                    (&format!("{:nw$} ", name, nw = name_width), Style::new().fg(Color::WHITE)),
+                    (&label, Style::new().fg(color)),
+                ],
+            );
+        }
+
+        let help = if self.running { "c: cancel | Esc: close (keeps running)" } else { "Esc: close" };
+        let help_y = inner.y + inner.height - 1;
+        buf.draw_line(Rect::new(x, help_y, w, 1), &[(help, Style::new().fg(Color::DARK_GRAY))]);
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::{KeyCode, Modifiers};
+
+    #[test]
+    fn cancel_only_while_running() {
+        let mut pane = BatchExportPane::default();
+        let state = AppState::new();
+        let event = InputEvent::new(KeyCode::Char('c'), Modifiers::default());
+        pane.handle_action(ActionId::BatchExport(BatchExportActionId::Cancel), &event, &state);
+        assert!(!pane.take_cancel());
+
+        let job = Job { pattern: 0, name: "song_Kit_A.wav".into(), state: JobState::Rendering };
+        pane.set_progress(&[job], std::path::Path::new("/tmp"), true);
+        pane.handle_action(ActionId::BatchExport(BatchExportActionId::Cancel), &event, &state);
+        assert!(pane.take_cancel());
+        assert!(!pane.take_cancel());
+    }
+}
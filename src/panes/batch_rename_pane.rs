@@ -0,0 +1,276 @@
+use std::any::Any;
+
+use crate::batch_rename::RenameRule;
+use crate::state::{AppState, InstrumentId};
+use crate::ui::action_id::{ActionId, BatchRenameActionId};
+use crate::ui::layout_helpers::center_rect;
+use crate::ui::widgets::TextInput;
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, InstrumentAction, KeyCode, Keymap, MixerAction, NavAction, Pane, Style};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    Instruments,
+    Buses,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Scope,
+    Find,
+    Replace,
+    Prefix,
+    Number,
+}
+
+/// Something a rename applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Item {
+    Instrument(InstrumentId),
+    Bus(u8),
+}
+
+const FIELDS: [Field; 5] = [Field::Scope, Field::Find, Field::Replace, Field::Prefix, Field::Number];
+
+/// Renames every instrument or bus matching a rule, with a live
+/// before/after preview. The renames are taken by main.rs as one batch.
+pub struct BatchRenamePane {
+    keymap: Keymap,
+    scope: Scope,
+    field: usize,
+    find: TextInput,
+    replace: TextInput,
+    prefix: TextInput,
+    number: TextInput,
+    pending_renames: Option<Action>,
+}
+
+impl BatchRenamePane {
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            scope: Scope::Instruments,
+            field: 1,
+            find: TextInput::new(""),
+            replace: TextInput::new(""),
+            prefix: TextInput::new(""),
+            number: TextInput::new(""),
+            pending_renames: None,
+        }
+    }
+
+    /// Called before push; `buses` picks the mixer's buses over instruments
+    pub fn open(&mut self, buses: bool) {
+        self.scope = if buses { Scope::Buses } else { Scope::Instruments };
+        for input in [&mut self.find, &mut self.replace, &mut self.prefix, &mut self.number] {
+            input.set_value("");
+        }
+        self.field = 1;
+        self.focus();
+    }
+
+    /// Renames confirmed since the last call, as one batch action
+    pub fn take_renames(&mut self) -> Option<Action> {
+        self.pending_renames.take()
+    }
+
+    fn rule(&self) -> RenameRule {
+        RenameRule {
+            find: self.find.value().to_string(),
+            replace: self.replace.value().to_string(),
+            prefix: self.prefix.value().to_string(),
+            number_from: RenameRule::parse_number(self.number.value()),
+        }
+    }
+
+    fn input_mut(&mut self, field: Field) -> Option<&mut TextInput> {
+        match field {
+            Field::Scope => None,
+            Field::Find => Some(&mut self.find),
+            Field::Replace => Some(&mut self.replace),
+            Field::Prefix => Some(&mut self.prefix),
+            Field::Number => Some(&mut self.number),
+        }
+    }
+
+    fn focus(&mut self) {
+        let current = FIELDS[self.field];
+        for field in FIELDS {
+            if let Some(input) = self.input_mut(field) {
+                input.set_focused(field == current);
+            }
+        }
+    }
+
+    /// Current names in scope
+    fn names(&self, state: &AppState) -> Vec<(Item, String)> {
+        match self.scope {
+            Scope::Instruments => state.instruments.instruments.iter().map(|i| (Item::Instrument(i.id), i.name.clone())).collect(),
+            Scope::Buses => state.session.mixer.buses.iter().map(|b| (Item::Bus(b.id), b.name.clone())).collect(),
+        }
+    }
+
+    /// Old and new name for everything the rule changes
+    fn preview(&self, state: &AppState) -> Vec<(Item, String, String)> {
+        let names = self.names(state);
+        let refs: Vec<&str> = names.iter().map(|(_, n)| n.as_str()).collect();
+        self.rule().apply(&refs).into_iter().zip(&names)
+            .filter_map(|(new_name, (item, old))| new_name.map(|new_name| (*item, old.clone(), new_name)))
+            .collect()
+    }
+}
+
+impl Default for BatchRenamePane {
+    fn default() -> Self {
+        Self::new(Keymap::new())
+    }
+}
+
+impl Pane for BatchRenamePane {
+    fn id(&self) -> &'static str {
+        "batch_rename"
+    }
+
+    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, state: &AppState) -> Action {
+        match action {
+            ActionId::BatchRename(BatchRenameActionId::NextField) => {
+                self.field = (self.field + 1) % FIELDS.len();
+                self.focus();
+            }
+            ActionId::BatchRename(BatchRenameActionId::PrevField) => {
+                self.field = (self.field + FIELDS.len() - 1) % FIELDS.len();
+                self.focus();
+            }
+            ActionId::BatchRename(BatchRenameActionId::Apply) => {
+                let renames: Vec<Action> = self.preview(state).into_iter()
+                    .map(|(item, _, name)| match item {
+                        Item::Instrument(id) => Action::Instrument(InstrumentAction::Rename(id, name)),
+                        Item::Bus(id) => Action::Mixer(MixerAction::RenameBus(id, name)),
+                    })
+                    .collect();
+                if !renames.is_empty() {
+                    self.pending_renames = Some(Action::Batch(renames));
+                    return Action::Nav(NavAction::PopPane);
+                }
+            }
+            ActionId::BatchRename(BatchRenameActionId::Close) => return Action::Nav(NavAction::PopPane),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn handle_raw_input(&mut self, event: &InputEvent, _state: &AppState) -> Action {
+        let field = FIELDS[self.field];
+        if field == Field::Scope {
+            if matches!(event.key, KeyCode::Left | KeyCode::Right | KeyCode::Char(' ')) {
+                self.scope = match self.scope {
+                    Scope::Instruments => Scope::Buses,
+                    Scope::Buses => Scope::Instruments,
+                };
+            }
+        } else if let Some(input) = self.input_mut(field) {
+            input.handle_input(event);
+        }
+        Action::None
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, state: &AppState) {
+        let rect = center_rect(area, 72, 24);
+        let border_style = Style::new().fg(Color::CYAN);
+        let inner = buf.draw_block(rect, " Batch Rename ", border_style, border_style);
+
+        let x = inner.x + 1;
+        let width = inner.width.saturating_sub(2);
+        let label_width = 10;
+        let dim = Style::new().fg(Color::DARK_GRAY);
+        let current = FIELDS[self.field];
+        for (row, field) in FIELDS.into_iter().enumerate() {
+            let y = inner.y + 1 + row as u16;
+            let label = match field {
+                Field::Scope => "Rename",
+                Field::Find => "Find",
+                Field::Replace => "Replace",
+                Field::Prefix => "Prefix",
+                Field::Number => "Number",
+            };
+            let label_style = if field == current { Style::new().fg(Color::WHITE).bold() } else { Style::new().fg(Color::GRAY) };
+            buf.draw_line(Rect::new(x, y, label_width, 1), &[(label, label_style)]);
+            let value_x = x + label_width;
+            let value_width = width.saturating_sub(label_width);
+            match field {
+                Field::Scope => {
+                    let text = match self.scope {
+                        Scope::Instruments => "< Instruments >",
+                        Scope::Buses => "< Buses >",
+                    };
+                    let style = if field == current { Style::new().fg(Color::CYAN).bg(Color::SELECTION_BG) } else { Style::new().fg(Color::CYAN) };
+                    buf.draw_line(Rect::new(value_x, y, value_width, 1), &[(text, style)]);
+                }
+                _ => {
+                    if let Some(input) = self.input_mut(field) {
+                        input.render_buf(buf.raw_buf(), value_x, y, value_width);
+                    }
+                }
+            }
+        }
+
+        let list_y = inner.y + 2 + FIELDS.len() as u16;
+        let rows = (inner.y + inner.height).saturating_sub(list_y + 2) as usize;
+        let preview = self.preview(state);
+        if preview.is_empty() {
+            buf.draw_line(Rect::new(x, list_y, width, 1), &[("Nothing to rename", dim)]);
+        }
+        for (row, (_, old, new)) in preview.iter().take(rows).enumerate() {
+            let old = format!("{:<24}", old);
+            buf.draw_line(
+                Rect::new(x, list_y + row as u16, width, 1),
+                &[(&old, Style::new().fg(Color::GRAY)), (" -> ", dim), (new, Style::new().fg(Color::WHITE))],
+            );
+        }
+        if preview.len() > rows {
+            let more = format!("... and {} more", preview.len() - rows);
+            buf.draw_line(Rect::new(x, list_y + rows as u16, width, 1), &[(&more, dim)]);
+        }
+
+        let help = "Tab/Up/Down: field | Left/Right: scope | Number: start, e.g. 1 or 01 | Enter: rename | Esc: cancel";
+        buf.draw_line(Rect::new(x, rect.y + rect.height.saturating_sub(2), width, 1), &[(help, dim)]);
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn on_enter(&mut self, _state: &AppState) {
+        self.focus();
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::SourceType;
+    use crate::ui::Modifiers;
+
+    #[test]
+    fn apply_batches_renames_of_matching_instruments() {
+        let mut state = AppState::new();
+        state.add_instrument(SourceType::Saw);
+        state.add_instrument(SourceType::Saw);
+        state.instruments.instruments[0].name = "Audio 1".to_string();
+        state.instruments.instruments[1].name = "Bass".to_string();
+
+        let mut pane = BatchRenamePane::default();
+        pane.open(false);
+        pane.find.set_value("Audio");
+        pane.replace.set_value("Vox");
+        assert_eq!(pane.preview(&state).len(), 1);
+
+        let action = pane.handle_action(ActionId::BatchRename(BatchRenameActionId::Apply), &InputEvent::new(KeyCode::Enter, Modifiers::default()), &state);
+        assert!(matches!(action, Action::Nav(NavAction::PopPane)));
+        let Some(Action::Batch(renames)) = pane.take_renames() else { panic!("expected a batch") };
+        assert!(matches!(&renames[..], [Action::Instrument(InstrumentAction::Rename(_, name))] if name == "Vox 1"));
+    }
+}
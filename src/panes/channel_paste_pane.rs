@@ -0,0 +1,174 @@
+use std::any::Any;
+
+use crate::action::ChannelParts;
+use crate::state::{AppState, InstrumentId};
+use crate::ui::action_id::{ActionId, ChannelPasteActionId};
+use crate::ui::layout_helpers::center_rect;
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, InstrumentAction, Keymap, NavAction, Pane, Style};
+
+const PARTS: [&str; 6] = ["Level", "Pan", "Sends", "Filter", "EQ", "Effects"];
+
+/// Choose which parts of a copied channel strip to paste onto another
+/// instrument. The selection is kept between pastes.
+pub struct ChannelPastePane {
+    keymap: Keymap,
+    source: Option<InstrumentId>,
+    target: Option<InstrumentId>,
+    include: [bool; 6],
+    cursor: usize,
+}
+
+impl ChannelPastePane {
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            source: None,
+            target: None,
+            include: [true; 6],
+            cursor: 0,
+        }
+    }
+
+    pub fn set_instruments(&mut self, source: InstrumentId, target: InstrumentId) {
+        self.source = Some(source);
+        self.target = Some(target);
+    }
+
+    fn parts(&self) -> ChannelParts {
+        let [level, pan, sends, filter, eq, effects] = self.include;
+        ChannelParts { level, pan, sends, filter, eq, effects }
+    }
+
+    fn paste_action(&self) -> Action {
+        match (self.source, self.target) {
+            (Some(from), Some(to)) if from != to && self.include.iter().any(|b| *b) => {
+                Action::Instrument(InstrumentAction::PasteChannel { from, to, parts: self.parts() })
+            }
+            _ => Action::None,
+        }
+    }
+}
+
+impl Default for ChannelPastePane {
+    fn default() -> Self {
+        Self::new(Keymap::new())
+    }
+}
+
+impl Pane for ChannelPastePane {
+    fn id(&self) -> &'static str {
+        "channel_paste"
+    }
+
+    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, _state: &AppState) -> Action {
+        match action {
+            ActionId::ChannelPaste(ChannelPasteActionId::Up) => {
+                self.cursor = self.cursor.saturating_sub(1);
+                Action::None
+            }
+            ActionId::ChannelPaste(ChannelPasteActionId::Down) => {
+                self.cursor = (self.cursor + 1).min(PARTS.len() - 1);
+                Action::None
+            }
+            ActionId::ChannelPaste(ChannelPasteActionId::Toggle) => {
+                self.include[self.cursor] = !self.include[self.cursor];
+                Action::None
+            }
+            ActionId::ChannelPaste(ChannelPasteActionId::All) => {
+                let all = !self.include.iter().all(|b| *b);
+                self.include = [all; 6];
+                Action::None
+            }
+            ActionId::ChannelPaste(ChannelPasteActionId::Apply) => {
+                match self.paste_action() {
+                    Action::None => Action::None,
+                    paste => Action::Batch(vec![paste, Action::Nav(NavAction::PopPane)]),
+                }
+            }
+            ActionId::ChannelPaste(ChannelPasteActionId::Close) => Action::Nav(NavAction::PopPane),
+            _ => Action::None,
+        }
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, state: &AppState) {
+        let rect = center_rect(area, 44, PARTS.len() as u16 + 7);
+        let border_style = Style::new().fg(Color::CYAN);
+        let inner = buf.draw_block(rect, " Paste Channel ", border_style, border_style);
+
+        let name = |id: Option<InstrumentId>| {
+            id.and_then(|id| state.instruments.instrument(id))
+                .map(|i| i.name.clone())
+                .unwrap_or_else(|| "---".to_string())
+        };
+        let header = format!("{} \u{2192} {}", name(self.source), name(self.target));
+        let x = inner.x + 2;
+        let w = inner.width.saturating_sub(4);
+        buf.draw_line(Rect::new(x, inner.y, w, 1), &[(&header, Style::new().fg(Color::WHITE).bold())]);
+
+        for (i, part) in PARTS.iter().enumerate() {
+            let y = inner.y + 2 + i as u16;
+            let is_selected = i == self.cursor;
+            if is_selected {
+                for cx in inner.x..inner.x + inner.width {
+                    buf.set_cell(cx, y, ' ', Style::new().bg(Color::SELECTION_BG));
+                }
+                buf.set_cell(x, y, '>', Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG).bold());
+            }
+            let check = if self.include[i] { "[x] " } else { "[ ] " };
+            let mut style = Style::new().fg(if self.include[i] { Color::WHITE } else { Color::DARK_GRAY });
+            if is_selected {
+                style = style.bg(Color::SELECTION_BG);
+            }
+            buf.draw_line(Rect::new(x + 2, y, w.saturating_sub(2), 1), &[(check, style), (part, style)]);
+        }
+
+        let help_y = rect.y + rect.height - 2;
+        if help_y < area.y + area.height {
+            buf.draw_line(
+                Rect::new(x, help_y, w, 1),
+                &[("t: toggle | a: all | Enter: paste | Esc", Style::new().fg(Color::DARK_GRAY))],
+            );
+        }
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn on_enter(&mut self, _state: &AppState) {
+        self.cursor = 0;
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pastes_only_selected_parts() {
+        let mut pane = ChannelPastePane::default();
+        pane.set_instruments(1, 2);
+        pane.include = [true, false, true, false, false, false];
+        match pane.paste_action() {
+            Action::Instrument(InstrumentAction::PasteChannel { from: 1, to: 2, parts }) => {
+                assert!(parts.level && parts.sends);
+                assert!(!parts.pan && !parts.effects);
+            }
+            _ => panic!("expected PasteChannel"),
+        }
+    }
+
+    #[test]
+    fn nothing_to_paste_onto_itself_or_with_no_parts() {
+        let mut pane = ChannelPastePane::default();
+        pane.set_instruments(3, 3);
+        assert!(matches!(pane.paste_action(), Action::None));
+        pane.set_instruments(3, 4);
+        pane.include = [false; 6];
+        assert!(matches!(pane.paste_action(), Action::None));
+    }
+}
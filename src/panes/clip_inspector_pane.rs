@@ -0,0 +1,224 @@
+use std::any::Any;
+
+use crate::state::AppState;
+use crate::state::arrangement::ClipId;
+use crate::ui::action_id::{ActionId, ClipInspectorActionId};
+use crate::ui::layout_helpers::center_rect;
+use crate::ui::{Rect, RenderBuf, Action, ArrangementAction, Color, InputEvent, Keymap, NavAction, Pane, Style};
+
+/// Fields editable in the clip inspector
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Gain,
+    Reverse,
+}
+
+const FIELDS: [Field; 2] = [Field::Gain, Field::Reverse];
+
+const MIN_GAIN_DB: f32 = -24.0;
+const MAX_GAIN_DB: f32 = 12.0;
+const GAIN_STEP_DB: f32 = 0.5;
+
+/// Non-destructive per-clip playback settings. Every placement of the clip
+/// shares them; the clip's notes are left untouched.
+pub struct ClipInspectorPane {
+    keymap: Keymap,
+    clip_id: Option<ClipId>,
+    clip_name: String,
+    gain_db: f32,
+    reverse: bool,
+    selected: usize,
+}
+
+impl ClipInspectorPane {
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            clip_id: None,
+            clip_name: String::new(),
+            gain_db: 0.0,
+            reverse: false,
+            selected: 0,
+        }
+    }
+
+    fn current_field(&self) -> Field {
+        FIELDS[self.selected]
+    }
+
+    fn adjust(&mut self, increase: bool) {
+        match self.current_field() {
+            Field::Gain => {
+                let delta = if increase { GAIN_STEP_DB } else { -GAIN_STEP_DB };
+                self.gain_db = (self.gain_db + delta).clamp(MIN_GAIN_DB, MAX_GAIN_DB);
+            }
+            Field::Reverse => self.reverse = !self.reverse,
+        }
+    }
+
+    fn reset(&mut self) {
+        match self.current_field() {
+            Field::Gain => self.gain_db = 0.0,
+            Field::Reverse => self.reverse = false,
+        }
+    }
+
+    fn field_label(field: Field) -> &'static str {
+        match field {
+            Field::Gain => "Gain",
+            Field::Reverse => "Reverse",
+        }
+    }
+
+    fn field_value(&self, field: Field) -> String {
+        match field {
+            Field::Gain => format!("{:+.1} dB", self.gain_db),
+            Field::Reverse => if self.reverse { "On".into() } else { "Off".into() },
+        }
+    }
+
+    fn emit(&self) -> Action {
+        let Some(clip_id) = self.clip_id else {
+            return Action::None;
+        };
+        match self.current_field() {
+            Field::Gain => Action::Arrangement(ArrangementAction::SetClipGain { clip_id, gain_db: self.gain_db }),
+            Field::Reverse => Action::Arrangement(ArrangementAction::SetClipReverse { clip_id, reverse: self.reverse }),
+        }
+    }
+}
+
+impl Default for ClipInspectorPane {
+    fn default() -> Self {
+        Self::new(Keymap::new())
+    }
+}
+
+impl Pane for ClipInspectorPane {
+    fn id(&self) -> &'static str {
+        "clip_inspector"
+    }
+
+    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, _state: &AppState) -> Action {
+        match action {
+            ActionId::ClipInspector(ClipInspectorActionId::Prev) => {
+                self.selected = self.selected.saturating_sub(1);
+                Action::None
+            }
+            ActionId::ClipInspector(ClipInspectorActionId::Next) => {
+                if self.selected < FIELDS.len() - 1 {
+                    self.selected += 1;
+                }
+                Action::None
+            }
+            ActionId::ClipInspector(ClipInspectorActionId::Decrease) => {
+                self.adjust(false);
+                self.emit()
+            }
+            ActionId::ClipInspector(ClipInspectorActionId::Increase) => {
+                self.adjust(true);
+                self.emit()
+            }
+            ActionId::ClipInspector(ClipInspectorActionId::Reset) => {
+                self.reset();
+                self.emit()
+            }
+            ActionId::ClipInspector(ClipInspectorActionId::Close) => Action::Nav(NavAction::PopPane),
+            _ => Action::None,
+        }
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, _state: &AppState) {
+        let rect = center_rect(area, 40, 8);
+
+        let title = format!(" Clip: {} ", self.clip_name);
+        let border_style = Style::new().fg(Color::CYAN);
+        let inner = buf.draw_block(rect, &title, border_style, border_style);
+
+        let label_col = inner.x + 2;
+        let value_col = label_col + 12;
+
+        for (i, field) in FIELDS.iter().enumerate() {
+            let y = inner.y + 1 + i as u16;
+            if y >= inner.y + inner.height {
+                break;
+            }
+            let is_selected = i == self.selected;
+
+            if is_selected {
+                for x in inner.x..inner.x + inner.width {
+                    buf.set_cell(x, y, ' ', Style::new().bg(Color::SELECTION_BG));
+                }
+                buf.set_cell(label_col, y, '>', Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG).bold());
+            }
+
+            let label_style = if is_selected {
+                Style::new().fg(Color::CYAN).bg(Color::SELECTION_BG)
+            } else {
+                Style::new().fg(Color::CYAN)
+            };
+            let val_style = if is_selected {
+                Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG)
+            } else {
+                Style::new().fg(Color::WHITE)
+            };
+            let label = format!("{:10}", Self::field_label(*field));
+            buf.draw_line(Rect::new(label_col + 2, y, 10, 1), &[(&label, label_style)]);
+            let val = self.field_value(*field);
+            buf.draw_line(Rect::new(value_col, y, inner.width.saturating_sub(14), 1), &[(&val, val_style)]);
+        }
+
+        let help_y = rect.y + rect.height - 2;
+        if help_y < area.y + area.height {
+            buf.draw_line(
+                Rect::new(inner.x + 2, help_y, inner.width.saturating_sub(2), 1),
+                &[("Left/Right: adjust | r: reset | Esc: close", Style::new().fg(Color::DARK_GRAY))],
+            );
+        }
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn on_enter(&mut self, state: &AppState) {
+        let arr = &state.session.arrangement;
+        let clip = state.instruments.instruments
+            .get(arr.selected_lane)
+            .and_then(|inst| arr.placement_at(inst.id, arr.cursor_tick))
+            .and_then(|placement| arr.clip(placement.clip_id));
+        self.clip_id = clip.map(|c| c.id);
+        self.clip_name = clip.map(|c| c.name.clone()).unwrap_or_default();
+        self.gain_db = clip.map(|c| c.gain_db).unwrap_or(0.0);
+        self.reverse = clip.map(|c| c.reverse).unwrap_or(false);
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gain_clamps_to_range() {
+        let mut pane = ClipInspectorPane::new(Keymap::new());
+        for _ in 0..100 {
+            pane.adjust(true);
+        }
+        assert_eq!(pane.gain_db, MAX_GAIN_DB);
+        pane.reset();
+        assert_eq!(pane.gain_db, 0.0);
+    }
+
+    #[test]
+    fn edits_without_clip_do_nothing() {
+        let mut pane = ClipInspectorPane::new(Keymap::new());
+        pane.selected = 1;
+        pane.adjust(true);
+        assert!(pane.reverse);
+        assert!(matches!(pane.emit(), Action::None));
+    }
+}
@@ -0,0 +1,382 @@
+use std::any::Any;
+
+use crate::state::AppState;
+use crate::ui::action_id::{ActionId, CompActionId};
+use crate::ui::layout_helpers::center_rect;
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, NavAction, Pane, PianoRollAction, Style};
+
+/// Timeline columns per beat in the comp view (one per 1/16 note)
+const COLS_PER_BEAT: u32 = 4;
+
+/// Ticks per timeline column at the given resolution
+fn ticks_per_col(ticks_per_beat: u32) -> u32 {
+    (ticks_per_beat / COLS_PER_BEAT).max(1)
+}
+
+/// Take lane colors, cycled by take index
+const TAKE_COLORS: [Color; 6] = [
+    Color::SKY_BLUE,
+    Color::ORANGE,
+    Color::TEAL,
+    Color::PINK,
+    Color::GOLD,
+    Color::PURPLE,
+];
+
+/// Comping view: lists recorded take lanes for the current piano roll track
+/// and lets regions of each take be chosen for the final comp.
+pub struct CompPane {
+    keymap: Keymap,
+    /// Piano roll track index being comped
+    track: usize,
+    /// Selected take index (row)
+    cursor_take: usize,
+    cursor_tick: u32,
+    view_start_tick: u32,
+    /// Range selection anchor tick. None = no selection.
+    selection_anchor: Option<u32>,
+    /// Timeline width in columns as of the last render, for scrolling
+    timeline_cols: u32,
+}
+
+impl CompPane {
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            track: 0,
+            cursor_take: 0,
+            cursor_tick: 0,
+            view_start_tick: 0,
+            selection_anchor: None,
+            timeline_cols: 0,
+        }
+    }
+
+    /// Selected range as (start_tick, end_tick). Without a selection the
+    /// range is the bar under the cursor.
+    fn selection_range(&self, ticks_per_bar: u32, ticks_per_col: u32) -> (u32, u32) {
+        match self.selection_anchor {
+            Some(anchor) => {
+                let (t0, t1) = crate::state::grid::normalize_tick_range(anchor, self.cursor_tick);
+                (t0, t1 + ticks_per_col)
+            }
+            None => {
+                let bar_start = self.cursor_tick - self.cursor_tick % ticks_per_bar;
+                (bar_start, bar_start + ticks_per_bar)
+            }
+        }
+    }
+
+    fn take_count(&self, state: &AppState) -> usize {
+        state.session.piano_roll.track_at(self.track)
+            .map(|t| t.takes.len())
+            .unwrap_or(0)
+    }
+
+    /// Keep the cursor within the timeline as wide as it was last drawn
+    fn scroll_to_cursor(&mut self, ticks_per_col: u32) {
+        if self.timeline_cols == 0 {
+            return;
+        }
+        let visible_ticks = self.timeline_cols * ticks_per_col;
+        if self.cursor_tick < self.view_start_tick {
+            self.view_start_tick = self.cursor_tick;
+        } else if self.cursor_tick >= self.view_start_tick + visible_ticks {
+            self.view_start_tick = self.cursor_tick + ticks_per_col - visible_ticks;
+        }
+    }
+}
+
+impl Default for CompPane {
+    fn default() -> Self {
+        Self::new(Keymap::new())
+    }
+}
+
+impl Pane for CompPane {
+    fn id(&self) -> &'static str {
+        "comp"
+    }
+
+    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, state: &AppState) -> Action {
+        let take_count = self.take_count(state);
+        let tpc = ticks_per_col(state.session.piano_roll.ticks_per_beat);
+        match action {
+            ActionId::Comp(CompActionId::Up) => {
+                self.cursor_take = self.cursor_take.saturating_sub(1);
+                Action::None
+            }
+            ActionId::Comp(CompActionId::Down) => {
+                if self.cursor_take + 1 < take_count {
+                    self.cursor_take += 1;
+                }
+                Action::None
+            }
+            ActionId::Comp(CompActionId::Left) => {
+                self.selection_anchor = None;
+                self.cursor_tick = self.cursor_tick.saturating_sub(tpc);
+                self.scroll_to_cursor(tpc);
+                Action::None
+            }
+            ActionId::Comp(CompActionId::Right) => {
+                self.selection_anchor = None;
+                self.cursor_tick += tpc;
+                self.scroll_to_cursor(tpc);
+                Action::None
+            }
+            ActionId::Comp(CompActionId::SelectLeft) => {
+                if self.selection_anchor.is_none() {
+                    self.selection_anchor = Some(self.cursor_tick);
+                }
+                self.cursor_tick = self.cursor_tick.saturating_sub(tpc);
+                self.scroll_to_cursor(tpc);
+                Action::None
+            }
+            ActionId::Comp(CompActionId::SelectRight) => {
+                if self.selection_anchor.is_none() {
+                    self.selection_anchor = Some(self.cursor_tick);
+                }
+                self.cursor_tick += tpc;
+                self.scroll_to_cursor(tpc);
+                Action::None
+            }
+            ActionId::Comp(CompActionId::UseRegion) => {
+                let Some(take) = state.session.piano_roll.track_at(self.track)
+                    .and_then(|t| t.takes.get(self.cursor_take)) else {
+                    return Action::None;
+                };
+                let (start_tick, end_tick) = self.selection_range(state.session.piano_roll.ticks_per_bar(), tpc);
+                self.selection_anchor = None;
+                Action::PianoRoll(PianoRollAction::CompRegion {
+                    track: self.track,
+                    take_id: take.id,
+                    start_tick,
+                    end_tick,
+                })
+            }
+            ActionId::Comp(CompActionId::UseTake) => {
+                let Some(take) = state.session.piano_roll.track_at(self.track)
+                    .and_then(|t| t.takes.get(self.cursor_take)) else {
+                    return Action::None;
+                };
+                let end_tick = take.notes.iter().map(|n| n.tick + n.duration).max().unwrap_or(0);
+                Action::PianoRoll(PianoRollAction::CompRegion {
+                    track: self.track,
+                    take_id: take.id,
+                    start_tick: 0,
+                    end_tick,
+                })
+            }
+            ActionId::Comp(CompActionId::DeleteTake) => {
+                let Some(take) = state.session.piano_roll.track_at(self.track)
+                    .and_then(|t| t.takes.get(self.cursor_take)) else {
+                    return Action::None;
+                };
+                let take_id = take.id;
+                if self.cursor_take + 1 >= take_count {
+                    self.cursor_take = self.cursor_take.saturating_sub(1);
+                }
+                Action::PianoRoll(PianoRollAction::DeleteTake { track: self.track, take_id })
+            }
+            ActionId::Comp(CompActionId::Flatten) => {
+                if take_count == 0 {
+                    return Action::None;
+                }
+                Action::PianoRoll(PianoRollAction::FlattenComp(self.track))
+            }
+            ActionId::Comp(CompActionId::Close) => Action::Nav(NavAction::PopPane),
+            _ => Action::None,
+        }
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, state: &AppState) {
+        let rect = center_rect(area, 97, 29);
+        let border_style = Style::new().fg(Color::CYAN);
+        let title = format!(" Takes: track {} ", self.track + 1);
+        let inner = buf.draw_block(rect, &title, border_style, border_style);
+
+        let Some(track) = state.session.piano_roll.track_at(self.track) else {
+            return;
+        };
+        let tpb = state.session.piano_roll.ticks_per_beat;
+        let tpbar = state.session.piano_roll.ticks_per_bar();
+        if track.takes.is_empty() {
+            let text = "(no takes - enable take lanes in record settings)";
+            let x = inner.x + inner.width.saturating_sub(text.len() as u16) / 2;
+            let y = inner.y + inner.height / 2;
+            buf.draw_line(Rect::new(x, y, text.len() as u16, 1), &[(text, Style::new().fg(Color::DARK_GRAY))]);
+            return;
+        }
+
+        let label_width: u16 = 12;
+        let timeline_x = inner.x + label_width + 1;
+        let timeline_width = inner.width.saturating_sub(label_width + 2);
+        let footer_height: u16 = 2;
+        let rows_y = inner.y + 2;
+        let rows_height = inner.height.saturating_sub(2 + footer_height);
+        let tpc = ticks_per_col(tpb);
+        self.timeline_cols = timeline_width as u32;
+        self.scroll_to_cursor(tpc);
+        let visible_end = self.view_start_tick + timeline_width as u32 * tpc;
+
+        // Header: bar numbers
+        for col in 0..timeline_width as u32 {
+            let tick = self.view_start_tick + col * tpc;
+            if tick % tpbar == 0 {
+                let label = format!("{}", tick / tpbar + 1);
+                buf.draw_line(
+                    Rect::new(timeline_x + col as u16, inner.y, label.len() as u16, 1),
+                    &[(&label, Style::new().fg(Color::DARK_GRAY))],
+                );
+            }
+        }
+
+        // Comp row: which take is active at each column
+        let comp_style = Style::new().fg(Color::WHITE).bold();
+        buf.draw_line(Rect::new(inner.x + 1, inner.y + 1, label_width, 1), &[("Comp", comp_style)]);
+        for col in 0..timeline_width as u32 {
+            let tick = self.view_start_tick + col * tpc;
+            if let Some(region) = track.comp.iter().find(|r| tick >= r.start_tick && tick < r.end_tick) {
+                if let Some(idx) = track.takes.iter().position(|t| t.id == region.take_id) {
+                    let color = TAKE_COLORS[idx % TAKE_COLORS.len()];
+                    buf.set_cell(timeline_x + col as u16, inner.y + 1, '\u{2580}', Style::new().fg(color));
+                }
+            }
+        }
+
+        let (sel_start, sel_end) = self.selection_range(tpbar, tpc);
+        for (i, take) in track.takes.iter().enumerate() {
+            let y = rows_y + i as u16;
+            if y >= rows_y + rows_height {
+                break;
+            }
+            let is_selected = i == self.cursor_take;
+            let color = TAKE_COLORS[i % TAKE_COLORS.len()];
+            let label_style = if is_selected {
+                Style::new().fg(color).bg(Color::SELECTION_BG).bold()
+            } else {
+                Style::new().fg(color)
+            };
+            let label = format!("{:<width$}", take.name, width = label_width as usize);
+            buf.draw_line(Rect::new(inner.x + 1, y, label_width, 1), &[(&label, label_style)]);
+            buf.set_cell(inner.x + label_width, y, '|', Style::new().fg(Color::GRAY));
+
+            for col in 0..timeline_width as u32 {
+                let tick = self.view_start_tick + col * tpc;
+                let x = timeline_x + col as u16;
+                let in_selection = is_selected && tick >= sel_start && tick < sel_end;
+                let has_note = take.notes.iter().any(|n| tick < n.tick + n.duration && tick + tpc > n.tick);
+                let in_comp = track.comp.iter()
+                    .any(|r| r.take_id == take.id && tick >= r.start_tick && tick < r.end_tick);
+                let bg = if in_selection { Color::SELECTION_BG } else { Color::new(20, 20, 20) };
+                let (ch, style) = if has_note {
+                    let fg = if in_comp { color } else { Color::DARK_GRAY };
+                    ('\u{2588}', Style::new().fg(fg).bg(bg))
+                } else if tick % tpbar == 0 {
+                    ('|', Style::new().fg(Color::new(50, 50, 50)).bg(bg))
+                } else {
+                    (' ', Style::new().bg(bg))
+                };
+                buf.set_cell(x, y, ch, style);
+            }
+        }
+
+        // Cursor
+        if self.cursor_tick >= self.view_start_tick && self.cursor_tick < visible_end {
+            let x = timeline_x + ((self.cursor_tick - self.view_start_tick) / tpc) as u16;
+            let y = rows_y + self.cursor_take as u16;
+            if y < rows_y + rows_height {
+                buf.set_cell(x, y, '\u{258c}', Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG));
+            }
+        }
+
+        // Footer
+        let footer_y = inner.y + inner.height - footer_height;
+        let bar = self.cursor_tick / tpbar + 1;
+        let beat = (self.cursor_tick % tpbar) / tpb + 1;
+        let status = format!("Bar {} Beat {}  |  Take {}/{}", bar, beat, self.cursor_take + 1, track.takes.len());
+        buf.draw_line(Rect::new(inner.x + 1, footer_y, inner.width.saturating_sub(2), 1),
+            &[(&status, Style::new().fg(Color::GRAY))]);
+        buf.draw_line(Rect::new(inner.x + 1, footer_y + 1, inner.width.saturating_sub(2), 1),
+            &[("Enter:use region  a:use take  d:delete take  f:flatten  Esc:back", Style::new().fg(Color::DARK_GRAY))]);
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn on_enter(&mut self, state: &AppState) {
+        // Comp the track of the globally selected instrument
+        let track = state.instruments.selected
+            .and_then(|idx| state.instruments.instruments.get(idx))
+            .and_then(|inst| state.session.piano_roll.track_order.iter().position(|&id| id == inst.id))
+            .unwrap_or(0);
+        if track != self.track {
+            self.cursor_take = 0;
+        }
+        self.track = track;
+        self.selection_anchor = None;
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::{KeyCode, Modifiers};
+
+    fn dummy_event() -> InputEvent {
+        InputEvent::new(KeyCode::Char('x'), Modifiers::default())
+    }
+
+    #[test]
+    fn range_defaults_to_bar_under_cursor() {
+        let mut pane = CompPane::new(Keymap::new());
+        pane.cursor_tick = 1920 + 480;
+        assert_eq!(pane.selection_range(1920, 120), (1920, 3840));
+        // Bars in 3/4 are three beats long
+        assert_eq!(pane.selection_range(1440, 120), (1440, 2880));
+    }
+
+    #[test]
+    fn shift_select_extends_range() {
+        let mut pane = CompPane::new(Keymap::new());
+        let state = AppState::new();
+        for _ in 0..4 {
+            pane.handle_action(ActionId::Comp(CompActionId::SelectRight), &dummy_event(), &state);
+        }
+        assert_eq!(pane.selection_anchor, Some(0));
+        let tpc = ticks_per_col(state.session.piano_roll.ticks_per_beat);
+        assert_eq!(pane.selection_range(1920, tpc), (0, 5 * tpc));
+
+        pane.handle_action(ActionId::Comp(CompActionId::Left), &dummy_event(), &state);
+        assert_eq!(pane.selection_anchor, None);
+    }
+
+    #[test]
+    fn scrolling_uses_the_drawn_timeline_width() {
+        let mut pane = CompPane::new(Keymap::new());
+        pane.timeline_cols = 10;
+        pane.cursor_tick = 10 * 120;
+        pane.scroll_to_cursor(120);
+        assert_eq!(pane.view_start_tick, 120);
+        // Twice as wide, the cursor is already in view
+        pane.view_start_tick = 0;
+        pane.timeline_cols = 20;
+        pane.scroll_to_cursor(120);
+        assert_eq!(pane.view_start_tick, 0);
+    }
+
+    #[test]
+    fn use_region_without_takes_is_noop() {
+        let mut pane = CompPane::new(Keymap::new());
+        let state = AppState::new();
+        let action = pane.handle_action(ActionId::Comp(CompActionId::UseRegion), &dummy_event(), &state);
+        assert!(matches!(action, Action::None));
+        let action = pane.handle_action(ActionId::Comp(CompActionId::Flatten), &dummy_event(), &state);
+        assert!(matches!(action, Action::None));
+    }
+}
@@ -0,0 +1,265 @@
+use std::any::Any;
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use crate::state::AppState;
+use crate::ui::action_id::{ActionId, ConsoleActionId};
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, MouseButton, MouseEvent, MouseEventKind, NavAction, Pane, Style};
+
+/// Messages kept in the history; older ones are dropped
+const MAX_ENTRIES: usize = 500;
+
+struct Entry {
+    secs: f64,
+    text: String,
+}
+
+impl Entry {
+    fn is_error(&self) -> bool {
+        let text = self.text.to_lowercase();
+        text.contains("error") || text.contains("fail")
+    }
+}
+
+/// History of status messages (server events, loads, exports, errors).
+/// A selected message can be copied to the system clipboard, for pasting
+/// error text or an exported file's path elsewhere.
+pub struct ConsolePane {
+    keymap: Keymap,
+    started: Instant,
+    entries: VecDeque<Entry>,
+    /// Selected entry; None follows the newest
+    cursor: Option<usize>,
+    /// Text to copy, taken by main.rs
+    pending_copy: Option<String>,
+    /// Result of the last copy, shown on the help line
+    notice: Option<String>,
+}
+
+impl ConsolePane {
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            started: Instant::now(),
+            entries: VecDeque::new(),
+            cursor: None,
+            pending_copy: None,
+            notice: None,
+        }
+    }
+
+    /// Add a message to the history
+    pub fn push(&mut self, text: &str) {
+        // Repeats of the latest message add nothing
+        if text.is_empty() || self.entries.back().map_or(false, |e| e.text == text) {
+            return;
+        }
+        self.entries.push_back(Entry {
+            secs: self.started.elapsed().as_secs_f64(),
+            text: text.to_string(),
+        });
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.pop_front();
+            self.cursor = self.cursor.map(|c| c.saturating_sub(1));
+        }
+    }
+
+    /// Text the user asked to copy since the last call
+    pub fn take_copy(&mut self) -> Option<String> {
+        self.pending_copy.take()
+    }
+
+    pub fn set_notice(&mut self, notice: String) {
+        self.notice = Some(notice);
+    }
+
+    fn selected(&self) -> Option<usize> {
+        let last = self.entries.len().checked_sub(1)?;
+        Some(self.cursor.unwrap_or(last).min(last))
+    }
+
+    fn move_by(&mut self, delta: isize) {
+        let Some(selected) = self.selected() else {
+            return;
+        };
+        let last = self.entries.len() - 1;
+        let next = selected.saturating_add_signed(delta).min(last);
+        self.cursor = if next == last { None } else { Some(next) };
+    }
+
+    fn copy_selected(&mut self) {
+        if let Some(entry) = self.selected().and_then(|i| self.entries.get(i)) {
+            self.pending_copy = Some(entry.text.clone());
+        }
+    }
+
+    /// Rows for messages inside the border, above the help line
+    fn list_rect(area: Rect) -> Rect {
+        Rect::new(area.x + 2, area.y + 1, area.width.saturating_sub(4), area.height.saturating_sub(3))
+    }
+
+    /// Index of the first message shown, keeping the selection in view
+    fn first_visible(&self, rows: usize) -> usize {
+        let bottom = self.entries.len().saturating_sub(rows);
+        match self.selected() {
+            Some(selected) if selected < bottom => selected,
+            _ => bottom,
+        }
+    }
+}
+
+impl Default for ConsolePane {
+    fn default() -> Self {
+        Self::new(Keymap::new())
+    }
+}
+
+impl Pane for ConsolePane {
+    fn id(&self) -> &'static str {
+        "console"
+    }
+
+    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, _state: &AppState) -> Action {
+        match action {
+            ActionId::Console(ConsoleActionId::Up) => self.move_by(-1),
+            ActionId::Console(ConsoleActionId::Down) => self.move_by(1),
+            ActionId::Console(ConsoleActionId::PageUp) => self.move_by(-10),
+            ActionId::Console(ConsoleActionId::PageDown) => self.move_by(10),
+            ActionId::Console(ConsoleActionId::Top) => {
+                if !self.entries.is_empty() {
+                    self.cursor = Some(0);
+                }
+            }
+            ActionId::Console(ConsoleActionId::Bottom) => self.cursor = None,
+            ActionId::Console(ConsoleActionId::Copy) => self.copy_selected(),
+            ActionId::Console(ConsoleActionId::Clear) => {
+                self.entries.clear();
+                self.cursor = None;
+            }
+            ActionId::Console(ConsoleActionId::Close) => return Action::Nav(NavAction::PopPane),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn handle_mouse(&mut self, event: &MouseEvent, area: Rect, _state: &AppState) -> Action {
+        let list = Self::list_rect(area);
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if event.column < list.x || event.column >= list.x + list.width
+                    || event.row < list.y || event.row >= list.y + list.height
+                {
+                    return Action::None;
+                }
+                let idx = self.first_visible(list.height as usize) + (event.row - list.y) as usize;
+                if idx >= self.entries.len() {
+                    return Action::None;
+                }
+                // Clicking the selected message again copies it
+                if self.selected() == Some(idx) {
+                    self.copy_selected();
+                } else {
+                    self.cursor = Some(idx);
+                }
+            }
+            MouseEventKind::ScrollUp => self.move_by(-3),
+            MouseEventKind::ScrollDown => self.move_by(3),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, _state: &AppState) {
+        let title = format!(" Console ({} messages) ", self.entries.len());
+        let border_style = Style::new().fg(Color::CYAN);
+        buf.draw_block(area, &title, border_style, border_style);
+
+        let list = Self::list_rect(area);
+        let dim = Style::new().fg(Color::DARK_GRAY);
+        let selected = self.selected();
+        let start = self.first_visible(list.height as usize);
+
+        for (row, (i, entry)) in self.entries.iter().enumerate().skip(start).take(list.height as usize).enumerate() {
+            let y = list.y + row as u16;
+            let is_selected = selected == Some(i);
+            let color = if entry.is_error() { Color::RED } else { Color::WHITE };
+            let mut time_style = Style::new().fg(Color::GRAY);
+            let mut text_style = Style::new().fg(color);
+            if is_selected {
+                for x in list.x..list.x + list.width {
+                    buf.set_cell(x, y, ' ', Style::new().bg(Color::SELECTION_BG));
+                }
+                time_style = time_style.bg(Color::SELECTION_BG);
+                text_style = text_style.bg(Color::SELECTION_BG);
+            }
+            let time = format!("{:>8.1}  ", entry.secs);
+            buf.draw_line(Rect::new(list.x, y, list.width, 1), &[(&time, time_style), (&entry.text, text_style)]);
+        }
+        if self.entries.is_empty() {
+            buf.draw_line(Rect::new(list.x, list.y, list.width, 1), &[("(no messages yet)", dim)]);
+        }
+
+        let help_y = area.y + area.height.saturating_sub(2);
+        let help = "Up/Down: select | y/Enter/click: copy | c: clear | Esc: close";
+        match &self.notice {
+            Some(notice) => buf.draw_line(Rect::new(list.x, help_y, list.width, 1),
+                &[(notice, Style::new().fg(Color::TEAL)), ("  ", dim), (help, dim)]),
+            None => buf.draw_line(Rect::new(list.x, help_y, list.width, 1), &[(help, dim)]),
+        }
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn on_enter(&mut self, _state: &AppState) {
+        self.cursor = None;
+        self.notice = None;
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::Modifiers;
+
+    #[test]
+    fn selection_follows_newest_until_moved() {
+        let mut pane = ConsolePane::default();
+        pane.push("one");
+        pane.push("two");
+        assert_eq!(pane.selected(), Some(1));
+        pane.move_by(-1);
+        pane.push("three");
+        assert_eq!(pane.selected(), Some(0));
+        pane.move_by(5);
+        assert_eq!(pane.cursor, None);
+        // Repeats are dropped
+        pane.push("three");
+        assert_eq!(pane.entries.len(), 3);
+    }
+
+    #[test]
+    fn clicking_selected_line_copies_it() {
+        let mut pane = ConsolePane::default();
+        let state = AppState::new();
+        pane.push("Exported /tmp/mix.wav");
+        pane.push("Server connected");
+
+        let area = Rect::new(0, 0, 80, 20);
+        let click = MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 4,
+            row: 1,
+            modifiers: Modifiers::default(),
+        };
+        pane.handle_mouse(&click, area, &state);
+        assert_eq!(pane.take_copy(), None);
+        pane.handle_mouse(&click, area, &state);
+        assert_eq!(pane.take_copy().as_deref(), Some("Exported /tmp/mix.wav"));
+    }
+}
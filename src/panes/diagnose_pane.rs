@@ -0,0 +1,106 @@
+use std::any::Any;
+
+use crate::diagnose::Check;
+use crate::state::AppState;
+use crate::ui::action_id::{ActionId, DiagnoseActionId};
+use crate::ui::layout_helpers::center_rect;
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, NavAction, Pane, Style};
+
+/// Result of the "why is this not sounding?" check for one instrument.
+/// Links are listed in signal order; the first broken one is explained.
+pub struct DiagnosePane {
+    keymap: Keymap,
+    instrument_name: String,
+    checks: Vec<Check>,
+}
+
+impl DiagnosePane {
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            instrument_name: String::new(),
+            checks: Vec::new(),
+        }
+    }
+
+    pub fn set_report(&mut self, instrument_name: &str, checks: Vec<Check>) {
+        self.instrument_name = instrument_name.to_string();
+        self.checks = checks;
+    }
+
+    fn first_failure(&self) -> Option<usize> {
+        self.checks.iter().position(|c| c.problem.is_some())
+    }
+}
+
+impl Default for DiagnosePane {
+    fn default() -> Self {
+        Self::new(Keymap::new())
+    }
+}
+
+impl Pane for DiagnosePane {
+    fn id(&self) -> &'static str {
+        "diagnose"
+    }
+
+    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, _state: &AppState) -> Action {
+        match action {
+            ActionId::Diagnose(DiagnoseActionId::Close) => Action::Nav(NavAction::PopPane),
+            _ => Action::None,
+        }
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, _state: &AppState) {
+        let rect = center_rect(area, 64, self.checks.len() as u16 + 9);
+        let border_style = Style::new().fg(Color::CYAN);
+        let title = format!(" Why is {} silent? ", self.instrument_name);
+        let inner = buf.draw_block(rect, &title, border_style, border_style);
+
+        let x = inner.x + 2;
+        let w = inner.width.saturating_sub(4);
+        let failure = self.first_failure();
+
+        // Links past the first failure are shown dimmed: they may be fine,
+        // but nothing reaches them
+        for (i, check) in self.checks.iter().enumerate() {
+            let y = inner.y + 1 + i as u16;
+            let (mark, color) = match failure {
+                Some(f) if i == f => ("\u{2717} ", Color::METER_HIGH),
+                Some(f) if i > f => ("\u{00b7} ", Color::DARK_GRAY),
+                _ => ("\u{2713} ", Color::METER_LOW),
+            };
+            let label_style = if failure.is_some_and(|f| i > f) {
+                Style::new().fg(Color::DARK_GRAY)
+            } else {
+                Style::new().fg(Color::WHITE)
+            };
+            buf.draw_line(Rect::new(x, y, w, 1), &[(mark, Style::new().fg(color).bold()), (check.label, label_style)]);
+        }
+
+        let msg_y = inner.y + 2 + self.checks.len() as u16;
+        let (msg, color) = match failure.and_then(|f| self.checks[f].problem.as_deref()) {
+            Some(problem) => (problem, Color::ORANGE),
+            None => ("Every link checks out. Is the instrument getting any notes?", Color::METER_LOW),
+        };
+        // Wrap the explanation over two lines
+        let chars: Vec<char> = msg.chars().collect();
+        for (row, chunk) in chars.chunks(w.max(1) as usize).take(2).enumerate() {
+            let line: String = chunk.iter().collect();
+            buf.draw_line(Rect::new(x, msg_y + row as u16, w, 1), &[(&line, Style::new().fg(color))]);
+        }
+
+        let help_y = rect.y + rect.height - 2;
+        if help_y < area.y + area.height {
+            buf.draw_line(Rect::new(x, help_y, w, 1), &[("Esc: close", Style::new().fg(Color::DARK_GRAY))]);
+        }
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
@@ -0,0 +1,313 @@
+use std::any::Any;
+
+use crate::export_format::{BitDepth, ExportFormat};
+use crate::export_region::{ExportRange, RegionSettings, MAX_PRE_ROLL_BARS, MAX_TAIL_SECS};
+use crate::state::AppState;
+use crate::ui::action_id::{ActionId, ExportActionId};
+use crate::ui::layout_helpers::center_rect;
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, NavAction, Pane, Style};
+
+/// What an export renders
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportTarget {
+    Master,
+    Stems,
+}
+
+/// Everything chosen in the export dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportJob {
+    pub target: ExportTarget,
+    pub range: ExportRange,
+    pub region: RegionSettings,
+    pub format: ExportFormat,
+}
+
+/// Which part of the song the Range field is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RangeKind {
+    Song,
+    Loop,
+    Bars,
+}
+
+/// Fields editable in the export dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Target,
+    Range,
+    FromBar,
+    ToBar,
+    PreRoll,
+    Tail,
+    Depth,
+    Dither,
+}
+
+const FIELDS: [Field; 8] = [
+    Field::Target,
+    Field::Range,
+    Field::FromBar,
+    Field::ToBar,
+    Field::PreRoll,
+    Field::Tail,
+    Field::Depth,
+    Field::Dither,
+];
+
+const MAX_BARS: u32 = 999;
+
+/// Export dialog: master bounce or stems, the whole song or a region,
+/// bit depth and dither. The confirmed job is taken by main.rs, which
+/// starts the render and post-processes the files once it finishes.
+pub struct ExportPane {
+    keymap: Keymap,
+    target: ExportTarget,
+    range: RangeKind,
+    from_bar: u32,
+    to_bar: u32,
+    region: RegionSettings,
+    format: ExportFormat,
+    selected: usize,
+    pending_start: Option<ExportJob>,
+}
+
+impl ExportPane {
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            target: ExportTarget::Master,
+            range: RangeKind::Song,
+            from_bar: 1,
+            to_bar: 4,
+            region: RegionSettings::default(),
+            format: ExportFormat::default(),
+            selected: 0,
+            pending_start: None,
+        }
+    }
+
+    /// Called before push with the last used settings. The range starts
+    /// on the loop when one is active.
+    pub fn open(&mut self, target: ExportTarget, region: RegionSettings, format: ExportFormat, state: &AppState) {
+        self.target = target;
+        self.region = region;
+        self.format = format;
+        self.selected = 0;
+        let piano_roll = &state.session.piano_roll;
+        self.range = if piano_roll.looping && piano_roll.loop_end > piano_roll.loop_start {
+            RangeKind::Loop
+        } else {
+            RangeKind::Song
+        };
+    }
+
+    /// Export confirmed since the last call
+    pub fn take_start(&mut self) -> Option<ExportJob> {
+        self.pending_start.take()
+    }
+
+    fn job(&self) -> ExportJob {
+        let range = match self.range {
+            RangeKind::Song => ExportRange::Song,
+            RangeKind::Loop => ExportRange::Loop,
+            RangeKind::Bars => ExportRange::Bars { from: self.from_bar, to: self.to_bar },
+        };
+        ExportJob { target: self.target, range, region: self.region, format: self.format }
+    }
+
+    fn step(value: u32, increase: bool, min: u32, max: u32) -> u32 {
+        if increase { (value + 1).min(max) } else { value.saturating_sub(1).max(min) }
+    }
+
+    fn adjust(&mut self, increase: bool) {
+        match FIELDS[self.selected] {
+            Field::Target => {
+                self.target = match self.target {
+                    ExportTarget::Master => ExportTarget::Stems,
+                    ExportTarget::Stems => ExportTarget::Master,
+                };
+            }
+            Field::Range => {
+                self.range = match (self.range, increase) {
+                    (RangeKind::Song, true) | (RangeKind::Bars, false) => RangeKind::Loop,
+                    (RangeKind::Loop, true) | (RangeKind::Song, false) => RangeKind::Bars,
+                    (RangeKind::Bars, true) | (RangeKind::Loop, false) => RangeKind::Song,
+                };
+            }
+            Field::FromBar => {
+                self.from_bar = Self::step(self.from_bar, increase, 1, MAX_BARS);
+                self.to_bar = self.to_bar.max(self.from_bar);
+            }
+            Field::ToBar => self.to_bar = Self::step(self.to_bar, increase, self.from_bar, MAX_BARS),
+            Field::PreRoll => self.region.pre_roll_bars = Self::step(self.region.pre_roll_bars, increase, 0, MAX_PRE_ROLL_BARS),
+            Field::Tail => self.region.tail_secs = Self::step(self.region.tail_secs, increase, 0, MAX_TAIL_SECS),
+            Field::Depth => {
+                let all = BitDepth::ALL;
+                let idx = all.iter().position(|d| *d == self.format.depth).unwrap_or(0);
+                let next = if increase { (idx + 1) % all.len() } else { (idx + all.len() - 1) % all.len() };
+                self.format.depth = all[next];
+            }
+            Field::Dither => self.format.dither = !self.format.dither,
+        }
+    }
+
+    fn field_label(field: Field) -> &'static str {
+        match field {
+            Field::Target => "Export",
+            Field::Range => "Range",
+            Field::FromBar => "From bar",
+            Field::ToBar => "To bar",
+            Field::PreRoll => "Pre-roll",
+            Field::Tail => "Tail",
+            Field::Depth => "Bit depth",
+            Field::Dither => "Dither",
+        }
+    }
+
+    fn field_value(&self, field: Field) -> String {
+        match field {
+            Field::Target => match self.target {
+                ExportTarget::Master => "Master bounce".into(),
+                ExportTarget::Stems => "Stems (one file per instrument)".into(),
+            },
+            Field::Range => match self.range {
+                RangeKind::Song => "Whole song".into(),
+                RangeKind::Loop => "Loop region".into(),
+                RangeKind::Bars => "Bars".into(),
+            },
+            Field::FromBar | Field::ToBar if self.range != RangeKind::Bars => "-".into(),
+            Field::FromBar => self.from_bar.to_string(),
+            Field::ToBar => self.to_bar.to_string(),
+            Field::PreRoll | Field::Tail if self.range == RangeKind::Song => "n/a for whole song".into(),
+            Field::PreRoll => match self.region.pre_roll_bars {
+                0 => "None".into(),
+                1 => "1 bar".into(),
+                n => format!("{} bars", n),
+            },
+            Field::Tail => format!("{} s", self.region.tail_secs),
+            Field::Depth => self.format.depth.name().into(),
+            Field::Dither if self.format.depth == BitDepth::Float32 => "n/a for float".into(),
+            Field::Dither => if self.format.dither { "TPDF".into() } else { "Off (truncate)".into() },
+        }
+    }
+}
+
+impl Default for ExportPane {
+    fn default() -> Self {
+        Self::new(Keymap::new())
+    }
+}
+
+impl Pane for ExportPane {
+    fn id(&self) -> &'static str {
+        "export"
+    }
+
+    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, _state: &AppState) -> Action {
+        match action {
+            ActionId::Export(ExportActionId::Prev) => self.selected = self.selected.saturating_sub(1),
+            ActionId::Export(ExportActionId::Next) => self.selected = (self.selected + 1).min(FIELDS.len() - 1),
+            ActionId::Export(ExportActionId::Decrease) => self.adjust(false),
+            ActionId::Export(ExportActionId::Increase) => self.adjust(true),
+            ActionId::Export(ExportActionId::Confirm) => {
+                self.pending_start = Some(self.job());
+                return Action::Nav(NavAction::PopPane);
+            }
+            ActionId::Export(ExportActionId::Cancel) => return Action::Nav(NavAction::PopPane),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, _state: &AppState) {
+        let rect = center_rect(area, 52, 14);
+
+        let border_style = Style::new().fg(Color::ORANGE);
+        let inner = buf.draw_block(rect, " Export ", border_style, border_style);
+
+        let label_col = inner.x + 2;
+        let value_col = label_col + 13;
+
+        for (i, field) in FIELDS.iter().enumerate() {
+            let y = inner.y + 1 + i as u16;
+            let is_selected = i == self.selected;
+
+            if is_selected {
+                for x in inner.x..inner.x + inner.width {
+                    buf.set_cell(x, y, ' ', Style::new().bg(Color::SELECTION_BG));
+                }
+                buf.set_cell(label_col, y, '>', Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG).bold());
+            }
+
+            let label_style = if is_selected {
+                Style::new().fg(Color::CYAN).bg(Color::SELECTION_BG)
+            } else {
+                Style::new().fg(Color::CYAN)
+            };
+            let val_style = if is_selected {
+                Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG)
+            } else {
+                Style::new().fg(Color::WHITE)
+            };
+            let label = format!("{:11}", Self::field_label(*field));
+            buf.draw_line(Rect::new(label_col + 2, y, 11, 1), &[(&label, label_style)]);
+            let val = self.field_value(*field);
+            buf.draw_line(Rect::new(value_col, y, inner.width.saturating_sub(15), 1), &[(&val, val_style)]);
+        }
+
+        let help_y = rect.y + rect.height - 2;
+        if help_y < area.y + area.height {
+            buf.draw_line(
+                Rect::new(inner.x + 2, help_y, inner.width.saturating_sub(2), 1),
+                &[("Left/Right: adjust | Enter: queue | Esc: cancel", Style::new().fg(Color::DARK_GRAY))],
+            );
+        }
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::{KeyCode, Modifiers};
+
+    #[test]
+    fn confirm_takes_the_job() {
+        let mut pane = ExportPane::default();
+        let state = AppState::new();
+        let event = InputEvent::new(KeyCode::Enter, Modifiers::default());
+        pane.open(ExportTarget::Stems, RegionSettings::default(), ExportFormat::default(), &state);
+        pane.selected = FIELDS.iter().position(|f| *f == Field::Depth).unwrap();
+        pane.handle_action(ActionId::Export(ExportActionId::Decrease), &event, &state);
+
+        let action = pane.handle_action(ActionId::Export(ExportActionId::Confirm), &event, &state);
+        assert!(matches!(action, Action::Nav(NavAction::PopPane)));
+        let job = pane.take_start().unwrap();
+        assert_eq!((job.target, job.range), (ExportTarget::Stems, ExportRange::Song));
+        assert_eq!(job.format, ExportFormat { depth: BitDepth::Int16, dither: true });
+        assert_eq!(pane.take_start(), None);
+    }
+
+    #[test]
+    fn bar_range_stays_ordered() {
+        let mut pane = ExportPane::default();
+        let state = AppState::new();
+        let event = InputEvent::new(KeyCode::Right, Modifiers::default());
+        pane.open(ExportTarget::Master, RegionSettings::default(), ExportFormat::default(), &state);
+        pane.selected = 1;
+        pane.handle_action(ActionId::Export(ExportActionId::Decrease), &event, &state);
+        pane.selected = 2;
+        for _ in 0..6 {
+            pane.handle_action(ActionId::Export(ExportActionId::Increase), &event, &state);
+        }
+        assert_eq!(pane.job().range, ExportRange::Bars { from: 7, to: 7 });
+    }
+}
@@ -2,14 +2,16 @@ use std::any::Any;
 use std::fs;
 use std::path::PathBuf;
 
+use crate::favorites::{self, Favorites};
 use crate::state::AppState;
 use crate::ui::action_id::{ActionId, FileBrowserActionId};
 use crate::ui::layout_helpers::center_rect;
 use crate::state::VstPluginKind;
 use crate::ui::{
     Rect, RenderBuf, Action, ChopperAction, Color, FileSelectAction, InputEvent, InstrumentAction, Keymap, MouseEvent,
-    MouseEventKind, MouseButton, NavAction, Pane, SequencerAction, SessionAction, Style,
+    MouseEventKind, MouseButton, NavAction, Pane, PianoRollAction, SequencerAction, SessionAction, Style,
 };
+use crate::usage::Usage;
 
 struct DirEntry {
     name: String,
@@ -28,6 +30,14 @@ pub struct FileBrowserPane {
     on_select_action: FileSelectAction,
     scroll_offset: usize,
     show_hidden: bool,
+    /// Start directories from preferences
+    samples_dir: Option<PathBuf>,
+    projects_dir: Option<PathBuf>,
+    impulse_responses_dir: Option<PathBuf>,
+    favorites: Favorites,
+    /// Hide files that have no stars
+    favorites_only: bool,
+    usage: Usage,
 }
 
 impl FileBrowserPane {
@@ -45,11 +55,57 @@ impl FileBrowserPane {
             on_select_action: FileSelectAction::ImportCustomSynthDef,
             scroll_offset: 0,
             show_hidden: false,
+            samples_dir: None,
+            projects_dir: None,
+            impulse_responses_dir: None,
+            favorites: Favorites::default(),
+            favorites_only: false,
+            usage: Usage::default(),
         };
         pane.refresh_entries();
         pane
     }
 
+    pub fn set_default_dirs(&mut self, samples: Option<PathBuf>, projects: Option<PathBuf>, impulse_responses: Option<PathBuf>) {
+        self.samples_dir = samples;
+        self.projects_dir = projects;
+        self.impulse_responses_dir = impulse_responses;
+    }
+
+    /// Whether the browser is choosing a sample, so the library can stand in
+    fn loads_sample(&self) -> bool {
+        matches!(
+            self.on_select_action,
+            FileSelectAction::LoadDrumSample(_) | FileSelectAction::AddRoundRobinSample(_)
+                | FileSelectAction::LoadChopperSample | FileSelectAction::LoadPitchedSample(_)
+        )
+    }
+
+    /// Action for choosing the file at `path`; also used for picks made in
+    /// the sample library. Sample picks are counted in usage.
+    pub fn select_file(&mut self, path: PathBuf) -> Action {
+        if self.loads_sample() {
+            self.usage.record(&favorites::file_key(&path));
+            self.usage.save();
+        }
+        match self.on_select_action {
+            FileSelectAction::ImportCustomSynthDef => Action::Session(SessionAction::ImportCustomSynthDef(path)),
+            FileSelectAction::ImportVstInstrument => Action::Session(SessionAction::ImportVstPlugin(path, VstPluginKind::Instrument)),
+            FileSelectAction::ImportVstEffect => Action::Session(SessionAction::ImportVstPlugin(path, VstPluginKind::Effect)),
+            FileSelectAction::LoadDrumSample(pad_idx) => Action::Sequencer(SequencerAction::LoadSampleResult(pad_idx, path)),
+            FileSelectAction::AddRoundRobinSample(pad_idx) => Action::Sequencer(SequencerAction::AddRoundRobinSampleResult(pad_idx, path)),
+            FileSelectAction::LoadChopperSample => Action::Chopper(ChopperAction::LoadSampleResult(path)),
+            FileSelectAction::LoadPitchedSample(id) => Action::Instrument(InstrumentAction::LoadSampleResult(id, path)),
+            FileSelectAction::LoadImpulseResponse(id, fx_idx) => Action::Instrument(InstrumentAction::LoadIRResult(id, fx_idx, path)),
+            FileSelectAction::ImportProject => Action::Session(SessionAction::LoadFrom(path)),
+            FileSelectAction::RunScript => Action::Session(SessionAction::RunScript(path)),
+            FileSelectAction::DetectTempo => Action::Session(SessionAction::DetectTempo(path)),
+            FileSelectAction::AudioToMidi(track, start_tick) => Action::PianoRoll(PianoRollAction::AudioToMidi { track, start_tick, path }),
+            FileSelectAction::ImportDrumPattern => Action::Sequencer(SequencerAction::ImportPattern(path)),
+            FileSelectAction::ImportMidiFile => Action::Session(SessionAction::ImportMidiFile(path)),
+        }
+    }
+
     /// Open for a specific action with optional start directory
     pub fn open_for(&mut self, action: FileSelectAction, start_dir: Option<PathBuf>) {
         self.on_select_action = action.clone();
@@ -60,17 +116,24 @@ impl FileBrowserPane {
                 self.bundle_extensions = Some(vec!["vst3".to_string(), "vst".to_string()]);
                 Some(vec!["vst3".to_string(), "vst".to_string()])
             }
-            FileSelectAction::LoadDrumSample(_) | FileSelectAction::LoadChopperSample | FileSelectAction::LoadPitchedSample(_) | FileSelectAction::LoadImpulseResponse(_, _) => {
+            FileSelectAction::LoadDrumSample(_) | FileSelectAction::AddRoundRobinSample(_) | FileSelectAction::LoadChopperSample | FileSelectAction::LoadPitchedSample(_) | FileSelectAction::LoadImpulseResponse(_, _) | FileSelectAction::AudioToMidi(_, _) | FileSelectAction::DetectTempo => {
                 Some(vec!["wav".to_string(), "aiff".to_string(), "aif".to_string()])
             }
-            FileSelectAction::ImportProject => Some(vec!["sqlite".to_string()]),
+            FileSelectAction::ImportProject => Some(vec!["sqlite".to_string(), "json".to_string()]),
+            FileSelectAction::RunScript => Some(vec!["rhai".to_string()]),
+            FileSelectAction::ImportDrumPattern => Some(vec!["h2song".to_string(), "mmp".to_string()]),
+            FileSelectAction::ImportMidiFile => Some(vec!["mid".to_string(), "midi".to_string()]),
         };
         let default_dir = match &self.on_select_action {
             FileSelectAction::ImportVstInstrument | FileSelectAction::ImportVstEffect => {
                 let vst3_dir = PathBuf::from("/Library/Audio/Plug-Ins/VST3");
                 if vst3_dir.exists() { Some(vst3_dir) } else { None }
             }
-            FileSelectAction::ImportProject => dirs::home_dir(),
+            FileSelectAction::ImportProject => self.projects_dir.clone().or_else(dirs::home_dir),
+            FileSelectAction::LoadDrumSample(_) | FileSelectAction::AddRoundRobinSample(_) | FileSelectAction::LoadChopperSample | FileSelectAction::LoadPitchedSample(_) => {
+                self.samples_dir.clone()
+            }
+            FileSelectAction::LoadImpulseResponse(_, _) => self.impulse_responses_dir.clone(),
             _ => None,
         };
         self.current_dir = start_dir.or(default_dir).unwrap_or_else(|| {
@@ -124,6 +187,10 @@ impl FileBrowserPane {
                     }
                 }
 
+                if !is_dir && self.favorites_only && !self.favorites.is_favorite(&favorites::file_key(&path)) {
+                    continue;
+                }
+
                 let entry = DirEntry { name, path, is_dir };
                 if is_dir {
                     dirs.push(entry);
@@ -146,6 +213,16 @@ impl FileBrowserPane {
         }
     }
 
+    /// Change the rating of the selected file; directories aren't rated
+    fn rate_selected(&mut self, change: impl FnOnce(&mut Favorites, &str)) {
+        let Some(entry) = self.entries.get(self.selected).filter(|e| !e.is_dir) else { return };
+        change(&mut self.favorites, &favorites::file_key(&entry.path));
+        self.favorites.save();
+        if self.favorites_only {
+            self.refresh_entries();
+        }
+    }
+
 }
 
 impl Default for FileBrowserPane {
@@ -170,33 +247,7 @@ impl Pane for FileBrowserPane {
                         self.refresh_entries();
                         Action::None
                     } else {
-                        // File selected
-                        match self.on_select_action {
-                            FileSelectAction::ImportCustomSynthDef => {
-                                Action::Session(SessionAction::ImportCustomSynthDef(entry.path.clone()))
-                            }
-                            FileSelectAction::ImportVstInstrument => {
-                                Action::Session(SessionAction::ImportVstPlugin(entry.path.clone(), VstPluginKind::Instrument))
-                            }
-                            FileSelectAction::ImportVstEffect => {
-                                Action::Session(SessionAction::ImportVstPlugin(entry.path.clone(), VstPluginKind::Effect))
-                            }
-                            FileSelectAction::LoadDrumSample(pad_idx) => {
-                                Action::Sequencer(SequencerAction::LoadSampleResult(pad_idx, entry.path.clone()))
-                            }
-                            FileSelectAction::LoadChopperSample => {
-                                Action::Chopper(ChopperAction::LoadSampleResult(entry.path.clone()))
-                            }
-                            FileSelectAction::LoadPitchedSample(id) => {
-                                Action::Instrument(InstrumentAction::LoadSampleResult(id, entry.path.clone()))
-                            }
-                            FileSelectAction::LoadImpulseResponse(id, fx_idx) => {
-                                Action::Instrument(InstrumentAction::LoadIRResult(id, fx_idx, entry.path.clone()))
-                            }
-                            FileSelectAction::ImportProject => {
-                                Action::Session(SessionAction::LoadFrom(entry.path.clone()))
-                            }
-                        }
+                        self.select_file(entry.path.clone())
                     }
                 } else {
                     Action::None
@@ -249,6 +300,28 @@ impl Pane for FileBrowserPane {
                 self.refresh_entries();
                 Action::None
             }
+            ActionId::FileBrowser(FileBrowserActionId::Library) if self.loads_sample() => {
+                Action::Nav(NavAction::PushPane("sample_library"))
+            }
+            ActionId::FileBrowser(FileBrowserActionId::ToggleStar) => {
+                self.rate_selected(|favs, key| favs.toggle(key));
+                Action::None
+            }
+            ActionId::FileBrowser(FileBrowserActionId::RateUp) => {
+                self.rate_selected(|favs, key| favs.adjust(key, 1));
+                Action::None
+            }
+            ActionId::FileBrowser(FileBrowserActionId::RateDown) => {
+                self.rate_selected(|favs, key| favs.adjust(key, -1));
+                Action::None
+            }
+            ActionId::FileBrowser(FileBrowserActionId::FavoritesOnly) => {
+                self.favorites_only = !self.favorites_only;
+                self.selected = 0;
+                self.scroll_offset = 0;
+                self.refresh_entries();
+                Action::None
+            }
             _ => Action::None,
         }
     }
@@ -262,11 +335,18 @@ impl Pane for FileBrowserPane {
             FileSelectAction::ImportVstEffect => " Import VST Effect ",
             FileSelectAction::LoadDrumSample(_) | FileSelectAction::LoadChopperSample => " Load Sample ",
             FileSelectAction::LoadPitchedSample(_) => " Load Sample ",
+            FileSelectAction::AddRoundRobinSample(_) => " Add Round-Robin Sample ",
             FileSelectAction::LoadImpulseResponse(_, _) => " Load Impulse Response ",
             FileSelectAction::ImportProject => " Import Project ",
+            FileSelectAction::RunScript => " Run Script ",
+            FileSelectAction::DetectTempo => " Detect Tempo ",
+            FileSelectAction::AudioToMidi(_, _) => " Audio to MIDI ",
+            FileSelectAction::ImportDrumPattern => " Import Drum Pattern ",
+            FileSelectAction::ImportMidiFile => " Import MIDI File ",
         };
+        let title = if self.favorites_only { format!("{}(favorites) ", title) } else { title.to_string() };
         let border_style = Style::new().fg(Color::PURPLE);
-        let inner = buf.draw_block(rect, title, border_style, border_style);
+        let inner = buf.draw_block(rect, &title, border_style, border_style);
 
         let content_x = inner.x + 1;
         let content_y = inner.y + 1;
@@ -307,7 +387,11 @@ impl Pane for FileBrowserPane {
                 .as_ref()
                 .map(|exts| exts.join("/"))
                 .unwrap_or_default();
-            let empty_msg = format!("(no .{} files found)", ext_label);
+            let empty_msg = if self.favorites_only {
+                format!("(no starred .{} files here; f: show all)", ext_label)
+            } else {
+                format!("(no .{} files found)", ext_label)
+            };
             buf.draw_line(
                 Rect::new(content_x, list_y, inner.width.saturating_sub(2), 1),
                 &[(&empty_msg, Style::new().fg(Color::DARK_GRAY))],
@@ -340,7 +424,7 @@ impl Pane for FileBrowserPane {
                     Style::new().fg(icon_color)
                 };
 
-                let max_name_width = inner.width.saturating_sub(6) as usize;
+                let max_name_width = inner.width.saturating_sub(12) as usize;
                 let display_name = if entry.name.len() > max_name_width {
                     format!("{}...", &entry.name[..max_name_width - 3])
                 } else {
@@ -354,10 +438,16 @@ impl Pane for FileBrowserPane {
                     Style::new().fg(name_color)
                 };
 
+                let rating = if entry.is_dir { 0 } else { self.favorites.rating(&favorites::file_key(&entry.path)) };
+                let star_style = if is_selected {
+                    Style::new().fg(Color::GOLD).bg(Color::SELECTION_BG)
+                } else {
+                    Style::new().fg(Color::GOLD)
+                };
                 let name_display = format!(" {}", display_name);
                 buf.draw_line(
                     Rect::new(content_x + 2, y, inner.width.saturating_sub(4), 1),
-                    &[(icon, icon_style), (&name_display, name_style)],
+                    &[(&favorites::stars(rating), star_style), (icon, icon_style), (&name_display, name_style)],
                 );
             }
 
@@ -378,11 +468,16 @@ impl Pane for FileBrowserPane {
         }
 
         // Help text
+        let help = if self.loads_sample() {
+            "Enter: select | Bksp: up | ~: home | &: hidden | /: library | *+-: stars | f: favorites"
+        } else {
+            "Enter: select | Bksp: up | ~: home | &: hidden | *+-: stars | f: favorites | Esc: cancel"
+        };
         let help_y = rect.y + rect.height - 2;
         if help_y < area.y + area.height {
             buf.draw_line(
                 Rect::new(content_x, help_y, inner.width.saturating_sub(2), 1),
-                &[("Enter: select | Backspace: parent | ~: home | &: hidden | Esc: cancel", Style::new().fg(Color::DARK_GRAY))],
+                &[(help, Style::new().fg(Color::DARK_GRAY))],
             );
         }
     }
@@ -417,54 +512,7 @@ impl Pane for FileBrowserPane {
                                 self.scroll_offset = 0;
                                 self.refresh_entries();
                             } else {
-                                match self.on_select_action {
-                                    FileSelectAction::ImportCustomSynthDef => {
-                                        return Action::Session(SessionAction::ImportCustomSynthDef(
-                                            self.entries[clicked_idx].path.clone(),
-                                        ));
-                                    }
-                                    FileSelectAction::LoadDrumSample(pad_idx) => {
-                                        return Action::Sequencer(SequencerAction::LoadSampleResult(
-                                            pad_idx,
-                                            self.entries[clicked_idx].path.clone(),
-                                        ));
-                                    }
-                                    FileSelectAction::LoadChopperSample => {
-                                        return Action::Chopper(ChopperAction::LoadSampleResult(
-                                            self.entries[clicked_idx].path.clone(),
-                                        ));
-                                    }
-                                    FileSelectAction::LoadPitchedSample(id) => {
-                                        return Action::Instrument(InstrumentAction::LoadSampleResult(
-                                            id,
-                                            self.entries[clicked_idx].path.clone(),
-                                        ));
-                                    }
-                                    FileSelectAction::ImportVstInstrument => {
-                                        return Action::Session(SessionAction::ImportVstPlugin(
-                                            self.entries[clicked_idx].path.clone(),
-                                            VstPluginKind::Instrument,
-                                        ));
-                                    }
-                                    FileSelectAction::ImportVstEffect => {
-                                        return Action::Session(SessionAction::ImportVstPlugin(
-                                            self.entries[clicked_idx].path.clone(),
-                                            VstPluginKind::Effect,
-                                        ));
-                                    }
-                                    FileSelectAction::LoadImpulseResponse(id, fx_idx) => {
-                                        return Action::Instrument(InstrumentAction::LoadIRResult(
-                                            id,
-                                            fx_idx,
-                                            self.entries[clicked_idx].path.clone(),
-                                        ));
-                                    }
-                                    FileSelectAction::ImportProject => {
-                                        return Action::Session(SessionAction::LoadFrom(
-                                            self.entries[clicked_idx].path.clone(),
-                                        ));
-                                    }
-                                }
+                                return self.select_file(self.entries[clicked_idx].path.clone());
                             }
                         } else {
                             self.selected = clicked_idx;
@@ -498,6 +546,8 @@ impl Pane for FileBrowserPane {
     }
 
     fn on_enter(&mut self, _state: &AppState) {
+        self.favorites = Favorites::load();
+        self.usage = Usage::load();
         self.refresh_entries();
     }
 }
@@ -546,4 +596,24 @@ mod tests {
 
         std::fs::remove_dir_all(&dir).ok();
     }
+
+    #[test]
+    fn favorites_only_hides_unstarred_files() {
+        let dir = make_temp_dir();
+        std::fs::create_dir(dir.join("sub")).unwrap();
+        std::fs::write(dir.join("a.scd"), "a").unwrap();
+        std::fs::write(dir.join("b.scd"), "b").unwrap();
+
+        let mut pane = FileBrowserPane::new(Keymap::new());
+        pane.open_for(FileSelectAction::ImportCustomSynthDef, Some(dir.clone()));
+        pane.favorites.toggle(&favorites::file_key(&dir.join("b.scd")));
+        let state = AppState::new();
+
+        use crate::ui::action_id::{ActionId, FileBrowserActionId};
+        pane.handle_action(ActionId::FileBrowser(FileBrowserActionId::FavoritesOnly), &dummy_event(), &state);
+        let names: Vec<&str> = pane.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["sub", "b.scd"]);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
 }
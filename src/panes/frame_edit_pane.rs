@@ -4,8 +4,10 @@ use crate::state::music::{Key, Scale};
 use crate::state::{AppState, MusicalSettings};
 use crate::ui::action_id::{ActionId, FrameEditActionId, ModeActionId};
 use crate::ui::layout_helpers::center_rect;
-use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, Pane, SessionAction, Style};
+use crate::tempo_detect::TempoEstimate;
+use crate::ui::{Rect, RenderBuf, Action, Color, FileSelectAction, InputEvent, Keymap, Pane, SessionAction, Style};
 use crate::ui::widgets::TextInput;
+use crate::value_entry;
 
 /// Fields editable in the frame editor
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -16,9 +18,14 @@ enum Field {
     Key,
     Scale,
     Snap,
+    HumanizeVelocity,
+    HumanizeTiming,
 }
 
-const FIELDS: [Field; 6] = [Field::Bpm, Field::TimeSig, Field::Tuning, Field::Key, Field::Scale, Field::Snap];
+const FIELDS: [Field; 8] = [
+    Field::Bpm, Field::TimeSig, Field::Tuning, Field::Key, Field::Scale, Field::Snap,
+    Field::HumanizeVelocity, Field::HumanizeTiming,
+];
 
 pub struct FrameEditPane {
     keymap: Keymap,
@@ -27,6 +34,8 @@ pub struct FrameEditPane {
     selected: usize,
     editing: bool,
     edit_input: TextInput,
+    /// Last tempo analysis, shown until the pane is re-entered
+    detected_tempo: Option<TempoEstimate>,
 }
 
 impl FrameEditPane {
@@ -38,6 +47,7 @@ impl FrameEditPane {
             selected: 0,
             editing: false,
             edit_input: TextInput::new(""),
+            detected_tempo: None,
         }
     }
 
@@ -48,6 +58,15 @@ impl FrameEditPane {
         self.original_settings = self.settings.clone();
         self.selected = 0;
         self.editing = false;
+        self.detected_tempo = None;
+    }
+
+    /// Load a tempo estimate into the BPM field (called after analysis)
+    pub fn apply_tempo_estimate(&mut self, estimate: TempoEstimate) -> Action {
+        self.settings.bpm = (estimate.bpm.round() as u16).clamp(20, 300);
+        self.selected = 0;
+        self.detected_tempo = Some(estimate);
+        Action::Session(SessionAction::UpdateSessionLive(self.settings.clone()))
     }
 
     fn current_field(&self) -> Field {
@@ -99,6 +118,14 @@ impl FrameEditPane {
             Field::Key => self.cycle_key(increase),
             Field::Scale => self.cycle_scale(increase),
             Field::Snap => self.settings.snap = !self.settings.snap,
+            Field::HumanizeVelocity => {
+                let delta = if increase { 0.05 } else { -0.05 };
+                self.settings.humanize_velocity = (self.settings.humanize_velocity + delta).clamp(0.0, 1.0);
+            }
+            Field::HumanizeTiming => {
+                let delta = if increase { 0.05 } else { -0.05 };
+                self.settings.humanize_timing = (self.settings.humanize_timing + delta).clamp(0.0, 1.0);
+            }
         }
     }
 
@@ -110,6 +137,8 @@ impl FrameEditPane {
             Field::Key => "Key",
             Field::Scale => "Scale",
             Field::Snap => "Snap",
+            Field::HumanizeVelocity => "Humanize Vel",
+            Field::HumanizeTiming => "Humanize Time",
         }
     }
 
@@ -121,12 +150,28 @@ impl FrameEditPane {
             Field::Key => self.settings.key.name().to_string(),
             Field::Scale => self.settings.scale.name().to_string(),
             Field::Snap => if self.settings.snap { "ON".into() } else { "OFF".into() },
+            Field::HumanizeVelocity => format!("{:.0}%", self.settings.humanize_velocity * 100.0),
+            Field::HumanizeTiming => format!("{:.0}%", self.settings.humanize_timing * 100.0),
         }
     }
 
     pub fn is_editing(&self) -> bool {
         self.editing
     }
+
+    /// Start typing a value for a numeric field; None for other fields
+    fn start_typing(&mut self) -> Option<Action> {
+        let val = match self.current_field() {
+            Field::Bpm => format!("{}", self.settings.bpm),
+            Field::Tuning => format!("{:.1}", self.settings.tuning_a4),
+            _ => return None,
+        };
+        self.edit_input.set_value(&val);
+        self.edit_input.select_all();
+        self.edit_input.set_focused(true);
+        self.editing = true;
+        Some(Action::PushLayer("text_edit"))
+    }
 }
 
 impl Default for FrameEditPane {
@@ -147,12 +192,12 @@ impl Pane for FrameEditPane {
                 let text = self.edit_input.value().to_string();
                 match self.current_field() {
                     Field::Bpm => {
-                        if let Ok(v) = text.parse::<u16>() {
-                            self.settings.bpm = v.clamp(20, 300);
+                        if let Some(v) = value_entry::parse(&text) {
+                            self.settings.bpm = v.round().clamp(20.0, 300.0) as u16;
                         }
                     }
                     Field::Tuning => {
-                        if let Ok(v) = text.parse::<f32>() {
+                        if let Some(v) = value_entry::parse(&text) {
                             self.settings.tuning_a4 = v.clamp(400.0, 480.0);
                         }
                     }
@@ -190,26 +235,31 @@ impl Pane for FrameEditPane {
                 Action::Session(SessionAction::UpdateSessionLive(self.settings.clone()))
             }
             ActionId::FrameEdit(FrameEditActionId::Confirm) => {
-                let field = self.current_field();
-                if matches!(field, Field::Bpm | Field::Tuning) {
-                    let val = match field {
-                        Field::Bpm => format!("{}", self.settings.bpm),
-                        Field::Tuning => format!("{:.1}", self.settings.tuning_a4),
-                        _ => unreachable!(),
-                    };
-                    self.edit_input.set_value(&val);
-                    self.edit_input.select_all();
-                    self.edit_input.set_focused(true);
-                    self.editing = true;
-                    Action::PushLayer("text_edit")
-                } else {
-                    Action::Session(SessionAction::UpdateSession(self.settings.clone()))
-                }
+                self.start_typing()
+                    .unwrap_or_else(|| Action::Session(SessionAction::UpdateSession(self.settings.clone())))
             }
+            ActionId::FrameEdit(FrameEditActionId::TypeValue) => self.start_typing().unwrap_or(Action::None),
             ActionId::FrameEdit(FrameEditActionId::Cancel) => {
                 self.settings = self.original_settings.clone();
                 Action::Session(SessionAction::UpdateSession(self.original_settings.clone()))
             }
+            // Persist to config so new projects start from these settings
+            ActionId::FrameEdit(FrameEditActionId::SaveDefaults) => {
+                Action::Session(SessionAction::SaveMusicalDefaults(self.settings.clone()))
+            }
+            ActionId::FrameEdit(FrameEditActionId::DetectTempo) => {
+                Action::Session(SessionAction::OpenFileBrowser(FileSelectAction::DetectTempo))
+            }
+            ActionId::FrameEdit(FrameEditActionId::ImportMidi) => {
+                Action::Session(SessionAction::OpenFileBrowser(FileSelectAction::ImportMidiFile))
+            }
+            ActionId::FrameEdit(FrameEditActionId::AlignTempo) => match self.detected_tempo {
+                Some(estimate) => Action::Session(SessionAction::AlignGridToAudio {
+                    bpm: estimate.bpm,
+                    downbeat_secs: estimate.first_downbeat,
+                }),
+                None => Action::None,
+            },
             _ => Action::None,
         }
     }
@@ -222,7 +272,7 @@ impl Pane for FrameEditPane {
     }
 
     fn render(&mut self, area: Rect, buf: &mut RenderBuf, _state: &AppState) {
-        let rect = center_rect(area, 50, 13);
+        let rect = center_rect(area, 64, 15);
 
         let border_style = Style::new().fg(Color::CYAN);
         let inner = buf.draw_block(rect, " Session ", border_style, border_style);
@@ -276,13 +326,22 @@ impl Pane for FrameEditPane {
             }
         }
 
+        // Tempo detection result
+        if let Some(estimate) = self.detected_tempo {
+            let y = inner.y + 1 + FIELDS.len() as u16 + 1;
+            if y < inner.y + inner.height {
+                let text = format!("Detected {:.1} BPM, downbeat at {:.2}s (a: align)", estimate.bpm, estimate.first_downbeat);
+                buf.draw_line(Rect::new(label_col + 2, y, inner.width.saturating_sub(4), 1), &[(&text, Style::new().fg(Color::GOLD))]);
+            }
+        }
+
         // Help
         let help_y = rect.y + rect.height - 2;
         if help_y < area.y + area.height {
             let help = if self.editing {
                 "Enter: confirm | Esc: cancel"
             } else {
-                "Left/Right: adjust | Enter: type/confirm | Esc: cancel"
+                "Left/Right: adjust | s: save default | d: detect | Esc: cancel"
             };
             buf.draw_line(
                 Rect::new(inner.x + 2, help_y, inner.width.saturating_sub(2), 1),
@@ -398,4 +457,40 @@ mod tests {
             _ => panic!("Expected UpdateSession on text cancel"),
         }
     }
+
+    #[test]
+    fn tempo_estimate_fills_bpm_and_enables_align() {
+        use crate::ui::action_id::{ActionId, FrameEditActionId};
+        let mut pane = FrameEditPane::new(Keymap::new());
+        let state = AppState::new();
+        pane.set_settings(MusicalSettings::default());
+
+        let align = pane.handle_action(ActionId::FrameEdit(FrameEditActionId::AlignTempo), &dummy_event(), &state);
+        assert!(matches!(align, Action::None));
+
+        pane.apply_tempo_estimate(TempoEstimate { bpm: 127.6, first_beat: 0.1, first_downbeat: 0.6 });
+        assert_eq!(pane.settings.bpm, 128);
+        let align = pane.handle_action(ActionId::FrameEdit(FrameEditActionId::AlignTempo), &dummy_event(), &state);
+        assert!(matches!(align, Action::Session(SessionAction::AlignGridToAudio { .. })));
+    }
+
+    #[test]
+    fn humanize_clamps_and_saves_as_default() {
+        use crate::ui::action_id::{ActionId, FrameEditActionId};
+        let mut pane = FrameEditPane::new(Keymap::new());
+        let state = AppState::new();
+        pane.set_settings(MusicalSettings::default());
+        pane.selected = FIELDS.iter().position(|f| *f == Field::HumanizeTiming).unwrap();
+
+        for _ in 0..30 {
+            pane.handle_action(ActionId::FrameEdit(FrameEditActionId::Increase), &dummy_event(), &state);
+        }
+        assert_eq!(pane.settings.humanize_timing, 1.0);
+
+        let action = pane.handle_action(ActionId::FrameEdit(FrameEditActionId::SaveDefaults), &dummy_event(), &state);
+        match action {
+            Action::Session(SessionAction::SaveMusicalDefaults(saved)) => assert_eq!(saved.humanize_timing, 1.0),
+            _ => panic!("Expected SaveMusicalDefaults"),
+        }
+    }
 }
@@ -1,10 +1,120 @@
 use std::any::Any;
 
 use crate::state::AppState;
-use crate::ui::action_id::{ActionId, HelpActionId};
+use crate::ui::action_id::{ActionId, HelpActionId, ModeActionId};
 use crate::ui::layout_helpers::center_rect;
+use crate::ui::widgets::TextInput;
 use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, MouseEvent, MouseEventKind, MouseButton, NavAction, Pane, Style};
 
+/// A short walkthrough of a common task
+struct Guide {
+    title: &'static str,
+    /// Panes the guide is most useful from; listed first there
+    panes: &'static [&'static str],
+    steps: &'static [&'static str],
+}
+
+const GUIDES: &[Guide] = &[
+    Guide {
+        title: "Make a first sound",
+        panes: &["instrument", "add"],
+        steps: &[
+            "F1 for instruments, then a (or Ctrl+n) to add one",
+            "Pick a source such as Saw and press Enter",
+            "Press / for the piano keyboard and play with the letter keys",
+            "Press / again to leave piano mode",
+        ],
+    },
+    Guide {
+        title: "Write and play back a melody",
+        panes: &["piano_roll"],
+        steps: &[
+            "Select an instrument, then F2 for the piano roll",
+            "Move with the arrow keys, Enter places or removes a note",
+            "Alt+Left/Right shortens or lengthens the note under the cursor",
+            "[ and ] set the loop, l toggles looping",
+            "Space plays and stops",
+        ],
+    },
+    Guide {
+        title: "Copy a phrase to another track",
+        panes: &["piano_roll"],
+        steps: &[
+            "Shift+arrows select notes in the piano roll, Ctrl+c copies them",
+            "Pick another instrument with 1-9 to move to its track",
+            "Ctrl+v pastes at the cursor, keeping timing and intervals",
+            "P pastes it several times back to back, to fill out a section",
+            "Ctrl+V pastes notes copied in another imbolc instance",
+        ],
+    },
+    Guide {
+        title: "Enter notes tracker-style",
+        panes: &["tracker", "piano_roll"],
+        steps: &[
+            "V in the piano roll switches to the tracker view",
+            "Rows are steps; z-m and q-p enter notes, [ and ] set the octave",
+            "1 ends the sounding note, Delete clears the row",
+            "Hex digits in the volume and length columns edit the note",
+            "+/- change lines per beat, V or Escape returns to the piano roll",
+        ],
+    },
+    Guide {
+        title: "Program a drum pattern",
+        panes: &["sequencer"],
+        steps: &[
+            "Add a Kit instrument, then F2 for the step sequencer",
+            "s loads a sample onto the selected pad",
+            "Enter toggles the step under the cursor",
+            "Space plays; * arms pad recording while it runs",
+        ],
+    },
+    Guide {
+        title: "Set up a send reverb",
+        panes: &["mixer", "instrument_edit"],
+        steps: &[
+            "F4 for the mixer, move right to a bus and press Enter",
+            "a adds an effect; choose a reverb and set its mix to 100%",
+            "Escape, then select the instrument to send from",
+            "t picks the bus as send target, g enables the send",
+            "Up/Down (or =) set how much is sent",
+        ],
+    },
+    Guide {
+        title: "Shape a sound",
+        panes: &["instrument_edit", "instrument"],
+        steps: &[
+            "Select an instrument and press Enter to edit it",
+            "Up/Down move between parameters, Left/Right adjust",
+            "= types an exact value, with units such as 1.2k or -6dB",
+            "The line under the editor explains the selected parameter",
+        ],
+    },
+    Guide {
+        title: "Automate a parameter",
+        panes: &["automation", "mixer"],
+        steps: &[
+            "F7 for automation and add a lane for the target",
+            "Place points on the timeline; the curve shows the value",
+            "Or arm the lane and move the control while playing",
+        ],
+    },
+    Guide {
+        title: "Save and reopen work",
+        panes: &[],
+        steps: &[
+            "Ctrl+s saves, Ctrl+S saves under a new name",
+            "Ctrl+o opens the project browser",
+            "Ctrl+z and Ctrl+Z undo and redo",
+        ],
+    },
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HelpTab {
+    Keys,
+    Guides,
+}
+
 pub struct HelpPane {
     keymap: Keymap,
     /// The keymap to display (from another pane)
@@ -15,6 +125,10 @@ pub struct HelpPane {
     title: String,
     /// Scroll offset for long keymaps
     scroll: usize,
+    tab: HelpTab,
+    /// Filter for keys and guides
+    search: TextInput,
+    searching: bool,
 }
 
 impl HelpPane {
@@ -25,6 +139,9 @@ impl HelpPane {
             return_to: "instrument",
             title: String::new(),
             scroll: 0,
+            tab: HelpTab::Keys,
+            search: TextInput::new(""),
+            searching: false,
         }
     }
 
@@ -33,6 +150,8 @@ impl HelpPane {
         self.return_to = pane_id;
         self.title = pane_title.to_string();
         self.scroll = 0;
+        self.tab = HelpTab::Keys;
+        self.search.set_value("");
 
         // Convert keymap bindings to display format
         self.display_keymap = keymap
@@ -41,6 +160,39 @@ impl HelpPane {
             .map(|b| (b.pattern.display(), b.description.to_string()))
             .collect();
     }
+
+    pub fn is_editing(&self) -> bool {
+        self.searching
+    }
+
+    /// Lines for the current tab that match the search, as (key, text).
+    /// Guide titles have empty text; guide steps have an empty key.
+    fn lines(&self) -> Vec<(String, String)> {
+        let query = self.search.value().to_lowercase();
+        let matches = |s: &str| s.to_lowercase().contains(&query);
+        match self.tab {
+            HelpTab::Keys => self.display_keymap.iter()
+                .filter(|(key, desc)| matches(key) || matches(desc))
+                .cloned()
+                .collect(),
+            HelpTab::Guides => {
+                let (mut here, elsewhere): (Vec<&Guide>, Vec<&Guide>) =
+                    GUIDES.iter().partition(|g| g.panes.contains(&self.return_to));
+                here.extend(elsewhere);
+                let mut lines = Vec::new();
+                for guide in here {
+                    if !matches(guide.title) && !guide.steps.iter().any(|s| matches(s)) {
+                        continue;
+                    }
+                    lines.push((guide.title.to_string(), String::new()));
+                    for (i, step) in guide.steps.iter().enumerate() {
+                        lines.push((String::new(), format!("{}. {}", i + 1, step)));
+                    }
+                }
+                lines
+            }
+        }
+    }
 }
 
 impl Default for HelpPane {
@@ -56,6 +208,17 @@ impl Pane for HelpPane {
 
     fn handle_action(&mut self, action: ActionId, _event: &InputEvent, _state: &AppState) -> Action {
         match action {
+            ActionId::Mode(ModeActionId::TextConfirm) => {
+                self.searching = false;
+                self.search.set_focused(false);
+                Action::None
+            }
+            ActionId::Mode(ModeActionId::TextCancel) => {
+                self.searching = false;
+                self.search.set_focused(false);
+                self.search.set_value("");
+                Action::None
+            }
             ActionId::Help(HelpActionId::Close) => Action::Nav(NavAction::PopPane),
             ActionId::Help(HelpActionId::Up) => {
                 if self.scroll > 0 {
@@ -72,53 +235,107 @@ impl Pane for HelpPane {
                 Action::None
             }
             ActionId::Help(HelpActionId::Bottom) => {
-                self.scroll = self.display_keymap.len().saturating_sub(1);
+                self.scroll = self.lines().len().saturating_sub(1);
                 Action::None
             }
+            ActionId::Help(HelpActionId::SwitchTab) => {
+                self.tab = match self.tab {
+                    HelpTab::Keys => HelpTab::Guides,
+                    HelpTab::Guides => HelpTab::Keys,
+                };
+                self.scroll = 0;
+                Action::None
+            }
+            ActionId::Help(HelpActionId::Search) => {
+                self.searching = true;
+                self.search.set_focused(true);
+                self.search.select_all();
+                self.scroll = 0;
+                Action::PushLayer("text_edit")
+            }
             _ => Action::None,
         }
     }
 
+    fn handle_raw_input(&mut self, event: &InputEvent, _state: &AppState) -> Action {
+        if self.searching && self.search.handle_input(event) {
+            self.scroll = 0;
+        }
+        Action::None
+    }
+
     fn render(&mut self, area: Rect, buf: &mut RenderBuf, _state: &AppState) {
-        let rect = center_rect(area, 60, 20);
+        let rect = center_rect(area, 72, 24);
         let title = format!(" Help: {} ", self.title);
 
         let border_style = Style::new().fg(Color::SKY_BLUE);
         let inner = buf.draw_block(rect, &title, border_style, border_style);
 
+        // Tabs and search
+        let active_tab = Style::new().fg(Color::BLACK).bg(Color::SKY_BLUE);
+        let inactive_tab = Style::new().fg(Color::DARK_GRAY);
+        let (keys_style, guides_style) = match self.tab {
+            HelpTab::Keys => (active_tab, inactive_tab),
+            HelpTab::Guides => (inactive_tab, active_tab),
+        };
+        buf.draw_line(Rect::new(inner.x + 1, inner.y, inner.width.saturating_sub(1), 1), &[
+            (" Keys ", keys_style),
+            (" ", inactive_tab),
+            (" Guides ", guides_style),
+            ("  /", Style::new().fg(Color::DARK_GRAY)),
+        ]);
+        let search_x = inner.x + 20;
+        if self.searching {
+            self.search.render_buf(buf.raw_buf(), search_x, inner.y, inner.width.saturating_sub(21));
+        } else if !self.search.value().is_empty() {
+            buf.draw_line(Rect::new(search_x, inner.y, inner.width.saturating_sub(21), 1),
+                &[(self.search.value(), Style::new().fg(Color::WHITE))]);
+        }
+
+        let lines = self.lines();
         let visible_lines = inner.height.saturating_sub(4) as usize;
-        let max_scroll = self.display_keymap.len().saturating_sub(visible_lines);
+        let max_scroll = lines.len().saturating_sub(visible_lines);
         let scroll = self.scroll.min(max_scroll);
 
         let key_style = Style::new().fg(Color::CYAN).bold();
         let desc_style = Style::new().fg(Color::WHITE);
+        let guide_style = Style::new().fg(Color::GOLD).bold();
 
-        for (i, (key, desc)) in self.display_keymap.iter().skip(scroll).take(visible_lines).enumerate() {
+        for (i, (key, desc)) in lines.iter().skip(scroll).take(visible_lines).enumerate() {
             let y = inner.y + 1 + i as u16;
             if y >= inner.y + inner.height {
                 break;
             }
 
+            let line_area = Rect::new(inner.x + 1, y, inner.width.saturating_sub(1), 1);
+            if desc.is_empty() {
+                buf.draw_line(line_area, &[(key.as_str(), guide_style)]);
+                continue;
+            }
             let max_desc_len = inner.width.saturating_sub(14) as usize;
             let desc_truncated: String = desc.chars().take(max_desc_len).collect();
             let key_formatted = format!("{:<12}", key);
 
-            let line_area = Rect::new(inner.x + 1, y, inner.width.saturating_sub(1), 1);
             buf.draw_line(line_area, &[
                 (&key_formatted, key_style),
                 (&desc_truncated, desc_style),
             ]);
         }
 
+        if lines.is_empty() {
+            let empty_area = Rect::new(inner.x + 1, inner.y + 1, inner.width.saturating_sub(1), 1);
+            buf.draw_line(empty_area, &[("No matches", Style::new().fg(Color::DARK_GRAY))]);
+        }
+
         // Scroll indicator
-        if self.display_keymap.len() > visible_lines {
+        if lines.len() > visible_lines {
             let indicator_y = rect.y + rect.height - 3;
             if indicator_y < area.y + area.height {
                 let indicator = format!(
                     "{}-{}/{}",
                     scroll + 1,
-                    (scroll + visible_lines).min(self.display_keymap.len()),
-                    self.display_keymap.len()
+                    (scroll + visible_lines).min(lines.len()),
+                    lines.len()
                 );
                 let ind_area = Rect::new(inner.x + 1, indicator_y, inner.width.saturating_sub(1), 1);
                 buf.draw_line(ind_area, &[(&indicator, Style::new().fg(Color::DARK_GRAY))]);
@@ -130,7 +347,7 @@ impl Pane for HelpPane {
         if help_y < area.y + area.height {
             let help_area = Rect::new(inner.x + 1, help_y, inner.width.saturating_sub(1), 1);
             buf.draw_line(help_area, &[
-                ("[ESC/F1] Close  [Up/Down] Scroll", Style::new().fg(Color::DARK_GRAY)),
+                ("[ESC/?] Close  [Up/Down] Scroll  [Tab] Keys/Guides  [/] Search", Style::new().fg(Color::DARK_GRAY)),
             ]);
         }
     }
@@ -161,3 +378,34 @@ impl Pane for HelpPane {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::{KeyCode, Modifiers};
+
+    fn dummy_event() -> InputEvent {
+        InputEvent::new(KeyCode::Char('x'), Modifiers::default())
+    }
+
+    #[test]
+    fn guides_for_the_current_pane_come_first() {
+        let mut pane = HelpPane::default();
+        pane.set_context("mixer", "Mixer", &Keymap::new());
+        let state = AppState::new();
+        pane.handle_action(ActionId::Help(HelpActionId::SwitchTab), &dummy_event(), &state);
+        assert_eq!(pane.lines()[0].0, "Set up a send reverb");
+    }
+
+    #[test]
+    fn search_filters_whole_guides() {
+        let mut pane = HelpPane::default();
+        pane.set_context("instrument", "Instruments", &Keymap::new());
+        let state = AppState::new();
+        pane.handle_action(ActionId::Help(HelpActionId::SwitchTab), &dummy_event(), &state);
+        pane.search.set_value("reverb");
+        let lines = pane.lines();
+        assert_eq!(lines[0].0, "Set up a send reverb");
+        assert!(lines.iter().filter(|(_, desc)| desc.is_empty()).count() == 1);
+    }
+}
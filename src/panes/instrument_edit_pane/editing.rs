@@ -1,7 +1,24 @@
 use super::{InstrumentEditPane, Section};
+use crate::param_units::Unit;
 use crate::state::param::{adjust_freq_semitone, adjust_musical_step};
+use crate::state::{Param, ParamValue};
 use crate::ui::{Action, InstrumentAction, InstrumentUpdate};
 
+/// Largest playback offset either way, enough for slow pads or outboard latency
+const MAX_PLAYBACK_OFFSET_MS: f32 = 500.0;
+
+/// Step a param, on a log scale when it is a frequency
+fn step_param(param: &mut Param, increase: bool, fraction: f32) {
+    if let ParamValue::Float(v) = param.value {
+        let unit = Unit::of(param);
+        if unit == Unit::Hz {
+            param.value = ParamValue::Float(unit.step(v, param.min, param.max, increase, fraction));
+            return;
+        }
+    }
+    param.adjust(increase, fraction);
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(super) enum AdjustMode {
     Tiny,
@@ -37,7 +54,7 @@ impl InstrumentEditPane {
                     if mode == AdjustMode::Musical {
                         param.adjust_musical(increase, tuning_a4);
                     } else {
-                        param.adjust(increase, fraction);
+                        step_param(param, increase, fraction);
                     }
                 }
             }
@@ -49,10 +66,7 @@ impl InstrumentEditPane {
                             if mode == AdjustMode::Musical {
                                 f.cutoff.value = adjust_freq_semitone(f.cutoff.value, increase, tuning_a4, f.cutoff.min, f.cutoff.max);
                             } else {
-                                let range = f.cutoff.max - f.cutoff.min;
-                                let delta = range * fraction;
-                                if increase { f.cutoff.value = (f.cutoff.value + delta).min(f.cutoff.max); }
-                                else { f.cutoff.value = (f.cutoff.value - delta).max(f.cutoff.min); }
+                                f.cutoff.value = Unit::Hz.step(f.cutoff.value, f.cutoff.min, f.cutoff.max, increase, fraction);
                             }
                         }
                         2 => {
@@ -72,7 +86,7 @@ impl InstrumentEditPane {
                                 if mode == AdjustMode::Musical {
                                     param.adjust_musical(increase, tuning_a4);
                                 } else {
-                                    param.adjust(increase, fraction);
+                                    step_param(param, increase, fraction);
                                 }
                             }
                         }
@@ -88,7 +102,7 @@ impl InstrumentEditPane {
                             if mode == AdjustMode::Musical {
                                 param.adjust_musical(increase, tuning_a4);
                             } else {
-                                param.adjust(increase, fraction);
+                                step_param(param, increase, fraction);
                             }
                         }
                     }
@@ -142,6 +156,15 @@ impl InstrumentEditPane {
         }
     }
 
+    /// Shift note scheduling earlier (negative) or later, in ms
+    pub(super) fn nudge_playback_offset(&mut self, delta_ms: f32) -> Action {
+        let Some(id) = self.instrument_id else {
+            return Action::None;
+        };
+        self.playback_offset_ms = (self.playback_offset_ms + delta_ms).clamp(-MAX_PLAYBACK_OFFSET_MS, MAX_PLAYBACK_OFFSET_MS);
+        Action::Instrument(InstrumentAction::SetPlaybackOffset(id, self.playback_offset_ms))
+    }
+
     pub(super) fn emit_update(&self) -> Action {
         if let Some(id) = self.instrument_id {
             Action::Instrument(InstrumentAction::Update(Box::new(InstrumentUpdate {
@@ -264,6 +287,32 @@ impl InstrumentEditPane {
         }
     }
 
+    /// Name of the parameter on the selected row, for its description
+    pub(super) fn selected_param_name(&self) -> Option<String> {
+        let (section, local_idx) = self.row_info(self.selected_row);
+        match section {
+            Section::Source => {
+                let param_idx = if self.source.is_sample() { local_idx.checked_sub(1)? } else { local_idx };
+                self.source_params.get(param_idx).map(|p| p.name.clone())
+            }
+            Section::Filter => match local_idx {
+                1 => Some("cutoff".to_string()),
+                2 => Some("resonance".to_string()),
+                idx => self.filter.as_ref()?.extra_params.get(idx.checked_sub(3)?).map(|p| p.name.clone()),
+            },
+            Section::Effects => {
+                let (effect_idx, param_offset) = self.effect_row_info(local_idx)?;
+                self.effects.get(effect_idx)?.params.get(param_offset.checked_sub(1)?).map(|p| p.name.clone())
+            }
+            Section::Lfo => match local_idx {
+                1 => Some("rate".to_string()),
+                2 => Some("depth".to_string()),
+                _ => None,
+            },
+            Section::Envelope => ["attack", "decay", "sustain", "release"].get(local_idx).map(|n| n.to_string()),
+        }
+    }
+
     /// Get current parameter value as a string for pre-filling text edit
     pub(super) fn current_value_string(&self) -> String {
         let (section, local_idx) = self.row_info(self.selected_row);
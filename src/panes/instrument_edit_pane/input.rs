@@ -4,6 +4,7 @@ use crate::state::{
     AppState, FilterConfig, FilterType,
 };
 use crate::ui::{Action, FileSelectAction, InputEvent, InstrumentAction, KeyCode, SessionAction, translate_key};
+use crate::value_entry;
 use crate::ui::action_id::{ActionId, InstrumentEditActionId, ModeActionId};
 
 impl InstrumentEditPane {
@@ -60,18 +61,18 @@ impl InstrumentEditPane {
                             local_idx
                         };
                         if let Some(param) = self.source_params.get_mut(param_idx) {
-                            param.parse_and_set(&text);
+                            param.parse_and_set(&value_entry::to_param_text(&text));
                         }
                     }
                     Section::Filter => {
                         if let Some(ref mut f) = self.filter {
                             match local_idx {
-                                1 => if let Ok(v) = text.parse::<f32>() { f.cutoff.value = v.clamp(f.cutoff.min, f.cutoff.max); },
-                                2 => if let Ok(v) = text.parse::<f32>() { f.resonance.value = v.clamp(f.resonance.min, f.resonance.max); },
+                                1 => if let Some(v) = value_entry::parse(&text) { f.cutoff.value = v.clamp(f.cutoff.min, f.cutoff.max); },
+                                2 => if let Some(v) = value_entry::parse(&text) { f.resonance.value = v.clamp(f.resonance.min, f.resonance.max); },
                                 idx => {
                                     let extra_idx = idx - 3;
                                     if let Some(param) = f.extra_params.get_mut(extra_idx) {
-                                        param.parse_and_set(&text);
+                                        param.parse_and_set(&value_entry::to_param_text(&text));
                                     }
                                 }
                             }
@@ -83,14 +84,14 @@ impl InstrumentEditPane {
                                 let param_idx = param_offset - 1;
                                 if let Some(effect) = self.effects.get_mut(effect_idx) {
                                     if let Some(param) = effect.params.get_mut(param_idx) {
-                                        param.parse_and_set(&text);
+                                        param.parse_and_set(&value_entry::to_param_text(&text));
                                     }
                                 }
                             }
                         }
                     }
                     Section::Envelope => {
-                        if let Ok(v) = text.parse::<f32>() {
+                        if let Some(v) = value_entry::parse(&text) {
                             let max = if local_idx == 2 { 1.0 } else { 5.0 };
                             let val = v.clamp(0.0, max);
                             match local_idx {
@@ -366,6 +367,21 @@ impl InstrumentEditPane {
                     Action::None
                 }
             }
+            ActionId::InstrumentEdit(InstrumentEditActionId::OffsetEarlier) => self.nudge_playback_offset(-1.0),
+            ActionId::InstrumentEdit(InstrumentEditActionId::OffsetLater) => self.nudge_playback_offset(1.0),
+            ActionId::InstrumentEdit(InstrumentEditActionId::OffsetEarlierBig) => self.nudge_playback_offset(-10.0),
+            ActionId::InstrumentEdit(InstrumentEditActionId::OffsetLaterBig) => self.nudge_playback_offset(10.0),
+            ActionId::InstrumentEdit(InstrumentEditActionId::RandomLooper) => {
+                if self.instrument_id.is_some() {
+                    Action::Nav(crate::ui::NavAction::PushPane("random_looper"))
+                } else {
+                    Action::None
+                }
+            }
+            ActionId::InstrumentEdit(InstrumentEditActionId::ExportScd) => {
+                self.pending_export = self.instrument_id;
+                Action::None
+            }
             ActionId::InstrumentEdit(InstrumentEditActionId::NextSection) => {
                 // Jump to first row of next section
                 let current = self.current_section();
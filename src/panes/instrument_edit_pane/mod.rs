@@ -31,12 +31,16 @@ pub struct InstrumentEditPane {
     amp_envelope: EnvConfig,
     polyphonic: bool,
     active: bool,
+    /// Note scheduling offset in ms; negative plays early
+    playback_offset_ms: f32,
     pub(crate) selected_row: usize,
     editing: bool,
     edit_input: TextInput,
     edit_backup_value: Option<String>,
     piano: PianoKeyboard,
     pad_keyboard: PadKeyboard,
+    /// Instrument to export as SuperCollider code, taken by the main loop
+    pending_export: Option<InstrumentId>,
 }
 
 impl InstrumentEditPane {
@@ -55,12 +59,14 @@ impl InstrumentEditPane {
             amp_envelope: EnvConfig::default(),
             polyphonic: true,
             active: true,
+            playback_offset_ms: 0.0,
             selected_row: 0,
             editing: false,
             edit_input: TextInput::new(""),
             edit_backup_value: None,
             piano: PianoKeyboard::new(),
             pad_keyboard: PadKeyboard::new(),
+            pending_export: None,
         }
     }
 
@@ -77,6 +83,7 @@ impl InstrumentEditPane {
         self.amp_envelope = instrument.amp_envelope.clone();
         self.polyphonic = instrument.polyphonic;
         self.active = instrument.active;
+        self.playback_offset_ms = instrument.playback_offset_ms;
         self.selected_row = 0;
     }
 
@@ -96,11 +103,17 @@ impl InstrumentEditPane {
         self.amp_envelope = instrument.amp_envelope.clone();
         self.polyphonic = instrument.polyphonic;
         self.active = instrument.active;
+        self.playback_offset_ms = instrument.playback_offset_ms;
         // Clamp selected_row to valid range (effects count may have changed)
         let max = self.total_rows().saturating_sub(1);
         self.selected_row = self.selected_row.min(max);
     }
 
+    /// Instrument whose `.scd` export was requested, if any
+    pub fn take_export(&mut self) -> Option<InstrumentId> {
+        self.pending_export.take()
+    }
+
     #[allow(dead_code)]
     pub fn instrument_id(&self) -> Option<InstrumentId> {
         self.instrument_id
@@ -135,6 +148,15 @@ impl InstrumentEditPane {
         }
     }
 
+    pub fn cursor_row(&self) -> usize {
+        self.selected_row
+    }
+
+    /// Put the cursor on `row`, clamped to the rows there are
+    pub fn set_cursor_row(&mut self, row: usize) {
+        self.selected_row = row.min(self.total_rows().saturating_sub(1));
+    }
+
     /// Apply edits back to an instrument
     #[allow(dead_code)]
     pub fn apply_to(&self, instrument: &mut Instrument) {
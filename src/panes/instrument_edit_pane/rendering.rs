@@ -1,11 +1,13 @@
 use super::InstrumentEditPane;
+use crate::param_help;
+use crate::param_units::Unit;
 use crate::state::{AppState, Param, ParamValue};
 use crate::ui::layout_helpers::center_rect;
 use crate::ui::widgets::TextInput;
 use crate::ui::{Rect, RenderBuf, Color, Style};
 
 impl InstrumentEditPane {
-    pub(super) fn render_impl(&mut self, area: Rect, buf: &mut RenderBuf, _state: &AppState) {
+    pub(super) fn render_impl(&mut self, area: Rect, buf: &mut RenderBuf, state: &AppState) {
         let rect = center_rect(area, 97, 29);
 
         let title = format!(" Edit: {} ({}) ", self.instrument_name, self.source.name());
@@ -21,6 +23,15 @@ impl InstrumentEditPane {
         let poly_str = if self.polyphonic { " POLY " } else { " MONO " };
         buf.draw_line(Rect::new(mode_x, rect.y, 6, 1), &[(poly_str, poly_style)]);
 
+        // Playback offset, shown with its tick equivalent at the current tempo
+        if self.playback_offset_ms != 0.0 {
+            let ticks_per_beat = state.session.piano_roll.ticks_per_beat as f32;
+            let ticks = (self.playback_offset_ms / 1000.0 * state.session.bpm as f32 / 60.0 * ticks_per_beat).round() as i32;
+            let offset_str = format!(" OFS {:+.0}ms ({:+}t) ", self.playback_offset_ms, ticks);
+            let offset_x = rect.x + rect.width / 2;
+            buf.draw_line(Rect::new(offset_x, rect.y, offset_str.len() as u16, 1), &[(&offset_str, Style::new().fg(Color::GOLD))]);
+        }
+
         // Active/Inactive indicator for AudioIn instruments
         if self.source.is_audio_input() {
             let active_style = Style::new().fg(
@@ -103,14 +114,14 @@ impl InstrumentEditPane {
             // Cutoff row
             {
                 let is_sel = self.selected_row == global_row;
-                render_value_row_buf(buf, content_x, y, "Cutoff", f.cutoff.value, f.cutoff.min, f.cutoff.max, is_sel, self.editing && is_sel, &mut self.edit_input);
+                render_value_row_buf(buf, content_x, y, "Cutoff", f.cutoff.value, f.cutoff.min, f.cutoff.max, Unit::Hz, is_sel, self.editing && is_sel, &mut self.edit_input);
                 y += 1;
                 global_row += 1;
             }
             // Resonance row
             {
                 let is_sel = self.selected_row == global_row;
-                render_value_row_buf(buf, content_x, y, "Resonance", f.resonance.value, f.resonance.min, f.resonance.max, is_sel, self.editing && is_sel, &mut self.edit_input);
+                render_value_row_buf(buf, content_x, y, "Resonance", f.resonance.value, f.resonance.min, f.resonance.max, Unit::Plain, is_sel, self.editing && is_sel, &mut self.edit_input);
                 y += 1;
                 global_row += 1;
             }
@@ -198,16 +209,7 @@ impl InstrumentEditPane {
         // Row 1: Rate
         {
             let is_sel = self.selected_row == global_row;
-            render_value_row_buf(buf, content_x, y, "Rate", self.lfo.rate, 0.1, 32.0, is_sel, self.editing && is_sel, &mut self.edit_input);
-            // Hz label
-            let hz_style = if is_sel {
-                Style::new().fg(Color::DARK_GRAY).bg(Color::SELECTION_BG)
-            } else {
-                Style::new().fg(Color::DARK_GRAY)
-            };
-            for (j, ch) in "Hz".chars().enumerate() {
-                buf.set_cell(content_x + 44 + j as u16, y, ch, hz_style);
-            }
+            render_value_row_buf(buf, content_x, y, "Rate", self.lfo.rate, 0.1, 32.0, Unit::Hz, is_sel, self.editing && is_sel, &mut self.edit_input);
             y += 1;
             global_row += 1;
         }
@@ -215,7 +217,7 @@ impl InstrumentEditPane {
         // Row 2: Depth
         {
             let is_sel = self.selected_row == global_row;
-            render_value_row_buf(buf, content_x, y, "Depth", self.lfo.depth, 0.0, 1.0, is_sel, self.editing && is_sel, &mut self.edit_input);
+            render_value_row_buf(buf, content_x, y, "Depth", self.lfo.depth, 0.0, 1.0, Unit::Percent, is_sel, self.editing && is_sel, &mut self.edit_input);
             y += 1;
             global_row += 1;
         }
@@ -247,7 +249,7 @@ impl InstrumentEditPane {
 
             for (label, (val, max)) in env_labels.iter().zip(env_values.iter().zip(env_maxes.iter())) {
                 let is_sel = self.selected_row == global_row;
-                render_value_row_buf(buf, content_x, y, label, *val, 0.0, *max, is_sel, self.editing && is_sel, &mut self.edit_input);
+                render_value_row_buf(buf, content_x, y, label, *val, 0.0, *max, Unit::for_param(label, 0.0, *max), is_sel, self.editing && is_sel, &mut self.edit_input);
                 y += 1;
                 global_row += 1;
             }
@@ -267,6 +269,16 @@ impl InstrumentEditPane {
         };
         buf.draw_line(Rect::new(content_x, help_y, inner.width.saturating_sub(2), 1),
             &[(help_text, Style::new().fg(Color::DARK_GRAY))]);
+
+        // Selected param's description, on the bottom border
+        let description = self.selected_param_name()
+            .and_then(|name| param_help::describe(&name, self.source, state));
+        if let Some(description) = description {
+            let text = format!(" {} ", description);
+            let width = (text.chars().count() as u16).min(rect.width.saturating_sub(4));
+            buf.draw_line(Rect::new(rect.x + 2, rect.y + rect.height - 1, width, 1),
+                &[(&text, Style::new().fg(Color::GRAY))]);
+        }
     }
 }
 
@@ -330,7 +342,7 @@ fn render_param_row_buf(
         edit_input.render_buf(buf.raw_buf(), x + 34, y, 10);
     } else {
         let value_str = match &param.value {
-            ParamValue::Float(v) => format!("{:.2}", v),
+            ParamValue::Float(v) => Unit::of(param).format(*v),
             ParamValue::Int(v) => format!("{}", v),
             ParamValue::Bool(v) => format!("{}", v),
         };
@@ -351,6 +363,7 @@ fn render_value_row_buf(
     x: u16, y: u16,
     name: &str,
     value: f32, min: f32, max: f32,
+    unit: Unit,
     is_selected: bool,
     is_editing: bool,
     edit_input: &mut TextInput,
@@ -391,7 +404,7 @@ fn render_value_row_buf(
         } else {
             Style::new().fg(Color::WHITE)
         };
-        let formatted = format!("{:.2}", value);
+        let formatted = format!("{:10}", unit.format(value));
         for (j, ch) in formatted.chars().enumerate() {
             buf.set_cell(x + 34 + j as u16, y, ch, val_style);
         }
@@ -1,9 +1,12 @@
 use std::any::Any;
+use std::collections::HashMap;
 
 use crate::state::{AppState, SourceType};
 use crate::ui::layout_helpers::center_rect;
 use crate::ui::{Rect, RenderBuf, Action, NavAction, InstrumentAction, SessionAction, Color, InputEvent, KeyCode, Keymap, MouseEvent, MouseEventKind, MouseButton, PadKeyboard, Pane, PianoKeyboard, Style, ToggleResult, translate_key};
 use crate::ui::action_id::{ActionId, InstrumentListActionId, ModeActionId};
+use crate::ui::widgets::TextInput;
+use crate::voice_activity::VoiceCount;
 
 fn source_color(source: SourceType) -> Color {
     match source {
@@ -28,6 +31,13 @@ pub struct InstrumentPane {
     pad_keyboard: PadKeyboard,
     /// When Some, we're waiting for the user to select a target instrument to link with
     linking_from: Option<crate::state::InstrumentId>,
+    /// Instrument being renamed inline
+    renaming: Option<crate::state::InstrumentId>,
+    rename_input: TextInput,
+    /// List row where a mouse drag started, for reordering
+    drag_row: Option<usize>,
+    /// Live voice counts per instrument, fed by main.rs
+    voices: HashMap<crate::state::InstrumentId, VoiceCount>,
 }
 
 impl InstrumentPane {
@@ -37,9 +47,46 @@ impl InstrumentPane {
             piano: PianoKeyboard::new(),
             pad_keyboard: PadKeyboard::new(),
             linking_from: None,
+            renaming: None,
+            rename_input: TextInput::new(""),
+            drag_row: None,
+            voices: HashMap::new(),
         }
     }
 
+    pub fn set_voice_activity(&mut self, voices: HashMap<crate::state::InstrumentId, VoiceCount>) {
+        self.voices = voices;
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.renaming.is_some()
+    }
+
+    fn finish_rename(&mut self, confirm: bool) -> Action {
+        let Some(id) = self.renaming.take() else {
+            return Action::None;
+        };
+        self.rename_input.set_focused(false);
+        let name = self.rename_input.value().trim().to_string();
+        if confirm && !name.is_empty() {
+            Action::Instrument(InstrumentAction::Rename(id, name))
+        } else {
+            Action::None
+        }
+    }
+
+    /// Move the selected instrument `delta` places in the list
+    fn move_selected(state: &AppState, delta: i32) -> Action {
+        let (Some(sel), Some(instrument)) = (state.instruments.selected, state.instruments.selected_instrument()) else {
+            return Action::None;
+        };
+        let target = sel as i64 + delta as i64;
+        if target < 0 || target >= state.instruments.instruments.len() as i64 {
+            return Action::None;
+        }
+        Action::Instrument(InstrumentAction::Move(instrument.id, delta))
+    }
+
     fn format_filter(instrument: &crate::state::instrument::Instrument) -> String {
         match &instrument.filter {
             Some(f) => format!("[{}]", f.filter_type.name()),
@@ -68,6 +115,10 @@ impl InstrumentPane {
     }
 }
 
+fn row_under(row: u16, list_y: u16, max_visible: usize) -> Option<usize> {
+    (row >= list_y && row < list_y + max_visible as u16).then(|| (row - list_y) as usize)
+}
+
 impl Default for InstrumentPane {
     fn default() -> Self {
         Self::new(Keymap::new())
@@ -116,6 +167,8 @@ impl Pane for InstrumentPane {
         }
 
         match action {
+            ActionId::Mode(ModeActionId::TextConfirm) => self.finish_rename(true),
+            ActionId::Mode(ModeActionId::TextCancel) => self.finish_rename(false),
             ActionId::InstrumentList(InstrumentListActionId::Quit) => Action::Quit,
             ActionId::InstrumentList(InstrumentListActionId::Next) => Action::Instrument(InstrumentAction::SelectNext),
             ActionId::InstrumentList(InstrumentListActionId::Prev) => Action::Instrument(InstrumentAction::SelectPrev),
@@ -152,6 +205,34 @@ impl Pane for InstrumentPane {
                 }
             }
 
+            ActionId::InstrumentList(InstrumentListActionId::Rename) => {
+                if let Some(instrument) = state.instruments.selected_instrument() {
+                    self.rename_input.set_value(&instrument.name);
+                    self.rename_input.select_all();
+                    self.rename_input.set_focused(true);
+                    self.renaming = Some(instrument.id);
+                    Action::PushLayer("text_edit")
+                } else {
+                    Action::None
+                }
+            }
+            ActionId::InstrumentList(InstrumentListActionId::Duplicate) => {
+                if let Some(instrument) = state.instruments.selected_instrument() {
+                    Action::Instrument(InstrumentAction::Duplicate { id: instrument.id, with_notes: false })
+                } else {
+                    Action::None
+                }
+            }
+            ActionId::InstrumentList(InstrumentListActionId::DuplicateWithNotes) => {
+                if let Some(instrument) = state.instruments.selected_instrument() {
+                    Action::Instrument(InstrumentAction::Duplicate { id: instrument.id, with_notes: true })
+                } else {
+                    Action::None
+                }
+            }
+            ActionId::InstrumentList(InstrumentListActionId::MoveUp) => Self::move_selected(state, -1),
+            ActionId::InstrumentList(InstrumentListActionId::MoveDown) => Self::move_selected(state, 1),
+
             // Piano layer actions
             ActionId::Mode(ModeActionId::PianoEscape) => {
                 let was_active = self.piano.is_active();
@@ -251,13 +332,14 @@ impl Pane for InstrumentPane {
             };
 
             // Build row as a Line with multiple spans
-            let name_str = format!("{:14}", &instrument.name[..instrument.name.len().min(14)]);
+            let name: String = instrument.name.chars().take(14).collect();
+            let name_str = format!("{:14}", name);
             let source_str = format!(" {:10}", instrument.source.name());
             let filter_str = format!(" {:12}", Self::format_filter(instrument));
             let eq_str = format!(" {:4}", Self::format_eq(instrument));
             let fx_raw = Self::format_effects(instrument);
             let fx_str = format!(" {:18}", &fx_raw[..fx_raw.len().min(18)]);
-            let level_str = format!(" {}", Self::format_level(instrument.level));
+            let level_str = format!(" {:10}", Self::format_level(instrument.level));
 
             let source_c = source_color(instrument.source);
 
@@ -266,6 +348,14 @@ impl Pane for InstrumentPane {
                 None => String::new(),
             };
 
+            // Voice count, in the warning color while voices are stolen
+            let voices = self.voices.get(&instrument.id).copied().unwrap_or_default();
+            let voices_str = match voices.voices {
+                0 if !voices.stealing => "     ".to_string(),
+                n => format!(" {:3}v", n),
+            };
+            let voices_color = if voices.stealing { Color::METER_HIGH } else { Color::SKY_BLUE };
+
             let mut spans: Vec<(&str, Style)> = vec![
                 (&name_str, mk_style(Color::WHITE)),
                 (&source_str, mk_style(source_c)),
@@ -273,12 +363,16 @@ impl Pane for InstrumentPane {
                 (&eq_str, mk_style(Color::EQ_COLOR)),
                 (&fx_str, mk_style(Color::FX_COLOR)),
                 (&level_str, mk_style(Color::LIME)),
+                (&voices_str, mk_style(voices_color)),
             ];
             if !layer_str.is_empty() {
                 spans.push((&layer_str, mk_style(Color::ORANGE)));
             }
             let line_width = inner.width.saturating_sub(3);
             buf.draw_line(Rect::new(content_x + 2, y, line_width, 1), &spans);
+            if self.renaming == Some(instrument.id) {
+                self.rename_input.render_buf(buf.raw_buf(), content_x + 2, y, 14);
+            }
 
             // Fill rest of line with selection bg
             if is_selected {
@@ -328,14 +422,16 @@ impl Pane for InstrumentPane {
 
         // Help text
         let help_y = rect.y + rect.height - 2;
-        let help_text = if self.linking_from.is_some() {
+        let help_text = if self.renaming.is_some() {
+            "Enter: rename | Esc: cancel"
+        } else if self.linking_from.is_some() {
             "\u{2191}/\u{2193}: select target | any other key: cancel"
         } else if self.pad_keyboard.is_active() {
             "R T Y U / F G H J / V B N M: trigger pads | /: cycle | Esc: exit"
         } else if self.piano.is_active() {
             "Play keys | [/]: octave | \u{2191}/\u{2193}: select instrument | /: cycle | Esc: exit"
         } else {
-            "a: add | d: delete | Enter: edit | l: link layer | L: unlink | /: piano"
+            "a: add | d: delete | Enter: edit | r: rename | y/Y: duplicate | Shift+\u{2191}/\u{2193}: move | l/L: link | /: piano"
         };
         buf.draw_line(
             Rect::new(content_x, help_y, inner.width.saturating_sub(2), 1),
@@ -364,17 +460,45 @@ impl Pane for InstrumentPane {
                 if col >= inner_x && row >= list_y && row < list_y + max_visible as u16 {
                     let clicked_idx = scroll_offset + (row - list_y) as usize;
                     if clicked_idx < state.instruments.instruments.len() {
+                        self.drag_row = Some(clicked_idx);
                         return Action::Instrument(InstrumentAction::Select(clicked_idx));
                     }
                 }
                 Action::None
             }
+            // Dragging a row moves the instrument one place per row crossed
+            MouseEventKind::Drag(MouseButton::Left) => {
+                let Some(from) = self.drag_row else {
+                    return Action::None;
+                };
+                let Some(row) = row_under(event.row, list_y, max_visible) else {
+                    return Action::None;
+                };
+                let to = scroll_offset + row;
+                if to == from || to >= state.instruments.instruments.len() {
+                    return Action::None;
+                }
+                let delta = if to > from { 1 } else { -1 };
+                self.drag_row = Some((from as i32 + delta) as usize);
+                Self::move_selected(state, delta)
+            }
+            MouseEventKind::Up(MouseButton::Left) => {
+                self.drag_row = None;
+                Action::None
+            }
             MouseEventKind::ScrollUp => Action::Instrument(InstrumentAction::SelectPrev),
             MouseEventKind::ScrollDown => Action::Instrument(InstrumentAction::SelectNext),
             _ => Action::None,
         }
     }
 
+    fn handle_raw_input(&mut self, event: &InputEvent, _state: &AppState) -> Action {
+        if self.renaming.is_some() {
+            self.rename_input.handle_input(event);
+        }
+        Action::None
+    }
+
     fn keymap(&self) -> &Keymap {
         &self.keymap
     }
@@ -486,4 +610,35 @@ mod tests {
         let action = pane.handle_action(ActionId::InstrumentList(InstrumentListActionId::Prev), &dummy_event(), &state);
         assert!(matches!(action, Action::Instrument(InstrumentAction::SelectPrev)));
     }
+
+    #[test]
+    fn move_stops_at_list_ends() {
+        use crate::ui::action_id::{ActionId, InstrumentListActionId};
+        let mut state = AppState::new();
+        let id = state.add_instrument(SourceType::Saw);
+        state.add_instrument(SourceType::Sin);
+        state.instruments.selected = Some(0);
+        let mut pane = InstrumentPane::new(Keymap::new());
+
+        let up = pane.handle_action(ActionId::InstrumentList(InstrumentListActionId::MoveUp), &dummy_event(), &state);
+        assert!(matches!(up, Action::None));
+        let down = pane.handle_action(ActionId::InstrumentList(InstrumentListActionId::MoveDown), &dummy_event(), &state);
+        assert!(matches!(down, Action::Instrument(InstrumentAction::Move(got, 1)) if got == id));
+    }
+
+    #[test]
+    fn rename_confirms_trimmed_name() {
+        use crate::ui::action_id::{ActionId, InstrumentListActionId};
+        let mut state = AppState::new();
+        let id = state.add_instrument(SourceType::Saw);
+        let mut pane = InstrumentPane::new(Keymap::new());
+
+        let action = pane.handle_action(ActionId::InstrumentList(InstrumentListActionId::Rename), &dummy_event(), &state);
+        assert!(matches!(action, Action::PushLayer("text_edit")));
+        assert!(pane.is_editing());
+        pane.rename_input.set_value("  Lead  ");
+        let action = pane.handle_action(ActionId::Mode(ModeActionId::TextConfirm), &dummy_event(), &state);
+        assert!(matches!(action, Action::Instrument(InstrumentAction::Rename(got, ref name)) if got == id && name == "Lead"));
+        assert!(!pane.is_editing());
+    }
 }
@@ -0,0 +1,156 @@
+use std::any::Any;
+
+use crate::latency::TimingReport;
+use crate::state::AppState;
+use crate::ui::action_id::{ActionId, LatencyActionId};
+use crate::ui::layout_helpers::center_rect;
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, NavAction, Pane, Style};
+
+/// Offsets listed before the rest are summed up as "+N more"
+const MAX_OFFSETS_SHOWN: usize = 3;
+
+/// Server latency, bundle lead time, playback offsets and dropped feedback,
+/// refreshed every frame while open, with advice when timing drifts.
+pub struct LatencyPane {
+    keymap: Keymap,
+    report: TimingReport,
+    running: bool,
+    /// Set when the user asked to clear the history; main.rs takes it
+    reset_requested: bool,
+}
+
+impl LatencyPane {
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            report: TimingReport::default(),
+            running: false,
+            reset_requested: false,
+        }
+    }
+
+    pub fn set_report(&mut self, report: TimingReport, running: bool) {
+        self.report = report;
+        self.running = running;
+    }
+
+    pub fn take_reset_requested(&mut self) -> bool {
+        std::mem::take(&mut self.reset_requested)
+    }
+
+    fn offsets_text(&self) -> String {
+        let offsets = &self.report.offsets;
+        if offsets.is_empty() {
+            return "none".into();
+        }
+        let mut text = offsets.iter()
+            .take(MAX_OFFSETS_SHOWN)
+            .map(|(name, ms)| format!("{} {:+.0} ms", name, ms))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if offsets.len() > MAX_OFFSETS_SHOWN {
+            text.push_str(&format!(", +{} more", offsets.len() - MAX_OFFSETS_SHOWN));
+        }
+        text
+    }
+}
+
+impl Default for LatencyPane {
+    fn default() -> Self {
+        Self::new(Keymap::new())
+    }
+}
+
+impl Pane for LatencyPane {
+    fn id(&self) -> &'static str {
+        "latency"
+    }
+
+    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, _state: &AppState) -> Action {
+        match action {
+            ActionId::Latency(LatencyActionId::Reset) => {
+                self.reset_requested = true;
+                Action::None
+            }
+            ActionId::Latency(LatencyActionId::Close) => Action::Nav(NavAction::PopPane),
+            _ => Action::None,
+        }
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, _state: &AppState) {
+        let r = &self.report;
+        let advice_lines = (r.suggestions.len() * 2).max(1) as u16;
+        let rect = center_rect(area, 68, advice_lines + 12);
+        let border_style = Style::new().fg(Color::CYAN);
+        let inner = buf.draw_block(rect, " Latency & Timing ", border_style, border_style);
+
+        let x = inner.x + 2;
+        let w = inner.width.saturating_sub(4);
+        let label_style = Style::new().fg(Color::CYAN);
+        let value_style = Style::new().fg(Color::WHITE);
+        let latency_color = |ms: f32| {
+            if r.lead_ms > 0.0 && ms > r.lead_ms {
+                Color::METER_HIGH
+            } else if ms > r.lead_ms / 2.0 {
+                Color::ORANGE
+            } else {
+                Color::METER_LOW
+            }
+        };
+
+        let (round_trip, avg_range) = if self.running && r.max_ms > 0.0 {
+            (
+                format!("{:.1} ms", r.last_ms),
+                format!("avg {:.1}  min {:.1}  max {:.1}  jitter {:.1} ms", r.avg_ms, r.min_ms, r.max_ms, r.jitter_ms),
+            )
+        } else {
+            ("--".to_string(), String::new())
+        };
+        let dropped = match (r.dropped_recent, r.dropped_total) {
+            (0, 0) => "none".to_string(),
+            (recent, total) => format!("{} in the last 30 s ({} total)", recent, total),
+        };
+        let rows: [(&str, String, Style); 5] = [
+            ("Round trip", round_trip, Style::new().fg(latency_color(r.max_ms)).bold()),
+            ("", avg_range, value_style),
+            ("Bundle lead", format!("{:.0} ms", r.lead_ms), value_style),
+            ("Offsets", self.offsets_text(), value_style),
+            ("Dropped", dropped, if r.dropped_recent > 0 { Style::new().fg(Color::ORANGE) } else { value_style }),
+        ];
+        for (i, (label, value, style)) in rows.iter().enumerate() {
+            let y = inner.y + 1 + i as u16;
+            let label = format!("{:13}", label);
+            buf.draw_line(Rect::new(x, y, w, 1), &[(&label, label_style), (value, *style)]);
+        }
+
+        // Advice, two lines per suggestion
+        let mut y = inner.y + 2 + rows.len() as u16;
+        if !self.running {
+            buf.draw_line(Rect::new(x, y, w, 1), &[("The audio server is not running.", Style::new().fg(Color::DARK_GRAY))]);
+        } else if r.suggestions.is_empty() {
+            buf.draw_line(Rect::new(x, y, w, 1), &[("Timing looks steady.", Style::new().fg(Color::METER_LOW))]);
+        } else {
+            for suggestion in &r.suggestions {
+                let chars: Vec<char> = suggestion.chars().collect();
+                for chunk in chars.chunks(w.max(1) as usize).take(2) {
+                    let line: String = chunk.iter().collect();
+                    buf.draw_line(Rect::new(x, y, w, 1), &[(&line, Style::new().fg(Color::ORANGE))]);
+                    y += 1;
+                }
+            }
+        }
+
+        let help_y = rect.y + rect.height - 2;
+        if help_y < area.y + area.height {
+            buf.draw_line(Rect::new(x, help_y, w, 1), &[("r: reset history | Esc: close", Style::new().fg(Color::DARK_GRAY))]);
+        }
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
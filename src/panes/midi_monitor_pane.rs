@@ -0,0 +1,272 @@
+use std::any::Any;
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use crate::midi::MidiEvent;
+use crate::state::AppState;
+use crate::ui::action_id::{ActionId, MidiMonitorActionId};
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, NavAction, Pane, Style};
+
+/// Messages kept in the log; older ones are dropped
+const MAX_ENTRIES: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    NoteOn,
+    NoteOff,
+    ControlChange,
+    PitchBend,
+    Pressure,
+}
+
+impl Kind {
+    fn label(self) -> &'static str {
+        match self {
+            Kind::NoteOn => "Note On",
+            Kind::NoteOff => "Note Off",
+            Kind::ControlChange => "CC",
+            Kind::PitchBend => "Pitch Bend",
+            Kind::Pressure => "Pressure",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Kind::NoteOn => Color::METER_LOW,
+            Kind::NoteOff => Color::GRAY,
+            Kind::ControlChange => Color::SKY_BLUE,
+            Kind::PitchBend => Color::PURPLE,
+            Kind::Pressure => Color::ORANGE,
+        }
+    }
+}
+
+struct Entry {
+    secs: f64,
+    device: String,
+    channel: u8,
+    kind: Kind,
+    data: String,
+}
+
+fn note_name(note: u8) -> String {
+    const NAMES: [&str; 12] = ["C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B"];
+    format!("{}{}", NAMES[(note % 12) as usize], note as i32 / 12 - 1)
+}
+
+/// Kind, channel and data column for an incoming event
+fn describe(event: &MidiEvent) -> Option<(Kind, u8, String)> {
+    match event {
+        // Note on with velocity 0 is a note off by convention
+        MidiEvent::NoteOn { channel, note, velocity: 0 } => {
+            Some((Kind::NoteOff, *channel, format!("{:<4} ({:3})", note_name(*note), note)))
+        }
+        MidiEvent::NoteOn { channel, note, velocity } => Some((
+            Kind::NoteOn,
+            *channel,
+            format!("{:<4} ({:3})  vel {:3}", note_name(*note), note, velocity),
+        )),
+        MidiEvent::NoteOff { channel, note, .. } => {
+            Some((Kind::NoteOff, *channel, format!("{:<4} ({:3})", note_name(*note), note)))
+        }
+        MidiEvent::ControlChange { channel, controller, value } => {
+            Some((Kind::ControlChange, *channel, format!("#{:<3}  val {:3}", controller, value)))
+        }
+        MidiEvent::PitchBend { channel, value } => {
+            Some((Kind::PitchBend, *channel, format!("{:+}", *value as i32 - 8192)))
+        }
+        MidiEvent::ChannelPressure { channel, value } => {
+            Some((Kind::Pressure, *channel, format!("val {:3}", value)))
+        }
+        _ => None,
+    }
+}
+
+/// Live log of incoming MIDI messages for debugging controller setups.
+/// Freezing holds the view still while messages keep arriving, so the log
+/// can be scrolled.
+pub struct MidiMonitorPane {
+    keymap: Keymap,
+    started: Instant,
+    entries: VecDeque<Entry>,
+    frozen: bool,
+    /// Rows scrolled up from the newest entry while frozen
+    scroll: usize,
+}
+
+impl MidiMonitorPane {
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            started: Instant::now(),
+            entries: VecDeque::new(),
+            frozen: false,
+            scroll: 0,
+        }
+    }
+
+    /// Log an incoming event from `device`
+    pub fn push(&mut self, event: &MidiEvent, device: Option<&str>) {
+        let Some((kind, channel, data)) = describe(event) else {
+            return;
+        };
+        self.entries.push_back(Entry {
+            secs: self.started.elapsed().as_secs_f64(),
+            device: device.unwrap_or("-").to_string(),
+            channel,
+            kind,
+            data,
+        });
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.pop_front();
+        } else if self.frozen {
+            // Keep the frozen view on the same messages
+            self.scroll += 1;
+        }
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.entries.len().saturating_sub(1)
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        self.frozen = true;
+        self.scroll = self.scroll.saturating_add_signed(delta).min(self.max_scroll());
+    }
+
+    fn toggle_freeze(&mut self) {
+        self.frozen = !self.frozen;
+        if !self.frozen {
+            self.scroll = 0;
+        }
+    }
+}
+
+impl Default for MidiMonitorPane {
+    fn default() -> Self {
+        Self::new(Keymap::new())
+    }
+}
+
+impl Pane for MidiMonitorPane {
+    fn id(&self) -> &'static str {
+        "midi_monitor"
+    }
+
+    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, _state: &AppState) -> Action {
+        match action {
+            ActionId::MidiMonitor(MidiMonitorActionId::Freeze) => self.toggle_freeze(),
+            ActionId::MidiMonitor(MidiMonitorActionId::Up) => self.scroll_by(1),
+            ActionId::MidiMonitor(MidiMonitorActionId::Down) => self.scroll_by(-1),
+            ActionId::MidiMonitor(MidiMonitorActionId::PageUp) => self.scroll_by(10),
+            ActionId::MidiMonitor(MidiMonitorActionId::PageDown) => self.scroll_by(-10),
+            ActionId::MidiMonitor(MidiMonitorActionId::Clear) => {
+                self.entries.clear();
+                self.scroll = 0;
+            }
+            ActionId::MidiMonitor(MidiMonitorActionId::Close) => return Action::Nav(NavAction::PopPane),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, state: &AppState) {
+        let title = if self.frozen { " MIDI Monitor [FROZEN] " } else { " MIDI Monitor " };
+        let border_style = Style::new().fg(Color::CYAN);
+        let inner = buf.draw_block(area, title, border_style, border_style);
+
+        if inner.height < 4 || inner.width < 40 {
+            return;
+        }
+
+        let x = inner.x + 1;
+        let w = inner.width.saturating_sub(2);
+        let dim = Style::new().fg(Color::DARK_GRAY);
+        let device_width = (w as usize).saturating_sub(50).clamp(6, 24);
+
+        let port = state.midi.connected_port.as_deref().unwrap_or("not connected");
+        let status = format!("Port: {}  |  {} messages", port, self.entries.len());
+        buf.draw_line(Rect::new(x, inner.y, w, 1), &[(&status, Style::new().fg(Color::GRAY))]);
+
+        let header = format!("{:>9}  {:<dw$}  {:>2}  {:<10}  Data", "Time", "Device", "Ch", "Type", dw = device_width);
+        buf.draw_line(Rect::new(x, inner.y + 1, w, 1), &[(&header, Style::new().fg(Color::CYAN).bold())]);
+
+        // Newest message at the bottom, above the help line
+        let rows = inner.height.saturating_sub(3) as usize;
+        let end = self.entries.len().saturating_sub(self.scroll);
+        let start = end.saturating_sub(rows);
+        for (row, entry) in self.entries.range(start..end).enumerate() {
+            let y = inner.y + 2 + row as u16;
+            let device: String = entry.device.chars().take(device_width).collect();
+            let line = format!(
+                "{:>9.3}  {:<dw$}  {:>2}  ",
+                entry.secs, device, entry.channel + 1, dw = device_width,
+            );
+            let kind = format!("{:<10}  ", entry.kind.label());
+            buf.draw_line(Rect::new(x, y, w, 1), &[
+                (&line, Style::new().fg(Color::GRAY)),
+                (&kind, Style::new().fg(entry.kind.color())),
+                (&entry.data, Style::new().fg(Color::WHITE)),
+            ]);
+        }
+        if self.entries.is_empty() {
+            buf.draw_line(Rect::new(x, inner.y + 2, w, 1), &[("(waiting for MIDI input)", dim)]);
+        }
+
+        let help = if self.frozen {
+            "Up/Down/PgUp/PgDn: scroll | f: follow | c: clear | Esc: close"
+        } else {
+            "f: freeze | Up/Down: scroll | c: clear | Esc: close"
+        };
+        buf.draw_line(Rect::new(x, inner.y + inner.height - 1, w, 1), &[(help, dim)]);
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note_on(note: u8, velocity: u8) -> MidiEvent {
+        MidiEvent::NoteOn { channel: 0, note, velocity }
+    }
+
+    #[test]
+    fn zero_velocity_note_on_is_note_off() {
+        let (kind, _, data) = describe(&note_on(60, 0)).unwrap();
+        assert_eq!(kind, Kind::NoteOff);
+        assert!(data.starts_with("C4"));
+        let (_, _, data) = describe(&MidiEvent::PitchBend { channel: 0, value: 8192 }).unwrap();
+        assert_eq!(data, "+0");
+    }
+
+    #[test]
+    fn frozen_view_stays_put() {
+        let mut pane = MidiMonitorPane::default();
+        pane.push(&note_on(60, 100), Some("Keys"));
+        pane.toggle_freeze();
+        pane.push(&note_on(62, 100), Some("Keys"));
+        pane.push(&note_on(64, 100), Some("Keys"));
+        assert_eq!(pane.scroll, 2);
+        pane.toggle_freeze();
+        assert_eq!(pane.scroll, 0);
+    }
+
+    #[test]
+    fn log_is_capped() {
+        let mut pane = MidiMonitorPane::default();
+        for _ in 0..MAX_ENTRIES + 10 {
+            pane.push(&note_on(60, 100), None);
+        }
+        assert_eq!(pane.entries.len(), MAX_ENTRIES);
+        pane.scroll_by(10_000);
+        assert_eq!(pane.scroll, MAX_ENTRIES - 1);
+    }
+}
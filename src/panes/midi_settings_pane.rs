@@ -3,7 +3,7 @@ use std::any::Any;
 use crate::action::{Action, MidiAction};
 use crate::state::AppState;
 use crate::ui::action_id::{ActionId, MidiSettingsActionId};
-use crate::ui::{Rect, RenderBuf, Color, InputEvent, Keymap, Pane, Style};
+use crate::ui::{Rect, RenderBuf, Color, InputEvent, Keymap, NavAction, Pane, Style};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Section {
@@ -108,6 +108,7 @@ impl Pane for MidiSettingsPane {
             ActionId::MidiSettings(MidiSettingsActionId::ClearLiveInstrument) => {
                 Action::Midi(MidiAction::SetLiveInputInstrument(None))
             }
+            ActionId::MidiSettings(MidiSettingsActionId::Monitor) => Action::Nav(NavAction::PushPane("midi_monitor")),
             _ => Action::None,
         }
     }
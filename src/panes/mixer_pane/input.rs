@@ -1,12 +1,29 @@
-use super::{MixerPane, MixerSection};
-use super::{CHANNEL_WIDTH, NUM_VISIBLE_CHANNELS, NUM_VISIBLE_BUSES, METER_HEIGHT};
+use super::{MixerPane, MixerSection, BUS_COLORS};
+use super::{CHANNEL_WIDTH, NUM_VISIBLE_BUSES, SEND_ROWS};
 use crate::state::{AppState, InstrumentId, MixerSelection};
 use crate::ui::{Rect, Action, InputEvent, MixerAction, InstrumentAction, NavAction, MouseEvent, MouseEventKind, MouseButton};
-use crate::ui::layout_helpers::center_rect;
-use crate::ui::action_id::{ActionId, MixerActionId};
+use crate::ui::action_id::{ActionId, MixerActionId, ModeActionId};
 
 impl MixerPane {
     pub(super) fn handle_action_impl(&mut self, action: ActionId, _event: &InputEvent, state: &AppState) -> Action {
+        match action {
+            ActionId::Mode(ModeActionId::TextConfirm) if self.jumping => {
+                return self.finish_jump(true, state);
+            }
+            ActionId::Mode(ModeActionId::TextCancel) if self.jumping => {
+                return self.finish_jump(false, state);
+            }
+            ActionId::Mode(ModeActionId::TextConfirm) if self.typing_level.is_some() => {
+                return self.finish_typing_level(true, state);
+            }
+            ActionId::Mode(ModeActionId::TextCancel) if self.typing_level.is_some() => {
+                return self.finish_typing_level(false, state);
+            }
+            ActionId::Mode(ModeActionId::TextConfirm) => return self.finish_rename(true),
+            ActionId::Mode(ModeActionId::TextCancel) => return self.finish_rename(false),
+            _ => {}
+        }
+
         // Detail mode handling
         if self.detail_mode.is_some() {
             return self.handle_detail_action(action, state);
@@ -48,6 +65,45 @@ impl MixerPane {
             }
             ActionId::Mixer(MixerActionId::Mute) => Action::Mixer(MixerAction::ToggleMute),
             ActionId::Mixer(MixerActionId::Solo) => Action::Mixer(MixerAction::ToggleSolo),
+            ActionId::Mixer(MixerActionId::MomentarySolo) => {
+                let selection = state.session.mixer.selection;
+                let now = std::time::Instant::now();
+                match self.momentary_solo {
+                    Some((held, _)) if held == selection => {
+                        self.momentary_solo = Some((held, now));
+                        Action::None
+                    }
+                    // Already soloed channels are left alone on release
+                    _ if Self::is_soloed(state, selection) => Action::None,
+                    Some((held, _)) => {
+                        self.momentary_solo = Some((selection, now));
+                        Action::Batch(vec![
+                            Action::Mixer(MixerAction::SetSoloAt(held, false)),
+                            Action::Mixer(MixerAction::SetSoloAt(selection, true)),
+                        ])
+                    }
+                    None => {
+                        self.momentary_solo = Some((selection, now));
+                        Action::Mixer(MixerAction::SetSoloAt(selection, true))
+                    }
+                }
+            }
+            ActionId::Mixer(MixerActionId::ExclusiveSolo) => Action::Mixer(MixerAction::ToggleExclusiveSolo),
+            ActionId::Mixer(MixerActionId::SoloSafe) => {
+                if matches!(state.session.mixer.selection, MixerSelection::Master) {
+                    Action::None
+                } else {
+                    Action::Mixer(MixerAction::ToggleSoloSafe)
+                }
+            }
+            ActionId::Mixer(MixerActionId::GainMatch) => Action::Mixer(MixerAction::ToggleGainMatch),
+            ActionId::Mixer(MixerActionId::AutomationMode) => {
+                if matches!(state.session.mixer.selection, MixerSelection::Instrument(_)) {
+                    Action::Mixer(MixerAction::CycleAutomationMode)
+                } else {
+                    Action::None
+                }
+            }
             ActionId::Mixer(MixerActionId::Output) => Action::Mixer(MixerAction::CycleOutput),
             ActionId::Mixer(MixerActionId::OutputRev) => Action::Mixer(MixerAction::CycleOutputReverse),
             ActionId::Mixer(MixerActionId::Section) => { self.send_target = None; Action::Mixer(MixerAction::CycleSection) }
@@ -74,6 +130,54 @@ impl MixerPane {
                     Action::None
                 }
             }
+            ActionId::Mixer(MixerActionId::Routing) => Action::Nav(NavAction::PushPane("routing")),
+            ActionId::Mixer(MixerActionId::CopyChannel) => {
+                if let MixerSelection::Instrument(idx) = state.session.mixer.selection {
+                    self.copied_channel = state.instruments.instruments.get(idx).map(|i| i.id);
+                }
+                Action::None
+            }
+            ActionId::Mixer(MixerActionId::PasteChannel) => {
+                if let (Some(source), MixerSelection::Instrument(idx)) = (self.copied_channel, state.session.mixer.selection) {
+                    if let Some(target) = state.instruments.instruments.get(idx) {
+                        if target.id != source && state.instruments.instrument(source).is_some() {
+                            self.pending_paste = Some((source, target.id));
+                        }
+                    }
+                }
+                Action::None
+            }
+            ActionId::Mixer(MixerActionId::TypeLevel) => self.start_typing_level(state),
+            ActionId::Mixer(MixerActionId::Zoom) => {
+                self.zoom = self.zoom.next();
+                Action::None
+            }
+            ActionId::Mixer(MixerActionId::Jump) => self.start_jump(),
+            ActionId::Mixer(MixerActionId::RenameBus) => {
+                let MixerSelection::Bus(bus_id) = state.session.mixer.selection else {
+                    return Action::None;
+                };
+                let Some(bus) = state.session.mixer.buses.iter().find(|b| b.id == bus_id) else {
+                    return Action::None;
+                };
+                self.rename_input.set_value(&bus.name);
+                self.rename_input.select_all();
+                self.rename_input.set_focused(true);
+                self.renaming_bus = Some(bus_id);
+                Action::PushLayer("text_edit")
+            }
+            ActionId::Mixer(MixerActionId::BusColor) => {
+                let MixerSelection::Bus(bus_id) = state.session.mixer.selection else {
+                    return Action::None;
+                };
+                match state.session.mixer.buses.iter().find(|b| b.id == bus_id) {
+                    Some(bus) => {
+                        let next = (bus.color as usize + 1) % BUS_COLORS.len();
+                        Action::Mixer(MixerAction::SetBusColor(bus_id, next as u8))
+                    }
+                    None => Action::None,
+                }
+            }
             ActionId::Mixer(MixerActionId::ClearSend) | ActionId::Mixer(MixerActionId::Escape) => { self.send_target = None; Action::None }
             ActionId::Mixer(MixerActionId::EnterDetail) => {
                 if let MixerSelection::Instrument(idx) = state.session.mixer.selection {
@@ -91,11 +195,8 @@ impl MixerPane {
     }
 
     pub(super) fn handle_mouse_impl(&mut self, event: &MouseEvent, area: Rect, state: &AppState) -> Action {
-        let box_width = (NUM_VISIBLE_CHANNELS as u16 * CHANNEL_WIDTH) + 2 +
-                        (NUM_VISIBLE_BUSES as u16 * CHANNEL_WIDTH) + 2 +
-                        CHANNEL_WIDTH + 4;
-        let box_height = METER_HEIGHT + 8;
-        let rect = center_rect(area, box_width, box_height);
+        let layout = self.strip_layout(area);
+        let rect = layout.rect;
         let base_x = rect.x + 2;
 
         let col = event.column;
@@ -109,7 +210,7 @@ impl MixerPane {
         // Calculate scroll offsets (same as render)
         let instrument_scroll = match state.session.mixer.selection {
             MixerSelection::Instrument(idx) => {
-                Self::calc_scroll_offset(idx, state.instruments.instruments.len(), NUM_VISIBLE_CHANNELS)
+                Self::calc_scroll_offset(idx, state.instruments.instruments.len(), layout.visible_channels)
             }
             _ => 0,
         };
@@ -123,9 +224,9 @@ impl MixerPane {
         match event.kind {
             MouseEventKind::Down(MouseButton::Left) => {
                 // Instrument channels region
-                let inst_end_x = base_x + (NUM_VISIBLE_CHANNELS as u16 * CHANNEL_WIDTH);
+                let inst_end_x = base_x + (layout.visible_channels as u16 * layout.channel_width);
                 if col >= base_x && col < inst_end_x {
-                    let channel = ((col - base_x) / CHANNEL_WIDTH) as usize;
+                    let channel = ((col - base_x) / layout.channel_width) as usize;
                     let idx = instrument_scroll + channel;
                     if idx < state.instruments.instruments.len() {
                         self.send_target = None;
@@ -247,6 +348,10 @@ impl MixerPane {
                 }
                 Action::None
             }
+            ActionId::Mixer(MixerActionId::BypassChain) => {
+                Action::Instrument(InstrumentAction::ToggleChainBypass(inst_id))
+            }
+            ActionId::Mixer(MixerActionId::GainMatch) => Action::Mixer(MixerAction::ToggleGainMatch),
             ActionId::Mixer(MixerActionId::ToggleFilter) => {
                 Action::Instrument(InstrumentAction::ToggleFilter(inst_id))
             }
@@ -301,7 +406,7 @@ impl MixerPane {
             ActionId::Mixer(MixerActionId::SendToggle) => {
                 if self.detail_section == MixerSection::Sends {
                     if let Some((_, inst)) = self.detail_instrument(state) {
-                        if let Some(send) = inst.sends.get(self.detail_cursor) {
+                        if let Some(send) = inst.sends.get(self.detail_cursor / SEND_ROWS) {
                             return Action::Mixer(MixerAction::ToggleSend(send.bus_id));
                         }
                     }
@@ -322,8 +427,12 @@ impl MixerPane {
             }
             MixerSection::Sends => {
                 if let Some((_, inst)) = self.detail_instrument(state) {
-                    if let Some(send) = inst.sends.get(self.detail_cursor) {
-                        return Action::Mixer(MixerAction::AdjustSend(send.bus_id, delta * 0.01));
+                    if let Some(send) = inst.sends.get(self.detail_cursor / SEND_ROWS) {
+                        return Action::Mixer(match self.detail_cursor % SEND_ROWS {
+                            0 => MixerAction::AdjustSend(send.bus_id, delta * 0.01),
+                            1 => MixerAction::AdjustSendLowCut(send.bus_id, delta),
+                            _ => MixerAction::AdjustSendHighCut(send.bus_id, delta),
+                        });
                     }
                 }
                 Action::None
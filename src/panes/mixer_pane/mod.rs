@@ -1,11 +1,18 @@
 mod input;
 mod rendering;
+mod tilt;
 
 use std::any::Any;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
-use crate::state::{AppState, InstrumentId};
-use crate::ui::{Rect, RenderBuf, Action, InputEvent, Keymap, MouseEvent, Pane};
+use crate::state::{AppState, InstrumentId, MixerSelection};
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, MixerAction, MouseEvent, Pane};
 use crate::ui::action_id::ActionId;
+use crate::ui::layout_helpers::center_rect;
+use crate::ui::widgets::TextInput;
+use crate::value_entry;
+use crate::voice_activity::VoiceCount;
 
 const CHANNEL_WIDTH: u16 = 8;
 const METER_HEIGHT: u16 = 12;
@@ -15,6 +22,51 @@ const NUM_VISIBLE_BUSES: usize = 2;
 /// Block characters for vertical meter
 const BLOCK_CHARS: [char; 8] = ['\u{2581}', '\u{2582}', '\u{2583}', '\u{2584}', '\u{2585}', '\u{2586}', '\u{2587}', '\u{2588}'];
 
+/// Bus colors, indexed by `MixerBus::color`. Index 0 is the default.
+const BUS_COLORS: [Color; 7] = [
+    Color::PURPLE, Color::TEAL, Color::ORANGE, Color::PINK, Color::LIME, Color::SKY_BLUE, Color::GOLD,
+];
+
+/// Momentary solo releases this long after the last key repeat. Longer
+/// than the usual initial key-repeat delay, since terminals don't report
+/// key releases.
+const MOMENTARY_HOLD: Duration = Duration::from_millis(600);
+
+/// Cursor stops per send in the detail view: level, low cut, high cut
+const SEND_ROWS: usize = 3;
+
+/// Send filter cutoffs at these extremes are open, shown as "off"
+const SEND_LOW_CUT_OFF: f32 = 20.0;
+const SEND_HIGH_CUT_OFF: f32 = 20_000.0;
+
+/// Short label for a send filter cutoff
+fn cut_label(hz: f32, open: bool) -> String {
+    if open {
+        "off".to_string()
+    } else if hz >= 1000.0 {
+        format!("{:.1}k", hz / 1000.0)
+    } else {
+        format!("{:.0}", hz)
+    }
+}
+
+/// Band gain change, in dB, that fills a band activity meter cell
+const BAND_METER_RANGE_DB: f32 = 12.0;
+
+/// Band activity cells for a multiband compressor's low, mid and high
+/// gain change in dB: orange for reduction, lime for upward gain
+fn band_meter(change_db: [f32; 3]) -> [(char, Color); 3] {
+    change_db.map(|db| {
+        let level = (db.abs() / BAND_METER_RANGE_DB).clamp(0.0, 1.0);
+        let ch = if level < 0.05 { '\u{00B7}' } else { BLOCK_CHARS[((level * 7.0).round() as usize).min(7)] };
+        (ch, if db > 0.0 { Color::LIME } else { Color::ORANGE })
+    })
+}
+
+fn bus_color(index: u8) -> Color {
+    BUS_COLORS[index as usize % BUS_COLORS.len()]
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum MixerSection {
     Effects,
@@ -56,6 +108,50 @@ impl MixerSection {
     }
 }
 
+/// What a typed level applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LevelTarget {
+    /// The selected channel's fader
+    Channel,
+    /// The selected instrument's send to a bus
+    Send(u8),
+}
+
+/// Width of instrument strips
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MixerZoom {
+    /// Narrow strips, as many as fit on screen
+    Compact,
+    Normal,
+    /// Wide strips with longer names, as many as fit on screen
+    Wide,
+}
+
+impl MixerZoom {
+    fn next(self) -> Self {
+        match self {
+            MixerZoom::Normal => MixerZoom::Compact,
+            MixerZoom::Compact => MixerZoom::Wide,
+            MixerZoom::Wide => MixerZoom::Normal,
+        }
+    }
+
+    fn channel_width(self) -> u16 {
+        match self {
+            MixerZoom::Compact => 5,
+            MixerZoom::Normal => CHANNEL_WIDTH,
+            MixerZoom::Wide => 12,
+        }
+    }
+}
+
+/// Mixer box and instrument strips for the current zoom
+struct StripLayout {
+    rect: Rect,
+    channel_width: u16,
+    visible_channels: usize,
+}
+
 pub struct MixerPane {
     keymap: Keymap,
     send_target: Option<u8>,
@@ -63,6 +159,26 @@ pub struct MixerPane {
     detail_section: MixerSection,
     detail_cursor: usize,
     effect_scroll: usize,
+    /// Bus being renamed inline
+    renaming_bus: Option<u8>,
+    rename_input: TextInput,
+    /// Level being typed inline
+    typing_level: Option<LevelTarget>,
+    level_input: TextInput,
+    zoom: MixerZoom,
+    /// Instrument number or name being typed to jump to
+    jumping: bool,
+    jump_input: TextInput,
+    /// Latest spectrum bands per bus, fed by main.rs while the mixer is shown
+    bus_spectrum: Vec<(u8, Vec<f32>)>,
+    /// Live voice counts per instrument, fed by main.rs
+    voices: HashMap<InstrumentId, VoiceCount>,
+    /// Instrument whose channel settings were copied
+    copied_channel: Option<InstrumentId>,
+    /// (source, target) paste waiting for main.rs to open the paste popup
+    pending_paste: Option<(InstrumentId, InstrumentId)>,
+    /// Channel soloed by a held key, and when the key was last seen
+    momentary_solo: Option<(MixerSelection, Instant)>,
 }
 
 impl MixerPane {
@@ -74,7 +190,174 @@ impl MixerPane {
             detail_section: MixerSection::Effects,
             detail_cursor: 0,
             effect_scroll: 0,
+            renaming_bus: None,
+            rename_input: TextInput::new(""),
+            typing_level: None,
+            level_input: TextInput::new(""),
+            zoom: MixerZoom::Normal,
+            jumping: false,
+            jump_input: TextInput::new(""),
+            bus_spectrum: Vec::new(),
+            voices: HashMap::new(),
+            copied_channel: None,
+            pending_paste: None,
+            momentary_solo: None,
+        }
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.renaming_bus.is_some() || self.typing_level.is_some() || self.jumping
+    }
+
+    /// Box and strip sizes for `area`; shared by rendering and mouse hit-testing
+    fn strip_layout(&self, area: Rect) -> StripLayout {
+        let channel_width = self.zoom.channel_width();
+        // Separators, buses, master and borders
+        let fixed = 2 + (NUM_VISIBLE_BUSES as u16 * CHANNEL_WIDTH) + 2 + CHANNEL_WIDTH + 4;
+        let visible_channels = match self.zoom {
+            MixerZoom::Normal => NUM_VISIBLE_CHANNELS,
+            _ => ((area.width.saturating_sub(fixed) / channel_width) as usize).max(1),
+        };
+        let box_width = visible_channels as u16 * channel_width + fixed;
+        StripLayout {
+            rect: center_rect(area, box_width, METER_HEIGHT + 8),
+            channel_width,
+            visible_channels,
+        }
+    }
+
+    fn start_jump(&mut self) -> Action {
+        self.jump_input.set_value("");
+        self.jump_input.set_focused(true);
+        self.jumping = true;
+        Action::PushLayer("text_edit")
+    }
+
+    /// Select the instrument whose number or name matches the typed text
+    fn finish_jump(&mut self, confirm: bool, state: &AppState) -> Action {
+        if !std::mem::take(&mut self.jumping) {
+            return Action::None;
+        }
+        self.jump_input.set_focused(false);
+        if !confirm {
+            return Action::None;
+        }
+        let query = self.jump_input.value().trim().to_lowercase();
+        if query.is_empty() {
+            return Action::None;
+        }
+        let instruments = &state.instruments.instruments;
+        let by_id = query.trim_start_matches('i').parse::<InstrumentId>().ok()
+            .and_then(|id| instruments.iter().position(|i| i.id == id));
+        let found = by_id.or_else(|| instruments.iter().position(|i| i.name.to_lowercase().contains(&query)));
+        match found {
+            Some(idx) => {
+                self.send_target = None;
+                Action::Mixer(MixerAction::SelectAt(MixerSelection::Instrument(idx)))
+            }
+            None => Action::None,
+        }
+    }
+
+    /// Current level of what a typed level applies to
+    fn target_level(target: LevelTarget, state: &AppState) -> Option<f32> {
+        let mixer = &state.session.mixer;
+        match (target, mixer.selection) {
+            (LevelTarget::Channel, MixerSelection::Instrument(idx)) => {
+                state.instruments.instruments.get(idx).map(|i| i.level)
+            }
+            (LevelTarget::Channel, MixerSelection::Bus(id)) => mixer.buses.iter().find(|b| b.id == id).map(|b| b.level),
+            (LevelTarget::Channel, MixerSelection::Master) => Some(mixer.master_level),
+            (LevelTarget::Send(bus_id), MixerSelection::Instrument(idx)) => state.instruments.instruments.get(idx)
+                .and_then(|i| i.sends.iter().find(|s| s.bus_id == bus_id))
+                .map(|s| s.level),
+            _ => None,
+        }
+    }
+
+    fn start_typing_level(&mut self, state: &AppState) -> Action {
+        let target = match self.send_target {
+            Some(bus_id) => LevelTarget::Send(bus_id),
+            None => LevelTarget::Channel,
+        };
+        let Some(level) = Self::target_level(target, state) else {
+            return Action::None;
+        };
+        let current = match target {
+            LevelTarget::Channel if level > 0.0 => format!("{:.1}", 20.0 * level.log10()),
+            LevelTarget::Channel => "-99".to_string(),
+            LevelTarget::Send(_) => format!("{:.0}", level * 100.0),
+        };
+        self.level_input.set_value(&current);
+        self.level_input.select_all();
+        self.level_input.set_focused(true);
+        self.typing_level = Some(target);
+        Action::PushLayer("text_edit")
+    }
+
+    /// Apply a typed level as an adjustment from the current one
+    fn finish_typing_level(&mut self, confirm: bool, state: &AppState) -> Action {
+        let Some(target) = self.typing_level.take() else {
+            return Action::None;
+        };
+        self.level_input.set_focused(false);
+        let text = self.level_input.value().to_string();
+        let typed = match target {
+            LevelTarget::Channel => value_entry::parse_level(&text),
+            LevelTarget::Send(_) => value_entry::parse_percent(&text),
+        };
+        let (Some(typed), Some(current), true) = (typed, Self::target_level(target, state), confirm) else {
+            return Action::None;
+        };
+        let delta = typed.clamp(0.0, 1.0) - current;
+        match target {
+            LevelTarget::Channel => Action::Mixer(MixerAction::AdjustLevel(delta)),
+            LevelTarget::Send(bus_id) => Action::Mixer(MixerAction::AdjustSend(bus_id, delta)),
+        }
+    }
+
+    fn finish_rename(&mut self, confirm: bool) -> Action {
+        let Some(bus_id) = self.renaming_bus.take() else {
+            return Action::None;
+        };
+        self.rename_input.set_focused(false);
+        let name = self.rename_input.value().trim().to_string();
+        if confirm && !name.is_empty() {
+            Action::Mixer(MixerAction::RenameBus(bus_id, name))
+        } else {
+            Action::None
+        }
+    }
+
+    /// Requested channel paste since the last call, if any
+    pub fn take_paste(&mut self) -> Option<(InstrumentId, InstrumentId)> {
+        self.pending_paste.take()
+    }
+
+    /// The un-solo action once the momentary solo key has been released
+    pub fn expire_momentary_solo(&mut self, now: Instant) -> Option<Action> {
+        let (selection, last_seen) = self.momentary_solo?;
+        if now.duration_since(last_seen) < MOMENTARY_HOLD {
+            return None;
         }
+        self.momentary_solo = None;
+        Some(Action::Mixer(MixerAction::SetSoloAt(selection, false)))
+    }
+
+    fn is_soloed(state: &AppState, selection: MixerSelection) -> bool {
+        match selection {
+            MixerSelection::Instrument(idx) => state.instruments.instruments.get(idx).is_some_and(|i| i.solo),
+            MixerSelection::Bus(id) => state.session.mixer.buses.iter().any(|b| b.id == id && b.solo),
+            MixerSelection::Master => true,
+        }
+    }
+
+    pub fn set_bus_spectrum(&mut self, bands: Vec<(u8, Vec<f32>)>) {
+        self.bus_spectrum = bands;
+    }
+
+    pub fn set_voice_activity(&mut self, voices: HashMap<InstrumentId, VoiceCount>) {
+        self.voices = voices;
     }
 
     #[allow(dead_code)]
@@ -106,7 +389,7 @@ impl MixerPane {
                     count.saturating_sub(1)
                 }
             }
-            MixerSection::Sends => inst.sends.len().saturating_sub(1),
+            MixerSection::Sends => (inst.sends.len() * SEND_ROWS).saturating_sub(1),
             MixerSection::Filter => {
                 if inst.filter.is_some() { 2 } else { 0 }
             }
@@ -149,6 +432,17 @@ impl Pane for MixerPane {
         self.handle_mouse_impl(event, area, state)
     }
 
+    fn handle_raw_input(&mut self, event: &InputEvent, _state: &AppState) -> Action {
+        if self.renaming_bus.is_some() {
+            self.rename_input.handle_input(event);
+        } else if self.typing_level.is_some() {
+            self.level_input.handle_input(event);
+        } else if self.jumping {
+            self.jump_input.handle_input(event);
+        }
+        Action::None
+    }
+
     fn render(&mut self, area: Rect, buf: &mut RenderBuf, state: &AppState) {
         if self.detail_mode.is_some() {
             self.render_detail_buf(buf, area, state);
@@ -215,4 +509,153 @@ mod tests {
         assert!(matches!(action, Action::Mixer(MixerAction::Move(1))));
         assert_eq!(pane.send_target, None);
     }
+
+    #[test]
+    fn automation_mode_only_for_instrument_channels() {
+        use crate::state::MixerSelection;
+        let mut pane = MixerPane::new(Keymap::new());
+        let mut state = AppState::new();
+
+        state.session.mixer.selection = MixerSelection::Master;
+        let action = pane.handle_action(ActionId::Mixer(MixerActionId::AutomationMode), &dummy_event(), &state);
+        assert!(matches!(action, Action::None));
+
+        state.session.mixer.selection = MixerSelection::Instrument(0);
+        let action = pane.handle_action(ActionId::Mixer(MixerActionId::AutomationMode), &dummy_event(), &state);
+        assert!(matches!(action, Action::Mixer(MixerAction::CycleAutomationMode)));
+    }
+
+    #[test]
+    fn rename_and_color_apply_to_selected_bus() {
+        use crate::state::MixerSelection;
+        use crate::ui::action_id::ModeActionId;
+        let mut pane = MixerPane::new(Keymap::new());
+        let mut state = AppState::new();
+
+        state.session.mixer.selection = MixerSelection::Instrument(0);
+        let action = pane.handle_action(ActionId::Mixer(MixerActionId::RenameBus), &dummy_event(), &state);
+        assert!(matches!(action, Action::None));
+        assert!(!pane.is_editing());
+
+        state.session.mixer.selection = MixerSelection::Bus(2);
+        let action = pane.handle_action(ActionId::Mixer(MixerActionId::RenameBus), &dummy_event(), &state);
+        assert!(matches!(action, Action::PushLayer("text_edit")));
+        pane.rename_input.set_value(" Drums ");
+        let action = pane.handle_action(ActionId::Mode(ModeActionId::TextConfirm), &dummy_event(), &state);
+        assert!(matches!(action, Action::Mixer(MixerAction::RenameBus(2, ref name)) if name == "Drums"));
+        assert!(!pane.is_editing());
+
+        let action = pane.handle_action(ActionId::Mixer(MixerActionId::BusColor), &dummy_event(), &state);
+        assert!(matches!(action, Action::Mixer(MixerAction::SetBusColor(2, 1))));
+    }
+
+    #[test]
+    fn jump_selects_by_number_or_name() {
+        use crate::state::SourceType;
+        use crate::ui::action_id::ModeActionId;
+        let mut pane = MixerPane::new(Keymap::new());
+        let mut state = AppState::new();
+        state.add_instrument(SourceType::Saw);
+        state.add_instrument(SourceType::Kit);
+        let kit_id = state.instruments.instruments[1].id;
+        state.instruments.instruments[1].name = "Drums".to_string();
+
+        pane.handle_action(ActionId::Mixer(MixerActionId::Jump), &dummy_event(), &state);
+        pane.jump_input.set_value(&kit_id.to_string());
+        let action = pane.handle_action(ActionId::Mode(ModeActionId::TextConfirm), &dummy_event(), &state);
+        assert!(matches!(action, Action::Mixer(MixerAction::SelectAt(MixerSelection::Instrument(1)))));
+
+        pane.handle_action(ActionId::Mixer(MixerActionId::Jump), &dummy_event(), &state);
+        pane.jump_input.set_value("drum");
+        let action = pane.handle_action(ActionId::Mode(ModeActionId::TextConfirm), &dummy_event(), &state);
+        assert!(matches!(action, Action::Mixer(MixerAction::SelectAt(MixerSelection::Instrument(1)))));
+        assert!(!pane.is_editing());
+    }
+
+    #[test]
+    fn typed_level_adjusts_from_current() {
+        use crate::ui::action_id::ModeActionId;
+        let mut pane = MixerPane::new(Keymap::new());
+        let mut state = AppState::new();
+        state.session.mixer.selection = MixerSelection::Master;
+        state.session.mixer.master_level = 0.5;
+
+        let action = pane.handle_action(ActionId::Mixer(MixerActionId::TypeLevel), &dummy_event(), &state);
+        assert!(matches!(action, Action::PushLayer("text_edit")));
+        assert!(pane.is_editing());
+        pane.level_input.set_value("0");
+        let action = pane.handle_action(ActionId::Mode(ModeActionId::TextConfirm), &dummy_event(), &state);
+        assert!(matches!(action, Action::Mixer(MixerAction::AdjustLevel(d)) if (d - 0.5).abs() < 1e-6));
+        assert!(!pane.is_editing());
+    }
+
+    #[test]
+    fn paste_needs_a_copied_channel() {
+        use crate::state::{MixerSelection, SourceType};
+        let mut pane = MixerPane::new(Keymap::new());
+        let mut state = AppState::new();
+        let a = state.add_instrument(SourceType::Saw);
+        let b = state.add_instrument(SourceType::Sin);
+
+        state.session.mixer.selection = MixerSelection::Instrument(1);
+        pane.handle_action(ActionId::Mixer(MixerActionId::PasteChannel), &dummy_event(), &state);
+        assert_eq!(pane.take_paste(), None);
+
+        state.session.mixer.selection = MixerSelection::Instrument(0);
+        pane.handle_action(ActionId::Mixer(MixerActionId::CopyChannel), &dummy_event(), &state);
+        state.session.mixer.selection = MixerSelection::Instrument(1);
+        pane.handle_action(ActionId::Mixer(MixerActionId::PasteChannel), &dummy_event(), &state);
+        assert_eq!(pane.take_paste(), Some((a, b)));
+    }
+
+    #[test]
+    fn momentary_solo_releases_after_hold() {
+        use crate::state::SourceType;
+        let mut pane = MixerPane::new(Keymap::new());
+        let mut state = AppState::new();
+        state.add_instrument(SourceType::Saw);
+        state.session.mixer.selection = MixerSelection::Instrument(0);
+
+        let action = pane.handle_action(ActionId::Mixer(MixerActionId::MomentarySolo), &dummy_event(), &state);
+        assert!(matches!(action, Action::Mixer(MixerAction::SetSoloAt(MixerSelection::Instrument(0), true))));
+        // Key repeat keeps it held
+        let action = pane.handle_action(ActionId::Mixer(MixerActionId::MomentarySolo), &dummy_event(), &state);
+        assert!(matches!(action, Action::None));
+
+        let now = Instant::now();
+        assert!(pane.expire_momentary_solo(now).is_none());
+        let released = pane.expire_momentary_solo(now + MOMENTARY_HOLD);
+        assert!(matches!(released, Some(Action::Mixer(MixerAction::SetSoloAt(MixerSelection::Instrument(0), false)))));
+        assert!(pane.expire_momentary_solo(now + MOMENTARY_HOLD * 2).is_none());
+    }
+
+    #[test]
+    fn gain_match_toggles_in_overview_and_detail() {
+        let mut pane = MixerPane::new(Keymap::new());
+        let mut state = AppState::new();
+        let action = pane.handle_action(ActionId::Mixer(MixerActionId::GainMatch), &dummy_event(), &state);
+        assert!(matches!(action, Action::Mixer(MixerAction::ToggleGainMatch)));
+
+        let id = state.add_instrument(crate::state::SourceType::Saw);
+        pane.detail_mode = Some(0);
+        let action = pane.handle_action(ActionId::Mixer(MixerActionId::GainMatch), &dummy_event(), &state);
+        assert!(matches!(action, Action::Mixer(MixerAction::ToggleGainMatch)));
+        let action = pane.handle_action(ActionId::Mixer(MixerActionId::BypassChain), &dummy_event(), &state);
+        assert!(matches!(action, Action::Instrument(crate::ui::InstrumentAction::ToggleChainBypass(got)) if got == id));
+    }
+
+    #[test]
+    fn send_cutoffs_read_short() {
+        assert_eq!(cut_label(SEND_LOW_CUT_OFF, true), "off");
+        assert_eq!(cut_label(150.0, false), "150");
+        assert_eq!(cut_label(8500.0, false), "8.5k");
+    }
+
+    #[test]
+    fn band_meter_tells_reduction_from_upward_gain() {
+        let cells = band_meter([-12.0, 0.0, 6.0]);
+        assert_eq!(cells[0], (BLOCK_CHARS[7], Color::ORANGE));
+        assert_eq!(cells[1].0, '\u{00B7}');
+        assert_eq!(cells[2].1, Color::LIME);
+    }
 }
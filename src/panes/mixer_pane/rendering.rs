@@ -1,8 +1,13 @@
-use super::{MixerPane, MixerSection};
-use super::{CHANNEL_WIDTH, METER_HEIGHT, NUM_VISIBLE_CHANNELS, NUM_VISIBLE_BUSES, BLOCK_CHARS};
-use crate::state::{AppState, MixerSelection, OutputTarget};
+use super::tilt::Tilt;
+use super::{band_meter, bus_color, cut_label, MixerPane, MixerSection};
+use super::{CHANNEL_WIDTH, METER_HEIGHT, NUM_VISIBLE_BUSES, BLOCK_CHARS};
+use super::{SEND_HIGH_CUT_OFF, SEND_LOW_CUT_OFF, SEND_ROWS};
+use crate::param_units::Unit;
+use crate::state::automation::AutomationMode;
+use crate::state::{AppState, EffectType, MixerSelection, OutputTarget};
 use crate::ui::{Rect, RenderBuf, Color, Style};
 use crate::ui::layout_helpers::center_rect;
+use crate::voice_activity::VoiceCount;
 
 impl MixerPane {
     fn level_to_db(level: f32) -> String {
@@ -40,20 +45,80 @@ impl MixerPane {
         }
     }
 
+    fn automation_mode_label(mode: AutomationMode) -> Option<(&'static str, Color)> {
+        match mode {
+            AutomationMode::Off => None,
+            AutomationMode::Read => Some(("R", Color::METER_LOW)),
+            AutomationMode::Touch => Some(("T", Color::METER_MID)),
+            AutomationMode::Latch => Some(("L", Color::ORANGE)),
+            AutomationMode::Write => Some(("W", Color::METER_HIGH)),
+        }
+    }
+
     fn write_str(buf: &mut RenderBuf, x: u16, y: u16, text: &str, style: Style) {
         for (i, ch) in text.chars().enumerate() {
             buf.set_cell(x + i as u16, y, ch, style);
         }
     }
 
-    pub(super) fn render_mixer_buf(&self, buf: &mut RenderBuf, area: Rect, state: &AppState) {
-        let box_width = (NUM_VISIBLE_CHANNELS as u16 * CHANNEL_WIDTH) + 2 +
-                        (NUM_VISIBLE_BUSES as u16 * CHANNEL_WIDTH) + 2 +
-                        CHANNEL_WIDTH + 4;
-        let box_height = METER_HEIGHT + 8;
-        let rect = center_rect(area, box_width, box_height);
+    /// Typed level over the selected channel's dB readout
+    fn render_level_input(&self, buf: &mut RenderBuf, x: u16, y: u16, width: u16) {
+        if self.typing_level.is_some() {
+            self.level_input.render_buf(buf.raw_buf(), x, y, width.saturating_sub(1));
+        }
+    }
+
+    /// Marker for channels that stay audible when others are soloed
+    fn render_solo_safe_buf(buf: &mut RenderBuf, x: u16, y: u16, solo_safe: bool) {
+        if solo_safe {
+            buf.set_cell(x + 4, y, '\u{25c7}', Style::new().fg(Color::SOLO_COLOR));
+        }
+    }
 
-        buf.draw_block(rect, " MIXER ", Style::new().fg(Color::CYAN), Style::new().fg(Color::CYAN));
+    /// Voice count at the right edge of a strip, in the warning color
+    /// while voices are being stolen. Blank when idle or too narrow.
+    fn render_voices_buf(buf: &mut RenderBuf, x: u16, y: u16, width: u16, voices: VoiceCount) {
+        if voices.voices == 0 && !voices.stealing {
+            return;
+        }
+        let text = format!("{}v", voices.voices);
+        let len = text.chars().count() as u16;
+        // Leave the output routing readable
+        if len + 5 > width {
+            return;
+        }
+        let style = if voices.stealing {
+            Style::new().fg(Color::METER_HIGH).bold()
+        } else {
+            Style::new().fg(Color::LIME)
+        };
+        Self::write_str(buf, x + width - 1 - len, y, &text, style);
+    }
+
+    /// Low/mid/high balance as three block characters, with a warning
+    /// marker when the balance is off. Blank when silent.
+    fn render_tilt_buf(buf: &mut RenderBuf, x: u16, y: u16, bands: &[f32]) {
+        let tilt = Tilt::from_bands(bands);
+        if tilt.is_silent() {
+            return;
+        }
+        let regions = [(tilt.low, Color::ORANGE), (tilt.mid, Color::METER_LOW), (tilt.high, Color::SKY_BLUE)];
+        for (i, (share, color)) in regions.iter().enumerate() {
+            let level = ((share * BLOCK_CHARS.len() as f32) as usize).min(BLOCK_CHARS.len() - 1);
+            buf.set_cell(x + i as u16, y, BLOCK_CHARS[level], Style::new().fg(*color));
+        }
+        if tilt.warning().is_some() {
+            buf.set_cell(x + 3, y, '!', Style::new().fg(Color::METER_HIGH).bold());
+        }
+    }
+
+    pub(super) fn render_mixer_buf(&mut self, buf: &mut RenderBuf, area: Rect, state: &AppState) {
+        let layout = self.strip_layout(area);
+        let rect = layout.rect;
+        let channel_w = layout.channel_width;
+
+        let title = if state.session.mixer.exclusive_solo { " MIXER [excl solo] " } else { " MIXER " };
+        buf.draw_block(rect, title, Style::new().fg(Color::CYAN), Style::new().fg(Color::CYAN));
 
         let base_x = rect.x + 2;
         let base_y = rect.y + 1;
@@ -68,7 +133,7 @@ impl MixerPane {
         // Calculate scroll offsets
         let instrument_scroll = match state.session.mixer.selection {
             MixerSelection::Instrument(idx) => {
-                Self::calc_scroll_offset(idx, state.instruments.instruments.len(), NUM_VISIBLE_CHANNELS)
+                Self::calc_scroll_offset(idx, state.instruments.instruments.len(), layout.visible_channels)
             }
             _ => 0,
         };
@@ -83,7 +148,7 @@ impl MixerPane {
         let mut x = base_x;
 
         // Render instrument channels
-        for i in 0..NUM_VISIBLE_CHANNELS {
+        for i in 0..layout.visible_channels {
             let idx = instrument_scroll + i;
             if idx < state.instruments.instruments.len() {
                 let instrument = &state.instruments.instruments[idx];
@@ -95,18 +160,47 @@ impl MixerPane {
                     format!("I{}", instrument.id)
                 };
                 Self::render_channel_buf(
-                    buf, x, &label, &instrument.name,
-                    instrument.level, instrument.mute, instrument.solo, Some(instrument.output_target), is_selected,
+                    buf, x, channel_w, &label, &instrument.name,
+                    instrument.level, instrument.mute, instrument.solo, Some(instrument.output_target),
+                    Some(instrument.automation_mode), is_selected,
                     label_y, name_y, meter_top_y, db_y, indicator_y, output_y,
                 );
+                Self::render_solo_safe_buf(buf, x, indicator_y, instrument.solo_safe);
+                if is_selected {
+                    self.render_level_input(buf, x, db_y, channel_w);
+                }
+
+                // Channels routed to a bus show the bus color on their output
+                if let (OutputTarget::Bus(bus_id), false) = (instrument.output_target, is_selected) {
+                    if let Some(bus) = state.session.mixer.buses.iter().find(|b| b.id == bus_id) {
+                        let out = Self::format_output(instrument.output_target);
+                        Self::write_str(buf, x, output_y, out, Style::new().fg(bus_color(bus.color)));
+                    }
+                }
+                if let Some(&voices) = self.voices.get(&instrument.id) {
+                    Self::render_voices_buf(buf, x, output_y, channel_w, voices);
+                }
             } else {
                 Self::render_empty_channel_buf(
-                    buf, x, &format!("I{}", idx + 1),
+                    buf, x, channel_w, &format!("I{}", idx + 1),
                     label_y, name_y, meter_top_y, db_y, indicator_y,
                 );
             }
 
-            x += CHANNEL_WIDTH;
+            x += channel_w;
+        }
+
+        // Channels scrolled off either side, on the bottom border
+        let total = state.instruments.instruments.len();
+        let hidden_right = total.saturating_sub(instrument_scroll + layout.visible_channels);
+        let scroll_style = Style::new().fg(Color::WHITE).bold();
+        let border_y = rect.y + rect.height - 1;
+        if instrument_scroll > 0 {
+            Self::write_str(buf, base_x, border_y, &format!("\u{25c2} {}", instrument_scroll), scroll_style);
+        }
+        if hidden_right > 0 {
+            let text = format!("{} \u{25b8}", hidden_right);
+            Self::write_str(buf, x.saturating_sub(text.chars().count() as u16 + 1), border_y, &text, scroll_style);
         }
 
         // Separator before buses
@@ -126,10 +220,23 @@ impl MixerPane {
             let is_selected = matches!(state.session.mixer.selection, MixerSelection::Bus(id) if id == bus.id);
 
             Self::render_channel_buf(
-                buf, x, &format!("BUS{}", bus.id), &bus.name,
-                bus.level, bus.mute, bus.solo, None, is_selected,
+                buf, x, CHANNEL_WIDTH, &format!("BUS{}", bus.id), &bus.name,
+                bus.level, bus.mute, bus.solo, None, None, is_selected,
                 label_y, name_y, meter_top_y, db_y, indicator_y, output_y,
             );
+            Self::render_solo_safe_buf(buf, x, indicator_y, bus.solo_safe);
+            if is_selected {
+                self.render_level_input(buf, x, db_y, CHANNEL_WIDTH);
+            }
+            if !is_selected {
+                Self::write_str(buf, x, label_y, &format!("BUS{}", bus.id), Style::new().fg(bus_color(bus.color)).bold());
+            }
+            if let Some((_, bands)) = self.bus_spectrum.iter().find(|(id, _)| *id == bus.id) {
+                Self::render_tilt_buf(buf, x, output_y, bands);
+            }
+            if self.renaming_bus == Some(bus.id) {
+                self.rename_input.render_buf(buf.raw_buf(), x, name_y, CHANNEL_WIDTH - 1);
+            }
 
             x += CHANNEL_WIDTH;
         }
@@ -144,10 +251,14 @@ impl MixerPane {
         // Master
         let is_master_selected = matches!(state.session.mixer.selection, MixerSelection::Master);
         Self::render_channel_buf(
-            buf, x, "MASTER", "",
-            state.session.mixer.master_level, state.session.mixer.master_mute, false, None, is_master_selected,
+            buf, x, CHANNEL_WIDTH, "MASTER", "",
+            state.session.mixer.master_level, state.session.mixer.master_mute, false, None, None, is_master_selected,
             label_y, name_y, meter_top_y, db_y, indicator_y, output_y,
         );
+        Self::render_tilt_buf(buf, x, output_y, &state.audio.visualization.spectrum_bands);
+        if is_master_selected {
+            self.render_level_input(buf, x, db_y, CHANNEL_WIDTH);
+        }
 
         // Send info line
         let send_y = output_y + 1;
@@ -166,12 +277,19 @@ impl MixerPane {
             }
         }
 
-        // Help text
+        // Help text, or the jump prompt while typing one
         let help_y = rect.y + rect.height - 2;
-        buf.draw_line(
-            Rect::new(base_x, help_y, rect.width.saturating_sub(4), 1),
-            &[("[\u{2190}/\u{2192}] Select  [\u{2191}/\u{2193}] Level  [M]ute [S]olo [o]ut  [t/T] Send  [g] Toggle", Style::new().fg(Color::DARK_GRAY))],
-        );
+        if self.jumping {
+            let prompt = "Jump to instrument: ";
+            Self::write_str(buf, base_x, help_y, prompt, Style::new().fg(Color::CYAN).bold());
+            let input_x = base_x + prompt.len() as u16;
+            self.jump_input.render_buf(buf.raw_buf(), input_x, help_y, 24);
+        } else {
+            buf.draw_line(
+                Rect::new(base_x, help_y, rect.width.saturating_sub(4), 1),
+                &[("[\u{2190}/\u{2192}] Select  [\u{2191}/\u{2193}] Level  [M]ute [s/S] Solo/safe [o]ut  [t/T] Send  [g] Toggle  [w] Auto  [y/Y] Copy/paste  [r/c] Bus  [z] Zoom  [J]ump", Style::new().fg(Color::DARK_GRAY))],
+            );
+        }
     }
 
     pub(super) fn render_detail_buf(&self, buf: &mut RenderBuf, area: Rect, state: &AppState) {
@@ -222,6 +340,22 @@ impl MixerPane {
         };
         Self::write_str(buf, col1_x, inner_y, "EFFECTS CHAIN", effects_header);
 
+        // Chain bypass and gain-match status, right of the header
+        let mut status_x = col1_x + 14;
+        if inst.chain_bypassed {
+            Self::write_str(buf, status_x, inner_y, "BYP", Style::new().fg(Color::ORANGE).bold());
+            status_x += 4;
+        }
+        if state.session.mixer.gain_match {
+            // Compensation the engine applied for the last bypass change
+            let gm = if inst.gain_match_db.abs() >= 0.05 {
+                format!("GM {:+.1}dB", inst.gain_match_db)
+            } else {
+                "GM".to_string()
+            };
+            Self::write_str(buf, status_x, inner_y, &gm, Style::new().fg(Color::TEAL));
+        }
+
         let mut ey = inner_y + 1;
         let mut cursor_pos = 0;
         for (ei, effect) in inst.effects.iter().enumerate() {
@@ -235,13 +369,26 @@ impl MixerPane {
                 normal
             };
             Self::write_str(buf, col1_x, ey, &effect_label, style);
+
+            // Per-band gain change reported by the audio monitor
+            if matches!(effect.effect_type, EffectType::MultibandComp) {
+                if let Some(change) = state.audio.visualization.band_reduction(inst.id, effect.id) {
+                    let mut mx = col1_x + effect_label.chars().count() as u16 + 1;
+                    for (label, (ch, color)) in ['L', 'M', 'H'].into_iter().zip(band_meter(change)) {
+                        if mx + 2 > col2_x - 1 { break; }
+                        buf.set_cell(mx, ey, label, dim);
+                        buf.set_cell(mx + 1, ey, ch, Style::new().fg(color));
+                        mx += 2;
+                    }
+                }
+            }
             ey += 1;
             cursor_pos += 1;
 
             for (pi, param) in effect.params.iter().take(4).enumerate() {
                 if ey >= inner_y + inner_h { break; }
                 let val_str = match &param.value {
-                    crate::state::ParamValue::Float(v) => format!("{:.2}", v),
+                    crate::state::ParamValue::Float(v) => Unit::of(param).format(*v),
                     crate::state::ParamValue::Int(v) => format!("{}", v),
                     crate::state::ParamValue::Bool(b) => if *b { "ON".to_string() } else { "OFF".to_string() },
                 };
@@ -280,14 +427,21 @@ impl MixerPane {
                 "OFF".to_string()
             };
             let send_text = format!("\u{2192}B{} {} {}", send.bus_id, bar, status);
-            let sstyle = if self.detail_section == MixerSection::Sends && self.detail_cursor == si {
-                selected_style
-            } else if send.enabled {
-                normal
-            } else {
-                dim
+            let low_text = format!(" LC:{}", cut_label(send.low_cut, send.low_cut <= SEND_LOW_CUT_OFF));
+            let high_text = format!(" HC:{}", cut_label(send.high_cut, send.high_cut >= SEND_HIGH_CUT_OFF));
+            let unselected = if send.enabled { normal } else { dim };
+            let style_for = |row: usize| {
+                if self.detail_section == MixerSection::Sends && self.detail_cursor == si * SEND_ROWS + row {
+                    selected_style
+                } else {
+                    unselected
+                }
             };
-            Self::write_str(buf, col2_x, sy, &send_text, sstyle);
+            let mut sx = col2_x;
+            for (row, text) in [send_text, low_text, high_text].iter().enumerate() {
+                Self::write_str(buf, sx, sy, text, style_for(row));
+                sx += text.chars().count() as u16;
+            }
             sy += 1;
         }
 
@@ -432,7 +586,7 @@ impl MixerPane {
 
         // ── Help bar ──
         let help_y = rect.y + rect.height - 2;
-        let help_text = "Tab: Section  \u{2191}/\u{2193}: Nav  PageUp/Dn: Adjust  [a]dd [d]el [e] Bypass  [f]ilter  [p/P] Pan  Esc: Back";
+        let help_text = "Tab: Section  \u{2191}/\u{2193}: Nav  PageUp/Dn: Adjust  [a]dd [d]el [e/E] Bypass [G] Match  [f]ilter  [p/P] Pan  Esc: Back";
         buf.draw_line(
             Rect::new(inner_x, help_y, inner_w, 1),
             &[(help_text, Style::new().fg(Color::DARK_GRAY))],
@@ -459,12 +613,14 @@ impl MixerPane {
     fn render_channel_buf(
         buf: &mut RenderBuf,
         x: u16,
+        width: u16,
         label: &str,
         name: &str,
         level: f32,
         mute: bool,
         solo: bool,
         output: Option<OutputTarget>,
+        auto_mode: Option<AutomationMode>,
         selected: bool,
         label_y: u16,
         name_y: u16,
@@ -473,7 +629,7 @@ impl MixerPane {
         indicator_y: u16,
         output_y: u16,
     ) {
-        let channel_w = (CHANNEL_WIDTH - 1) as usize;
+        let channel_w = (width - 1) as usize;
 
         let label_style = if selected {
             Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG).bold()
@@ -499,7 +655,7 @@ impl MixerPane {
         }
 
         // Vertical meter
-        let meter_x = x + (CHANNEL_WIDTH / 2).saturating_sub(1);
+        let meter_x = x + (width / 2).saturating_sub(1);
         Self::render_meter_buf(buf, meter_x, meter_top_y, METER_HEIGHT, level);
 
         // Selection indicator
@@ -531,6 +687,11 @@ impl MixerPane {
             buf.set_cell(x + j as u16, indicator_y, ch, indicator_style);
         }
 
+        // Automation mode (blank when off)
+        if let Some((mode_str, color)) = auto_mode.and_then(Self::automation_mode_label) {
+            Self::write_str(buf, x + 2, indicator_y, mode_str, Style::new().fg(color).bold());
+        }
+
         // Output routing
         if let Some(target) = output {
             let routing_style = if selected {
@@ -548,6 +709,7 @@ impl MixerPane {
     fn render_empty_channel_buf(
         buf: &mut RenderBuf,
         x: u16,
+        width: u16,
         label: &str,
         label_y: u16,
         name_y: u16,
@@ -555,7 +717,7 @@ impl MixerPane {
         db_y: u16,
         indicator_y: u16,
     ) {
-        let channel_w = (CHANNEL_WIDTH - 1) as usize;
+        let channel_w = (width - 1) as usize;
         let dark_gray = Style::new().fg(Color::DARK_GRAY);
 
         for (j, ch) in label.chars().take(channel_w).enumerate() {
@@ -565,7 +727,7 @@ impl MixerPane {
             buf.set_cell(x + j as u16, name_y, ch, dark_gray);
         }
 
-        let meter_x = x + (CHANNEL_WIDTH / 2).saturating_sub(1);
+        let meter_x = x + (width / 2).saturating_sub(1);
         for row in 0..METER_HEIGHT {
             buf.set_cell(meter_x, meter_top_y + row, '·', dark_gray);
         }
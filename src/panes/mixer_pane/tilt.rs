@@ -0,0 +1,67 @@
+//! Low/mid/high energy balance from the 7-band spectrum analysis.
+
+/// Bands 60 and 150 Hz are low, 400 Hz to 2.5 kHz mid, 6 and 15 kHz high
+const LOW_BANDS: usize = 2;
+const HIGH_START: usize = 5;
+
+/// Low share above which a mix reads as muddy
+const MUDDY_LOW: f32 = 0.55;
+/// High share above which a mix reads as harsh
+const HARSH_HIGH: f32 = 0.40;
+
+/// Share of energy in each region; sums to 1.0 unless silent
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(super) struct Tilt {
+    pub low: f32,
+    pub mid: f32,
+    pub high: f32,
+}
+
+impl Tilt {
+    pub fn from_bands(bands: &[f32]) -> Self {
+        let energy = |range: &[f32]| range.iter().map(|a| a * a).sum::<f32>();
+        let low = energy(&bands[..LOW_BANDS.min(bands.len())]);
+        let mid = energy(bands.get(LOW_BANDS..HIGH_START.min(bands.len())).unwrap_or(&[]));
+        let high = energy(bands.get(HIGH_START..).unwrap_or(&[]));
+        let total = low + mid + high;
+        if total <= f32::EPSILON {
+            return Self::default();
+        }
+        Self { low: low / total, mid: mid / total, high: high / total }
+    }
+
+    pub fn is_silent(&self) -> bool {
+        self.low + self.mid + self.high <= f32::EPSILON
+    }
+
+    /// Short warning when the balance leans too far either way
+    pub fn warning(&self) -> Option<&'static str> {
+        if self.low > MUDDY_LOW {
+            Some("muddy")
+        } else if self.high > HARSH_HIGH {
+            Some("harsh")
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shares_sum_to_one() {
+        let tilt = Tilt::from_bands(&[0.5, 0.4, 0.3, 0.3, 0.2, 0.1, 0.05]);
+        assert!((tilt.low + tilt.mid + tilt.high - 1.0).abs() < 1e-5);
+        assert_eq!(Tilt::from_bands(&[0.0; 7]), Tilt::default());
+        assert!(Tilt::from_bands(&[]).is_silent());
+    }
+
+    #[test]
+    fn flags_bass_heavy_and_bright_mixes() {
+        assert_eq!(Tilt::from_bands(&[0.9, 0.8, 0.2, 0.1, 0.1, 0.05, 0.0]).warning(), Some("muddy"));
+        assert_eq!(Tilt::from_bands(&[0.1, 0.1, 0.2, 0.2, 0.3, 0.7, 0.6]).warning(), Some("harsh"));
+        assert_eq!(Tilt::from_bands(&[0.4, 0.4, 0.4, 0.4, 0.4, 0.3, 0.2]).warning(), None);
+    }
+}
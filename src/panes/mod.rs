@@ -1,49 +1,95 @@
 mod add_effect_pane;
 mod add_pane;
 mod automation_pane;
+mod channel_paste_pane;
+mod clip_inspector_pane;
 mod command_palette_pane;
+mod comp_pane;
 mod confirm_pane;
+mod console_pane;
+mod diagnose_pane;
 mod eq_pane;
 mod file_browser_pane;
 mod frame_edit_pane;
 mod help_pane;
 mod home_pane;
+mod latency_pane;
 mod mixer_pane;
 mod piano_roll_pane;
 mod project_browser_pane;
+mod project_check_pane;
+mod quantize_pane;
 mod save_as_pane;
 mod sequencer_pane;
 mod server_pane;
 mod instrument_edit_pane;
 mod instrument_pane;
+mod routing_pane;
 mod sample_chopper_pane;
+mod sample_library_pane;
+mod project_search_pane;
+mod batch_rename_pane;
+mod export_pane;
+mod batch_export_pane;
+mod render_queue_pane;
+mod sclang_pane;
+mod midi_monitor_pane;
 mod midi_settings_pane;
 mod quit_prompt_pane;
+mod note_generator_pane;
+mod random_looper_pane;
+mod record_settings_pane;
+mod time_edit_pane;
 mod track_pane;
+mod tracker_pane;
 mod vst_param_pane;
+mod undo_history_pane;
 mod waveform_pane;
 
 pub use add_effect_pane::AddEffectPane;
 pub use add_pane::AddPane;
 pub use automation_pane::AutomationPane;
+pub use channel_paste_pane::ChannelPastePane;
+pub use clip_inspector_pane::ClipInspectorPane;
 pub use command_palette_pane::CommandPalettePane;
+pub use comp_pane::CompPane;
 pub use confirm_pane::{ConfirmPane, PendingAction};
+pub use console_pane::ConsolePane;
+pub use diagnose_pane::DiagnosePane;
 pub use eq_pane::EqPane;
 pub use file_browser_pane::FileBrowserPane;
 pub use frame_edit_pane::FrameEditPane;
 pub use help_pane::HelpPane;
 pub use home_pane::HomePane;
+pub use latency_pane::LatencyPane;
 pub use mixer_pane::MixerPane;
 pub use piano_roll_pane::PianoRollPane;
 pub use project_browser_pane::ProjectBrowserPane;
+pub use project_check_pane::ProjectCheckPane;
+pub use quantize_pane::{QuantizePane, QuantizeTarget};
 pub use save_as_pane::SaveAsPane;
-pub use sequencer_pane::SequencerPane;
+pub use sequencer_pane::{pattern_secs, SequencerPane};
 pub use server_pane::ServerPane;
 pub use instrument_edit_pane::InstrumentEditPane;
 pub use instrument_pane::InstrumentPane;
+pub use routing_pane::RoutingPane;
 pub use sample_chopper_pane::SampleChopperPane;
+pub use sample_library_pane::SampleLibraryPane;
+pub use project_search_pane::ProjectSearchPane;
+pub use batch_rename_pane::BatchRenamePane;
+pub use export_pane::{ExportJob, ExportPane, ExportTarget};
+pub use batch_export_pane::BatchExportPane;
+pub use render_queue_pane::{QueueRequest, RenderQueuePane};
+pub use sclang_pane::SclangPane;
+pub use midi_monitor_pane::MidiMonitorPane;
 pub use midi_settings_pane::MidiSettingsPane;
 pub use quit_prompt_pane::QuitPromptPane;
+pub use note_generator_pane::NoteGeneratorPane;
+pub use random_looper_pane::RandomLooperPane;
+pub use record_settings_pane::RecordSettingsPane;
+pub use time_edit_pane::TimeEditPane;
 pub use track_pane::TrackPane;
+pub use tracker_pane::TrackerPane;
 pub use vst_param_pane::VstParamPane;
+pub use undo_history_pane::UndoHistoryPane;
 pub use waveform_pane::WaveformPane;
\ No newline at end of file
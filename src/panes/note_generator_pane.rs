@@ -0,0 +1,447 @@
+use std::any::Any;
+
+use crate::state::music::Scale;
+use crate::state::AppState;
+use crate::ui::action_id::{ActionId, NoteGeneratorActionId};
+use crate::ui::layout_helpers::center_rect;
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, NavAction, Pane, PianoRollAction, Style};
+
+/// Fields editable in the generator popup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Scale,
+    Density,
+    LowOctave,
+    HighOctave,
+    Grid,
+    Bars,
+    Seed,
+}
+
+const FIELDS: [Field; 7] = [
+    Field::Scale,
+    Field::Density,
+    Field::LowOctave,
+    Field::HighOctave,
+    Field::Grid,
+    Field::Bars,
+    Field::Seed,
+];
+
+/// Rhythm grid choices as divisions of a whole note
+const GRIDS: [(u32, &str); 5] = [(2, "1/2"), (4, "1/4"), (8, "1/8"), (12, "1/8T"), (16, "1/16")];
+const BAR_CHOICES: [u32; 4] = [1, 2, 4, 8];
+
+/// Settings for the probability-based melody generator
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MelodySettings {
+    pub scale: Scale,
+    /// Pitch class of the root (0 = C)
+    pub root: u8,
+    /// Chance (0-100) that a grid step gets a note
+    pub density: u8,
+    pub low_octave: u8,
+    pub high_octave: u8,
+    /// Rhythm grid as a division of a whole note (8 = eighths)
+    pub grid: u32,
+    pub bars: u32,
+    pub seed: u32,
+}
+
+impl Default for MelodySettings {
+    fn default() -> Self {
+        Self {
+            scale: Scale::ALL[0],
+            root: 0,
+            density: 60,
+            low_octave: 3,
+            high_octave: 4,
+            grid: 8,
+            bars: 2,
+            seed: 1,
+        }
+    }
+}
+
+/// xorshift32 — deterministic per seed so a melody can be reproduced
+struct Rng(u32);
+
+impl Rng {
+    fn new(seed: u32) -> Self {
+        Rng(seed.wrapping_mul(0x9E37_79B9) | 1)
+    }
+
+    fn next(&mut self) -> u32 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.0 = x;
+        x
+    }
+
+    fn percent(&mut self) -> u8 {
+        (self.next() % 100) as u8
+    }
+}
+
+/// Generate `(pitch, tick, duration, velocity)` notes starting at `start_tick`.
+///
+/// Steps on the rhythm grid are filled with probability `density`. Pitches
+/// random-walk over the scale within the octave range, preferring small steps.
+/// A note followed by a rest is held through it.
+pub(crate) fn generate_melody(
+    settings: &MelodySettings,
+    start_tick: u32,
+    ticks_per_beat: u32,
+    ticks_per_bar: u32,
+) -> Vec<(u8, u32, u32, u8)> {
+    let intervals = settings.scale.intervals();
+    let low = settings.low_octave.min(settings.high_octave) as i32;
+    let high = settings.low_octave.max(settings.high_octave) as i32;
+    // Every scale pitch in range, lowest first (MIDI octave -1 starts at 0)
+    let pitches: Vec<u8> = (low..=high)
+        .flat_map(|oct| intervals.iter().map(move |i| (oct + 1) * 12 + settings.root as i32 + *i as i32))
+        .filter(|p| (0..=127).contains(p))
+        .map(|p| p as u8)
+        .collect();
+    let grid = ticks_per_beat * 4 / settings.grid.max(1);
+    if pitches.is_empty() || grid == 0 {
+        return Vec::new();
+    }
+
+    let mut rng = Rng::new(settings.seed);
+    let steps = settings.bars * ticks_per_bar / grid;
+    let hits: Vec<bool> = (0..steps).map(|_| rng.percent() < settings.density).collect();
+
+    let mut notes = Vec::new();
+    let mut degree = (rng.next() as usize) % pitches.len();
+    for (step, hit) in hits.iter().enumerate() {
+        if !hit {
+            continue;
+        }
+        let rests = hits[step + 1..].iter().take_while(|h| !**h).count() as u32;
+        let held = if rests > 0 && rng.percent() < 50 { 2 } else { 1 };
+        let duration = grid * held.min(rests + 1);
+        let velocity = 80 + (rng.next() % 31) as u8;
+        notes.push((pitches[degree], start_tick + step as u32 * grid, duration, velocity));
+
+        // Weighted walk: mostly steps of one, sometimes leaps
+        let move_by: i32 = match rng.percent() {
+            0..=19 => 0,
+            20..=69 => 1,
+            70..=89 => 2,
+            _ => 4,
+        };
+        let up = rng.next() & 1 == 0;
+        let next = degree as i32 + if up { move_by } else { -move_by };
+        degree = next.clamp(0, pitches.len() as i32 - 1) as usize;
+    }
+    notes
+}
+
+pub struct NoteGeneratorPane {
+    keymap: Keymap,
+    settings: MelodySettings,
+    selected: usize,
+    track: usize,
+    start_tick: u32,
+    /// Piano roll resolution and bar length, as of opening
+    ticks_per_beat: u32,
+    ticks_per_bar: u32,
+}
+
+impl NoteGeneratorPane {
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            settings: MelodySettings::default(),
+            selected: 0,
+            track: 0,
+            start_tick: 0,
+            ticks_per_beat: 480,
+            ticks_per_bar: 1920,
+        }
+    }
+
+    fn current_field(&self) -> Field {
+        FIELDS[self.selected]
+    }
+
+    fn adjust(&mut self, increase: bool) {
+        let s = &mut self.settings;
+        match self.current_field() {
+            Field::Scale => {
+                let idx = Scale::ALL.iter().position(|x| *x == s.scale).unwrap_or(0);
+                let len = Scale::ALL.len();
+                s.scale = Scale::ALL[if increase { (idx + 1) % len } else { (idx + len - 1) % len }];
+            }
+            Field::Density => {
+                let delta: i16 = if increase { 5 } else { -5 };
+                s.density = (s.density as i16 + delta).clamp(0, 100) as u8;
+            }
+            Field::LowOctave => {
+                s.low_octave = if increase { (s.low_octave + 1).min(s.high_octave) } else { s.low_octave.saturating_sub(1) };
+            }
+            Field::HighOctave => {
+                s.high_octave = if increase { (s.high_octave + 1).min(8) } else { s.high_octave.saturating_sub(1).max(s.low_octave) };
+            }
+            Field::Grid => {
+                let idx = GRIDS.iter().position(|(g, _)| *g == s.grid).unwrap_or(2);
+                // Increasing makes the grid finer
+                let new_idx = if increase { (idx + 1).min(GRIDS.len() - 1) } else { idx.saturating_sub(1) };
+                s.grid = GRIDS[new_idx].0;
+            }
+            Field::Bars => {
+                let idx = BAR_CHOICES.iter().position(|b| *b == s.bars).unwrap_or(1);
+                let new_idx = if increase { (idx + 1).min(BAR_CHOICES.len() - 1) } else { idx.saturating_sub(1) };
+                s.bars = BAR_CHOICES[new_idx];
+            }
+            Field::Seed => {
+                s.seed = if increase { s.seed.wrapping_add(1) } else { s.seed.wrapping_sub(1) };
+            }
+        }
+    }
+
+    fn field_label(field: Field) -> &'static str {
+        match field {
+            Field::Scale => "Scale",
+            Field::Density => "Density",
+            Field::LowOctave => "Low oct",
+            Field::HighOctave => "High oct",
+            Field::Grid => "Rhythm",
+            Field::Bars => "Length",
+            Field::Seed => "Seed",
+        }
+    }
+
+    fn field_value(&self, field: Field) -> String {
+        let s = &self.settings;
+        match field {
+            Field::Scale => s.scale.name().to_string(),
+            Field::Density => format!("{}%", s.density),
+            Field::LowOctave => format!("C{}", s.low_octave),
+            Field::HighOctave => format!("C{}", s.high_octave),
+            Field::Grid => match GRIDS.iter().find(|(g, _)| *g == s.grid) {
+                Some((_, label)) => label.to_string(),
+                None => format!("1/{}", s.grid),
+            },
+            Field::Bars => format!("{} bar{}", s.bars, if s.bars == 1 { "" } else { "s" }),
+            Field::Seed => s.seed.to_string(),
+        }
+    }
+
+    /// Replace the target range with a freshly generated melody, as one undo step
+    fn write_action(&self) -> Action {
+        let end_tick = self.start_tick + self.settings.bars * self.ticks_per_bar;
+        let mut actions = vec![Action::PianoRoll(PianoRollAction::DeleteNotesInRegion {
+            track: self.track,
+            start_tick: self.start_tick,
+            end_tick,
+            start_pitch: 0,
+            end_pitch: 127,
+        })];
+        for (pitch, tick, duration, velocity) in generate_melody(&self.settings, self.start_tick, self.ticks_per_beat, self.ticks_per_bar) {
+            actions.push(Action::PianoRoll(PianoRollAction::ToggleNote {
+                pitch,
+                tick,
+                duration,
+                velocity,
+                track: self.track,
+            }));
+        }
+        Action::Batch(actions)
+    }
+}
+
+impl Default for NoteGeneratorPane {
+    fn default() -> Self {
+        Self::new(Keymap::new())
+    }
+}
+
+impl Pane for NoteGeneratorPane {
+    fn id(&self) -> &'static str {
+        "note_generator"
+    }
+
+    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, _state: &AppState) -> Action {
+        match action {
+            ActionId::NoteGenerator(NoteGeneratorActionId::Prev) => {
+                self.selected = self.selected.saturating_sub(1);
+                Action::None
+            }
+            ActionId::NoteGenerator(NoteGeneratorActionId::Next) => {
+                if self.selected < FIELDS.len() - 1 {
+                    self.selected += 1;
+                }
+                Action::None
+            }
+            ActionId::NoteGenerator(NoteGeneratorActionId::Decrease) => {
+                self.adjust(false);
+                Action::None
+            }
+            ActionId::NoteGenerator(NoteGeneratorActionId::Increase) => {
+                self.adjust(true);
+                Action::None
+            }
+            ActionId::NoteGenerator(NoteGeneratorActionId::Reroll) => {
+                self.settings.seed = Rng::new(self.settings.seed).next() % 10_000;
+                self.write_action()
+            }
+            ActionId::NoteGenerator(NoteGeneratorActionId::Write) => self.write_action(),
+            ActionId::NoteGenerator(NoteGeneratorActionId::Close) => Action::Nav(NavAction::PopPane),
+            _ => Action::None,
+        }
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, _state: &AppState) {
+        let rect = center_rect(area, 44, 14);
+
+        let border_style = Style::new().fg(Color::PURPLE);
+        let inner = buf.draw_block(rect, " Melody Generator ", border_style, border_style);
+
+        let label_col = inner.x + 2;
+        let value_col = label_col + 12;
+
+        let target = format!(
+            "Track {} · bar {}",
+            self.track + 1,
+            self.start_tick / self.ticks_per_bar + 1,
+        );
+        buf.draw_line(Rect::new(label_col, inner.y, inner.width.saturating_sub(2), 1), &[(&target, Style::new().fg(Color::GRAY))]);
+
+        for (i, field) in FIELDS.iter().enumerate() {
+            let y = inner.y + 2 + i as u16;
+            if y >= inner.y + inner.height {
+                break;
+            }
+            let is_selected = i == self.selected;
+
+            if is_selected {
+                for x in inner.x..inner.x + inner.width {
+                    buf.set_cell(x, y, ' ', Style::new().bg(Color::SELECTION_BG));
+                }
+                buf.set_cell(label_col, y, '>', Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG).bold());
+            }
+
+            let label_style = if is_selected {
+                Style::new().fg(Color::CYAN).bg(Color::SELECTION_BG)
+            } else {
+                Style::new().fg(Color::CYAN)
+            };
+            let val_style = if is_selected {
+                Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG)
+            } else {
+                Style::new().fg(Color::WHITE)
+            };
+            let label = format!("{:10}", Self::field_label(*field));
+            buf.draw_line(Rect::new(label_col + 2, y, 10, 1), &[(&label, label_style)]);
+            let val = self.field_value(*field);
+            buf.draw_line(Rect::new(value_col, y, inner.width.saturating_sub(14), 1), &[(&val, val_style)]);
+        }
+
+        let help_y = rect.y + rect.height - 2;
+        if help_y < area.y + area.height {
+            buf.draw_line(
+                Rect::new(inner.x + 2, help_y, inner.width.saturating_sub(2), 1),
+                &[("Enter: write | r: re-roll | Esc: close", Style::new().fg(Color::DARK_GRAY))],
+            );
+        }
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn on_enter(&mut self, state: &AppState) {
+        // Generate into the selected instrument's track, following the session key/scale
+        self.track = state.instruments.selected
+            .and_then(|idx| state.instruments.instruments.get(idx))
+            .and_then(|inst| state.session.piano_roll.track_order.iter().position(|&id| id == inst.id))
+            .unwrap_or(0);
+        let pr = &state.session.piano_roll;
+        self.ticks_per_beat = pr.ticks_per_beat.max(1);
+        self.ticks_per_bar = pr.ticks_per_bar().max(1);
+        self.start_tick = if pr.looping && pr.loop_end > pr.loop_start {
+            pr.loop_start - pr.loop_start % self.ticks_per_bar
+        } else {
+            0
+        };
+        self.settings.scale = state.session.scale;
+        self.settings.root = state.session.key.semitone();
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::{KeyCode, Modifiers};
+
+    fn dummy_event() -> InputEvent {
+        InputEvent::new(KeyCode::Char('x'), Modifiers::default())
+    }
+
+    #[test]
+    fn melody_is_deterministic_per_seed() {
+        let settings = MelodySettings::default();
+        assert_eq!(generate_melody(&settings, 0, 480, 1920), generate_melody(&settings, 0, 480, 1920));
+        let other = MelodySettings { seed: 2, ..settings.clone() };
+        assert_ne!(generate_melody(&settings, 0, 480, 1920), generate_melody(&other, 0, 480, 1920));
+    }
+
+    #[test]
+    fn melody_stays_in_range_and_on_grid() {
+        let settings = MelodySettings { density: 100, bars: 4, ..Default::default() };
+        let notes = generate_melody(&settings, 1920, 480, 1920);
+        // Eighths at 480 ticks per beat
+        assert_eq!(notes.len(), 4 * 8);
+        let intervals = settings.scale.intervals();
+        for (pitch, tick, duration, _) in notes {
+            assert!((48..=71).contains(&pitch), "pitch {} out of octave range", pitch);
+            assert!(intervals.contains(&((pitch % 12 + 12 - settings.root) % 12)));
+            assert_eq!((tick - 1920) % 240, 0);
+            assert!(tick + duration <= 1920 + 4 * 1920);
+        }
+        // Bars of 3/4 hold three beats of steps
+        assert_eq!(generate_melody(&settings, 0, 480, 1440).len(), 4 * 6);
+        // The same eighths at a finer resolution
+        assert_eq!(generate_melody(&settings, 0, 960, 3840).len(), 4 * 8);
+    }
+
+    #[test]
+    fn zero_density_is_silent() {
+        let settings = MelodySettings { density: 0, ..Default::default() };
+        assert!(generate_melody(&settings, 0, 480, 1920).is_empty());
+    }
+
+    #[test]
+    fn reroll_changes_seed_and_writes() {
+        let mut pane = NoteGeneratorPane::new(Keymap::new());
+        let state = AppState::new();
+        let seed = pane.settings.seed;
+        let action = pane.handle_action(ActionId::NoteGenerator(NoteGeneratorActionId::Reroll), &dummy_event(), &state);
+        assert_ne!(pane.settings.seed, seed);
+        match action {
+            Action::Batch(actions) => assert!(matches!(
+                actions.first(),
+                Some(Action::PianoRoll(PianoRollAction::DeleteNotesInRegion { .. }))
+            )),
+            _ => panic!("Expected a batch replacing the region"),
+        }
+    }
+
+    #[test]
+    fn octave_range_cannot_invert() {
+        let mut pane = NoteGeneratorPane::new(Keymap::new());
+        pane.selected = 2; // Low octave
+        for _ in 0..5 {
+            pane.adjust(true);
+        }
+        assert_eq!(pane.settings.low_octave, pane.settings.high_octave);
+    }
+}
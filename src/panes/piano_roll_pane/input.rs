@@ -1,10 +1,43 @@
 
-use crate::state::AppState;
+use std::time::Instant;
+
+use crate::audition::{self, Audition};
+use crate::panes::{ExportTarget, QuantizeTarget};
+use crate::state::{AppState, ClipboardContents, ClipboardNote};
 use crate::ui::layout_helpers::center_rect;
-use crate::ui::{Rect, Action, InputEvent, KeyCode, MouseButton, MouseEvent, MouseEventKind, PianoRollAction, translate_key};
+use crate::ui::{Rect, Action, InputEvent, KeyCode, MouseButton, MouseEvent, MouseEventKind, NavAction, PianoRollAction, SessionAction, FileSelectAction, translate_key};
 use crate::ui::action_id::{ActionId, PianoRollActionId, ModeActionId};
 
-use super::PianoRollPane;
+use super::{scale_mask, PianoRollPane, ScaleMode};
+
+/// Most copies one paste-repeat lays down
+const MAX_PASTE_REPEATS: u32 = 64;
+
+/// Velocity change per press with the velocity lane open
+const VELOCITY_STEP: i16 = 8;
+
+/// `(track, tick, pitch, duration, velocity)` of a note being reshaped
+type NoteRef = (usize, u32, u8, u32, u8);
+
+/// Velocity at `tick` on the line from `(t0, v0)` to `(t1, v1)`
+pub(super) fn ramp_velocity(tick: u32, (t0, v0): (u32, u8), (t1, v1): (u32, u8)) -> u8 {
+    if t1 <= t0 {
+        return v0;
+    }
+    let pos = (tick.clamp(t0, t1) - t0) as f32 / (t1 - t0) as f32;
+    (v0 as f32 + (v1 as f32 - v0 as f32) * pos).round().clamp(1.0, 127.0) as u8
+}
+
+/// `duration` one grid `step` longer or shorter, never below a step and,
+/// growing, never past the next note on the same pitch `room` ticks away
+pub(super) fn resized_duration(duration: u32, step: u32, grow: bool, room: Option<u32>) -> u32 {
+    if grow {
+        let longer = duration + step;
+        room.map_or(longer, |room| longer.min(room.max(duration)))
+    } else {
+        duration.saturating_sub(step).max(step.min(duration))
+    }
+}
 
 impl PianoRollPane {
     /// Get the instrument ID for the current track from state
@@ -17,8 +50,261 @@ impl PianoRollPane {
 }
 
 impl PianoRollPane {
+    /// Build an edit for each track in the edit group, as one undo step
+    fn edit_linked(&self, state: &AppState, edit: impl Fn(usize) -> Action) -> Action {
+        let mut actions: Vec<Action> = self.edit_tracks(state.session.piano_roll.track_order.len())
+            .into_iter()
+            .map(edit)
+            .collect();
+        if actions.len() == 1 {
+            actions.remove(0)
+        } else {
+            Action::Batch(actions)
+        }
+    }
+
+    /// Transpose the selection (or the note at the cursor) on all linked tracks
+    fn transpose_selection(&mut self, semitones: i8, state: &AppState) -> Action {
+        let (_, start_tick, end_tick, start_pitch, end_pitch) = self.selection_region();
+        let action = self.edit_linked(state, |track| Action::PianoRoll(PianoRollAction::TransposeNotesInRegion {
+            track, start_tick, end_tick, start_pitch, end_pitch, semitones,
+        }));
+        // Keep the selection on the moved notes
+        let shift = |p: u8| (p as i16 + semitones as i16).clamp(0, 127) as u8;
+        self.cursor_pitch = shift(self.cursor_pitch);
+        if let Some((tick, pitch)) = self.selection_anchor {
+            self.selection_anchor = Some((tick, shift(pitch)));
+        }
+        self.scroll_to_cursor();
+        action
+    }
+
+    /// Move the selection (or the note at the cursor) one grid cell in time
+    fn nudge_selection(&mut self, later: bool, state: &AppState) -> Action {
+        let (_, start_tick, end_tick, start_pitch, end_pitch) = self.selection_region();
+        let step = self.ticks_per_cell();
+        if !later && start_tick < step {
+            return Action::None;
+        }
+        let delta_ticks = if later { step as i32 } else { -(step as i32) };
+        let action = self.edit_linked(state, |track| Action::PianoRoll(PianoRollAction::NudgeNotesInRegion {
+            track, start_tick, end_tick, start_pitch, end_pitch, delta_ticks,
+        }));
+        let shift = |t: u32| t.saturating_add_signed(delta_ticks);
+        self.cursor_tick = shift(self.cursor_tick);
+        if let Some((tick, pitch)) = self.selection_anchor {
+            self.selection_anchor = Some((shift(tick), pitch));
+        }
+        self.scroll_to_cursor();
+        action
+    }
+
+    /// Notes a velocity or length edit reshapes: those starting in the
+    /// selection, or the one under the cursor, on every edit group track
+    pub(super) fn note_targets(&self, state: &AppState) -> Vec<NoteRef> {
+        let piano_roll = &state.session.piano_roll;
+        let selected = self.selection_anchor.map(|_| self.selection_region());
+        let mut notes: Vec<NoteRef> = self.edit_tracks(piano_roll.track_order.len()).into_iter()
+            .filter_map(|track| piano_roll.track_at(track).map(|t| (track, t)))
+            .flat_map(|(track, t)| {
+                t.notes.iter()
+                    .filter(move |n| match selected {
+                        Some((_, t0, t1, p0, p1)) => (t0..t1).contains(&n.tick) && (p0..=p1).contains(&n.pitch),
+                        None => n.pitch == self.cursor_pitch && n.tick <= self.cursor_tick && self.cursor_tick < n.tick + n.duration,
+                    })
+                    .map(move |n| (track, n.tick, n.pitch, n.duration, n.velocity))
+            })
+            .collect();
+        notes.sort_by_key(|&(track, tick, pitch, _, _)| (tick, track, pitch));
+        notes
+    }
+
+    /// Replace each note with a copy at its new `(duration, velocity)`, as
+    /// one undo step
+    fn reshape_notes(notes: &[NoteRef], reshape: impl Fn(&NoteRef) -> (u32, u8)) -> Action {
+        let actions: Vec<Action> = notes.iter()
+            .filter(|n| reshape(n) != (n.3, n.4))
+            .flat_map(|n| {
+                let &(track, tick, pitch, _, _) = n;
+                let (duration, velocity) = reshape(n);
+                [
+                    Action::PianoRoll(PianoRollAction::DeleteNotesInRegion {
+                        track, start_tick: tick, end_tick: tick + 1, start_pitch: pitch, end_pitch: pitch,
+                    }),
+                    Action::PianoRoll(PianoRollAction::ToggleNote { pitch, tick, duration, velocity, track }),
+                ]
+            })
+            .collect();
+        if actions.is_empty() { Action::None } else { Action::Batch(actions) }
+    }
+
+    /// With the velocity lane open, +/- reshape the targeted notes; with it
+    /// closed, or nothing under the cursor, they set the default velocity
+    fn adjust_velocity(&mut self, up: bool, state: &AppState) -> Action {
+        let notes = if self.velocity_lane_visible { self.note_targets(state) } else { Vec::new() };
+        if notes.is_empty() {
+            self.adjust_default_velocity(if up { 10 } else { -10 });
+            return Action::None;
+        }
+        let delta = if up { VELOCITY_STEP } else { -VELOCITY_STEP };
+        Self::reshape_notes(&notes, |n| (n.3, (n.4 as i16 + delta).clamp(1, 127) as u8))
+    }
+
+    /// Ramp velocities across the selection from its first note's to its last's
+    fn ramp_velocities(&self, state: &AppState) -> Action {
+        if self.selection_anchor.is_none() {
+            return Action::None;
+        }
+        let notes = self.note_targets(state);
+        let (Some(first), Some(last)) = (notes.first(), notes.last()) else { return Action::None };
+        let (from, to) = ((first.1, first.4), (last.1, last.4));
+        Self::reshape_notes(&notes, |n| (n.3, ramp_velocity(n.1, from, to)))
+    }
+
+    /// Lengthen or shorten the targeted notes by one grid step; with
+    /// nothing targeted, change the length new notes get instead
+    fn resize_notes(&mut self, grow: bool, state: &AppState) -> Action {
+        let step = self.ticks_per_cell();
+        let notes = self.note_targets(state);
+        if notes.is_empty() {
+            self.adjust_default_duration(if grow { step as i32 } else { -(step as i32) });
+            return Action::None;
+        }
+        let piano_roll = &state.session.piano_roll;
+        // Ticks to the next note on the same track and pitch
+        let room = |&(track, tick, pitch, _, _): &NoteRef| {
+            piano_roll.track_at(track)?.notes.iter()
+                .filter(|n| n.pitch == pitch && n.tick > tick)
+                .map(|n| n.tick - tick)
+                .min()
+        };
+        Self::reshape_notes(&notes, |n| (resized_duration(n.3, step, grow, room(n)), n.4))
+    }
+
+    /// Ticks between copies when tiling `notes`: the copied region's length,
+    /// or the notes' extent rounded up to whole bars when they outgrow it
+    fn phrase_span(&self, notes: &[ClipboardNote], ticks_per_bar: u32) -> u32 {
+        let extent = notes.iter().map(|n| n.tick_offset + n.duration).max().unwrap_or(0);
+        match self.copied_span {
+            Some(span) if span >= extent && span > 0 => span,
+            _ => extent.div_ceil(ticks_per_bar.max(1)).max(1) * ticks_per_bar.max(1),
+        }
+    }
+
+    /// Ask main.rs to open the quantize popup on the selection, across the
+    /// edit group, or on whole tracks when nothing is selected
+    fn request_quantize(&mut self, state: &AppState) {
+        let tracks = self.edit_tracks(state.session.piano_roll.track_order.len());
+        if tracks.is_empty() {
+            return;
+        }
+        let (start_tick, end_tick, start_pitch, end_pitch) = if self.selection_anchor.is_some() {
+            let (_, t0, t1, p0, p1) = self.selection_region();
+            (t0, t1, p0, p1)
+        } else {
+            (0, u32::MAX, 0, 127)
+        };
+        self.pending_quantize = Some(QuantizeTarget {
+            tracks, start_tick, end_tick, start_pitch, end_pitch, grid: self.ticks_per_cell(),
+        });
+    }
+
+    /// Ask how many times to paste the clipboard's notes
+    fn start_paste_repeat(&mut self, state: &AppState) -> Action {
+        if !matches!(state.clipboard.contents, Some(ClipboardContents::PianoRollNotes(_))) {
+            return Action::None;
+        }
+        self.repeat_input.set_value("4");
+        self.repeat_input.select_all();
+        self.repeat_input.set_focused(true);
+        self.typing_repeats = true;
+        Action::PushLayer("text_edit")
+    }
+
+    /// Paste the notes back to back from the cursor, as one undo step
+    fn finish_paste_repeat(&mut self, confirm: bool, state: &AppState) -> Action {
+        if !std::mem::take(&mut self.typing_repeats) {
+            return Action::None;
+        }
+        self.repeat_input.set_focused(false);
+        let count = self.repeat_input.value().trim().parse::<u32>().ok()
+            .filter(|n| (1..=MAX_PASTE_REPEATS).contains(n));
+        let (Some(count), Some(ClipboardContents::PianoRollNotes(notes)), true) = (count, &state.clipboard.contents, confirm) else {
+            return Action::None;
+        };
+        let span = self.phrase_span(notes, state.session.piano_roll.ticks_per_bar());
+        let pastes = (0..count)
+            .map(|i| Action::PianoRoll(PianoRollAction::PasteNotes {
+                track: self.current_track,
+                anchor_tick: self.cursor_tick + i * span,
+                anchor_pitch: self.cursor_pitch,
+                notes: notes.clone(),
+            }))
+            .collect();
+        self.selection_anchor = None;
+        Action::Batch(pastes)
+    }
+
+    /// Queue the selected notes (or the note under the cursor) to play
+    /// through the track's instrument without starting the transport
+    fn audition_selection(&mut self, state: &AppState) {
+        let Some(track) = state.session.piano_roll.track_at(self.current_track) else { return };
+        let notes: Vec<_> = if self.selection_anchor.is_some() {
+            let (_, start_tick, end_tick, start_pitch, end_pitch) = self.selection_region();
+            track.notes.iter()
+                .filter(|n| n.tick >= start_tick && n.tick < end_tick)
+                .filter(|n| n.pitch >= start_pitch && n.pitch <= end_pitch)
+                .map(|n| (n.tick, n.duration, n.pitch, n.velocity))
+                .collect()
+        } else {
+            track.notes.iter()
+                .filter(|n| n.pitch == self.cursor_pitch)
+                .filter(|n| n.tick <= self.cursor_tick && self.cursor_tick < n.tick + n.duration)
+                .map(|n| (n.tick, n.duration, n.pitch, n.velocity))
+                .collect()
+        };
+        if notes.is_empty() {
+            return;
+        }
+        self.pending_audition = Some(Audition::new(
+            audition::relative_notes(notes),
+            state.session.bpm as f32,
+            state.session.piano_roll.ticks_per_beat,
+            self.current_instrument_id(state),
+            self.current_track,
+            Instant::now(),
+        ));
+    }
+
+    /// In scrub mode, briefly play the current track's notes at the cursor
+    fn scrub_at_cursor(&mut self, state: &AppState) {
+        if !self.scrub {
+            return;
+        }
+        let Some(track) = state.session.piano_roll.track_at(self.current_track) else { return };
+        let notes = audition::scrub_notes(
+            track.notes.iter().map(|n| (n.tick, n.duration, n.pitch, n.velocity)),
+            self.cursor_tick,
+            self.ticks_per_cell(),
+        );
+        if notes.is_empty() {
+            return;
+        }
+        self.pending_audition = Some(Audition::new(
+            notes,
+            state.session.bpm as f32,
+            state.session.piano_roll.ticks_per_beat,
+            self.current_instrument_id(state),
+            self.current_track,
+            Instant::now(),
+        ));
+    }
+
     pub(super) fn handle_action_impl(&mut self, action: ActionId, event: &InputEvent, state: &AppState) -> Action {
+        self.scale_mask = scale_mask(state);
         match action {
+            ActionId::Mode(ModeActionId::TextConfirm) => self.finish_paste_repeat(true, state),
+            ActionId::Mode(ModeActionId::TextCancel) => self.finish_paste_repeat(false, state),
             // Piano mode actions (from piano layer)
             ActionId::Mode(ModeActionId::PianoEscape) => {
                 self.piano.deactivate();
@@ -40,7 +326,12 @@ impl PianoRollPane {
             ActionId::Mode(ModeActionId::PianoKey) => {
                 if let KeyCode::Char(c) = event.key {
                     let c = translate_key(c, state.keyboard_layout);
-                    if let Some(pitches) = self.piano.key_to_pitches(c) {
+                    if let Some(mut pitches) = self.piano.key_to_pitches(c) {
+                        // Locked to the scale, out-of-scale keys play the degree below
+                        if self.scale_mode.locks() {
+                            pitches = pitches.into_iter().map(|p| self.snap_to_scale(p)).collect();
+                            pitches.dedup();
+                        }
                         let instrument_id = self.current_instrument_id(state);
                         let track = self.current_track;
                         if pitches.len() == 1 {
@@ -59,7 +350,12 @@ impl PianoRollPane {
             // Normal grid navigation
             ActionId::PianoRoll(PianoRollActionId::Up) => {
                 self.selection_anchor = None;
-                if self.cursor_pitch < 127 {
+                if self.scale_mode.locks() {
+                    if let Some(pitch) = self.scale_step(self.cursor_pitch, true) {
+                        self.cursor_pitch = pitch;
+                        self.scroll_to_cursor();
+                    }
+                } else if self.cursor_pitch < 127 {
                     self.cursor_pitch += 1;
                     self.scroll_to_cursor();
                 }
@@ -67,16 +363,30 @@ impl PianoRollPane {
             }
             ActionId::PianoRoll(PianoRollActionId::Down) => {
                 self.selection_anchor = None;
-                if self.cursor_pitch > 0 {
+                if self.scale_mode.locks() {
+                    if let Some(pitch) = self.scale_step(self.cursor_pitch, false) {
+                        self.cursor_pitch = pitch;
+                        self.scroll_to_cursor();
+                    }
+                } else if self.cursor_pitch > 0 {
                     self.cursor_pitch -= 1;
                     self.scroll_to_cursor();
                 }
                 Action::None
             }
+            ActionId::PianoRoll(PianoRollActionId::ScaleMode) => {
+                self.scale_mode = self.scale_mode.next();
+                if self.scale_mode.locks() {
+                    self.cursor_pitch = self.snap_to_scale(self.cursor_pitch);
+                    self.scroll_to_cursor();
+                }
+                Action::None
+            }
             ActionId::PianoRoll(PianoRollActionId::Right) => {
                 self.selection_anchor = None;
                 self.cursor_tick += self.ticks_per_cell();
                 self.scroll_to_cursor();
+                self.scrub_at_cursor(state);
                 Action::None
             }
             ActionId::PianoRoll(PianoRollActionId::Left) => {
@@ -84,6 +394,7 @@ impl PianoRollPane {
                 let step = self.ticks_per_cell();
                 self.cursor_tick = self.cursor_tick.saturating_sub(step);
                 self.scroll_to_cursor();
+                self.scrub_at_cursor(state);
                 Action::None
             }
             ActionId::PianoRoll(PianoRollActionId::SelectUp) => {
@@ -123,30 +434,49 @@ impl PianoRollPane {
                 self.scroll_to_cursor();
                 Action::None
             }
-            ActionId::PianoRoll(PianoRollActionId::ToggleNote) => Action::PianoRoll(PianoRollAction::ToggleNote {
-                pitch: self.cursor_pitch,
-                tick: self.cursor_tick,
-                duration: self.default_duration,
-                velocity: self.default_velocity,
-                track: self.current_track,
-            }),
-            ActionId::PianoRoll(PianoRollActionId::GrowDuration) => {
-                self.adjust_default_duration(self.ticks_per_cell() as i32);
+            ActionId::PianoRoll(PianoRollActionId::ToggleNote) => {
+                // Locked off the scale, existing notes can be removed but not added
+                if self.scale_mode.locks() && !self.scale_mask[self.cursor_pitch as usize % 12] {
+                    let on_note = state.session.piano_roll.track_at(self.current_track).is_some_and(|track| {
+                        track.notes.iter().any(|n| n.pitch == self.cursor_pitch && n.tick == self.cursor_tick)
+                    });
+                    if !on_note {
+                        return Action::None;
+                    }
+                }
+                Action::PianoRoll(PianoRollAction::ToggleNote {
+                    pitch: self.cursor_pitch,
+                    tick: self.cursor_tick,
+                    duration: self.default_duration,
+                    velocity: self.default_velocity,
+                    track: self.current_track,
+                })
+            }
+            ActionId::PianoRoll(PianoRollActionId::GrowDuration) => self.resize_notes(true, state),
+            ActionId::PianoRoll(PianoRollActionId::ShrinkDuration) => self.resize_notes(false, state),
+            ActionId::PianoRoll(PianoRollActionId::VelUp) => self.adjust_velocity(true, state),
+            ActionId::PianoRoll(PianoRollActionId::VelDown) => self.adjust_velocity(false, state),
+            ActionId::PianoRoll(PianoRollActionId::VelocityRamp) => self.ramp_velocities(state),
+            ActionId::PianoRoll(PianoRollActionId::ToggleGhosts) => {
+                self.ghost_notes = !self.ghost_notes;
                 Action::None
             }
-            ActionId::PianoRoll(PianoRollActionId::ShrinkDuration) => {
-                self.adjust_default_duration(-(self.ticks_per_cell() as i32));
+            ActionId::PianoRoll(PianoRollActionId::ToggleVelocityLane) => {
+                self.velocity_lane_visible = !self.velocity_lane_visible;
+                if self.velocity_lane_visible {
+                    self.automation_overlay_visible = false;
+                }
                 Action::None
             }
-            ActionId::PianoRoll(PianoRollActionId::VelUp) => {
-                self.adjust_default_velocity(10);
+            ActionId::PianoRoll(PianoRollActionId::PlayStop) => Action::PianoRoll(PianoRollAction::PlayStop),
+            ActionId::PianoRoll(PianoRollActionId::Audition) => {
+                self.audition_selection(state);
                 Action::None
             }
-            ActionId::PianoRoll(PianoRollActionId::VelDown) => {
-                self.adjust_default_velocity(-10);
+            ActionId::PianoRoll(PianoRollActionId::ToggleScrub) => {
+                self.scrub = !self.scrub;
                 Action::None
             }
-            ActionId::PianoRoll(PianoRollActionId::PlayStop) => Action::PianoRoll(PianoRollAction::PlayStop),
             ActionId::PianoRoll(PianoRollActionId::Loop) => Action::PianoRoll(PianoRollAction::ToggleLoop),
             ActionId::PianoRoll(PianoRollActionId::LoopStart) => Action::PianoRoll(PianoRollAction::SetLoopStart(self.cursor_tick)),
             ActionId::PianoRoll(PianoRollActionId::LoopEnd) => Action::PianoRoll(PianoRollAction::SetLoopEnd(self.cursor_tick)),
@@ -193,21 +523,55 @@ impl PianoRollPane {
             ActionId::PianoRoll(PianoRollActionId::TogglePoly) => Action::PianoRoll(PianoRollAction::TogglePolyMode(self.current_track)),
             ActionId::PianoRoll(PianoRollActionId::RenderToWav) => Action::PianoRoll(PianoRollAction::RenderToWav(self.current_instrument_id(state))),
             ActionId::PianoRoll(PianoRollActionId::BounceToWav) => {
-                if state.io.pending_export.is_some() {
-                    Action::PianoRoll(PianoRollAction::CancelExport)
-                } else {
-                    Action::PianoRoll(PianoRollAction::BounceToWav)
-                }
+                // Exports queue up behind a running one
+                self.pending_export_dialog = Some(ExportTarget::Master);
+                Action::None
             }
             ActionId::PianoRoll(PianoRollActionId::ExportStems) => {
-                if state.io.pending_export.is_some() {
-                    Action::PianoRoll(PianoRollAction::CancelExport)
-                } else {
-                    Action::PianoRoll(PianoRollAction::ExportStems)
-                }
+                // Exports queue up behind a running one
+                self.pending_export_dialog = Some(ExportTarget::Stems);
+                Action::None
+            }
+            ActionId::PianoRoll(PianoRollActionId::RecordSettings) => {
+                Action::Nav(NavAction::PushPane("record_settings"))
+            }
+            ActionId::PianoRoll(PianoRollActionId::Takes) => Action::Nav(NavAction::PushPane("comp")),
+            ActionId::PianoRoll(PianoRollActionId::Generate) => Action::Nav(NavAction::PushPane("note_generator")),
+            ActionId::PianoRoll(PianoRollActionId::Tracker) => Action::Nav(NavAction::PushPane("tracker")),
+            ActionId::PianoRoll(PianoRollActionId::PasteRepeat) => self.start_paste_repeat(state),
+            ActionId::PianoRoll(PianoRollActionId::Quantize) => {
+                self.request_quantize(state);
+                Action::None
+            }
+            ActionId::PianoRoll(PianoRollActionId::AudioToMidi) => {
+                // Notes land on the current track, starting at the cursor's bar
+                let tpbar = state.session.piano_roll.ticks_per_bar();
+                let bar_start = self.cursor_tick - self.cursor_tick % tpbar;
+                Action::Session(SessionAction::OpenFileBrowser(FileSelectAction::AudioToMidi(self.current_track, bar_start)))
             }
+            ActionId::PianoRoll(PianoRollActionId::TransposeUp) => self.transpose_selection(1, state),
+            ActionId::PianoRoll(PianoRollActionId::TransposeDown) => self.transpose_selection(-1, state),
+            ActionId::PianoRoll(PianoRollActionId::NudgeLeft) => self.nudge_selection(false, state),
+            ActionId::PianoRoll(PianoRollActionId::NudgeRight) => self.nudge_selection(true, state),
+            ActionId::PianoRoll(PianoRollActionId::DeleteRange) => {
+                let (_, start_tick, end_tick, start_pitch, end_pitch) = self.selection_region();
+                self.selection_anchor = None;
+                self.edit_linked(state, |track| Action::PianoRoll(PianoRollAction::DeleteNotesInRegion {
+                    track, start_tick, end_tick, start_pitch, end_pitch,
+                }))
+            }
+            ActionId::PianoRoll(PianoRollActionId::ToggleLink) => {
+                self.toggle_track_link();
+                Action::None
+            }
+            ActionId::PianoRoll(PianoRollActionId::ClearLinks) => {
+                self.linked_tracks.clear();
+                Action::None
+            }
+            ActionId::PianoRoll(PianoRollActionId::SwingDown) => Action::PianoRoll(PianoRollAction::AdjustTrackSwing(self.current_track, -0.05)),
+            ActionId::PianoRoll(PianoRollActionId::SwingUp) => Action::PianoRoll(PianoRollAction::AdjustTrackSwing(self.current_track, 0.05)),
             ActionId::PianoRoll(PianoRollActionId::ToggleAutomation) => {
-                self.automation_overlay_visible = !self.automation_overlay_visible;
+                self.set_automation_overlay(!self.automation_overlay_visible);
                 Action::None
             }
             ActionId::PianoRoll(PianoRollActionId::AutomationLanePrev) => {
@@ -236,7 +600,7 @@ impl PianoRollPane {
         }
     }
 
-    pub(super) fn handle_mouse_impl(&mut self, event: &MouseEvent, area: Rect, _state: &AppState) -> Action {
+    pub(super) fn handle_mouse_impl(&mut self, event: &MouseEvent, area: Rect, state: &AppState) -> Action {
         let rect = center_rect(area, 97, 29);
         let key_col_width: u16 = 5;
         let header_height: u16 = 2;
@@ -248,9 +612,32 @@ impl PianoRollPane {
 
         let col = event.column;
         let row = event.row;
+        self.scale_mask = scale_mask(state);
+        let mask = self.scale_mask;
+
+        // Beat ruler under the grid: click or drag to move the playhead
+        let ruler_y = grid_y + grid_height;
+        if row == ruler_y && col >= grid_x && col < grid_x + grid_width
+            && matches!(event.kind, MouseEventKind::Down(MouseButton::Left) | MouseEventKind::Drag(MouseButton::Left))
+        {
+            let tick = self.view_start_tick + (col - grid_x) as u32 * self.ticks_per_cell();
+            let delta = tick as i64 - state.audio.playhead as i64;
+            return Action::PianoRoll(PianoRollAction::SeekRelative(delta as i32));
+        }
 
         match event.kind {
             MouseEventKind::Down(MouseButton::Left) => {
+                // Play and loop toggles in the header
+                if row == rect.y + 1 {
+                    let (num, den) = state.session.piano_roll.time_signature;
+                    let play_x = rect.x + 4 + format!("{}/{}", num, den).len() as u16;
+                    if col >= play_x && col < play_x + 2 {
+                        return Action::PianoRoll(PianoRollAction::PlayStop);
+                    }
+                    if col == play_x + 4 {
+                        return Action::PianoRoll(PianoRollAction::ToggleLoop);
+                    }
+                }
                 self.selection_anchor = None;
                 // Click on the grid area
                 if col >= grid_x && col < grid_x + grid_width
@@ -258,10 +645,12 @@ impl PianoRollPane {
                 {
                     let grid_col = col - grid_x;
                     let grid_row = row - grid_y;
-                    let pitch = self.view_bottom_pitch.saturating_add((grid_height - 1 - grid_row) as u8);
                     let tick = self.view_start_tick + grid_col as u32 * self.ticks_per_cell();
 
-                    if pitch <= 127 {
+                    if let Some(pitch) = self.pitch_at_row(&mask, grid_height - 1 - grid_row) {
+                        if self.scale_mode.locks() && !mask[pitch as usize % 12] {
+                            return Action::None;
+                        }
                         self.cursor_pitch = pitch;
                         self.cursor_tick = tick;
                         return Action::PianoRoll(PianoRollAction::ToggleNote {
@@ -276,8 +665,7 @@ impl PianoRollPane {
                 // Click on piano key column to set pitch
                 if col >= rect.x && col < grid_x && row >= grid_y && row < grid_y + grid_height {
                     let grid_row = row - grid_y;
-                    let pitch = self.view_bottom_pitch.saturating_add((grid_height - 1 - grid_row) as u8);
-                    if pitch <= 127 {
+                    if let Some(pitch) = self.pitch_at_row(&mask, grid_height - 1 - grid_row) {
                         self.cursor_pitch = pitch;
                     }
                 }
@@ -290,9 +678,8 @@ impl PianoRollPane {
                 {
                     let grid_col = col - grid_x;
                     let grid_row = row - grid_y;
-                    let pitch = self.view_bottom_pitch.saturating_add((grid_height - 1 - grid_row) as u8);
                     let tick = self.view_start_tick + grid_col as u32 * self.ticks_per_cell();
-                    if pitch <= 127 {
+                    if let Some(pitch) = self.pitch_at_row(&mask, grid_height - 1 - grid_row) {
                         self.cursor_pitch = pitch;
                         self.cursor_tick = tick;
                     }
@@ -2,12 +2,64 @@ mod input;
 mod rendering;
 
 use std::any::Any;
+use std::collections::BTreeSet;
 
-
+use crate::audition::Audition;
+use crate::av_sync::VisualOffset;
+use crate::panes::{ExportTarget, QuantizeTarget};
 use crate::state::AppState;
 use crate::ui::layout_helpers::center_rect;
+use crate::ui::widgets::TextInput;
 use crate::ui::{Rect, RenderBuf, Action, InputEvent, Keymap, MouseEvent, Pane, PianoKeyboard, ToggleResult};
 use crate::ui::action_id::ActionId;
+use crate::view_state::PianoRollView;
+
+/// How the session's key and scale shape the grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(super) enum ScaleMode {
+    #[default]
+    Off,
+    /// In-scale rows are highlighted
+    Highlight,
+    /// The cursor and note entry stay on scale degrees
+    Lock,
+    /// Locked, with out-of-scale rows hidden
+    Fold,
+}
+
+impl ScaleMode {
+    fn next(self) -> Self {
+        match self {
+            ScaleMode::Off => ScaleMode::Highlight,
+            ScaleMode::Highlight => ScaleMode::Lock,
+            ScaleMode::Lock => ScaleMode::Fold,
+            ScaleMode::Fold => ScaleMode::Off,
+        }
+    }
+
+    pub(super) fn label(self) -> &'static str {
+        match self {
+            ScaleMode::Off => "",
+            ScaleMode::Highlight => "  SCALE",
+            ScaleMode::Lock => "  SCALE:LOCK",
+            ScaleMode::Fold => "  SCALE:FOLD",
+        }
+    }
+
+    pub(super) fn locks(self) -> bool {
+        matches!(self, ScaleMode::Lock | ScaleMode::Fold)
+    }
+}
+
+/// Pitch classes, from C, in the session's key and scale
+pub(super) fn scale_mask(state: &AppState) -> [bool; 12] {
+    let root = state.session.key.semitone() as usize;
+    let mut mask = [false; 12];
+    for interval in state.session.scale.intervals() {
+        mask[(root + *interval as usize) % 12] = true;
+    }
+    mask
+}
 
 pub struct PianoRollPane {
     keymap: Keymap,
@@ -28,8 +80,31 @@ pub struct PianoRollPane {
     // Automation overlay
     pub(super) automation_overlay_visible: bool,
     pub(super) automation_overlay_lane_idx: Option<usize>, // index into automation.lanes for overlay display
+    /// Velocity bars in place of the automation overlay
+    pub(super) velocity_lane_visible: bool,
     /// Selection anchor — set when Shift+Arrow begins. None = no active selection.
     pub(crate) selection_anchor: Option<(u32, u8)>,  // (tick, pitch)
+    /// Edit group: linked tracks whose notes are edited together. Not saved.
+    pub(super) linked_tracks: BTreeSet<usize>,
+    /// Audition requested by the last key press, taken by main.rs
+    pub(super) pending_audition: Option<Audition>,
+    /// Scrub mode: moving the cursor in time plays the notes under it
+    pub(super) scrub: bool,
+    /// Other tracks' notes drawn dimmed behind the current one
+    pub(super) ghost_notes: bool,
+    pub(super) scale_mode: ScaleMode,
+    /// Scale as of the last input, for scrolling without the state at hand
+    pub(super) scale_mask: [bool; 12],
+    /// Export dialog requested by the last key press, taken by main.rs
+    pub(super) pending_export_dialog: Option<ExportTarget>,
+    /// Quantize popup requested by the last key press, taken by main.rs
+    pub(super) pending_quantize: Option<QuantizeTarget>,
+    /// Length of the region last copied here, so pasted phrases tile on it
+    pub(super) copied_span: Option<u32>,
+    /// Typing how many times to paste
+    pub(super) typing_repeats: bool,
+    pub(super) repeat_input: TextInput,
+    pub(super) visual_offset: VisualOffset,
 }
 
 impl PianoRollPane {
@@ -48,10 +123,28 @@ impl PianoRollPane {
             recording: false,
             automation_overlay_visible: false,
             automation_overlay_lane_idx: None,
+            velocity_lane_visible: false,
             selection_anchor: None,
+            linked_tracks: BTreeSet::new(),
+            pending_audition: None,
+            scrub: false,
+            ghost_notes: false,
+            scale_mode: ScaleMode::Off,
+            scale_mask: [true; 12],
+            pending_export_dialog: None,
+            pending_quantize: None,
+            copied_span: None,
+            typing_repeats: false,
+            repeat_input: TextInput::new(""),
+            visual_offset: VisualOffset::default(),
         }
     }
 
+    /// Draw the playhead this far ahead of the transport
+    pub fn set_visual_offset(&mut self, offset: VisualOffset) {
+        self.visual_offset = offset;
+    }
+
     /// Set current track index directly (for external syncing from global instrument selection)
     #[allow(dead_code)]
     pub fn current_track(&self) -> usize { self.current_track }
@@ -78,6 +171,52 @@ impl PianoRollPane {
         self.current_track = idx;
     }
 
+    pub fn view(&self) -> PianoRollView {
+        PianoRollView {
+            start_tick: self.view_start_tick,
+            bottom_pitch: self.view_bottom_pitch,
+            zoom: self.zoom_level,
+            cursor_tick: self.cursor_tick,
+            cursor_pitch: self.cursor_pitch,
+        }
+    }
+
+    /// Restore a saved view, e.g. when a project is reopened
+    pub fn set_view(&mut self, view: PianoRollView) {
+        self.view_start_tick = view.start_tick;
+        self.view_bottom_pitch = view.bottom_pitch.min(127);
+        self.zoom_level = view.zoom.clamp(1, 5);
+        self.cursor_tick = view.cursor_tick;
+        self.cursor_pitch = view.cursor_pitch.min(127);
+    }
+
+    pub fn automation_overlay(&self) -> bool {
+        self.automation_overlay_visible
+    }
+
+    pub fn set_automation_overlay(&mut self, visible: bool) {
+        self.automation_overlay_visible = visible;
+        if visible {
+            self.velocity_lane_visible = false;
+        }
+    }
+
+    /// Remember the length of the region just copied
+    pub fn set_copied_span(&mut self, ticks: u32) {
+        self.copied_span = Some(ticks);
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.typing_repeats
+    }
+
+    /// Put the cursor on `tick` and `pitch` and scroll it into view
+    pub fn jump_to(&mut self, tick: u32, pitch: u8) {
+        self.cursor_tick = tick;
+        self.cursor_pitch = pitch;
+        self.scroll_to_cursor();
+    }
+
     pub fn jump_to_end(&mut self) {
         // Jump to a reasonable far position (e.g., 16 bars worth)
         self.cursor_tick = 480 * 4 * 16; // 16 bars at 4/4
@@ -100,6 +239,38 @@ impl PianoRollPane {
         }
     }
 
+    /// Take the audition requested since the last call
+    pub fn take_audition(&mut self) -> Option<Audition> {
+        self.pending_audition.take()
+    }
+
+    /// Take the quantize popup requested since the last call
+    pub fn take_quantize(&mut self) -> Option<QuantizeTarget> {
+        self.pending_quantize.take()
+    }
+
+    /// Take the export dialog requested since the last call
+    pub fn take_export_dialog(&mut self) -> Option<ExportTarget> {
+        self.pending_export_dialog.take()
+    }
+
+    /// Add or remove the current track from the edit group
+    pub(crate) fn toggle_track_link(&mut self) {
+        if !self.linked_tracks.remove(&self.current_track) {
+            self.linked_tracks.insert(self.current_track);
+        }
+    }
+
+    /// Tracks an edit applies to: the whole edit group when the current
+    /// track belongs to it, otherwise just the current track.
+    pub(crate) fn edit_tracks(&self, track_count: usize) -> Vec<usize> {
+        if self.linked_tracks.contains(&self.current_track) {
+            self.linked_tracks.iter().copied().filter(|&t| t < track_count).collect()
+        } else {
+            vec![self.current_track]
+        }
+    }
+
     /// Ticks per grid cell based on zoom level
     pub(crate) fn ticks_per_cell(&self) -> u32 {
         crate::state::grid::ticks_per_cell(self.zoom_level)
@@ -110,12 +281,48 @@ impl PianoRollPane {
         crate::state::grid::snap_to_grid(tick, self.zoom_level)
     }
 
+    /// Pitch shown `rows_up` rows above the bottom of the grid; folding
+    /// skips pitches outside `mask`
+    pub(super) fn pitch_at_row(&self, mask: &[bool; 12], rows_up: u16) -> Option<u8> {
+        if self.scale_mode == ScaleMode::Fold {
+            (self.view_bottom_pitch..=127).filter(|p| mask[*p as usize % 12]).nth(rows_up as usize)
+        } else {
+            u8::try_from(self.view_bottom_pitch as u16 + rows_up).ok().filter(|p| *p <= 127)
+        }
+    }
+
+    /// Next pitch up or down from `pitch` that is in the scale
+    pub(super) fn scale_step(&self, pitch: u8, up: bool) -> Option<u8> {
+        if up {
+            (pitch.saturating_add(1)..=127).find(|p| *p > pitch && self.scale_mask[*p as usize % 12])
+        } else {
+            (0..pitch).rev().find(|p| self.scale_mask[*p as usize % 12])
+        }
+    }
+
+    /// `pitch` if it is in the scale, else the nearest scale pitch below
+    /// it, or above it when there is none below
+    pub(super) fn snap_to_scale(&self, pitch: u8) -> u8 {
+        if self.scale_mask[pitch as usize % 12] {
+            return pitch;
+        }
+        self.scale_step(pitch, false).or_else(|| self.scale_step(pitch, true)).unwrap_or(pitch)
+    }
+
     /// Ensure cursor is visible by adjusting view
     pub(crate) fn scroll_to_cursor(&mut self) {
         // Vertical: keep cursor within visible range
         let visible_rows = 24u8;
         if self.cursor_pitch < self.view_bottom_pitch {
             self.view_bottom_pitch = self.cursor_pitch;
+        } else if self.scale_mode == ScaleMode::Fold {
+            // Rows are scale pitches, so count those up to the cursor
+            let below: Vec<u8> = (self.view_bottom_pitch..self.cursor_pitch)
+                .filter(|p| self.scale_mask[*p as usize % 12])
+                .collect();
+            if below.len() >= visible_rows as usize {
+                self.view_bottom_pitch = below[below.len() + 1 - visible_rows as usize];
+            }
         } else if self.cursor_pitch >= self.view_bottom_pitch.saturating_add(visible_rows) {
             self.view_bottom_pitch = self.cursor_pitch.saturating_sub(visible_rows - 1);
         }
@@ -176,11 +383,18 @@ impl Pane for PianoRollPane {
         self.handle_mouse_impl(event, area, state)
     }
 
+    fn handle_raw_input(&mut self, event: &InputEvent, _state: &AppState) -> Action {
+        if self.typing_repeats {
+            self.repeat_input.handle_input(event);
+        }
+        Action::None
+    }
+
     fn render(&mut self, area: Rect, buf: &mut RenderBuf, state: &AppState) {
         self.render_notes_buf(buf, area, state);
 
-        // Automation overlay
-        if self.automation_overlay_visible {
+        // Automation overlay or velocity lane
+        if self.automation_overlay_visible || self.velocity_lane_visible {
             let rect = center_rect(area, 97, 29);
             let key_col_width: u16 = 5;
             let header_height: u16 = 2;
@@ -194,7 +408,11 @@ impl Pane for PianoRollPane {
             let overlay_y = rect.y + header_height + grid_height - overlay_rows;
             let overlay_area = Rect::new(rect.x, overlay_y, rect.width, overlay_rows);
 
-            self.render_automation_overlay(buf, overlay_area, grid_x, grid_width, state);
+            if self.velocity_lane_visible {
+                self.render_velocity_lane(buf, overlay_area, grid_x, grid_width, state);
+            } else {
+                self.render_automation_overlay(buf, overlay_area, grid_x, grid_width, state);
+            }
         }
     }
 
@@ -242,6 +460,28 @@ mod tests {
         InputEvent::new(KeyCode::Char('x'), Modifiers::default())
     }
 
+    #[test]
+    fn clicking_the_ruler_seeks_to_that_tick() {
+        use crate::ui::{MouseButton, MouseEventKind};
+        let mut pane = PianoRollPane::new(Keymap::new());
+        let state = AppState::new();
+
+        let area = Rect::new(0, 0, 120, 40);
+        let rect = center_rect(area, 97, 29);
+        // Ruler sits under the grid, after the 2-row header
+        let event = MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: rect.x + 5 + 4,
+            row: rect.y + rect.height - 3,
+            modifiers: Modifiers::default(),
+        };
+        let expected = (pane.view_start_tick + 4 * pane.ticks_per_cell()) as i64 - state.audio.playhead as i64;
+        match pane.handle_mouse(&event, area, &state) {
+            Action::PianoRoll(PianoRollAction::SeekRelative(delta)) => assert_eq!(delta as i64, expected),
+            _ => panic!("Expected SeekRelative"),
+        }
+    }
+
     #[test]
     fn cursor_moves_with_arrow_actions() {
         let mut pane = PianoRollPane::new(Keymap::new());
@@ -295,4 +535,161 @@ mod tests {
         let action = pane.handle_action(ActionId::PianoRoll(PianoRollActionId::ToggleNote), &dummy_event(), &state);
         assert!(matches!(action, Action::PianoRoll(PianoRollAction::ToggleNote { .. })));
     }
+
+    #[test]
+    fn record_settings_pushes_popup() {
+        use crate::ui::NavAction;
+        let mut pane = PianoRollPane::new(Keymap::new());
+        let state = AppState::new();
+
+        let action = pane.handle_action(ActionId::PianoRoll(PianoRollActionId::RecordSettings), &dummy_event(), &state);
+        assert!(matches!(action, Action::Nav(NavAction::PushPane("record_settings"))));
+    }
+
+    #[test]
+    fn edit_group_applies_only_from_linked_track() {
+        let mut pane = PianoRollPane::new(Keymap::new());
+        pane.set_current_track(0);
+        pane.toggle_track_link();
+        pane.set_current_track(2);
+        pane.toggle_track_link();
+        assert_eq!(pane.edit_tracks(4), vec![0, 2]);
+        // Removed tracks drop out of the group
+        assert_eq!(pane.edit_tracks(2), vec![0]);
+
+        pane.set_current_track(1);
+        assert_eq!(pane.edit_tracks(4), vec![1]);
+        pane.set_current_track(2);
+        pane.toggle_track_link();
+        assert_eq!(pane.edit_tracks(4), vec![2]);
+    }
+
+    #[test]
+    fn transpose_moves_cursor_with_notes() {
+        let mut pane = PianoRollPane::new(Keymap::new());
+        let state = AppState::new();
+
+        let action = pane.handle_action(ActionId::PianoRoll(PianoRollActionId::TransposeUp), &dummy_event(), &state);
+        assert!(matches!(action, Action::PianoRoll(PianoRollAction::TransposeNotesInRegion { semitones: 1, .. })));
+        assert_eq!(pane.cursor_pitch, 61);
+    }
+
+    #[test]
+    fn paste_repeat_tiles_the_copied_phrase() {
+        use crate::state::{ClipboardContents, ClipboardNote};
+        use crate::ui::action_id::ModeActionId;
+        let mut pane = PianoRollPane::new(Keymap::new());
+        let mut state = AppState::new();
+
+        // Nothing to paste yet
+        let action = pane.handle_action(ActionId::PianoRoll(PianoRollActionId::PasteRepeat), &dummy_event(), &state);
+        assert!(matches!(action, Action::None));
+
+        state.clipboard.contents = Some(ClipboardContents::PianoRollNotes(vec![
+            ClipboardNote { tick_offset: 0, pitch_offset: 0, duration: 240, velocity: 100 },
+        ]));
+        pane.set_copied_span(960);
+        pane.cursor_tick = 480;
+        let action = pane.handle_action(ActionId::PianoRoll(PianoRollActionId::PasteRepeat), &dummy_event(), &state);
+        assert!(matches!(action, Action::PushLayer("text_edit")));
+        assert!(pane.is_editing());
+
+        pane.repeat_input.set_value("3");
+        let action = pane.handle_action(ActionId::Mode(ModeActionId::TextConfirm), &dummy_event(), &state);
+        let Action::Batch(pastes) = action else { panic!("Expected a batch of pastes") };
+        let anchors: Vec<u32> = pastes.iter().map(|a| match a {
+            Action::PianoRoll(PianoRollAction::PasteNotes { anchor_tick, .. }) => *anchor_tick,
+            _ => panic!("Expected PasteNotes"),
+        }).collect();
+        assert_eq!(anchors, vec![480, 1440, 2400]);
+        assert!(!pane.is_editing());
+    }
+
+    #[test]
+    fn scrub_without_notes_queues_nothing() {
+        let mut pane = PianoRollPane::new(Keymap::new());
+        let state = AppState::new();
+
+        pane.handle_action(ActionId::PianoRoll(PianoRollActionId::ToggleScrub), &dummy_event(), &state);
+        assert!(pane.scrub);
+        pane.handle_action(ActionId::PianoRoll(PianoRollActionId::Right), &dummy_event(), &state);
+        assert!(pane.take_audition().is_none());
+    }
+
+    #[test]
+    fn velocity_ramp_runs_between_the_end_notes() {
+        use super::input::ramp_velocity;
+        assert_eq!(ramp_velocity(0, (0, 40), (480, 120)), 40);
+        assert_eq!(ramp_velocity(240, (0, 40), (480, 120)), 80);
+        assert_eq!(ramp_velocity(480, (0, 40), (480, 120)), 120);
+        assert_eq!(ramp_velocity(120, (0, 100), (480, 20)), 80);
+        // Notes all at one tick keep the first velocity
+        assert_eq!(ramp_velocity(0, (0, 64), (0, 127)), 64);
+
+        // The lane and the automation overlay share the strip
+        let mut pane = PianoRollPane::new(Keymap::new());
+        let state = AppState::new();
+        pane.set_automation_overlay(true);
+        pane.handle_action(ActionId::PianoRoll(PianoRollActionId::ToggleVelocityLane), &dummy_event(), &state);
+        assert!(pane.velocity_lane_visible);
+        assert!(!pane.automation_overlay_visible);
+    }
+
+    #[test]
+    fn resizing_keeps_notes_within_a_step_and_their_neighbours() {
+        use super::input::resized_duration;
+        assert_eq!(resized_duration(480, 120, true, None), 600);
+        assert_eq!(resized_duration(480, 120, false, None), 360);
+        // Never shorter than one step, and never shrinking what already is
+        assert_eq!(resized_duration(120, 120, false, None), 120);
+        assert_eq!(resized_duration(60, 120, false, None), 60);
+        // Growing stops at the next note on the same pitch
+        assert_eq!(resized_duration(480, 120, true, Some(540)), 540);
+        assert_eq!(resized_duration(480, 120, true, Some(480)), 480);
+
+        // Nothing under the cursor: the default length changes instead
+        let mut pane = PianoRollPane::new(Keymap::new());
+        let state = AppState::new();
+        let before = pane.default_duration;
+        let action = pane.handle_action(ActionId::PianoRoll(PianoRollActionId::GrowDuration), &dummy_event(), &state);
+        assert!(matches!(action, Action::None));
+        assert_eq!(pane.default_duration, before + pane.ticks_per_cell());
+    }
+
+    #[test]
+    fn ghost_notes_toggle_and_tell_tracks_apart() {
+        use super::rendering::ghost_color;
+        let mut pane = PianoRollPane::new(Keymap::new());
+        let state = AppState::new();
+        pane.handle_action(ActionId::PianoRoll(PianoRollActionId::ToggleGhosts), &dummy_event(), &state);
+        assert!(pane.ghost_notes);
+        assert_ne!(ghost_color(0), ghost_color(1));
+        assert_eq!(ghost_color(0), ghost_color(6));
+    }
+
+    #[test]
+    fn scale_lock_steps_and_folds_over_degrees() {
+        let mut pane = PianoRollPane::new(Keymap::new());
+        // C major
+        pane.scale_mask = [true, false, true, false, true, true, false, true, false, true, false, true];
+        assert_eq!(pane.scale_step(60, true), Some(62));
+        assert_eq!(pane.scale_step(64, true), Some(65));
+        assert_eq!(pane.scale_step(60, false), Some(59));
+        assert_eq!(pane.scale_step(127, true), None);
+        assert_eq!(pane.snap_to_scale(61), 60);
+        assert_eq!(pane.snap_to_scale(62), 62);
+
+        let mask = pane.scale_mask;
+        pane.view_bottom_pitch = 60;
+        assert_eq!(pane.pitch_at_row(&mask, 1), Some(61));
+        pane.scale_mode = ScaleMode::Fold;
+        assert_eq!(pane.pitch_at_row(&mask, 1), Some(62));
+        assert_eq!(pane.pitch_at_row(&mask, 7), Some(72));
+
+        // Folded, scrolling counts scale rows rather than semitones
+        pane.cursor_pitch = 108;
+        pane.scroll_to_cursor();
+        let rows = (pane.view_bottom_pitch..108).filter(|p| mask[*p as usize % 12]).count();
+        assert_eq!(rows, 23);
+    }
 }
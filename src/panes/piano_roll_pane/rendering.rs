@@ -1,8 +1,9 @@
-use crate::state::AppState;
+use crate::state::{AppState, RecordMode};
+use crate::panes::record_settings_pane::grid_label;
 use crate::ui::layout_helpers::center_rect;
 use crate::ui::{Rect, RenderBuf, Color, Style};
 
-use super::PianoRollPane;
+use super::{scale_mask, PianoRollPane, ScaleMode};
 
 /// MIDI note name for a given pitch (0-127)
 pub(super) fn note_name(pitch: u8) -> String {
@@ -23,6 +24,17 @@ pub(super) const AUTOMATION_BLOCKS: [char; 8] = [
     '\u{2585}', '\u{2586}', '\u{2587}', '\u{2588}',
 ];
 
+/// Dimmed hues for other tracks' ghost notes, cycled by track index
+const GHOST_COLORS: [(u8, u8, u8); 6] = [
+    (40, 70, 90), (80, 60, 30), (40, 80, 50), (80, 40, 70), (70, 70, 40), (50, 50, 90),
+];
+
+/// Ghost note color for the track at `index`
+pub(super) fn ghost_color(index: usize) -> Color {
+    let (r, g, b) = GHOST_COLORS[index % GHOST_COLORS.len()];
+    Color::new(r, g, b)
+}
+
 impl PianoRollPane {
     /// Render the automation overlay strip at the bottom of the note grid
     pub(super) fn render_automation_overlay(
@@ -126,9 +138,83 @@ impl PianoRollPane {
         }
     }
 
+    /// Render the velocity lane: one bar per column for the loudest note
+    /// starting in it on the current track
+    pub(super) fn render_velocity_lane(
+        &self,
+        buf: &mut RenderBuf,
+        overlay_area: Rect,
+        grid_x: u16,
+        grid_width: u16,
+        state: &AppState,
+    ) {
+        let overlay_height = overlay_area.height;
+        if overlay_height == 0 { return; }
+
+        let sep_style = Style::new().fg(Color::new(50, 40, 60));
+        for x in overlay_area.x..overlay_area.x + overlay_area.width {
+            buf.set_cell(x, overlay_area.y, '─', sep_style);
+        }
+        for y in overlay_area.y + 1..overlay_area.y + overlay_height {
+            for x in overlay_area.x..overlay_area.x + overlay_area.width {
+                buf.set_cell(x, y, ' ', Style::new());
+            }
+        }
+
+        // Label, and the velocity being edited below it
+        let targets = self.note_targets(state);
+        let label_style = Style::new().fg(Color::CYAN);
+        let value = match (targets.first(), targets.len()) {
+            (Some(n), 1) => n.4.to_string(),
+            (Some(_), count) => format!("{}n", count),
+            (None, _) => "—".to_string(),
+        };
+        for (row, text) in ["Vel", value.as_str()].iter().enumerate() {
+            let y = overlay_area.y + 1 + row as u16;
+            if y >= overlay_area.y + overlay_height { break; }
+            for (i, ch) in text.chars().enumerate() {
+                let x = overlay_area.x + i as u16;
+                if x >= grid_x { break; }
+                buf.set_cell(x, y, ch, label_style);
+            }
+        }
+
+        let graph_rows = overlay_height.saturating_sub(1);
+        if graph_rows == 0 { return; }
+        let Some(track) = state.session.piano_roll.track_at(self.current_track) else { return };
+
+        let tpc = self.ticks_per_cell();
+        let bar_style = Style::new().fg(Color::PINK);
+        let target_style = Style::new().fg(Color::GOLD);
+        let bottom = overlay_area.y + overlay_height - 1;
+
+        for col in 0..grid_width {
+            let x = grid_x + col;
+            if x >= overlay_area.x + overlay_area.width { break; }
+            let tick = self.view_start_tick + col as u32 * tpc;
+            let Some(note) = track.notes.iter()
+                .filter(|n| n.tick >= tick && n.tick < tick + tpc)
+                .max_by_key(|n| n.velocity)
+            else { continue };
+
+            let targeted = targets.iter()
+                .any(|t| t.0 == self.current_track && t.1 == note.tick && t.2 == note.pitch);
+            let style = if targeted { target_style } else { bar_style };
+
+            // Height in eighths of a row, at least a sliver for any note
+            let eighths = ((note.velocity as u32 * graph_rows as u32 * 8 + 63) / 127).max(1);
+            for r in 0..graph_rows {
+                let fill = eighths.saturating_sub(r as u32 * 8).min(8);
+                if fill == 0 { break; }
+                buf.set_cell(x, bottom - r, AUTOMATION_BLOCKS[fill as usize - 1], style);
+            }
+        }
+    }
+
     /// Render notes grid (buffer version)
     pub(super) fn render_notes_buf(&self, buf: &mut RenderBuf, area: Rect, state: &AppState) {
         let piano_roll = &state.session.piano_roll;
+        let playhead = self.visual_offset.playhead(state);
         let rect = center_rect(area, 97, 29);
 
         // Layout constants
@@ -171,10 +257,26 @@ impl PianoRollPane {
             ts_den,
             play_icon,
             loop_icon,
-            piano_roll.tick_to_beat(state.audio.playhead),
+            piano_roll.tick_to_beat(playhead),
         );
+        let swing_text = piano_roll.track_at(self.current_track)
+            .filter(|track| track.swing > 0.0)
+            .map(|track| format!("  Swing:{:.0}%", track.swing * 100.0))
+            .unwrap_or_default();
+        let link_text = if self.linked_tracks.is_empty() {
+            String::new()
+        } else {
+            let tracks: Vec<String> = self.linked_tracks.iter().map(|t| (t + 1).to_string()).collect();
+            let marker = if self.linked_tracks.contains(&self.current_track) { "*" } else { "" };
+            format!("  Linked{}:{}", marker, tracks.join(","))
+        };
+        let scrub_text = if self.scrub { "  SCRUB" } else { "" };
+        let ghost_text = if self.ghost_notes { "  GHOSTS" } else { "" };
+        let scale_text = self.scale_mode.label();
         buf.draw_line(Rect::new(rect.x + 1, header_y, rect.width.saturating_sub(2), 1),
-            &[(&header_text, Style::new().fg(Color::WHITE))]);
+            &[(&header_text, Style::new().fg(Color::WHITE)), (&swing_text, Style::new().fg(Color::DARK_GRAY)),
+              (&link_text, Style::new().fg(Color::GOLD)), (scrub_text, Style::new().fg(Color::CYAN)),
+              (ghost_text, Style::new().fg(Color::GRAY)), (scale_text, Style::new().fg(Color::LIME))]);
 
         // Loop range indicator
         if piano_roll.looping {
@@ -223,18 +325,19 @@ impl PianoRollPane {
         }
 
         // Piano keys column + grid rows
+        let mask = scale_mask(state);
+        let root = (state.session.key.semitone() % 12) as u8;
         for row in 0..grid_height {
-            let pitch = self.view_bottom_pitch.saturating_add((grid_height - 1 - row) as u8);
-            if pitch > 127 {
-                continue;
-            }
+            let Some(pitch) = self.pitch_at_row(&mask, grid_height - 1 - row) else { continue };
             let y = grid_y + row;
 
-            // Piano key label
+            // Piano key label; with a scale shown, rows out of it are the dim ones
             let name = note_name(pitch);
-            let is_black = is_black_key(pitch);
+            let is_black = if self.scale_mode == ScaleMode::Off { is_black_key(pitch) } else { !mask[pitch as usize % 12] };
             let key_style = if pitch == self.cursor_pitch {
                 Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG)
+            } else if self.scale_mode != ScaleMode::Off && pitch % 12 == root {
+                Style::new().fg(Color::LIME)
             } else if is_black {
                 Style::new().fg(Color::GRAY)
             } else {
@@ -263,10 +366,21 @@ impl PianoRollPane {
                     track.notes.iter().any(|n| n.pitch == pitch && n.tick == tick)
                 });
 
+                // First other track with a note here, when ghosts are shown
+                let ghost = if self.ghost_notes && !has_note {
+                    (0..piano_roll.track_order.len())
+                        .filter(|&i| i != self.current_track)
+                        .find(|&i| piano_roll.track_at(i).is_some_and(|track| {
+                            track.notes.iter().any(|n| n.pitch == pitch && tick >= n.tick && tick < n.tick + n.duration)
+                        }))
+                } else {
+                    None
+                };
+
                 let is_cursor = pitch == self.cursor_pitch && tick == self.cursor_tick;
                 let is_playhead = piano_roll.playing
-                    && tick <= state.audio.playhead
-                    && state.audio.playhead < tick + self.ticks_per_cell();
+                    && tick <= playhead
+                    && playhead < tick + self.ticks_per_cell();
 
                 let tpb = piano_roll.ticks_per_beat;
                 let tpbar = piano_roll.ticks_per_bar();
@@ -305,6 +419,8 @@ impl PianoRollPane {
                     } else {
                         ('█', Style::new().fg(Color::MAGENTA))
                     }
+                } else if let Some(track) = ghost {
+                    ('▒', Style::new().fg(ghost_color(track)))
                 } else if is_playhead {
                     ('│', Style::new().fg(Color::GREEN))
                 } else if is_bar_line {
@@ -356,8 +472,15 @@ impl PianoRollPane {
                 self.default_duration,
             )
         };
-        buf.draw_line(Rect::new(rect.x + 1, status_y, rect.width.saturating_sub(2), 1),
-            &[(&vel_str, Style::new().fg(Color::GRAY))]);
+        if self.typing_repeats {
+            let prompt = "Paste how many times: ";
+            buf.draw_line(Rect::new(rect.x + 1, status_y, prompt.len() as u16, 1),
+                &[(prompt, Style::new().fg(Color::WHITE))]);
+            self.repeat_input.render_buf(buf.raw_buf(), rect.x + 1 + prompt.len() as u16, status_y, 6);
+        } else {
+            buf.draw_line(Rect::new(rect.x + 1, status_y, rect.width.saturating_sub(2), 1),
+                &[(&vel_str, Style::new().fg(Color::GRAY))]);
+        }
 
         // Piano mode indicator
         if self.piano.is_active() {
@@ -365,7 +488,16 @@ impl PianoRollPane {
             let mut indicator_x = rect.x + rect.width - piano_str.len() as u16 - 1;
 
             if self.recording {
-                let rec_str = " REC ";
+                let rs = &piano_roll.record_settings;
+                let mode = match rs.mode {
+                    RecordMode::Overdub => "OVR",
+                    RecordMode::Replace => "RPL",
+                };
+                let rec_str = if rs.quantize_grid > 0 {
+                    format!(" REC {} Q{} {}% ", mode, grid_label(rs.quantize_grid, piano_roll.ticks_per_beat), rs.quantize_strength)
+                } else {
+                    format!(" REC {} ", mode)
+                };
                 indicator_x -= rec_str.len() as u16;
                 let rec_style = Style::new().fg(Color::WHITE).bg(Color::RED);
                 for (j, ch) in rec_str.chars().enumerate() {
@@ -0,0 +1,464 @@
+use std::any::Any;
+use std::path::PathBuf;
+
+use crate::av_sync::{MAX_OFFSET_MS, OFFSET_STEP_MS};
+use crate::export_format::ExportFormat;
+use crate::export_region::RegionSettings;
+use crate::low_power::FRAME_RATES;
+use crate::practice::SessionTimer;
+use crate::preferences::{layout_name, GraphicsMode, Preferences, LAYOUT_NAMES};
+use crate::sample_import::ResampleQuality;
+use crate::state::AppState;
+use crate::velocity::{KeyVelocityMode, VelocityCurve};
+use crate::workspace::Workspace;
+use crate::ui::action_id::{ActionId, ModeActionId, PreferencesActionId};
+use crate::ui::layout_helpers::center_rect;
+use crate::ui::widgets::TextInput;
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, NavAction, Pane, Style};
+
+/// Fields editable in the preferences pane
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    KeyboardLayout,
+    KeyVelocityMode,
+    KeyVelocity,
+    AccentVelocity,
+    MidiVelocityCurve,
+    PanicMidiReset,
+    Autosave,
+    SessionTimer,
+    Graphics,
+    FrameRate,
+    LowPower,
+    VisualOffset,
+    SamplesDir,
+    ProjectsDir,
+    ImpulseResponsesDir,
+    ResampleQuality,
+    AutoStartServer,
+    ServerAddress,
+}
+
+const FIELDS: [Field; 19] = [
+    Field::KeyboardLayout,
+    Field::KeyVelocityMode,
+    Field::KeyVelocity,
+    Field::AccentVelocity,
+    Field::MidiVelocityCurve,
+    Field::PanicMidiReset,
+    Field::Autosave,
+    Field::SessionTimer,
+    Field::Graphics,
+    Field::FrameRate,
+    Field::LowPower,
+    Field::VisualOffset,
+    Field::SamplesDir,
+    Field::ProjectsDir,
+    Field::ImpulseResponsesDir,
+    Field::ResampleQuality,
+    Field::AutoStartServer,
+    Field::ServerAddress,
+];
+
+const AUTOSAVE_STEPS: [u32; 6] = [0, 1, 2, 5, 10, 15];
+
+fn step_velocity(velocity: u8, increase: bool) -> u8 {
+    if increase { velocity.saturating_add(5).min(127) } else { velocity.saturating_sub(5).max(1) }
+}
+
+impl Field {
+    fn is_text(self) -> bool {
+        matches!(self, Field::SamplesDir | Field::ProjectsDir | Field::ImpulseResponsesDir | Field::ServerAddress)
+    }
+}
+
+/// Application-wide preferences. Changes are saved to config.toml and
+/// applied immediately; server options take effect on the next start.
+pub struct PreferencesPane {
+    keymap: Keymap,
+    prefs: Preferences,
+    selected: usize,
+    editing: bool,
+    edit_input: TextInput,
+    /// Set when prefs changed; main.rs takes it to save and apply
+    changed: bool,
+}
+
+impl PreferencesPane {
+    pub fn new(keymap: Keymap, prefs: Preferences) -> Self {
+        Self {
+            keymap,
+            prefs,
+            selected: 0,
+            editing: false,
+            edit_input: TextInput::new(""),
+            changed: false,
+        }
+    }
+
+    /// Changed preferences since the last call, if any
+    pub fn take_changed(&mut self) -> Option<Preferences> {
+        if std::mem::take(&mut self.changed) {
+            Some(self.prefs.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Record that the tutorial was seen, so saving other edits keeps it
+    pub fn set_tutorial_seen(&mut self) {
+        self.prefs.tutorial_seen = true;
+    }
+
+    /// Record the export dialog's choice, so saving other edits keeps it
+    pub fn set_export_settings(&mut self, format: ExportFormat, region: RegionSettings) {
+        self.prefs.export_format = format;
+        self.prefs.export_region = region;
+    }
+
+    /// Record saved workspaces, so saving other edits keeps them
+    pub fn set_workspaces(&mut self, workspaces: Vec<Workspace>) {
+        self.prefs.workspaces = workspaces;
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.editing
+    }
+
+    fn current_field(&self) -> Field {
+        FIELDS[self.selected]
+    }
+
+    fn adjust(&mut self, increase: bool) {
+        let p = &mut self.prefs;
+        match self.current_field() {
+            Field::KeyboardLayout => {
+                let idx = LAYOUT_NAMES.iter().position(|n| *n == p.keyboard_layout).unwrap_or(0);
+                let len = LAYOUT_NAMES.len();
+                let next = if increase { (idx + 1) % len } else { (idx + len - 1) % len };
+                p.keyboard_layout = LAYOUT_NAMES[next].to_string();
+            }
+            Field::KeyVelocityMode => {
+                p.key_velocity_mode = match p.key_velocity_mode {
+                    KeyVelocityMode::Fixed => KeyVelocityMode::Rows,
+                    KeyVelocityMode::Rows => KeyVelocityMode::Fixed,
+                };
+            }
+            Field::KeyVelocity => p.key_velocity = step_velocity(p.key_velocity, increase),
+            Field::AccentVelocity => p.key_accent_velocity = step_velocity(p.key_accent_velocity, increase),
+            Field::MidiVelocityCurve => {
+                let all = VelocityCurve::ALL;
+                let idx = all.iter().position(|c| *c == p.midi_velocity_curve).unwrap_or(0);
+                let next = if increase { (idx + 1) % all.len() } else { (idx + all.len() - 1) % all.len() };
+                p.midi_velocity_curve = all[next];
+            }
+            Field::Autosave => {
+                let idx = AUTOSAVE_STEPS.iter().position(|m| *m >= p.autosave_minutes).unwrap_or(0);
+                let new_idx = if increase { (idx + 1).min(AUTOSAVE_STEPS.len() - 1) } else { idx.saturating_sub(1) };
+                p.autosave_minutes = AUTOSAVE_STEPS[new_idx];
+            }
+            Field::SessionTimer => {
+                let all = SessionTimer::ALL;
+                let idx = all.iter().position(|t| *t == p.session_timer).unwrap_or(0);
+                let next = if increase { (idx + 1) % all.len() } else { (idx + all.len() - 1) % all.len() };
+                p.session_timer = all[next];
+            }
+            Field::Graphics => {
+                let all = GraphicsMode::ALL;
+                let idx = all.iter().position(|g| *g == p.graphics).unwrap_or(0);
+                let next = if increase { (idx + 1) % all.len() } else { (idx + all.len() - 1) % all.len() };
+                p.graphics = all[next];
+            }
+            Field::FrameRate => {
+                // Rates run fastest first, so "increase" moves toward the front
+                let idx = FRAME_RATES.iter().position(|r| *r <= p.frame_rate).unwrap_or(0);
+                let new_idx = if increase { idx.saturating_sub(1) } else { (idx + 1).min(FRAME_RATES.len() - 1) };
+                p.frame_rate = FRAME_RATES[new_idx];
+            }
+            Field::VisualOffset => {
+                p.visual_offset_ms = if increase {
+                    (p.visual_offset_ms + OFFSET_STEP_MS).min(MAX_OFFSET_MS)
+                } else {
+                    p.visual_offset_ms.saturating_sub(OFFSET_STEP_MS)
+                };
+            }
+            Field::ResampleQuality => {
+                let all = ResampleQuality::ALL;
+                let idx = all.iter().position(|q| *q == p.resample_quality).unwrap_or(0);
+                let next = if increase { (idx + 1) % all.len() } else { (idx + all.len() - 1) % all.len() };
+                p.resample_quality = all[next];
+            }
+            Field::PanicMidiReset => p.panic_midi_reset = !p.panic_midi_reset,
+            Field::LowPower => p.low_power = !p.low_power,
+            Field::AutoStartServer => p.auto_start_server = !p.auto_start_server,
+            _ => return,
+        }
+        self.changed = true;
+    }
+
+    fn text_value(&self, field: Field) -> String {
+        let path = |p: &Option<PathBuf>| p.as_ref().map(|p| p.display().to_string()).unwrap_or_default();
+        match field {
+            Field::SamplesDir => path(&self.prefs.samples_dir),
+            Field::ProjectsDir => path(&self.prefs.projects_dir),
+            Field::ImpulseResponsesDir => path(&self.prefs.impulse_responses_dir),
+            Field::ServerAddress => self.prefs.server_address.clone(),
+            _ => String::new(),
+        }
+    }
+
+    fn set_text_value(&mut self, field: Field, text: &str) {
+        let text = text.trim();
+        let path = if text.is_empty() { None } else { Some(PathBuf::from(text)) };
+        match field {
+            Field::SamplesDir => self.prefs.samples_dir = path,
+            Field::ProjectsDir => self.prefs.projects_dir = path,
+            Field::ImpulseResponsesDir => self.prefs.impulse_responses_dir = path,
+            Field::ServerAddress if !text.is_empty() => self.prefs.server_address = text.to_string(),
+            _ => return,
+        }
+        self.changed = true;
+    }
+
+    fn field_label(field: Field) -> &'static str {
+        match field {
+            Field::KeyboardLayout => "Keyboard",
+            Field::KeyVelocityMode => "Key velocity",
+            Field::KeyVelocity => "  Velocity",
+            Field::AccentVelocity => "  Accent",
+            Field::MidiVelocityCurve => "MIDI curve",
+            Field::PanicMidiReset => "MIDI panic",
+            Field::Autosave => "Autosave",
+            Field::SessionTimer => "Timer",
+            Field::Graphics => "Graphics",
+            Field::FrameRate => "Frame rate",
+            Field::LowPower => "Low power",
+            Field::VisualOffset => "A/V offset",
+            Field::SamplesDir => "Samples dir",
+            Field::ProjectsDir => "Projects dir",
+            Field::ImpulseResponsesDir => "IR dir",
+            Field::ResampleQuality => "Resample",
+            Field::AutoStartServer => "Start server",
+            Field::ServerAddress => "Server addr",
+        }
+    }
+
+    fn field_value(&self, field: Field) -> String {
+        match field {
+            Field::KeyboardLayout if self.prefs.keyboard_layout == "auto" => {
+                format!("auto ({})", layout_name(self.prefs.keyboard_layout()))
+            }
+            Field::KeyboardLayout => self.prefs.keyboard_layout.clone(),
+            Field::KeyVelocityMode => self.prefs.key_velocity_mode.name().into(),
+            Field::KeyVelocity => self.prefs.key_velocity.to_string(),
+            Field::AccentVelocity if self.prefs.key_velocity_mode == KeyVelocityMode::Fixed => {
+                format!("{} (unused)", self.prefs.key_accent_velocity)
+            }
+            Field::AccentVelocity => self.prefs.key_accent_velocity.to_string(),
+            Field::MidiVelocityCurve => self.prefs.midi_velocity_curve.name().into(),
+            Field::PanicMidiReset => if self.prefs.panic_midi_reset { "All notes off to MIDI out".into() } else { "Off".into() },
+            Field::Autosave => match self.prefs.autosave_minutes {
+                0 => "Off".into(),
+                m => format!("every {} min", m),
+            },
+            Field::SessionTimer => self.prefs.session_timer.name().into(),
+            Field::Graphics if self.prefs.graphics == GraphicsMode::Auto => {
+                let detected = if self.prefs.graphics.high_res() { GraphicsMode::Braille } else { GraphicsMode::Blocks };
+                format!("auto ({})", detected.name())
+            }
+            Field::Graphics => self.prefs.graphics.name().into(),
+            Field::FrameRate => format!("{} fps", self.prefs.frame_rate),
+            Field::LowPower => if self.prefs.low_power { "Pause meters when unfocused or idle".into() } else { "Off".into() },
+            Field::VisualOffset => match self.prefs.visual_offset_ms {
+                0 => "Off".into(),
+                ms => format!("playhead drawn {} ms ahead", ms),
+            },
+            Field::ResampleQuality => match self.prefs.resample_quality {
+                ResampleQuality::Off => "Off (imports keep their rate)".into(),
+                q => format!("{} (to server rate on import)", q.name()),
+            },
+            Field::AutoStartServer => if self.prefs.auto_start_server { "On launch".into() } else { "Manual".into() },
+            Field::ServerAddress => format!("{} (next start)", self.prefs.server_address),
+            f => {
+                let value = self.text_value(f);
+                if value.is_empty() { "(default)".into() } else { value }
+            }
+        }
+    }
+}
+
+impl Default for PreferencesPane {
+    fn default() -> Self {
+        Self::new(Keymap::new(), Preferences::default())
+    }
+}
+
+impl Pane for PreferencesPane {
+    fn id(&self) -> &'static str {
+        "preferences"
+    }
+
+    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, _state: &AppState) -> Action {
+        match action {
+            ActionId::Mode(ModeActionId::TextConfirm) => {
+                let text = self.edit_input.value().to_string();
+                self.set_text_value(self.current_field(), &text);
+                self.editing = false;
+                self.edit_input.set_focused(false);
+                Action::None
+            }
+            ActionId::Mode(ModeActionId::TextCancel) => {
+                self.editing = false;
+                self.edit_input.set_focused(false);
+                Action::None
+            }
+            ActionId::Preferences(PreferencesActionId::Prev) => {
+                self.selected = self.selected.saturating_sub(1);
+                Action::None
+            }
+            ActionId::Preferences(PreferencesActionId::Next) => {
+                if self.selected < FIELDS.len() - 1 {
+                    self.selected += 1;
+                }
+                Action::None
+            }
+            ActionId::Preferences(PreferencesActionId::Decrease) => {
+                self.adjust(false);
+                Action::None
+            }
+            ActionId::Preferences(PreferencesActionId::Increase) => {
+                self.adjust(true);
+                Action::None
+            }
+            ActionId::Preferences(PreferencesActionId::Edit) => {
+                let field = self.current_field();
+                if field.is_text() {
+                    self.edit_input.set_value(&self.text_value(field));
+                    self.edit_input.select_all();
+                    self.edit_input.set_focused(true);
+                    self.editing = true;
+                    Action::PushLayer("text_edit")
+                } else {
+                    self.adjust(true);
+                    Action::None
+                }
+            }
+            ActionId::Preferences(PreferencesActionId::Close) => Action::Nav(NavAction::PopPane),
+            _ => Action::None,
+        }
+    }
+
+    fn handle_raw_input(&mut self, event: &InputEvent, _state: &AppState) -> Action {
+        if self.editing {
+            self.edit_input.handle_input(event);
+        }
+        Action::None
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, _state: &AppState) {
+        let rect = center_rect(area, 64, 23);
+
+        let border_style = Style::new().fg(Color::CYAN);
+        let inner = buf.draw_block(rect, " Preferences ", border_style, border_style);
+
+        let label_col = inner.x + 2;
+        let value_col = label_col + 15;
+        let value_width = inner.width.saturating_sub(18);
+
+        for (i, field) in FIELDS.iter().enumerate() {
+            let y = inner.y + 1 + i as u16;
+            if y >= inner.y + inner.height {
+                break;
+            }
+            let is_selected = i == self.selected;
+
+            if is_selected {
+                for x in inner.x..inner.x + inner.width {
+                    buf.set_cell(x, y, ' ', Style::new().bg(Color::SELECTION_BG));
+                }
+                buf.set_cell(label_col, y, '>', Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG).bold());
+            }
+
+            let label_style = if is_selected {
+                Style::new().fg(Color::CYAN).bg(Color::SELECTION_BG)
+            } else {
+                Style::new().fg(Color::CYAN)
+            };
+            let label = format!("{:13}", Self::field_label(*field));
+            buf.draw_line(Rect::new(label_col + 2, y, 13, 1), &[(&label, label_style)]);
+
+            if is_selected && self.editing {
+                self.edit_input.render_buf(buf.raw_buf(), value_col, y, value_width);
+            } else {
+                let val_style = if is_selected {
+                    Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG)
+                } else {
+                    Style::new().fg(Color::WHITE)
+                };
+                let val = self.field_value(*field);
+                buf.draw_line(Rect::new(value_col, y, value_width, 1), &[(&val, val_style)]);
+            }
+        }
+
+        let help_y = rect.y + rect.height - 2;
+        if help_y < area.y + area.height {
+            let help = if self.editing {
+                "Enter: confirm | Esc: cancel"
+            } else {
+                "Left/Right: adjust | Enter: edit path | Esc: close"
+            };
+            buf.draw_line(
+                Rect::new(inner.x + 2, help_y, inner.width.saturating_sub(2), 1),
+                &[(help, Style::new().fg(Color::DARK_GRAY))],
+            );
+        }
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adjusting_marks_changed_once() {
+        let mut pane = PreferencesPane::default();
+        pane.selected = FIELDS.iter().position(|f| *f == Field::Autosave).unwrap();
+        pane.adjust(true);
+        let prefs = pane.take_changed().unwrap();
+        assert_eq!(prefs.autosave_minutes, 1);
+        assert!(pane.take_changed().is_none());
+    }
+
+    #[test]
+    fn velocities_stay_in_midi_range() {
+        let mut pane = PreferencesPane::default();
+        pane.selected = FIELDS.iter().position(|f| *f == Field::KeyVelocity).unwrap();
+        for _ in 0..40 {
+            pane.adjust(true);
+        }
+        assert_eq!(pane.prefs.key_velocity, 127);
+        for _ in 0..40 {
+            pane.adjust(false);
+        }
+        assert_eq!(pane.prefs.key_velocity, 1);
+    }
+
+    #[test]
+    fn empty_path_resets_to_default() {
+        let mut pane = PreferencesPane::default();
+        pane.set_text_value(Field::SamplesDir, "/tmp/samples");
+        assert_eq!(pane.prefs.samples_dir, Some(PathBuf::from("/tmp/samples")));
+        pane.set_text_value(Field::SamplesDir, "  ");
+        assert_eq!(pane.prefs.samples_dir, None);
+        pane.set_text_value(Field::ServerAddress, "");
+        assert_eq!(pane.prefs.server_address, Preferences::default().server_address);
+    }
+}
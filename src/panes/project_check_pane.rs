@@ -0,0 +1,138 @@
+use std::any::Any;
+
+use crate::project_check::{self, Severity};
+use crate::state::AppState;
+use crate::ui::action_id::{ActionId, ProjectCheckActionId};
+use crate::ui::layout_helpers::center_rect;
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, NavAction, Pane, Style};
+
+/// Project statistics plus a fix-it list of integrity issues. The list is
+/// rescanned from state on every frame, so fixed issues drop off.
+pub struct ProjectCheckPane {
+    keymap: Keymap,
+    cursor: usize,
+}
+
+impl ProjectCheckPane {
+    pub fn new(keymap: Keymap) -> Self {
+        Self { keymap, cursor: 0 }
+    }
+}
+
+impl Default for ProjectCheckPane {
+    fn default() -> Self {
+        Self::new(Keymap::new())
+    }
+}
+
+impl Pane for ProjectCheckPane {
+    fn id(&self) -> &'static str {
+        "project_check"
+    }
+
+    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, state: &AppState) -> Action {
+        match action {
+            ActionId::ProjectCheck(ProjectCheckActionId::Up) => {
+                self.cursor = self.cursor.saturating_sub(1);
+                Action::None
+            }
+            ActionId::ProjectCheck(ProjectCheckActionId::Down) => {
+                let count = project_check::scan(state).len();
+                self.cursor = (self.cursor + 1).min(count.saturating_sub(1));
+                Action::None
+            }
+            ActionId::ProjectCheck(ProjectCheckActionId::Fix) => {
+                project_check::scan(state)
+                    .into_iter()
+                    .nth(self.cursor)
+                    .and_then(|issue| issue.fix)
+                    .unwrap_or(Action::None)
+            }
+            ActionId::ProjectCheck(ProjectCheckActionId::Close) => Action::Nav(NavAction::PopPane),
+            _ => Action::None,
+        }
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, state: &AppState) {
+        let rect = center_rect(area, 72, area.height.saturating_sub(4).clamp(12, 28));
+        let border_style = Style::new().fg(Color::CYAN);
+        let inner = buf.draw_block(rect, " Project Check ", border_style, border_style);
+
+        let x = inner.x + 1;
+        let w = inner.width.saturating_sub(2);
+        let dim = Style::new().fg(Color::DARK_GRAY);
+
+        // Statistics
+        let stats = project_check::stats(state);
+        let bars = stats.length_ticks as f32 / state.session.piano_roll.ticks_per_bar().max(1) as f32;
+        let lines = [
+            format!(
+                "Instruments: {}   Notes: {}   Length: {:.1} bars",
+                stats.instruments, stats.notes, bars,
+            ),
+            format!(
+                "Clips: {} ({} placed)   Automation: {} lanes, {} points   Buses: {}",
+                stats.clips, stats.placements, stats.automation_lanes, stats.automation_points, stats.buses,
+            ),
+        ];
+        for (i, line) in lines.iter().enumerate() {
+            buf.draw_line(Rect::new(x, inner.y + i as u16, w, 1), &[(line, Style::new().fg(Color::WHITE))]);
+        }
+
+        // Issues
+        let issues = project_check::scan(state);
+        self.cursor = self.cursor.min(issues.len().saturating_sub(1));
+        let list_y = inner.y + 3;
+        let title = format!("Issues ({})", issues.len());
+        buf.draw_line(Rect::new(x, list_y, w, 1), &[(&title, Style::new().fg(Color::CYAN).bold())]);
+
+        if issues.is_empty() {
+            buf.draw_line(Rect::new(x, list_y + 1, w, 1), &[("  No problems found", Style::new().fg(Color::METER_LOW))]);
+        }
+
+        let visible = inner.height.saturating_sub(6) as usize;
+        let scroll = if self.cursor >= visible { self.cursor + 1 - visible } else { 0 };
+        for (i, issue) in issues.iter().enumerate().skip(scroll).take(visible) {
+            let y = list_y + 1 + (i - scroll) as u16;
+            let is_selected = i == self.cursor;
+            let (marker, color) = match issue.severity {
+                Severity::Warning => ("! ", Color::ORANGE),
+                Severity::Info => ("· ", Color::GRAY),
+            };
+            let fix = if issue.fix.is_some() { format!("  [{}]", issue.fix_label) } else { String::new() };
+            let mut msg_style = Style::new().fg(Color::WHITE);
+            let mut marker_style = Style::new().fg(color);
+            let mut fix_style = dim;
+            if is_selected {
+                for cx in inner.x..inner.x + inner.width {
+                    buf.set_cell(cx, y, ' ', Style::new().bg(Color::SELECTION_BG));
+                }
+                msg_style = msg_style.bg(Color::SELECTION_BG);
+                marker_style = marker_style.bg(Color::SELECTION_BG);
+                fix_style = Style::new().fg(Color::GOLD).bg(Color::SELECTION_BG);
+            }
+            buf.draw_line(Rect::new(x, y, w, 1), &[
+                (marker, marker_style),
+                (&issue.message, msg_style),
+                (&fix, fix_style),
+            ]);
+        }
+
+        buf.draw_line(
+            Rect::new(x, inner.y + inner.height - 1, w, 1),
+            &[("Up/Down: select | Enter: fix | Esc: close", dim)],
+        );
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn on_enter(&mut self, _state: &AppState) {
+        self.cursor = 0;
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
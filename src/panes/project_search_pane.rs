@@ -0,0 +1,172 @@
+use std::any::Any;
+
+use crate::project_search::{self, Hit, Target};
+use crate::state::AppState;
+use crate::ui::action_id::{ActionId, ProjectSearchActionId};
+use crate::ui::layout_helpers::center_rect;
+use crate::ui::widgets::TextInput;
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, MouseEvent, MouseEventKind, NavAction, Pane, Style};
+
+/// Search overlay over instruments, automation lanes and notes. The chosen
+/// hit is taken by main.rs, which moves the UI to it.
+pub struct ProjectSearchPane {
+    keymap: Keymap,
+    query: TextInput,
+    hits: Vec<Hit>,
+    selected: usize,
+    scroll: usize,
+    /// Chosen hit, taken by main.rs
+    pending_jump: Option<Target>,
+}
+
+impl ProjectSearchPane {
+    pub fn new(keymap: Keymap) -> Self {
+        let mut query = TextInput::new("");
+        query.set_focused(true);
+        Self {
+            keymap,
+            query,
+            hits: Vec::new(),
+            selected: 0,
+            scroll: 0,
+            pending_jump: None,
+        }
+    }
+
+    pub fn take_jump(&mut self) -> Option<Target> {
+        self.pending_jump.take()
+    }
+
+    fn update_hits(&mut self, state: &AppState) {
+        self.hits = project_search::search(state, self.query.value());
+        self.selected = self.selected.min(self.hits.len().saturating_sub(1));
+    }
+
+    fn move_by(&mut self, delta: isize) {
+        let last = self.hits.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+}
+
+impl Default for ProjectSearchPane {
+    fn default() -> Self {
+        Self::new(Keymap::new())
+    }
+}
+
+impl Pane for ProjectSearchPane {
+    fn id(&self) -> &'static str {
+        "project_search"
+    }
+
+    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, _state: &AppState) -> Action {
+        match action {
+            ActionId::ProjectSearch(ProjectSearchActionId::Up) => self.move_by(-1),
+            ActionId::ProjectSearch(ProjectSearchActionId::Down) => self.move_by(1),
+            ActionId::ProjectSearch(ProjectSearchActionId::Select) => {
+                if let Some(hit) = self.hits.get(self.selected) {
+                    self.pending_jump = Some(hit.target);
+                    return Action::Nav(NavAction::PopPane);
+                }
+            }
+            ActionId::ProjectSearch(ProjectSearchActionId::Close) => return Action::Nav(NavAction::PopPane),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn handle_raw_input(&mut self, event: &InputEvent, state: &AppState) -> Action {
+        if self.query.handle_input(event) {
+            self.selected = 0;
+            self.scroll = 0;
+            self.update_hits(state);
+        }
+        Action::None
+    }
+
+    fn handle_mouse(&mut self, event: &MouseEvent, _area: Rect, _state: &AppState) -> Action {
+        match event.kind {
+            MouseEventKind::ScrollUp => self.move_by(-3),
+            MouseEventKind::ScrollDown => self.move_by(3),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, _state: &AppState) {
+        let rect = center_rect(area, 80, 24);
+        let border_style = Style::new().fg(Color::CYAN);
+        let inner = buf.draw_block(rect, " Search Project ", border_style, border_style);
+
+        let x = inner.x + 1;
+        let width = inner.width.saturating_sub(2);
+        self.query.render_buf(buf.raw_buf(), x, inner.y + 1, width);
+
+        let list_y = inner.y + 3;
+        let rows = inner.height.saturating_sub(5) as usize;
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if rows > 0 && self.selected >= self.scroll + rows {
+            self.scroll = self.selected + 1 - rows;
+        }
+        for (row, hit) in self.hits.iter().enumerate().skip(self.scroll).take(rows) {
+            let y = list_y + (row - self.scroll) as u16;
+            let mut kind_style = Style::new().fg(Color::DARK_GRAY);
+            let mut label_style = Style::new().fg(Color::WHITE);
+            let mut detail_style = Style::new().fg(Color::GRAY);
+            if row == self.selected {
+                for cx in x..x + width {
+                    buf.set_cell(cx, y, ' ', Style::new().bg(Color::SELECTION_BG));
+                }
+                kind_style = kind_style.bg(Color::SELECTION_BG);
+                label_style = label_style.bg(Color::SELECTION_BG);
+                detail_style = detail_style.bg(Color::SELECTION_BG);
+            }
+            let kind = format!("{:<11}", hit.kind);
+            let label = format!("{:<32} ", hit.label);
+            buf.draw_line(Rect::new(x, y, width, 1), &[(&kind, kind_style), (&label, label_style), (&hit.detail, detail_style)]);
+        }
+        let dim = Style::new().fg(Color::DARK_GRAY);
+        if self.hits.is_empty() {
+            buf.draw_line(Rect::new(x, list_y, width, 1), &[("No matches", dim)]);
+        }
+
+        let help = "type a name or a note (C4, f#2, note:60) | Enter: go to | Up/Down | Esc: close";
+        buf.draw_line(Rect::new(x, rect.y + rect.height.saturating_sub(2), width, 1), &[(help, dim)]);
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn on_enter(&mut self, state: &AppState) {
+        self.query.set_focused(true);
+        self.update_hits(state);
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::SourceType;
+    use crate::ui::{KeyCode, Modifiers};
+
+    #[test]
+    fn select_takes_the_hit() {
+        let mut pane = ProjectSearchPane::default();
+        let mut state = AppState::new();
+        state.add_instrument(SourceType::Saw);
+        state.instruments.instruments[0].name = "Pad".to_string();
+        pane.query.set_value("pad");
+        pane.update_hits(&state);
+
+        let action = pane.handle_action(ActionId::ProjectSearch(ProjectSearchActionId::Select), &InputEvent::new(KeyCode::Enter, Modifiers::default()), &state);
+        assert!(matches!(action, Action::Nav(NavAction::PopPane)));
+        assert_eq!(pane.take_jump(), Some(Target::Instrument(0)));
+        assert_eq!(pane.take_jump(), None);
+    }
+}
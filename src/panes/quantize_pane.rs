@@ -0,0 +1,297 @@
+use std::any::Any;
+use std::collections::BTreeSet;
+
+use crate::state::AppState;
+use crate::ui::action_id::{ActionId, QuantizeActionId};
+use crate::ui::layout_helpers::center_rect;
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, NavAction, Pane, PianoRollAction, Style};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Grid,
+    Strength,
+    Ends,
+}
+
+const FIELDS: [Field; 3] = [Field::Grid, Field::Strength, Field::Ends];
+
+/// Grid choices as divisions of a whole note, coarsest first
+const GRIDS: [(u32, &str); 7] = [
+    (2, "1/2"), (4, "1/4"), (8, "1/8"), (12, "1/8T"), (16, "1/16"), (24, "1/16T"), (32, "1/32"),
+];
+
+/// Ticks in grid choice `idx` at the given resolution
+fn grid_ticks(idx: usize, ticks_per_beat: u32) -> u32 {
+    (ticks_per_beat * 4 / GRIDS[idx].0).max(1)
+}
+
+/// Notes the piano roll asked to quantize: its selection on each track of
+/// the edit group, or whole tracks when nothing is selected
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuantizeTarget {
+    pub tracks: Vec<usize>,
+    pub start_tick: u32,
+    pub end_tick: u32,
+    pub start_pitch: u8,
+    pub end_pitch: u8,
+    /// The piano roll's grid, used as the starting choice
+    pub grid: u32,
+}
+
+impl QuantizeTarget {
+    fn contains(&self, tick: u32, pitch: u8) -> bool {
+        (self.start_tick..self.end_tick).contains(&tick) && (self.start_pitch..=self.end_pitch).contains(&pitch)
+    }
+}
+
+/// Move `tick` toward the nearest grid line by `strength` percent
+fn snap(tick: u32, grid: u32, strength: u8) -> u32 {
+    let nearest = (tick + grid / 2) / grid * grid;
+    let moved = tick as f64 + (nearest as f64 - tick as f64) * strength as f64 / 100.0;
+    moved.round() as u32
+}
+
+/// Quantize `(tick, duration)`: the start always, the end too when `ends`
+/// is set. A note whose end would collapse onto its start keeps its length.
+pub(crate) fn quantize_note(tick: u32, duration: u32, grid: u32, strength: u8, ends: bool) -> (u32, u32) {
+    if grid == 0 {
+        return (tick, duration);
+    }
+    let start = snap(tick, grid, strength);
+    if !ends {
+        return (start, duration);
+    }
+    let end = snap(tick + duration, grid, strength);
+    if end > start { (start, end - start) } else { (start, duration) }
+}
+
+/// Snap selected note starts (and optionally ends) to a grid, part of the
+/// way or all of it
+pub struct QuantizePane {
+    keymap: Keymap,
+    target: Option<QuantizeTarget>,
+    /// Index into GRIDS
+    grid: usize,
+    /// Percent of the way to the grid line
+    strength: u8,
+    ends: bool,
+    selected: usize,
+}
+
+impl QuantizePane {
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            target: None,
+            grid: 4,
+            strength: 100,
+            ends: false,
+            selected: 0,
+        }
+    }
+
+    /// Aim at `target`, starting from the coarsest grid no wider than the
+    /// piano roll's; strength and ends carry over from the last use
+    pub fn open(&mut self, target: QuantizeTarget, ticks_per_beat: u32) {
+        self.grid = (0..GRIDS.len())
+            .find(|&idx| grid_ticks(idx, ticks_per_beat) <= target.grid)
+            .unwrap_or(GRIDS.len() - 1);
+        self.target = Some(target);
+    }
+
+    fn adjust(&mut self, increase: bool) {
+        match FIELDS[self.selected] {
+            Field::Grid => {
+                // Increasing makes the grid finer
+                self.grid = if increase { (self.grid + 1).min(GRIDS.len() - 1) } else { self.grid.saturating_sub(1) };
+            }
+            Field::Strength => {
+                self.strength = if increase { (self.strength + 5).min(100) } else { self.strength.saturating_sub(5) };
+            }
+            Field::Ends => self.ends = !self.ends,
+        }
+    }
+
+    /// `(track, tick, pitch, duration, velocity)` of every targeted note
+    fn targeted_notes(&self, state: &AppState) -> Vec<(usize, u32, u8, u32, u8)> {
+        let Some(t) = &self.target else { return Vec::new() };
+        t.tracks.iter()
+            .filter_map(|&track| state.session.piano_roll.track_at(track).map(|pr_track| (track, pr_track)))
+            .flat_map(|(track, pr_track)| {
+                pr_track.notes.iter()
+                    .filter(move |n| t.contains(n.tick, n.pitch))
+                    .map(move |n| (track, n.tick, n.pitch, n.duration, n.velocity))
+            })
+            .collect()
+    }
+
+    /// Rewrite the targeted notes quantized, as one undo step
+    fn quantize_action(&self, state: &AppState) -> Action {
+        let Some(t) = &self.target else { return Action::None };
+        let notes = self.targeted_notes(state);
+        if notes.is_empty() {
+            return Action::None;
+        }
+        let mut actions: Vec<Action> = t.tracks.iter()
+            .map(|&track| Action::PianoRoll(PianoRollAction::DeleteNotesInRegion {
+                track,
+                start_tick: t.start_tick,
+                end_tick: t.end_tick,
+                start_pitch: t.start_pitch,
+                end_pitch: t.end_pitch,
+            }))
+            .collect();
+        // Notes left outside the region; placing on one would toggle it off
+        let kept: BTreeSet<(usize, u32, u8)> = t.tracks.iter()
+            .filter_map(|&track| state.session.piano_roll.track_at(track).map(|pr_track| (track, pr_track)))
+            .flat_map(|(track, pr_track)| {
+                pr_track.notes.iter()
+                    .filter(move |n| !t.contains(n.tick, n.pitch))
+                    .map(move |n| (track, n.tick, n.pitch))
+            })
+            .collect();
+        let grid = grid_ticks(self.grid, state.session.piano_roll.ticks_per_beat);
+        let mut placed = BTreeSet::new();
+        for (track, tick, pitch, duration, velocity) in notes {
+            let (tick, duration) = quantize_note(tick, duration, grid, self.strength, self.ends);
+            // Notes snapped onto the same spot merge into one
+            if kept.contains(&(track, tick, pitch)) || !placed.insert((track, tick, pitch)) {
+                continue;
+            }
+            actions.push(Action::PianoRoll(PianoRollAction::ToggleNote { pitch, tick, duration, velocity, track }));
+        }
+        Action::Batch(actions)
+    }
+}
+
+impl Default for QuantizePane {
+    fn default() -> Self {
+        Self::new(Keymap::new())
+    }
+}
+
+impl Pane for QuantizePane {
+    fn id(&self) -> &'static str {
+        "quantize"
+    }
+
+    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, state: &AppState) -> Action {
+        match action {
+            ActionId::Quantize(QuantizeActionId::Prev) => {
+                self.selected = self.selected.saturating_sub(1);
+                Action::None
+            }
+            ActionId::Quantize(QuantizeActionId::Next) => {
+                self.selected = (self.selected + 1).min(FIELDS.len() - 1);
+                Action::None
+            }
+            ActionId::Quantize(QuantizeActionId::Decrease) => {
+                self.adjust(false);
+                Action::None
+            }
+            ActionId::Quantize(QuantizeActionId::Increase) => {
+                self.adjust(true);
+                Action::None
+            }
+            ActionId::Quantize(QuantizeActionId::Apply) => match self.quantize_action(state) {
+                Action::Batch(mut actions) => {
+                    actions.push(Action::Nav(NavAction::PopPane));
+                    Action::Batch(actions)
+                }
+                _ => Action::Nav(NavAction::PopPane),
+            },
+            ActionId::Quantize(QuantizeActionId::Close) => Action::Nav(NavAction::PopPane),
+            _ => Action::None,
+        }
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, state: &AppState) {
+        let rect = center_rect(area, 44, FIELDS.len() as u16 + 7);
+        let border_style = Style::new().fg(Color::PURPLE);
+        let inner = buf.draw_block(rect, " Quantize ", border_style, border_style);
+
+        let label_col = inner.x + 2;
+        let value_col = label_col + 12;
+
+        let count = self.targeted_notes(state).len();
+        let tracks = self.target.as_ref().map_or(0, |t| t.tracks.len());
+        let summary = match (count, tracks) {
+            (0, _) => "No notes selected".to_string(),
+            (n, 1) => format!("{} note{}", n, if n == 1 { "" } else { "s" }),
+            (n, t) => format!("{} notes on {} tracks", n, t),
+        };
+        buf.draw_line(Rect::new(label_col, inner.y, inner.width.saturating_sub(2), 1), &[(&summary, Style::new().fg(Color::GRAY))]);
+
+        for (i, field) in FIELDS.iter().enumerate() {
+            let y = inner.y + 2 + i as u16;
+            let is_selected = i == self.selected;
+            if is_selected {
+                for x in inner.x..inner.x + inner.width {
+                    buf.set_cell(x, y, ' ', Style::new().bg(Color::SELECTION_BG));
+                }
+                buf.set_cell(label_col, y, '>', Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG).bold());
+            }
+            let (label_style, val_style) = if is_selected {
+                (Style::new().fg(Color::CYAN).bg(Color::SELECTION_BG), Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG))
+            } else {
+                (Style::new().fg(Color::CYAN), Style::new().fg(Color::WHITE))
+            };
+            let (label, value) = match field {
+                Field::Grid => ("Grid", GRIDS[self.grid].1.to_string()),
+                Field::Strength => ("Strength", format!("{}%", self.strength)),
+                Field::Ends => ("Note ends", if self.ends { "Quantize".into() } else { "Keep lengths".into() }),
+            };
+            let label = format!("{:10}", label);
+            buf.draw_line(Rect::new(label_col + 2, y, 10, 1), &[(&label, label_style)]);
+            buf.draw_line(Rect::new(value_col, y, inner.width.saturating_sub(14), 1), &[(&value, val_style)]);
+        }
+
+        let help_y = rect.y + rect.height - 2;
+        if help_y < area.y + area.height {
+            buf.draw_line(
+                Rect::new(inner.x + 2, help_y, inner.width.saturating_sub(2), 1),
+                &[("Enter: quantize | Esc: close", Style::new().fg(Color::DARK_GRAY))],
+            );
+        }
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strength_moves_part_of_the_way() {
+        // 130 is 10 ticks past the 1/16 line at 120
+        assert_eq!(quantize_note(130, 200, 120, 100, false), (120, 200));
+        assert_eq!(quantize_note(130, 200, 120, 50, false), (125, 200));
+        assert_eq!(quantize_note(130, 200, 120, 0, false), (130, 200));
+        // End at 330 snaps to 360
+        assert_eq!(quantize_note(130, 200, 120, 100, true), (120, 240));
+        // An end that would land on the start keeps the length
+        assert_eq!(quantize_note(10, 20, 120, 100, true), (0, 20));
+    }
+
+    #[test]
+    fn grids_follow_the_project_resolution() {
+        assert_eq!(grid_ticks(1, 480), 480);
+        assert_eq!(grid_ticks(4, 960), 240);
+        assert_eq!(grid_ticks(3, 960), 320);
+
+        // Opens on the piano roll's grid at either resolution
+        let target = |grid| QuantizeTarget { tracks: vec![0], start_tick: 0, end_tick: 1920, start_pitch: 0, end_pitch: 127, grid };
+        let mut pane = QuantizePane::default();
+        pane.open(target(240), 960);
+        assert_eq!(GRIDS[pane.grid].1, "1/16");
+        pane.open(target(240), 480);
+        assert_eq!(GRIDS[pane.grid].1, "1/8");
+    }
+}
@@ -0,0 +1,283 @@
+use std::any::Any;
+
+use crate::state::{AppState, InstrumentId, RandomLooperConfig};
+use crate::ui::action_id::{ActionId, RandomLooperActionId};
+use crate::ui::layout_helpers::center_rect;
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, InstrumentAction, Keymap, NavAction, Pane, Style};
+
+/// Fields editable in the random looper popup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Enabled,
+    Length,
+    Lock,
+    Range,
+    Rate,
+    Gate,
+}
+
+const FIELDS: [Field; 6] = [Field::Enabled, Field::Length, Field::Lock, Field::Range, Field::Rate, Field::Gate];
+
+/// Register lengths offered by the length field
+const LENGTHS: [u8; 8] = [2, 3, 4, 5, 6, 8, 12, 16];
+/// Clock divisions as divisions of a whole note
+const RATES: [(u32, &str); 5] = [(2, "1/2"), (4, "1/4"), (8, "1/8"), (12, "1/8T"), (16, "1/16")];
+
+/// Ticks in clock division `idx` at the given resolution
+fn rate_ticks(idx: usize, ticks_per_beat: u32) -> u32 {
+    (ticks_per_beat * 4 / RATES[idx].0).max(1)
+}
+
+/// Turing-machine style looper: a shift register clocked by the transport.
+/// Each step the bit leaving the register is fed back in, or flipped with
+/// probability `100 - lock`, so high lock freezes the loop and low lock mutates it.
+pub struct RandomLooperPane {
+    keymap: Keymap,
+    instrument_id: Option<InstrumentId>,
+    config: RandomLooperConfig,
+    selected: usize,
+}
+
+impl RandomLooperPane {
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            instrument_id: None,
+            config: RandomLooperConfig::default(),
+            selected: 0,
+        }
+    }
+
+    fn current_field(&self) -> Field {
+        FIELDS[self.selected]
+    }
+
+    fn adjust(&mut self, increase: bool, ticks_per_beat: u32) {
+        let c = &mut self.config;
+        match self.current_field() {
+            Field::Enabled => c.enabled = !c.enabled,
+            Field::Length => {
+                let idx = LENGTHS.iter().position(|l| *l == c.length).unwrap_or(5);
+                let new_idx = if increase { (idx + 1).min(LENGTHS.len() - 1) } else { idx.saturating_sub(1) };
+                c.length = LENGTHS[new_idx];
+            }
+            Field::Lock => {
+                let delta: i16 = if increase { 5 } else { -5 };
+                c.lock = (c.lock as i16 + delta).clamp(0, 100) as u8;
+            }
+            Field::Range => {
+                c.range = if increase { (c.range + 1).min(48) } else { c.range.saturating_sub(1).max(1) };
+            }
+            Field::Rate => {
+                let idx = (0..RATES.len()).position(|i| rate_ticks(i, ticks_per_beat) == c.rate_ticks).unwrap_or(2);
+                let new_idx = if increase { (idx + 1).min(RATES.len() - 1) } else { idx.saturating_sub(1) };
+                c.rate_ticks = rate_ticks(new_idx, ticks_per_beat);
+            }
+            Field::Gate => {
+                let delta: i16 = if increase { 5 } else { -5 };
+                c.gate = (c.gate as i16 + delta).clamp(5, 100) as u8;
+            }
+        }
+    }
+
+    fn field_label(field: Field) -> &'static str {
+        match field {
+            Field::Enabled => "Looper",
+            Field::Length => "Length",
+            Field::Lock => "Lock",
+            Field::Range => "Range",
+            Field::Rate => "Rate",
+            Field::Gate => "Gate",
+        }
+    }
+
+    fn field_value(&self, field: Field, ticks_per_beat: u32) -> String {
+        let c = &self.config;
+        match field {
+            Field::Enabled => if c.enabled { "On".into() } else { "Off".into() },
+            Field::Length => format!("{} steps", c.length),
+            Field::Lock => match c.lock {
+                100 => "100% (frozen)".into(),
+                50 => "50% (random)".into(),
+                l => format!("{}%", l),
+            },
+            Field::Range => format!("{} st", c.range),
+            Field::Rate => match (0..RATES.len()).find(|&i| rate_ticks(i, ticks_per_beat) == c.rate_ticks) {
+                Some(idx) => RATES[idx].1.into(),
+                None => format!("{} ticks", c.rate_ticks),
+            },
+            Field::Gate => format!("{}%", c.gate),
+        }
+    }
+
+    fn emit(&self) -> Action {
+        match self.instrument_id {
+            Some(id) => Action::Instrument(InstrumentAction::SetRandomLooper(id, self.config.clone())),
+            None => Action::None,
+        }
+    }
+}
+
+impl Default for RandomLooperPane {
+    fn default() -> Self {
+        Self::new(Keymap::new())
+    }
+}
+
+impl Pane for RandomLooperPane {
+    fn id(&self) -> &'static str {
+        "random_looper"
+    }
+
+    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, state: &AppState) -> Action {
+        match action {
+            ActionId::RandomLooper(RandomLooperActionId::Prev) => {
+                self.selected = self.selected.saturating_sub(1);
+                Action::None
+            }
+            ActionId::RandomLooper(RandomLooperActionId::Next) => {
+                if self.selected < FIELDS.len() - 1 {
+                    self.selected += 1;
+                }
+                Action::None
+            }
+            // Changes apply live so the lock knob can be played while the loop runs
+            ActionId::RandomLooper(RandomLooperActionId::Decrease) => {
+                self.adjust(false, state.session.piano_roll.ticks_per_beat);
+                self.emit()
+            }
+            ActionId::RandomLooper(RandomLooperActionId::Increase) => {
+                self.adjust(true, state.session.piano_roll.ticks_per_beat);
+                self.emit()
+            }
+            ActionId::RandomLooper(RandomLooperActionId::Reseed) => match self.instrument_id {
+                Some(id) => Action::Instrument(InstrumentAction::ReseedRandomLooper(id)),
+                None => Action::None,
+            },
+            ActionId::RandomLooper(RandomLooperActionId::Close) => Action::Nav(NavAction::PopPane),
+            _ => Action::None,
+        }
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, state: &AppState) {
+        let rect = center_rect(area, 44, 13);
+
+        let border_style = Style::new().fg(Color::TEAL);
+        let inner = buf.draw_block(rect, " Random Looper ", border_style, border_style);
+
+        let label_col = inner.x + 2;
+        let value_col = label_col + 12;
+
+        for (i, field) in FIELDS.iter().enumerate() {
+            let y = inner.y + 1 + i as u16;
+            if y >= inner.y + inner.height {
+                break;
+            }
+            let is_selected = i == self.selected;
+
+            if is_selected {
+                for x in inner.x..inner.x + inner.width {
+                    buf.set_cell(x, y, ' ', Style::new().bg(Color::SELECTION_BG));
+                }
+                buf.set_cell(label_col, y, '>', Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG).bold());
+            }
+
+            let label_style = if is_selected {
+                Style::new().fg(Color::CYAN).bg(Color::SELECTION_BG)
+            } else {
+                Style::new().fg(Color::CYAN)
+            };
+            let val_style = if is_selected {
+                Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG)
+            } else {
+                Style::new().fg(Color::WHITE)
+            };
+            let label = format!("{:10}", Self::field_label(*field));
+            buf.draw_line(Rect::new(label_col + 2, y, 10, 1), &[(&label, label_style)]);
+            let val = self.field_value(*field, state.session.piano_roll.ticks_per_beat);
+            buf.draw_line(Rect::new(value_col, y, inner.width.saturating_sub(14), 1), &[(&val, val_style)]);
+        }
+
+        // Live register contents reported back from the audio thread
+        let reg_y = inner.y + 1 + FIELDS.len() as u16 + 1;
+        if reg_y < inner.y + inner.height {
+            let register = self.instrument_id
+                .and_then(|id| state.instruments.instrument(id))
+                .map(|inst| inst.random_looper.register)
+                .unwrap_or(0);
+            let bits: String = (0..self.config.length)
+                .map(|i| if register >> i & 1 == 1 { '■' } else { '·' })
+                .collect();
+            buf.draw_line(
+                Rect::new(label_col + 2, reg_y, inner.width.saturating_sub(4), 1),
+                &[("Register  ", Style::new().fg(Color::CYAN)), (&bits, Style::new().fg(Color::TEAL))],
+            );
+        }
+
+        let help_y = rect.y + rect.height - 2;
+        if help_y < area.y + area.height {
+            buf.draw_line(
+                Rect::new(inner.x + 2, help_y, inner.width.saturating_sub(2), 1),
+                &[("Left/Right: adjust live | r: reseed | Esc: close", Style::new().fg(Color::DARK_GRAY))],
+            );
+        }
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn on_enter(&mut self, state: &AppState) {
+        let instrument = state.instruments.selected_instrument();
+        self.instrument_id = instrument.map(|i| i.id);
+        self.config = instrument.map(|i| i.random_looper.clone()).unwrap_or_default();
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::{KeyCode, Modifiers};
+
+    fn dummy_event() -> InputEvent {
+        InputEvent::new(KeyCode::Char('x'), Modifiers::default())
+    }
+
+    #[test]
+    fn lock_clamps_to_percent_range() {
+        let mut pane = RandomLooperPane::new(Keymap::new());
+        pane.selected = 2;
+        for _ in 0..30 {
+            pane.adjust(true, 480);
+        }
+        assert_eq!(pane.config.lock, 100);
+        for _ in 0..30 {
+            pane.adjust(false, 480);
+        }
+        assert_eq!(pane.config.lock, 0);
+    }
+
+    #[test]
+    fn rates_follow_the_project_resolution() {
+        let mut pane = RandomLooperPane::new(Keymap::new());
+        pane.selected = FIELDS.iter().position(|f| *f == Field::Rate).unwrap();
+        pane.config.rate_ticks = 480;
+        pane.adjust(true, 960);
+        // 480 is a 1/8 at 960 ticks per beat; one step finer is a 1/8T
+        assert_eq!(pane.config.rate_ticks, 320);
+        assert_eq!(pane.field_value(Field::Rate, 960), "1/8T");
+    }
+
+    #[test]
+    fn edits_without_instrument_do_nothing() {
+        let mut pane = RandomLooperPane::new(Keymap::new());
+        let state = AppState::new();
+        pane.on_enter(&state);
+        let action = pane.handle_action(ActionId::RandomLooper(RandomLooperActionId::Increase), &dummy_event(), &state);
+        assert!(matches!(action, Action::None));
+    }
+}
@@ -0,0 +1,303 @@
+use std::any::Any;
+
+use crate::state::{AppState, RecordMode, RecordSettings};
+use crate::ui::action_id::{ActionId, RecordSettingsActionId};
+use crate::ui::layout_helpers::center_rect;
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, NavAction, Pane, PianoRollAction, Style};
+
+/// Fields editable in the record settings popup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Mode,
+    QuantizeGrid,
+    QuantizeStrength,
+    TakeLanes,
+}
+
+const FIELDS: [Field; 4] = [Field::Mode, Field::QuantizeGrid, Field::QuantizeStrength, Field::TakeLanes];
+
+/// Input quantize grid choices as divisions of a beat (0 = off)
+const QUANTIZE_DIVISIONS: [u32; 6] = [0, 1, 2, 3, 4, 8];
+const QUANTIZE_LABELS: [&str; 6] = ["Off", "1/4", "1/8", "1/8T", "1/16", "1/32"];
+
+/// Input quantize grid choices in ticks at the given resolution (0 = off)
+fn quantize_grids(ticks_per_beat: u32) -> [u32; 6] {
+    QUANTIZE_DIVISIONS.map(|d| if d == 0 { 0 } else { ticks_per_beat / d })
+}
+
+pub struct RecordSettingsPane {
+    keymap: Keymap,
+    settings: RecordSettings,
+    original_settings: RecordSettings,
+    selected: usize,
+}
+
+impl RecordSettingsPane {
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            settings: RecordSettings::default(),
+            original_settings: RecordSettings::default(),
+            selected: 0,
+        }
+    }
+
+    /// Set record settings to edit (called on enter)
+    pub fn set_settings(&mut self, settings: RecordSettings) {
+        self.settings = settings;
+        self.original_settings = self.settings.clone();
+        self.selected = 0;
+    }
+
+    fn current_field(&self) -> Field {
+        FIELDS[self.selected]
+    }
+
+    fn cycle_grid(&mut self, forward: bool, ticks_per_beat: u32) {
+        let grids = quantize_grids(ticks_per_beat);
+        let idx = grids.iter().position(|g| *g == self.settings.quantize_grid).unwrap_or(0);
+        let len = grids.len();
+        self.settings.quantize_grid = if forward {
+            grids[(idx + 1) % len]
+        } else {
+            grids[(idx + len - 1) % len]
+        };
+    }
+
+    fn adjust(&mut self, increase: bool, state: &AppState) {
+        match self.current_field() {
+            Field::Mode => {
+                self.settings.mode = match self.settings.mode {
+                    RecordMode::Overdub => RecordMode::Replace,
+                    RecordMode::Replace => RecordMode::Overdub,
+                };
+            }
+            Field::QuantizeGrid => self.cycle_grid(increase, state.session.piano_roll.ticks_per_beat),
+            Field::QuantizeStrength => {
+                let delta: i16 = if increase { 10 } else { -10 };
+                self.settings.quantize_strength = (self.settings.quantize_strength as i16 + delta).clamp(0, 100) as u8;
+            }
+            Field::TakeLanes => self.settings.take_lanes = !self.settings.take_lanes,
+        }
+    }
+
+    fn field_label(field: Field) -> &'static str {
+        match field {
+            Field::Mode => "Mode",
+            Field::QuantizeGrid => "Quantize",
+            Field::QuantizeStrength => "Strength",
+            Field::TakeLanes => "Takes",
+        }
+    }
+
+    fn field_value(&self, field: Field, state: &AppState) -> String {
+        match field {
+            Field::Mode => match self.settings.mode {
+                RecordMode::Overdub => "Overdub (merge)".into(),
+                RecordMode::Replace => "Replace".into(),
+            },
+            Field::QuantizeGrid => grid_label(self.settings.quantize_grid, state.session.piano_roll.ticks_per_beat).into(),
+            Field::QuantizeStrength => format!("{}%", self.settings.quantize_strength),
+            Field::TakeLanes => if self.settings.take_lanes { "New lane per pass".into() } else { "Merge into track".into() },
+        }
+    }
+}
+
+/// Display label for an input quantize grid in ticks
+pub(crate) fn grid_label(grid: u32, ticks_per_beat: u32) -> &'static str {
+    quantize_grids(ticks_per_beat).iter()
+        .position(|g| *g == grid)
+        .map(|idx| QUANTIZE_LABELS[idx])
+        .unwrap_or("Custom")
+}
+
+impl Default for RecordSettingsPane {
+    fn default() -> Self {
+        Self::new(Keymap::new())
+    }
+}
+
+impl Pane for RecordSettingsPane {
+    fn id(&self) -> &'static str {
+        "record_settings"
+    }
+
+    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, state: &AppState) -> Action {
+        match action {
+            ActionId::RecordSettings(RecordSettingsActionId::Prev) => {
+                if self.selected > 0 {
+                    self.selected -= 1;
+                }
+                Action::None
+            }
+            ActionId::RecordSettings(RecordSettingsActionId::Next) => {
+                if self.selected < FIELDS.len() - 1 {
+                    self.selected += 1;
+                }
+                Action::None
+            }
+            ActionId::RecordSettings(RecordSettingsActionId::Decrease) => {
+                self.adjust(false, state);
+                Action::None
+            }
+            ActionId::RecordSettings(RecordSettingsActionId::Increase) => {
+                self.adjust(true, state);
+                Action::None
+            }
+            ActionId::RecordSettings(RecordSettingsActionId::Confirm) => {
+                Action::PianoRoll(PianoRollAction::SetRecordSettings(self.settings.clone()))
+            }
+            ActionId::RecordSettings(RecordSettingsActionId::Cancel) => {
+                self.settings = self.original_settings.clone();
+                Action::Nav(NavAction::PopPane)
+            }
+            _ => Action::None,
+        }
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, state: &AppState) {
+        let rect = center_rect(area, 44, 10);
+
+        let border_style = Style::new().fg(Color::RED);
+        let inner = buf.draw_block(rect, " Record Settings ", border_style, border_style);
+
+        let label_col = inner.x + 2;
+        let value_col = label_col + 12;
+
+        for (i, field) in FIELDS.iter().enumerate() {
+            let y = inner.y + 1 + i as u16;
+            if y >= inner.y + inner.height {
+                break;
+            }
+            let is_selected = i == self.selected;
+
+            if is_selected {
+                for x in inner.x..inner.x + inner.width {
+                    buf.set_cell(x, y, ' ', Style::new().bg(Color::SELECTION_BG));
+                }
+                buf.set_cell(label_col, y, '>', Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG).bold());
+            }
+
+            let label_style = if is_selected {
+                Style::new().fg(Color::CYAN).bg(Color::SELECTION_BG)
+            } else {
+                Style::new().fg(Color::CYAN)
+            };
+            let val_style = if is_selected {
+                Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG)
+            } else {
+                Style::new().fg(Color::WHITE)
+            };
+            let label = format!("{:10}", Self::field_label(*field));
+            buf.draw_line(Rect::new(label_col + 2, y, 10, 1), &[(&label, label_style)]);
+            let val = self.field_value(*field, state);
+            buf.draw_line(Rect::new(value_col, y, inner.width.saturating_sub(14), 1), &[(&val, val_style)]);
+        }
+
+        let help_y = rect.y + rect.height - 2;
+        if help_y < area.y + area.height {
+            buf.draw_line(
+                Rect::new(inner.x + 2, help_y, inner.width.saturating_sub(2), 1),
+                &[("Left/Right: adjust | Enter: apply | Esc: cancel", Style::new().fg(Color::DARK_GRAY))],
+            );
+        }
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn on_enter(&mut self, state: &AppState) {
+        self.set_settings(state.session.piano_roll.record_settings.clone());
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::{KeyCode, Modifiers};
+
+    fn dummy_event() -> InputEvent {
+        InputEvent::new(KeyCode::Char('x'), Modifiers::default())
+    }
+
+    #[test]
+    fn adjust_mode_toggles_overdub_replace() {
+        let mut pane = RecordSettingsPane::new(Keymap::new());
+        let state = AppState::new();
+        pane.set_settings(RecordSettings::default());
+        let before = pane.settings.mode;
+
+        pane.handle_action(ActionId::RecordSettings(RecordSettingsActionId::Increase), &dummy_event(), &state);
+        assert_ne!(pane.settings.mode, before);
+        pane.handle_action(ActionId::RecordSettings(RecordSettingsActionId::Increase), &dummy_event(), &state);
+        assert_eq!(pane.settings.mode, before);
+    }
+
+    #[test]
+    fn strength_clamps_to_percent_range() {
+        let mut pane = RecordSettingsPane::new(Keymap::new());
+        let state = AppState::new();
+        pane.set_settings(RecordSettings::default());
+        pane.selected = 2;
+
+        for _ in 0..20 {
+            pane.handle_action(ActionId::RecordSettings(RecordSettingsActionId::Increase), &dummy_event(), &state);
+        }
+        assert_eq!(pane.settings.quantize_strength, 100);
+        for _ in 0..20 {
+            pane.handle_action(ActionId::RecordSettings(RecordSettingsActionId::Decrease), &dummy_event(), &state);
+        }
+        assert_eq!(pane.settings.quantize_strength, 0);
+    }
+
+    #[test]
+    fn confirm_emits_settings() {
+        let mut pane = RecordSettingsPane::new(Keymap::new());
+        let state = AppState::new();
+        pane.set_settings(RecordSettings::default());
+        pane.selected = 1;
+        pane.handle_action(ActionId::RecordSettings(RecordSettingsActionId::Increase), &dummy_event(), &state);
+
+        let action = pane.handle_action(ActionId::RecordSettings(RecordSettingsActionId::Confirm), &dummy_event(), &state);
+        match action {
+            Action::PianoRoll(PianoRollAction::SetRecordSettings(s)) => {
+                assert_eq!(s.quantize_grid, pane.settings.quantize_grid);
+            }
+            _ => panic!("Expected SetRecordSettings on confirm"),
+        }
+    }
+
+    #[test]
+    fn grids_follow_the_project_resolution() {
+        let mut pane = RecordSettingsPane::new(Keymap::new());
+        let mut state = AppState::new();
+        state.session.piano_roll.ticks_per_beat = 960;
+        pane.set_settings(RecordSettings { quantize_grid: 0, ..RecordSettings::default() });
+        pane.selected = 1;
+
+        pane.handle_action(ActionId::RecordSettings(RecordSettingsActionId::Increase), &dummy_event(), &state);
+        assert_eq!(pane.settings.quantize_grid, 960);
+        assert_eq!(grid_label(960, 960), "1/4");
+        assert_eq!(grid_label(240, 960), "1/16");
+        assert_eq!(grid_label(320, 960), "1/8T");
+        assert_eq!(grid_label(100, 960), "Custom");
+    }
+
+    #[test]
+    fn cancel_reverts_and_pops() {
+        let mut pane = RecordSettingsPane::new(Keymap::new());
+        let state = AppState::new();
+        let original = RecordSettings::default();
+        pane.set_settings(original.clone());
+        pane.settings.quantize_strength = 37;
+
+        let action = pane.handle_action(ActionId::RecordSettings(RecordSettingsActionId::Cancel), &dummy_event(), &state);
+        assert!(matches!(action, Action::Nav(NavAction::PopPane)));
+        assert_eq!(pane.settings, original);
+    }
+}
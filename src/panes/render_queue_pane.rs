@@ -0,0 +1,164 @@
+use std::any::Any;
+
+use crate::render_queue::{QueueState, QueuedJob};
+use crate::state::AppState;
+use crate::ui::action_id::{ActionId, RenderQueueActionId};
+use crate::ui::layout_helpers::center_rect;
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, NavAction, Pane, Style};
+
+/// What the user asked of the queue, taken by main.rs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueRequest {
+    Cancel(u32),
+    ClearFinished,
+}
+
+/// The render queue: every export job with its state, and the running
+/// job's progress. main.rs owns the queue and copies it in each frame.
+pub struct RenderQueuePane {
+    keymap: Keymap,
+    jobs: Vec<QueuedJob>,
+    selected: usize,
+    pending: Option<QueueRequest>,
+}
+
+impl RenderQueuePane {
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            jobs: Vec::new(),
+            selected: 0,
+            pending: None,
+        }
+    }
+
+    pub fn set_jobs(&mut self, jobs: &[QueuedJob]) {
+        self.jobs = jobs.to_vec();
+        self.selected = self.selected.min(self.jobs.len().saturating_sub(1));
+    }
+
+    /// Cancel or clear requested since the last call
+    pub fn take_request(&mut self) -> Option<QueueRequest> {
+        self.pending.take()
+    }
+
+    fn state_label(state: &QueueState, progress: f32) -> (String, Color) {
+        match state {
+            QueueState::Waiting => ("waiting".into(), Color::DARK_GRAY),
+            QueueState::Running => {
+                let filled = (progress.clamp(0.0, 1.0) * 10.0) as usize;
+                (format!("[{}{}] {:3.0}%", "=".repeat(filled), " ".repeat(10 - filled), progress * 100.0), Color::YELLOW)
+            }
+            QueueState::Done(files) => (format!("done, {} file(s)", files.len()), Color::GREEN),
+            QueueState::Failed(e) => (format!("failed: {}", e), Color::RED),
+            QueueState::Cancelled => ("cancelled".into(), Color::DARK_GRAY),
+        }
+    }
+}
+
+impl Default for RenderQueuePane {
+    fn default() -> Self {
+        Self::new(Keymap::new())
+    }
+}
+
+impl Pane for RenderQueuePane {
+    fn id(&self) -> &'static str {
+        "render_queue"
+    }
+
+    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, _state: &AppState) -> Action {
+        match action {
+            ActionId::RenderQueue(RenderQueueActionId::Up) => self.selected = self.selected.saturating_sub(1),
+            ActionId::RenderQueue(RenderQueueActionId::Down) => {
+                self.selected = (self.selected + 1).min(self.jobs.len().saturating_sub(1));
+            }
+            ActionId::RenderQueue(RenderQueueActionId::Cancel) => {
+                if let Some(job) = self.jobs.get(self.selected) {
+                    self.pending = Some(QueueRequest::Cancel(job.id));
+                }
+            }
+            ActionId::RenderQueue(RenderQueueActionId::ClearFinished) => self.pending = Some(QueueRequest::ClearFinished),
+            ActionId::RenderQueue(RenderQueueActionId::Close) => return Action::Nav(NavAction::PopPane),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, state: &AppState) {
+        let rect = center_rect(area, 64, (self.jobs.len() as u16 + 5).clamp(8, 24));
+        let border_style = Style::new().fg(Color::ORANGE);
+        let inner = buf.draw_block(rect, " Render Queue ", border_style, border_style);
+
+        let x = inner.x + 1;
+        let w = inner.width.saturating_sub(2);
+        if self.jobs.is_empty() {
+            buf.draw_line(
+                Rect::new(x, inner.y + 1, w, 1),
+                &[("No exports queued. Confirm one in the export dialog.", Style::new().fg(Color::DARK_GRAY))],
+            );
+        }
+
+        let visible = inner.height.saturating_sub(2) as usize;
+        let scroll = if self.selected >= visible { self.selected + 1 - visible } else { 0 };
+        let label_width = 34usize.min(w as usize / 2 + 4);
+        for (i, job) in self.jobs.iter().enumerate().skip(scroll).take(visible) {
+            let y = inner.y + (i - scroll) as u16;
+            let is_selected = i == self.selected;
+            let bg = if is_selected { Color::SELECTION_BG } else { Color::BLACK };
+            if is_selected {
+                for cx in inner.x..inner.x + inner.width {
+                    buf.set_cell(cx, y, ' ', Style::new().bg(bg));
+                }
+            }
+            let label: String = job.label().chars().take(label_width).collect();
+            let (status, color) = Self::state_label(&job.state, state.io.export_progress);
+            buf.draw_line(
+                Rect::new(x, y, w, 1),
+                &[
+                    (&format!("{:lw$} ", label, lw = label_width), Style::new().fg(Color::WHITE).bg(bg)),
+                    (&status, Style::new().fg(color).bg(bg)),
+                ],
+            );
+        }
+
+        let help_y = inner.y + inner.height - 1;
+        buf.draw_line(
+            Rect::new(x, help_y, w, 1),
+            &[("x: cancel job | C: clear finished | Esc: close", Style::new().fg(Color::DARK_GRAY))],
+        );
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export_format::ExportFormat;
+    use crate::export_region::{ExportRange, RegionSettings};
+    use crate::panes::{ExportJob, ExportTarget};
+    use crate::ui::{KeyCode, Modifiers};
+
+    #[test]
+    fn cancel_targets_the_selected_job() {
+        let mut pane = RenderQueuePane::default();
+        let state = AppState::new();
+        let event = InputEvent::new(KeyCode::Char('x'), Modifiers::default());
+        let job = ExportJob { target: ExportTarget::Master, range: ExportRange::Loop, region: RegionSettings::default(), format: ExportFormat::default() };
+        pane.set_jobs(&[
+            QueuedJob { id: 1, job, state: QueueState::Running },
+            QueuedJob { id: 2, job, state: QueueState::Waiting },
+        ]);
+        pane.handle_action(ActionId::RenderQueue(RenderQueueActionId::Down), &event, &state);
+        pane.handle_action(ActionId::RenderQueue(RenderQueueActionId::Cancel), &event, &state);
+        assert_eq!(pane.take_request(), Some(QueueRequest::Cancel(2)));
+        assert_eq!(pane.take_request(), None);
+    }
+}
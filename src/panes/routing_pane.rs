@@ -0,0 +1,186 @@
+use std::any::Any;
+
+use crate::state::{AppState, Instrument, OutputTarget};
+use crate::ui::action_id::{ActionId, RoutingActionId};
+use crate::ui::layout_helpers::center_rect;
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, NavAction, Pane, Style};
+
+/// One row of the diagram as colored segments
+type Line = Vec<(String, Color)>;
+
+fn bus_label(state: &AppState, bus_id: u8) -> String {
+    match state.session.mixer.buses.iter().find(|b| b.id == bus_id) {
+        Some(bus) if !bus.name.is_empty() => format!("B{} {}", bus_id, bus.name),
+        _ => format!("B{}", bus_id),
+    }
+}
+
+fn target_label(state: &AppState, target: OutputTarget) -> String {
+    match target {
+        OutputTarget::Master => "MASTER".to_string(),
+        OutputTarget::Bus(id) => bus_label(state, id),
+    }
+}
+
+fn instrument_lines(state: &AppState, inst: &Instrument) -> Vec<Line> {
+    let mut first: Line = vec![(format!("I{} {}", inst.id, inst.name), Color::CYAN)];
+    if !inst.active {
+        first.push((" (inactive)".to_string(), Color::DARK_GRAY));
+    }
+    if inst.mute {
+        first.push((" (muted)".to_string(), Color::MUTE_COLOR));
+    }
+    if let Some(filter) = &inst.filter {
+        first.push((format!(" \u{2500}[{:?}]", filter.filter_type), Color::WHITE));
+    }
+    for effect in &inst.effects {
+        let color = if effect.enabled { Color::WHITE } else { Color::DARK_GRAY };
+        let mark = if effect.enabled { "" } else { " off" };
+        first.push((format!(" \u{2500}[{}{}]", effect.effect_type.name(), mark), color));
+    }
+    first.push((" \u{2500}\u{25b6} ".to_string(), Color::DARK_GRAY));
+    first.push((target_label(state, inst.output_target), Color::GOLD));
+
+    let mut lines = vec![first];
+    for send in inst.sends.iter().filter(|s| s.enabled && s.level > 0.0) {
+        lines.push(vec![
+            (format!("   \u{2514} send {:.0}% \u{2500}\u{25b6} ", send.level * 100.0), Color::TEAL),
+            (bus_label(state, send.bus_id), Color::PURPLE),
+        ]);
+    }
+    lines
+}
+
+/// Signal flow as text: each instrument through its filter and effect
+/// chain to its output, its sends, then each bus to the master.
+fn routing_lines(state: &AppState) -> Vec<Line> {
+    let mut lines = Vec::new();
+    for inst in &state.instruments.instruments {
+        lines.extend(instrument_lines(state, inst));
+    }
+    if !lines.is_empty() {
+        lines.push(Vec::new());
+    }
+    for bus in &state.session.mixer.buses {
+        let fed = state.instruments.instruments.iter().any(|inst| {
+            matches!(inst.output_target, OutputTarget::Bus(id) if id == bus.id)
+                || inst.sends.iter().any(|s| s.bus_id == bus.id && s.enabled && s.level > 0.0)
+        });
+        if !fed {
+            continue;
+        }
+        let mut line: Line = vec![(bus_label(state, bus.id), Color::PURPLE)];
+        if bus.mute {
+            line.push((" (muted)".to_string(), Color::MUTE_COLOR));
+        }
+        line.push((" \u{2500}\u{25b6} ".to_string(), Color::DARK_GRAY));
+        line.push(("MASTER".to_string(), Color::GOLD));
+        lines.push(line);
+    }
+    let mut master: Line = vec![("MASTER \u{2500}\u{25b6} out".to_string(), Color::GOLD)];
+    if state.session.mixer.master_mute {
+        master.push((" (muted)".to_string(), Color::MUTE_COLOR));
+    }
+    lines.push(master);
+    lines
+}
+
+/// Read-only diagram of the current signal flow, for working out why
+/// something is silent or where a send ends up.
+pub struct RoutingPane {
+    keymap: Keymap,
+    scroll: usize,
+}
+
+impl RoutingPane {
+    pub fn new(keymap: Keymap) -> Self {
+        Self { keymap, scroll: 0 }
+    }
+}
+
+impl Default for RoutingPane {
+    fn default() -> Self {
+        Self::new(Keymap::new())
+    }
+}
+
+impl Pane for RoutingPane {
+    fn id(&self) -> &'static str {
+        "routing"
+    }
+
+    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, state: &AppState) -> Action {
+        match action {
+            ActionId::Routing(RoutingActionId::Up) => self.scroll = self.scroll.saturating_sub(1),
+            ActionId::Routing(RoutingActionId::Down) => {
+                let count = routing_lines(state).len();
+                self.scroll = (self.scroll + 1).min(count.saturating_sub(1));
+            }
+            ActionId::Routing(RoutingActionId::Close) => return Action::Nav(NavAction::PopPane),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, state: &AppState) {
+        let rect = center_rect(area, area.width.saturating_sub(4).min(100), area.height.saturating_sub(4).clamp(8, 32));
+        let border_style = Style::new().fg(Color::CYAN);
+        let inner = buf.draw_block(rect, " Signal Flow ", border_style, border_style);
+
+        let x = inner.x + 1;
+        let w = inner.width.saturating_sub(2);
+        let visible = inner.height.saturating_sub(1) as usize;
+        let lines = routing_lines(state);
+        self.scroll = self.scroll.min(lines.len().saturating_sub(visible));
+
+        for (i, line) in lines.iter().skip(self.scroll).take(visible).enumerate() {
+            let spans: Vec<(&str, Style)> = line.iter().map(|(text, color)| (text.as_str(), Style::new().fg(*color))).collect();
+            buf.draw_line(Rect::new(x, inner.y + i as u16, w, 1), &spans);
+        }
+
+        buf.draw_line(
+            Rect::new(x, inner.y + inner.height - 1, w, 1),
+            &[("Up/Down: scroll | Esc: close", Style::new().fg(Color::DARK_GRAY))],
+        );
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn on_enter(&mut self, _state: &AppState) {
+        self.scroll = 0;
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::SourceType;
+
+    fn text(line: &Line) -> String {
+        line.iter().map(|(t, _)| t.as_str()).collect()
+    }
+
+    #[test]
+    fn instrument_flows_to_its_output() {
+        let mut state = AppState::new();
+        state.add_instrument(SourceType::Saw);
+        let lines = routing_lines(&state);
+        let first = text(&lines[0]);
+        assert!(first.starts_with("I"));
+        assert!(first.ends_with("MASTER"));
+        assert!(text(lines.last().unwrap()).starts_with("MASTER"));
+    }
+
+    #[test]
+    fn empty_project_shows_only_master() {
+        let state = AppState::new();
+        let lines = routing_lines(&state);
+        assert_eq!(lines.len(), 1);
+    }
+}
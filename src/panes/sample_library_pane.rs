@@ -0,0 +1,314 @@
+use std::any::Any;
+use std::path::PathBuf;
+
+use crate::favorites::{self, Favorites};
+use crate::sample_library::{self, IndexEvent, SampleInfo};
+use crate::state::AppState;
+use crate::ui::action_id::{ActionId, SampleLibraryActionId};
+use crate::ui::widgets::TextInput;
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, MouseEvent, MouseEventKind, NavAction, Pane, Style};
+use crate::usage::Usage;
+
+/// Keyword/tag search over the indexed sample directories. Opened from the
+/// file browser while choosing a sample; the pick is taken by main.rs and
+/// handed back to the file browser as if chosen there.
+pub struct SampleLibraryPane {
+    keymap: Keymap,
+    query: TextInput,
+    samples: Vec<SampleInfo>,
+    /// Indices into `samples` matching the query
+    results: Vec<usize>,
+    selected: usize,
+    scroll: usize,
+    /// Indexing progress or outcome, shown in the title
+    status: String,
+    /// Chosen file, taken by main.rs
+    pending_choice: Option<PathBuf>,
+    pending_rescan: bool,
+    favorites: Favorites,
+    /// Hide samples that have no stars
+    favorites_only: bool,
+    /// Orders results by last use until a query is typed
+    usage: Usage,
+}
+
+impl SampleLibraryPane {
+    pub fn new(keymap: Keymap) -> Self {
+        let mut query = TextInput::new("");
+        query.set_focused(true);
+        Self {
+            keymap,
+            query,
+            samples: Vec::new(),
+            results: Vec::new(),
+            selected: 0,
+            scroll: 0,
+            status: "not indexed".to_string(),
+            pending_choice: None,
+            pending_rescan: false,
+            favorites: Favorites::default(),
+            favorites_only: false,
+            usage: Usage::default(),
+        }
+    }
+
+    pub fn set_samples(&mut self, samples: Vec<SampleInfo>) {
+        self.samples = samples;
+        self.update_results();
+    }
+
+    pub fn index_event(&mut self, event: &IndexEvent) {
+        self.status = match event {
+            IndexEvent::Progress { done, total } => format!("indexing {}/{}", done, total),
+            IndexEvent::Finished { analyzed, total } => format!("{} samples, {} updated", total, analyzed),
+            IndexEvent::Failed(e) => format!("index failed: {}", e),
+        };
+    }
+
+    pub fn take_choice(&mut self) -> Option<PathBuf> {
+        self.pending_choice.take()
+    }
+
+    pub fn take_rescan(&mut self) -> bool {
+        std::mem::take(&mut self.pending_rescan)
+    }
+
+    fn update_results(&mut self) {
+        self.results = sample_library::search(&self.samples, self.query.value());
+        if self.favorites_only {
+            let (samples, favs) = (&self.samples, &self.favorites);
+            self.results.retain(|&i| favs.is_favorite(&favorites::file_key(&samples[i].path)));
+        }
+        if self.query.value().trim().is_empty() {
+            let (samples, usage) = (&self.samples, &self.usage);
+            self.results.sort_by_cached_key(|&i| std::cmp::Reverse(usage.last_used(&favorites::file_key(&samples[i].path))));
+        }
+        self.selected = self.selected.min(self.results.len().saturating_sub(1));
+    }
+
+    fn move_by(&mut self, delta: isize) {
+        let last = self.results.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    fn selected_sample(&self) -> Option<&SampleInfo> {
+        self.results.get(self.selected).and_then(|&i| self.samples.get(i))
+    }
+
+    fn rate_selected(&mut self, change: impl FnOnce(&mut Favorites, &str)) {
+        let Some(key) = self.selected_sample().map(|s| favorites::file_key(&s.path)) else { return };
+        change(&mut self.favorites, &key);
+        self.favorites.save();
+        if self.favorites_only {
+            self.update_results();
+        }
+    }
+}
+
+impl Default for SampleLibraryPane {
+    fn default() -> Self {
+        Self::new(Keymap::new())
+    }
+}
+
+fn describe(sample: &SampleInfo) -> String {
+    let mut parts = Vec::new();
+    if sample.sample_rate > 0 {
+        parts.push(format!("{:.2}s", sample.duration_secs));
+        parts.push(if sample.channels == 1 { "mono".to_string() } else { format!("{}ch", sample.channels) });
+    }
+    if let Some(bpm) = sample.bpm {
+        parts.push(format!("{} bpm", bpm));
+    }
+    if let Some(key) = &sample.key {
+        parts.push(key.clone());
+    }
+    parts.join("  ")
+}
+
+impl Pane for SampleLibraryPane {
+    fn id(&self) -> &'static str {
+        "sample_library"
+    }
+
+    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, _state: &AppState) -> Action {
+        match action {
+            ActionId::SampleLibrary(SampleLibraryActionId::Up) => self.move_by(-1),
+            ActionId::SampleLibrary(SampleLibraryActionId::Down) => self.move_by(1),
+            ActionId::SampleLibrary(SampleLibraryActionId::PageUp) => self.move_by(-10),
+            ActionId::SampleLibrary(SampleLibraryActionId::PageDown) => self.move_by(10),
+            ActionId::SampleLibrary(SampleLibraryActionId::Select) => {
+                if let Some(sample) = self.selected_sample() {
+                    self.pending_choice = Some(sample.path.clone());
+                    return Action::Nav(NavAction::PopPane);
+                }
+            }
+            ActionId::SampleLibrary(SampleLibraryActionId::Rescan) => {
+                self.pending_rescan = true;
+                self.status = "indexing".to_string();
+            }
+            ActionId::SampleLibrary(SampleLibraryActionId::ToggleStar) => self.rate_selected(|favs, key| favs.toggle(key)),
+            ActionId::SampleLibrary(SampleLibraryActionId::RateUp) => self.rate_selected(|favs, key| favs.adjust(key, 1)),
+            ActionId::SampleLibrary(SampleLibraryActionId::RateDown) => self.rate_selected(|favs, key| favs.adjust(key, -1)),
+            ActionId::SampleLibrary(SampleLibraryActionId::FavoritesOnly) => {
+                self.favorites_only = !self.favorites_only;
+                self.selected = 0;
+                self.scroll = 0;
+                self.update_results();
+            }
+            ActionId::SampleLibrary(SampleLibraryActionId::Close) => return Action::Nav(NavAction::PopPane),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn handle_raw_input(&mut self, event: &InputEvent, _state: &AppState) -> Action {
+        if self.query.handle_input(event) {
+            self.selected = 0;
+            self.scroll = 0;
+            self.update_results();
+        }
+        Action::None
+    }
+
+    fn handle_mouse(&mut self, event: &MouseEvent, _area: Rect, _state: &AppState) -> Action {
+        match event.kind {
+            MouseEventKind::ScrollUp => self.move_by(-3),
+            MouseEventKind::ScrollDown => self.move_by(3),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, _state: &AppState) {
+        let filter = if self.favorites_only { ", favorites" } else { "" };
+        let title = format!(" Sample Library ({}{}) ", self.status, filter);
+        let border_style = Style::new().fg(Color::CYAN);
+        buf.draw_block(area, &title, border_style, border_style);
+
+        let x = area.x + 2;
+        let width = area.width.saturating_sub(4);
+        self.query.render_buf(buf.raw_buf(), x, area.y + 1, width);
+
+        let dim = Style::new().fg(Color::DARK_GRAY);
+        let list_y = area.y + 3;
+        let rows = area.height.saturating_sub(6) as usize;
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if rows > 0 && self.selected >= self.scroll + rows {
+            self.scroll = self.selected + 1 - rows;
+        }
+        for (row, &idx) in self.results.iter().enumerate().skip(self.scroll).take(rows) {
+            let sample = &self.samples[idx];
+            let y = list_y + (row - self.scroll) as u16;
+            let is_selected = row == self.selected;
+            let mut name_style = Style::new().fg(Color::WHITE);
+            let mut info_style = Style::new().fg(Color::GRAY);
+            let mut star_style = Style::new().fg(Color::GOLD);
+            if is_selected {
+                for cx in x..x + width {
+                    buf.set_cell(cx, y, ' ', Style::new().bg(Color::SELECTION_BG));
+                }
+                name_style = name_style.bg(Color::SELECTION_BG);
+                info_style = info_style.bg(Color::SELECTION_BG);
+                star_style = star_style.bg(Color::SELECTION_BG);
+            }
+            let stars = format!("{} ", favorites::stars(self.favorites.rating(&favorites::file_key(&sample.path))));
+            let name = format!("{:<40} ", sample.name);
+            buf.draw_line(Rect::new(x, y, width, 1), &[(&stars, star_style), (&name, name_style), (&describe(sample), info_style)]);
+        }
+        if self.results.is_empty() {
+            let text = if self.samples.is_empty() {
+                "Library is empty: set a samples directory in preferences, then Alt+r to index"
+            } else if self.favorites_only {
+                "No starred matches (Alt+f: show all)"
+            } else {
+                "No matches"
+            };
+            buf.draw_line(Rect::new(x, list_y, width, 1), &[(text, dim)]);
+        }
+
+        if let Some(sample) = self.selected_sample() {
+            let tags = format!("#{}", sample.tags.join(" #"));
+            buf.draw_line(Rect::new(x, area.y + area.height.saturating_sub(3), width, 1), &[(&tags, Style::new().fg(Color::TEAL))]);
+        }
+        let help = "type: words, #tag, bpm:120, bpm:90-110, key:am | Enter: load | Ctrl+t/Left/Right: stars | Alt+f: favorites | Alt+r: rescan | Esc: back";
+        buf.draw_line(Rect::new(x, area.y + area.height.saturating_sub(2), width, 1), &[(help, dim)]);
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn on_enter(&mut self, _state: &AppState) {
+        self.query.set_focused(true);
+        self.favorites = Favorites::load();
+        self.usage = Usage::load();
+        self.update_results();
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::{KeyCode, Modifiers};
+    use std::path::Path;
+
+    fn sample(name: &str, tags: &[&str]) -> SampleInfo {
+        SampleInfo {
+            path: PathBuf::from("/lib").join(name),
+            name: name.to_string(),
+            duration_secs: 0.5,
+            channels: 1,
+            sample_rate: 44100,
+            bpm: None,
+            key: None,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn query_filters_and_select_takes_the_path() {
+        let mut pane = SampleLibraryPane::default();
+        let state = AppState::new();
+        pane.set_samples(vec![sample("kick.wav", &["drums", "kick"]), sample("snare.wav", &["drums", "snare"])]);
+        assert_eq!(pane.results.len(), 2);
+
+        pane.query.set_value("snare");
+        pane.update_results();
+        assert_eq!(pane.results, vec![1]);
+
+        let action = pane.handle_action(ActionId::SampleLibrary(SampleLibraryActionId::Select), &InputEvent::new(KeyCode::Enter, Modifiers::default()), &state);
+        assert!(matches!(action, Action::Nav(NavAction::PopPane)));
+        assert_eq!(pane.take_choice(), Some(PathBuf::from("/lib/snare.wav")));
+    }
+
+    #[test]
+    fn favorites_filter_keeps_starred_samples() {
+        let mut pane = SampleLibraryPane::default();
+        let state = AppState::new();
+        pane.set_samples(vec![sample("kick.wav", &["drums"]), sample("snare.wav", &["drums"])]);
+        pane.favorites.toggle(&favorites::file_key(Path::new("/lib/kick.wav")));
+
+        pane.handle_action(ActionId::SampleLibrary(SampleLibraryActionId::FavoritesOnly), &InputEvent::new(KeyCode::Char('f'), Modifiers::default()), &state);
+        assert_eq!(pane.results, vec![0]);
+        pane.handle_action(ActionId::SampleLibrary(SampleLibraryActionId::FavoritesOnly), &InputEvent::new(KeyCode::Char('f'), Modifiers::default()), &state);
+        assert_eq!(pane.results, vec![0, 1]);
+    }
+
+    #[test]
+    fn recently_used_samples_come_first_without_a_query() {
+        let mut pane = SampleLibraryPane::default();
+        pane.usage.record(&favorites::file_key(Path::new("/lib/snare.wav")));
+        pane.set_samples(vec![sample("kick.wav", &["drums"]), sample("snare.wav", &["drums"])]);
+        assert_eq!(pane.results, vec![1, 0]);
+
+        pane.query.set_value("drums");
+        pane.update_results();
+        assert_eq!(pane.results, vec![0, 1]);
+    }
+}
@@ -10,6 +10,8 @@ pub struct SaveAsPane {
     keymap: Keymap,
     text_input: TextInput,
     error: Option<String>,
+    /// Preferred projects directory from preferences
+    projects_dir: Option<PathBuf>,
 }
 
 impl SaveAsPane {
@@ -20,6 +22,7 @@ impl SaveAsPane {
             keymap,
             text_input,
             error: None,
+            projects_dir: None,
         }
     }
 
@@ -31,7 +34,14 @@ impl SaveAsPane {
         self.error = None;
     }
 
-    fn projects_dir() -> PathBuf {
+    pub fn set_projects_dir(&mut self, dir: Option<PathBuf>) {
+        self.projects_dir = dir;
+    }
+
+    fn projects_dir(&self) -> PathBuf {
+        if let Some(dir) = &self.projects_dir {
+            return dir.clone();
+        }
         if let Some(home) = std::env::var_os("HOME") {
             PathBuf::from(home)
                 .join(".config")
@@ -61,8 +71,13 @@ impl Pane for SaveAsPane {
                     return Action::None;
                 }
 
-                let dir = Self::projects_dir();
-                let path = dir.join(format!("{}.sqlite", name));
+                let dir = self.projects_dir();
+                // A .json name exports the project as JSON instead
+                let path = if name.to_lowercase().ends_with(".json") {
+                    dir.join(&name)
+                } else {
+                    dir.join(format!("{}.sqlite", name))
+                };
                 Action::Session(SessionAction::SaveAs(path))
             }
             KeyCode::Escape => {
@@ -87,7 +102,7 @@ impl Pane for SaveAsPane {
 
         // Label
         let label_area = Rect::new(inner.x + 1, inner.y + 1, inner.width.saturating_sub(2), 1);
-        buf.draw_line(label_area, &[("Project name:", Style::new().fg(Color::DARK_GRAY))]);
+        buf.draw_line(label_area, &[("Project name (name.json exports JSON):", Style::new().fg(Color::DARK_GRAY))]);
 
         // Text input field (rat-widget backed)
         let field_y = inner.y + 2;
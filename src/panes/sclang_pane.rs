@@ -0,0 +1,237 @@
+use std::any::Any;
+use std::collections::VecDeque;
+
+use crate::state::AppState;
+use crate::ui::action_id::{ActionId, SclangActionId};
+use crate::ui::widgets::TextInput;
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, MouseEvent, MouseEventKind, NavAction, Pane, Style};
+
+/// Output lines kept; older ones are dropped
+const MAX_LINES: usize = 1000;
+/// Entered lines kept for recall
+const MAX_HISTORY: usize = 100;
+
+/// Prefix marking code the user sent, in the output
+const ECHO: &str = "> ";
+
+/// REPL for a sclang process sharing imbolc's server. Code typed here is
+/// taken by main.rs, which starts sclang on first use and feeds its
+/// replies back.
+pub struct SclangPane {
+    keymap: Keymap,
+    input: TextInput,
+    output: VecDeque<String>,
+    /// Entered code, oldest first
+    history: Vec<String>,
+    /// Position while recalling history; None is the line being typed
+    history_pos: Option<usize>,
+    /// Lines scrolled back from the newest output
+    scroll: usize,
+    running: bool,
+    /// Code to evaluate, taken by main.rs
+    pending_code: Option<String>,
+}
+
+impl SclangPane {
+    pub fn new(keymap: Keymap) -> Self {
+        let mut input = TextInput::new("");
+        input.set_focused(true);
+        Self {
+            keymap,
+            input,
+            output: VecDeque::new(),
+            history: Vec::new(),
+            history_pos: None,
+            scroll: 0,
+            running: false,
+            pending_code: None,
+        }
+    }
+
+    /// Code entered since the last call
+    pub fn take_code(&mut self) -> Option<String> {
+        self.pending_code.take()
+    }
+
+    /// Add a line of sclang output
+    pub fn push_output(&mut self, line: &str) {
+        self.output.push_back(line.to_string());
+        if self.output.len() > MAX_LINES {
+            self.output.pop_front();
+        }
+        // Keep the view still while scrolled back
+        if self.scroll > 0 {
+            self.scroll = (self.scroll + 1).min(self.output.len());
+        }
+    }
+
+    pub fn set_running(&mut self, running: bool) {
+        self.running = running;
+    }
+
+    fn send(&mut self) {
+        let code = self.input.value().trim().to_string();
+        if code.is_empty() {
+            return;
+        }
+        self.push_output(&format!("{}{}", ECHO, code));
+        if self.history.last() != Some(&code) {
+            self.history.push(code.clone());
+            if self.history.len() > MAX_HISTORY {
+                self.history.remove(0);
+            }
+        }
+        self.history_pos = None;
+        self.scroll = 0;
+        self.input.set_value("");
+        self.pending_code = Some(code);
+    }
+
+    /// Step through entered code; `older` goes back in time
+    fn recall(&mut self, older: bool) {
+        if self.history.is_empty() {
+            return;
+        }
+        let last = self.history.len() - 1;
+        self.history_pos = match (self.history_pos, older) {
+            (None, true) => Some(last),
+            (None, false) => None,
+            (Some(pos), true) => Some(pos.saturating_sub(1)),
+            (Some(pos), false) if pos >= last => None,
+            (Some(pos), false) => Some(pos + 1),
+        };
+        let text = self.history_pos.map_or("", |pos| self.history[pos].as_str()).to_string();
+        self.input.set_value(&text);
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        let max = self.output.len().saturating_sub(1);
+        self.scroll = self.scroll.saturating_add_signed(delta).min(max);
+    }
+}
+
+impl Default for SclangPane {
+    fn default() -> Self {
+        Self::new(Keymap::new())
+    }
+}
+
+impl Pane for SclangPane {
+    fn id(&self) -> &'static str {
+        "sclang"
+    }
+
+    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, _state: &AppState) -> Action {
+        match action {
+            ActionId::Sclang(SclangActionId::Send) => self.send(),
+            ActionId::Sclang(SclangActionId::HistoryPrev) => self.recall(true),
+            ActionId::Sclang(SclangActionId::HistoryNext) => self.recall(false),
+            ActionId::Sclang(SclangActionId::PageUp) => self.scroll_by(10),
+            ActionId::Sclang(SclangActionId::PageDown) => self.scroll_by(-10),
+            ActionId::Sclang(SclangActionId::Stop) => self.pending_code = Some("CmdPeriod.run;".to_string()),
+            ActionId::Sclang(SclangActionId::Clear) => {
+                self.output.clear();
+                self.scroll = 0;
+            }
+            ActionId::Sclang(SclangActionId::Close) => return Action::Nav(NavAction::PopPane),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn handle_raw_input(&mut self, event: &InputEvent, _state: &AppState) -> Action {
+        self.input.handle_input(event);
+        Action::None
+    }
+
+    fn handle_mouse(&mut self, event: &MouseEvent, _area: Rect, _state: &AppState) -> Action {
+        match event.kind {
+            MouseEventKind::ScrollUp => self.scroll_by(3),
+            MouseEventKind::ScrollDown => self.scroll_by(-3),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, _state: &AppState) {
+        let status = if self.running { "running" } else { "starts on first send" };
+        let title = format!(" sclang ({}) ", status);
+        let border_style = Style::new().fg(Color::CYAN);
+        buf.draw_block(area, &title, border_style, border_style);
+
+        let x = area.x + 2;
+        let width = area.width.saturating_sub(4);
+        let rows = area.height.saturating_sub(5) as usize;
+        let end = self.output.len().saturating_sub(self.scroll);
+        let start = end.saturating_sub(rows);
+        for (row, line) in self.output.range(start..end).enumerate() {
+            let color = if line.starts_with(ECHO) {
+                Color::TEAL
+            } else if line.starts_with("ERROR") || line.starts_with("FAILURE") || line.contains("WARNING") {
+                Color::RED
+            } else {
+                Color::WHITE
+            };
+            buf.draw_line(Rect::new(x, area.y + 1 + row as u16, width, 1), &[(line, Style::new().fg(color))]);
+        }
+        let dim = Style::new().fg(Color::DARK_GRAY);
+        if self.output.is_empty() {
+            buf.draw_line(Rect::new(x, area.y + 1, width, 1),
+                &[("Type SuperCollider code; s is imbolc's server. e.g. { SinOsc.ar(440, 0, 0.1) }.play", dim)]);
+        }
+
+        let input_y = area.y + area.height.saturating_sub(3);
+        self.input.render_buf(buf.raw_buf(), x, input_y, width);
+
+        let help_y = area.y + area.height.saturating_sub(2);
+        let help = "Enter: evaluate | Up/Down: history | PgUp/PgDn: scroll | Ctrl+.: stop sounds | Alt+l: clear | Esc: close";
+        buf.draw_line(Rect::new(x, help_y, width, 1), &[(help, dim)]);
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn on_enter(&mut self, _state: &AppState) {
+        self.input.set_focused(true);
+        self.scroll = 0;
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sending_echoes_and_records_history() {
+        let mut pane = SclangPane::default();
+        pane.input.set_value("1 + 2");
+        pane.send();
+        assert_eq!(pane.take_code().as_deref(), Some("1 + 2"));
+        assert_eq!(pane.output.back().map(String::as_str), Some("> 1 + 2"));
+        assert_eq!(pane.input.value(), "");
+
+        pane.input.set_value("s.queryAllNodes");
+        pane.send();
+        pane.recall(true);
+        assert_eq!(pane.input.value(), "s.queryAllNodes");
+        pane.recall(true);
+        assert_eq!(pane.input.value(), "1 + 2");
+        pane.recall(false);
+        pane.recall(false);
+        assert_eq!(pane.input.value(), "");
+    }
+
+    #[test]
+    fn blank_input_sends_nothing() {
+        let mut pane = SclangPane::default();
+        pane.input.set_value("   ");
+        pane.send();
+        assert_eq!(pane.take_code(), None);
+        assert!(pane.output.is_empty());
+    }
+}
@@ -1,10 +1,41 @@
 use std::any::Any;
+use std::time::Instant;
 
-use crate::state::drum_sequencer::NUM_PADS;
+use crate::av_sync::VisualOffset;
+use crate::perf_macros::PerformanceMacro;
+use crate::state::drum_sequencer::{DrumSequencerState, PadFilter, NUM_PADS};
 use crate::state::AppState;
 use crate::ui::layout_helpers::center_rect;
-use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, MouseEvent, MouseEventKind, MouseButton, NavAction, Pane, SequencerAction, Style};
-use crate::ui::action_id::{ActionId, SequencerActionId};
+use crate::ui::{Rect, RenderBuf, Action, Color, FileSelectAction, InputEvent, InstrumentAction, KeyCode, Keymap, MouseEvent, MouseEventKind, MouseButton, NavAction, PadKeyboard, Pane, SequencerAction, SessionAction, Style, ToggleResult, translate_key};
+use crate::ui::action_id::{ActionId, ModeActionId, SequencerActionId};
+
+/// What hitting a pad does to the pattern while the transport runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PadRecord {
+    Off,
+    /// Write a step at the playhead
+    Record,
+    /// Clear the step at the playhead; hold the pad to erase as it passes
+    Erase,
+}
+
+impl PadRecord {
+    fn next(self) -> Self {
+        match self {
+            PadRecord::Off => PadRecord::Record,
+            PadRecord::Record => PadRecord::Erase,
+            PadRecord::Erase => PadRecord::Off,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            PadRecord::Off => "",
+            PadRecord::Record => "  REC",
+            PadRecord::Erase => "  ERASE",
+        }
+    }
+}
 
 pub struct SequencerPane {
     keymap: Keymap,
@@ -13,6 +44,15 @@ pub struct SequencerPane {
     view_start_step: usize,
     /// Selection anchor (pad, step). None = no selection.
     pub(crate) selection_anchor: Option<(usize, usize)>,
+    pad_keyboard: PadKeyboard,
+    pad_record: PadRecord,
+    /// Performance macro started by the last key press, taken by main.rs
+    pending_macro: Option<PerformanceMacro>,
+    /// Bounce of every pattern requested, taken by main.rs
+    pending_batch_export: bool,
+    /// State steps are set to while dragging with the left button
+    paint: Option<bool>,
+    visual_offset: VisualOffset,
 }
 
 impl SequencerPane {
@@ -23,6 +63,68 @@ impl SequencerPane {
             cursor_step: 0,
             view_start_step: 0,
             selection_anchor: None,
+            pad_keyboard: PadKeyboard::new(),
+            pad_record: PadRecord::Off,
+            pending_macro: None,
+            pending_batch_export: false,
+            paint: None,
+            visual_offset: VisualOffset::default(),
+        }
+    }
+
+    /// Draw the step highlight this far ahead of the transport
+    pub fn set_visual_offset(&mut self, offset: VisualOffset) {
+        self.visual_offset = offset;
+    }
+
+    fn length_label(length: usize) -> String {
+        format!("  Length: {}", length)
+    }
+
+    fn swing_label(swing: f32) -> String {
+        format!("  Swing: {:.0}%", swing * 100.0)
+    }
+
+    fn bpm_label(bpm: f32) -> String {
+        format!("  BPM: {:.0}", bpm)
+    }
+
+    /// Columns from the header start to the PLAY/STOP label, for mouse hits
+    fn play_label_offset(length: usize, swing: f32, bpm: f32) -> u16 {
+        let before = "Pattern A".len()
+            + Self::length_label(length).len()
+            + Self::swing_label(swing).len()
+            + Self::bpm_label(bpm).len();
+        before as u16 + 2
+    }
+
+    /// Take the performance macro started since the last call
+    pub fn take_macro(&mut self) -> Option<PerformanceMacro> {
+        self.pending_macro.take()
+    }
+
+    /// Whether a batch export of all patterns was asked for since the last call
+    pub fn take_batch_export(&mut self) -> bool {
+        std::mem::take(&mut self.pending_batch_export)
+    }
+
+    /// Action for a pad hit: play it, and write or erase the step at the
+    /// playhead when recording into a running pattern
+    fn hit_pad(&self, pad: usize, seq: &DrumSequencerState) -> Action {
+        let play = Action::Instrument(InstrumentAction::PlayDrumPad(pad));
+        if !seq.playing {
+            return play;
+        }
+        let step = seq.current_step;
+        let active = seq.pattern().steps[pad].get(step).map_or(false, |s| s.active);
+        match self.pad_record {
+            PadRecord::Off => play,
+            PadRecord::Record if !active => {
+                Action::Batch(vec![play, Action::Sequencer(SequencerAction::ToggleStep(pad, step))])
+            }
+            PadRecord::Record => play,
+            PadRecord::Erase if active => Action::Sequencer(SequencerAction::ToggleStep(pad, step)),
+            PadRecord::Erase => Action::None,
         }
     }
 
@@ -54,6 +156,20 @@ impl SequencerPane {
 
 }
 
+/// Seconds one pass of a pattern lasts; steps are sixteenth notes
+pub fn pattern_secs(length: usize, bpm: f32) -> f32 {
+    length as f32 * 60.0 / bpm.max(1.0) / 4.0
+}
+
+/// Cutoff as "850Hz" or "2.4k"
+fn format_hz(hz: f32) -> String {
+    if hz >= 1000.0 {
+        format!("{:.1}k", hz / 1000.0)
+    } else {
+        format!("{:.0}Hz", hz)
+    }
+}
+
 impl Default for SequencerPane {
     fn default() -> Self {
         Self::new(Keymap::new())
@@ -65,7 +181,12 @@ impl Pane for SequencerPane {
         "sequencer"
     }
 
-    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, state: &AppState) -> Action {
+    fn handle_action(&mut self, action: ActionId, event: &InputEvent, state: &AppState) -> Action {
+        if action == ActionId::Mode(ModeActionId::PadEscape) {
+            self.pad_keyboard.deactivate();
+            return Action::ExitPerformanceMode;
+        }
+
         let seq = match state.instruments.selected_drum_sequencer() {
             Some(s) => s,
             None => return Action::None,
@@ -73,6 +194,19 @@ impl Pane for SequencerPane {
         let pattern_length = seq.pattern().length;
 
         match action {
+            ActionId::Mode(ModeActionId::PadKey) => {
+                if let KeyCode::Char(c) = event.key {
+                    let c = translate_key(c, state.keyboard_layout);
+                    if let Some(pad) = self.pad_keyboard.key_to_pad(c) {
+                        return self.hit_pad(pad, seq);
+                    }
+                }
+                Action::None
+            }
+            ActionId::Sequencer(SequencerActionId::CyclePadRecord) => {
+                self.pad_record = self.pad_record.next();
+                Action::None
+            }
             ActionId::Sequencer(SequencerActionId::VelUp) => {
                 return Action::Sequencer(SequencerAction::AdjustVelocity(
                     self.cursor_pad,
@@ -155,6 +289,52 @@ impl Pane for SequencerPane {
             ActionId::Sequencer(SequencerActionId::LoadSample) => {
                 Action::Sequencer(SequencerAction::LoadSample(self.cursor_pad))
             }
+            ActionId::Sequencer(SequencerActionId::AddRoundRobin) => {
+                Action::Session(SessionAction::OpenFileBrowser(FileSelectAction::AddRoundRobinSample(self.cursor_pad)))
+            }
+            ActionId::Sequencer(SequencerActionId::ClearRoundRobin) => Action::Sequencer(SequencerAction::ClearRoundRobin(self.cursor_pad)),
+            ActionId::Sequencer(SequencerActionId::StartJitterDown) => Action::Sequencer(SequencerAction::AdjustPadStartJitter(self.cursor_pad, -1.0)),
+            ActionId::Sequencer(SequencerActionId::StartJitterUp) => Action::Sequencer(SequencerAction::AdjustPadStartJitter(self.cursor_pad, 1.0)),
+            ActionId::Sequencer(SequencerActionId::PitchJitterDown) => Action::Sequencer(SequencerAction::AdjustPadPitchJitter(self.cursor_pad, -5.0)),
+            ActionId::Sequencer(SequencerActionId::PitchJitterUp) => Action::Sequencer(SequencerAction::AdjustPadPitchJitter(self.cursor_pad, 5.0)),
+            ActionId::Sequencer(SequencerActionId::CycleFilter) => Action::Sequencer(SequencerAction::CyclePadFilter(self.cursor_pad)),
+            ActionId::Sequencer(SequencerActionId::CutoffDown) => Action::Sequencer(SequencerAction::AdjustPadCutoff(self.cursor_pad, -0.05)),
+            ActionId::Sequencer(SequencerActionId::CutoffUp) => Action::Sequencer(SequencerAction::AdjustPadCutoff(self.cursor_pad, 0.05)),
+            ActionId::Sequencer(SequencerActionId::DriveDown) => Action::Sequencer(SequencerAction::AdjustPadDrive(self.cursor_pad, -0.05)),
+            ActionId::Sequencer(SequencerActionId::DriveUp) => Action::Sequencer(SequencerAction::AdjustPadDrive(self.cursor_pad, 0.05)),
+            ActionId::Sequencer(SequencerActionId::Roll) => {
+                if seq.playing {
+                    self.pending_macro = Some(PerformanceMacro::roll(self.cursor_pad, state.session.bpm as f32, Instant::now()));
+                }
+                Action::None
+            }
+            ActionId::Sequencer(SequencerActionId::FilterSweep) => {
+                if let Some(inst) = state.instruments.selected_instrument().filter(|_| seq.playing) {
+                    self.pending_macro = Some(PerformanceMacro::filter_sweep(inst.id, state.session.bpm as f32, Instant::now()));
+                }
+                Action::None
+            }
+            ActionId::Sequencer(SequencerActionId::BouncePattern) => {
+                if state.io.pending_render.is_some() {
+                    return Action::None;
+                }
+                match state.instruments.selected_instrument() {
+                    Some(inst) => Action::Sequencer(SequencerAction::BouncePattern {
+                        instrument_id: inst.id,
+                        duration_secs: pattern_secs(pattern_length, state.session.bpm as f32),
+                    }),
+                    None => Action::None,
+                }
+            }
+            ActionId::Sequencer(SequencerActionId::BounceAllPatterns) => {
+                if state.io.pending_render.is_none() {
+                    self.pending_batch_export = true;
+                }
+                Action::None
+            }
+            ActionId::Sequencer(SequencerActionId::ImportPattern) => {
+                Action::Session(SessionAction::OpenFileBrowser(FileSelectAction::ImportDrumPattern))
+            }
             ActionId::Sequencer(SequencerActionId::Chopper) => Action::Nav(NavAction::PushPane("sample_chopper")),
             ActionId::Sequencer(SequencerActionId::ClearPad) => Action::Sequencer(SequencerAction::ClearPad(self.cursor_pad)),
             ActionId::Sequencer(SequencerActionId::ClearPattern) => Action::Sequencer(SequencerAction::ClearPattern),
@@ -167,7 +347,11 @@ impl Pane for SequencerPane {
             ActionId::Sequencer(SequencerActionId::PitchUpOctave) => Action::Sequencer(SequencerAction::AdjustPadPitch(self.cursor_pad, 12)),
             ActionId::Sequencer(SequencerActionId::PitchDownOctave) => Action::Sequencer(SequencerAction::AdjustPadPitch(self.cursor_pad, -12)),
             ActionId::Sequencer(SequencerActionId::StepPitchUp) => Action::Sequencer(SequencerAction::AdjustStepPitch(self.cursor_pad, self.cursor_step, 1)),
+            ActionId::Sequencer(SequencerActionId::SwingDown) => Action::Sequencer(SequencerAction::AdjustPatternSwing(-0.05)),
+            ActionId::Sequencer(SequencerActionId::SwingUp) => Action::Sequencer(SequencerAction::AdjustPatternSwing(0.05)),
             ActionId::Sequencer(SequencerActionId::StepPitchDown) => Action::Sequencer(SequencerAction::AdjustStepPitch(self.cursor_pad, self.cursor_step, -1)),
+            ActionId::Sequencer(SequencerActionId::NudgeEarlier) => Action::Sequencer(SequencerAction::AdjustStepNudge(self.cursor_pad, self.cursor_step, -0.05)),
+            ActionId::Sequencer(SequencerActionId::NudgeLater) => Action::Sequencer(SequencerAction::AdjustStepNudge(self.cursor_pad, self.cursor_step, 0.05)),
             _ => Action::None,
         }
     }
@@ -191,6 +375,7 @@ impl Pane for SequencerPane {
             }
         };
         let pattern = seq.pattern();
+        let drawn_step = self.visual_offset.step(seq, state);
         let visible = self.visible_steps(box_width);
 
         // Calculate effective scroll
@@ -220,16 +405,29 @@ impl Pane for SequencerPane {
         let play_color = if seq.playing { Color::GREEN } else { Color::GRAY };
 
         let pat_str = format!("Pattern {}", pattern_label);
-        let len_str = format!("  Length: {}", pattern.length);
-        let bpm_str = format!("  BPM: {:.0}", state.audio.bpm);
+        let len_str = Self::length_label(pattern.length);
+        let swing_str = Self::swing_label(pattern.swing);
+        let bpm_str = Self::bpm_label(state.audio.bpm);
         let play_str = format!("  {}", play_label);
         buf.draw_line(Rect::new(cx, cy, rect.width.saturating_sub(4), 1), &[
             (&pat_str, Style::new().fg(Color::WHITE).bold()),
             (&len_str, Style::new().fg(Color::DARK_GRAY)),
+            (&swing_str, Style::new().fg(Color::DARK_GRAY)),
             (&bpm_str, Style::new().fg(Color::DARK_GRAY)),
             (&play_str, Style::new().fg(play_color).bold()),
+            (self.pad_record.label(), Style::new().fg(Color::RED).bold()),
         ]);
 
+        // Bounce indicator
+        let bouncing = state.io.pending_render.as_ref().map_or(false, |render| {
+            state.instruments.selected_instrument().map_or(false, |inst| inst.id == render.instrument_id)
+        });
+        if bouncing {
+            let label = " BOUNCING ";
+            let x = rect.x + rect.width - label.len() as u16 - 2;
+            buf.draw_line(Rect::new(x, cy, label.len() as u16, 1), &[(label, Style::new().fg(Color::WHITE).bg(Color::RED))]);
+        }
+
         // Step number header
         let header_y = cy + 2;
         let label_width: u16 = 11;
@@ -279,7 +477,7 @@ impl Pane for SequencerPane {
                 let step_idx = view_start + i;
                 let x = step_col_start + (i as u16) * 3;
                 let is_cursor = is_cursor_row && step_idx == self.cursor_step;
-                let is_playhead = seq.playing && step_idx == seq.current_step;
+                let is_playhead = seq.playing && step_idx == drawn_step;
 
                 let step = &pattern.steps[pad_idx][step_idx];
                 let is_beat = step_idx % 4 == 0;
@@ -314,7 +512,17 @@ impl Pane for SequencerPane {
                 };
 
                 let style = Style::new().fg(fg).bg(bg);
-                let chars: Vec<char> = if step.active { " █ " } else { " · " }.chars().collect();
+                // Nudged steps lean toward the side they fire on
+                let glyph = if !step.active {
+                    " · "
+                } else if step.nudge < 0.0 {
+                    "◂█ "
+                } else if step.nudge > 0.0 {
+                    " █▸"
+                } else {
+                    " █ "
+                };
+                let chars: Vec<char> = glyph.chars().collect();
                 for (j, ch) in chars.iter().enumerate() {
                     buf.set_cell(x + j as u16, y, *ch, style);
                 }
@@ -367,6 +575,16 @@ impl Pane for SequencerPane {
         let mut info_parts: Vec<String> = Vec::new();
         if pad.reverse { info_parts.push("REV".to_string()); }
         if pad.pitch != 0 { info_parts.push(format!("{:+}st", pad.pitch)); }
+        if !pad.round_robin.is_empty() { info_parts.push(format!("RR:{}", pad.round_robin.len() + 1)); }
+        if pad.start_jitter_ms > 0.0 { info_parts.push(format!("±{:.0}ms", pad.start_jitter_ms)); }
+        if pad.pitch_jitter_cents > 0.0 { info_parts.push(format!("±{:.0}c", pad.pitch_jitter_cents)); }
+        let filter = match pad.filter {
+            PadFilter::Off => None,
+            PadFilter::LowPass => Some("LP"),
+            PadFilter::HighPass => Some("HP"),
+        };
+        if let Some(filter) = filter { info_parts.push(format!("{} {}", filter, format_hz(pad.cutoff_hz))); }
+        if pad.drive > 0.0 { info_parts.push(format!("Drv {:.0}%", pad.drive * 100.0)); }
         let info_str = info_parts.join(" ");
         for (j, ch) in info_str.chars().enumerate() {
             buf.set_cell(info_x + j as u16, detail_y, ch, Style::new().fg(Color::CYAN));
@@ -375,11 +593,14 @@ impl Pane for SequencerPane {
 
         // Velocity
         let step = &pattern.steps[self.cursor_pad][self.cursor_step];
-        let vel_str = if step.pitch_offset != 0 {
+        let mut vel_str = if step.pitch_offset != 0 {
             format!("Vel: {}  P:{:+}", step.velocity, step.pitch_offset)
         } else {
             format!("Vel: {}", step.velocity)
         };
+        if step.nudge != 0.0 {
+            vel_str.push_str(&format!("  Nudge:{:+.0}%", step.nudge * 100.0));
+        }
         for (j, ch) in vel_str.chars().enumerate() {
             buf.set_cell(info_x + info_offset + j as u16, detail_y, ch, dark_gray);
         }
@@ -397,7 +618,7 @@ impl Pane for SequencerPane {
         let help_y = rect.y + rect.height - 2;
         buf.draw_line(
             Rect::new(cx, help_y, rect.width.saturating_sub(4), 1),
-            &[("Enter:toggle  Space:play  s:sample  c:chop  r:rev  -/=:pitch  C-Up/Dn:step pitch", Style::new().fg(Color::DARK_GRAY))],
+            &[("Enter:toggle  Space:play  s:sample  c:chop  r:rev  -/=:pitch  C-Up/Dn:step pitch  A-←→:nudge", Style::new().fg(Color::DARK_GRAY))],
         );
     }
 
@@ -431,17 +652,29 @@ impl Pane for SequencerPane {
         let col = event.column;
         let row = event.row;
 
+        // Step under the pointer, if any
+        let step_at = if col >= step_col_start && row >= grid_y && row < grid_y + NUM_PADS as u16 {
+            let pad_idx = (row - grid_y) as usize;
+            let step_idx = view_start + ((col - step_col_start) / 3) as usize;
+            (step_idx < pattern.length && step_idx < view_start + visible).then_some((pad_idx, step_idx))
+        } else {
+            None
+        };
+
         match event.kind {
             MouseEventKind::Down(MouseButton::Left) => {
-                // Click on step grid
-                if col >= step_col_start && row >= grid_y && row < grid_y + NUM_PADS as u16 {
-                    let pad_idx = (row - grid_y) as usize;
-                    let step_offset = (col - step_col_start) / 3;
-                    let step_idx = view_start + step_offset as usize;
-                    if pad_idx < NUM_PADS && step_idx < pattern.length {
-                        self.cursor_pad = pad_idx;
-                        self.cursor_step = step_idx;
-                        return Action::Sequencer(SequencerAction::ToggleStep(pad_idx, step_idx));
+                // Click on step grid; dragging from here paints the same state
+                if let Some((pad_idx, step_idx)) = step_at {
+                    self.cursor_pad = pad_idx;
+                    self.cursor_step = step_idx;
+                    self.paint = Some(!pattern.steps[pad_idx][step_idx].active);
+                    return Action::Sequencer(SequencerAction::ToggleStep(pad_idx, step_idx));
+                }
+                // Click on the PLAY/STOP label in the header
+                if row == rect.y + 1 {
+                    let play_x = cx + Self::play_label_offset(pattern.length, pattern.swing, state.audio.bpm);
+                    if col >= play_x && col < play_x + 4 {
+                        return Action::Sequencer(SequencerAction::PlayStop);
                     }
                 }
                 // Click on pad label to select pad
@@ -453,6 +686,21 @@ impl Pane for SequencerPane {
                 }
                 Action::None
             }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                let (Some(target), Some((pad_idx, step_idx))) = (self.paint, step_at) else {
+                    return Action::None;
+                };
+                self.cursor_pad = pad_idx;
+                self.cursor_step = step_idx;
+                if pattern.steps[pad_idx][step_idx].active != target {
+                    return Action::Sequencer(SequencerAction::ToggleStep(pad_idx, step_idx));
+                }
+                Action::None
+            }
+            MouseEventKind::Up(MouseButton::Left) => {
+                self.paint = None;
+                Action::None
+            }
             MouseEventKind::ScrollUp => {
                 self.cursor_pad = self.cursor_pad.saturating_sub(1);
                 Action::None
@@ -469,6 +717,26 @@ impl Pane for SequencerPane {
         &self.keymap
     }
 
+    fn toggle_performance_mode(&mut self, _state: &AppState) -> ToggleResult {
+        if self.pad_keyboard.is_active() {
+            self.pad_keyboard.deactivate();
+            ToggleResult::Deactivated
+        } else {
+            self.pad_keyboard.activate();
+            ToggleResult::ActivatedPad
+        }
+    }
+
+    fn activate_pad(&mut self) {
+        self.pad_keyboard.activate();
+    }
+
+    fn deactivate_performance(&mut self) {
+        self.pad_keyboard.deactivate();
+    }
+
+    fn supports_performance_mode(&self) -> bool { true }
+
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
@@ -517,6 +785,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn dragging_paints_steps_to_the_clicked_state() {
+        let mut state = AppState::new();
+        state.add_instrument(SourceType::Kit);
+        let mut pane = SequencerPane::new(Keymap::new());
+
+        let area = Rect::new(0, 0, 120, 40);
+        let rect = center_rect(area, 97, 29);
+        let (step_x, grid_y) = (rect.x + 2 + 11, rect.y + 4);
+        let mouse = |kind, step: u16| MouseEvent { kind, column: step_x + step * 3, row: grid_y, modifiers: Modifiers::default() };
+
+        let action = pane.handle_mouse(&mouse(MouseEventKind::Down(MouseButton::Left), 0), area, &state);
+        assert!(matches!(action, Action::Sequencer(SequencerAction::ToggleStep(0, 0))));
+        let action = pane.handle_mouse(&mouse(MouseEventKind::Drag(MouseButton::Left), 2), area, &state);
+        assert!(matches!(action, Action::Sequencer(SequencerAction::ToggleStep(0, 2))));
+
+        // Releasing the button ends the drag
+        pane.handle_mouse(&mouse(MouseEventKind::Up(MouseButton::Left), 2), area, &state);
+        let action = pane.handle_mouse(&mouse(MouseEventKind::Drag(MouseButton::Left), 3), area, &state);
+        assert!(matches!(action, Action::None));
+    }
+
     #[test]
     fn chopper_pushes_sample_chopper() {
         let mut state = AppState::new();
@@ -529,4 +819,56 @@ mod tests {
             _ => panic!("Expected PushPane(sample_chopper)"),
         }
     }
+
+    #[test]
+    fn pad_hits_record_and_erase_at_playhead() {
+        let mut state = AppState::new();
+        state.add_instrument(SourceType::Kit);
+        let mut pane = SequencerPane::new(Keymap::new());
+        let seq = state.instruments.instruments[0].drum_sequencer.as_mut().unwrap();
+        seq.playing = true;
+        seq.current_step = 3;
+
+        let seq = state.instruments.selected_drum_sequencer().unwrap();
+        assert!(matches!(pane.hit_pad(2, seq), Action::Instrument(InstrumentAction::PlayDrumPad(2))));
+
+        pane.pad_record = PadRecord::Record;
+        match pane.hit_pad(2, seq) {
+            Action::Batch(actions) => {
+                assert!(matches!(actions[1], Action::Sequencer(SequencerAction::ToggleStep(2, 3))));
+            }
+            _ => panic!("Expected Batch"),
+        }
+
+        // Nothing to erase on an empty step
+        pane.pad_record = PadRecord::Erase;
+        assert!(matches!(pane.hit_pad(2, seq), Action::None));
+    }
+
+    #[test]
+    fn add_round_robin_browses_for_cursor_pad() {
+        let mut state = AppState::new();
+        state.add_instrument(SourceType::Kit);
+        let mut pane = SequencerPane::new(Keymap::new());
+        pane.cursor_pad = 4;
+
+        let action = pane.handle_action(ActionId::Sequencer(SequencerActionId::AddRoundRobin), &dummy_event(), &state);
+        assert!(matches!(
+            action,
+            Action::Session(SessionAction::OpenFileBrowser(FileSelectAction::AddRoundRobinSample(4)))
+        ));
+    }
+
+    #[test]
+    fn cutoff_formats_in_khz_above_1000() {
+        assert_eq!(format_hz(850.0), "850Hz");
+        assert_eq!(format_hz(2400.0), "2.4k");
+    }
+
+    #[test]
+    fn pattern_length_in_seconds() {
+        // 16 sixteenths at 120 BPM is one bar of two seconds
+        assert!((pattern_secs(16, 120.0) - 2.0).abs() < 1e-6);
+        assert!((pattern_secs(32, 60.0) - 8.0).abs() < 1e-6);
+    }
 }
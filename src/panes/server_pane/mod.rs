@@ -36,6 +36,8 @@ pub struct ServerPane {
     log_lines: Vec<String>,
     log_path: PathBuf,
     pub(super) diagnostics: Vec<DiagnosticCheck>,
+    /// Status messages not yet moved to the console, taken by main.rs
+    new_messages: Vec<String>,
 }
 
 impl ServerPane {
@@ -84,6 +86,7 @@ impl ServerPane {
             log_lines: Vec::new(),
             log_path,
             diagnostics: Vec::new(),
+            new_messages: Vec::new(),
         };
         pane.refresh_diagnostics();
         pane
@@ -92,9 +95,17 @@ impl ServerPane {
     pub fn set_status(&mut self, status: ServerStatus, message: &str) {
         self.status = status;
         self.message = message.to_string();
+        if !message.is_empty() {
+            self.new_messages.push(message.to_string());
+        }
         self.refresh_log();
     }
 
+    /// Status messages set since the last call
+    pub fn take_messages(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.new_messages)
+    }
+
     pub fn set_server_running(&mut self, running: bool) {
         self.server_running = running;
         self.refresh_log();
@@ -0,0 +1,253 @@
+use std::any::Any;
+
+use crate::state::AppState;
+use crate::ui::action_id::{ActionId, TimeEditActionId};
+use crate::ui::layout_helpers::center_rect;
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, NavAction, Pane, SessionAction, Style};
+
+/// Fields editable in the time edit popup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Operation,
+    FromBar,
+    Bars,
+}
+
+const FIELDS: [Field; 3] = [Field::Operation, Field::FromBar, Field::Bars];
+
+const MAX_BARS: u32 = 999;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operation {
+    Insert,
+    Delete,
+}
+
+/// Insert or remove whole bars across the project. Notes, automation,
+/// drum patterns and arrangement placements after the edit point all move.
+pub struct TimeEditPane {
+    keymap: Keymap,
+    operation: Operation,
+    /// First bar affected, 1-based
+    from_bar: u32,
+    bars: u32,
+    selected: usize,
+    /// Result of the last apply, shown until the next edit
+    last_applied: Option<String>,
+}
+
+impl TimeEditPane {
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            operation: Operation::Insert,
+            from_bar: 1,
+            bars: 1,
+            selected: 0,
+            last_applied: None,
+        }
+    }
+
+    fn current_field(&self) -> Field {
+        FIELDS[self.selected]
+    }
+
+    fn adjust(&mut self, increase: bool) {
+        match self.current_field() {
+            Field::Operation => {
+                self.operation = match self.operation {
+                    Operation::Insert => Operation::Delete,
+                    Operation::Delete => Operation::Insert,
+                };
+            }
+            Field::FromBar => {
+                self.from_bar = if increase { (self.from_bar + 1).min(MAX_BARS) } else { self.from_bar.saturating_sub(1).max(1) };
+            }
+            Field::Bars => {
+                self.bars = if increase { (self.bars + 1).min(MAX_BARS) } else { self.bars.saturating_sub(1).max(1) };
+            }
+        }
+        self.last_applied = None;
+    }
+
+    fn field_label(&self, field: Field) -> &'static str {
+        match (field, self.operation) {
+            (Field::Operation, _) => "Operation",
+            (Field::FromBar, Operation::Insert) => "At bar",
+            (Field::FromBar, Operation::Delete) => "From bar",
+            (Field::Bars, _) => "Bars",
+        }
+    }
+
+    fn field_value(&self, field: Field) -> String {
+        match field {
+            Field::Operation => match self.operation {
+                Operation::Insert => "Insert time".into(),
+                Operation::Delete => "Delete time".into(),
+            },
+            Field::FromBar => self.from_bar.to_string(),
+            Field::Bars if self.operation == Operation::Delete => {
+                format!("{}  (bars {}-{})", self.bars, self.from_bar, self.from_bar + self.bars - 1)
+            }
+            Field::Bars => self.bars.to_string(),
+        }
+    }
+
+    /// The edit for a bar length of `ticks_per_bar`
+    fn edit_action(&self, ticks_per_bar: u32) -> Action {
+        let start_tick = (self.from_bar - 1) * ticks_per_bar;
+        let ticks = self.bars * ticks_per_bar;
+        match self.operation {
+            Operation::Insert => Action::Session(SessionAction::InsertTime { at_tick: start_tick, ticks }),
+            Operation::Delete => Action::Session(SessionAction::DeleteTime { start_tick, end_tick: start_tick + ticks }),
+        }
+    }
+
+    fn summary(&self) -> String {
+        let bars = if self.bars == 1 { "bar".to_string() } else { format!("{} bars", self.bars) };
+        match self.operation {
+            Operation::Insert => format!("Inserted {} at bar {}", bars, self.from_bar),
+            Operation::Delete => format!("Deleted {} from bar {}", bars, self.from_bar),
+        }
+    }
+}
+
+impl Default for TimeEditPane {
+    fn default() -> Self {
+        Self::new(Keymap::new())
+    }
+}
+
+impl Pane for TimeEditPane {
+    fn id(&self) -> &'static str {
+        "time_edit"
+    }
+
+    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, state: &AppState) -> Action {
+        match action {
+            ActionId::TimeEdit(TimeEditActionId::Prev) => {
+                self.selected = self.selected.saturating_sub(1);
+                Action::None
+            }
+            ActionId::TimeEdit(TimeEditActionId::Next) => {
+                if self.selected < FIELDS.len() - 1 {
+                    self.selected += 1;
+                }
+                Action::None
+            }
+            ActionId::TimeEdit(TimeEditActionId::Decrease) => {
+                self.adjust(false);
+                Action::None
+            }
+            ActionId::TimeEdit(TimeEditActionId::Increase) => {
+                self.adjust(true);
+                Action::None
+            }
+            ActionId::TimeEdit(TimeEditActionId::Apply) => {
+                let ticks_per_bar = state.session.piano_roll.ticks_per_bar();
+                self.last_applied = Some(self.summary());
+                self.edit_action(ticks_per_bar)
+            }
+            ActionId::TimeEdit(TimeEditActionId::Close) => Action::Nav(NavAction::PopPane),
+            _ => Action::None,
+        }
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, _state: &AppState) {
+        let rect = center_rect(area, 48, 9);
+
+        let border_style = Style::new().fg(Color::CYAN);
+        let inner = buf.draw_block(rect, " Insert / Delete Time ", border_style, border_style);
+
+        let label_col = inner.x + 2;
+        let value_col = label_col + 12;
+
+        for (i, field) in FIELDS.iter().enumerate() {
+            let y = inner.y + 1 + i as u16;
+            if y >= inner.y + inner.height {
+                break;
+            }
+            let is_selected = i == self.selected;
+
+            if is_selected {
+                for x in inner.x..inner.x + inner.width {
+                    buf.set_cell(x, y, ' ', Style::new().bg(Color::SELECTION_BG));
+                }
+                buf.set_cell(label_col, y, '>', Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG).bold());
+            }
+
+            let label_style = if is_selected {
+                Style::new().fg(Color::CYAN).bg(Color::SELECTION_BG)
+            } else {
+                Style::new().fg(Color::CYAN)
+            };
+            let val_style = if is_selected {
+                Style::new().fg(Color::WHITE).bg(Color::SELECTION_BG)
+            } else {
+                Style::new().fg(Color::WHITE)
+            };
+            let label = format!("{:10}", self.field_label(*field));
+            buf.draw_line(Rect::new(label_col + 2, y, 10, 1), &[(&label, label_style)]);
+            let val = self.field_value(*field);
+            buf.draw_line(Rect::new(value_col, y, inner.width.saturating_sub(14), 1), &[(&val, val_style)]);
+        }
+
+        if let Some(ref msg) = self.last_applied {
+            let y = inner.y + 1 + FIELDS.len() as u16 + 1;
+            buf.draw_line(Rect::new(label_col, y, inner.width.saturating_sub(4), 1), &[(msg, Style::new().fg(Color::METER_LOW))]);
+        }
+
+        let help_y = rect.y + rect.height - 2;
+        if help_y < area.y + area.height {
+            buf.draw_line(
+                Rect::new(inner.x + 2, help_y, inner.width.saturating_sub(2), 1),
+                &[("Left/Right: adjust | Enter: apply | Esc: close", Style::new().fg(Color::DARK_GRAY))],
+            );
+        }
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn on_enter(&mut self, state: &AppState) {
+        // Start from the bar under the arrangement cursor
+        let ticks_per_bar = state.session.piano_roll.ticks_per_bar().max(1);
+        self.from_bar = state.session.arrangement.cursor_tick / ticks_per_bar + 1;
+        self.last_applied = None;
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delete_covers_whole_bars() {
+        let mut pane = TimeEditPane::default();
+        pane.operation = Operation::Delete;
+        pane.from_bar = 3;
+        pane.bars = 2;
+        assert!(matches!(
+            pane.edit_action(1920),
+            Action::Session(SessionAction::DeleteTime { start_tick: 3840, end_tick: 7680 })
+        ));
+        assert_eq!(pane.field_value(Field::Bars), "2  (bars 3-4)");
+    }
+
+    #[test]
+    fn bars_stay_at_least_one() {
+        let mut pane = TimeEditPane::default();
+        pane.selected = 2;
+        pane.adjust(false);
+        assert_eq!(pane.bars, 1);
+        assert!(matches!(
+            pane.edit_action(1440),
+            Action::Session(SessionAction::InsertTime { at_tick: 0, ticks: 1440 })
+        ));
+    }
+}
@@ -1,10 +1,11 @@
 use std::any::Any;
 
-use crate::state::{AppState, SourceType};
+use crate::av_sync::VisualOffset;
+use crate::state::{AppState, InstrumentId, SourceType};
 use crate::state::arrangement::PlayMode;
 use crate::ui::action_id::{ActionId, TrackActionId};
 use crate::ui::layout_helpers::center_rect;
-use crate::ui::{Rect, RenderBuf, Action, ArrangementAction, Color, InputEvent, Keymap, Pane, Style};
+use crate::ui::{Rect, RenderBuf, Action, ArrangementAction, Color, InputEvent, Keymap, NavAction, Pane, Style};
 
 fn source_color(source: SourceType) -> Color {
     match source {
@@ -23,10 +24,34 @@ fn source_color(source: SourceType) -> Color {
     }
 }
 
+/// Clamp fade lengths so fade-in and fade-out together fit inside the placement
+fn clamp_fades(length: u32, fade_in: i64, fade_out: i64) -> (u32, u32) {
+    let fade_in = fade_in.clamp(0, length as i64) as u32;
+    let fade_out = fade_out.clamp(0, (length - fade_in) as i64) as u32;
+    (fade_in, fade_out)
+}
+
+/// Tick ranges where consecutive placements on one lane overlap. Playback
+/// crossfades these automatically, so they're drawn as crossfade regions.
+fn crossfade_regions(spans: &[(u32, u32)]) -> Vec<(u32, u32)> {
+    let mut sorted = spans.to_vec();
+    sorted.sort_by_key(|(start, _)| *start);
+    let mut regions = Vec::new();
+    let mut prev_end = 0;
+    for (i, (start, end)) in sorted.iter().enumerate() {
+        if i > 0 && *start < prev_end {
+            regions.push((*start, prev_end.min(*end)));
+        }
+        prev_end = prev_end.max(*end);
+    }
+    regions
+}
+
 pub struct TrackPane {
     keymap: Keymap,
     /// Index into current instrument's clips list for placement selection
     selected_clip_index: usize,
+    visual_offset: VisualOffset,
 }
 
 impl TrackPane {
@@ -34,13 +59,44 @@ impl TrackPane {
         Self {
             keymap,
             selected_clip_index: 0,
+            visual_offset: VisualOffset::default(),
         }
     }
 
+    /// Draw the playhead this far ahead of the transport
+    pub fn set_visual_offset(&mut self, offset: VisualOffset) {
+        self.visual_offset = offset;
+    }
+
     fn ticks_per_bar(&self, state: &AppState) -> u32 {
         let (beats, _) = state.session.time_signature;
         beats as u32 * 480
     }
+
+    /// Nudge the fades of the placement under the cursor by whole columns
+    fn adjust_fades(&self, state: &AppState, instrument_id: InstrumentId, fade_in_cols: i64, fade_out_cols: i64) -> Action {
+        let arr = &state.session.arrangement;
+        let Some(placement) = arr.placement_at(instrument_id, arr.cursor_tick) else {
+            return Action::None;
+        };
+        let Some(clip) = arr.clip(placement.clip_id) else {
+            return Action::None;
+        };
+        let step = arr.ticks_per_col.max(1) as i64;
+        let (fade_in_ticks, fade_out_ticks) = clamp_fades(
+            placement.effective_length(clip),
+            placement.fade_in_ticks as i64 + fade_in_cols * step,
+            placement.fade_out_ticks as i64 + fade_out_cols * step,
+        );
+        if fade_in_ticks == placement.fade_in_ticks && fade_out_ticks == placement.fade_out_ticks {
+            return Action::None;
+        }
+        Action::Arrangement(ArrangementAction::SetPlacementFades {
+            placement_id: placement.id,
+            fade_in_ticks,
+            fade_out_ticks,
+        })
+    }
 }
 
 impl Default for TrackPane {
@@ -178,6 +234,18 @@ impl Pane for TrackPane {
                     Action::None
                 }
             }
+            ActionId::Track(TrackActionId::Inspect) => {
+                if arr.placement_at(instrument_id, arr.cursor_tick).is_some() {
+                    Action::Nav(NavAction::PushPane("clip_inspector"))
+                } else {
+                    Action::None
+                }
+            }
+            ActionId::Track(TrackActionId::TimeEdit) => Action::Nav(NavAction::PushPane("time_edit")),
+            ActionId::Track(TrackActionId::FadeInShorter) => self.adjust_fades(state, instrument_id, -1, 0),
+            ActionId::Track(TrackActionId::FadeInLonger) => self.adjust_fades(state, instrument_id, 1, 0),
+            ActionId::Track(TrackActionId::FadeOutShorter) => self.adjust_fades(state, instrument_id, 0, -1),
+            ActionId::Track(TrackActionId::FadeOutLonger) => self.adjust_fades(state, instrument_id, 0, 1),
             ActionId::Track(TrackActionId::ZoomIn) => Action::Arrangement(ArrangementAction::ZoomIn),
             ActionId::Track(TrackActionId::ZoomOut) => Action::Arrangement(ArrangementAction::ZoomOut),
             ActionId::Track(TrackActionId::SelectNextPlacement) => {
@@ -430,11 +498,12 @@ impl Pane for TrackPane {
 
                     // Render clip block
                     let block_width = vis_end - vis_start;
-                    let name = &clip.name;
-                    let display_name: String = if name.len() > block_width as usize {
-                        name[..block_width as usize].to_string()
+                    let name = if clip.reverse { format!("◂{}", clip.name) } else { clip.name.clone() };
+                    let name_len = name.chars().count();
+                    let display_name: String = if name_len > block_width as usize {
+                        name.chars().take(block_width as usize).collect()
                     } else {
-                        let padding = block_width as usize - name.len();
+                        let padding = block_width as usize - name_len;
                         let left_pad = 0;
                         let right_pad = padding;
                         format!("{}{}{}", " ".repeat(left_pad), name, " ".repeat(right_pad))
@@ -462,6 +531,25 @@ impl Pane for TrackPane {
                         }
                     }
 
+                    // Fade handles on the second row: ramp up from the start, down to the end
+                    let fade_y = lane_y + 1;
+                    if fade_y < lanes_area_y + lanes_area_height {
+                        let end_tick = placement.end_tick(clip);
+                        let fade_in_end = placement.start_tick + placement.fade_in_ticks;
+                        let fade_out_start = end_tick.saturating_sub(placement.fade_out_ticks);
+                        for j in vis_start..vis_end {
+                            let tick = arr.view_start_tick + j as u32 * ticks_per_col;
+                            let glyph = if tick < fade_in_end {
+                                '◢'
+                            } else if tick + ticks_per_col > fade_out_start && placement.fade_out_ticks > 0 {
+                                '◣'
+                            } else {
+                                continue;
+                            };
+                            buf.set_cell(timeline_x + j, fade_y, glyph, style);
+                        }
+                    }
+
                     // Clip boundary markers
                     if vis_start > 0 || placement.start_tick >= arr.view_start_tick {
                         let x = timeline_x + vis_start;
@@ -482,6 +570,25 @@ impl Pane for TrackPane {
                 }
             }
 
+            // Overlapping placements crossfade during playback
+            let spans: Vec<(u32, u32)> = placements.iter()
+                .filter_map(|p| arr.clip(p.clip_id).map(|clip| (p.start_tick, p.end_tick(clip))))
+                .collect();
+            let xfade_style = Style::new().fg(Color::BLACK).bg(Color::GOLD);
+            for (start, end) in crossfade_regions(&spans) {
+                for col in 0..timeline_width as u32 {
+                    let tick = arr.view_start_tick + col * ticks_per_col;
+                    if tick + ticks_per_col <= start || tick >= end {
+                        continue;
+                    }
+                    for row in 0..lane_height {
+                        let y = lane_y + row;
+                        if y >= lanes_area_y + lanes_area_height { break; }
+                        buf.set_cell(timeline_x + col as u16, y, '╳', xfade_style);
+                    }
+                }
+            }
+
             // Horizontal separator below each lane
             if vi + 1 < max_visible && i + 1 < num_instruments {
                 let sep_y = lane_y + lane_height;
@@ -494,7 +601,7 @@ impl Pane for TrackPane {
         }
 
         // --- Playhead ---
-        let playhead_tick = state.audio.playhead;
+        let playhead_tick = self.visual_offset.playhead(state);
         if playhead_tick >= arr.view_start_tick {
             let playhead_col = (playhead_tick - arr.view_start_tick) / ticks_per_col;
             if (playhead_col as u16) < timeline_width {
@@ -530,7 +637,7 @@ impl Pane for TrackPane {
         let footer_y = inner.y + inner.height - 2;
 
         // Line 1: key hints
-        let hints = "n:new  p:place  Enter:edit  d:del  m:mode  Space:play  z/x:zoom";
+        let hints = "n:new  p:place  Enter:edit  d:del  m:mode  Space:play  z/x:zoom  {/}:fade in  (/):fade out  i:inspect  t:time";
         buf.draw_line(
             Rect::new(inner.x + 1, footer_y, inner.width.saturating_sub(2), 1),
             &[(hints, Style::new().fg(Color::DARK_GRAY))],
@@ -548,7 +655,12 @@ impl Pane for TrackPane {
             format!("Clip: {} [{}/{}]", clips[idx].name, idx + 1, clips.len())
         };
 
-        let pos_str = format!("Bar {} Beat {}  |  {}", bar, beat, clip_info);
+        let fade_info = arr.placement_at(inst_id, arr.cursor_tick)
+            .filter(|p| p.fade_in_ticks > 0 || p.fade_out_ticks > 0)
+            .map(|p| format!("  |  Fade {:.2}/{:.2} beats", p.fade_in_ticks as f32 / 480.0, p.fade_out_ticks as f32 / 480.0))
+            .unwrap_or_default();
+
+        let pos_str = format!("Bar {} Beat {}  |  {}{}", bar, beat, clip_info, fade_info);
         buf.draw_line(
             Rect::new(inner.x + 1, footer_y + 1, inner.width.saturating_sub(2), 1),
             &[(&pos_str, Style::new().fg(Color::GRAY))],
@@ -563,3 +675,26 @@ impl Pane for TrackPane {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fades_fit_inside_placement() {
+        assert_eq!(clamp_fades(1920, -240, 480), (0, 480));
+        assert_eq!(clamp_fades(1920, 1500, 960), (1500, 420));
+        assert_eq!(clamp_fades(1920, 4000, 10), (1920, 0));
+    }
+
+    #[test]
+    fn overlaps_become_crossfades() {
+        let spans = [(1920, 3840), (0, 1920), (3600, 5760), (5000, 5200)];
+        assert_eq!(crossfade_regions(&spans), vec![(3600, 3840), (5000, 5200)]);
+    }
+
+    #[test]
+    fn adjacent_placements_do_not_crossfade() {
+        assert!(crossfade_regions(&[(0, 1920), (1920, 3840)]).is_empty());
+    }
+}
@@ -0,0 +1,558 @@
+use std::any::Any;
+use std::time::Instant;
+
+use crate::audition::{self, Audition};
+use crate::av_sync::VisualOffset;
+use crate::state::AppState;
+use crate::ui::action_id::{ActionId, TrackerActionId};
+use crate::ui::layout_helpers::center_rect;
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, KeyCode, Keymap, NavAction, Pane, PianoRollAction, Style, translate_key};
+
+/// Tracker note keys: two rows of the keyboard an octave apart, from C
+const NOTE_KEYS: [(char, u8); 29] = [
+    ('z', 0), ('s', 1), ('x', 2), ('d', 3), ('c', 4), ('v', 5), ('g', 6),
+    ('b', 7), ('h', 8), ('n', 9), ('j', 10), ('m', 11),
+    ('q', 12), ('2', 13), ('w', 14), ('3', 15), ('e', 16), ('r', 17), ('5', 18),
+    ('t', 19), ('6', 20), ('y', 21), ('7', 22), ('u', 23),
+    ('i', 24), ('9', 25), ('o', 26), ('0', 27), ('p', 28),
+];
+
+const NOTE_NAMES: [&str; 12] = ["C-", "C#", "D-", "D#", "E-", "F-", "F#", "G-", "G#", "A-", "A#", "B-"];
+
+/// Lines per beat the view steps through
+const LINES_PER_BEAT: [u32; 4] = [2, 4, 8, 16];
+
+/// Columns for one track, "C-4 01 64 L04", plus a separator
+const TRACK_WIDTH: u16 = 14;
+const ROW_LABEL_WIDTH: u16 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Note,
+    Instr,
+    Vol,
+    /// Note length in rows, shown as an `L` effect
+    Fx,
+}
+
+impl Field {
+    const ALL: [Field; 4] = [Field::Note, Field::Instr, Field::Vol, Field::Fx];
+
+    /// Offset and width within a track's columns
+    fn span(self) -> (u16, u16) {
+        match self {
+            Field::Note => (0, 3),
+            Field::Instr => (4, 2),
+            Field::Vol => (7, 2),
+            Field::Fx => (10, 3),
+        }
+    }
+}
+
+/// A piano roll note starting in a row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RowNote {
+    tick: u32,
+    pitch: u8,
+    duration: u32,
+    velocity: u8,
+}
+
+fn note_name(pitch: u8) -> String {
+    format!("{}{}", NOTE_NAMES[pitch as usize % 12], (pitch / 12).saturating_sub(1))
+}
+
+/// `value` with one hex digit replaced, the high one when `high`
+fn set_nibble(value: u8, digit: u8, high: bool) -> u8 {
+    if high {
+        (digit << 4) | (value & 0x0f)
+    } else {
+        (value & 0xf0) | digit
+    }
+}
+
+fn toggle(track: usize, note: RowNote) -> Action {
+    Action::PianoRoll(PianoRollAction::ToggleNote {
+        pitch: note.pitch,
+        tick: note.tick,
+        duration: note.duration,
+        velocity: note.velocity,
+        track,
+    })
+}
+
+/// Swap `old` for `new` as one undo step
+fn replace(track: usize, old: RowNote, new: RowNote) -> Action {
+    Action::Batch(vec![toggle(track, old), toggle(track, new)])
+}
+
+/// Tracker-style view of the piano roll: rows are time steps, each track
+/// gets note, instrument, volume and length columns. Notes are entered
+/// from the keyboard and edited in place; everything lands in the same
+/// piano roll data, so both views stay interchangeable.
+pub struct TrackerPane {
+    keymap: Keymap,
+    track: usize,
+    row: u32,
+    field: Field,
+    /// Index into LINES_PER_BEAT
+    lpb_idx: usize,
+    octave: u8,
+    /// Rows the cursor advances after an entry
+    edit_step: u32,
+    /// Next hex digit typed replaces the high nibble
+    high_nibble: bool,
+    view_row: u32,
+    view_track: usize,
+    /// Audition of an entered note, taken by main.rs
+    pending_audition: Option<Audition>,
+    visual_offset: VisualOffset,
+}
+
+impl TrackerPane {
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            track: 0,
+            row: 0,
+            field: Field::Note,
+            lpb_idx: 1,
+            octave: 4,
+            edit_step: 1,
+            high_nibble: true,
+            view_row: 0,
+            view_track: 0,
+            pending_audition: None,
+            visual_offset: VisualOffset::default(),
+        }
+    }
+
+    /// Draw the playhead this far ahead of the transport
+    pub fn set_visual_offset(&mut self, offset: VisualOffset) {
+        self.visual_offset = offset;
+    }
+
+    /// Take the audition requested since the last call
+    pub fn take_audition(&mut self) -> Option<Audition> {
+        self.pending_audition.take()
+    }
+
+    fn lines_per_beat(&self) -> u32 {
+        LINES_PER_BEAT[self.lpb_idx]
+    }
+
+    /// Rows in one bar of the session's time signature
+    fn rows_per_bar(&self, state: &AppState) -> u32 {
+        self.lines_per_beat() * state.session.time_signature.0.max(1) as u32
+    }
+
+    fn ticks_per_row(&self, state: &AppState) -> u32 {
+        (state.session.piano_roll.ticks_per_beat / self.lines_per_beat()).max(1)
+    }
+
+    fn row_tick(&self, row: u32, state: &AppState) -> u32 {
+        row * self.ticks_per_row(state)
+    }
+
+    fn move_rows(&mut self, delta: i64) {
+        self.row = (self.row as i64 + delta).max(0) as u32;
+        self.high_nibble = true;
+    }
+
+    fn move_field(&mut self, forward: bool, track_count: usize) {
+        let idx = Field::ALL.iter().position(|f| *f == self.field).unwrap_or(0);
+        if forward {
+            if idx + 1 < Field::ALL.len() {
+                self.field = Field::ALL[idx + 1];
+            } else if self.track + 1 < track_count {
+                self.track += 1;
+                self.field = Field::Note;
+            }
+        } else if idx > 0 {
+            self.field = Field::ALL[idx - 1];
+        } else if self.track > 0 {
+            self.track -= 1;
+            self.field = Field::Fx;
+        }
+        self.high_nibble = true;
+    }
+
+    /// Notes of `track` starting in `row`, lowest first
+    fn row_notes(&self, state: &AppState, track: usize, row: u32) -> Vec<RowNote> {
+        let start = self.row_tick(row, state);
+        let end = start + self.ticks_per_row(state);
+        let mut notes: Vec<RowNote> = state.session.piano_roll.track_at(track)
+            .map(|t| t.notes.iter()
+                .filter(|n| n.tick >= start && n.tick < end)
+                .map(|n| RowNote { tick: n.tick, pitch: n.pitch, duration: n.duration, velocity: n.velocity })
+                .collect())
+            .unwrap_or_default();
+        notes.sort_by_key(|n| (n.pitch, n.tick));
+        notes
+    }
+
+    /// Whether `row` is where the track's last sounding notes stop
+    fn is_note_off(&self, state: &AppState, track: usize, row: u32) -> bool {
+        let start = self.row_tick(row, state);
+        let end = start + self.ticks_per_row(state);
+        let Some(t) = state.session.piano_roll.track_at(track) else {
+            return false;
+        };
+        let ends_here = t.notes.iter().any(|n| n.tick < start && (start..end).contains(&(n.tick + n.duration)));
+        let sounding = t.notes.iter().any(|n| n.tick < end && n.tick + n.duration > start);
+        ends_here && !sounding
+    }
+
+    fn instrument_id(&self, state: &AppState) -> u32 {
+        state.session.piano_roll.track_order.get(self.track).copied().unwrap_or(0)
+    }
+
+    /// A note key, note-off or hex digit typed in the current field
+    fn enter_key(&mut self, c: char, state: &AppState) -> Action {
+        if state.session.piano_roll.track_at(self.track).is_none() {
+            return Action::None;
+        }
+        let existing = self.row_notes(state, self.track, self.row).first().copied();
+        let action = match self.field {
+            Field::Note if c == '1' => self.note_off(state),
+            Field::Note => {
+                let Some(&(_, offset)) = NOTE_KEYS.iter().find(|(key, _)| *key == c) else {
+                    return Action::None;
+                };
+                let pitch = ((self.octave as u16 + 1) * 12 + offset as u16).min(127) as u8;
+                let ticks_per_beat = state.session.piano_roll.ticks_per_beat;
+                let note = match existing {
+                    Some(old) => RowNote { pitch, ..old },
+                    None => RowNote { tick: self.row_tick(self.row, state), pitch, duration: ticks_per_beat, velocity: 100 },
+                };
+                self.pending_audition = Some(Audition::new(
+                    audition::relative_notes([(0, note.duration.min(ticks_per_beat), note.pitch, note.velocity)]),
+                    state.session.bpm as f32,
+                    ticks_per_beat,
+                    self.instrument_id(state),
+                    self.track,
+                    Instant::now(),
+                ));
+                match existing {
+                    Some(old) => replace(self.track, old, note),
+                    None => toggle(self.track, note),
+                }
+            }
+            Field::Instr => return Action::None,
+            Field::Vol | Field::Fx => {
+                let (Some(digit), Some(old)) = (c.to_digit(16), existing) else {
+                    return Action::None;
+                };
+                let new = if self.field == Field::Vol {
+                    let velocity = set_nibble(old.velocity, digit as u8, self.high_nibble).clamp(1, 127);
+                    RowNote { velocity, ..old }
+                } else {
+                    let rows = (old.duration / self.ticks_per_row(state)).min(255) as u8;
+                    let rows = set_nibble(rows, digit as u8, self.high_nibble).max(1);
+                    RowNote { duration: rows as u32 * self.ticks_per_row(state), ..old }
+                };
+                if self.high_nibble {
+                    // Stay on the row for the low digit
+                    self.high_nibble = false;
+                    return replace(self.track, old, new);
+                }
+                replace(self.track, old, new)
+            }
+        };
+        self.move_rows(self.edit_step as i64);
+        action
+    }
+
+    /// End the notes sounding into the cursor row there
+    fn note_off(&self, state: &AppState) -> Action {
+        let start = self.row_tick(self.row, state);
+        let Some(t) = state.session.piano_roll.track_at(self.track) else {
+            return Action::None;
+        };
+        let actions: Vec<Action> = t.notes.iter()
+            .filter(|n| n.tick < start && n.tick + n.duration > start)
+            .flat_map(|n| {
+                let old = RowNote { tick: n.tick, pitch: n.pitch, duration: n.duration, velocity: n.velocity };
+                [toggle(self.track, old), toggle(self.track, RowNote { duration: start - n.tick, ..old })]
+            })
+            .collect();
+        if actions.is_empty() { Action::None } else { Action::Batch(actions) }
+    }
+
+    /// Remove the notes starting in the cursor row
+    fn clear_row(&mut self, state: &AppState) -> Action {
+        let actions: Vec<Action> = self.row_notes(state, self.track, self.row)
+            .into_iter()
+            .map(|n| toggle(self.track, n))
+            .collect();
+        self.move_rows(self.edit_step as i64);
+        if actions.is_empty() { Action::None } else { Action::Batch(actions) }
+    }
+}
+
+impl Default for TrackerPane {
+    fn default() -> Self {
+        Self::new(Keymap::new())
+    }
+}
+
+impl Pane for TrackerPane {
+    fn id(&self) -> &'static str {
+        "tracker"
+    }
+
+    fn handle_action(&mut self, action: ActionId, event: &InputEvent, state: &AppState) -> Action {
+        let track_count = state.session.piano_roll.track_order.len();
+        let bar = self.rows_per_bar(state) as i64;
+        match action {
+            ActionId::Tracker(TrackerActionId::Up) => self.move_rows(-1),
+            ActionId::Tracker(TrackerActionId::Down) => self.move_rows(1),
+            ActionId::Tracker(TrackerActionId::PageUp) => self.move_rows(-bar),
+            ActionId::Tracker(TrackerActionId::PageDown) => self.move_rows(bar),
+            ActionId::Tracker(TrackerActionId::Home) => self.move_rows(-(self.row as i64)),
+            ActionId::Tracker(TrackerActionId::End) => {
+                let last_tick = state.session.piano_roll.track_at(self.track)
+                    .and_then(|t| t.notes.iter().map(|n| n.tick).max())
+                    .unwrap_or(0);
+                self.row = last_tick / self.ticks_per_row(state);
+                self.high_nibble = true;
+            }
+            ActionId::Tracker(TrackerActionId::Left) => self.move_field(false, track_count),
+            ActionId::Tracker(TrackerActionId::Right) => self.move_field(true, track_count),
+            ActionId::Tracker(TrackerActionId::NextTrack) => {
+                if self.track + 1 < track_count {
+                    self.track += 1;
+                }
+                self.high_nibble = true;
+            }
+            ActionId::Tracker(TrackerActionId::PrevTrack) => {
+                self.track = self.track.saturating_sub(1);
+                self.high_nibble = true;
+            }
+            ActionId::Tracker(TrackerActionId::Key) => {
+                if let KeyCode::Char(c) = event.key {
+                    return self.enter_key(translate_key(c, state.keyboard_layout), state);
+                }
+            }
+            ActionId::Tracker(TrackerActionId::Clear) => return self.clear_row(state),
+            ActionId::Tracker(TrackerActionId::OctaveUp) => self.octave = (self.octave + 1).min(9),
+            ActionId::Tracker(TrackerActionId::OctaveDown) => self.octave = self.octave.saturating_sub(1),
+            ActionId::Tracker(TrackerActionId::StepUp) => self.edit_step = (self.edit_step + 1).min(16),
+            ActionId::Tracker(TrackerActionId::StepDown) => self.edit_step = self.edit_step.saturating_sub(1),
+            ActionId::Tracker(TrackerActionId::ZoomIn) | ActionId::Tracker(TrackerActionId::ZoomOut) => {
+                // Keep the cursor on the same tick
+                let tick = self.row_tick(self.row, state);
+                self.lpb_idx = if action == ActionId::Tracker(TrackerActionId::ZoomIn) {
+                    (self.lpb_idx + 1).min(LINES_PER_BEAT.len() - 1)
+                } else {
+                    self.lpb_idx.saturating_sub(1)
+                };
+                self.row = tick / self.ticks_per_row(state);
+            }
+            ActionId::Tracker(TrackerActionId::Close) => return Action::Nav(NavAction::PopPane),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, state: &AppState) {
+        let rect = center_rect(area, 97, 29);
+        let title = format!(" Tracker  LPB {}  Oct {}  Step {} ", self.lines_per_beat(), self.octave, self.edit_step);
+        let border_style = Style::new().fg(Color::CYAN);
+        let inner = buf.draw_block(rect, &title, border_style, border_style);
+
+        let piano_roll = &state.session.piano_roll;
+        if piano_roll.track_order.is_empty() {
+            let text = "(no tracks - add an instrument first)";
+            let x = inner.x + inner.width.saturating_sub(text.len() as u16) / 2;
+            buf.draw_line(Rect::new(x, inner.y + inner.height / 2, text.len() as u16, 1), &[(text, Style::new().fg(Color::DARK_GRAY))]);
+            return;
+        }
+        self.track = self.track.min(piano_roll.track_order.len() - 1);
+
+        // Keep the cursor, or the playhead while playing, in view
+        let rows = inner.height.saturating_sub(2) as u32;
+        let tracks = (inner.width.saturating_sub(ROW_LABEL_WIDTH) / TRACK_WIDTH).max(1) as usize;
+        let playhead_row = piano_roll.playing.then(|| self.visual_offset.playhead(state) / self.ticks_per_row(state));
+        let focus = playhead_row.unwrap_or(self.row);
+        if focus < self.view_row {
+            self.view_row = focus;
+        } else if focus >= self.view_row + rows {
+            self.view_row = focus + 1 - rows;
+        }
+        if self.track < self.view_track {
+            self.view_track = self.track;
+        } else if self.track >= self.view_track + tracks {
+            self.view_track = self.track + 1 - tracks;
+        }
+
+        let dim = Style::new().fg(Color::DARK_GRAY);
+        let lpb = self.lines_per_beat();
+        let bar = self.rows_per_bar(state);
+        let first_x = inner.x + ROW_LABEL_WIDTH;
+
+        // Track headers
+        for (col, track) in (self.view_track..piano_roll.track_order.len()).take(tracks).enumerate() {
+            let id = piano_roll.track_order[track];
+            let name = state.instruments.instruments.iter()
+                .find(|i| i.id == id)
+                .map(|i| i.name.as_str())
+                .unwrap_or("?");
+            let label = format!("{:02} {}", track + 1, name);
+            let style = if track == self.track { Style::new().fg(Color::WHITE).bold() } else { Style::new().fg(Color::GRAY) };
+            buf.draw_line(Rect::new(first_x + col as u16 * TRACK_WIDTH, inner.y, TRACK_WIDTH - 1, 1), &[(&label, style)]);
+        }
+
+        for line in 0..rows {
+            let row = self.view_row + line;
+            let y = inner.y + 1 + line as u16;
+            let bg = if playhead_row == Some(row) {
+                Color::new(50, 45, 15)
+            } else if row == self.row {
+                Color::new(30, 30, 45)
+            } else {
+                Color::new(15, 15, 20)
+            };
+            let label_color = if row % bar == 0 {
+                Color::GOLD
+            } else if row % lpb == 0 {
+                Color::WHITE
+            } else {
+                Color::DARK_GRAY
+            };
+            let label = format!("{:>3} ", row);
+            buf.draw_line(Rect::new(inner.x, y, ROW_LABEL_WIDTH, 1), &[(&label, Style::new().fg(label_color))]);
+
+            for (col, track) in (self.view_track..piano_roll.track_order.len()).take(tracks).enumerate() {
+                let x = first_x + col as u16 * TRACK_WIDTH;
+                let notes = self.row_notes(state, track, row);
+                let (note, instr, vol, fx) = match notes.first() {
+                    Some(n) => {
+                        let mut name = note_name(n.pitch);
+                        name.truncate(3);
+                        (name, format!("{:02X}", piano_roll.track_order[track] % 0x100),
+                            format!("{:02X}", n.velocity), format!("L{:02X}", (n.duration / self.ticks_per_row(state)).min(255)))
+                    }
+                    None if self.is_note_off(state, track, row) => ("OFF".to_string(), "..".to_string(), "..".to_string(), "...".to_string()),
+                    None => ("---".to_string(), "..".to_string(), "..".to_string(), "...".to_string()),
+                };
+                let fields = [(Field::Note, note), (Field::Instr, instr), (Field::Vol, vol), (Field::Fx, fx)];
+                for x in x..x + TRACK_WIDTH - 1 {
+                    buf.set_cell(x, y, ' ', Style::new().bg(bg));
+                }
+                for (field, text) in &fields {
+                    let (offset, width) = field.span();
+                    let is_cursor = row == self.row && track == self.track && *field == self.field;
+                    let color = match field {
+                        _ if text.starts_with('-') || text.starts_with('.') => Color::new(60, 60, 70),
+                        Field::Note if text == "OFF" => Color::ORANGE,
+                        Field::Note => Color::WHITE,
+                        Field::Instr => Color::SKY_BLUE,
+                        Field::Vol => Color::TEAL,
+                        Field::Fx => Color::PINK,
+                    };
+                    let style = Style::new().fg(color).bg(if is_cursor { Color::SELECTION_BG } else { bg });
+                    buf.draw_line(Rect::new(x + offset, y, width, 1), &[(text, style)]);
+                }
+                if notes.len() > 1 {
+                    buf.set_cell(x + 3, y, '+', Style::new().fg(Color::GOLD).bg(bg));
+                }
+                buf.set_cell(x + TRACK_WIDTH - 1, y, '\u{2502}', Style::new().fg(Color::new(50, 50, 60)));
+            }
+        }
+
+        let help = "z-m q-p: note  1: off  0-F: vol/len  Del: clear  Tab: track  [ ]: octave  { }: step  +/-: zoom  Esc: back";
+        buf.draw_line(Rect::new(inner.x + 1, inner.y + inner.height - 1, inner.width.saturating_sub(2), 1), &[(help, dim)]);
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn on_enter(&mut self, state: &AppState) {
+        // Start on the track of the globally selected instrument
+        if let Some(track) = state.instruments.selected
+            .and_then(|idx| state.instruments.instruments.get(idx))
+            .and_then(|inst| state.session.piano_roll.track_order.iter().position(|&id| id == inst.id))
+        {
+            self.track = track;
+        }
+        self.high_nibble = true;
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::SourceType;
+    use crate::ui::Modifiers;
+
+    fn key(c: char) -> InputEvent {
+        InputEvent::new(KeyCode::Char(c), Modifiers::default())
+    }
+
+    #[test]
+    fn note_keys_enter_notes_and_advance() {
+        let mut state = AppState::new();
+        state.add_instrument(SourceType::Saw);
+        let mut pane = TrackerPane::default();
+        pane.on_enter(&state);
+        pane.row = 4;
+
+        let action = pane.handle_action(ActionId::Tracker(TrackerActionId::Key), &key('z'), &state);
+        assert!(matches!(action, Action::PianoRoll(PianoRollAction::ToggleNote { pitch: 60, tick: 480, track: 0, .. })));
+        assert_eq!(pane.row, 5);
+        assert!(pane.take_audition().is_some());
+
+        // Upper row is an octave higher
+        let action = pane.handle_action(ActionId::Tracker(TrackerActionId::Key), &key('q'), &state);
+        assert!(matches!(action, Action::PianoRoll(PianoRollAction::ToggleNote { pitch: 72, tick: 600, .. })));
+    }
+
+    #[test]
+    fn rows_follow_the_project_resolution() {
+        let mut state = AppState::new();
+        state.add_instrument(SourceType::Saw);
+        state.session.piano_roll.ticks_per_beat = 960;
+        let mut pane = TrackerPane::default();
+        pane.on_enter(&state);
+        pane.row = 4;
+
+        let action = pane.handle_action(ActionId::Tracker(TrackerActionId::Key), &key('z'), &state);
+        assert!(matches!(action, Action::PianoRoll(PianoRollAction::ToggleNote { tick: 960, duration: 960, .. })));
+    }
+
+    #[test]
+    fn zoom_keeps_the_cursor_tick() {
+        let mut pane = TrackerPane::default();
+        let state = AppState::new();
+        pane.row = 8;
+        pane.handle_action(ActionId::Tracker(TrackerActionId::ZoomIn), &key('+'), &state);
+        assert_eq!(pane.lines_per_beat(), 8);
+        assert_eq!(pane.row, 16);
+        pane.handle_action(ActionId::Tracker(TrackerActionId::ZoomOut), &key('-'), &state);
+        pane.handle_action(ActionId::Tracker(TrackerActionId::ZoomOut), &key('-'), &state);
+        assert_eq!(pane.row, 4);
+    }
+
+    #[test]
+    fn pages_move_a_bar_of_the_time_signature() {
+        let mut state = AppState::new();
+        state.session.time_signature = (3, 4);
+        let mut pane = TrackerPane::default();
+        pane.handle_action(ActionId::Tracker(TrackerActionId::PageDown), &key(' '), &state);
+        assert_eq!(pane.row, 12);
+    }
+
+    #[test]
+    fn hex_digits_fill_high_then_low_nibble() {
+        assert_eq!(set_nibble(0x64, 0x7, true), 0x74);
+        assert_eq!(set_nibble(0x74, 0xf, false), 0x7f);
+        assert_eq!(note_name(60), "C-4");
+        assert_eq!(note_name(70), "A#4");
+    }
+}
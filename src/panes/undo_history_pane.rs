@@ -0,0 +1,185 @@
+use std::any::Any;
+
+use crate::state::AppState;
+use crate::ui::action_id::{ActionId, UndoHistoryActionId};
+use crate::ui::layout_helpers::center_rect;
+use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, NavAction, Pane, Style};
+
+/// One point in the history: the state after `label` ran
+#[derive(Debug, Clone, PartialEq)]
+struct Row {
+    label: String,
+    /// Undo (negative) or redo (positive) steps from the current state
+    offset: isize,
+}
+
+/// History rows, newest first: redoable operations, then the current state,
+/// then older undoable ones, ending with the state before any edit.
+/// `undo` and `redo` are labels ordered nearest-first.
+fn history_rows(undo: &[&str], redo: &[&str]) -> Vec<Row> {
+    let redo_rows = redo.iter().enumerate().rev().map(|(i, label)| Row {
+        label: label.to_string(),
+        offset: i as isize + 1,
+    });
+    let undo_rows = undo.iter().enumerate().map(|(i, label)| Row {
+        label: label.to_string(),
+        offset: -(i as isize),
+    });
+    let start = Row { label: "(start)".to_string(), offset: -(undo.len() as isize) };
+    redo_rows.chain(undo_rows).chain(std::iter::once(start)).collect()
+}
+
+fn rows_for(state: &AppState) -> Vec<Row> {
+    let undo: Vec<&str> = state.undo_history.undo_labels().collect();
+    let redo: Vec<&str> = state.undo_history.redo_labels().collect();
+    history_rows(&undo, &redo)
+}
+
+/// Browsable list of recent undoable operations. Jumping to an entry undoes
+/// or redoes everything between it and the current state.
+pub struct UndoHistoryPane {
+    keymap: Keymap,
+    cursor: usize,
+    /// Steps to undo (negative) or redo (positive); main.rs takes it
+    pending_jump: Option<isize>,
+}
+
+impl UndoHistoryPane {
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            keymap,
+            cursor: 0,
+            pending_jump: None,
+        }
+    }
+
+    /// Requested jump since the last call, if any
+    pub fn take_jump(&mut self) -> Option<isize> {
+        self.pending_jump.take()
+    }
+
+    fn current_index(rows: &[Row]) -> usize {
+        rows.iter().position(|r| r.offset == 0).unwrap_or(0)
+    }
+}
+
+impl Default for UndoHistoryPane {
+    fn default() -> Self {
+        Self::new(Keymap::new())
+    }
+}
+
+impl Pane for UndoHistoryPane {
+    fn id(&self) -> &'static str {
+        "undo_history"
+    }
+
+    fn handle_action(&mut self, action: ActionId, _event: &InputEvent, state: &AppState) -> Action {
+        let rows = rows_for(state);
+        let last = rows.len().saturating_sub(1);
+        match action {
+            ActionId::UndoHistory(UndoHistoryActionId::Up) => self.cursor = self.cursor.saturating_sub(1),
+            ActionId::UndoHistory(UndoHistoryActionId::Down) => self.cursor = (self.cursor + 1).min(last),
+            ActionId::UndoHistory(UndoHistoryActionId::Top) => self.cursor = 0,
+            ActionId::UndoHistory(UndoHistoryActionId::Bottom) => self.cursor = last,
+            ActionId::UndoHistory(UndoHistoryActionId::Current) => self.cursor = Self::current_index(&rows),
+            ActionId::UndoHistory(UndoHistoryActionId::Jump) => {
+                if let Some(row) = rows.get(self.cursor) {
+                    if row.offset != 0 {
+                        self.pending_jump = Some(row.offset);
+                    }
+                }
+            }
+            ActionId::UndoHistory(UndoHistoryActionId::Close) => return Action::Nav(NavAction::PopPane),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut RenderBuf, state: &AppState) {
+        let rect = center_rect(area, 56, area.height.saturating_sub(4).clamp(8, 30));
+        let border_style = Style::new().fg(Color::CYAN);
+        let inner = buf.draw_block(rect, " Undo History ", border_style, border_style);
+
+        let rows = rows_for(state);
+        self.cursor = self.cursor.min(rows.len().saturating_sub(1));
+        let current = Self::current_index(&rows);
+
+        let x = inner.x + 1;
+        let w = inner.width.saturating_sub(2);
+        let visible = inner.height.saturating_sub(2) as usize;
+        let scroll = if self.cursor >= visible { self.cursor + 1 - visible } else { 0 };
+
+        for (i, row) in rows.iter().enumerate().skip(scroll).take(visible) {
+            let y = inner.y + (i - scroll) as u16;
+            let is_selected = i == self.cursor;
+            let bg = if is_selected { Color::SELECTION_BG } else { Color::BLACK };
+            let (marker, color) = if i == current {
+                ("● ", Color::GOLD)
+            } else if row.offset > 0 {
+                ("  ", Color::DARK_GRAY)
+            } else {
+                ("  ", Color::WHITE)
+            };
+            if is_selected {
+                for cx in inner.x..inner.x + inner.width {
+                    buf.set_cell(cx, y, ' ', Style::new().bg(bg));
+                }
+            }
+            let steps = match row.offset {
+                0 => String::new(),
+                n if n > 0 => format!("redo {}", n),
+                n => format!("undo {}", -n),
+            };
+            let label_width = (w as usize).saturating_sub(steps.len() + 3);
+            let label: String = row.label.chars().take(label_width).collect();
+            let text = format!("{}{:<lw$} {}", marker, label, steps, lw = label_width);
+            let mut style = Style::new().fg(color);
+            if is_selected {
+                style = style.bg(bg);
+            }
+            buf.draw_line(Rect::new(x, y, w, 1), &[(&text, style)]);
+        }
+
+        let help_y = inner.y + inner.height - 1;
+        buf.draw_line(
+            Rect::new(x, help_y, w, 1),
+            &[("Enter: jump here | c: current | Esc: close", Style::new().fg(Color::DARK_GRAY))],
+        );
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn on_enter(&mut self, state: &AppState) {
+        self.cursor = Self::current_index(&rows_for(state));
+        self.pending_jump = None;
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_put_redo_above_current() {
+        let rows = history_rows(&["Add note", "Transpose"], &["Delete note"]);
+        let offsets: Vec<isize> = rows.iter().map(|r| r.offset).collect();
+        assert_eq!(offsets, vec![1, 0, -1, -2]);
+        assert_eq!(rows[0].label, "Delete note");
+        assert_eq!(rows[1].label, "Add note");
+        assert_eq!(rows[3].label, "(start)");
+    }
+
+    #[test]
+    fn empty_history_is_just_start() {
+        let rows = history_rows(&[], &[]);
+        assert_eq!(rows, vec![Row { label: "(start)".into(), offset: 0 }]);
+        assert_eq!(UndoHistoryPane::current_index(&rows), 0);
+    }
+}
@@ -3,6 +3,7 @@ use std::any::Any;
 use crate::state::AppState;
 use crate::ui::action_id::{ActionId, WaveformActionId};
 use crate::ui::layout_helpers::center_rect;
+use crate::ui::widgets::{bar_top, BrailleCanvas};
 use crate::ui::{Rect, RenderBuf, Action, Color, InputEvent, Keymap, Pane, Style};
 
 /// Waveform display characters (8 levels)
@@ -65,6 +66,12 @@ pub struct WaveformPane {
     pub audio_in_waveform: Option<Vec<f32>>,
     /// Current display mode
     mode: WaveformMode,
+    /// Braille traces and eighth-block bars instead of whole cells
+    high_res: bool,
+    /// Held peaks per spectrum band, fed by main.rs
+    band_peaks: Vec<f32>,
+    /// Held peak of the scope trace
+    scope_peak: f32,
 }
 
 impl WaveformPane {
@@ -73,8 +80,20 @@ impl WaveformPane {
             keymap,
             audio_in_waveform: None,
             mode: WaveformMode::Waveform,
+            high_res: false,
+            band_peaks: Vec::new(),
+            scope_peak: 0.0,
         }
     }
+
+    pub fn set_high_res(&mut self, high_res: bool) {
+        self.high_res = high_res;
+    }
+
+    pub fn set_peak_holds(&mut self, band_peaks: Vec<f32>, scope_peak: f32) {
+        self.band_peaks = band_peaks;
+        self.scope_peak = scope_peak;
+    }
 }
 
 impl Default for WaveformPane {
@@ -119,36 +138,56 @@ impl WaveformPane {
         // Draw waveform
         let waveform_len = waveform.len();
         let max_half = (grid_height / 2).max(1);
-        for col in 0..grid_width as usize {
-            let sample_idx = if waveform_len > 0 {
-                (col * waveform_len / grid_width as usize).min(waveform_len - 1)
-            } else {
-                0
-            };
-            let amplitude = if sample_idx < waveform_len {
-                waveform[sample_idx].abs().min(1.0)
-            } else {
-                0.0
-            };
-            let bar_height = (amplitude * half_height) as u16;
-
-            for dy in 0..bar_height.min(max_half) {
-                let y = center_y.saturating_sub(dy + 1);
-                let frac = (dy + 1) as f32 / max_half as f32;
-                let color = waveform_color(frac);
-                let style = Style::new().fg(color);
-                let char_idx = if dy + 1 == bar_height { ((amplitude * 7.0) as usize).min(7) } else { 7 };
-                buf.set_cell(grid_x + col as u16, y, WAVEFORM_CHARS[char_idx], style);
+        if self.high_res {
+            let mut canvas = BrailleCanvas::new(grid_width, grid_height);
+            let center_dot = canvas.dot_height() / 2;
+            let half_dots = center_dot.saturating_sub(1) as f32;
+            for x in 0..canvas.dot_width() {
+                if waveform_len == 0 {
+                    break;
+                }
+                let idx = (x * waveform_len / canvas.dot_width()).min(waveform_len - 1);
+                let reach = (waveform[idx].abs().min(1.0) * half_dots) as usize;
+                if reach > 0 {
+                    canvas.vline(x, center_dot - reach, center_dot + reach);
+                }
             }
-            for dy in 0..bar_height.min(max_half) {
-                let y = center_y + dy + 1;
-                if y < grid_y + grid_height {
+            canvas.render(buf, grid_x, grid_y, |_, row| {
+                let distance = (row as f32 + 0.5 - grid_height as f32 / 2.0).abs();
+                Style::new().fg(waveform_color(distance / (grid_height as f32 / 2.0)))
+            });
+        } else {
+            for col in 0..grid_width as usize {
+                let sample_idx = if waveform_len > 0 {
+                    (col * waveform_len / grid_width as usize).min(waveform_len - 1)
+                } else {
+                    0
+                };
+                let amplitude = if sample_idx < waveform_len {
+                    waveform[sample_idx].abs().min(1.0)
+                } else {
+                    0.0
+                };
+                let bar_height = (amplitude * half_height) as u16;
+
+                for dy in 0..bar_height.min(max_half) {
+                    let y = center_y.saturating_sub(dy + 1);
                     let frac = (dy + 1) as f32 / max_half as f32;
                     let color = waveform_color(frac);
                     let style = Style::new().fg(color);
                     let char_idx = if dy + 1 == bar_height { ((amplitude * 7.0) as usize).min(7) } else { 7 };
                     buf.set_cell(grid_x + col as u16, y, WAVEFORM_CHARS[char_idx], style);
                 }
+                for dy in 0..bar_height.min(max_half) {
+                    let y = center_y + dy + 1;
+                    if y < grid_y + grid_height {
+                        let frac = (dy + 1) as f32 / max_half as f32;
+                        let color = waveform_color(frac);
+                        let style = Style::new().fg(color);
+                        let char_idx = if dy + 1 == bar_height { ((amplitude * 7.0) as usize).min(7) } else { 7 };
+                        buf.set_cell(grid_x + col as u16, y, WAVEFORM_CHARS[char_idx], style);
+                    }
+                }
             }
         }
 
@@ -178,7 +217,8 @@ impl WaveformPane {
         for (i, &amp) in bands.iter().enumerate() {
             let bar_x = grid_x + (i * band_width) as u16 + 1;
             let bar_width = (band_width - gap).max(1);
-            let bar_height = (amp.min(1.0) * grid_height as f32) as u16;
+            let eighths = (amp.min(1.0) * grid_height as f32 * 8.0) as u16;
+            let bar_height = if self.high_res { eighths.div_ceil(8) } else { eighths / 8 };
 
             // Draw bar from bottom up
             for dy in 0..bar_height.min(grid_height) {
@@ -186,9 +226,23 @@ impl WaveformPane {
                 let frac = (dy + 1) as f32 / grid_height as f32;
                 let color = waveform_color(frac);
                 let style = Style::new().fg(color);
+                let ch = if self.high_res { bar_top((eighths - dy * 8).min(8) as u8) } else { WAVEFORM_CHARS[7] };
+                for bx in 0..bar_width as u16 {
+                    if bar_x + bx < grid_x + grid_width {
+                        buf.set_cell(bar_x + bx, y, ch, style);
+                    }
+                }
+            }
+
+            // Held peak just above the bar
+            let peak = self.band_peaks.get(i).copied().unwrap_or(0.0);
+            let peak_rows = (peak.min(1.0) * grid_height as f32) as u16;
+            if peak_rows > bar_height && peak_rows <= grid_height {
+                let y = grid_y + grid_height - peak_rows;
+                let style = Style::new().fg(waveform_color(peak_rows as f32 / grid_height as f32));
                 for bx in 0..bar_width as u16 {
                     if bar_x + bx < grid_x + grid_width {
-                        buf.set_cell(bar_x + bx, y, WAVEFORM_CHARS[7], style);
+                        buf.set_cell(bar_x + bx, y, '\u{2594}', style);
                     }
                 }
             }
@@ -238,30 +292,53 @@ impl WaveformPane {
             buf.set_cell(grid_x + x, center_y, '\u{2500}', dark_gray);
         }
 
+        // Held peak as dotted lines either side of center
+        let peak_offset = (self.scope_peak.min(1.0) * half_height) as u16;
+        if peak_offset > 0 {
+            for x in (0..grid_width).step_by(2) {
+                buf.set_cell(grid_x + x, center_y.saturating_sub(peak_offset).max(grid_y), '\u{00b7}', dark_gray);
+                buf.set_cell(grid_x + x, (center_y + peak_offset).min(grid_y + grid_height - 1), '\u{00b7}', dark_gray);
+            }
+        }
+
         // Draw scope trace
         let scope_len = scope.len();
         let green = Style::new().fg(Color::new(60, 200, 80));
-        for col in 0..grid_width as usize {
-            let sample_idx = if scope_len > 0 {
-                (col * scope_len / grid_width as usize).min(scope_len - 1)
-            } else {
-                continue;
-            };
-            let sample = scope[sample_idx].clamp(-1.0, 1.0);
-            let pixel_y = center_y as f32 - (sample * half_height);
-            let y = (pixel_y as u16).clamp(grid_y, grid_y + grid_height - 1);
-            buf.set_cell(grid_x + col as u16, y, '\u{2588}', green);
-
-            // Draw a connecting line between consecutive samples
-            if col > 0 && scope_len > 1 {
-                let prev_idx = ((col - 1) * scope_len / grid_width as usize).min(scope_len - 1);
-                let prev_sample = scope[prev_idx].clamp(-1.0, 1.0);
-                let prev_pixel_y = center_y as f32 - (prev_sample * half_height);
-                let prev_y = (prev_pixel_y as u16).clamp(grid_y, grid_y + grid_height - 1);
-                let (y_min, y_max) = if y < prev_y { (y, prev_y) } else { (prev_y, y) };
-                for fill_y in y_min..=y_max {
-                    if fill_y >= grid_y && fill_y < grid_y + grid_height {
-                        buf.set_cell(grid_x + col as u16, fill_y, '\u{2588}', green);
+        if self.high_res && scope_len > 0 {
+            let mut canvas = BrailleCanvas::new(grid_width, grid_height);
+            let max_dot = canvas.dot_height().saturating_sub(1);
+            let dot_y = |sample: f32| ((1.0 - sample.clamp(-1.0, 1.0)) / 2.0 * max_dot as f32).round() as usize;
+            let mut prev = None;
+            for x in 0..canvas.dot_width() {
+                let idx = (x * scope_len / canvas.dot_width()).min(scope_len - 1);
+                let y = dot_y(scope[idx]);
+                canvas.vline(x, prev.unwrap_or(y), y);
+                prev = Some(y);
+            }
+            canvas.render(buf, grid_x, grid_y, |_, _| green);
+        } else {
+            for col in 0..grid_width as usize {
+                let sample_idx = if scope_len > 0 {
+                    (col * scope_len / grid_width as usize).min(scope_len - 1)
+                } else {
+                    continue;
+                };
+                let sample = scope[sample_idx].clamp(-1.0, 1.0);
+                let pixel_y = center_y as f32 - (sample * half_height);
+                let y = (pixel_y as u16).clamp(grid_y, grid_y + grid_height - 1);
+                buf.set_cell(grid_x + col as u16, y, '\u{2588}', green);
+
+                // Draw a connecting line between consecutive samples
+                if col > 0 && scope_len > 1 {
+                    let prev_idx = ((col - 1) * scope_len / grid_width as usize).min(scope_len - 1);
+                    let prev_sample = scope[prev_idx].clamp(-1.0, 1.0);
+                    let prev_pixel_y = center_y as f32 - (prev_sample * half_height);
+                    let prev_y = (prev_pixel_y as u16).clamp(grid_y, grid_y + grid_height - 1);
+                    let (y_min, y_max) = if y < prev_y { (y, prev_y) } else { (prev_y, y) };
+                    for fill_y in y_min..=y_max {
+                        if fill_y >= grid_y && fill_y < grid_y + grid_height {
+                            buf.set_cell(grid_x + col as u16, fill_y, '\u{2588}', green);
+                        }
                     }
                 }
             }
@@ -321,7 +398,8 @@ impl WaveformPane {
         let rms_frac = ((rms_db + db_range) / db_range).clamp(0.0, 1.0);
 
         let peak_height = (peak_frac * height as f32) as u16;
-        let rms_height = (rms_frac * height as f32) as u16;
+        let rms_eighths = (rms_frac * height as f32 * 8.0) as u16;
+        let rms_height = if self.high_res { rms_eighths.div_ceil(8) } else { rms_eighths / 8 };
 
         // Split width: RMS bars take most of it, peak indicator on the side
         let rms_width = width.saturating_sub(2);
@@ -332,8 +410,9 @@ impl WaveformPane {
             let frac = (dy + 1) as f32 / height as f32;
             let color = waveform_color(frac);
             let style = Style::new().fg(color);
+            let ch = if self.high_res { bar_top((rms_eighths - dy * 8).min(8) as u8) } else { WAVEFORM_CHARS[7] };
             for bx in 0..rms_width {
-                buf.set_cell(x + bx, row, WAVEFORM_CHARS[7], style);
+                buf.set_cell(x + bx, row, ch, style);
             }
         }
 
@@ -398,6 +477,10 @@ impl Pane for WaveformPane {
                 self.mode = self.mode.next();
                 Action::None
             }
+            ActionId::Waveform(WaveformActionId::ToggleHighRes) => {
+                self.high_res = !self.high_res;
+                Action::None
+            }
             _ => Action::None,
         }
     }
@@ -0,0 +1,79 @@
+//! One-line descriptions of synth parameters, shown under the instrument
+//! editor for the selected row.
+//!
+//! Custom synthdefs can describe their own params in their spec; built-in
+//! params are described here by name.
+
+use crate::state::{AppState, SourceType};
+
+/// Built-in param names and what they do. Matched case-insensitively.
+const DESCRIPTIONS: &[(&str, &str)] = &[
+    ("attack", "Time to rise from silence to full level after a note starts"),
+    ("decay", "Time to fall from full level to the sustain level"),
+    ("sustain", "Level held while a note is down, after the decay"),
+    ("release", "Time to fade to silence after a note is let go"),
+    ("cutoff", "Frequency above which the filter starts removing sound"),
+    ("resonance", "Emphasis at the cutoff; high values ring or whistle"),
+    ("freq", "Frequency the sound or effect is centred on"),
+    ("rate", "Speed of the modulation or repeats, in cycles per second"),
+    ("depth", "How far the modulation moves its target"),
+    ("level", "Output volume"),
+    ("amp", "Output volume"),
+    ("gain", "Volume boost or cut"),
+    ("drive", "Amount of saturation; adds harmonics and grit"),
+    ("mix", "Balance between the dry and processed sound"),
+    ("wet", "Level of the processed sound"),
+    ("room", "Size of the simulated space"),
+    ("damp", "How quickly high frequencies die away in the tail"),
+    ("time", "Delay between repeats"),
+    ("delay", "Delay between repeats"),
+    ("feedback", "How much of the output is fed back in; more means longer tails"),
+    ("threshold", "Level above which the effect starts acting"),
+    ("ratio", "How strongly levels above the threshold are reduced"),
+    ("crossover", "Frequency where one band hands over to the next"),
+    ("makeup", "Gain added back after compression"),
+    ("upward", "How much quiet material below the threshold is raised"),
+    ("detune", "Pitch spread between voices; thickens the sound"),
+    ("spread", "Stereo spread between voices"),
+    ("width", "Pulse width or stereo width; changes the tone's hollowness"),
+    ("pan", "Position in the stereo field"),
+    ("bits", "Bit depth; lower values sound crunchier"),
+    ("index", "Modulation amount for FM; higher values are brighter and harsher"),
+];
+
+/// Description of a built-in param
+pub fn builtin(name: &str) -> Option<&'static str> {
+    let name = name.to_ascii_lowercase();
+    DESCRIPTIONS.iter()
+        .find(|(n, _)| *n == name)
+        .or_else(|| DESCRIPTIONS.iter().find(|(n, _)| name.contains(n)))
+        .map(|(_, desc)| *desc)
+}
+
+/// Description of a param on an instrument with `source`, preferring the
+/// custom synthdef's own text
+pub fn describe(name: &str, source: SourceType, state: &AppState) -> Option<String> {
+    if let SourceType::Custom(id) = source {
+        let custom = state.session.custom_synthdefs.synthdefs.iter()
+            .find(|s| s.id == id)
+            .and_then(|s| s.params.iter().find(|p| p.name == name))
+            .and_then(|p| p.description.clone());
+        if custom.is_some() {
+            return custom;
+        }
+    }
+    builtin(name).map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_then_partial_names_match() {
+        assert_eq!(builtin("Attack"), builtin("attack"));
+        assert!(builtin("cutoff").unwrap().contains("filter"));
+        assert_eq!(builtin("lfo_rate"), builtin("rate"));
+        assert_eq!(builtin("xyzzy"), None);
+    }
+}
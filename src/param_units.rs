@@ -0,0 +1,139 @@
+//! Display units for synth parameters.
+//!
+//! Built-in sources and effects declare each param's unit in core
+//! (`Param::unit`). Custom synthdefs and VSTs may leave it unset, and rows
+//! that aren't params (automation lanes, fixed LFO and envelope rows) have
+//! none; for those the unit is inferred from the name and range. Values are
+//! formatted for display and frequencies are stepped on a log scale, so
+//! each press moves the same musical distance at 80 Hz as at 8 kHz.
+
+use crate::state::{Param, ParamUnit};
+
+/// How a parameter's stored value is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    /// Frequency in Hz, stepped logarithmically
+    Hz,
+    /// Linear gain, shown in dB
+    Gain,
+    /// Already in dB
+    Db,
+    /// Time in seconds, shown in ms below a second
+    Time,
+    /// Fraction of 0..=1, shown as a percentage
+    Percent,
+    Plain,
+}
+
+impl From<ParamUnit> for Unit {
+    fn from(unit: ParamUnit) -> Self {
+        match unit {
+            ParamUnit::Hz => Unit::Hz,
+            ParamUnit::Gain => Unit::Gain,
+            ParamUnit::Db => Unit::Db,
+            ParamUnit::Seconds => Unit::Time,
+            ParamUnit::Percent => Unit::Percent,
+            ParamUnit::None => Unit::Plain,
+        }
+    }
+}
+
+impl Unit {
+    /// Unit of a param: the one core declares, else inferred from its name
+    pub fn of(param: &Param) -> Self {
+        param.unit
+            .map(Unit::from)
+            .unwrap_or_else(|| Unit::for_param(&param.name, param.min, param.max))
+    }
+
+    /// Infer the unit from a name and range, for params that don't declare one
+    pub fn for_param(name: &str, min: f32, max: f32) -> Self {
+        let name = name.to_ascii_lowercase();
+        let has = |words: &[&str]| words.iter().any(|w| name.contains(w));
+        if has(&["freq", "cutoff", "hz", "lpf", "hpf"]) {
+            Unit::Hz
+        } else if has(&["db", "thresh"]) || (has(&["gain"]) && min < 0.0) {
+            Unit::Db
+        } else if has(&["level", "amp", "volume", "gain"]) && min >= 0.0 {
+            Unit::Gain
+        } else if has(&["attack", "decay", "release", "time", "delay"]) && min >= 0.0 {
+            Unit::Time
+        } else if has(&["mix", "wet", "depth", "amount", "feedback", "sustain", "width"])
+            && min >= 0.0
+            && max <= 1.0
+        {
+            Unit::Percent
+        } else {
+            Unit::Plain
+        }
+    }
+
+    pub fn format(self, value: f32) -> String {
+        match self {
+            Unit::Hz if value >= 1000.0 => format!("{:.2}kHz", value / 1000.0),
+            Unit::Hz if value >= 100.0 => format!("{:.0}Hz", value),
+            Unit::Hz => format!("{:.1}Hz", value),
+            Unit::Gain if value <= 0.0 => "-inf dB".to_string(),
+            Unit::Gain => format!("{:.1}dB", 20.0 * value.log10()),
+            Unit::Db => format!("{:.1}dB", value),
+            Unit::Time if value < 1.0 => format!("{:.0}ms", value * 1000.0),
+            Unit::Time => format!("{:.2}s", value),
+            Unit::Percent => format!("{:.0}%", value * 100.0),
+            Unit::Plain => format!("{:.2}", value),
+        }
+    }
+
+    /// Step `value` by `fraction` of the range, on a log scale for frequencies
+    pub fn step(self, value: f32, min: f32, max: f32, increase: bool, fraction: f32) -> f32 {
+        let stepped = if self == Unit::Hz && min > 0.0 {
+            let ratio = (max / min).powf(fraction);
+            if increase { value.max(min) * ratio } else { value / ratio }
+        } else {
+            let delta = (max - min) * fraction;
+            if increase { value + delta } else { value - delta }
+        };
+        stepped.clamp(min, max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn units_inferred_from_names() {
+        assert_eq!(Unit::for_param("cutoff", 20.0, 20000.0), Unit::Hz);
+        assert_eq!(Unit::for_param("level", 0.0, 1.0), Unit::Gain);
+        assert_eq!(Unit::for_param("gain", -24.0, 24.0), Unit::Db);
+        assert_eq!(Unit::for_param("Attack", 0.0, 5.0), Unit::Time);
+        assert_eq!(Unit::for_param("mix", 0.0, 1.0), Unit::Percent);
+        assert_eq!(Unit::for_param("ratio", 1.0, 20.0), Unit::Plain);
+    }
+
+    #[test]
+    fn declared_units_map_across() {
+        assert_eq!(Unit::from(ParamUnit::Hz), Unit::Hz);
+        assert_eq!(Unit::from(ParamUnit::Seconds), Unit::Time);
+        assert_eq!(Unit::from(ParamUnit::None), Unit::Plain);
+    }
+
+    #[test]
+    fn values_formatted_with_units() {
+        assert_eq!(Unit::Hz.format(2400.0), "2.40kHz");
+        assert_eq!(Unit::Hz.format(440.0), "440Hz");
+        assert_eq!(Unit::Gain.format(1.0), "0.0dB");
+        assert_eq!(Unit::Gain.format(0.0), "-inf dB");
+        assert_eq!(Unit::Time.format(0.25), "250ms");
+        assert_eq!(Unit::Time.format(1.5), "1.50s");
+        assert_eq!(Unit::Percent.format(0.5), "50%");
+    }
+
+    #[test]
+    fn frequency_steps_are_proportional() {
+        let low = Unit::Hz.step(100.0, 20.0, 20000.0, true, 0.05);
+        let high = Unit::Hz.step(1000.0, 20.0, 20000.0, true, 0.05);
+        assert!((high / 1000.0 - low / 100.0).abs() < 1e-4);
+        assert_eq!(Unit::Hz.step(20000.0, 20.0, 20000.0, true, 0.05), 20000.0);
+        assert!((Unit::Plain.step(0.5, 0.0, 1.0, false, 0.1) - 0.4).abs() < 1e-6);
+    }
+}
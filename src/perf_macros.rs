@@ -0,0 +1,133 @@
+//! Drum sequencer performance macros: short gestures played over time
+//! while a pattern runs.
+//!
+//! Filter sweeps are sent as automation values, so they are written into
+//! the cutoff lane when automation recording is armed. main.rs polls the
+//! running macro and dispatches its actions.
+
+use std::time::{Duration, Instant};
+
+use crate::action::{Action, AutomationAction, InstrumentAction};
+use crate::state::automation::AutomationTarget;
+use crate::state::InstrumentId;
+
+/// Hits per beat in a roll (32nd notes)
+const ROLL_HITS_PER_BEAT: u32 = 8;
+/// Beats a sweep lasts (one bar of 4/4)
+const SWEEP_BEATS: f64 = 4.0;
+/// Lowest point of a sweep, as normalized cutoff
+const SWEEP_FLOOR: f32 = 0.1;
+/// Gap between sweep values, so lanes are not flooded with points
+const SWEEP_INTERVAL: Duration = Duration::from_millis(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    /// Repeat a pad for one beat
+    Roll { pad: usize },
+    /// Close the filter and open it again over one bar, ending fully open
+    FilterSweep { instrument_id: InstrumentId },
+}
+
+pub struct PerformanceMacro {
+    kind: Kind,
+    started: Instant,
+    secs_per_beat: f64,
+    /// Roll hits fired so far
+    hits: u32,
+    last_sweep: Option<Instant>,
+    done: bool,
+}
+
+impl PerformanceMacro {
+    fn new(kind: Kind, bpm: f32, now: Instant) -> Self {
+        Self {
+            kind,
+            started: now,
+            secs_per_beat: 60.0 / bpm.max(1.0) as f64,
+            hits: 0,
+            last_sweep: None,
+            done: false,
+        }
+    }
+
+    pub fn roll(pad: usize, bpm: f32, now: Instant) -> Self {
+        Self::new(Kind::Roll { pad }, bpm, now)
+    }
+
+    pub fn filter_sweep(instrument_id: InstrumentId, bpm: f32, now: Instant) -> Self {
+        Self::new(Kind::FilterSweep { instrument_id }, bpm, now)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Actions due by `now`
+    pub fn poll(&mut self, now: Instant) -> Vec<Action> {
+        if self.done {
+            return Vec::new();
+        }
+        let beats = now.saturating_duration_since(self.started).as_secs_f64() / self.secs_per_beat;
+        match self.kind {
+            Kind::Roll { pad } => {
+                let due = ((beats * ROLL_HITS_PER_BEAT as f64) as u32 + 1).min(ROLL_HITS_PER_BEAT);
+                let actions = (self.hits..due)
+                    .map(|_| Action::Instrument(InstrumentAction::PlayDrumPad(pad)))
+                    .collect();
+                self.hits = due;
+                self.done = self.hits >= ROLL_HITS_PER_BEAT;
+                actions
+            }
+            Kind::FilterSweep { instrument_id } => {
+                let progress = (beats / SWEEP_BEATS).min(1.0);
+                self.done = progress >= 1.0;
+                if !self.done && self.last_sweep.map_or(false, |t| now.duration_since(t) < SWEEP_INTERVAL) {
+                    return Vec::new();
+                }
+                self.last_sweep = Some(now);
+                let value = sweep_value(progress);
+                vec![Action::Automation(AutomationAction::RecordValue(
+                    AutomationTarget::FilterCutoff(instrument_id),
+                    value,
+                ))]
+            }
+        }
+    }
+}
+
+/// Normalized cutoff at `progress` (0..=1) through a sweep
+fn sweep_value(progress: f64) -> f32 {
+    let dip = (progress * std::f64::consts::PI).sin() as f32;
+    1.0 - dip * (1.0 - SWEEP_FLOOR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roll_fires_all_hits_within_a_beat() {
+        let start = Instant::now();
+        // 120 BPM: a beat is half a second
+        let mut roll = PerformanceMacro::roll(3, 120.0, start);
+        assert_eq!(roll.poll(start).len(), 1);
+        assert_eq!(roll.poll(start + Duration::from_millis(250)).len(), 4);
+        assert_eq!(roll.poll(start + Duration::from_secs(2)).len(), 3);
+        assert!(roll.is_done());
+    }
+
+    #[test]
+    fn sweep_dips_and_ends_open() {
+        assert_eq!(sweep_value(0.0), 1.0);
+        assert!((sweep_value(0.5) - SWEEP_FLOOR).abs() < 1e-6);
+        assert!((sweep_value(1.0) - 1.0).abs() < 1e-6);
+
+        let start = Instant::now();
+        let mut sweep = PerformanceMacro::filter_sweep(1, 120.0, start);
+        assert_eq!(sweep.poll(start).len(), 1);
+        // Rate limited between values
+        assert!(sweep.poll(start + Duration::from_millis(10)).is_empty());
+        assert_eq!(sweep.poll(start + Duration::from_secs(3)).len(), 1);
+        assert!(sweep.is_done());
+    }
+}
@@ -0,0 +1,225 @@
+//! Time spent per project, and the session timer in the status bar.
+//!
+//! Totals are kept in `~/.config/imbolc/practice.toml`, keyed by project
+//! path. Time counts as playing while the transport runs, and as editing
+//! while there has been input within the last minute.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// Input gap after which the user is considered away
+const IDLE_AFTER: Duration = Duration::from_secs(60);
+/// How often accumulated time is written out
+const SAVE_EVERY: Duration = Duration::from_secs(60);
+
+const FOCUS_MINUTES: u64 = 25;
+const BREAK_MINUTES: u64 = 5;
+
+/// What the status bar timer shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionTimer {
+    #[default]
+    Off,
+    /// Time since launch and the project's total
+    Elapsed,
+    /// 25 minute focus / 5 minute break cycle
+    Pomodoro,
+}
+
+impl SessionTimer {
+    pub const ALL: [SessionTimer; 3] = [SessionTimer::Off, SessionTimer::Elapsed, SessionTimer::Pomodoro];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SessionTimer::Off => "Off",
+            SessionTimer::Elapsed => "Elapsed",
+            SessionTimer::Pomodoro => "Pomodoro",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectTime {
+    pub playing_secs: u64,
+    pub editing_secs: u64,
+}
+
+impl ProjectTime {
+    pub fn total_secs(&self) -> u64 {
+        self.playing_secs + self.editing_secs
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Store {
+    #[serde(default)]
+    projects: BTreeMap<String, ProjectTime>,
+}
+
+pub struct PracticeTracker {
+    store: Store,
+    started: Instant,
+    last_tick: Instant,
+    last_input: Instant,
+    last_save: Instant,
+    /// Sub-second remainders not yet credited to the store
+    pending_playing: Duration,
+    pending_editing: Duration,
+    dirty: bool,
+}
+
+impl PracticeTracker {
+    pub fn load() -> Self {
+        let store = store_path()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|s| toml::from_str(&s).ok())
+            .unwrap_or_default();
+        Self::with_store(store, Instant::now())
+    }
+
+    fn with_store(store: Store, now: Instant) -> Self {
+        Self {
+            store,
+            started: now,
+            last_tick: now,
+            last_input: now,
+            last_save: now,
+            pending_playing: Duration::ZERO,
+            pending_editing: Duration::ZERO,
+            dirty: false,
+        }
+    }
+
+    pub fn note_input(&mut self, now: Instant) {
+        self.last_input = now;
+    }
+
+    /// Credit the time since the last tick to `project`
+    pub fn tick(&mut self, now: Instant, playing: bool, project: Option<&Path>) {
+        let elapsed = now.saturating_duration_since(self.last_tick);
+        self.last_tick = now;
+        let Some(project) = project else { return };
+
+        let entry = self.store.projects.entry(project.display().to_string()).or_default();
+        let (pending, total) = if playing {
+            (&mut self.pending_playing, &mut entry.playing_secs)
+        } else if now.saturating_duration_since(self.last_input) < IDLE_AFTER {
+            (&mut self.pending_editing, &mut entry.editing_secs)
+        } else {
+            return;
+        };
+        *pending += elapsed;
+        let whole = pending.as_secs();
+        if whole > 0 {
+            *total += whole;
+            *pending -= Duration::from_secs(whole);
+            self.dirty = true;
+        }
+
+        if self.dirty && now.saturating_duration_since(self.last_save) >= SAVE_EVERY {
+            self.save();
+            self.last_save = now;
+        }
+    }
+
+    pub fn project_time(&self, project: &Path) -> ProjectTime {
+        self.store.projects.get(&project.display().to_string()).copied().unwrap_or_default()
+    }
+
+    pub fn save(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        let Some(path) = store_path() else { return };
+        let result = toml::to_string_pretty(&self.store)
+            .map_err(|e| e.to_string())
+            .and_then(|text| {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                }
+                std::fs::write(&path, text).map_err(|e| e.to_string())
+            });
+        match result {
+            Ok(()) => self.dirty = false,
+            Err(e) => log::error!("practice: could not save: {}", e),
+        }
+    }
+
+    /// Status bar text for `timer`, if it is on
+    pub fn status_text(&self, timer: SessionTimer, now: Instant, project: Option<&Path>) -> Option<String> {
+        let session = now.saturating_duration_since(self.started).as_secs();
+        match timer {
+            SessionTimer::Off => None,
+            SessionTimer::Elapsed => {
+                let mut text = format!(" Session {} ", format_duration(session));
+                if let Some(project) = project {
+                    let total = self.project_time(project).total_secs();
+                    text.push_str(&format!("\u{00b7} Project {} ", format_duration(total)));
+                }
+                Some(text)
+            }
+            SessionTimer::Pomodoro => Some(pomodoro_text(session)),
+        }
+    }
+}
+
+fn store_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("imbolc").join("practice.toml"))
+}
+
+/// `h:mm` once past an hour, `m:ss` before
+fn format_duration(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}:{:02}", secs / 3600, (secs / 60) % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+fn pomodoro_text(session_secs: u64) -> String {
+    let cycle = (FOCUS_MINUTES + BREAK_MINUTES) * 60;
+    let into = session_secs % cycle;
+    if into < FOCUS_MINUTES * 60 {
+        format!(" Focus {} ", format_duration(FOCUS_MINUTES * 60 - into))
+    } else {
+        format!(" Break {} ", format_duration(cycle - into))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn idle_time_is_not_counted() {
+        let start = Instant::now();
+        let mut tracker = PracticeTracker::with_store(Store::default(), start);
+        let project = Path::new("/songs/a.imbolc");
+
+        tracker.tick(start + Duration::from_secs(30), false, Some(project));
+        tracker.tick(start + Duration::from_secs(40), true, Some(project));
+        // No input for over a minute and not playing: away
+        tracker.tick(start + Duration::from_secs(200), false, Some(project));
+        assert_eq!(tracker.project_time(project), ProjectTime { playing_secs: 10, editing_secs: 30 });
+    }
+
+    #[test]
+    fn untitled_projects_are_not_tracked() {
+        let start = Instant::now();
+        let mut tracker = PracticeTracker::with_store(Store::default(), start);
+        tracker.tick(start + Duration::from_secs(5), true, None);
+        assert!(tracker.store.projects.is_empty());
+    }
+
+    #[test]
+    fn pomodoro_alternates_focus_and_break() {
+        assert_eq!(pomodoro_text(0), " Focus 25:00 ");
+        assert_eq!(pomodoro_text(25 * 60 + 1), " Break 4:59 ");
+        assert_eq!(pomodoro_text(30 * 60), " Focus 25:00 ");
+        assert_eq!(format_duration(3 * 3600 + 65), "3:01");
+    }
+}
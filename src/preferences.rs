@@ -0,0 +1,329 @@
+//! Application preferences, kept in the `[preferences]` table of
+//! `~/.config/imbolc/config.toml`.
+//!
+//! Other tables in the file belong to the core config and are preserved
+//! when preferences are saved.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::av_sync::VisualOffset;
+use crate::export_format::ExportFormat;
+use crate::export_region::RegionSettings;
+use crate::panes::{FileBrowserPane, PianoRollPane, SaveAsPane, SequencerPane, TrackPane, TrackerPane, WaveformPane};
+use crate::practice::SessionTimer;
+use crate::sample_import::ResampleQuality;
+use crate::state::{AppState, KeyboardLayout};
+use crate::ui::PaneManager;
+use crate::velocity::{KeyVelocityMode, VelocityCurve};
+use crate::workspace::{self, Workspace};
+
+const TABLE: &str = "preferences";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    /// Note-key layout for the piano/pad performance layers
+    pub keyboard_layout: String,
+    /// Velocity of notes played on the computer keyboard
+    pub key_velocity_mode: KeyVelocityMode,
+    pub key_velocity: u8,
+    pub key_accent_velocity: u8,
+    /// Curve applied to incoming MIDI note velocities
+    pub midi_velocity_curve: VelocityCurve,
+    /// The panic key also sends all-notes-off to the MIDI output
+    pub panic_midi_reset: bool,
+    /// Minutes between autosaves of a project that has a path; 0 disables
+    pub autosave_minutes: u32,
+    /// Session timer shown in the status bar
+    pub session_timer: SessionTimer,
+    pub samples_dir: Option<PathBuf>,
+    /// More directories for the sample library index, besides `samples_dir`
+    pub library_dirs: Vec<PathBuf>,
+    pub projects_dir: Option<PathBuf>,
+    pub impulse_responses_dir: Option<PathBuf>,
+    /// How imported samples are converted to the server's rate
+    pub resample_quality: ResampleQuality,
+    /// Bit depth and dither last chosen in the export dialog
+    pub export_format: ExportFormat,
+    /// Pre-roll and tail last chosen for region exports
+    pub export_region: RegionSettings,
+    /// Start scsynth and connect on launch
+    pub auto_start_server: bool,
+    pub server_address: String,
+    /// Braille/eighth-block visualizations, or whole cells
+    pub graphics: GraphicsMode,
+    /// Screen redraws per second
+    pub frame_rate: u32,
+    /// Pause visualizations while the terminal is unfocused or idle
+    pub low_power: bool,
+    /// Draw the playhead this far ahead of the transport, for slow terminals
+    pub visual_offset_ms: u32,
+    /// The first-run tutorial was finished or skipped
+    pub tutorial_seen: bool,
+    /// Layouts recalled with Alt+1 to Alt+3
+    pub workspaces: Vec<Workspace>,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            keyboard_layout: "auto".to_string(),
+            key_velocity_mode: KeyVelocityMode::Fixed,
+            key_velocity: 100,
+            key_accent_velocity: 127,
+            midi_velocity_curve: VelocityCurve::Linear,
+            panic_midi_reset: true,
+            autosave_minutes: 0,
+            session_timer: SessionTimer::Off,
+            samples_dir: None,
+            library_dirs: Vec::new(),
+            projects_dir: None,
+            impulse_responses_dir: None,
+            resample_quality: ResampleQuality::Good,
+            export_format: ExportFormat::default(),
+            export_region: RegionSettings::default(),
+            auto_start_server: true,
+            server_address: "127.0.0.1:57110".to_string(),
+            graphics: GraphicsMode::Auto,
+            frame_rate: 60,
+            low_power: false,
+            visual_offset_ms: 0,
+            tutorial_seen: false,
+            workspaces: workspace::defaults(),
+        }
+    }
+}
+
+/// Layout names in picker order; "auto" asks the system
+pub const LAYOUT_NAMES: [&str; 6] = ["auto", "qwerty", "colemak", "dvorak", "azerty", "qwertz"];
+
+pub fn layout_from_name(name: &str) -> KeyboardLayout {
+    match name {
+        "auto" => detect_layout().unwrap_or(KeyboardLayout::Qwerty),
+        "colemak" => KeyboardLayout::Colemak,
+        "dvorak" => KeyboardLayout::Dvorak,
+        "azerty" => KeyboardLayout::Azerty,
+        "qwertz" => KeyboardLayout::Qwertz,
+        _ => KeyboardLayout::Qwerty,
+    }
+}
+
+pub fn layout_name(layout: KeyboardLayout) -> &'static str {
+    match layout {
+        KeyboardLayout::Qwerty => "qwerty",
+        KeyboardLayout::Colemak => "colemak",
+        KeyboardLayout::Dvorak => "dvorak",
+        KeyboardLayout::Azerty => "azerty",
+        KeyboardLayout::Qwertz => "qwertz",
+    }
+}
+
+/// Map an XKB layout/variant pair to a note-key layout
+fn layout_from_xkb(layout: &str, variant: &str) -> Option<KeyboardLayout> {
+    // Only the first layout counts when several are configured
+    let layout = layout.split(',').next()?.trim();
+    let variant = variant.split(',').next().unwrap_or("").trim();
+    match (layout, variant) {
+        (_, "colemak") => Some(KeyboardLayout::Colemak),
+        (_, "dvorak") | ("dvorak", _) => Some(KeyboardLayout::Dvorak),
+        ("fr" | "be", _) => Some(KeyboardLayout::Azerty),
+        ("de" | "at" | "ch" | "cz" | "hu" | "sk" | "si" | "hr", _) => Some(KeyboardLayout::Qwertz),
+        ("", _) => None,
+        _ => Some(KeyboardLayout::Qwerty),
+    }
+}
+
+/// Best-effort system layout detection from XKB settings
+pub fn detect_layout() -> Option<KeyboardLayout> {
+    let env = |key: &str| std::env::var(key).ok();
+    if let Some(layout) = env("XKB_DEFAULT_LAYOUT") {
+        return layout_from_xkb(&layout, &env("XKB_DEFAULT_VARIANT").unwrap_or_default());
+    }
+    // Debian-style console/X11 keyboard config
+    let text = std::fs::read_to_string("/etc/default/keyboard").ok()?;
+    let value = |key: &str| {
+        text.lines()
+            .find_map(|l| l.trim().strip_prefix(key)?.strip_prefix('='))
+            .map(|v| v.trim().trim_matches('"').to_string())
+            .unwrap_or_default()
+    };
+    layout_from_xkb(&value("XKBLAYOUT"), &value("XKBVARIANT"))
+}
+
+/// How finely waveforms, scopes and meters are drawn
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphicsMode {
+    /// High resolution when the terminal looks capable
+    #[default]
+    Auto,
+    Braille,
+    Blocks,
+}
+
+impl GraphicsMode {
+    pub const ALL: [GraphicsMode; 3] = [GraphicsMode::Auto, GraphicsMode::Braille, GraphicsMode::Blocks];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GraphicsMode::Auto => "auto",
+            GraphicsMode::Braille => "braille",
+            GraphicsMode::Blocks => "blocks",
+        }
+    }
+
+    pub fn high_res(self) -> bool {
+        match self {
+            GraphicsMode::Auto => terminal_supports_braille(),
+            GraphicsMode::Braille => true,
+            GraphicsMode::Blocks => false,
+        }
+    }
+}
+
+/// Best guess at whether the terminal can show braille: a UTF-8 locale
+/// and not the Linux console, whose font lacks the glyphs
+fn terminal_supports_braille() -> bool {
+    let env = |key: &str| std::env::var(key).unwrap_or_default();
+    let term = env("TERM");
+    if matches!(term.as_str(), "linux" | "dumb" | "vt100" | "vt220") {
+        return false;
+    }
+    let locale = [env("LC_ALL"), env("LC_CTYPE"), env("LANG")]
+        .into_iter()
+        .find(|v| !v.is_empty())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    locale.contains("utf-8") || locale.contains("utf8")
+}
+
+impl Preferences {
+    /// Load from config.toml. Without a `[preferences]` table, start from
+    /// defaults and the layout the core config already chose.
+    pub fn load(current_layout: KeyboardLayout) -> Self {
+        let table = config_path()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|s| s.parse::<toml::Table>().ok())
+            .unwrap_or_default();
+        Self::from_table(&table).unwrap_or_else(|| Self {
+            keyboard_layout: layout_name(current_layout).to_string(),
+            ..Self::default()
+        })
+    }
+
+    fn from_table(table: &toml::Table) -> Option<Self> {
+        table.get(TABLE)?.clone().try_into().ok()
+    }
+
+    /// Replace the `[preferences]` table, leaving the rest untouched
+    fn merge_into(&self, table: &mut toml::Table) -> Result<(), String> {
+        let value = toml::Value::try_from(self).map_err(|e| e.to_string())?;
+        table.insert(TABLE.to_string(), value);
+        Ok(())
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = config_path().ok_or("no config directory")?;
+        let mut table = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|s| s.parse::<toml::Table>().ok())
+            .unwrap_or_default();
+        self.merge_into(&mut table)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let text = toml::to_string_pretty(&table).map_err(|e| e.to_string())?;
+        std::fs::write(&path, text).map_err(|e| e.to_string())
+    }
+
+    pub fn keyboard_layout(&self) -> KeyboardLayout {
+        layout_from_name(&self.keyboard_layout)
+    }
+
+    /// Directories indexed by the sample library
+    pub fn sample_library_roots(&self) -> Vec<PathBuf> {
+        self.samples_dir.iter().chain(&self.library_dirs).cloned().collect()
+    }
+
+    /// Velocity for a note played with the (QWERTY-translated) key `key`
+    pub fn key_velocity_for(&self, key: char) -> u8 {
+        crate::velocity::key_velocity(self.key_velocity_mode, self.key_velocity, self.key_accent_velocity, key)
+    }
+
+    /// Push preference values out to the state and panes that use them
+    pub(crate) fn apply(&self, state: &mut AppState, panes: &mut PaneManager, autosave_interval: &mut Option<Duration>) {
+        state.keyboard_layout = self.keyboard_layout();
+        *autosave_interval = match self.autosave_minutes {
+            0 => None,
+            m => Some(Duration::from_secs(m as u64 * 60)),
+        };
+        if let Some(fb) = panes.get_pane_mut::<FileBrowserPane>("file_browser") {
+            fb.set_default_dirs(self.samples_dir.clone(), self.projects_dir.clone(), self.impulse_responses_dir.clone());
+        }
+        if let Some(sa) = panes.get_pane_mut::<SaveAsPane>("save_as") {
+            sa.set_projects_dir(self.projects_dir.clone());
+        }
+        if let Some(wf) = panes.get_pane_mut::<WaveformPane>("waveform") {
+            wf.set_high_res(self.graphics.high_res());
+        }
+        let offset = VisualOffset::new(self.visual_offset_ms);
+        if let Some(pr) = panes.get_pane_mut::<PianoRollPane>("piano_roll") {
+            pr.set_visual_offset(offset);
+        }
+        if let Some(tracker) = panes.get_pane_mut::<TrackerPane>("tracker") {
+            tracker.set_visual_offset(offset);
+        }
+        if let Some(track) = panes.get_pane_mut::<TrackPane>("track") {
+            track.set_visual_offset(offset);
+        }
+        if let Some(seq) = panes.get_pane_mut::<SequencerPane>("sequencer") {
+            seq.set_visual_offset(offset);
+        }
+    }
+}
+
+fn config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("imbolc").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_fields_use_defaults() {
+        let table: toml::Table = "[preferences]\nautosave_minutes = 5\n".parse().unwrap();
+        let prefs = Preferences::from_table(&table).unwrap();
+        assert_eq!(prefs.autosave_minutes, 5);
+        assert_eq!(prefs.server_address, Preferences::default().server_address);
+    }
+
+    #[test]
+    fn saving_keeps_other_tables() {
+        let mut table: toml::Table = "[defaults]\nbpm = 100\n".parse().unwrap();
+        let prefs = Preferences { samples_dir: Some(PathBuf::from("/samples")), ..Preferences::default() };
+        prefs.merge_into(&mut table).unwrap();
+        assert!(table.contains_key("defaults"));
+        assert_eq!(Preferences::from_table(&table), Some(prefs));
+    }
+
+    #[test]
+    fn layout_names_round_trip() {
+        for name in LAYOUT_NAMES.iter().skip(1) {
+            assert_eq!(layout_name(layout_from_name(name)), *name);
+        }
+    }
+
+    #[test]
+    fn xkb_settings_pick_layouts() {
+        assert_eq!(layout_from_xkb("de", ""), Some(KeyboardLayout::Qwertz));
+        assert_eq!(layout_from_xkb("fr,us", ""), Some(KeyboardLayout::Azerty));
+        assert_eq!(layout_from_xkb("us", "colemak"), Some(KeyboardLayout::Colemak));
+        assert_eq!(layout_from_xkb("us", "dvorak,"), Some(KeyboardLayout::Dvorak));
+        assert_eq!(layout_from_xkb("gb", ""), Some(KeyboardLayout::Qwerty));
+        assert_eq!(layout_from_xkb("", ""), None);
+    }
+}
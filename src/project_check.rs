@@ -0,0 +1,183 @@
+//! Project statistics and integrity checks.
+//!
+//! `scan` looks for problems that don't stop a project from loading but
+//! usually aren't intended. Each issue may carry a fix: the action that
+//! resolves it.
+
+use std::path::Path;
+
+use crate::action::{Action, AutomationAction, FileSelectAction, InstrumentAction, MixerAction, PianoRollAction, SessionAction};
+use crate::state::{AppState, SourceType};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Info,
+}
+
+pub struct Issue {
+    pub severity: Severity,
+    pub message: String,
+    /// Short description of `fix`
+    pub fix_label: &'static str,
+    pub fix: Option<Action>,
+}
+
+/// Headline numbers for the project
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Stats {
+    pub instruments: usize,
+    pub notes: usize,
+    pub clips: usize,
+    pub placements: usize,
+    pub automation_lanes: usize,
+    pub automation_points: usize,
+    pub buses: usize,
+    /// End of the last note, in ticks
+    pub length_ticks: u32,
+}
+
+pub fn stats(state: &AppState) -> Stats {
+    let piano_roll = &state.session.piano_roll;
+    let tracks = (0..piano_roll.track_order.len()).filter_map(|i| piano_roll.track_at(i));
+    let mut stats = Stats {
+        instruments: state.instruments.instruments.len(),
+        clips: state.session.arrangement.clips.len(),
+        placements: state.session.arrangement.placements.len(),
+        automation_lanes: state.session.automation.lanes.len(),
+        automation_points: state.session.automation.lanes.iter().map(|l| l.points.len()).sum(),
+        buses: state.session.mixer.buses.len(),
+        ..Stats::default()
+    };
+    for track in tracks {
+        stats.notes += track.notes.len();
+        let end = track.notes.iter().map(|n| n.tick + n.duration).max().unwrap_or(0);
+        stats.length_ticks = stats.length_ticks.max(end);
+    }
+    stats
+}
+
+pub fn scan(state: &AppState) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    check_notes_past_loop(state, &mut issues);
+    check_missing_files(state, &mut issues);
+    check_orphan_lanes(state, &mut issues);
+    check_unused_buses(state, &mut issues);
+    issues
+}
+
+fn check_notes_past_loop(state: &AppState, issues: &mut Vec<Issue>) {
+    let piano_roll = &state.session.piano_roll;
+    if !piano_roll.looping || piano_roll.loop_end == 0 {
+        return;
+    }
+    let loop_end = piano_roll.loop_end;
+    for (track_idx, inst_id) in piano_roll.track_order.iter().enumerate() {
+        let Some(track) = piano_roll.track_at(track_idx) else { continue };
+        let count = track.notes.iter().filter(|n| n.tick >= loop_end).count();
+        if count == 0 {
+            continue;
+        }
+        let name = instrument_name(state, *inst_id);
+        issues.push(Issue {
+            severity: Severity::Info,
+            message: format!("{}: {} note(s) start after the loop end", name, count),
+            fix_label: "delete them",
+            fix: Some(Action::PianoRoll(PianoRollAction::DeleteNotesInRegion {
+                track: track_idx,
+                start_tick: loop_end,
+                end_tick: u32::MAX,
+                start_pitch: 0,
+                end_pitch: 127,
+            })),
+        });
+    }
+}
+
+fn check_missing_files(state: &AppState, issues: &mut Vec<Issue>) {
+    for inst in &state.instruments.instruments {
+        let sample_path = inst.sampler_config.as_ref().and_then(|c| c.sample_path.as_deref());
+        if let Some(path) = sample_path.filter(|p| !p.exists()) {
+            issues.push(Issue {
+                severity: Severity::Warning,
+                message: format!("{}: sample not found: {}", inst.name, file_name(path)),
+                fix_label: "locate sample",
+                fix: Some(Action::Session(SessionAction::OpenFileBrowser(FileSelectAction::LoadPitchedSample(inst.id)))),
+            });
+        }
+        if let SourceType::Vst(plugin_id) = inst.source {
+            let missing = match state.session.vst_plugins.get(plugin_id) {
+                Some(plugin) => !plugin.plugin_path.exists(),
+                None => true,
+            };
+            if missing {
+                issues.push(Issue {
+                    severity: Severity::Warning,
+                    message: format!("{}: VST plugin is missing", inst.name),
+                    fix_label: "remove instrument",
+                    fix: Some(Action::Instrument(InstrumentAction::Delete(inst.id))),
+                });
+            }
+        }
+    }
+}
+
+fn check_orphan_lanes(state: &AppState, issues: &mut Vec<Issue>) {
+    for lane in &state.session.automation.lanes {
+        let Some(inst_id) = lane.target.instrument_id() else { continue };
+        if state.instruments.instrument(inst_id).is_none() {
+            issues.push(Issue {
+                severity: Severity::Warning,
+                message: format!("Automation lane {} targets a deleted instrument", lane.target.name()),
+                fix_label: "remove lane",
+                fix: Some(Action::Automation(AutomationAction::RemoveLane(lane.id))),
+            });
+        }
+    }
+}
+
+fn check_unused_buses(state: &AppState, issues: &mut Vec<Issue>) {
+    for bus in &state.session.mixer.buses {
+        let used = state.instruments.instruments.iter()
+            .flat_map(|inst| inst.sends.iter())
+            .any(|send| send.bus_id == bus.id && send.enabled && send.level > 0.0);
+        if !used {
+            issues.push(Issue {
+                severity: Severity::Info,
+                message: format!("Bus {} ({}) receives no sends", bus.id, bus.name),
+                fix_label: "remove bus",
+                fix: Some(Action::Mixer(MixerAction::RemoveBus(bus.id))),
+            });
+        }
+    }
+}
+
+fn instrument_name(state: &AppState, id: crate::state::InstrumentId) -> String {
+    state.instruments.instrument(id)
+        .map(|i| i.name.clone())
+        .unwrap_or_else(|| format!("#{}", id))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_project_is_clean() {
+        let state = AppState::new();
+        assert_eq!(stats(&state).notes, 0);
+        assert!(scan(&state).iter().all(|i| i.severity != Severity::Warning));
+    }
+
+    #[test]
+    fn file_name_falls_back_to_full_path() {
+        assert_eq!(file_name(Path::new("/samples/kick.wav")), "kick.wav");
+        assert_eq!(file_name(Path::new("/")), "/");
+    }
+}
@@ -0,0 +1,368 @@
+//! Project export and import as JSON, alongside the SQLite project files.
+//!
+//! The schema is defined here rather than by serializing the state, so it
+//! stays stable as the state changes and reads well in diffs. Keys are
+//! written in a fixed order and params are sorted by name.
+//!
+//! It covers tempo, time signature, instruments with their source params,
+//! mixer levels, routing and sends, and piano roll notes. Effects,
+//! automation, drum patterns and samples are only kept in SQLite.
+//! Importing builds those parts into a fresh project through dispatched
+//! actions, one instrument at a time so each one's settings can name the
+//! id it was given; instruments whose source isn't built in (custom
+//! synthdefs, VSTs) are skipped.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, InstrumentAction, InstrumentUpdate, MixerAction, PianoRollAction, SessionAction};
+use crate::state::{AppState, InstrumentId, MixerSelection, OutputTarget, ParamValue, SourceType};
+
+/// Value of the `format` key
+pub const FORMAT: &str = "imbolc-project";
+/// Schema version; bumped on incompatible changes
+pub const VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectJson {
+    pub format: String,
+    pub version: u32,
+    pub bpm: f64,
+    pub time_signature: [u32; 2],
+    pub master: MasterJson,
+    pub buses: Vec<BusJson>,
+    pub instruments: Vec<InstrumentJson>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MasterJson {
+    pub level: f32,
+    pub mute: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BusJson {
+    pub id: u8,
+    pub name: String,
+    pub level: f32,
+    pub pan: f32,
+    pub mute: bool,
+    pub solo: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstrumentJson {
+    /// Id in the exported project; only used to tell instruments apart
+    pub id: InstrumentId,
+    pub name: String,
+    /// Source name as shown in the add menu
+    pub source: String,
+    pub level: f32,
+    pub pan: f32,
+    pub mute: bool,
+    pub solo: bool,
+    /// "master" or "bus:N"
+    pub output: String,
+    pub sends: Vec<SendJson>,
+    pub params: BTreeMap<String, f64>,
+    pub notes: Vec<NoteJson>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SendJson {
+    pub bus: u8,
+    pub level: f32,
+    pub enabled: bool,
+    /// Send filter cutoffs in Hz; older exports leave them open
+    #[serde(default = "open_low_cut")]
+    pub low_cut: f32,
+    #[serde(default = "open_high_cut")]
+    pub high_cut: f32,
+}
+
+fn open_low_cut() -> f32 {
+    20.0
+}
+
+fn open_high_cut() -> f32 {
+    20_000.0
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NoteJson {
+    pub tick: u32,
+    pub pitch: u8,
+    pub duration: u32,
+    pub velocity: u8,
+}
+
+fn output_name(target: OutputTarget) -> String {
+    match target {
+        OutputTarget::Master => "master".to_string(),
+        OutputTarget::Bus(id) => format!("bus:{}", id),
+    }
+}
+
+fn parse_output(name: &str) -> OutputTarget {
+    name.strip_prefix("bus:")
+        .and_then(|id| id.parse().ok())
+        .map_or(OutputTarget::Master, OutputTarget::Bus)
+}
+
+fn param_number(value: &ParamValue) -> f64 {
+    match value {
+        ParamValue::Float(v) => *v as f64,
+        ParamValue::Int(v) => *v as f64,
+        ParamValue::Bool(v) => if *v { 1.0 } else { 0.0 },
+    }
+}
+
+/// Stored param value converted to the kind the param already has
+fn param_value(current: &ParamValue, number: f64) -> ParamValue {
+    match current {
+        ParamValue::Float(_) => ParamValue::Float(number as f32),
+        ParamValue::Int(_) => ParamValue::Int(number.round() as _),
+        ParamValue::Bool(_) => ParamValue::Bool(number != 0.0),
+    }
+}
+
+impl ProjectJson {
+    pub fn from_state(state: &AppState) -> Self {
+        let session = &state.session;
+        let piano_roll = &session.piano_roll;
+        let instruments = state.instruments.instruments.iter()
+            .map(|inst| {
+                let notes = piano_roll.track_order.iter()
+                    .position(|id| *id == inst.id)
+                    .and_then(|idx| piano_roll.track_at(idx))
+                    .map(|track| {
+                        let mut notes: Vec<NoteJson> = track.notes.iter()
+                            .map(|n| NoteJson { tick: n.tick, pitch: n.pitch, duration: n.duration, velocity: n.velocity })
+                            .collect();
+                        notes.sort_by_key(|n| (n.tick, n.pitch));
+                        notes
+                    })
+                    .unwrap_or_default();
+                InstrumentJson {
+                    id: inst.id,
+                    name: inst.name.clone(),
+                    source: inst.source.name().to_string(),
+                    level: inst.level,
+                    pan: inst.pan,
+                    mute: inst.mute,
+                    solo: inst.solo,
+                    output: output_name(inst.output_target),
+                    sends: inst.sends.iter()
+                        .map(|s| SendJson { bus: s.bus_id, level: s.level, enabled: s.enabled, low_cut: s.low_cut, high_cut: s.high_cut })
+                        .collect(),
+                    params: inst.source_params.iter()
+                        .map(|p| (p.name.clone(), param_number(&p.value)))
+                        .collect(),
+                    notes,
+                }
+            })
+            .collect();
+        Self {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            bpm: session.bpm as f64,
+            time_signature: [session.time_signature.0 as u32, session.time_signature.1 as u32],
+            master: MasterJson { level: session.mixer.master_level, mute: session.mixer.master_mute },
+            buses: session.mixer.buses.iter()
+                .map(|b| BusJson { id: b.id, name: b.name.clone(), level: b.level, pan: b.pan, mute: b.mute, solo: b.solo })
+                .collect(),
+            instruments,
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let project: Self = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        if project.format != FORMAT {
+            return Err(format!("not an {} file", FORMAT));
+        }
+        if project.version > VERSION {
+            return Err(format!("written by a newer version (schema {})", project.version));
+        }
+        Ok(project)
+    }
+
+    /// Actions setting the tempo, meter, master and buses of the project
+    /// being imported into `state`
+    pub fn session_actions(&self, state: &AppState) -> Vec<Action> {
+        let mut settings = state.session.musical_settings();
+        settings.bpm = self.bpm.round() as _;
+        settings.time_signature = (self.time_signature[0] as _, self.time_signature[1] as _);
+        let mut actions = vec![
+            Action::Session(SessionAction::UpdateSession(settings)),
+            Action::Mixer(MixerAction::SetLevelAt(MixerSelection::Master, self.master.level)),
+            Action::Mixer(MixerAction::SetMuteAt(MixerSelection::Master, self.master.mute)),
+        ];
+        for bus in &self.buses {
+            if !state.session.mixer.buses.iter().any(|b| b.id == bus.id) {
+                continue;
+            }
+            let at = MixerSelection::Bus(bus.id);
+            actions.extend([
+                Action::Mixer(MixerAction::RenameBus(bus.id, bus.name.clone())),
+                Action::Mixer(MixerAction::SetLevelAt(at, bus.level)),
+                Action::Mixer(MixerAction::SetPanAt(at, bus.pan)),
+                Action::Mixer(MixerAction::SetMuteAt(at, bus.mute)),
+                Action::Mixer(MixerAction::SetSoloAt(at, bus.solo)),
+            ]);
+        }
+        actions
+    }
+}
+
+impl InstrumentJson {
+    /// Built-in source to add for this instrument; None for custom synthdefs and VSTs
+    pub fn source_type(&self) -> Option<SourceType> {
+        SourceType::all().into_iter().find(|s| s.name() == self.source)
+    }
+
+    /// Actions giving the freshly added instrument `id` this instrument's
+    /// name, mixer settings, params and notes
+    pub fn actions(&self, state: &AppState, id: InstrumentId) -> Vec<Action> {
+        let Some(idx) = state.instruments.instruments.iter().position(|inst| inst.id == id) else {
+            return Vec::new();
+        };
+        let inst = &state.instruments.instruments[idx];
+        let at = MixerSelection::Instrument(idx);
+        let mut actions = vec![
+            Action::Instrument(InstrumentAction::Rename(id, self.name.clone())),
+            Action::Mixer(MixerAction::SetLevelAt(at, self.level)),
+            Action::Mixer(MixerAction::SetPanAt(at, self.pan)),
+            Action::Mixer(MixerAction::SetMuteAt(at, self.mute)),
+            Action::Mixer(MixerAction::SetSoloAt(at, self.solo)),
+            Action::Instrument(InstrumentAction::SetOutput(id, parse_output(&self.output))),
+        ];
+        actions.extend(self.sends.iter()
+            .filter(|send| inst.sends.iter().any(|s| s.bus_id == send.bus))
+            .map(|send| Action::Instrument(InstrumentAction::SetSend {
+                id,
+                bus_id: send.bus,
+                level: send.level,
+                enabled: send.enabled,
+                low_cut: send.low_cut,
+                high_cut: send.high_cut,
+            })));
+
+        let mut source_params = inst.source_params.clone();
+        for param in source_params.iter_mut() {
+            if let Some(number) = self.params.get(&param.name) {
+                param.value = param_value(&param.value, *number);
+            }
+        }
+        actions.push(Action::Instrument(InstrumentAction::Update(Box::new(InstrumentUpdate {
+            id,
+            source: inst.source,
+            source_params,
+            filter: inst.filter.clone(),
+            eq: inst.eq.clone(),
+            effects: inst.effects.clone(),
+            lfo: inst.lfo.clone(),
+            amp_envelope: inst.amp_envelope.clone(),
+            polyphonic: inst.polyphonic,
+            active: inst.active,
+        }))));
+
+        // The project is new, so toggling only ever adds
+        if let Some(track) = state.session.piano_roll.track_order.iter().position(|t| *t == id) {
+            actions.extend(self.notes.iter().map(|n| Action::PianoRoll(PianoRollAction::ToggleNote {
+                pitch: n.pitch,
+                tick: n.tick,
+                duration: n.duration,
+                velocity: n.velocity,
+                track,
+            })));
+        }
+        actions
+    }
+}
+
+pub fn export(state: &AppState, path: &Path) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(&ProjectJson::from_state(state)).map_err(|e| e.to_string())?;
+    std::fs::write(path, json + "\n").map_err(|e| e.to_string())
+}
+
+pub fn load(path: &Path) -> Result<ProjectJson, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    ProjectJson::parse(&text)
+}
+
+/// Whether `path` names a JSON project rather than a SQLite one
+pub fn is_json_path(path: &Path) -> bool {
+    path.extension().map_or(false, |ext| ext.eq_ignore_ascii_case("json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_round_trips_through_json() {
+        let mut state = AppState::new();
+        state.add_instrument(SourceType::Saw);
+        state.instruments.instruments[0].name = "Lead".to_string();
+        state.instruments.instruments[0].level = 0.5;
+
+        let exported = ProjectJson::from_state(&state);
+        let text = serde_json::to_string_pretty(&exported).unwrap();
+        let parsed = ProjectJson::parse(&text).unwrap();
+        assert_eq!(parsed, exported);
+    }
+
+    #[test]
+    fn import_goes_through_actions() {
+        let mut state = AppState::new();
+        state.add_instrument(SourceType::Saw);
+        let mut project = ProjectJson::from_state(&AppState::new());
+        project.bpm = 96.0;
+        let id = state.instruments.instruments[0].id;
+        project.instruments = vec![InstrumentJson {
+            id,
+            name: "Lead".to_string(),
+            source: SourceType::Saw.name().to_string(),
+            level: 0.5,
+            pan: 0.0,
+            mute: false,
+            solo: false,
+            output: "master".to_string(),
+            sends: Vec::new(),
+            params: BTreeMap::new(),
+            notes: vec![NoteJson { tick: 0, pitch: 60, duration: 480, velocity: 100 }],
+        }];
+
+        let session = project.session_actions(&state);
+        assert!(matches!(&session[0], Action::Session(SessionAction::UpdateSession(s)) if s.bpm == 96));
+
+        let inst = &project.instruments[0];
+        assert!(matches!(inst.source_type(), Some(SourceType::Saw)));
+        let actions = inst.actions(&state, id);
+        assert!(matches!(&actions[0], Action::Instrument(InstrumentAction::Rename(got, name)) if *got == id && name == "Lead"));
+        assert!(matches!(actions.last(), Some(Action::PianoRoll(PianoRollAction::ToggleNote { pitch: 60, track: 0, .. }))));
+        // An id the project doesn't have yields nothing
+        assert!(inst.actions(&AppState::new(), id).is_empty());
+    }
+
+    #[test]
+    fn other_json_is_rejected() {
+        assert!(ProjectJson::parse("{}").is_err());
+        let mut project = ProjectJson::from_state(&AppState::new());
+        project.format = "something-else".to_string();
+        assert!(ProjectJson::parse(&serde_json::to_string(&project).unwrap()).is_err());
+        project.format = FORMAT.to_string();
+        project.version = VERSION + 1;
+        assert!(ProjectJson::parse(&serde_json::to_string(&project).unwrap()).is_err());
+    }
+
+    #[test]
+    fn outputs_parse_back() {
+        assert_eq!(parse_output(&output_name(OutputTarget::Bus(3))), OutputTarget::Bus(3));
+        assert_eq!(parse_output("master"), OutputTarget::Master);
+        assert!(is_json_path(Path::new("song.JSON")));
+        assert!(!is_json_path(Path::new("song.sqlite")));
+    }
+}
@@ -0,0 +1,160 @@
+//! Project-wide search: instruments by name, automation lanes by target,
+//! and piano roll tracks holding a pitch.
+//!
+//! Any query is matched against names. A query that reads as a note
+//! (`C4`, `f#2`, `Bb3`, or `note:60`) also finds every track with notes at
+//! that pitch, pointing at the first one.
+
+use crate::state::AppState;
+
+/// Where a hit takes the UI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Instrument(usize),
+    AutomationLane {
+        lane: usize,
+        /// Owning instrument, which must be selected for the lane to show
+        instrument: Option<usize>,
+    },
+    Note {
+        instrument: usize,
+        tick: u32,
+        pitch: u8,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hit {
+    pub kind: &'static str,
+    pub label: String,
+    pub detail: String,
+    pub target: Target,
+}
+
+/// MIDI pitch for a note name like `C4` (60) or `Eb2`, or `note:60`
+pub fn parse_pitch(text: &str) -> Option<u8> {
+    let text = text.trim();
+    if let Some(number) = text.strip_prefix("note:") {
+        return number.trim().parse().ok().filter(|&n: &u8| n < 128);
+    }
+    let mut chars = text.chars();
+    let base = match chars.next()?.to_ascii_uppercase() {
+        'C' => 0,
+        'D' => 2,
+        'E' => 4,
+        'F' => 5,
+        'G' => 7,
+        'A' => 9,
+        'B' => 11,
+        _ => return None,
+    };
+    let rest = chars.as_str();
+    let (accidental, octave) = if let Some(octave) = rest.strip_prefix('#') {
+        (1, octave)
+    } else if let Some(octave) = rest.strip_prefix('b') {
+        (-1, octave)
+    } else {
+        (0, rest)
+    };
+    let octave: i32 = octave.parse().ok()?;
+    let pitch = (octave + 1) * 12 + base + accidental;
+    u8::try_from(pitch).ok().filter(|&p| p < 128)
+}
+
+fn note_name(pitch: u8) -> String {
+    let names = ["C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B"];
+    format!("{}{}", names[(pitch % 12) as usize], (pitch / 12) as i8 - 1)
+}
+
+pub fn search(state: &AppState, query: &str) -> Vec<Hit> {
+    let needle = query.trim().to_lowercase();
+    let instruments = &state.instruments.instruments;
+    let mut hits = Vec::new();
+
+    for (idx, inst) in instruments.iter().enumerate() {
+        if inst.name.to_lowercase().contains(&needle) {
+            hits.push(Hit {
+                kind: "instrument",
+                label: inst.name.clone(),
+                detail: format!("#{}", idx + 1),
+                target: Target::Instrument(idx),
+            });
+        }
+    }
+
+    for (lane_idx, lane) in state.session.automation.lanes.iter().enumerate() {
+        let name = lane.target.name();
+        if !name.to_lowercase().contains(&needle) {
+            continue;
+        }
+        let owner = lane.target.instrument_id()
+            .and_then(|id| instruments.iter().position(|inst| inst.id == id));
+        let detail = owner.map_or_else(|| "global".to_string(), |idx| instruments[idx].name.clone());
+        hits.push(Hit {
+            kind: "automation",
+            label: name.to_string(),
+            detail,
+            target: Target::AutomationLane { lane: lane_idx, instrument: owner },
+        });
+    }
+
+    if let Some(pitch) = parse_pitch(query) {
+        let piano_roll = &state.session.piano_roll;
+        let (beats_per_bar, _) = state.session.time_signature;
+        for (track_idx, &inst_id) in piano_roll.track_order.iter().enumerate() {
+            let Some(track) = piano_roll.track_at(track_idx) else { continue };
+            let Some(instrument) = instruments.iter().position(|inst| inst.id == inst_id) else { continue };
+            let matching = track.notes.iter().filter(|n| n.pitch == pitch);
+            let Some(first) = matching.clone().map(|n| n.tick).min() else { continue };
+            let beat = piano_roll.tick_to_beat(first) as f64;
+            let beats_per_bar = (beats_per_bar as f64).max(1.0);
+            hits.push(Hit {
+                kind: "notes",
+                label: format!("{} {}", instruments[instrument].name, note_name(pitch)),
+                detail: format!(
+                    "{} notes, first at {}.{}",
+                    matching.count(),
+                    (beat / beats_per_bar).floor() as u32 + 1,
+                    (beat % beats_per_bar).floor() as u32 + 1,
+                ),
+                target: Target::Note { instrument, tick: first, pitch },
+            });
+        }
+    }
+
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::SourceType;
+
+    #[test]
+    fn note_names_parse_to_midi_pitches() {
+        assert_eq!(parse_pitch("C4"), Some(60));
+        assert_eq!(parse_pitch("c#4"), Some(61));
+        assert_eq!(parse_pitch("Bb3"), Some(58));
+        assert_eq!(parse_pitch("A-1"), Some(9));
+        assert_eq!(parse_pitch("note:127"), Some(127));
+        assert_eq!(parse_pitch("note:200"), None);
+        assert_eq!(parse_pitch("Bass"), None);
+        assert_eq!(parse_pitch("G9"), Some(127));
+        assert_eq!(parse_pitch("A9"), None);
+        assert_eq!(note_name(61), "C#4");
+    }
+
+    #[test]
+    fn finds_instruments_by_name() {
+        let mut state = AppState::new();
+        state.add_instrument(SourceType::Saw);
+        state.add_instrument(SourceType::Saw);
+        state.instruments.instruments[0].name = "Lead".to_string();
+        state.instruments.instruments[1].name = "Sub Bass".to_string();
+
+        let hits = search(&state, "bass");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].target, Target::Instrument(1));
+        assert!(search(&state, "zzz").is_empty());
+    }
+}
@@ -0,0 +1,75 @@
+//! Releasing held notes before routing is rebuilt.
+//!
+//! A rebuild tears down and recreates each instrument's groups and buses.
+//! Voices still sounding at that moment are cut off mid-envelope, or
+//! linger on with nothing left to send them a note-off. When a flush would
+//! rebuild under sounding voices, they are released first and the flush
+//! waits until the engine reports them silent, or a short while at most.
+
+use std::time::{Duration, Instant};
+
+/// Longest a rebuild waits for release tails
+const RELEASE_WAIT: Duration = Duration::from_millis(150);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// Release every voice, then check again next frame
+    Release,
+    /// Voices are still fading
+    Wait,
+    /// Flush now; `released` when voices were let go for it, so the
+    /// engine's held notes should be forgotten too
+    Flush { released: bool },
+}
+
+#[derive(Debug, Default)]
+pub struct RebuildGuard {
+    releasing_since: Option<Instant>,
+}
+
+impl RebuildGuard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// What to do with a pending flush that `rebuilds` routing or not,
+    /// while voices are `sounding` or not
+    pub fn step(&mut self, rebuilds: bool, sounding: bool, now: Instant) -> Step {
+        match self.releasing_since {
+            None if rebuilds && sounding => {
+                self.releasing_since = Some(now);
+                Step::Release
+            }
+            None => Step::Flush { released: false },
+            Some(since) if sounding && now.duration_since(since) < RELEASE_WAIT => Step::Wait,
+            Some(_) => {
+                self.releasing_since = None;
+                Step::Flush { released: true }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebuilds_wait_for_released_voices() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut guard = RebuildGuard::new();
+
+        // Nothing to protect
+        assert_eq!(guard.step(true, false, at(0)), Step::Flush { released: false });
+        assert_eq!(guard.step(false, true, at(0)), Step::Flush { released: false });
+
+        assert_eq!(guard.step(true, true, at(0)), Step::Release);
+        assert_eq!(guard.step(true, true, at(50)), Step::Wait);
+        assert_eq!(guard.step(true, false, at(80)), Step::Flush { released: true });
+
+        // Voices that never report silent don't hold the rebuild forever
+        assert_eq!(guard.step(true, true, at(100)), Step::Release);
+        assert_eq!(guard.step(true, true, at(100) + RELEASE_WAIT), Step::Flush { released: true });
+    }
+}
@@ -0,0 +1,157 @@
+//! Queue of export jobs, rendered one after another.
+//!
+//! Every job confirmed in the export dialog lands here. main.rs starts
+//! the first waiting job whenever no export is running and reports back
+//! when it ends; the queue pane shows the list and cancels jobs.
+
+use std::path::PathBuf;
+
+use crate::export_region::ExportRange;
+use crate::panes::{ExportJob, ExportTarget};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueueState {
+    Waiting,
+    Running,
+    Done(Vec<PathBuf>),
+    Failed(String),
+    Cancelled,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueuedJob {
+    pub id: u32,
+    pub job: ExportJob,
+    pub state: QueueState,
+}
+
+impl QueuedJob {
+    /// Short description, e.g. "Stems, bars 5-8, 24-bit"
+    pub fn label(&self) -> String {
+        let target = match self.job.target {
+            ExportTarget::Master => "Master",
+            ExportTarget::Stems => "Stems",
+        };
+        let range = match self.job.range {
+            ExportRange::Song => "whole song".to_string(),
+            ExportRange::Loop => "loop".to_string(),
+            ExportRange::Bars { from, to } => format!("bars {}-{}", from, to),
+        };
+        format!("{}, {}, {}", target, range, self.job.format.depth.name())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct RenderQueue {
+    jobs: Vec<QueuedJob>,
+    next_id: u32,
+    /// The running job was cancelled and is being stopped
+    stopping: bool,
+}
+
+impl RenderQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn jobs(&self) -> &[QueuedJob] {
+        &self.jobs
+    }
+
+    pub fn push(&mut self, job: ExportJob) -> u32 {
+        self.next_id += 1;
+        self.jobs.push(QueuedJob { id: self.next_id, job, state: QueueState::Waiting });
+        self.next_id
+    }
+
+    pub fn running(&self) -> Option<&QueuedJob> {
+        self.jobs.iter().find(|j| j.state == QueueState::Running)
+    }
+
+    /// Mark the first waiting job running and return it
+    pub fn start_next(&mut self) -> Option<ExportJob> {
+        if self.running().is_some() {
+            return None;
+        }
+        let next = self.jobs.iter_mut().find(|j| j.state == QueueState::Waiting)?;
+        next.state = QueueState::Running;
+        Some(next.job)
+    }
+
+    /// Whether the running job was cancelled; its files are left as they are
+    pub fn stopping(&self) -> bool {
+        self.stopping
+    }
+
+    /// Record how the running job ended
+    pub fn finish(&mut self, state: QueueState) {
+        let stopped = std::mem::take(&mut self.stopping);
+        if let Some(job) = self.jobs.iter_mut().find(|j| j.state == QueueState::Running) {
+            job.state = if stopped { QueueState::Cancelled } else { state };
+        }
+    }
+
+    /// Cancel a job. Returns true when it was the running one, which the
+    /// caller has to stop.
+    pub fn cancel(&mut self, id: u32) -> bool {
+        let Some(job) = self.jobs.iter_mut().find(|j| j.id == id) else { return false };
+        match job.state {
+            QueueState::Waiting => {
+                job.state = QueueState::Cancelled;
+                false
+            }
+            QueueState::Running => {
+                self.stopping = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Drop jobs that have ended
+    pub fn clear_finished(&mut self) {
+        self.jobs.retain(|j| matches!(j.state, QueueState::Waiting | QueueState::Running));
+    }
+
+    pub fn waiting(&self) -> usize {
+        self.jobs.iter().filter(|j| j.state == QueueState::Waiting).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export_format::ExportFormat;
+    use crate::export_region::RegionSettings;
+
+    fn job(target: ExportTarget) -> ExportJob {
+        ExportJob { target, range: ExportRange::Song, region: RegionSettings::default(), format: ExportFormat::default() }
+    }
+
+    #[test]
+    fn runs_one_job_at_a_time_in_order() {
+        let mut queue = RenderQueue::new();
+        queue.push(job(ExportTarget::Master));
+        queue.push(job(ExportTarget::Stems));
+        assert_eq!(queue.start_next().map(|j| j.target), Some(ExportTarget::Master));
+        assert_eq!(queue.start_next(), None);
+        queue.finish(QueueState::Done(Vec::new()));
+        assert_eq!(queue.start_next().map(|j| j.target), Some(ExportTarget::Stems));
+    }
+
+    #[test]
+    fn cancelling_a_waiting_job_skips_it() {
+        let mut queue = RenderQueue::new();
+        let first = queue.push(job(ExportTarget::Master));
+        let second = queue.push(job(ExportTarget::Stems));
+        queue.start_next();
+        assert!(!queue.cancel(second));
+        assert!(queue.cancel(first));
+        assert!(queue.stopping());
+        queue.finish(QueueState::Done(Vec::new()));
+        assert_eq!(queue.jobs()[0].state, QueueState::Cancelled);
+        assert_eq!(queue.start_next(), None);
+        queue.clear_finished();
+        assert!(queue.jobs().is_empty());
+    }
+}
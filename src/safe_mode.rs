@@ -0,0 +1,323 @@
+//! Safe-mode startup (`--safe-mode`) and project repair.
+//!
+//! In safe mode a project that fails to load is salvaged instead of
+//! rejected: its tables are copied row by row into a fresh database,
+//! leaving out rows SQLite can't read. Rows pointing at rows that no
+//! longer exist are dropped from the copy, then any row the loader still
+//! rejects is found by trying the load without it, and the copy is
+//! loaded. Once
+//! loaded, instruments and effects whose VST plugin is gone and automation
+//! lanes left without an instrument are removed. Everything left out is
+//! reported so the user knows what to check before saving over the
+//! original.
+
+use std::path::{Path, PathBuf};
+
+use rusqlite::types::Value;
+use rusqlite::{params_from_iter, Connection, OpenFlags};
+
+use crate::action::{Action, AutomationAction, InstrumentAction};
+use crate::state::{self, AppState, EffectType, SourceType};
+
+pub const FLAG: &str = "--safe-mode";
+
+pub fn requested(args: &[String]) -> bool {
+    args.iter().any(|a| a == FLAG)
+}
+
+/// A project loaded through the recovery path
+pub struct Recovered {
+    /// Removals still to dispatch, as one undoable batch
+    pub repairs: Action,
+    /// One line per thing left out
+    pub skipped: Vec<String>,
+}
+
+impl Recovered {
+    pub fn summary(&self) -> String {
+        match self.skipped.len() {
+            0 => "Safe mode: project loaded cleanly".to_string(),
+            n => format!("Safe mode: skipped {} item(s): {}", n, self.skipped.join("; ")),
+        }
+    }
+}
+
+/// Load `path` into `state`, salvaging what it can
+pub fn load(path: &Path, state: &mut AppState) -> Result<Recovered, String> {
+    let mut skipped = Vec::new();
+    let (session, instruments) = match state::persistence::load_project(path) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            let err = e.to_string();
+            log::warn!("safe mode: {} did not load ({}), salvaging rows", path.display(), err);
+            let copy = salvage_path(path);
+            let _ = std::fs::remove_file(&copy);
+            skipped.extend(salvage(path, &copy)?);
+            let loaded = drop_rejected_rows(&copy)
+                .and_then(|dropped| {
+                    skipped.extend(dropped);
+                    state::persistence::load_project(&copy).map_err(|e| e.to_string())
+                })
+                .map_err(|e| format!("{} (salvaged copy: {})", err, e));
+            let _ = std::fs::remove_file(&copy);
+            loaded?
+        }
+    };
+    state.session = session;
+    state.instruments = instruments;
+
+    let (messages, actions): (Vec<String>, Vec<Action>) = repairs(state).into_iter().unzip();
+    skipped.extend(messages);
+    for line in &skipped {
+        log::warn!("safe mode: skipped {}", line);
+    }
+    Ok(Recovered { repairs: Action::Batch(actions), skipped })
+}
+
+fn salvage_path(path: &Path) -> PathBuf {
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    std::env::temp_dir().join(format!("imbolc-salvage-{}-{}", std::process::id(), name))
+}
+
+/// Copy every readable row of `from` into a new database at `to`
+fn salvage(from: &Path, to: &Path) -> Result<Vec<String>, String> {
+    let src = Connection::open_with_flags(from, OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(|e| e.to_string())?;
+    let dest = Connection::open(to).map_err(|e| e.to_string())?;
+    let schema = |kind: &str| -> Result<Vec<(String, String)>, String> {
+        let mut stmt = src
+            .prepare("SELECT name, sql FROM sqlite_master WHERE type = ?1 AND sql IS NOT NULL AND name NOT LIKE 'sqlite_%'")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([kind], |row| Ok((row.get(0)?, row.get(1)?))).map_err(|e| e.to_string())?;
+        Ok(rows.filter_map(Result::ok).collect())
+    };
+
+    let mut skipped = Vec::new();
+    for (table, sql) in schema("table")? {
+        if let Err(e) = dest.execute_batch(&sql) {
+            skipped.push(format!("table {} ({})", table, e));
+            continue;
+        }
+        let lost = copy_rows(&src, &dest, &table);
+        if lost > 0 {
+            skipped.push(format!("{} unreadable row(s) in {}", lost, table));
+        }
+    }
+    for (_, sql) in schema("index")? {
+        let _ = dest.execute_batch(&sql);
+    }
+    Ok(skipped)
+}
+
+/// Copy `table` row by row; returns how many rows were lost
+fn copy_rows(src: &Connection, dest: &Connection, table: &str) -> usize {
+    let count: usize = src
+        .query_row(&format!("SELECT COUNT(*) FROM \"{}\"", table), [], |row| row.get(0))
+        .unwrap_or(0);
+    let Ok(mut stmt) = src.prepare(&format!("SELECT * FROM \"{}\"", table)) else { return count };
+    let columns = stmt.column_count();
+    let insert = format!("INSERT INTO \"{}\" VALUES ({})", table, vec!["?"; columns].join(", "));
+    let Ok(mut rows) = stmt.query([]) else { return count };
+    let mut copied = 0;
+    let mut read = 0;
+    // A corrupt page usually ends the scan, so count what was never reached too
+    while let Ok(Some(row)) = rows.next() {
+        read += 1;
+        let values: Result<Vec<Value>, _> = (0..columns).map(|i| row.get::<_, Value>(i)).collect();
+        if let Ok(values) = values {
+            if dest.execute(&insert, params_from_iter(values)).is_ok() {
+                copied += 1;
+            }
+        }
+    }
+    count.max(read) - copied
+}
+
+/// What loading `path` fails with, if it does
+fn load_error(path: &Path) -> Option<String> {
+    state::persistence::load_project(path).err().map(|e| e.to_string())
+}
+
+/// Delete rows whose foreign keys point at rows that aren't there
+fn drop_dangling_references(conn: &Connection) -> Result<Vec<String>, String> {
+    let mut stmt = conn.prepare("PRAGMA foreign_key_check").map_err(|e| e.to_string())?;
+    let dangling: Vec<(String, Option<i64>, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(Result::ok)
+        .collect();
+    let mut skipped = Vec::new();
+    for (table, rowid, parent) in dangling {
+        let Some(rowid) = rowid else { continue };
+        if conn.execute(&format!("DELETE FROM \"{}\" WHERE rowid = ?1", table), [rowid]).is_ok() {
+            skipped.push(format!("row {} of {} (missing {})", rowid, table, parent));
+        }
+    }
+    Ok(skipped)
+}
+
+/// Load error of a scratch copy of `path` with rows of `table` deleted:
+/// all of them, or just `rowid`
+fn load_without(path: &Path, scratch: &Path, table: &str, rowid: Option<i64>) -> Result<Option<String>, String> {
+    std::fs::copy(path, scratch).map_err(|e| e.to_string())?;
+    let conn = Connection::open(scratch).map_err(|e| e.to_string())?;
+    match rowid {
+        Some(rowid) => conn.execute(&format!("DELETE FROM \"{}\" WHERE rowid = ?1", table), [rowid]),
+        None => conn.execute(&format!("DELETE FROM \"{}\"", table), []),
+    }
+    .map_err(|e| e.to_string())?;
+    drop(conn);
+    Ok(load_error(scratch))
+}
+
+/// Drop rows from the salvaged copy at `path` until the loader accepts it:
+/// dangling references first, then rows the load fails on
+fn drop_rejected_rows(path: &Path) -> Result<Vec<String>, String> {
+    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+    let mut skipped = drop_dangling_references(&conn)?;
+    let mut tables: Vec<String> =
+        first_column(&conn, "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'")
+            .map_err(|e| e.to_string())?;
+    tables.sort();
+    drop(conn);
+
+    let scratch = path.with_extension("probe");
+    let result = drop_failing_rows(path, &scratch, &tables, &mut skipped);
+    let _ = std::fs::remove_file(&scratch);
+    result.map(|()| skipped)
+}
+
+/// Take out one row at a time until `path` loads. A row goes when the load
+/// succeeds without it or, when no single row does that, when its error
+/// moves on to something else.
+fn drop_failing_rows(path: &Path, scratch: &Path, tables: &[String], skipped: &mut Vec<String>) -> Result<(), String> {
+    let Some(mut error) = load_error(path) else { return Ok(()) };
+    'search: loop {
+        for must_load in [true, false] {
+            for table in tables {
+                // Tables the error has nothing to do with are passed over whole
+                if load_without(path, scratch, table, None)?.as_ref() == Some(&error) {
+                    continue;
+                }
+                let conn = Connection::open(path).map_err(|e| e.to_string())?;
+                // WITHOUT ROWID tables can't be narrowed down and are left as they are
+                let rowids: Vec<i64> = first_column(&conn, &format!("SELECT rowid FROM \"{}\"", table)).unwrap_or_default();
+                drop(conn);
+                for rowid in rowids {
+                    let outcome = load_without(path, scratch, table, Some(rowid))?;
+                    let accepted = match &outcome {
+                        None => true,
+                        Some(e) => !must_load && *e != error,
+                    };
+                    if accepted {
+                        std::fs::copy(scratch, path).map_err(|e| e.to_string())?;
+                        skipped.push(format!("row {} of {} ({})", rowid, table, error));
+                        match outcome {
+                            None => return Ok(()),
+                            Some(next) => error = next,
+                        }
+                        continue 'search;
+                    }
+                }
+            }
+        }
+        return Err(error);
+    }
+}
+
+fn first_column<T: rusqlite::types::FromSql>(conn: &Connection, sql: &str) -> rusqlite::Result<Vec<T>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map([], |row| row.get(0))?;
+    rows.collect()
+}
+
+/// Entities that can't be brought back up, and the action that drops each
+pub fn repairs(state: &AppState) -> Vec<(String, Action)> {
+    let plugin_missing = |id| match state.session.vst_plugins.get(id) {
+        Some(plugin) => !plugin.plugin_path.exists(),
+        None => true,
+    };
+    let mut repairs = Vec::new();
+    for inst in &state.instruments.instruments {
+        if let SourceType::Vst(plugin_id) = inst.source {
+            if plugin_missing(plugin_id) {
+                repairs.push((
+                    format!("instrument {} (VST plugin missing)", inst.name),
+                    Action::Instrument(InstrumentAction::Delete(inst.id)),
+                ));
+                continue;
+            }
+        }
+        // Highest index first so earlier removals don't shift later ones
+        for (idx, effect) in inst.effects.iter().enumerate().rev() {
+            if let EffectType::Vst(plugin_id) = effect.effect_type {
+                if plugin_missing(plugin_id) {
+                    repairs.push((
+                        format!("effect {} on {} (VST plugin missing)", idx + 1, inst.name),
+                        Action::Instrument(InstrumentAction::RemoveEffect(inst.id, idx)),
+                    ));
+                }
+            }
+        }
+    }
+    for lane in &state.session.automation.lanes {
+        let Some(inst_id) = lane.target.instrument_id() else { continue };
+        if state.instruments.instrument(inst_id).is_none() {
+            repairs.push((
+                format!("automation lane {} (instrument deleted)", lane.target.name()),
+                Action::Automation(AutomationAction::RemoveLane(lane.id)),
+            ));
+        }
+    }
+    repairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flag_is_recognized() {
+        assert!(requested(&["imbolc".to_string(), "--safe-mode".to_string()]));
+        assert!(!requested(&["imbolc".to_string(), "song.sqlite".to_string()]));
+    }
+
+    #[test]
+    fn salvage_copies_tables_and_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("broken.sqlite");
+        let to = dir.path().join("copy.sqlite");
+        let conn = Connection::open(&from).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE notes (id INTEGER PRIMARY KEY, pitch INTEGER);
+             CREATE INDEX notes_pitch ON notes (pitch);
+             INSERT INTO notes (pitch) VALUES (60), (64);",
+        ).unwrap();
+        drop(conn);
+
+        assert!(salvage(&from, &to).unwrap().is_empty());
+        let copy = Connection::open(&to).unwrap();
+        let count: i64 = copy.query_row("SELECT COUNT(*) FROM notes", [], |row| row.get(0)).unwrap();
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn dangling_references_are_dropped() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE instruments (id INTEGER PRIMARY KEY);
+             CREATE TABLE effects (id INTEGER PRIMARY KEY, instrument_id INTEGER REFERENCES instruments (id));
+             INSERT INTO instruments (id) VALUES (1);
+             INSERT INTO effects (instrument_id) VALUES (1), (7);",
+        ).unwrap();
+
+        let skipped = drop_dangling_references(&conn).unwrap();
+        assert_eq!(skipped, vec!["row 2 of effects (missing instruments)".to_string()]);
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM effects", [], |row| row.get(0)).unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn clean_project_needs_no_repairs() {
+        assert!(repairs(&AppState::new()).is_empty());
+    }
+}
@@ -0,0 +1,350 @@
+//! Sample rate conversion for imported samples.
+//!
+//! A WAV whose rate differs from the running server's plays at the wrong
+//! pitch and speed, so sample picks are converted on the way in, on a
+//! background thread, and the load goes ahead once that is done. The converted
+//! file goes next to the project (`<project>_samples/`) or, for an unsaved
+//! project, into the data directory. A `.json` sidecar records where it
+//! came from and its original format; a later pick of the same file with
+//! the same settings reuses the conversion.
+//!
+//! Files hound can't read (AIFF) are passed through unchanged.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, ChopperAction, InstrumentAction, SequencerAction};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResampleQuality {
+    /// Leave samples at their own rate
+    Off,
+    /// Linear interpolation
+    Fast,
+    /// Windowed sinc, 16 taps
+    #[default]
+    Good,
+    /// Windowed sinc, 64 taps
+    Best,
+}
+
+impl ResampleQuality {
+    pub const ALL: [ResampleQuality; 4] = [ResampleQuality::Off, ResampleQuality::Fast, ResampleQuality::Good, ResampleQuality::Best];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ResampleQuality::Off => "Off",
+            ResampleQuality::Fast => "Fast",
+            ResampleQuality::Good => "Good",
+            ResampleQuality::Best => "Best",
+        }
+    }
+
+    /// Sinc taps either side of the output point; 0 means linear
+    fn half_taps(self) -> usize {
+        match self {
+            ResampleQuality::Off | ResampleQuality::Fast => 0,
+            ResampleQuality::Good => 8,
+            ResampleQuality::Best => 32,
+        }
+    }
+}
+
+/// What a converted sample was made from, kept in its sidecar
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Origin {
+    pub original: PathBuf,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub bits_per_sample: u16,
+    pub float: bool,
+    pub frames: u32,
+    pub quality: ResampleQuality,
+}
+
+/// Resample interleaved audio from `from` Hz to `to` Hz
+pub fn resample(input: &[f32], channels: usize, from: u32, to: u32, quality: ResampleQuality) -> Vec<f32> {
+    let channels = channels.max(1);
+    let frames = input.len() / channels;
+    if from == to || from == 0 || to == 0 || frames == 0 {
+        return input.to_vec();
+    }
+    let out_frames = (frames as u64 * to as u64).div_ceil(from as u64) as usize;
+    let step = from as f64 / to as f64;
+    let at = |frame: isize, ch: usize| -> f64 {
+        if frame < 0 || frame as usize >= frames { 0.0 } else { input[frame as usize * channels + ch] as f64 }
+    };
+
+    // Below 1 when downsampling, so the kernel also filters out what would alias
+    let cutoff = (to as f64 / from as f64).min(1.0);
+    let width = quality.half_taps() as f64 / cutoff;
+    let mut output = Vec::with_capacity(out_frames * channels);
+    for i in 0..out_frames {
+        let t = i as f64 * step;
+        for ch in 0..channels {
+            let value = if quality.half_taps() == 0 {
+                let k = t.floor();
+                let frac = t - k;
+                at(k as isize, ch) * (1.0 - frac) + at(k as isize + 1, ch) * frac
+            } else {
+                let first = (t - width).floor() as isize + 1;
+                let last = (t + width).floor() as isize;
+                (first..=last)
+                    .map(|k| {
+                        let x = t - k as f64;
+                        at(k, ch) * cutoff * sinc(cutoff * x) * blackman(x / width)
+                    })
+                    .sum()
+            };
+            output.push(value as f32);
+        }
+    }
+    output
+}
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-9 {
+        1.0
+    } else {
+        let px = std::f64::consts::PI * x;
+        px.sin() / px
+    }
+}
+
+/// Blackman window over [-1, 1]
+fn blackman(u: f64) -> f64 {
+    if u.abs() >= 1.0 {
+        return 0.0;
+    }
+    let a = std::f64::consts::PI * u;
+    0.42 + 0.5 * a.cos() + 0.08 * (2.0 * a).cos()
+}
+
+/// Where converted samples for a project live
+pub fn import_dir(project: Option<&Path>) -> Option<PathBuf> {
+    match project {
+        Some(project) => {
+            let stem = project.file_stem()?.to_string_lossy();
+            Some(project.with_file_name(format!("{}_samples", stem)))
+        }
+        None => Some(dirs::data_dir()?.join("imbolc").join("resampled")),
+    }
+}
+
+fn sidecar(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".json");
+    PathBuf::from(name)
+}
+
+/// Origin recorded for a converted sample, if it has one
+pub fn origin(converted: &Path) -> Option<Origin> {
+    let text = std::fs::read_to_string(sidecar(converted)).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+/// Convert `path` to `rate` in `dir`. None when it is already at that
+/// rate, conversion is off, or it isn't a WAV.
+pub fn convert(path: &Path, rate: u32, quality: ResampleQuality, dir: &Path) -> Result<Option<PathBuf>, String> {
+    if quality == ResampleQuality::Off {
+        return Ok(None);
+    }
+    let Ok(mut reader) = hound::WavReader::open(path) else { return Ok(None) };
+    let spec = reader.spec();
+    if spec.sample_rate == rate {
+        return Ok(None);
+    }
+    let wanted = Origin {
+        original: path.to_path_buf(),
+        sample_rate: spec.sample_rate,
+        channels: spec.channels,
+        bits_per_sample: spec.bits_per_sample,
+        float: spec.sample_format == hound::SampleFormat::Float,
+        frames: reader.duration(),
+        quality,
+    };
+
+    // Reuse an earlier conversion of the same file; otherwise find a free name
+    let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_else(|| "sample".into());
+    let mut dest = dir.join(format!("{}_{}.wav", stem, rate));
+    for n in 2.. {
+        if !dest.exists() {
+            break;
+        }
+        if origin(&dest).as_ref() == Some(&wanted) {
+            return Ok(Some(dest));
+        }
+        dest = dir.join(format!("{}_{}_{}.wav", stem, rate, n));
+    }
+
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>().map_err(|e| e.to_string())?,
+        hound::SampleFormat::Int => {
+            let scale = 1.0 / (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader.samples::<i32>()
+                .map(|s| s.map(|v| v as f32 * scale))
+                .collect::<Result<_, _>>()
+                .map_err(|e| e.to_string())?
+        }
+    };
+    let converted = resample(&samples, spec.channels as usize, spec.sample_rate, rate, quality);
+
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let out_spec = hound::WavSpec {
+        channels: spec.channels,
+        sample_rate: rate,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    let mut writer = hound::WavWriter::create(&dest, out_spec).map_err(|e| e.to_string())?;
+    for sample in converted {
+        writer.write_sample(sample).map_err(|e| e.to_string())?;
+    }
+    writer.finalize().map_err(|e| e.to_string())?;
+    let json = serde_json::to_string_pretty(&wanted).map_err(|e| e.to_string())?;
+    std::fs::write(sidecar(&dest), json).map_err(|e| e.to_string())?;
+    Ok(Some(dest))
+}
+
+fn sample_path_mut(action: &mut Action) -> Option<&mut PathBuf> {
+    match action {
+        Action::Instrument(InstrumentAction::LoadSampleResult(_, path))
+        | Action::Instrument(InstrumentAction::LoadIRResult(_, _, path))
+        | Action::Sequencer(SequencerAction::LoadSampleResult(_, path))
+        | Action::Sequencer(SequencerAction::AddRoundRobinSampleResult(_, path))
+        | Action::Chopper(ChopperAction::LoadSampleResult(path)) => Some(path),
+        _ => None,
+    }
+}
+
+/// Point a sample-loading action at a converted copy of its file.
+/// Returns a status line when something happened.
+fn convert_action(action: &mut Action, dir: &Path, rate: u32, quality: ResampleQuality) -> Option<String> {
+    let path = sample_path_mut(action)?;
+    match convert(path, rate, quality, dir) {
+        Ok(Some(converted)) => {
+            let message = format!("Converted {} to {} Hz", file_name(path), rate);
+            *path = converted;
+            Some(message)
+        }
+        Ok(None) => None,
+        Err(e) => Some(format!("Sample rate conversion failed, loading original: {}", e)),
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default()
+}
+
+/// A sample-loading action held back while its file is converted
+pub struct Conversion {
+    /// Shown while the conversion runs
+    pub status: String,
+    done: Receiver<(Action, Option<String>)>,
+}
+
+impl Conversion {
+    /// Start converting the file `action` loads when its rate differs from
+    /// `rate`. None when the action can go ahead as it is.
+    pub fn start(action: &Action, project: Option<&Path>, rate: u32, quality: ResampleQuality) -> Option<Self> {
+        let mut action = action.clone();
+        let path = sample_path_mut(&mut action)?;
+        // Only the header is read here; the samples are read on the thread
+        let spec = hound::WavReader::open(&*path).ok()?.spec();
+        if quality == ResampleQuality::Off || spec.sample_rate == rate {
+            return None;
+        }
+        let dir = import_dir(project)?;
+        let status = format!("Converting {} to {} Hz...", file_name(path), rate);
+        let (tx, done) = mpsc::channel();
+        std::thread::spawn(move || {
+            let message = convert_action(&mut action, &dir, rate, quality);
+            let _ = tx.send((action, message));
+        });
+        Some(Self { status, done })
+    }
+
+    /// The action, pointed at the converted file, and a status line once done
+    pub fn poll(&self) -> Option<(Action, Option<String>)> {
+        self.done.try_recv().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resampling_keeps_length_and_level() {
+        let input = vec![0.5f32; 441 * 2];
+        for quality in [ResampleQuality::Fast, ResampleQuality::Good, ResampleQuality::Best] {
+            let output = resample(&input, 2, 44100, 48000, quality);
+            assert_eq!(output.len(), 480 * 2);
+            // Away from the edges a constant stays constant
+            for sample in &output[100..800] {
+                assert!((sample - 0.5).abs() < 0.01, "{:?}: {}", quality, sample);
+            }
+        }
+    }
+
+    #[test]
+    fn converts_once_and_records_origin() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("hit.wav");
+        let spec = hound::WavSpec { channels: 1, sample_rate: 44100, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+        let mut writer = hound::WavWriter::create(&source, spec).unwrap();
+        for i in 0..4410 {
+            writer.write_sample(((i % 100) as i16 - 50) * 100).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let out_dir = dir.path().join("out");
+        let converted = convert(&source, 48000, ResampleQuality::Good, &out_dir).unwrap().unwrap();
+        assert_eq!(hound::WavReader::open(&converted).unwrap().spec().sample_rate, 48000);
+        let origin = origin(&converted).unwrap();
+        assert_eq!((origin.sample_rate, origin.bits_per_sample, origin.frames), (44100, 16, 4410));
+
+        assert_eq!(convert(&source, 48000, ResampleQuality::Good, &out_dir).unwrap(), Some(converted));
+        assert_eq!(convert(&source, 44100, ResampleQuality::Good, &out_dir).unwrap(), None);
+        assert_eq!(convert(&source, 48000, ResampleQuality::Off, &out_dir).unwrap(), None);
+    }
+
+    #[test]
+    fn conversion_hands_back_the_action_pointed_at_the_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("loop.wav");
+        let spec = hound::WavSpec { channels: 1, sample_rate: 44100, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+        let mut writer = hound::WavWriter::create(&source, spec).unwrap();
+        for _ in 0..441 {
+            writer.write_sample(0i16).unwrap();
+        }
+        writer.finalize().unwrap();
+        let project = dir.path().join("song.sqlite");
+        let action = Action::Chopper(ChopperAction::LoadSampleResult(source.clone()));
+
+        assert!(Conversion::start(&action, Some(&project), 44100, ResampleQuality::Good).is_none());
+        let conversion = Conversion::start(&action, Some(&project), 48000, ResampleQuality::Good).unwrap();
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        let (converted, message) = loop {
+            if let Some(done) = conversion.poll() {
+                break done;
+            }
+            assert!(std::time::Instant::now() < deadline);
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        };
+        let Action::Chopper(ChopperAction::LoadSampleResult(path)) = converted else { panic!() };
+        assert!(path.starts_with(dir.path().join("song_samples")));
+        assert!(message.unwrap().contains("48000 Hz"));
+    }
+
+    #[test]
+    fn import_dir_sits_next_to_the_project() {
+        assert_eq!(
+            import_dir(Some(Path::new("/music/song.sqlite"))),
+            Some(PathBuf::from("/music/song_samples")),
+        );
+    }
+}
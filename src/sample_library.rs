@@ -0,0 +1,462 @@
+//! Sample library: an index of the sample directories with keyword and
+//! tag search.
+//!
+//! Indexing runs on a background thread and stores one row per file in
+//! `~/.config/imbolc/samples.sqlite`, skipping files whose modification
+//! time hasn't changed. WAV files are analysed for duration, channels,
+//! tempo (loops of 2 s to 60 s) and key; other audio files are indexed by
+//! name only. Explicit tempo and key in a file name ("loop_120bpm_Am.wav")
+//! win over detection. Tags are the folder names below the library root
+//! and the words of the file name.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::UNIX_EPOCH;
+
+use regex::Regex;
+use rusqlite::{params, Connection};
+
+use crate::audio_to_midi::read_mono;
+use crate::tempo_detect;
+
+pub const AUDIO_EXTENSIONS: [&str; 5] = ["wav", "aif", "aiff", "flac", "ogg"];
+
+/// Loops in this range get tempo detection; one-shots don't have a tempo
+const TEMPO_MIN_SECS: f32 = 2.0;
+const TEMPO_MAX_SECS: f32 = 60.0;
+/// Key detection looks at the start of the file only
+const KEY_MAX_SECS: f32 = 30.0;
+/// Correlation a key profile must reach to be reported
+const KEY_MIN_CORRELATION: f32 = 0.6;
+/// Tempo tolerance when searching with `bpm:N`
+const BPM_TOLERANCE: f32 = 2.0;
+
+const NOTE_NAMES: [&str; 12] = ["C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B"];
+
+/// Krumhansl-Kessler key profiles, from the tonic up
+const MAJOR_PROFILE: [f32; 12] = [6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88];
+const MINOR_PROFILE: [f32; 12] = [6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SampleInfo {
+    pub path: PathBuf,
+    pub name: String,
+    pub duration_secs: f32,
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub bpm: Option<f32>,
+    /// e.g. "A minor", "F# major"
+    pub key: Option<String>,
+    pub tags: Vec<String>,
+}
+
+pub fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map_or(false, |e| AUDIO_EXTENSIONS.iter().any(|a| e.eq_ignore_ascii_case(a)))
+}
+
+/// Folder names between `root` and the file, then the words of its name
+fn tags_for(path: &Path, root: &Path) -> Vec<String> {
+    let mut tags = BTreeSet::new();
+    if let Ok(relative) = path.strip_prefix(root) {
+        if let Some(parent) = relative.parent() {
+            for folder in parent.iter() {
+                tags.insert(folder.to_string_lossy().to_lowercase());
+            }
+        }
+    }
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_lowercase()).unwrap_or_default();
+    for word in stem.split(|c: char| !c.is_alphanumeric()) {
+        if word.len() >= 2 && !word.chars().all(|c| c.is_ascii_digit()) {
+            tags.insert(word.to_string());
+        }
+    }
+    tags.into_iter().collect()
+}
+
+/// Tempo written in a file name, e.g. "120bpm" or "98 BPM"
+fn bpm_from_name(name: &str) -> Option<f32> {
+    let re = Regex::new(r"(?i)(\d{2,3}(?:\.\d+)?)\s*-?bpm").ok()?;
+    let bpm: f32 = re.captures(name)?.get(1)?.as_str().parse().ok()?;
+    (40.0..=300.0).contains(&bpm).then_some(bpm)
+}
+
+/// Key written in a file name as its own word, e.g. "Am", "F#min", "Ebmaj".
+/// A bare note letter is too ambiguous ("Kick A") and is ignored.
+fn key_from_name(name: &str) -> Option<String> {
+    let re = Regex::new(r"^([A-Ga-g])(#|b)?(m|min|minor|maj|major)?$").ok()?;
+    name.split(|c: char| c == '_' || c == '-' || c == ' ' || c == '.')
+        .filter_map(|word| re.captures(word))
+        .find(|caps| caps.get(2).is_some() || caps.get(3).is_some())
+        .and_then(|caps| {
+            let letter = caps[1].to_ascii_uppercase();
+            let natural = NOTE_NAMES.iter().position(|n| *n == letter)? as i32;
+            let pitch_class = match caps.get(2).map(|m| m.as_str()) {
+                Some("#") => natural + 1,
+                Some("b") => natural - 1,
+                _ => natural,
+            }.rem_euclid(12) as usize;
+            let minor = caps.get(3).map_or(false, |m| m.as_str().starts_with('m') && !m.as_str().starts_with("maj"));
+            Some(key_name(pitch_class, minor))
+        })
+}
+
+fn key_name(pitch_class: usize, minor: bool) -> String {
+    format!("{} {}", NOTE_NAMES[pitch_class % 12], if minor { "minor" } else { "major" })
+}
+
+/// Energy per pitch class, from Goertzel filters at each semitone C2..B6
+fn chroma(samples: &[f32], rate: u32) -> [f32; 12] {
+    const FRAME: usize = 4096;
+    let mut bins = [0.0; 12];
+    for frame in samples.chunks_exact(FRAME) {
+        for midi in 36..96 {
+            let freq = 440.0 * 2f32.powf((midi as f32 - 69.0) / 12.0);
+            let coeff = 2.0 * (2.0 * std::f32::consts::PI * freq / rate as f32).cos();
+            let (mut s1, mut s2) = (0.0f32, 0.0f32);
+            for &x in frame {
+                let s0 = x + coeff * s1 - s2;
+                s2 = s1;
+                s1 = s0;
+            }
+            bins[midi % 12] += (s1 * s1 + s2 * s2 - coeff * s1 * s2).max(0.0).sqrt();
+        }
+    }
+    bins
+}
+
+fn correlation(a: &[f32; 12], b: &[f32; 12]) -> f32 {
+    let mean_a = a.iter().sum::<f32>() / 12.0;
+    let mean_b = b.iter().sum::<f32>() / 12.0;
+    let (mut num, mut den_a, mut den_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        let (da, db) = (x - mean_a, y - mean_b);
+        num += da * db;
+        den_a += da * da;
+        den_b += db * db;
+    }
+    if den_a <= 0.0 || den_b <= 0.0 { 0.0 } else { num / (den_a * den_b).sqrt() }
+}
+
+/// Best-matching key for pitched material, if any key fits well enough
+fn detect_key(samples: &[f32], rate: u32) -> Option<String> {
+    let len = samples.len().min((KEY_MAX_SECS * rate as f32) as usize);
+    let bins = chroma(&samples[..len], rate);
+    let mut best: Option<(f32, usize, bool)> = None;
+    for tonic in 0..12 {
+        for (profile, minor) in [(&MAJOR_PROFILE, false), (&MINOR_PROFILE, true)] {
+            let rotated: [f32; 12] = std::array::from_fn(|i| profile[(i + 12 - tonic) % 12]);
+            let r = correlation(&bins, &rotated);
+            if best.map_or(true, |(b, _, _)| r > b) {
+                best = Some((r, tonic, minor));
+            }
+        }
+    }
+    best.filter(|(r, _, _)| *r >= KEY_MIN_CORRELATION)
+        .map(|(_, tonic, minor)| key_name(tonic, minor))
+}
+
+/// Index entry for one file under `root`
+pub fn analyze(path: &Path, root: &Path) -> SampleInfo {
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let mut info = SampleInfo {
+        path: path.to_path_buf(),
+        bpm: bpm_from_name(&name),
+        key: key_from_name(&name),
+        name,
+        duration_secs: 0.0,
+        channels: 0,
+        sample_rate: 0,
+        tags: tags_for(path, root),
+    };
+    let Ok(reader) = hound::WavReader::open(path) else {
+        return info;
+    };
+    let spec = reader.spec();
+    info.channels = spec.channels;
+    info.sample_rate = spec.sample_rate;
+    info.duration_secs = reader.duration() as f32 / spec.sample_rate.max(1) as f32;
+    drop(reader);
+
+    let wants_tempo = info.bpm.is_none() && (TEMPO_MIN_SECS..=TEMPO_MAX_SECS).contains(&info.duration_secs);
+    if wants_tempo || info.key.is_none() {
+        if let Ok((samples, rate)) = read_mono(path) {
+            if wants_tempo {
+                // Only the tempo is kept, so the bar length doesn't matter
+                info.bpm = tempo_detect::estimate_tempo(&samples, rate, 4).map(|t| (t.bpm * 10.0).round() / 10.0);
+            }
+            if info.key.is_none() {
+                info.key = detect_key(&samples, rate);
+            }
+        }
+    }
+    info
+}
+
+fn modified(path: &Path) -> i64 {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+/// Audio files below `root`, skipping hidden entries
+fn walk(root: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            walk(&path, out);
+        } else if is_audio_file(&path) {
+            out.push(path);
+        }
+    }
+}
+
+pub fn db_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("imbolc").join("samples.sqlite"))
+}
+
+fn open(path: &Path) -> Result<Connection, String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS samples (
+            path TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            modified INTEGER NOT NULL,
+            duration REAL NOT NULL,
+            channels INTEGER NOT NULL,
+            sample_rate INTEGER NOT NULL,
+            bpm REAL,
+            key TEXT,
+            tags TEXT NOT NULL
+        );",
+    ).map_err(|e| e.to_string())?;
+    Ok(conn)
+}
+
+fn store(conn: &Connection, info: &SampleInfo, modified: i64) -> rusqlite::Result<usize> {
+    conn.execute(
+        "INSERT OR REPLACE INTO samples (path, name, modified, duration, channels, sample_rate, bpm, key, tags)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            info.path.to_string_lossy(),
+            info.name,
+            modified,
+            info.duration_secs as f64,
+            info.channels,
+            info.sample_rate,
+            info.bpm.map(|b| b as f64),
+            info.key,
+            info.tags.join(" "),
+        ],
+    )
+}
+
+/// Everything in the index, sorted by name
+pub fn load_all(db: &Path) -> Result<Vec<SampleInfo>, String> {
+    let conn = open(db)?;
+    let mut stmt = conn
+        .prepare("SELECT path, name, duration, channels, sample_rate, bpm, key, tags FROM samples ORDER BY name COLLATE NOCASE")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([], |row| {
+        let tags: String = row.get(7)?;
+        Ok(SampleInfo {
+            path: PathBuf::from(row.get::<_, String>(0)?),
+            name: row.get(1)?,
+            duration_secs: row.get::<_, f64>(2)? as f32,
+            channels: row.get(3)?,
+            sample_rate: row.get(4)?,
+            bpm: row.get::<_, Option<f64>>(5)?.map(|b| b as f32),
+            key: row.get(6)?,
+            tags: tags.split_whitespace().map(str::to_string).collect(),
+        })
+    }).map_err(|e| e.to_string())?;
+    rows.collect::<Result<_, _>>().map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IndexEvent {
+    Progress { done: usize, total: usize },
+    Finished { analyzed: usize, total: usize },
+    Failed(String),
+}
+
+fn index(roots: &[PathBuf], db: &Path, tx: &Sender<IndexEvent>) -> Result<(usize, usize), String> {
+    let conn = open(db)?;
+    let mut files = Vec::new();
+    for root in roots {
+        let start = files.len();
+        walk(root, &mut files);
+        files[start..].sort();
+    }
+    let total = files.len();
+
+    // Drop entries for files that are gone
+    let known: Vec<String> = {
+        let mut stmt = conn.prepare("SELECT path FROM samples").map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([], |row| row.get(0)).map_err(|e| e.to_string())?;
+        rows.collect::<Result<_, _>>().map_err(|e| e.to_string())?
+    };
+    for path in known.iter().filter(|p| !Path::new(p).exists()) {
+        conn.execute("DELETE FROM samples WHERE path = ?1", [path]).map_err(|e| e.to_string())?;
+    }
+
+    let mut analyzed = 0;
+    for (done, path) in files.iter().enumerate() {
+        let modified = modified(path);
+        let stored: Option<i64> = conn
+            .query_row("SELECT modified FROM samples WHERE path = ?1", [path.to_string_lossy()], |row| row.get(0))
+            .ok();
+        if stored != Some(modified) {
+            let root = roots.iter().find(|r| path.starts_with(r)).map_or(Path::new(""), |r| r.as_path());
+            store(&conn, &analyze(path, root), modified).map_err(|e| e.to_string())?;
+            analyzed += 1;
+        }
+        if done % 50 == 0 && tx.send(IndexEvent::Progress { done, total }).is_err() {
+            break;
+        }
+    }
+    Ok((analyzed, total))
+}
+
+/// Indexing in progress on a background thread
+pub struct Indexer {
+    events: Receiver<IndexEvent>,
+}
+
+impl Indexer {
+    pub fn start(roots: Vec<PathBuf>, db: PathBuf) -> Self {
+        let (tx, events) = mpsc::channel();
+        std::thread::spawn(move || {
+            let event = match index(&roots, &db, &tx) {
+                Ok((analyzed, total)) => IndexEvent::Finished { analyzed, total },
+                Err(e) => IndexEvent::Failed(e),
+            };
+            let _ = tx.send(event);
+        });
+        Self { events }
+    }
+
+    /// Events since the last call
+    pub fn poll(&self) -> Vec<IndexEvent> {
+        self.events.try_iter().collect()
+    }
+}
+
+/// Whether `sample` matches every term of `query`. Terms are words to find
+/// in the name or tags, `#tag` for an exact tag, `bpm:120` or
+/// `bpm:110-130`, and `key:am` / `key:f#` (tonic, optionally `m`).
+fn matches(sample: &SampleInfo, query: &str) -> bool {
+    query.split_whitespace().all(|term| {
+        let term = term.to_lowercase();
+        if let Some(range) = term.strip_prefix("bpm:") {
+            let Some(bpm) = sample.bpm else { return false };
+            return match range.split_once('-') {
+                Some((lo, hi)) => match (lo.parse::<f32>(), hi.parse::<f32>()) {
+                    (Ok(lo), Ok(hi)) => (lo..=hi).contains(&bpm),
+                    _ => false,
+                },
+                None => range.parse::<f32>().map_or(false, |b| (bpm - b).abs() <= BPM_TOLERANCE),
+            };
+        }
+        if let Some(key) = term.strip_prefix("key:") {
+            let wanted = key_from_name(key).or_else(|| key_from_name(&format!("{}maj", key)));
+            return wanted.is_some() && wanted == sample.key;
+        }
+        if let Some(tag) = term.strip_prefix('#') {
+            return sample.tags.iter().any(|t| t == tag);
+        }
+        sample.name.to_lowercase().contains(&term) || sample.tags.iter().any(|t| t.contains(&term))
+    })
+}
+
+/// Indices of the samples matching `query`, in order
+pub fn search(samples: &[SampleInfo], query: &str) -> Vec<usize> {
+    samples.iter().enumerate().filter(|(_, s)| matches(s, query)).map(|(i, _)| i).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(name: &str, bpm: Option<f32>, key: Option<&str>) -> SampleInfo {
+        SampleInfo {
+            path: PathBuf::from("/lib/drums").join(name),
+            name: name.to_string(),
+            duration_secs: 1.0,
+            channels: 2,
+            sample_rate: 44100,
+            bpm,
+            key: key.map(str::to_string),
+            tags: tags_for(&Path::new("/lib/drums").join(name), Path::new("/lib")),
+        }
+    }
+
+    #[test]
+    fn names_carry_tempo_and_key() {
+        assert_eq!(bpm_from_name("funk_loop_98BPM.wav"), Some(98.0));
+        assert_eq!(bpm_from_name("kick_909.wav"), None);
+        assert_eq!(key_from_name("pad_Am_120bpm.wav").as_deref(), Some("A minor"));
+        assert_eq!(key_from_name("bass-F#maj.wav").as_deref(), Some("F# major"));
+        assert_eq!(key_from_name("lead Ebmin.wav").as_deref(), Some("D# minor"));
+        assert_eq!(key_from_name("Kick A.wav"), None);
+    }
+
+    #[test]
+    fn tags_come_from_folders_and_words() {
+        let tags = tags_for(Path::new("/lib/Drums/Kicks/deep_kick_01.wav"), Path::new("/lib"));
+        assert_eq!(tags, vec!["deep", "drums", "kick", "kicks"]);
+    }
+
+    #[test]
+    fn search_combines_terms() {
+        let samples = vec![
+            sample("deep_kick.wav", None, None),
+            sample("funk_loop_98bpm.wav", Some(98.0), None),
+            sample("pad_Am.wav", Some(120.0), Some("A minor")),
+        ];
+        let names = |q: &str| search(&samples, q).into_iter().map(|i| samples[i].name.clone()).collect::<Vec<_>>();
+        assert_eq!(names("kick"), vec!["deep_kick.wav"]);
+        assert_eq!(names("#drums bpm:97"), vec!["funk_loop_98bpm.wav"]);
+        assert_eq!(names("bpm:100-130"), vec!["pad_Am.wav"]);
+        assert_eq!(names("key:am"), vec!["pad_Am.wav"]);
+        assert!(names("key:c").is_empty());
+        assert_eq!(names("").len(), 3);
+    }
+
+    #[test]
+    fn sustained_chord_is_in_its_key() {
+        let rate = 22050;
+        // A minor triad, a few seconds long
+        let samples: Vec<f32> = (0..rate * 3)
+            .map(|i| {
+                let t = i as f32 / rate as f32;
+                [220.0, 261.63, 329.63].iter().map(|f| (2.0 * std::f32::consts::PI * f * t).sin()).sum::<f32>() / 3.0
+            })
+            .collect();
+        let key = detect_key(&samples, rate as u32).unwrap_or_default();
+        assert!(key == "A minor" || key == "C major", "got {}", key);
+    }
+
+    #[test]
+    fn index_round_trips_through_sqlite() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("samples.sqlite");
+        let conn = open(&db).unwrap();
+        let info = sample("pad_Am.wav", Some(120.0), Some("A minor"));
+        store(&conn, &info, 1).unwrap();
+        assert_eq!(load_all(&db).unwrap(), vec![info]);
+    }
+}
@@ -0,0 +1,371 @@
+//! SuperCollider code for an instrument's synth chain, so a patch can be
+//! taken further in sclang.
+//!
+//! The export is two SynthDefs: `<name>_voice` (source, filter, amp
+//! envelope) is played once per note into a bus, and `<name>_fx` (effects,
+//! level, pan) reads that bus. A last block wires them up and plays a
+//! test phrase. Every current param value becomes an argument default, so
+//! the patch can be tweaked with `.set`. Sources and effects without a
+//! close UGen equivalent are approximated, or left as a comment listing
+//! their params; the generated code always evaluates.
+
+use std::path::{Path, PathBuf};
+
+use crate::state::{EffectType, FilterType, Instrument, Param, ParamValue, SourceType};
+
+/// Arguments of the SynthDef being built, in declaration order
+#[derive(Default)]
+struct Controls {
+    args: Vec<(String, String)>,
+}
+
+/// Param name to argument name, for one block of params
+type Names = Vec<(String, String)>;
+
+impl Controls {
+    /// Add an argument, renaming it if the name is taken. Returns the name used.
+    fn add(&mut self, name: &str, default: String) -> String {
+        let mut unique = name.to_string();
+        let mut n = 2;
+        while self.args.iter().any(|(taken, _)| *taken == unique) {
+            unique = format!("{}{}", name, n);
+            n += 1;
+        }
+        self.args.push((unique.clone(), default));
+        unique
+    }
+
+    /// Add an argument per param. A `prefix` keeps blocks apart, e.g. `fx1_`.
+    fn params(&mut self, prefix: &str, params: &[Param]) -> Names {
+        params.iter()
+            .map(|p| {
+                let arg = self.add(&format!("{}{}", prefix, identifier(&p.name)), param_literal(&p.value));
+                (p.name.clone(), arg)
+            })
+            .collect()
+    }
+
+    fn declaration(&self) -> String {
+        let args: Vec<String> = self.args.iter().map(|(name, default)| format!("{} = {}", name, default)).collect();
+        format!("|{}|", args.join(", "))
+    }
+}
+
+/// Lowercase sclang identifier for a param name
+fn identifier(name: &str) -> String {
+    let mut id: String = name.trim().chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    if !id.starts_with(|c: char| c.is_ascii_lowercase()) {
+        id.insert(0, 'p');
+    }
+    id
+}
+
+fn number(value: f64) -> String {
+    let rounded = (value * 10000.0).round() / 10000.0;
+    format!("{}", rounded)
+}
+
+fn param_literal(value: &ParamValue) -> String {
+    match value {
+        ParamValue::Float(v) => number(*v as f64),
+        ParamValue::Int(v) => format!("{}", v),
+        ParamValue::Bool(v) => if *v { "1".to_string() } else { "0".to_string() },
+    }
+}
+
+/// Argument for the first param matching one of `candidates`, or `fallback`
+fn pick(names: &Names, candidates: &[&str], fallback: &str) -> String {
+    candidates.iter()
+        .find_map(|c| names.iter().find(|(param, _)| param.eq_ignore_ascii_case(c)))
+        .map_or_else(|| fallback.to_string(), |(_, arg)| arg.clone())
+}
+
+/// Comment listing params that don't feed the code
+fn unused_comment(names: &Names) -> String {
+    let args: Vec<&str> = names.iter().map(|(_, arg)| arg.as_str()).collect();
+    if args.is_empty() { String::new() } else { format!(" (args: {})", args.join(", ")) }
+}
+
+/// Expression for the source, reading `freq` and `gate`
+fn source_expr(source: SourceType, names: &Names) -> String {
+    let detune = || pick(names, &["detune", "spread"], "0.01");
+    let ratio = || pick(names, &["ratio", "mod_ratio"], "2");
+    let index = || pick(names, &["index", "mod_index", "depth"], "1");
+    match source {
+        SourceType::Sin => "SinOsc.ar(freq)".to_string(),
+        SourceType::Saw => "Saw.ar(freq)".to_string(),
+        SourceType::Sqr => "Pulse.ar(freq, 0.5)".to_string(),
+        SourceType::Tri => "LFTri.ar(freq)".to_string(),
+        SourceType::Pulse => format!("Pulse.ar(freq, {})", pick(names, &["width", "pw", "pulse_width"], "0.5")),
+        SourceType::Noise => "WhiteNoise.ar".to_string(),
+        SourceType::SuperSaw => format!("Mix(Saw.ar(freq * [1, 1 + {d}, 1 - {d}])) / 3", d = detune()),
+        SourceType::FM => format!("SinOsc.ar(freq, SinOsc.ar(freq * {}) * {})", ratio(), index()),
+        SourceType::PhaseMod => format!("SinOsc.ar(freq, SinOsc.ar(freq * {}) * {} * pi)", ratio(), index()),
+        SourceType::FBSin => format!("SinOscFB.ar(freq, {})", pick(names, &["feedback", "fb"], "0.5")),
+        SourceType::Sync => format!("SyncSaw.ar(freq, freq * {})", ratio()),
+        SourceType::Ring => format!("SinOsc.ar(freq) * SinOsc.ar(freq * {})", ratio()),
+        SourceType::Pluck => format!(
+            "Pluck.ar(WhiteNoise.ar, gate, 0.2, freq.reciprocal, {}, {})",
+            pick(names, &["decay"], "2"),
+            pick(names, &["coef", "damping", "damp"], "0.3"),
+        ),
+        SourceType::Formant => format!(
+            "Formant.ar(freq, {}, {})",
+            pick(names, &["formant", "formfreq"], "1760"),
+            pick(names, &["bandwidth", "bwfreq"], "880"),
+        ),
+        SourceType::Gendy => "Gendy1.ar(minfreq: freq, maxfreq: freq * 1.01)".to_string(),
+        SourceType::Chaos => "LorenzL.ar(freq * 8)".to_string(),
+        SourceType::Membrane => format!(
+            "SinOsc.ar(freq * EnvGen.kr(Env.perc(0, {}), gate).linexp(0, 1, 1, 4))",
+            pick(names, &["decay", "pitch_decay"], "0.1"),
+        ),
+        _ => "Saw.ar(freq)".to_string(),
+    }
+}
+
+/// Extra comment for sources the expression only stands in for
+fn source_note(source: SourceType) -> Option<String> {
+    match source {
+        SourceType::Sin | SourceType::Saw | SourceType::Sqr | SourceType::Tri | SourceType::Pulse
+        | SourceType::Noise | SourceType::SuperSaw | SourceType::FM | SourceType::PhaseMod
+        | SourceType::FBSin | SourceType::Sync | SourceType::Ring | SourceType::Pluck
+        | SourceType::Formant | SourceType::Gendy | SourceType::Chaos | SourceType::Membrane => None,
+        SourceType::AudioIn => Some("audio input: swap in SoundIn.ar(0)".to_string()),
+        SourceType::BusIn => Some("bus input: swap in In.ar(yourBus)".to_string()),
+        SourceType::Vst(_) => Some("VST instrument: host it with the VSTPlugin extension".to_string()),
+        _ => Some(format!("{} has no single UGen equivalent; Saw.ar stands in", source.name())),
+    }
+}
+
+fn filter_line(filter_type: FilterType, names: &Names) -> String {
+    let rq = "(1 - res).clip(0.05, 1)";
+    match filter_type {
+        FilterType::Lpf => format!("sig = RLPF.ar(sig, cutoff, {});", rq),
+        FilterType::Hpf => format!("sig = RHPF.ar(sig, cutoff, {});", rq),
+        FilterType::Bpf => format!("sig = BPF.ar(sig, cutoff, {});", rq),
+        FilterType::Notch => format!("sig = BRF.ar(sig, cutoff, {});", rq),
+        FilterType::Comb => "sig = CombC.ar(sig, 0.05, cutoff.reciprocal, res * 2);".to_string(),
+        FilterType::ResDrive => format!(
+            "sig = RLPF.ar((sig * {}).tanh, cutoff, {});",
+            pick(names, &["drive"], "2"),
+            rq,
+        ),
+        _ => format!("sig = RLPF.ar(sig, cutoff, {}); // {} approximated as a low-pass", rq, filter_type.name()),
+    }
+}
+
+/// Lines for one effect slot, or `None` when only a comment fits
+fn effect_lines(effect_type: EffectType, names: &Names) -> Option<Vec<String>> {
+    let mix = || pick(names, &["mix", "wet", "dry_wet"], "0.3");
+    let rate = || pick(names, &["rate", "speed"], "0.5");
+    let depth = || pick(names, &["depth"], "0.5");
+    let lines = match effect_type {
+        EffectType::Delay => vec![format!(
+            "sig = sig + (CombC.ar(sig, 2, {}, {} * 8) * {});",
+            pick(names, &["time", "delay", "delay_time"], "0.25"),
+            pick(names, &["feedback", "fb"], "0.4"),
+            mix(),
+        )],
+        EffectType::Reverb => vec![format!(
+            "sig = FreeVerb.ar(sig, {}, {}, {});",
+            mix(),
+            pick(names, &["room", "size", "room_size"], "0.7"),
+            pick(names, &["damp", "damping"], "0.5"),
+        )],
+        EffectType::Chorus => vec![format!(
+            "sig = sig + (DelayC.ar(sig, 0.05, SinOsc.kr({}).range(0.005, 0.005 + ({} * 0.01))) * {});",
+            rate(), depth(), mix(),
+        )],
+        EffectType::Flanger => vec![format!(
+            "sig = sig + (DelayC.ar(sig, 0.02, SinOsc.kr({}).range(0.0005, 0.0005 + ({} * 0.005))) * {});",
+            rate(), depth(), mix(),
+        )],
+        EffectType::Phaser => vec![format!(
+            "sig = sig + (AllpassC.ar(sig, 0.01, SinOsc.kr({}).range(0.0005, 0.0005 + ({} * 0.004)), 0) * {});",
+            rate(), depth(), mix(),
+        )],
+        EffectType::Tremolo => vec![format!("sig = sig * SinOsc.kr({}).range(1 - {}, 1);", rate(), depth())],
+        EffectType::Distortion => vec![format!("sig = (sig * {}).tanh;", pick(names, &["drive", "gain"], "4"))],
+        EffectType::Saturator => vec![format!("sig = (sig * {}).softclip;", pick(names, &["drive", "gain"], "2"))],
+        EffectType::Wavefolder => vec![format!("sig = (sig * {}).fold2(1);", pick(names, &["drive", "fold", "gain"], "2"))],
+        EffectType::Bitcrusher => vec![format!(
+            "sig = Latch.ar(sig, Impulse.ar({})).round(2 ** (1 - {}));",
+            pick(names, &["rate", "sample_rate", "downsample"], "8000"),
+            pick(names, &["bits", "bit_depth"], "8"),
+        )],
+        EffectType::RingMod => vec![format!("sig = sig * SinOsc.ar({});", pick(names, &["freq", "frequency"], "440"))],
+        EffectType::FreqShifter => vec![format!("sig = FreqShift.ar(sig, {});", pick(names, &["shift", "freq"], "100"))],
+        EffectType::PitchShifter => vec![format!(
+            "sig = PitchShift.ar(sig, 0.2, {});",
+            pick(names, &["ratio", "pitch"], "1"),
+        )],
+        EffectType::Limiter => vec![format!("sig = Limiter.ar(sig, {});", pick(names, &["ceiling", "level", "threshold"], "0.9"))],
+        EffectType::Gate => vec![format!("sig = Compander.ar(sig, sig, {}, 10, 1);", pick(names, &["threshold", "thresh"], "0.05"))],
+        _ => return None,
+    };
+    Some(lines)
+}
+
+/// Prefix for a SynthDef name, from the instrument name
+fn def_name(inst: &Instrument) -> String {
+    let id = identifier(&inst.name);
+    let trimmed = id.trim_matches('_');
+    if trimmed.is_empty() { format!("instrument{}", inst.id) } else { trimmed.to_string() }
+}
+
+/// Readable sclang code recreating the instrument's chain
+pub fn generate(inst: &Instrument) -> String {
+    let name = def_name(inst);
+    let mut out = String::new();
+    out.push_str(&format!("// {} ({}), exported from imbolc\n", inst.name, inst.source.name()));
+    out.push_str("// Evaluate each block in turn, e.g. with Ctrl+Enter in the IDE.\n\n");
+
+    // Voice: source, filter, envelope
+    let mut voice = Controls::default();
+    voice.add("out", "0".to_string());
+    voice.add("freq", "440".to_string());
+    voice.add("gate", "1".to_string());
+    voice.add("amp", "0.8".to_string());
+    let env = &inst.amp_envelope;
+    for (arg, value) in [("attack", env.attack), ("decay", env.decay), ("sustain", env.sustain), ("release", env.release)] {
+        voice.add(arg, number(value as f64));
+    }
+    let source_names = voice.params("", &inst.source_params);
+    let filter_names = inst.filter.as_ref().map(|f| {
+        voice.add("cutoff", number(f.cutoff.value as f64));
+        voice.add("res", number(f.resonance.value as f64));
+        voice.params("filter_", &f.extra_params)
+    });
+
+    let mut body = vec![
+        "var sig, env;".to_string(),
+        format!("// Source: {}", inst.source.name()),
+    ];
+    if let Some(note) = source_note(inst.source) {
+        body.push(format!("// {}{}", note, unused_comment(&source_names)));
+    }
+    body.push(format!("sig = {};", source_expr(inst.source, &source_names)));
+    if let (Some(filter), Some(names)) = (&inst.filter, &filter_names) {
+        body.push(format!("// Filter: {}", filter.filter_type.name()));
+        body.push(filter_line(filter.filter_type, names));
+    }
+    body.push("env = EnvGen.kr(Env.adsr(attack, decay, sustain, release), gate, doneAction: 2);".to_string());
+    body.push("Out.ar(out, sig * env * amp);".to_string());
+    out.push_str(&synthdef(&format!("{}_voice", name), &voice, &body));
+
+    // Effects, level and pan
+    let mut fx = Controls::default();
+    fx.add("in", "0".to_string());
+    fx.add("out", "0".to_string());
+    fx.add("level", number(inst.level as f64));
+    fx.add("pan", number(inst.pan as f64));
+    let mut body = vec!["var sig = In.ar(in, 1);".to_string()];
+    for (i, slot) in inst.effects.iter().enumerate() {
+        let names = fx.params(&format!("fx{}_", i + 1), &slot.params);
+        let state = if slot.enabled { "" } else { ", bypassed" };
+        body.push(format!("// {}: {}{}", i + 1, slot.effect_type.name(), state));
+        match effect_lines(slot.effect_type, &names) {
+            Some(lines) if slot.enabled => body.extend(lines),
+            Some(lines) => body.extend(lines.into_iter().map(|l| format!("// {}", l))),
+            None if slot.effect_type.is_vst() => {
+                body.push(format!("// VST effect: host it with the VSTPlugin extension{}", unused_comment(&names)));
+            }
+            None => body.push(format!("// no built-in UGen equivalent{}", unused_comment(&names))),
+        }
+    }
+    if inst.lfo.enabled {
+        body.push(format!(
+            "// imbolc LFO: {} at {} Hz, depth {}, on {}; e.g. param * SinOsc.kr(rate).range(1 - depth, 1)",
+            inst.lfo.shape.name(),
+            number(inst.lfo.rate as f64),
+            number(inst.lfo.depth as f64),
+            inst.lfo.target.name(),
+        ));
+    }
+    body.push("Out.ar(out, Pan2.ar(sig * level, pan));".to_string());
+    out.push_str(&synthdef(&format!("{}_fx", name), &fx, &body));
+
+    out.push_str("// Play: the effects sit after every voice on a private bus\n(\n");
+    out.push_str(&format!("~{}Bus = Bus.audio(s, 1);\n", name));
+    out.push_str(&format!("~{n}Fx = Synth(\\{n}_fx, [\\in, ~{n}Bus], addAction: \\addToTail);\n", n = name));
+    out.push_str(&format!(
+        "Pbind(\\instrument, \\{n}_voice, \\out, ~{n}Bus, \\degree, Pseq([0, 2, 4, 7], 2), \\dur, 0.25).play;\n",
+        n = name,
+    ));
+    out.push_str(")\n");
+    out
+}
+
+fn synthdef(name: &str, controls: &Controls, body: &[String]) -> String {
+    let mut out = format!("(\nSynthDef(\\{}, {{ {}\n", name, controls.declaration());
+    for line in body {
+        out.push_str("    ");
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.push_str("}).add;\n)\n\n");
+    out
+}
+
+/// Where exports go: next to the project when it has been saved
+pub fn export_dir(project_path: Option<&Path>) -> PathBuf {
+    project_path
+        .and_then(|p| p.parent())
+        .map(Path::to_path_buf)
+        .or_else(|| dirs::config_dir().map(|d| d.join("imbolc").join("scd")))
+        .unwrap_or_else(|| PathBuf::from("scd"))
+}
+
+/// Write `<name>.scd` into `dir`. Returns the file written.
+pub fn write(inst: &Instrument, dir: &Path) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let path = dir.join(format!("{}.scd", def_name(inst)));
+    std::fs::write(&path, generate(inst)).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::AppState;
+
+    fn saw() -> Instrument {
+        let mut state = AppState::new();
+        state.add_instrument(SourceType::Saw);
+        state.instruments.instruments.remove(0)
+    }
+
+    #[test]
+    fn chain_becomes_voice_and_fx_synthdefs() {
+        let mut inst = saw();
+        inst.name = "Big Lead!".to_string();
+        inst.level = 0.5;
+        let code = generate(&inst);
+        assert!(code.contains("SynthDef(\\big_lead_voice"));
+        assert!(code.contains("SynthDef(\\big_lead_fx"));
+        assert!(code.contains("sig = Saw.ar(freq);"));
+        assert!(code.contains("level = 0.5"));
+        assert!(code.contains("Env.adsr(attack, decay, sustain, release)"));
+        assert_eq!(code.matches('(').count(), code.matches(')').count());
+        assert_eq!(code.matches('{').count(), code.matches('}').count());
+    }
+
+    #[test]
+    fn argument_names_stay_unique_and_valid() {
+        let mut controls = Controls::default();
+        assert_eq!(controls.add("freq", "1".to_string()), "freq");
+        assert_eq!(controls.add("freq", "2".to_string()), "freq2");
+        assert_eq!(identifier("Decay Time"), "decay_time");
+        assert_eq!(identifier("2nd"), "p2nd");
+    }
+
+    #[test]
+    fn missing_params_fall_back_to_literals() {
+        let names = vec![("Time".to_string(), "fx1_time".to_string())];
+        assert_eq!(pick(&names, &["time"], "0.25"), "fx1_time");
+        assert_eq!(pick(&names, &["feedback"], "0.4"), "0.4");
+    }
+}
@@ -0,0 +1,122 @@
+//! A sclang process for live coding next to the arrangement.
+//!
+//! sclang is started on first use with its stdin piped. Code is evaluated
+//! when a form feed follows it, the same protocol editor plugins use.
+//! `s` is pointed at imbolc's scsynth with `Server.remote`, so anything
+//! played from sclang mixes with the tracks. Output lines from stdout and
+//! stderr are collected on reader threads.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+/// Ends a chunk of code for sclang to evaluate and print
+const EVAL: char = '\x0c';
+
+const DEFAULT_ADDRESS: (&str, u16) = ("127.0.0.1", 57110);
+
+/// Host and port from a "host:port" server address
+fn host_port(address: &str) -> (String, u16) {
+    address.rsplit_once(':')
+        .and_then(|(host, port)| Some((host.to_string(), port.parse().ok()?)))
+        .filter(|(host, _)| !host.is_empty())
+        .unwrap_or_else(|| (DEFAULT_ADDRESS.0.to_string(), DEFAULT_ADDRESS.1))
+}
+
+/// Code run at start so `s` is imbolc's server
+fn startup_code(server_address: &str) -> String {
+    let (host, port) = host_port(server_address);
+    format!(
+        "Server.default = s = Server.remote(\\imbolc, NetAddr(\"{}\", {})); \"imbolc: s is the server at {}:{}\".postln;",
+        host, port, host, port,
+    )
+}
+
+/// Output line without the prompt sclang prints before results
+fn clean_line(line: &str) -> &str {
+    let line = line.trim_end_matches(['\r', '\n']);
+    line.strip_prefix("sc3> ").unwrap_or(line)
+}
+
+fn forward_lines(stream: impl Read + Send + 'static, tx: Sender<String>) {
+    std::thread::spawn(move || {
+        for line in BufReader::new(stream).lines() {
+            let Ok(line) = line else { break };
+            let line = clean_line(&line);
+            if !line.is_empty() && tx.send(line.to_string()).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+pub struct Sclang {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+}
+
+impl Sclang {
+    /// Start sclang from PATH, connected to the scsynth at `server_address`
+    pub fn start(server_address: &str) -> Result<Self, String> {
+        let mut child = Command::new("sclang")
+            .args(["-i", "imbolc"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("could not start sclang: {}", e))?;
+        let (tx, lines) = mpsc::channel();
+        if let Some(stdout) = child.stdout.take() {
+            forward_lines(stdout, tx.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            forward_lines(stderr, tx);
+        }
+        let stdin = child.stdin.take().ok_or("sclang has no stdin")?;
+        let mut sclang = Self { child, stdin, lines };
+        sclang.send(&startup_code(server_address))?;
+        Ok(sclang)
+    }
+
+    /// Evaluate `code`; the result arrives as output lines
+    pub fn send(&mut self, code: &str) -> Result<(), String> {
+        write!(self.stdin, "{}{}", code, EVAL)
+            .and_then(|_| self.stdin.flush())
+            .map_err(|e| format!("sclang: {}", e))
+    }
+
+    /// Output lines since the last call
+    pub fn poll(&mut self) -> Vec<String> {
+        self.lines.try_iter().collect()
+    }
+
+    pub fn is_running(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+}
+
+impl Drop for Sclang {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_address_is_split() {
+        assert_eq!(host_port("10.0.0.2:57120"), ("10.0.0.2".to_string(), 57120));
+        assert_eq!(host_port("nonsense"), ("127.0.0.1".to_string(), 57110));
+        assert!(startup_code("127.0.0.1:57110").contains("NetAddr(\"127.0.0.1\", 57110)"));
+    }
+
+    #[test]
+    fn prompts_are_stripped() {
+        assert_eq!(clean_line("sc3> -> 3\r\n"), "-> 3");
+        assert_eq!(clean_line("ERROR: syntax"), "ERROR: syntax");
+    }
+}
@@ -0,0 +1,391 @@
+//! Rhai scripting for batch edits and generators.
+//!
+//! Scripts see a read-only snapshot of the session and queue edits through
+//! the API below. The queued edits are dispatched as one batch so a whole
+//! script run undoes in a single step.
+//!
+//! ```rhai
+//! // Root-and-fifth bassline on track 0
+//! let root = 36 + key_root();
+//! let beat = ticks_per_beat();
+//! for bar in 0..4 {
+//!     let start = bar * ticks_per_bar();
+//!     add_note(0, root, start, beat, 100);
+//!     add_note(0, root + 7, start + 2 * beat, beat, 90);
+//! }
+//!
+//! // Four on the floor on the selected drum machine's first pad
+//! for step in 0..drum_length() {
+//!     set_step(0, step, step % 4 == 0);
+//! }
+//! ```
+//!
+//! `instruments()` lists each instrument as a map of `id`, `name`,
+//! `track` (its piano roll track, or -1), `drums` and `selected`.
+
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use rhai::{Array, Dynamic, Engine, Map};
+
+use crate::action::{Action, PianoRollAction, SequencerAction};
+use crate::state::AppState;
+
+/// Upper bound on script work, so a runaway loop can't hang the UI
+const MAX_OPERATIONS: u64 = 5_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ScriptNote {
+    pitch: u8,
+    tick: u32,
+    duration: u32,
+    velocity: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ScriptInstrument {
+    id: i64,
+    name: String,
+    /// Piano roll track index
+    track: Option<usize>,
+    drums: bool,
+    selected: bool,
+}
+
+/// Read-only view of the session handed to scripts
+#[derive(Debug, Clone, Default)]
+struct Snapshot {
+    tracks: Vec<Vec<ScriptNote>>,
+    instruments: Vec<ScriptInstrument>,
+    /// Active steps per pad of the selected drum machine's current pattern
+    drum_steps: Vec<Vec<bool>>,
+    drum_length: usize,
+    bpm: f64,
+    ticks_per_beat: i64,
+    ticks_per_bar: i64,
+    key_root: i64,
+    scale: Vec<i64>,
+}
+
+impl Snapshot {
+    fn from_state(state: &AppState) -> Self {
+        let piano_roll = &state.session.piano_roll;
+        let tracks = (0..piano_roll.track_order.len())
+            .map(|idx| {
+                piano_roll.track_at(idx)
+                    .map(|track| {
+                        track.notes.iter()
+                            .map(|n| ScriptNote { pitch: n.pitch, tick: n.tick, duration: n.duration, velocity: n.velocity })
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            })
+            .collect();
+        let instruments = state.instruments.instruments.iter().enumerate()
+            .map(|(idx, inst)| ScriptInstrument {
+                id: inst.id as i64,
+                name: inst.name.clone(),
+                track: piano_roll.track_order.iter().position(|&id| id == inst.id),
+                drums: inst.drum_sequencer.is_some(),
+                selected: state.instruments.selected == Some(idx),
+            })
+            .collect();
+        let (drum_steps, drum_length) = state.instruments.selected_drum_sequencer()
+            .map(|seq| {
+                let pattern = seq.pattern();
+                let steps = pattern.steps.iter()
+                    .map(|pad| pad.iter().take(pattern.length).map(|s| s.active).collect())
+                    .collect();
+                (steps, pattern.length)
+            })
+            .unwrap_or_default();
+        Self {
+            tracks,
+            instruments,
+            drum_steps,
+            drum_length,
+            bpm: state.session.bpm as f64,
+            ticks_per_beat: piano_roll.ticks_per_beat as i64,
+            ticks_per_bar: piano_roll.ticks_per_bar() as i64,
+            key_root: state.session.key.semitone() as i64,
+            scale: state.session.scale.intervals().iter().map(|i| *i as i64).collect(),
+        }
+    }
+}
+
+/// Edits queued by a script, applied against the snapshot as they arrive
+#[derive(Debug, Default)]
+struct ScriptOutput {
+    tracks: Vec<Vec<ScriptNote>>,
+    drum_steps: Vec<Vec<bool>>,
+    actions: Vec<Action>,
+    log: Vec<String>,
+}
+
+impl ScriptOutput {
+    fn toggle(&mut self, track: usize, note: ScriptNote) {
+        self.actions.push(Action::PianoRoll(PianoRollAction::ToggleNote {
+            pitch: note.pitch,
+            tick: note.tick,
+            duration: note.duration,
+            velocity: note.velocity,
+            track,
+        }));
+    }
+
+    fn add_note(&mut self, track: i64, pitch: i64, tick: i64, duration: i64, velocity: i64) {
+        let Some(notes) = usize::try_from(track).ok().and_then(|t| self.tracks.get(t)) else {
+            return;
+        };
+        let note = ScriptNote {
+            pitch: pitch.clamp(0, 127) as u8,
+            tick: tick.max(0) as u32,
+            duration: duration.max(1) as u32,
+            velocity: velocity.clamp(1, 127) as u8,
+        };
+        if notes.iter().any(|n| n.pitch == note.pitch && n.tick == note.tick) {
+            return;
+        }
+        self.tracks[track as usize].push(note);
+        self.toggle(track as usize, note);
+    }
+
+    fn remove_note(&mut self, track: i64, pitch: i64, tick: i64) {
+        let Some(notes) = usize::try_from(track).ok().and_then(|t| self.tracks.get_mut(t)) else {
+            return;
+        };
+        let Some(pos) = notes.iter().position(|n| n.pitch as i64 == pitch && n.tick as i64 == tick) else {
+            return;
+        };
+        let note = notes.remove(pos);
+        self.toggle(track as usize, note);
+    }
+
+    /// Turn a step of the selected drum machine's current pattern on or off
+    fn set_step(&mut self, pad: i64, step: i64, on: bool) {
+        let (Ok(pad), Ok(step)) = (usize::try_from(pad), usize::try_from(step)) else {
+            return;
+        };
+        let Some(active) = self.drum_steps.get_mut(pad).and_then(|steps| steps.get_mut(step)) else {
+            return;
+        };
+        if *active != on {
+            *active = on;
+            self.actions.push(Action::Sequencer(SequencerAction::ToggleStep(pad, step)));
+        }
+    }
+}
+
+fn instrument_map(inst: &ScriptInstrument) -> Dynamic {
+    let mut map = Map::new();
+    map.insert("id".into(), inst.id.into());
+    map.insert("name".into(), inst.name.clone().into());
+    map.insert("track".into(), inst.track.map_or(-1, |t| t as i64).into());
+    map.insert("drums".into(), inst.drums.into());
+    map.insert("selected".into(), inst.selected.into());
+    map.into()
+}
+
+fn note_map(note: &ScriptNote) -> Dynamic {
+    let mut map = Map::new();
+    map.insert("pitch".into(), (note.pitch as i64).into());
+    map.insert("tick".into(), (note.tick as i64).into());
+    map.insert("duration".into(), (note.duration as i64).into());
+    map.insert("velocity".into(), (note.velocity as i64).into());
+    map.into()
+}
+
+/// Result of a script run
+#[derive(Debug)]
+pub struct ScriptRun {
+    /// Edits to dispatch as a single undo step (`Action::None` if nothing changed)
+    pub action: Action,
+    pub edit_count: usize,
+    /// Lines printed by the script
+    pub log: Vec<String>,
+}
+
+fn build_engine(snapshot: Rc<Snapshot>, output: Rc<RefCell<ScriptOutput>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+
+    let s = snapshot.clone();
+    engine.register_fn("track_count", move || s.tracks.len() as i64);
+    let s = snapshot.clone();
+    engine.register_fn("bpm", move || s.bpm);
+    let s = snapshot.clone();
+    engine.register_fn("ticks_per_beat", move || s.ticks_per_beat);
+    let s = snapshot.clone();
+    engine.register_fn("ticks_per_bar", move || s.ticks_per_bar);
+    let s = snapshot.clone();
+    engine.register_fn("instruments", move || s.instruments.iter().map(instrument_map).collect::<Array>());
+    let s = snapshot.clone();
+    engine.register_fn("drum_length", move || s.drum_length as i64);
+    let s = snapshot.clone();
+    engine.register_fn("key_root", move || s.key_root);
+    let s = snapshot.clone();
+    engine.register_fn("scale", move || s.scale.iter().map(|i| Dynamic::from(*i)).collect::<Array>());
+    let o = output.clone();
+    engine.register_fn("notes", move |track: i64| -> Array {
+        let out = o.borrow();
+        usize::try_from(track).ok()
+            .and_then(|t| out.tracks.get(t))
+            .map(|notes| notes.iter().map(note_map).collect())
+            .unwrap_or_default()
+    });
+    let o = output.clone();
+    engine.register_fn("add_note", move |track: i64, pitch: i64, tick: i64, duration: i64, velocity: i64| {
+        o.borrow_mut().add_note(track, pitch, tick, duration, velocity);
+    });
+    let o = output.clone();
+    engine.register_fn("remove_note", move |track: i64, pitch: i64, tick: i64| {
+        o.borrow_mut().remove_note(track, pitch, tick);
+    });
+    let o = output.clone();
+    engine.register_fn("drum_steps", move |pad: i64| -> Array {
+        let out = o.borrow();
+        usize::try_from(pad).ok()
+            .and_then(|p| out.drum_steps.get(p))
+            .map(|steps| steps.iter().map(|on| Dynamic::from(*on)).collect())
+            .unwrap_or_default()
+    });
+    let o = output.clone();
+    engine.register_fn("set_step", move |pad: i64, step: i64, on: bool| {
+        o.borrow_mut().set_step(pad, step, on);
+    });
+    let o = output;
+    engine.on_print(move |text| o.borrow_mut().log.push(text.to_string()));
+    engine
+}
+
+fn run_source(source: &str, snapshot: Snapshot) -> Result<ScriptRun, String> {
+    let output = Rc::new(RefCell::new(ScriptOutput {
+        tracks: snapshot.tracks.clone(),
+        drum_steps: snapshot.drum_steps.clone(),
+        ..Default::default()
+    }));
+    let engine = build_engine(Rc::new(snapshot), output.clone());
+    engine.run(source).map_err(|e| e.to_string())?;
+    drop(engine);
+
+    let output = Rc::try_unwrap(output).map(RefCell::into_inner).unwrap_or_default();
+    let edit_count = output.actions.len();
+    let action = if output.actions.is_empty() { Action::None } else { Action::Batch(output.actions) };
+    Ok(ScriptRun { action, edit_count, log: output.log })
+}
+
+/// Run a script file against the current state
+pub fn run_script(path: &Path, state: &AppState) -> Result<ScriptRun, String> {
+    let source = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    run_source(&source, Snapshot::from_state(state))
+}
+
+/// Directory scripts are looked up in by default
+pub fn scripts_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("imbolc").join("scripts"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> Snapshot {
+        Snapshot {
+            tracks: vec![vec![ScriptNote { pitch: 60, tick: 0, duration: 480, velocity: 100 }], Vec::new()],
+            instruments: vec![
+                ScriptInstrument { id: 1, name: "Bass".into(), track: Some(0), drums: false, selected: false },
+                ScriptInstrument { id: 2, name: "Kit".into(), track: Some(1), drums: true, selected: true },
+            ],
+            drum_steps: vec![vec![true, false, false, false], vec![false; 4]],
+            drum_length: 4,
+            bpm: 120.0,
+            ticks_per_beat: 960,
+            ticks_per_bar: 2880,
+            key_root: 0,
+            scale: vec![0, 2, 4, 5, 7, 9, 11],
+        }
+    }
+
+    fn batch_len(action: &Action) -> usize {
+        match action {
+            Action::Batch(actions) => actions.len(),
+            _ => 0,
+        }
+    }
+
+    #[test]
+    fn script_adds_notes_as_one_batch() {
+        let run = run_source("for i in 0..4 { add_note(1, 48, i * 480, 240, 100); }", snapshot()).unwrap();
+        assert_eq!(run.edit_count, 4);
+        assert_eq!(batch_len(&run.action), 4);
+    }
+
+    #[test]
+    fn duplicate_and_missing_notes_are_ignored() {
+        let run = run_source("add_note(0, 60, 0, 480, 100); remove_note(1, 60, 0); add_note(9, 60, 0, 1, 1);", snapshot()).unwrap();
+        assert_eq!(run.edit_count, 0);
+        assert!(matches!(run.action, Action::None));
+    }
+
+    #[test]
+    fn notes_reflect_earlier_edits() {
+        let src = r#"
+            remove_note(0, 60, 0);
+            print(notes(0).len());
+        "#;
+        let run = run_source(src, snapshot()).unwrap();
+        assert_eq!(run.edit_count, 1);
+        assert_eq!(run.log, vec!["0".to_string()]);
+    }
+
+    #[test]
+    fn scale_is_exposed() {
+        let run = run_source("let s = scale(); add_note(1, 48 + key_root() + s[4], 0, 480, 100);", snapshot()).unwrap();
+        match run.action {
+            Action::Batch(actions) => assert!(matches!(
+                actions.as_slice(),
+                [Action::PianoRoll(PianoRollAction::ToggleNote { pitch: 55, .. })]
+            )),
+            _ => panic!("expected batch"),
+        }
+    }
+
+    #[test]
+    fn timing_and_instruments_are_exposed() {
+        let src = r#"
+            print(ticks_per_beat());
+            print(ticks_per_bar());
+            for inst in instruments() {
+                if inst.drums { print(`${inst.name} ${inst.track}`); }
+            }
+        "#;
+        let run = run_source(src, snapshot()).unwrap();
+        assert_eq!(run.log, vec!["960".to_string(), "2880".to_string(), "Kit 1".to_string()]);
+    }
+
+    #[test]
+    fn drum_steps_toggle_only_on_change() {
+        let src = "for step in 0..drum_length() { set_step(0, step, step % 2 == 0); } set_step(9, 0, true); print(drum_steps(0));";
+        let run = run_source(src, snapshot()).unwrap();
+        // Step 0 was already on
+        match &run.action {
+            Action::Batch(actions) => assert!(matches!(
+                actions.as_slice(),
+                [Action::Sequencer(SequencerAction::ToggleStep(0, 2))]
+            )),
+            _ => panic!("expected batch"),
+        }
+        assert_eq!(run.log, vec!["[true, false, true, false]".to_string()]);
+    }
+
+    #[test]
+    fn syntax_errors_are_reported() {
+        assert!(run_source("add_note(", snapshot()).is_err());
+    }
+
+    #[test]
+    fn runaway_scripts_are_stopped() {
+        assert!(run_source("loop {}", snapshot()).is_err());
+    }
+}
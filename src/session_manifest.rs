@@ -0,0 +1,174 @@
+//! Session files written next to exported stems, so the mix can be
+//! rebuilt in another DAW.
+//!
+//! `session.rpp` is a REAPER project that opens with every stem on its own
+//! track at the project start, with the instrument's gain, pan, mute and
+//! solo. `session.json` carries the same tracks plus markers for DAWs
+//! without an importer (Ardour: import the stems "at session start", then
+//! set gains and pans from the file). Markers are the loop points.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::state::{AppState, InstrumentId};
+
+const TICKS_PER_BEAT: f64 = 480.0;
+
+#[derive(Debug, Serialize)]
+struct Manifest {
+    format: &'static str,
+    bpm: f64,
+    time_signature: [u32; 2],
+    master_gain_db: f64,
+    markers: Vec<Marker>,
+    tracks: Vec<Track>,
+}
+
+#[derive(Debug, Serialize)]
+struct Marker {
+    name: String,
+    secs: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct Track {
+    name: String,
+    /// Stem file, relative to the manifest
+    file: String,
+    /// Linear amplitude, as the mixer stores it
+    gain: f64,
+    gain_db: f64,
+    /// -1 (left) to 1 (right)
+    pan: f64,
+    mute: bool,
+    solo: bool,
+    length_secs: f64,
+}
+
+fn gain_db(gain: f64) -> f64 {
+    if gain <= 0.0 { -144.0 } else { 20.0 * gain.log10() }
+}
+
+/// Length of a WAV file in seconds; 0 when it can't be read
+fn wav_secs(path: &Path) -> f64 {
+    hound::WavReader::open(path)
+        .map(|r| r.duration() as f64 / r.spec().sample_rate.max(1) as f64)
+        .unwrap_or(0.0)
+}
+
+fn manifest(state: &AppState, stems: &[(InstrumentId, PathBuf)]) -> Manifest {
+    let session = &state.session;
+    let bpm = session.bpm as f64;
+    let secs = |tick: u32| tick as f64 * 60.0 / (bpm.max(1.0) * TICKS_PER_BEAT);
+    let piano_roll = &session.piano_roll;
+    let markers = if piano_roll.looping && piano_roll.loop_end > piano_roll.loop_start {
+        vec![
+            Marker { name: "Loop start".to_string(), secs: secs(piano_roll.loop_start) },
+            Marker { name: "Loop end".to_string(), secs: secs(piano_roll.loop_end) },
+        ]
+    } else {
+        Vec::new()
+    };
+
+    let tracks = stems.iter()
+        .map(|(id, path)| {
+            let inst = state.instruments.instruments.iter().find(|i| i.id == *id);
+            let gain = inst.map_or(1.0, |i| i.level as f64);
+            Track {
+                name: inst.map_or_else(|| format!("Instrument {}", id), |i| i.name.clone()),
+                file: path.file_name().map(|f| f.to_string_lossy().into_owned()).unwrap_or_default(),
+                gain,
+                gain_db: gain_db(gain),
+                pan: inst.map_or(0.0, |i| i.pan as f64),
+                mute: inst.map_or(false, |i| i.mute),
+                solo: inst.map_or(false, |i| i.solo),
+                length_secs: wav_secs(path),
+            }
+        })
+        .collect();
+
+    Manifest {
+        format: "imbolc-stems",
+        bpm,
+        time_signature: [session.time_signature.0 as u32, session.time_signature.1 as u32],
+        master_gain_db: gain_db(session.mixer.master_level as f64),
+        markers,
+        tracks,
+    }
+}
+
+/// Quote a string for a REAPER project line
+fn rpp_quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "'"))
+}
+
+fn to_rpp(manifest: &Manifest) -> String {
+    let mut out = String::from("<REAPER_PROJECT 0.1 \"6.0\" 0\n");
+    out.push_str(&format!("  TEMPO {} {} {}\n", manifest.bpm, manifest.time_signature[0], manifest.time_signature[1]));
+    for (i, marker) in manifest.markers.iter().enumerate() {
+        out.push_str(&format!("  MARKER {} {:.6} {} 0\n", i + 1, marker.secs, rpp_quote(&marker.name)));
+    }
+    for track in &manifest.tracks {
+        out.push_str("  <TRACK\n");
+        out.push_str(&format!("    NAME {}\n", rpp_quote(&track.name)));
+        out.push_str(&format!("    VOLPAN {:.6} {:.6} -1 -1 1\n", track.gain, track.pan));
+        out.push_str(&format!("    MUTESOLO {} {} 0\n", track.mute as u8, if track.solo { 2 } else { 0 }));
+        out.push_str("    <ITEM\n");
+        out.push_str("      POSITION 0\n");
+        out.push_str(&format!("      LENGTH {:.6}\n", track.length_secs));
+        out.push_str(&format!("      NAME {}\n", rpp_quote(&track.file)));
+        out.push_str("      <SOURCE WAVE\n");
+        out.push_str(&format!("        FILE {}\n", rpp_quote(&track.file)));
+        out.push_str("      >\n    >\n  >\n");
+    }
+    out.push_str(">\n");
+    out
+}
+
+/// Write `session.rpp` and `session.json` next to the stems. Returns the
+/// directory written to.
+pub fn write(state: &AppState, stems: &[(InstrumentId, PathBuf)]) -> Result<PathBuf, String> {
+    let dir = stems.first()
+        .and_then(|(_, path)| path.parent())
+        .ok_or("no stems were exported")?
+        .to_path_buf();
+    let manifest = manifest(state, stems);
+    let json = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join("session.json"), json + "\n").map_err(|e| e.to_string())?;
+    std::fs::write(dir.join("session.rpp"), to_rpp(&manifest)).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::SourceType;
+
+    #[test]
+    fn stems_become_tracks_with_mixer_settings() {
+        let mut state = AppState::new();
+        state.add_instrument(SourceType::Saw);
+        let inst = &mut state.instruments.instruments[0];
+        inst.name = "Lead \"1\"".to_string();
+        inst.level = 0.5;
+        inst.pan = -0.25;
+        let id = inst.id;
+
+        let manifest = manifest(&state, &[(id, PathBuf::from("/tmp/stems/lead.wav"))]);
+        assert_eq!(manifest.tracks[0].file, "lead.wav");
+        assert!((manifest.tracks[0].gain_db + 6.02).abs() < 0.01);
+
+        let rpp = to_rpp(&manifest);
+        assert!(rpp.contains("NAME \"Lead '1'\""));
+        assert!(rpp.contains("VOLPAN 0.500000 -0.250000"));
+        assert!(rpp.contains("FILE \"lead.wav\""));
+        assert_eq!(rpp.matches('<').count(), rpp.matches('>').count());
+    }
+
+    #[test]
+    fn silence_has_a_floor() {
+        assert_eq!(gain_db(0.0), -144.0);
+        assert!(gain_db(1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,574 @@
+//! Experimental session sharing: two instances exchange edit operations
+//! over a TCP connection as newline-delimited JSON.
+//!
+//! The host listens on the loopback interface unless given an address,
+//! and a guest must send the host's key before anything else; use an SSH
+//! tunnel or `--host=0.0.0.0:port` to share across machines. Connecting
+//! happens on a background thread, so the UI runs while it waits.
+//!
+//! Whatever changes the notes or the mixer is shared, however it was
+//! dispatched: the shared parts of the state are compared with what the
+//! peer was last told after every pass of the main loop. Notes are sent as
+//! additions and removals that each side applies only when they would
+//! change something, so both end up with the same notes. Mixer values are
+//! sent as absolute values and merged last-writer-wins per control, using
+//! a Lamport clock with the peer id as tie-breaker.
+
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, MixerAction, PianoRollAction};
+use crate::state::{AppState, MixerSelection};
+
+/// Default port used when `--host` is given without one
+pub const DEFAULT_PORT: u16 = 7878;
+
+/// How long a connecting guest has to send the key
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Mixer channel addressed by a shared operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SharedTarget {
+    Instrument(usize),
+    Bus(u8),
+    Master,
+}
+
+impl SharedTarget {
+    fn to_selection(self) -> MixerSelection {
+        match self {
+            SharedTarget::Instrument(idx) => MixerSelection::Instrument(idx),
+            SharedTarget::Bus(id) => MixerSelection::Bus(id),
+            SharedTarget::Master => MixerSelection::Master,
+        }
+    }
+}
+
+/// Edit operation exchanged between peers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SharedOp {
+    AddNote { track: usize, pitch: u8, tick: u32, duration: u32, velocity: u8 },
+    RemoveNote { track: usize, pitch: u8, tick: u32 },
+    Level { target: SharedTarget, level: f32 },
+    Pan { target: SharedTarget, pan: f32 },
+    Mute { target: SharedTarget, mute: bool },
+    Solo { target: SharedTarget, solo: bool },
+}
+
+/// Key identifying the control an op writes to, for last-writer-wins merging
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ControlKey {
+    Level(SharedTarget),
+    Pan(SharedTarget),
+    Mute(SharedTarget),
+    Solo(SharedTarget),
+}
+
+/// Duration and velocity of the note on `track` at `pitch` and `tick`
+fn note_at(state: &AppState, track: usize, pitch: u8, tick: u32) -> Option<(u32, u8)> {
+    state.session.piano_roll.track_at(track)?
+        .notes.iter()
+        .find(|n| n.pitch == pitch && n.tick == tick)
+        .map(|n| (n.duration, n.velocity))
+}
+
+impl SharedOp {
+    fn control_key(&self) -> Option<ControlKey> {
+        match self {
+            SharedOp::AddNote { .. } | SharedOp::RemoveNote { .. } => None,
+            SharedOp::Level { target, .. } => Some(ControlKey::Level(*target)),
+            SharedOp::Pan { target, .. } => Some(ControlKey::Pan(*target)),
+            SharedOp::Mute { target, .. } => Some(ControlKey::Mute(*target)),
+            SharedOp::Solo { target, .. } => Some(ControlKey::Solo(*target)),
+        }
+    }
+
+    /// Action that applies this op to the local state; None when the state
+    /// already agrees
+    pub fn to_action(&self, state: &AppState) -> Option<Action> {
+        let toggle = |track: usize, pitch: u8, tick: u32, duration: u32, velocity: u8| {
+            Action::PianoRoll(PianoRollAction::ToggleNote { pitch, tick, duration, velocity, track })
+        };
+        Some(match self {
+            SharedOp::AddNote { track, pitch, tick, duration, velocity } => {
+                if note_at(state, *track, *pitch, *tick).is_some() {
+                    return None;
+                }
+                toggle(*track, *pitch, *tick, *duration, *velocity)
+            }
+            SharedOp::RemoveNote { track, pitch, tick } => {
+                let (duration, velocity) = note_at(state, *track, *pitch, *tick)?;
+                toggle(*track, *pitch, *tick, duration, velocity)
+            }
+            SharedOp::Level { target, level } => Action::Mixer(MixerAction::SetLevelAt(target.to_selection(), *level)),
+            SharedOp::Pan { target, pan } => Action::Mixer(MixerAction::SetPanAt(target.to_selection(), *pan)),
+            SharedOp::Mute { target, mute } => Action::Mixer(MixerAction::SetMuteAt(target.to_selection(), *mute)),
+            SharedOp::Solo { target, solo } => Action::Mixer(MixerAction::SetSoloAt(target.to_selection(), *solo)),
+        })
+    }
+}
+
+/// Mixer values of one channel; master has no pan or solo
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Channel {
+    level: f32,
+    pan: Option<f32>,
+    mute: bool,
+    solo: Option<bool>,
+}
+
+/// The shared parts of the state, as the peer was last told them
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Snapshot {
+    /// (track, pitch, tick) to (duration, velocity)
+    notes: HashMap<(usize, u8, u32), (u32, u8)>,
+    channels: HashMap<SharedTarget, Channel>,
+}
+
+impl Snapshot {
+    fn of(state: &AppState) -> Self {
+        let piano_roll = &state.session.piano_roll;
+        let notes = (0..piano_roll.track_order.len())
+            .filter_map(|track| piano_roll.track_at(track).map(|t| (track, t)))
+            .flat_map(|(track, t)| t.notes.iter().map(move |n| ((track, n.pitch, n.tick), (n.duration, n.velocity))))
+            .collect();
+
+        let mixer = &state.session.mixer;
+        let mut channels = HashMap::new();
+        for (idx, inst) in state.instruments.instruments.iter().enumerate() {
+            let channel = Channel { level: inst.level, pan: Some(inst.pan), mute: inst.mute, solo: Some(inst.solo) };
+            channels.insert(SharedTarget::Instrument(idx), channel);
+        }
+        for bus in &mixer.buses {
+            let channel = Channel { level: bus.level, pan: Some(bus.pan), mute: bus.mute, solo: Some(bus.solo) };
+            channels.insert(SharedTarget::Bus(bus.id), channel);
+        }
+        let master = Channel { level: mixer.master_level, pan: None, mute: mixer.master_mute, solo: None };
+        channels.insert(SharedTarget::Master, master);
+        Self { notes, channels }
+    }
+
+    /// Ops that take a peer holding `self` to `newer`
+    fn changes(&self, newer: &Snapshot) -> Vec<SharedOp> {
+        let mut ops = Vec::new();
+        // Removals first, so a note changed in place is removed before it is re-added
+        for (&(track, pitch, tick), old) in &self.notes {
+            if newer.notes.get(&(track, pitch, tick)) != Some(old) {
+                ops.push(SharedOp::RemoveNote { track, pitch, tick });
+            }
+        }
+        for (&(track, pitch, tick), &(duration, velocity)) in &newer.notes {
+            if self.notes.get(&(track, pitch, tick)) != Some(&(duration, velocity)) {
+                ops.push(SharedOp::AddNote { track, pitch, tick, duration, velocity });
+            }
+        }
+        for (&target, new) in &newer.channels {
+            let old = self.channels.get(&target);
+            if old.map(|c| c.level) != Some(new.level) {
+                ops.push(SharedOp::Level { target, level: new.level });
+            }
+            if let Some(pan) = new.pan.filter(|_| old.and_then(|c| c.pan) != new.pan) {
+                ops.push(SharedOp::Pan { target, pan });
+            }
+            if old.map(|c| c.mute) != Some(new.mute) {
+                ops.push(SharedOp::Mute { target, mute: new.mute });
+            }
+            if let Some(solo) = new.solo.filter(|_| old.and_then(|c| c.solo) != new.solo) {
+                ops.push(SharedOp::Solo { target, solo });
+            }
+        }
+        ops
+    }
+}
+
+/// Wire envelope: an op stamped with the sender's clock and peer id
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Envelope {
+    clock: u64,
+    peer: u8,
+    op: SharedOp,
+}
+
+/// Lamport clock plus the last writer of each mixer control
+#[derive(Debug, Default)]
+struct MergeState {
+    clock: u64,
+    last_write: HashMap<ControlKey, (u64, u8)>,
+}
+
+impl MergeState {
+    fn stamp_local(&mut self, peer: u8, op: &SharedOp) -> u64 {
+        self.clock += 1;
+        if let Some(key) = op.control_key() {
+            self.last_write.insert(key, (self.clock, peer));
+        }
+        self.clock
+    }
+
+    /// Returns true if a remote op should be applied
+    fn accept_remote(&mut self, envelope: &Envelope) -> bool {
+        self.clock = self.clock.max(envelope.clock) + 1;
+        let Some(key) = envelope.op.control_key() else {
+            return true;
+        };
+        let stamp = (envelope.clock, envelope.peer);
+        match self.last_write.get(&key) {
+            Some(existing) if *existing > stamp => false,
+            _ => {
+                self.last_write.insert(key, stamp);
+                true
+            }
+        }
+    }
+}
+
+enum Link {
+    /// Listening or connecting on a background thread
+    Waiting(Receiver<std::io::Result<TcpStream>>),
+    Open { stream: TcpStream, incoming: Receiver<Envelope> },
+    Closed,
+}
+
+/// A sharing session, connected or waiting for its peer
+pub struct SessionShare {
+    peer: u8,
+    link: Link,
+    merge: MergeState,
+    /// What the peer has been told
+    shared: Snapshot,
+    /// Status line to show, taken by main.rs
+    pending_status: Option<String>,
+}
+
+impl SessionShare {
+    /// Listen on `addr` for a guest that sends `key` (host side, peer id 0)
+    pub fn host(addr: &str, key: String) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let status = format!("Session share: waiting on {} (key {})", listener.local_addr()?, key);
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        let _ = tx.send(Err(e));
+                        return;
+                    }
+                };
+                if handshake_ok(&stream, &key) {
+                    let _ = tx.send(Ok(stream));
+                    return;
+                }
+                log::warn!("session share: refused a connection with the wrong key");
+            }
+        });
+        Ok(Self::waiting(0, rx, status))
+    }
+
+    /// Connect to a hosting peer at `addr` with its `key` (guest side, peer id 1)
+    pub fn join(addr: &str, key: String) -> Self {
+        let status = format!("Session share: connecting to {}", addr);
+        let addr = addr.to_string();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let connected = TcpStream::connect(&addr).and_then(|mut stream| {
+                stream.write_all(format!("{}\n", key).as_bytes())?;
+                Ok(stream)
+            });
+            let _ = tx.send(connected);
+        });
+        Self::waiting(1, rx, status)
+    }
+
+    fn waiting(peer: u8, connecting: Receiver<std::io::Result<TcpStream>>, status: String) -> Self {
+        Self {
+            peer,
+            link: Link::Waiting(connecting),
+            merge: MergeState::default(),
+            shared: Snapshot::default(),
+            pending_status: Some(status),
+        }
+    }
+
+    /// Take the status line to show, if something happened
+    pub fn take_status(&mut self) -> Option<String> {
+        self.pending_status.take()
+    }
+
+    /// Finish connecting if the background thread is done. Both sides start
+    /// from the project as it is; only later edits are shared.
+    fn check_connected(&mut self, state: &AppState) {
+        let Link::Waiting(connecting) = &self.link else { return };
+        let connected = match connecting.try_recv() {
+            Ok(connected) => connected,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => Err(std::io::Error::other("connection thread ended")),
+        };
+        match connected.and_then(open) {
+            Ok((stream, incoming)) => {
+                self.link = Link::Open { stream, incoming };
+                self.shared = Snapshot::of(state);
+                self.pending_status = Some("Session share: connected".to_string());
+            }
+            Err(e) => {
+                log::error!("session share: could not connect: {}", e);
+                self.link = Link::Closed;
+                self.pending_status = Some(format!("Session share: could not connect: {}", e));
+            }
+        }
+    }
+
+    /// Send the peer whatever changed since it was last told, whichever
+    /// path dispatched it. Call before applying the peer's ops.
+    pub fn send_changes(&mut self, state: &AppState) {
+        self.check_connected(state);
+        let Link::Open { stream, .. } = &mut self.link else { return };
+        let current = Snapshot::of(state);
+        if current == self.shared {
+            return;
+        }
+        for op in self.shared.changes(&current) {
+            let clock = self.merge.stamp_local(self.peer, &op);
+            let envelope = Envelope { clock, peer: self.peer, op };
+            let Ok(mut line) = serde_json::to_string(&envelope) else { continue };
+            line.push('\n');
+            if let Err(e) = stream.write_all(line.as_bytes()) {
+                log::warn!("session share: send failed: {}", e);
+                self.link = Link::Closed;
+                self.pending_status = Some(format!("Session share: connection lost: {}", e));
+                return;
+            }
+        }
+        self.shared = current;
+    }
+
+    /// Drain remote ops that won the merge, to apply with `SharedOp::to_action`
+    pub fn poll(&mut self) -> Vec<SharedOp> {
+        let Link::Open { incoming, .. } = &self.link else { return Vec::new() };
+        let mut ops = Vec::new();
+        while let Ok(envelope) = incoming.try_recv() {
+            if self.merge.accept_remote(&envelope) {
+                ops.push(envelope.op);
+            }
+        }
+        ops
+    }
+
+    /// The peer's ops have been applied; they are not sent back
+    pub fn applied(&mut self, state: &AppState) {
+        if matches!(self.link, Link::Open { .. }) {
+            self.shared = Snapshot::of(state);
+        }
+    }
+}
+
+/// Whether the first line a guest sends is `key`
+fn handshake_ok(stream: &TcpStream, key: &str) -> bool {
+    if stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT)).is_err() {
+        return false;
+    }
+    let mut line = String::new();
+    let Ok(reader) = stream.try_clone() else { return false };
+    // Read byte by byte so nothing after the key is lost to a buffer
+    let mut bytes = std::io::Read::bytes(reader);
+    while let Some(Ok(byte)) = bytes.next() {
+        if byte == b'\n' || line.len() > 256 {
+            break;
+        }
+        line.push(byte as char);
+    }
+    stream.set_read_timeout(None).is_ok() && line.trim() == key
+}
+
+/// Start reading envelopes from a connected stream on a background thread
+fn open(stream: TcpStream) -> std::io::Result<(TcpStream, Receiver<Envelope>)> {
+    stream.set_nodelay(true)?;
+    let reader = stream.try_clone()?;
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in BufReader::new(reader).lines() {
+            let Ok(line) = line else { break };
+            match serde_json::from_str::<Envelope>(&line) {
+                Ok(envelope) => {
+                    if tx.send(envelope).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => log::warn!("session share: bad message: {}", e),
+            }
+        }
+    });
+    Ok((stream, rx))
+}
+
+/// A key for guests to connect with
+fn new_key() -> String {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u32(std::process::id());
+    format!("{:016x}", hasher.finish())
+}
+
+/// Where `--host` listens: a port on loopback, or a full address
+fn host_addr(value: Option<&str>) -> String {
+    match value {
+        Some(addr) if addr.contains(':') => addr.to_string(),
+        Some(port) => format!("127.0.0.1:{}", port.parse().unwrap_or(DEFAULT_PORT)),
+        None => format!("127.0.0.1:{}", DEFAULT_PORT),
+    }
+}
+
+/// Parse `--host[=port|=addr:port]` / `--join=addr` with `--share-key=key`
+/// from the command line
+pub fn from_args(args: &[String]) -> Option<std::io::Result<SessionShare>> {
+    let key = args.iter().find_map(|a| a.strip_prefix("--share-key=")).map(str::to_string);
+    for arg in args {
+        if arg == "--host" || arg.starts_with("--host=") {
+            let addr = host_addr(arg.strip_prefix("--host="));
+            return Some(SessionShare::host(&addr, key.unwrap_or_else(new_key)));
+        }
+        if let Some(addr) = arg.strip_prefix("--join=") {
+            let Some(key) = key else {
+                return Some(Err(std::io::Error::other("--join needs the host's --share-key=")));
+            };
+            return Some(Ok(SessionShare::join(addr, key)));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope(clock: u64, peer: u8, level: f32) -> Envelope {
+        Envelope { clock, peer, op: SharedOp::Level { target: SharedTarget::Master, level } }
+    }
+
+    #[test]
+    fn op_round_trips_through_json() {
+        let env = Envelope {
+            clock: 3,
+            peer: 1,
+            op: SharedOp::AddNote { track: 2, pitch: 60, tick: 480, duration: 240, velocity: 100 },
+        };
+        let json = serde_json::to_string(&env).unwrap();
+        assert_eq!(serde_json::from_str::<Envelope>(&json).unwrap(), env);
+    }
+
+    #[test]
+    fn newer_remote_write_wins() {
+        let mut merge = MergeState::default();
+        let op = SharedOp::Level { target: SharedTarget::Master, level: 0.5 };
+        let local = merge.stamp_local(0, &op);
+        assert!(merge.accept_remote(&envelope(local + 1, 1, 0.8)));
+    }
+
+    #[test]
+    fn stale_remote_write_is_dropped() {
+        let mut merge = MergeState::default();
+        let op = SharedOp::Level { target: SharedTarget::Master, level: 0.5 };
+        merge.stamp_local(0, &op);
+        merge.stamp_local(0, &op);
+        assert!(!merge.accept_remote(&envelope(1, 1, 0.8)));
+    }
+
+    #[test]
+    fn concurrent_writes_break_ties_by_peer() {
+        let mut merge = MergeState::default();
+        let op = SharedOp::Level { target: SharedTarget::Master, level: 0.5 };
+        let clock = merge.stamp_local(0, &op);
+        // Same clock from the guest (peer 1) beats the host
+        assert!(merge.accept_remote(&envelope(clock, 1, 0.8)));
+    }
+
+    #[test]
+    fn notes_always_apply() {
+        let mut merge = MergeState::default();
+        let env = Envelope {
+            clock: 0,
+            peer: 1,
+            op: SharedOp::AddNote { track: 0, pitch: 60, tick: 0, duration: 480, velocity: 100 },
+        };
+        merge.stamp_local(0, &SharedOp::Mute { target: SharedTarget::Master, mute: true });
+        assert!(merge.accept_remote(&env));
+    }
+
+    #[test]
+    fn changed_notes_are_removed_then_added() {
+        let mut old = Snapshot::default();
+        old.notes.insert((0, 60, 0), (480, 100));
+        old.notes.insert((0, 64, 480), (480, 100));
+        let mut new = old.clone();
+        new.notes.remove(&(0, 64, 480));
+        new.notes.insert((0, 60, 0), (240, 100));
+        new.notes.insert((1, 67, 960), (120, 90));
+
+        let ops = old.changes(&new);
+        assert_eq!(ops.len(), 4);
+        let first_add = ops.iter().position(|op| matches!(op, SharedOp::AddNote { .. })).unwrap();
+        assert!(ops[..first_add].iter().all(|op| matches!(op, SharedOp::RemoveNote { .. })));
+        assert!(ops.contains(&SharedOp::RemoveNote { track: 0, pitch: 60, tick: 0 }));
+        assert!(ops.contains(&SharedOp::AddNote { track: 1, pitch: 67, tick: 960, duration: 120, velocity: 90 }));
+        assert!(new.changes(&new).is_empty());
+    }
+
+    #[test]
+    fn mixer_changes_are_sent_as_values() {
+        let state = AppState::new();
+        let old = Snapshot::of(&state);
+        let mut new = old.clone();
+        if let Some(master) = new.channels.get_mut(&SharedTarget::Master) {
+            master.level = 0.25;
+            master.mute = !master.mute;
+        }
+        let ops = old.changes(&new);
+        assert_eq!(ops.len(), 2);
+        assert!(ops.contains(&SharedOp::Level { target: SharedTarget::Master, level: 0.25 }));
+    }
+
+    #[test]
+    fn note_ops_only_act_when_they_change_something() {
+        let state = AppState::new();
+        assert!(SharedOp::RemoveNote { track: 0, pitch: 60, tick: 0 }.to_action(&state).is_none());
+        let add = SharedOp::AddNote { track: 0, pitch: 60, tick: 0, duration: 480, velocity: 100 };
+        assert!(matches!(add.to_action(&state), Some(Action::PianoRoll(PianoRollAction::ToggleNote { .. }))));
+    }
+
+    #[test]
+    fn host_listens_on_loopback_unless_given_an_address() {
+        assert_eq!(host_addr(None), "127.0.0.1:7878");
+        assert_eq!(host_addr(Some("9000")), "127.0.0.1:9000");
+        assert_eq!(host_addr(Some("0.0.0.0:9000")), "0.0.0.0:9000");
+    }
+
+    #[test]
+    fn guest_with_the_key_is_accepted() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let guest = std::thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream.write_all(b"secret\n{}\n").unwrap();
+            stream
+        });
+        let (stream, _) = listener.accept().unwrap();
+        assert!(handshake_ok(&stream, "secret"));
+        // What follows the key is left for the reader
+        let mut rest = String::new();
+        BufReader::new(&stream).read_line(&mut rest).unwrap();
+        assert_eq!(rest, "{}\n");
+        guest.join().unwrap();
+    }
+
+    #[test]
+    fn parses_share_flags() {
+        assert!(from_args(&["imbolc".into(), "song.sqlite".into()]).is_none());
+        assert!(matches!(from_args(&["imbolc".into(), "--join=host:7878".into()]), Some(Err(_))));
+    }
+}
@@ -6,6 +6,7 @@ use crate::ui::StatusEvent;
 /// Returns status events for the UI layer to forward to the server pane.
 pub fn auto_start_sc(
     audio: &mut AudioHandle,
+    server_address: &str,
 ) -> Vec<StatusEvent> {
     if std::env::var("IMBOLC_NO_AUDIO").is_ok() {
         return Vec::new();
@@ -26,7 +27,7 @@ pub fn auto_start_sc(
                 message: "Server started".to_string(),
                 server_running: Some(true),
             });
-            match audio.connect("127.0.0.1:57110") {
+            match audio.connect(server_address) {
                 Ok(()) => {
                     events.push(StatusEvent {
                         status: audio::ServerStatus::Connected,
@@ -0,0 +1,177 @@
+//! Tempo and downbeat estimation for recorded audio.
+//!
+//! Builds an onset-strength envelope from positive changes in log energy,
+//! autocorrelates it over plausible beat periods, then finds the beat phase
+//! and which beat of the bar carries the strongest accents.
+
+use std::path::Path;
+
+use crate::audio_to_midi::read_mono;
+
+/// Envelope resolution: ~11.6ms per frame at 11025 Hz
+const HOP: usize = 128;
+const ANALYSIS_RATE: u32 = 11025;
+const MIN_BPM: f32 = 60.0;
+const MAX_BPM: f32 = 200.0;
+/// Estimates are folded into this range to avoid half/double-time answers
+const PREFERRED_MIN_BPM: f32 = 75.0;
+const PREFERRED_MAX_BPM: f32 = 170.0;
+
+/// Result of a tempo analysis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TempoEstimate {
+    pub bpm: f32,
+    /// Time of the first detected beat, in seconds
+    pub first_beat: f32,
+    /// Time of the first downbeat (beat 1 of a bar), in seconds
+    pub first_downbeat: f32,
+}
+
+/// Onset strength per frame and the frame rate in Hz
+fn onset_envelope(samples: &[f32], rate: u32) -> (Vec<f32>, f32) {
+    let factor = (rate / ANALYSIS_RATE).max(1) as usize;
+    let hop = HOP * factor;
+    let energy: Vec<f32> = samples
+        .chunks(hop)
+        .map(|c| (c.iter().map(|s| s * s).sum::<f32>() / c.len() as f32 + 1e-9).ln())
+        .collect();
+    let mut onsets = vec![0.0; energy.len()];
+    for i in 1..energy.len() {
+        onsets[i] = (energy[i] - energy[i - 1]).max(0.0);
+    }
+    (onsets, rate as f32 / hop as f32)
+}
+
+fn autocorrelation(env: &[f32], lag: usize) -> f32 {
+    env.iter().zip(&env[lag..]).map(|(a, b)| a * b).sum::<f32>() / (env.len() - lag) as f32
+}
+
+/// Sum of onset strength at `phase + k * period`
+fn comb_sum(env: &[f32], period: f32, phase: f32) -> f32 {
+    let mut sum = 0.0;
+    let mut t = phase;
+    while let Some(v) = env.get(t.round() as usize) {
+        sum += v;
+        t += period;
+    }
+    sum
+}
+
+/// Estimate tempo and downbeat from mono samples, in bars of `beats_per_bar`
+pub fn estimate_tempo(samples: &[f32], rate: u32, beats_per_bar: usize) -> Option<TempoEstimate> {
+    let beats_per_bar = beats_per_bar.max(1);
+    let (env, frame_rate) = onset_envelope(samples, rate);
+    let min_lag = (frame_rate * 60.0 / MAX_BPM).floor() as usize;
+    let max_lag = (frame_rate * 60.0 / MIN_BPM).ceil() as usize;
+    // Need a few beats at the slowest tempo
+    if env.len() < max_lag * 4 || min_lag < 1 {
+        return None;
+    }
+
+    let scores: Vec<f32> = (0..=max_lag + 1).map(|lag| if lag >= min_lag { autocorrelation(&env, lag) } else { 0.0 }).collect();
+    let best = (min_lag..=max_lag).max_by(|a, b| scores[*a].total_cmp(&scores[*b]))?;
+    if scores[best] <= 0.0 {
+        return None;
+    }
+    // Parabolic refinement of the peak lag
+    let (a, b, c) = (scores[best - 1], scores[best], scores[best + 1]);
+    let denom = a - 2.0 * b + c;
+    let offset = if denom.abs() > f32::EPSILON { (0.5 * (a - c) / denom).clamp(-0.5, 0.5) } else { 0.0 };
+    let period = best as f32 + offset;
+
+    let mut bpm = 60.0 * frame_rate / period;
+    while bpm < PREFERRED_MIN_BPM {
+        bpm *= 2.0;
+    }
+    while bpm > PREFERRED_MAX_BPM {
+        bpm /= 2.0;
+    }
+    let beat_period = 60.0 * frame_rate / bpm;
+
+    // Beat phase: the offset within one period that lines up with the most onsets
+    let steps = beat_period.ceil() as usize;
+    let phase = (0..steps)
+        .map(|p| p as f32)
+        .max_by(|a, b| comb_sum(&env, beat_period, *a).total_cmp(&comb_sum(&env, beat_period, *b)))?;
+
+    // Downbeat: which beat of the bar has the strongest accents
+    let bar_period = beat_period * beats_per_bar as f32;
+    let downbeat = (0..beats_per_bar)
+        .map(|beat| phase + beat as f32 * beat_period)
+        .max_by(|a, b| comb_sum(&env, bar_period, *a).total_cmp(&comb_sum(&env, bar_period, *b)))?;
+
+    Some(TempoEstimate {
+        bpm: (bpm * 10.0).round() / 10.0,
+        first_beat: phase / frame_rate,
+        first_downbeat: downbeat / frame_rate,
+    })
+}
+
+/// Analyze a WAV file's tempo, in bars of `beats_per_bar`
+pub fn analyze_file(path: &Path, beats_per_bar: usize) -> Result<TempoEstimate, String> {
+    let (samples, rate) = read_mono(path)?;
+    estimate_tempo(&samples, rate, beats_per_bar).ok_or_else(|| "no steady beat found".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Click track with accented downbeats every `beats_per_bar` clicks
+    fn clicks(bpm: f32, offset: f32, secs: f32, rate: u32, beats_per_bar: usize) -> Vec<f32> {
+        let mut samples = vec![0.0; (secs * rate as f32) as usize];
+        let period = 60.0 / bpm;
+        let mut beat = 0;
+        let mut t = offset;
+        while t < secs {
+            let start = (t * rate as f32) as usize;
+            let amp = if beat % beats_per_bar == 0 { 1.0 } else { 0.4 };
+            for (i, s) in samples.iter_mut().skip(start).take(rate as usize / 50).enumerate() {
+                *s = amp * (i as f32 * 0.3).sin() * (1.0 - i as f32 / (rate as f32 / 50.0));
+            }
+            beat += 1;
+            t += period;
+        }
+        samples
+    }
+
+    #[test]
+    fn detects_click_tempo() {
+        let est = estimate_tempo(&clicks(120.0, 0.0, 12.0, 44100, 4), 44100, 4).unwrap();
+        assert!((est.bpm - 120.0).abs() < 1.5, "got {}", est.bpm);
+    }
+
+    #[test]
+    fn folds_into_preferred_range() {
+        let est = estimate_tempo(&clicks(200.0, 0.0, 12.0, 44100, 4), 44100, 4).unwrap();
+        assert!(est.bpm >= PREFERRED_MIN_BPM && est.bpm <= PREFERRED_MAX_BPM, "got {}", est.bpm);
+    }
+
+    #[test]
+    fn finds_accented_downbeat() {
+        // First click is beat 4 of a bar, so the downbeat comes one beat later
+        let mut samples = clicks(120.0, 0.1, 12.0, 44100, 4);
+        let lead = clicks(120.0, 0.0, 0.5, 44100, 4);
+        let quiet: Vec<f32> = lead.iter().map(|s| s * 0.4).collect();
+        samples.splice(0..0, quiet);
+        let est = estimate_tempo(&samples, 44100, 4).unwrap();
+        assert!((est.first_downbeat - 0.6).abs() < 0.05, "got {}", est.first_downbeat);
+        assert!(est.first_beat < 0.15, "got {}", est.first_beat);
+    }
+
+    #[test]
+    fn finds_downbeat_in_three_four() {
+        // Beat 3 of a bar leads in; counting in fours would put the downbeat at 1.6
+        let mut samples = clicks(120.0, 0.0, 12.0, 44100, 3);
+        let lead = clicks(120.0, 0.1, 0.6, 44100, 3);
+        let quiet: Vec<f32> = lead.iter().map(|s| s * 0.4).collect();
+        samples.splice(0..0, quiet);
+        let est = estimate_tempo(&samples, 44100, 3).unwrap();
+        assert!((est.first_downbeat - 0.6).abs() < 0.05, "got {}", est.first_downbeat);
+    }
+
+    #[test]
+    fn silence_has_no_tempo() {
+        assert!(estimate_tempo(&vec![0.0; 44100 * 5], 44100, 4).is_none());
+    }
+}
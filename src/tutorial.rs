@@ -0,0 +1,178 @@
+//! First-run tutorial: a small overlay that walks through adding an
+//! instrument, playing it, entering notes and playing them back.
+//!
+//! Steps advance on their own as the user does each thing. main.rs feeds
+//! the tutorial every pane action and ticks it with the state each frame,
+//! since some steps (such as starting playback) are global actions that
+//! never reach a pane.
+
+use crate::action::{Action, InstrumentAction, PianoRollAction};
+use crate::panes::PreferencesPane;
+use crate::preferences::Preferences;
+use crate::state::AppState;
+use crate::ui::{Color, PaneManager, Rect, RenderBuf, Style};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    AddInstrument,
+    PlayNote,
+    EnterNotes,
+    PlayBack,
+    Done,
+}
+
+impl Step {
+    fn next(self) -> Self {
+        match self {
+            Step::AddInstrument => Step::PlayNote,
+            Step::PlayNote => Step::EnterNotes,
+            Step::EnterNotes => Step::PlayBack,
+            Step::PlayBack | Step::Done => Step::Done,
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Step::AddInstrument => "Add an instrument",
+            Step::PlayNote => "Play it",
+            Step::EnterNotes => "Enter some notes",
+            Step::PlayBack => "Play them back",
+            Step::Done => "All done",
+        }
+    }
+
+    fn lines(self) -> &'static [&'static str] {
+        match self {
+            Step::AddInstrument => &["Press Ctrl+n, pick a source", "such as Saw and press Enter"],
+            Step::PlayNote => &["Press / for the piano keyboard", "and play a few letter keys"],
+            Step::EnterNotes => &["F2 opens the piano roll. Move", "with the arrows, Enter places a note"],
+            Step::PlayBack => &["Press Space to play,", "and Space again to stop"],
+            Step::Done => &["? shows keys and guides", "in any pane. F12 closes this"],
+        }
+    }
+}
+
+/// Steps the user completes; `Done` is only a closing message
+const STEP_COUNT: usize = 4;
+
+pub struct Tutorial {
+    step: Step,
+    /// Instruments when the tutorial started, so existing ones don't count
+    start_instruments: usize,
+}
+
+impl Tutorial {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            step: Step::AddInstrument,
+            start_instruments: state.instruments.instruments.len(),
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.step == Step::Done
+    }
+
+    /// Advance on an action the user caused
+    pub fn observe(&mut self, action: &Action) {
+        let completed = match (self.step, action) {
+            (Step::PlayNote, Action::Instrument(InstrumentAction::PlayNote(..))) => true,
+            (Step::PlayNote, Action::PianoRoll(PianoRollAction::PlayNote { .. })) => true,
+            (Step::EnterNotes, Action::PianoRoll(PianoRollAction::ToggleNote { .. })) => true,
+            _ => false,
+        };
+        if completed {
+            self.step = self.step.next();
+        }
+    }
+
+    /// Advance on state the user changed
+    pub fn tick(&mut self, state: &AppState) {
+        let completed = match self.step {
+            Step::AddInstrument => state.instruments.instruments.len() > self.start_instruments,
+            Step::PlayBack => state.session.piano_roll.playing,
+            _ => false,
+        };
+        if completed {
+            self.step = self.step.next();
+        }
+    }
+
+    /// Overlay in the top-right corner of `area`
+    pub fn render(&self, area: Rect, buf: &mut RenderBuf) {
+        let width = 40;
+        if area.width < width + 2 || area.height < 8 {
+            return;
+        }
+        let rect = Rect::new(area.x + area.width - width - 1, area.y + 1, width, 6);
+        let title = match self.step {
+            Step::Done => " Tutorial ".to_string(),
+            step => format!(" Tutorial {}/{} ", step as usize + 1, STEP_COUNT),
+        };
+
+        // Clear background
+        let bg_style = Style::new().bg(Color::new(20, 20, 30));
+        for y in rect.y..rect.y + rect.height {
+            for x in rect.x..rect.x + rect.width {
+                buf.set_cell(x, y, ' ', bg_style);
+            }
+        }
+
+        let border_style = Style::new().fg(Color::GOLD);
+        let inner = buf.draw_block(rect, &title, border_style, border_style);
+
+        buf.draw_line(Rect::new(inner.x + 1, inner.y, inner.width.saturating_sub(2), 1),
+            &[(self.step.title(), Style::new().fg(Color::GOLD).bold())]);
+        for (i, line) in self.step.lines().iter().enumerate() {
+            buf.draw_line(Rect::new(inner.x + 1, inner.y + 1 + i as u16, inner.width.saturating_sub(2), 1),
+                &[(line, Style::new().fg(Color::WHITE))]);
+        }
+        if self.step != Step::Done {
+            buf.draw_line(Rect::new(inner.x + 1, inner.y + inner.height - 1, inner.width.saturating_sub(2), 1),
+                &[("F12: skip tutorial", Style::new().fg(Color::DARK_GRAY))]);
+        }
+    }
+}
+
+/// Remember that the tutorial was finished or skipped
+pub(crate) fn mark_seen(prefs: &mut Preferences, panes: &mut PaneManager) {
+    if prefs.tutorial_seen {
+        return;
+    }
+    prefs.tutorial_seen = true;
+    if let Err(e) = prefs.save() {
+        log::error!("preferences: could not save: {}", e);
+    }
+    if let Some(pane) = panes.get_pane_mut::<PreferencesPane>("preferences") {
+        pane.set_tutorial_seen();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::SourceType;
+
+    #[test]
+    fn steps_advance_as_the_user_goes() {
+        let mut state = AppState::new();
+        let mut tutorial = Tutorial::new(&state);
+
+        // Notes don't count before there's an instrument
+        tutorial.observe(&Action::Instrument(InstrumentAction::PlayNote(60, 100)));
+        tutorial.tick(&state);
+        assert_eq!(tutorial.step, Step::AddInstrument);
+
+        state.add_instrument(SourceType::Saw);
+        tutorial.tick(&state);
+        tutorial.observe(&Action::Instrument(InstrumentAction::PlayNote(60, 100)));
+        assert_eq!(tutorial.step, Step::EnterNotes);
+
+        tutorial.observe(&Action::PianoRoll(PianoRollAction::ToggleNote {
+            pitch: 60, tick: 0, duration: 480, velocity: 100, track: 0,
+        }));
+        state.session.piano_roll.playing = true;
+        tutorial.tick(&state);
+        assert!(tutorial.is_done());
+    }
+}
@@ -61,6 +61,7 @@ pub enum GlobalActionId {
     Copy,
     Cut,
     Paste,
+    PasteSystem,
     SelectAll,
     AddInstrument,
     DeleteInstrument,
@@ -76,8 +77,25 @@ pub enum GlobalActionId {
     SelectTwoDigit,
     PlayStop,
     RefreshScreen,
+    RunScript,
+    ImportMidi,
+    ExportMidi,
+    Preferences,
+    UndoHistory,
+    ProjectCheck,
+    WhySilent,
+    Latency,
+    Tutorial,
+    Console,
+    Sclang,
+    ProjectSearch,
+    BatchRename,
+    RenderQueue,
+    Panic,
+    SaveWorkspace,
     SwitchPane(PaneId),
     SelectInstrument(u8), // 1-10
+    Workspace(u8), // 1-3
 }
 
 impl GlobalActionId {
@@ -94,6 +112,7 @@ impl GlobalActionId {
             GlobalActionId::Copy => "copy",
             GlobalActionId::Cut => "cut",
             GlobalActionId::Paste => "paste",
+            GlobalActionId::PasteSystem => "paste_system",
             GlobalActionId::SelectAll => "select_all",
             GlobalActionId::AddInstrument => "add_instrument",
             GlobalActionId::DeleteInstrument => "delete_instrument",
@@ -109,6 +128,22 @@ impl GlobalActionId {
             GlobalActionId::SelectNextInstrument => "select_next_instrument",
             GlobalActionId::SelectTwoDigit => "select_two_digit",
             GlobalActionId::RefreshScreen => "refresh_screen",
+            GlobalActionId::RunScript => "run_script",
+            GlobalActionId::ImportMidi => "import_midi",
+            GlobalActionId::ExportMidi => "export_midi",
+            GlobalActionId::Preferences => "preferences",
+            GlobalActionId::UndoHistory => "undo_history",
+            GlobalActionId::ProjectCheck => "project_check",
+            GlobalActionId::WhySilent => "why_silent",
+            GlobalActionId::Latency => "latency",
+            GlobalActionId::Tutorial => "tutorial",
+            GlobalActionId::Console => "console",
+            GlobalActionId::Sclang => "sclang",
+            GlobalActionId::ProjectSearch => "project_search",
+            GlobalActionId::BatchRename => "batch_rename",
+            GlobalActionId::RenderQueue => "render_queue",
+            GlobalActionId::Panic => "panic",
+            GlobalActionId::SaveWorkspace => "save_workspace",
             GlobalActionId::SwitchPane(pane) => match pane {
                 PaneId::InstrumentEdit => "switch:instrument",
                 PaneId::InstrumentList => "switch:instrument_list",
@@ -134,6 +169,12 @@ impl GlobalActionId {
                 10 => "select:10",
                 _ => "select:invalid",
             },
+            GlobalActionId::Workspace(n) => match n {
+                1 => "workspace:1",
+                2 => "workspace:2",
+                3 => "workspace:3",
+                _ => "workspace:invalid",
+            },
         }
     }
 
@@ -150,6 +191,7 @@ impl GlobalActionId {
             "copy" => Some(GlobalActionId::Copy),
             "cut" => Some(GlobalActionId::Cut),
             "paste" => Some(GlobalActionId::Paste),
+            "paste_system" => Some(GlobalActionId::PasteSystem),
             "select_all" => Some(GlobalActionId::SelectAll),
             "add_instrument" => Some(GlobalActionId::AddInstrument),
             "delete_instrument" => Some(GlobalActionId::DeleteInstrument),
@@ -165,6 +207,22 @@ impl GlobalActionId {
             "select_next_instrument" => Some(GlobalActionId::SelectNextInstrument),
             "select_two_digit" => Some(GlobalActionId::SelectTwoDigit),
             "refresh_screen" => Some(GlobalActionId::RefreshScreen),
+            "run_script" => Some(GlobalActionId::RunScript),
+            "import_midi" => Some(GlobalActionId::ImportMidi),
+            "export_midi" => Some(GlobalActionId::ExportMidi),
+            "preferences" => Some(GlobalActionId::Preferences),
+            "undo_history" => Some(GlobalActionId::UndoHistory),
+            "project_check" => Some(GlobalActionId::ProjectCheck),
+            "why_silent" => Some(GlobalActionId::WhySilent),
+            "latency" => Some(GlobalActionId::Latency),
+            "tutorial" => Some(GlobalActionId::Tutorial),
+            "console" => Some(GlobalActionId::Console),
+            "sclang" => Some(GlobalActionId::Sclang),
+            "project_search" => Some(GlobalActionId::ProjectSearch),
+            "batch_rename" => Some(GlobalActionId::BatchRename),
+            "render_queue" => Some(GlobalActionId::RenderQueue),
+            "panic" => Some(GlobalActionId::Panic),
+            "save_workspace" => Some(GlobalActionId::SaveWorkspace),
             "switch:instrument" => Some(GlobalActionId::SwitchPane(PaneId::InstrumentEdit)),
             "switch:instrument_list" => Some(GlobalActionId::SwitchPane(PaneId::InstrumentList)),
             "switch:piano_roll_or_sequencer" => {
@@ -187,6 +245,9 @@ impl GlobalActionId {
             "select:8" => Some(GlobalActionId::SelectInstrument(8)),
             "select:9" => Some(GlobalActionId::SelectInstrument(9)),
             "select:10" => Some(GlobalActionId::SelectInstrument(10)),
+            "workspace:1" => Some(GlobalActionId::Workspace(1)),
+            "workspace:2" => Some(GlobalActionId::Workspace(2)),
+            "workspace:3" => Some(GlobalActionId::Workspace(3)),
             _ => None,
         }
     }
@@ -207,6 +268,11 @@ define_action_enum! {
         Load => "load",
         LinkLayer => "link_layer",
         UnlinkLayer => "unlink_layer",
+        Rename => "rename",
+        Duplicate => "duplicate",
+        DuplicateWithNotes => "duplicate_with_notes",
+        MoveUp => "move_up",
+        MoveDown => "move_down",
     }
 }
 
@@ -240,6 +306,12 @@ define_action_enum! {
         ToggleActive => "toggle_active",
         LoadSample => "load_sample",
         VstParams => "vst_params",
+        RandomLooper => "random_looper",
+        ExportScd => "export_scd",
+        OffsetEarlier => "offset_earlier",
+        OffsetLater => "offset_later",
+        OffsetEarlierBig => "offset_earlier_big",
+        OffsetLaterBig => "offset_later_big",
         Done => "done",
     }
 }
@@ -280,6 +352,20 @@ define_action_enum! {
         ClearSend => "clear_send",
         Increase => "increase",
         Decrease => "decrease",
+        AutomationMode => "automation_mode",
+        RenameBus => "rename_bus",
+        TypeLevel => "type_level",
+        Zoom => "zoom",
+        Jump => "jump",
+        BusColor => "bus_color",
+        BypassChain => "bypass_chain",
+        GainMatch => "gain_match",
+        CopyChannel => "copy_channel",
+        PasteChannel => "paste_channel",
+        Routing => "routing",
+        SoloSafe => "solo_safe",
+        MomentarySolo => "momentary_solo",
+        ExclusiveSolo => "exclusive_solo",
     }
 }
 
@@ -317,6 +403,28 @@ define_action_enum! {
         RenderToWav => "render_to_wav",
         BounceToWav => "bounce_to_wav",
         ExportStems => "export_stems",
+        RecordSettings => "record_settings",
+        Takes => "takes",
+        Generate => "generate",
+        AudioToMidi => "audio_to_midi",
+        SwingDown => "swing_down",
+        SwingUp => "swing_up",
+        TransposeUp => "transpose_up",
+        TransposeDown => "transpose_down",
+        NudgeLeft => "nudge_left",
+        NudgeRight => "nudge_right",
+        DeleteRange => "delete_range",
+        ToggleLink => "toggle_link",
+        ClearLinks => "clear_links",
+        Audition => "audition",
+        ToggleScrub => "toggle_scrub",
+        Tracker => "tracker",
+        PasteRepeat => "paste_repeat",
+        Quantize => "quantize",
+        ToggleVelocityLane => "toggle_velocity_lane",
+        VelocityRamp => "velocity_ramp",
+        ToggleGhosts => "toggle_ghosts",
+        ScaleMode => "scale_mode",
     }
 }
 
@@ -351,6 +459,27 @@ define_action_enum! {
         VelDown => "vel_down",
         StepPitchUp => "step_pitch_up",
         StepPitchDown => "step_pitch_down",
+        SwingDown => "swing_down",
+        SwingUp => "swing_up",
+        CyclePadRecord => "cycle_pad_record",
+        NudgeEarlier => "nudge_earlier",
+        NudgeLater => "nudge_later",
+        AddRoundRobin => "add_round_robin",
+        ClearRoundRobin => "clear_round_robin",
+        StartJitterDown => "start_jitter_down",
+        StartJitterUp => "start_jitter_up",
+        PitchJitterDown => "pitch_jitter_down",
+        PitchJitterUp => "pitch_jitter_up",
+        CycleFilter => "cycle_filter",
+        CutoffDown => "cutoff_down",
+        CutoffUp => "cutoff_up",
+        DriveDown => "drive_down",
+        DriveUp => "drive_up",
+        Roll => "roll",
+        FilterSweep => "filter_sweep",
+        BouncePattern => "bounce_pattern",
+        BounceAllPatterns => "bounce_all_patterns",
+        ImportPattern => "import_pattern",
     }
 }
 
@@ -377,6 +506,10 @@ define_action_enum! {
         Cancel => "cancel",
         Next => "next",
         Prev => "prev",
+        ToggleStar => "toggle_star",
+        RateUp => "rate_up",
+        RateDown => "rate_down",
+        FavoritesOnly => "favorites_only",
     }
 }
 
@@ -398,6 +531,8 @@ define_action_enum! {
         Down => "down",
         Top => "top",
         Bottom => "bottom",
+        SwitchTab => "switch_tab",
+        Search => "search",
     }
 }
 
@@ -410,6 +545,73 @@ define_action_enum! {
         Increase => "increase",
         Confirm => "confirm",
         Cancel => "cancel",
+        DetectTempo => "detect_tempo",
+        AlignTempo => "align_tempo",
+        ImportMidi => "import_midi",
+        SaveDefaults => "save_defaults",
+        TypeValue => "type_value",
+    }
+}
+
+define_action_enum! {
+    /// Record settings popup actions
+    pub enum RecordSettingsActionId {
+        Prev => "prev",
+        Next => "next",
+        Decrease => "decrease",
+        Increase => "increase",
+        Confirm => "confirm",
+        Cancel => "cancel",
+    }
+}
+
+define_action_enum! {
+    /// Export dialog actions
+    pub enum ExportActionId {
+        Prev => "prev",
+        Next => "next",
+        Decrease => "decrease",
+        Increase => "increase",
+        Confirm => "confirm",
+        Cancel => "cancel",
+    }
+}
+
+define_action_enum! {
+    /// Batch pattern export progress actions
+    pub enum BatchExportActionId {
+        Up => "up",
+        Down => "down",
+        Cancel => "cancel",
+        Close => "close",
+    }
+}
+
+define_action_enum! {
+    /// Render queue actions
+    pub enum RenderQueueActionId {
+        Up => "up",
+        Down => "down",
+        Cancel => "cancel",
+        ClearFinished => "clear_finished",
+        Close => "close",
+    }
+}
+
+define_action_enum! {
+    /// Take comping layer actions
+    pub enum CompActionId {
+        Up => "up",
+        Down => "down",
+        Left => "left",
+        Right => "right",
+        SelectLeft => "select_left",
+        SelectRight => "select_right",
+        UseRegion => "use_region",
+        UseTake => "use_take",
+        DeleteTake => "delete_take",
+        Flatten => "flatten",
+        Close => "close",
     }
 }
 
@@ -425,6 +627,11 @@ define_action_enum! {
         GotoTop => "goto_top",
         GotoBottom => "goto_bottom",
         ToggleHidden => "toggle_hidden",
+        Library => "library",
+        ToggleStar => "toggle_star",
+        RateUp => "rate_up",
+        RateDown => "rate_down",
+        FavoritesOnly => "favorites_only",
     }
 }
 
@@ -529,6 +736,17 @@ define_action_enum! {
         PlacePoint => "place_point",
         DeletePoint => "delete_point",
         CycleCurve => "cycle_curve",
+        CycleCurveBack => "cycle_curve_back",
+        SelectLeft => "select_left",
+        SelectRight => "select_right",
+        ShiftLeft => "shift_left",
+        ShiftRight => "shift_right",
+        ScaleUp => "scale_up",
+        ScaleDown => "scale_down",
+        Stretch => "stretch",
+        Compress => "compress",
+        Thin => "thin",
+        Generate => "generate",
         ClearLane => "clear_lane",
         ToggleRecording => "toggle_recording",
         ToggleArm => "toggle_arm",
@@ -589,6 +807,12 @@ define_action_enum! {
         SelectPrevPlacement => "select_prev_placement",
         SelectPrevClip => "select_prev_clip",
         SelectNextClip => "select_next_clip",
+        FadeInShorter => "fade_in_shorter",
+        FadeInLonger => "fade_in_longer",
+        FadeOutShorter => "fade_out_shorter",
+        FadeOutLonger => "fade_out_longer",
+        Inspect => "inspect",
+        TimeEdit => "time_edit",
     }
 }
 
@@ -620,6 +844,7 @@ define_action_enum! {
     /// Waveform layer actions
     pub enum WaveformActionId {
         CycleMode => "cycle_mode",
+        ToggleHighRes => "toggle_high_res",
     }
 }
 
@@ -636,6 +861,7 @@ define_action_enum! {
         SetChannelAll => "set_channel_all",
         SetLiveInstrument => "set_live_instrument",
         ClearLiveInstrument => "clear_live_instrument",
+        Monitor => "monitor",
     }
 }
 
@@ -679,6 +905,242 @@ define_action_enum! {
     }
 }
 
+define_action_enum! {
+    /// Melody generator popup actions
+    pub enum NoteGeneratorActionId {
+        Prev => "prev",
+        Next => "next",
+        Decrease => "decrease",
+        Increase => "increase",
+        Reroll => "reroll",
+        Write => "write",
+        Close => "close",
+    }
+}
+
+define_action_enum! {
+    /// Quantize popup actions
+    pub enum QuantizeActionId {
+        Prev => "prev",
+        Next => "next",
+        Decrease => "decrease",
+        Increase => "increase",
+        Apply => "apply",
+        Close => "close",
+    }
+}
+
+define_action_enum! {
+    /// Random looper popup actions
+    pub enum RandomLooperActionId {
+        Prev => "prev",
+        Next => "next",
+        Decrease => "decrease",
+        Increase => "increase",
+        Reseed => "reseed",
+        Close => "close",
+    }
+}
+
+define_action_enum! {
+    /// Clip inspector layer actions
+    pub enum ClipInspectorActionId {
+        Prev => "prev",
+        Next => "next",
+        Decrease => "decrease",
+        Increase => "increase",
+        Reset => "reset",
+        Close => "close",
+    }
+}
+
+define_action_enum! {
+    /// Preferences pane actions
+    pub enum PreferencesActionId {
+        Prev => "prev",
+        Next => "next",
+        Decrease => "decrease",
+        Increase => "increase",
+        Edit => "edit",
+        Close => "close",
+    }
+}
+
+define_action_enum! {
+    /// MIDI monitor layer actions
+    pub enum MidiMonitorActionId {
+        Freeze => "freeze",
+        Up => "up",
+        Down => "down",
+        PageUp => "page_up",
+        PageDown => "page_down",
+        Clear => "clear",
+        Close => "close",
+    }
+}
+
+define_action_enum! {
+    /// Console message history actions
+    pub enum ConsoleActionId {
+        Up => "up",
+        Down => "down",
+        PageUp => "page_up",
+        PageDown => "page_down",
+        Top => "top",
+        Bottom => "bottom",
+        Copy => "copy",
+        Clear => "clear",
+        Close => "close",
+    }
+}
+
+define_action_enum! {
+    /// Undo history layer actions
+    pub enum UndoHistoryActionId {
+        Up => "up",
+        Down => "down",
+        Top => "top",
+        Bottom => "bottom",
+        Current => "current",
+        Jump => "jump",
+        Close => "close",
+    }
+}
+
+define_action_enum! {
+    /// Insert/delete time popup actions
+    pub enum TimeEditActionId {
+        Prev => "prev",
+        Next => "next",
+        Decrease => "decrease",
+        Increase => "increase",
+        Apply => "apply",
+        Close => "close",
+    }
+}
+
+define_action_enum! {
+    /// Project check layer actions
+    pub enum ProjectCheckActionId {
+        Up => "up",
+        Down => "down",
+        Fix => "fix",
+        Close => "close",
+    }
+}
+
+define_action_enum! {
+    /// Channel paste layer actions
+    pub enum ChannelPasteActionId {
+        Up => "up",
+        Down => "down",
+        Toggle => "toggle",
+        All => "all",
+        Apply => "apply",
+        Close => "close",
+    }
+}
+
+define_action_enum! {
+    /// Routing diagram layer actions
+    pub enum RoutingActionId {
+        Up => "up",
+        Down => "down",
+        Close => "close",
+    }
+}
+
+define_action_enum! {
+    /// Diagnostics layer actions
+    pub enum DiagnoseActionId {
+        Close => "close",
+    }
+}
+
+define_action_enum! {
+    /// Latency and timing diagnostics layer actions
+    pub enum LatencyActionId {
+        Reset => "reset",
+        Close => "close",
+    }
+}
+
+define_action_enum! {
+    /// Tracker view layer actions
+    pub enum TrackerActionId {
+        Up => "up",
+        Down => "down",
+        Left => "left",
+        Right => "right",
+        PageUp => "page_up",
+        PageDown => "page_down",
+        Home => "home",
+        End => "end",
+        NextTrack => "next_track",
+        PrevTrack => "prev_track",
+        Key => "key",
+        Clear => "clear",
+        OctaveUp => "octave_up",
+        OctaveDown => "octave_down",
+        StepUp => "step_up",
+        StepDown => "step_down",
+        ZoomIn => "zoom_in",
+        ZoomOut => "zoom_out",
+        Close => "close",
+    }
+}
+
+define_action_enum! {
+    /// Sample library search layer actions
+    pub enum SampleLibraryActionId {
+        Up => "up",
+        Down => "down",
+        PageUp => "page_up",
+        PageDown => "page_down",
+        Select => "select",
+        Rescan => "rescan",
+        ToggleStar => "toggle_star",
+        RateUp => "rate_up",
+        RateDown => "rate_down",
+        FavoritesOnly => "favorites_only",
+        Close => "close",
+    }
+}
+
+define_action_enum! {
+    /// sclang REPL layer actions
+    pub enum SclangActionId {
+        Send => "send",
+        HistoryPrev => "history_prev",
+        HistoryNext => "history_next",
+        PageUp => "page_up",
+        PageDown => "page_down",
+        Stop => "stop",
+        Clear => "clear",
+        Close => "close",
+    }
+}
+
+define_action_enum! {
+    /// Project search layer actions
+    pub enum ProjectSearchActionId {
+        Up => "up",
+        Down => "down",
+        Select => "select",
+        Close => "close",
+    }
+}
+
+define_action_enum! {
+    /// Batch rename layer actions
+    pub enum BatchRenameActionId {
+        NextField => "next_field",
+        PrevField => "prev_field",
+        Apply => "apply",
+        Close => "close",
+    }
+}
+
 /// Top-level action identifier wrapping all layer-specific action enums
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ActionId {
@@ -694,6 +1156,11 @@ pub enum ActionId {
     Home(HomeActionId),
     Help(HelpActionId),
     FrameEdit(FrameEditActionId),
+    RecordSettings(RecordSettingsActionId),
+    Export(ExportActionId),
+    BatchExport(BatchExportActionId),
+    RenderQueue(RenderQueueActionId),
+    Comp(CompActionId),
     FileBrowser(FileBrowserActionId),
     SampleChopper(SampleChopperActionId),
     Automation(AutomationActionId),
@@ -704,6 +1171,25 @@ pub enum ActionId {
     MidiSettings(MidiSettingsActionId),
     Confirm(ConfirmActionId),
     ProjectBrowser(ProjectBrowserActionId),
+    NoteGenerator(NoteGeneratorActionId),
+    Quantize(QuantizeActionId),
+    RandomLooper(RandomLooperActionId),
+    ClipInspector(ClipInspectorActionId),
+    Preferences(PreferencesActionId),
+    MidiMonitor(MidiMonitorActionId),
+    UndoHistory(UndoHistoryActionId),
+    Console(ConsoleActionId),
+    TimeEdit(TimeEditActionId),
+    ProjectCheck(ProjectCheckActionId),
+    ChannelPaste(ChannelPasteActionId),
+    Routing(RoutingActionId),
+    Diagnose(DiagnoseActionId),
+    Latency(LatencyActionId),
+    Tracker(TrackerActionId),
+    Sclang(SclangActionId),
+    SampleLibrary(SampleLibraryActionId),
+    ProjectSearch(ProjectSearchActionId),
+    BatchRename(BatchRenameActionId),
 }
 
 impl ActionId {
@@ -721,6 +1207,11 @@ impl ActionId {
             ActionId::Home(a) => a.as_str(),
             ActionId::Help(a) => a.as_str(),
             ActionId::FrameEdit(a) => a.as_str(),
+            ActionId::RecordSettings(a) => a.as_str(),
+            ActionId::Export(a) => a.as_str(),
+            ActionId::BatchExport(a) => a.as_str(),
+            ActionId::RenderQueue(a) => a.as_str(),
+            ActionId::Comp(a) => a.as_str(),
             ActionId::FileBrowser(a) => a.as_str(),
             ActionId::SampleChopper(a) => a.as_str(),
             ActionId::Automation(a) => a.as_str(),
@@ -731,6 +1222,25 @@ impl ActionId {
             ActionId::MidiSettings(a) => a.as_str(),
             ActionId::Confirm(a) => a.as_str(),
             ActionId::ProjectBrowser(a) => a.as_str(),
+            ActionId::NoteGenerator(a) => a.as_str(),
+            ActionId::Quantize(a) => a.as_str(),
+            ActionId::RandomLooper(a) => a.as_str(),
+            ActionId::ClipInspector(a) => a.as_str(),
+            ActionId::Preferences(a) => a.as_str(),
+            ActionId::MidiMonitor(a) => a.as_str(),
+            ActionId::UndoHistory(a) => a.as_str(),
+            ActionId::Console(a) => a.as_str(),
+            ActionId::TimeEdit(a) => a.as_str(),
+            ActionId::ProjectCheck(a) => a.as_str(),
+            ActionId::ChannelPaste(a) => a.as_str(),
+            ActionId::Routing(a) => a.as_str(),
+            ActionId::Diagnose(a) => a.as_str(),
+            ActionId::Latency(a) => a.as_str(),
+            ActionId::Tracker(a) => a.as_str(),
+            ActionId::Sclang(a) => a.as_str(),
+            ActionId::SampleLibrary(a) => a.as_str(),
+            ActionId::ProjectSearch(a) => a.as_str(),
+            ActionId::BatchRename(a) => a.as_str(),
         }
     }
 }
@@ -751,6 +1261,13 @@ pub fn parse_action_id(layer: &str, action: &str) -> Option<ActionId> {
         "home" => HomeActionId::from_str(action).map(ActionId::Home),
         "help" => HelpActionId::from_str(action).map(ActionId::Help),
         "frame_edit" => FrameEditActionId::from_str(action).map(ActionId::FrameEdit),
+        "record_settings" => {
+            RecordSettingsActionId::from_str(action).map(ActionId::RecordSettings)
+        }
+        "export" => ExportActionId::from_str(action).map(ActionId::Export),
+        "batch_export" => BatchExportActionId::from_str(action).map(ActionId::BatchExport),
+        "render_queue" => RenderQueueActionId::from_str(action).map(ActionId::RenderQueue),
+        "comp" => CompActionId::from_str(action).map(ActionId::Comp),
         "file_browser" => FileBrowserActionId::from_str(action).map(ActionId::FileBrowser),
         "sample_chopper" => {
             SampleChopperActionId::from_str(action).map(ActionId::SampleChopper)
@@ -765,6 +1282,25 @@ pub fn parse_action_id(layer: &str, action: &str) -> Option<ActionId> {
         "project_browser" => {
             ProjectBrowserActionId::from_str(action).map(ActionId::ProjectBrowser)
         }
+        "note_generator" => NoteGeneratorActionId::from_str(action).map(ActionId::NoteGenerator),
+        "quantize" => QuantizeActionId::from_str(action).map(ActionId::Quantize),
+        "random_looper" => RandomLooperActionId::from_str(action).map(ActionId::RandomLooper),
+        "clip_inspector" => ClipInspectorActionId::from_str(action).map(ActionId::ClipInspector),
+        "preferences" => PreferencesActionId::from_str(action).map(ActionId::Preferences),
+        "midi_monitor" => MidiMonitorActionId::from_str(action).map(ActionId::MidiMonitor),
+        "undo_history" => UndoHistoryActionId::from_str(action).map(ActionId::UndoHistory),
+        "console" => ConsoleActionId::from_str(action).map(ActionId::Console),
+        "time_edit" => TimeEditActionId::from_str(action).map(ActionId::TimeEdit),
+        "project_check" => ProjectCheckActionId::from_str(action).map(ActionId::ProjectCheck),
+        "channel_paste" => ChannelPasteActionId::from_str(action).map(ActionId::ChannelPaste),
+        "routing" => RoutingActionId::from_str(action).map(ActionId::Routing),
+        "diagnose" => DiagnoseActionId::from_str(action).map(ActionId::Diagnose),
+        "latency" => LatencyActionId::from_str(action).map(ActionId::Latency),
+        "tracker" => TrackerActionId::from_str(action).map(ActionId::Tracker),
+        "sclang" => SclangActionId::from_str(action).map(ActionId::Sclang),
+        "sample_library" => SampleLibraryActionId::from_str(action).map(ActionId::SampleLibrary),
+        "project_search" => ProjectSearchActionId::from_str(action).map(ActionId::ProjectSearch),
+        "batch_rename" => BatchRenameActionId::from_str(action).map(ActionId::BatchRename),
         "piano_mode" | "pad_mode" | "text_edit" | "command_palette" => {
             ModeActionId::from_str(action).map(ActionId::Mode)
         }
@@ -791,6 +1327,7 @@ mod tests {
             GlobalActionId::Copy,
             GlobalActionId::Cut,
             GlobalActionId::Paste,
+            GlobalActionId::PasteSystem,
             GlobalActionId::SelectAll,
             GlobalActionId::AddInstrument,
             GlobalActionId::DeleteInstrument,
@@ -805,6 +1342,22 @@ mod tests {
             GlobalActionId::SelectPrevInstrument,
             GlobalActionId::SelectNextInstrument,
             GlobalActionId::SelectTwoDigit,
+            GlobalActionId::RunScript,
+            GlobalActionId::ImportMidi,
+            GlobalActionId::ExportMidi,
+            GlobalActionId::Preferences,
+            GlobalActionId::UndoHistory,
+            GlobalActionId::ProjectCheck,
+            GlobalActionId::WhySilent,
+            GlobalActionId::Latency,
+            GlobalActionId::Tutorial,
+            GlobalActionId::Console,
+            GlobalActionId::Sclang,
+            GlobalActionId::ProjectSearch,
+            GlobalActionId::BatchRename,
+            GlobalActionId::RenderQueue,
+            GlobalActionId::Panic,
+            GlobalActionId::SaveWorkspace,
             GlobalActionId::SwitchPane(PaneId::InstrumentEdit),
             GlobalActionId::SwitchPane(PaneId::InstrumentList),
             GlobalActionId::SwitchPane(PaneId::PianoRollOrSequencer),
@@ -825,6 +1378,9 @@ mod tests {
             GlobalActionId::SelectInstrument(8),
             GlobalActionId::SelectInstrument(9),
             GlobalActionId::SelectInstrument(10),
+            GlobalActionId::Workspace(1),
+            GlobalActionId::Workspace(2),
+            GlobalActionId::Workspace(3),
         ];
 
         for action in actions {
@@ -849,6 +1405,11 @@ mod tests {
             InstrumentListActionId::Load,
             InstrumentListActionId::LinkLayer,
             InstrumentListActionId::UnlinkLayer,
+            InstrumentListActionId::Rename,
+            InstrumentListActionId::Duplicate,
+            InstrumentListActionId::DuplicateWithNotes,
+            InstrumentListActionId::MoveUp,
+            InstrumentListActionId::MoveDown,
         ];
 
         for action in actions {
@@ -888,6 +1449,12 @@ mod tests {
             InstrumentEditActionId::ToggleActive,
             InstrumentEditActionId::LoadSample,
             InstrumentEditActionId::VstParams,
+            InstrumentEditActionId::RandomLooper,
+            InstrumentEditActionId::ExportScd,
+            InstrumentEditActionId::OffsetEarlier,
+            InstrumentEditActionId::OffsetLater,
+            InstrumentEditActionId::OffsetEarlierBig,
+            InstrumentEditActionId::OffsetLaterBig,
             InstrumentEditActionId::Done,
         ];
 
@@ -934,6 +1501,17 @@ mod tests {
             MixerActionId::ClearSend,
             MixerActionId::Increase,
             MixerActionId::Decrease,
+            MixerActionId::AutomationMode,
+            MixerActionId::RenameBus,
+            MixerActionId::BusColor,
+            MixerActionId::BypassChain,
+            MixerActionId::GainMatch,
+            MixerActionId::CopyChannel,
+            MixerActionId::PasteChannel,
+            MixerActionId::Routing,
+            MixerActionId::SoloSafe,
+            MixerActionId::MomentarySolo,
+            MixerActionId::ExclusiveSolo,
         ];
 
         for action in actions {
@@ -996,6 +1574,17 @@ mod tests {
             AutomationActionId::PlacePoint,
             AutomationActionId::DeletePoint,
             AutomationActionId::CycleCurve,
+            AutomationActionId::CycleCurveBack,
+            AutomationActionId::SelectLeft,
+            AutomationActionId::SelectRight,
+            AutomationActionId::ShiftLeft,
+            AutomationActionId::ShiftRight,
+            AutomationActionId::ScaleUp,
+            AutomationActionId::ScaleDown,
+            AutomationActionId::Stretch,
+            AutomationActionId::Compress,
+            AutomationActionId::Thin,
+            AutomationActionId::Generate,
             AutomationActionId::ClearLane,
             AutomationActionId::ToggleRecording,
             AutomationActionId::ToggleArm,
@@ -0,0 +1,137 @@
+//! Meter ballistics computed from wall-clock time, so meters move the same
+//! at any frame rate.
+//!
+//! Levels rise almost at once and fall at a fixed rate in dB per second.
+//! The held peak stays put for a moment, then falls at the same rate.
+
+use std::time::{Duration, Instant};
+
+/// Time constant of the rise toward a louder input
+const ATTACK: Duration = Duration::from_millis(10);
+
+/// Fall rate of levels and of the held peak once it lets go
+const RELEASE_DB_PER_SEC: f32 = 20.0;
+
+/// How long the held peak stays before falling
+const PEAK_HOLD: Duration = Duration::from_millis(1500);
+
+/// Below this (about -90 dB) a level reads as silence
+const FLOOR: f32 = 3e-5;
+
+/// One smoothed level with a held peak, fed raw amplitudes each frame
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Meter {
+    level: f32,
+    peak: f32,
+    peak_at: Option<Instant>,
+    last: Option<Instant>,
+}
+
+fn fall(value: f32, secs: f32) -> f32 {
+    let fallen = value * 10f32.powf(-RELEASE_DB_PER_SEC * secs / 20.0);
+    if fallen < FLOOR { 0.0 } else { fallen }
+}
+
+impl Meter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, input: f32, now: Instant) {
+        let input = input.max(0.0);
+        let dt = self.last.map(|last| now.duration_since(last).as_secs_f32()).unwrap_or(f32::INFINITY);
+        self.last = Some(now);
+
+        self.level = if input >= self.level {
+            let rise = 1.0 - (-dt / ATTACK.as_secs_f32()).exp();
+            self.level + (input - self.level) * rise
+        } else {
+            fall(self.level, dt.min(60.0)).max(input)
+        };
+
+        if self.level >= self.peak {
+            self.peak = self.level;
+            self.peak_at = Some(now);
+        } else if let Some(at) = self.peak_at {
+            let held = now.duration_since(at);
+            if held > PEAK_HOLD {
+                // Fall from where the hold ended, never below the level
+                let falling = (held - PEAK_HOLD).as_secs_f32().min(dt);
+                self.peak = fall(self.peak, falling).max(self.level);
+            }
+        }
+    }
+
+    /// Smoothed level
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+
+    /// Highest recent level, held then falling
+    pub fn peak(&self) -> f32 {
+        self.peak
+    }
+}
+
+/// A meter per band, resized to the input
+#[derive(Debug, Clone, Default)]
+pub struct BandMeters {
+    meters: Vec<Meter>,
+}
+
+impl BandMeters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed raw band levels and replace them with the smoothed ones
+    pub fn apply(&mut self, bands: &mut [f32], now: Instant) {
+        self.meters.resize(bands.len(), Meter::new());
+        for (meter, band) in self.meters.iter_mut().zip(bands) {
+            meter.update(*band, now);
+            *band = meter.level();
+        }
+    }
+
+    pub fn peaks(&self) -> Vec<f32> {
+        self.meters.iter().map(Meter::peak).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn db(amp: f32) -> f32 {
+        20.0 * amp.log10()
+    }
+
+    #[test]
+    fn release_is_the_same_at_any_frame_rate() {
+        let start = Instant::now();
+        let mut fast = Meter::new();
+        let mut slow = Meter::new();
+        fast.update(1.0, start);
+        slow.update(1.0, start);
+        for frame in 1..=60 {
+            fast.update(0.0, start + Duration::from_millis(frame * 1000 / 60));
+        }
+        for frame in 1..=10 {
+            slow.update(0.0, start + Duration::from_millis(frame * 100));
+        }
+        assert!((db(fast.level()) - db(slow.level())).abs() < 0.1);
+        assert!((db(fast.level()) + RELEASE_DB_PER_SEC).abs() < 0.1);
+    }
+
+    #[test]
+    fn peak_holds_then_falls() {
+        let start = Instant::now();
+        let mut meter = Meter::new();
+        meter.update(0.5, start);
+        meter.update(0.0, start + Duration::from_millis(1000));
+        assert_eq!(meter.peak(), 0.5);
+        meter.update(0.0, start + PEAK_HOLD + Duration::from_millis(500));
+        assert!(meter.peak() < 0.5);
+        assert!(meter.peak() >= meter.level());
+    }
+}
@@ -1,3 +1,6 @@
+use std::time::Instant;
+
+use super::ballistics::Meter;
 use super::{Color, Rect, RenderBuf, Style};
 use crate::audio::ServerStatus;
 use crate::state::AppState;
@@ -20,7 +23,7 @@ pub struct Frame {
     /// Raw peak from audio engine (0.0–1.0+)
     master_peak: f32,
     /// Smoothed display value (fast attack, slow decay)
-    peak_display: f32,
+    peak_display: Meter,
     /// Navigation history (browser-style)
     pub view_history: Vec<ViewState>,
     /// Current position in view_history
@@ -29,6 +32,10 @@ pub struct Frame {
     pub recording: bool,
     /// Elapsed recording time in seconds
     pub recording_secs: u64,
+    /// Session or pomodoro timer text for the bottom border
+    pub session_timer: Option<String>,
+    /// Urgent notice for the header, cleared by the next key press
+    pub alert: Option<String>,
     /// SuperCollider average CPU load (%)
     sc_cpu: f32,
     /// OSC round-trip latency (ms)
@@ -41,11 +48,13 @@ impl Frame {
             project_name: "untitled".to_string(),
             master_mute: false,
             master_peak: 0.0,
-            peak_display: 0.0,
+            peak_display: Meter::new(),
             view_history: Vec::new(),
             history_cursor: 0,
             recording: false,
             recording_secs: 0,
+            session_timer: None,
+            alert: None,
             sc_cpu: 0.0,
             osc_latency_ms: 0.0,
         }
@@ -59,8 +68,7 @@ impl Frame {
     pub fn set_master_peak(&mut self, peak: f32, mute: bool) {
         self.master_peak = peak;
         self.master_mute = mute;
-        // Fast attack, slow decay
-        self.peak_display = peak.max(self.peak_display * 0.85);
+        self.peak_display.update(peak, Instant::now());
     }
 
     /// Update SC CPU and latency metrics (call each frame from main loop)
@@ -150,6 +158,15 @@ impl Frame {
             cursor = arec_start;
         }
 
+        // Alert (e.g. the audio server crashed)
+        if let Some(ref alert) = self.alert {
+            let alert_text = format!(" {} ", alert);
+            let alert_start = cursor.saturating_sub(alert_text.chars().count() as u16);
+            let alert_style = Style::new().fg(Color::WHITE).bg(Color::MUTE_COLOR).bold();
+            buf.draw_str(alert_start, area.y, &alert_text, alert_style);
+            cursor = alert_start;
+        }
+
         // Instrument indicator (to the left of REC)
         if !inst_indicator.is_empty() {
             let inst_start = cursor.saturating_sub(inst_indicator.len() as u16);
@@ -194,6 +211,16 @@ impl Frame {
             buf.draw_str(x + cpu_text.len() as u16, bottom_y, &lat_text, Style::new().fg(lat_color));
         }
 
+        // Session timer, centred on the bottom border
+        if let Some(ref timer) = self.session_timer {
+            let bottom_y = area.y + area.height.saturating_sub(1);
+            let len = timer.chars().count() as u16;
+            if area.width > len + 40 {
+                let x = area.x + (area.width - len) / 2;
+                buf.draw_str(x, bottom_y, timer, Style::new().fg(Color::SKY_BLUE));
+            }
+        }
+
         // Right-aligned SC and MIDI status indicators on bottom border
         if area.width > 50 {
             let bottom_y = area.y + area.height.saturating_sub(1);
@@ -242,7 +269,7 @@ impl Frame {
             return;
         }
 
-        let level = if self.master_mute { 0.0 } else { self.peak_display.min(1.0) };
+        let level = if self.master_mute { 0.0 } else { self.peak_display.level().min(1.0) };
         let total_sub = meter_height as f32 * 8.0;
         let filled_sub = (level * total_sub) as u16;
 
@@ -49,6 +49,7 @@ fn intern(s: String) -> &'static str {
 /// - `"Ctrl+s"` → Ctrl('s')
 /// - `"Alt+x"` → Alt('x')
 /// - `"Ctrl+Left"` → CtrlKey(KeyCode::Left)
+/// - `"Alt+Up"` → AltKey(KeyCode::Up)
 /// - `"Shift+Right"` → ShiftKey(KeyCode::Right)
 /// - `"F1"` → Key(KeyCode::F(1))
 ///
@@ -62,7 +63,11 @@ fn parse_key(s: &str) -> Option<KeyPattern> {
             parse_named_key(rest).map(KeyPattern::CtrlKey)
         }
     } else if let Some(rest) = s.strip_prefix("Alt+") {
-        Some(KeyPattern::Alt(rest.chars().next().unwrap()))
+        if rest.len() == 1 {
+            Some(KeyPattern::Alt(rest.chars().next().unwrap()))
+        } else {
+            parse_named_key(rest).map(KeyPattern::AltKey)
+        }
     } else if let Some(rest) = s.strip_prefix("Shift+") {
         parse_named_key(rest).map(KeyPattern::ShiftKey)
     } else if s.len() == 1 {
@@ -192,6 +197,9 @@ fn build_pane_keymaps(layers: &HashMap<String, LayerConfig>) -> HashMap<String,
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::super::action_id::ActionId;
+    use super::super::layer::{LayerResult, LayerStack};
+    use super::super::{InputEvent, Modifiers};
 
     #[test]
     fn test_parse_key_char() {
@@ -211,6 +219,7 @@ mod tests {
         assert_eq!(parse_key("Ctrl+s"), Some(KeyPattern::Ctrl('s')));
         assert_eq!(parse_key("Alt+x"), Some(KeyPattern::Alt('x')));
         assert_eq!(parse_key("Ctrl+Left"), Some(KeyPattern::CtrlKey(KeyCode::Left)));
+        assert_eq!(parse_key("Alt+Left"), Some(KeyPattern::AltKey(KeyCode::Left)));
         assert_eq!(parse_key("Shift+Right"), Some(KeyPattern::ShiftKey(KeyCode::Right)));
     }
 
@@ -224,9 +233,98 @@ mod tests {
     fn test_parse_key_unknown() {
         assert_eq!(parse_key("Bogus"), None);
         assert_eq!(parse_key("Ctrl+Bogus"), None);
+        assert_eq!(parse_key("Alt+Bogus"), None);
         assert_eq!(parse_key("Shift+Bogus"), None);
     }
 
+    /// Action bound to Alt+`key` in a pane's embedded keymap
+    fn alt_binding(pane: &str, key: KeyCode) -> Option<ActionId> {
+        let (_, pane_keymaps) = load_keybindings();
+        let event = InputEvent::new(key, Modifiers { alt: true, ..Modifiers::none() });
+        pane_keymaps.get(pane)?.lookup(&event)
+    }
+
+    #[test]
+    fn test_alt_arrows_resize_notes() {
+        assert_eq!(alt_binding("piano_roll", KeyCode::Right), parse_action_id("piano_roll", "grow_duration"));
+        assert_eq!(alt_binding("piano_roll", KeyCode::Left), parse_action_id("piano_roll", "shrink_duration"));
+    }
+
+    #[test]
+    fn test_alt_arrows_transpose() {
+        assert_eq!(alt_binding("piano_roll", KeyCode::Up), parse_action_id("piano_roll", "transpose_up"));
+        assert_eq!(alt_binding("piano_roll", KeyCode::Down), parse_action_id("piano_roll", "transpose_down"));
+    }
+
+    #[test]
+    fn test_alt_arrows_nudge_steps() {
+        assert_eq!(alt_binding("sequencer", KeyCode::Left), parse_action_id("sequencer", "nudge_earlier"));
+        assert_eq!(alt_binding("sequencer", KeyCode::Right), parse_action_id("sequencer", "nudge_later"));
+    }
+
+    #[test]
+    fn test_alt_arrows_pitch_jitter() {
+        assert_eq!(alt_binding("sequencer", KeyCode::Up), parse_action_id("sequencer", "pitch_jitter_up"));
+        assert_eq!(alt_binding("sequencer", KeyCode::Down), parse_action_id("sequencer", "pitch_jitter_down"));
+    }
+
+    #[test]
+    fn test_alt_arrows_shift_and_scale_automation() {
+        assert_eq!(alt_binding("automation", KeyCode::Left), parse_action_id("automation", "shift_left"));
+        assert_eq!(alt_binding("automation", KeyCode::Right), parse_action_id("automation", "shift_right"));
+        assert_eq!(alt_binding("automation", KeyCode::Up), parse_action_id("automation", "scale_up"));
+        assert_eq!(alt_binding("automation", KeyCode::Down), parse_action_id("automation", "scale_down"));
+    }
+
+    /// Action a plain key resolves to with `pane` open over the global layer
+    fn resolved_in(pane: &'static str, c: char) -> Option<ActionId> {
+        let (layers, _) = load_keybindings();
+        let mut stack = LayerStack::new(layers);
+        stack.push("global");
+        stack.set_pane_layer(pane);
+        match stack.resolve(&InputEvent::new(KeyCode::Char(c), Modifiers::none())) {
+            LayerResult::Action(action) => Some(action),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_tracker_digits_override_instrument_select() {
+        for c in '0'..='9' {
+            assert_eq!(resolved_in("tracker", c), parse_action_id("tracker", "key"));
+        }
+        // Other panes leave the digits to the global layer
+        assert_eq!(resolved_in("mixer", '1'), parse_action_id("global", "select:1"));
+        assert_eq!(resolved_in("mixer", '0'), parse_action_id("global", "select:10"));
+    }
+
+    /// Action bound to Ctrl+`c` in a pane's embedded keymap
+    fn ctrl_binding(pane: &str, c: char) -> Option<ActionId> {
+        let (_, pane_keymaps) = load_keybindings();
+        let event = InputEvent::new(KeyCode::Char(c), Modifiers::ctrl());
+        pane_keymaps.get(pane)?.lookup(&event)
+    }
+
+    #[test]
+    fn test_sclang_clear_leaves_ctrl_l_to_load() {
+        assert_eq!(alt_binding("sclang", KeyCode::Char('l')), parse_action_id("sclang", "clear"));
+        assert_eq!(ctrl_binding("sclang", 'l'), None);
+    }
+
+    #[test]
+    fn test_sample_library_leaves_record_and_frame_edit_keys() {
+        assert_eq!(alt_binding("sample_library", KeyCode::Char('r')), parse_action_id("sample_library", "rescan"));
+        assert_eq!(alt_binding("sample_library", KeyCode::Char('f')), parse_action_id("sample_library", "favorites_only"));
+        assert_eq!(ctrl_binding("sample_library", 'r'), None);
+        assert_eq!(ctrl_binding("sample_library", 'f'), None);
+    }
+
+    #[test]
+    fn test_channel_paste_leaves_space_to_play_stop() {
+        assert_eq!(resolved_in("channel_paste", 't'), parse_action_id("channel_paste", "toggle"));
+        assert_eq!(resolved_in("channel_paste", ' '), parse_action_id("global", "play_stop"));
+    }
+
     #[test]
     fn test_load_embedded_keybindings() {
         let (layers, pane_keymaps) = load_keybindings();
@@ -17,6 +17,8 @@ pub enum KeyPattern {
     /// Ctrl + special key
     #[allow(dead_code)]
     CtrlKey(KeyCode),
+    /// Alt + special key
+    AltKey(KeyCode),
     /// Shift + special key (arrows, Tab, etc.)
     ShiftKey(KeyCode),
 }
@@ -40,6 +42,7 @@ impl KeyPattern {
                 matches!(event.key, KeyCode::Char(c) if c == *ch) && event.modifiers.alt
             }
             KeyPattern::CtrlKey(code) => event.key == *code && event.modifiers.ctrl,
+            KeyPattern::AltKey(code) => event.key == *code && event.modifiers.alt,
             KeyPattern::ShiftKey(code) => event.key == *code && event.modifiers.shift,
         }
     }
@@ -52,6 +55,7 @@ impl KeyPattern {
             KeyPattern::Ctrl(ch) => format!("Ctrl+{}", ch),
             KeyPattern::Alt(ch) => format!("Alt+{}", ch),
             KeyPattern::CtrlKey(code) => format!("Ctrl+{:?}", code),
+            KeyPattern::AltKey(code) => format!("Alt+{:?}", code),
             KeyPattern::ShiftKey(code) => format!("Shift+{:?}", code),
         }
     }
@@ -219,6 +223,16 @@ mod tests {
         assert!(!pattern.matches(&event_no_ctrl));
     }
 
+    #[test]
+    fn test_alt_key_pattern_matches() {
+        let pattern = KeyPattern::AltKey(KeyCode::Left);
+        let event = InputEvent::new(KeyCode::Left, Modifiers { alt: true, ..Modifiers::none() });
+        assert!(pattern.matches(&event));
+
+        let event_no_alt = InputEvent::new(KeyCode::Left, Modifiers::none());
+        assert!(!pattern.matches(&event_no_alt));
+    }
+
     #[test]
     fn test_keymap_lookup() {
         let keymap = Keymap::new()
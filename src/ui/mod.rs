@@ -1,4 +1,5 @@
 pub mod action_id;
+pub mod ballistics;
 pub mod frame;
 pub mod input;
 pub mod keybindings;
@@ -93,6 +93,12 @@ impl PaneManager {
         self.panes[self.active_index].as_ref()
     }
 
+    /// ID of the pane under any pushed modals
+    pub fn base_id(&self) -> &'static str {
+        let index = self.stack.first().copied().unwrap_or(self.active_index);
+        self.panes[index].id()
+    }
+
     /// Get the currently active pane mutably
     pub fn active_mut(&mut self) -> &mut dyn Pane {
         self.panes[self.active_index].as_mut()
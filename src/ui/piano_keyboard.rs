@@ -5,6 +5,9 @@ pub fn translate_key(c: char, layout: KeyboardLayout) -> char {
     match layout {
         KeyboardLayout::Qwerty => c,
         KeyboardLayout::Colemak => colemak_to_qwerty(c),
+        KeyboardLayout::Dvorak => dvorak_to_qwerty(c),
+        KeyboardLayout::Azerty => azerty_to_qwerty(c),
+        KeyboardLayout::Qwertz => qwertz_to_qwerty(c),
     }
 }
 
@@ -29,6 +32,58 @@ fn colemak_to_qwerty(c: char) -> char {
     }
 }
 
+fn dvorak_to_qwerty(c: char) -> char {
+    match c {
+        // number row
+        '[' => '-', ']' => '=',
+        // top row
+        '\'' => 'q', ',' => 'w', '.' => 'e', 'p' => 'r', 'y' => 't',
+        'f' => 'y', 'g' => 'u', 'c' => 'i', 'r' => 'o', 'l' => 'p',
+        '/' => '[', '=' => ']',
+        // home row
+        'a' => 'a', 'o' => 's', 'e' => 'd', 'u' => 'f', 'i' => 'g',
+        'd' => 'h', 'h' => 'j', 't' => 'k', 'n' => 'l', 's' => ';', '-' => '\'',
+        // bottom row
+        ';' => 'z', 'q' => 'x', 'j' => 'c', 'k' => 'v', 'x' => 'b',
+        'b' => 'n', 'w' => ',', 'v' => '.', 'z' => '/',
+        // uppercase (Stradella shifted rows)
+        '"' => 'Q', '<' => 'W', '>' => 'E', 'P' => 'R', 'Y' => 'T',
+        'F' => 'Y', 'G' => 'U', 'C' => 'I', 'R' => 'O', 'L' => 'P',
+        'O' => 'S', 'E' => 'D', 'U' => 'F', 'I' => 'G',
+        'D' => 'H', 'H' => 'J', 'T' => 'K', 'N' => 'L', 'S' => ':',
+        ':' => 'Z', 'Q' => 'X', 'J' => 'C', 'K' => 'V', 'X' => 'B',
+        'B' => 'N', 'W' => '<', 'V' => '>', 'Z' => '?',
+        other => other,
+    }
+}
+
+fn azerty_to_qwerty(c: char) -> char {
+    match c {
+        // number row (unshifted AZERTY gives symbols)
+        '&' => '1', 'é' => '2', '"' => '3', '\'' => '4', '(' => '5',
+        '-' => '6', 'è' => '7', '_' => '8', 'ç' => '9', 'à' => '0',
+        // letters that move
+        'a' => 'q', 'z' => 'w', 'q' => 'a', 'm' => ';', 'ù' => '\'',
+        'w' => 'z', ',' => 'm', ';' => ',', ':' => '.', '!' => '/',
+        // uppercase
+        'A' => 'Q', 'Z' => 'W', 'Q' => 'A', 'M' => ':', '%' => '"',
+        'W' => 'Z', '?' => 'M', '.' => '<', '/' => '>',
+        other => other,
+    }
+}
+
+fn qwertz_to_qwerty(c: char) -> char {
+    match c {
+        'z' => 'y', 'y' => 'z', 'ß' => '-',
+        'ü' => '[', '+' => ']', 'ö' => ';', 'ä' => '\'', '-' => '/',
+        // uppercase
+        'Z' => 'Y', 'Y' => 'Z', '?' => '_',
+        'Ü' => '{', '*' => '}', 'Ö' => ':', 'Ä' => '"',
+        ';' => '<', ':' => '>', '_' => '?',
+        other => other,
+    }
+}
+
 /// Piano keyboard layout starting note.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PianoLayout {
@@ -364,3 +419,31 @@ impl PianoKeyboard {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// QWERTY home-row piano keys, as typed on each layout
+    const HOME_ROW: &str = "asdfghjkl;";
+
+    #[test]
+    fn dvorak_home_row_maps_to_qwerty() {
+        let typed: String = "aoeuidhtns".chars().map(|c| translate_key(c, KeyboardLayout::Dvorak)).collect();
+        assert_eq!(typed, HOME_ROW);
+    }
+
+    #[test]
+    fn azerty_home_row_maps_to_qwerty() {
+        let typed: String = "qsdfghjklm".chars().map(|c| translate_key(c, KeyboardLayout::Azerty)).collect();
+        assert_eq!(typed, HOME_ROW);
+        assert_eq!(translate_key('z', KeyboardLayout::Azerty), 'w');
+    }
+
+    #[test]
+    fn qwertz_swaps_y_and_z() {
+        assert_eq!(translate_key('z', KeyboardLayout::Qwertz), 'y');
+        assert_eq!(translate_key('y', KeyboardLayout::Qwertz), 'z');
+        assert_eq!(translate_key('ö', KeyboardLayout::Qwertz), ';');
+    }
+}
@@ -2,7 +2,7 @@ use std::io::{self, Stdout};
 use std::time::Duration;
 
 use crossterm::{
-    event::{self, Event, KeyEvent, KeyCode as CrosstermKeyCode, KeyModifiers, MouseEvent as CrosstermMouseEvent, MouseEventKind as CrosstermMouseEventKind, MouseButton as CrosstermMouseButton, EnableMouseCapture, DisableMouseCapture},
+    event::{self, Event, KeyEvent, KeyCode as CrosstermKeyCode, KeyModifiers, MouseEvent as CrosstermMouseEvent, MouseEventKind as CrosstermMouseEventKind, MouseButton as CrosstermMouseButton, EnableMouseCapture, DisableMouseCapture, EnableFocusChange, DisableFocusChange},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -20,6 +20,8 @@ use super::{AppEvent, InputEvent, InputSource, KeyCode, Modifiers, MouseButton,
 /// Ratatui-based terminal backend
 pub struct RatatuiBackend {
     terminal: Terminal<CrosstermBackend<Stdout>>,
+    /// Terminal window has focus; stays true if the terminal never reports it
+    focused: bool,
 }
 
 impl RatatuiBackend {
@@ -27,13 +29,13 @@ impl RatatuiBackend {
     pub fn new() -> io::Result<Self> {
         let backend = CrosstermBackend::new(io::stdout());
         let terminal = Terminal::new(backend)?;
-        Ok(Self { terminal })
+        Ok(Self { terminal, focused: true })
     }
 
-    /// Enter raw mode and alternate screen with mouse capture
+    /// Enter raw mode and alternate screen with mouse capture and focus reports
     pub fn start(&mut self) -> io::Result<()> {
         enable_raw_mode()?;
-        execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+        execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture, EnableFocusChange)?;
         self.terminal.clear()?;
         Ok(())
     }
@@ -41,7 +43,7 @@ impl RatatuiBackend {
     /// Leave raw mode and alternate screen
     pub fn stop(&mut self) -> io::Result<()> {
         disable_raw_mode()?;
-        execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+        execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, DisableFocusChange)?;
         Ok(())
     }
 
@@ -74,6 +76,11 @@ impl RatatuiBackend {
         Ok(())
     }
 
+    /// Whether the terminal window has focus, as last reported
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
     /// Clear the terminal screen (useful for recovering from display corruption)
     pub fn clear(&mut self) -> io::Result<()> {
         self.terminal.clear()
@@ -116,6 +123,14 @@ impl InputSource for RatatuiBackend {
                     // Discarded mouse event (Moved, etc.) — drain with zero timeout
                     t = Duration::ZERO;
                 }
+                Event::FocusGained => {
+                    self.focused = true;
+                    t = Duration::ZERO;
+                }
+                Event::FocusLost => {
+                    self.focused = false;
+                    t = Duration::ZERO;
+                }
                 _ => {
                     // Discarded event (Resize, etc.) — drain with zero timeout
                     t = Duration::ZERO;
@@ -0,0 +1,111 @@
+use crate::ui::{RenderBuf, Style};
+
+/// Dot bits within a braille cell, indexed by [row][column]
+const DOT_BITS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// Eighth-block characters, from one eighth to a full cell
+pub const EIGHTH_BLOCKS: [char; 8] = ['\u{2581}', '\u{2582}', '\u{2583}', '\u{2584}', '\u{2585}', '\u{2586}', '\u{2587}', '\u{2588}'];
+
+/// Character for the top cell of a bar `eighths` eighths tall (1..=8)
+pub fn bar_top(eighths: u8) -> char {
+    EIGHTH_BLOCKS[eighths.clamp(1, 8) as usize - 1]
+}
+
+/// Grid of braille dots, two across and four down per terminal cell, for
+/// plotting waveforms and traces at higher resolution than whole cells.
+pub struct BrailleCanvas {
+    /// Size in cells
+    width: u16,
+    height: u16,
+    cells: Vec<u8>,
+}
+
+impl BrailleCanvas {
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![0; width as usize * height as usize],
+        }
+    }
+
+    /// Width in dots
+    pub fn dot_width(&self) -> usize {
+        self.width as usize * 2
+    }
+
+    /// Height in dots
+    pub fn dot_height(&self) -> usize {
+        self.height as usize * 4
+    }
+
+    /// Set the dot at (`x`, `y`), counted from the top left; off-canvas dots are ignored
+    pub fn set(&mut self, x: usize, y: usize) {
+        if x >= self.dot_width() || y >= self.dot_height() {
+            return;
+        }
+        let cell = (y / 4) * self.width as usize + x / 2;
+        self.cells[cell] |= DOT_BITS[y % 4][x % 2];
+    }
+
+    /// Set a vertical run of dots in column `x` between `y0` and `y1` inclusive
+    pub fn vline(&mut self, x: usize, y0: usize, y1: usize) {
+        let (top, bottom) = if y0 <= y1 { (y0, y1) } else { (y1, y0) };
+        for y in top..=bottom {
+            self.set(x, y);
+        }
+    }
+
+    /// Draw the set cells at (`x`, `y`), styled by cell column and row
+    pub fn render(&self, buf: &mut RenderBuf, x: u16, y: u16, style: impl Fn(u16, u16) -> Style) {
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let bits = self.cells[row as usize * self.width as usize + col as usize];
+                if bits == 0 {
+                    continue;
+                }
+                if let Some(ch) = char::from_u32(0x2800 + bits as u32) {
+                    buf.set_cell(x + col, y + row, ch, style(col, row));
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    fn cell(&self, col: u16, row: u16) -> u8 {
+        self.cells[row as usize * self.width as usize + col as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dots_map_to_braille_bits() {
+        let mut canvas = BrailleCanvas::new(2, 1);
+        canvas.set(0, 0);
+        canvas.set(1, 3);
+        canvas.set(2, 1);
+        assert_eq!(canvas.cell(0, 0), 0x01 | 0x80);
+        assert_eq!(canvas.cell(1, 0), 0x02);
+        // Off-canvas dots are dropped
+        canvas.set(4, 0);
+        canvas.set(0, 4);
+    }
+
+    #[test]
+    fn vline_fills_between_ends() {
+        let mut canvas = BrailleCanvas::new(1, 2);
+        canvas.vline(0, 6, 2);
+        assert_eq!(canvas.cell(0, 0), 0x04 | 0x40);
+        assert_eq!(canvas.cell(0, 1), 0x01 | 0x02 | 0x04);
+    }
+
+    #[test]
+    fn bar_tops_clamp_to_blocks() {
+        assert_eq!(bar_top(1), '\u{2581}');
+        assert_eq!(bar_top(8), '\u{2588}');
+        assert_eq!(bar_top(12), '\u{2588}');
+    }
+}
@@ -0,0 +1,175 @@
+use std::time::{Duration, Instant};
+
+use crate::av_sync::VisualOffset;
+use crate::state::AppState;
+use crate::ui::{Color, Rect, RenderBuf, Style};
+
+/// Terminals report key presses but not releases, so notes from the
+/// computer keyboard are shown as held for this long after each press.
+const KEY_HOLD: Duration = Duration::from_millis(300);
+
+/// Strip of piano keys shown in performance mode. Highlights notes held on
+/// the computer keyboard or a MIDI controller, plus notes the sequencer is
+/// currently playing on the selected track.
+pub struct KeyboardStrip {
+    /// Release deadline for notes played from computer keys
+    key_until: [Option<Instant>; 128],
+    /// Notes held down on a MIDI controller
+    midi_held: [bool; 128],
+}
+
+impl KeyboardStrip {
+    pub fn new() -> Self {
+        Self {
+            key_until: [None; 128],
+            midi_held: [false; 128],
+        }
+    }
+
+    /// Note played from the computer keyboard
+    pub fn key_pressed(&mut self, pitch: u8) {
+        self.key_pressed_at(pitch, Instant::now());
+    }
+
+    fn key_pressed_at(&mut self, pitch: u8, now: Instant) {
+        if let Some(slot) = self.key_until.get_mut(pitch as usize) {
+            *slot = Some(now + KEY_HOLD);
+        }
+    }
+
+    /// MIDI note on (`down`) or off
+    pub fn midi_note(&mut self, pitch: u8, down: bool) {
+        if let Some(held) = self.midi_held.get_mut(pitch as usize) {
+            *held = down;
+        }
+    }
+
+    fn is_held_at(&self, pitch: u8, now: Instant) -> bool {
+        let i = pitch as usize;
+        self.midi_held[i] || self.key_until[i].is_some_and(|until| now < until)
+    }
+
+    pub fn is_held(&self, pitch: u8) -> bool {
+        self.is_held_at(pitch, Instant::now())
+    }
+
+    /// Draw the strip. `sounding` holds pitches being played back.
+    pub fn render(&self, area: Rect, buf: &mut RenderBuf, sounding: &[u8]) {
+        if area.width < 12 || area.height < 2 {
+            return;
+        }
+        let low = strip_low_note(area.width);
+        let count = (area.width as usize).min(128 - low as usize);
+        let now = Instant::now();
+
+        for i in 0..count {
+            let pitch = low + i as u8;
+            let x = area.x + i as u16;
+            let black = is_black_key(pitch);
+            let color = if self.is_held_at(pitch, now) {
+                Color::PINK
+            } else if sounding.contains(&pitch) {
+                Color::TEAL
+            } else if black {
+                Color::DARK_GRAY
+            } else {
+                Color::WHITE
+            };
+            let style = Style::new().fg(color);
+            buf.set_cell(x, area.y, '█', style);
+            // White keys extend below the black ones
+            if !black {
+                buf.set_cell(x, area.y + 1, '█', style);
+            } else {
+                buf.set_cell(x, area.y + 1, ' ', Style::new());
+            }
+        }
+    }
+}
+
+impl Default for KeyboardStrip {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_black_key(pitch: u8) -> bool {
+    matches!(pitch % 12, 1 | 3 | 6 | 8 | 10)
+}
+
+/// Lowest note shown: the C that centres the visible octaves on middle C
+fn strip_low_note(width: u16) -> u8 {
+    let octaves = (width / 12).clamp(1, 10) as u8;
+    60u8.saturating_sub(octaves / 2 * 12)
+}
+
+/// Pitches of `(pitch, tick, duration)` notes sounding at `playhead`
+fn sounding_notes(notes: impl IntoIterator<Item = (u8, u32, u32)>, playhead: u32) -> Vec<u8> {
+    let mut pitches: Vec<u8> = notes
+        .into_iter()
+        .filter(|&(_, tick, duration)| tick <= playhead && playhead < tick + duration)
+        .map(|(pitch, _, _)| pitch)
+        .collect();
+    pitches.sort_unstable();
+    pitches.dedup();
+    pitches
+}
+
+/// Notes the sequencer is playing on the selected instrument's track, at
+/// the playhead as drawn
+pub fn playing_on_selected_track(state: &AppState, offset: VisualOffset) -> Vec<u8> {
+    let piano_roll = &state.session.piano_roll;
+    if !piano_roll.playing {
+        return Vec::new();
+    }
+    let Some(inst) = state.instruments.selected_instrument() else {
+        return Vec::new();
+    };
+    piano_roll.track_order.iter()
+        .position(|id| *id == inst.id)
+        .and_then(|idx| piano_roll.track_at(idx))
+        .map(|track| sounding_notes(
+            track.notes.iter().map(|n| (n.pitch, n.tick, n.duration)),
+            offset.playhead(state),
+        ))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_presses_release_after_hold() {
+        let mut strip = KeyboardStrip::new();
+        let now = Instant::now();
+        strip.key_pressed_at(60, now);
+        assert!(strip.is_held_at(60, now + Duration::from_millis(100)));
+        assert!(!strip.is_held_at(60, now + KEY_HOLD));
+    }
+
+    #[test]
+    fn midi_notes_hold_until_note_off() {
+        let mut strip = KeyboardStrip::new();
+        let later = Instant::now() + Duration::from_secs(10);
+        strip.midi_note(64, true);
+        assert!(strip.is_held_at(64, later));
+        strip.midi_note(64, false);
+        assert!(!strip.is_held_at(64, later));
+    }
+
+    #[test]
+    fn sounding_notes_cover_note_span() {
+        let notes = [(60, 0, 480), (64, 480, 480), (60, 240, 480)];
+        assert_eq!(sounding_notes(notes, 0), vec![60]);
+        assert_eq!(sounding_notes(notes, 480), vec![60, 64]);
+        assert_eq!(sounding_notes(notes, 960), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn strip_centres_on_middle_c() {
+        assert_eq!(strip_low_note(24), 48);
+        assert_eq!(strip_low_note(60), 36);
+        assert_eq!(strip_low_note(500), 0);
+    }
+}
@@ -1,3 +1,7 @@
+mod braille;
+mod keyboard_strip;
 mod text_input;
 
+pub use braille::{bar_top, BrailleCanvas};
+pub use keyboard_strip::{playing_on_selected_track, KeyboardStrip};
 pub use text_input::TextInput;
@@ -0,0 +1,136 @@
+//! How often and how recently sources, effects and samples are picked,
+//! for the "recent" and "most used" sections of the add menus.
+//!
+//! Kept in `~/.config/imbolc/usage.toml`, using the same asset keys as
+//! favorites. Recency is an increasing counter rather than a clock, so
+//! picks made in the same second still order correctly.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// Entries shown in each quick list
+pub const QUICK_LIST_LEN: usize = 5;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct Entry {
+    count: u32,
+    /// Value of `Store::seq` at the latest pick
+    last: u64,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct Store {
+    #[serde(default)]
+    seq: u64,
+    #[serde(default)]
+    assets: BTreeMap<String, Entry>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Usage {
+    store: Store,
+}
+
+impl Usage {
+    pub fn load() -> Self {
+        let store = store_path()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|s| toml::from_str(&s).ok())
+            .unwrap_or_default();
+        Self { store }
+    }
+
+    /// Note a pick of the asset with `key`
+    pub fn record(&mut self, key: &str) {
+        self.store.seq += 1;
+        let entry = self.store.assets.entry(key.to_string()).or_default();
+        entry.count += 1;
+        entry.last = self.store.seq;
+    }
+
+    pub fn count(&self, key: &str) -> u32 {
+        self.store.assets.get(key).map_or(0, |e| e.count)
+    }
+
+    /// Ordering value for recency; higher is more recent, 0 is never
+    pub fn last_used(&self, key: &str) -> u64 {
+        self.store.assets.get(key).map_or(0, |e| e.last)
+    }
+
+    /// Recently and most used items among `items`, keyed as in the store,
+    /// at most `QUICK_LIST_LEN` each
+    pub fn quick_lists<T: Clone>(&self, items: &[(String, T)]) -> (Vec<T>, Vec<T>) {
+        let mut used: Vec<&(String, T)> = items.iter().filter(|(key, _)| self.count(key) > 0).collect();
+        used.sort_by_key(|(key, _)| std::cmp::Reverse(self.last_used(key)));
+        let recent = used.iter().take(QUICK_LIST_LEN).map(|(_, item)| item.clone()).collect();
+        // Stable sort, so equal counts stay most recent first
+        used.sort_by_key(|(key, _)| std::cmp::Reverse(self.count(key)));
+        let most_used = used.iter().take(QUICK_LIST_LEN).map(|(_, item)| item.clone()).collect();
+        (recent, most_used)
+    }
+
+    pub fn save(&self) {
+        let Some(path) = store_path() else { return };
+        let result = toml::to_string_pretty(&self.store)
+            .map_err(|e| e.to_string())
+            .and_then(|text| {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                }
+                std::fs::write(&path, text).map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            log::error!("usage: could not save: {}", e);
+        }
+    }
+}
+
+fn store_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("imbolc").join("usage.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(names: &[&str]) -> Vec<(String, String)> {
+        names.iter().map(|n| (n.to_string(), n.to_string())).collect()
+    }
+
+    #[test]
+    fn recent_and_most_used_orderings() {
+        let mut usage = Usage::default();
+        for key in ["saw", "saw", "saw", "sine", "fm", "fm"] {
+            usage.record(key);
+        }
+        let (recent, most) = usage.quick_lists(&items(&["saw", "sine", "fm", "noise"]));
+        assert_eq!(recent, vec!["fm", "sine", "saw"]);
+        assert_eq!(most, vec!["saw", "fm", "sine"]);
+    }
+
+    #[test]
+    fn lists_are_capped() {
+        let mut usage = Usage::default();
+        let names: Vec<String> = (0..8).map(|i| format!("s{}", i)).collect();
+        for name in &names {
+            usage.record(name);
+        }
+        let all: Vec<(String, String)> = names.iter().map(|n| (n.clone(), n.clone())).collect();
+        let (recent, most) = usage.quick_lists(&all);
+        assert_eq!(recent.len(), QUICK_LIST_LEN);
+        assert_eq!(recent[0], "s7");
+        assert_eq!(most.len(), QUICK_LIST_LEN);
+    }
+
+    #[test]
+    fn round_trips_through_toml() {
+        let mut usage = Usage::default();
+        usage.record("effect:Reverb");
+        let text = toml::to_string_pretty(&usage.store).unwrap();
+        let store: Store = toml::from_str(&text).unwrap();
+        assert_eq!(store.assets.get("effect:Reverb").map(|e| e.count), Some(1));
+        assert_eq!(store.seq, 1);
+    }
+}
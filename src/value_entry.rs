@@ -0,0 +1,93 @@
+//! Parsing typed parameter values with optional units.
+//!
+//! Values come back in the units parameters are stored in: dB becomes
+//! linear gain, kHz becomes Hz, ms becomes seconds and % becomes a
+//! fraction. A bare number is taken as-is.
+
+/// Parse text such as "1250", "1.2k", "-6dB", "250ms", "50%" or "174.5 bpm"
+pub fn parse(text: &str) -> Option<f32> {
+    let text = text.trim().to_ascii_lowercase();
+    let split = text
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e')))
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f32 = number.trim().parse().ok()?;
+    let value = match unit.trim() {
+        "" | "hz" | "s" | "sec" | "bpm" | "st" | "c" | "ct" => number,
+        "db" => 10f32.powf(number / 20.0),
+        "k" | "khz" => number * 1000.0,
+        "ms" => number / 1000.0,
+        "%" => number / 100.0,
+        _ => return None,
+    };
+    value.is_finite().then_some(value)
+}
+
+/// Parse a level as linear gain; a bare number is dB, as meters show it
+pub fn parse_level(text: &str) -> Option<f32> {
+    match text.trim().parse::<f32>() {
+        Ok(db) => Some(10f32.powf(db / 20.0)),
+        Err(_) => parse(text),
+    }
+}
+
+/// Parse an amount as a fraction; a bare number is a percentage
+pub fn parse_percent(text: &str) -> Option<f32> {
+    match text.trim().parse::<f32>() {
+        Ok(percent) => Some(percent / 100.0),
+        Err(_) => parse(text),
+    }
+}
+
+/// Text for a parameter's own parser: the parsed number when the text has
+/// a unit, otherwise the text unchanged (enum and bool params keep names)
+pub fn to_param_text(text: &str) -> String {
+    match parse(text) {
+        Some(value) => value.to_string(),
+        None => text.trim().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: Option<f32>, b: f32) -> bool {
+        a.map_or(false, |a| (a - b).abs() < 1e-3)
+    }
+
+    #[test]
+    fn plain_numbers_pass_through() {
+        assert!(close(parse("1250"), 1250.0));
+        assert!(close(parse(" 174.5 "), 174.5));
+        assert!(close(parse("-3"), -3.0));
+    }
+
+    #[test]
+    fn units_convert_to_stored_units() {
+        assert!(close(parse("-6dB"), 0.501));
+        assert!(close(parse("0 db"), 1.0));
+        assert!(close(parse("1.2k"), 1200.0));
+        assert!(close(parse("2.5kHz"), 2500.0));
+        assert!(close(parse("250ms"), 0.25));
+        assert!(close(parse("50%"), 0.5));
+        assert!(close(parse("174.5 BPM"), 174.5));
+    }
+
+    #[test]
+    fn bare_levels_are_db_and_bare_amounts_percent() {
+        assert!(close(parse_level("-6"), 0.501));
+        assert!(close(parse_level("50%"), 0.5));
+        assert!(close(parse_percent("25"), 0.25));
+        assert!(close(parse_percent("-6dB"), 0.501));
+    }
+
+    #[test]
+    fn junk_is_rejected() {
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("saw"), None);
+        assert_eq!(parse("12 parsecs"), None);
+        assert_eq!(to_param_text(" Saw "), "Saw");
+        assert_eq!(to_param_text("50%"), "0.5");
+    }
+}
@@ -0,0 +1,129 @@
+//! Velocity handling for live playing: the velocity of notes played on the
+//! computer keyboard, and the response curve applied to MIDI note input.
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::{Action, InstrumentAction, PianoRollAction};
+
+/// How computer-keyboard notes get their velocity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyVelocityMode {
+    /// Every note plays at the key velocity
+    #[default]
+    Fixed,
+    /// Keys on the number and top letter rows play at the accent velocity
+    Rows,
+}
+
+impl KeyVelocityMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyVelocityMode::Fixed => "Fixed",
+            KeyVelocityMode::Rows => "Row accents",
+        }
+    }
+}
+
+/// Response curve for MIDI note velocities
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VelocityCurve {
+    #[default]
+    Linear,
+    /// Light playing comes out louder
+    Soft,
+    /// Takes a firm touch to play loud
+    Hard,
+}
+
+impl VelocityCurve {
+    pub const ALL: [VelocityCurve; 3] = [VelocityCurve::Linear, VelocityCurve::Soft, VelocityCurve::Hard];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            VelocityCurve::Linear => "Linear",
+            VelocityCurve::Soft => "Soft",
+            VelocityCurve::Hard => "Hard",
+        }
+    }
+
+    pub fn apply(self, velocity: u8) -> u8 {
+        let exponent = match self {
+            VelocityCurve::Linear => return velocity,
+            VelocityCurve::Soft => 0.5,
+            VelocityCurve::Hard => 2.0,
+        };
+        if velocity == 0 {
+            return 0;
+        }
+        let shaped = (velocity.min(127) as f32 / 127.0).powf(exponent) * 127.0;
+        // Never turn a note on into a note off
+        (shaped.round() as u8).max(1)
+    }
+}
+
+/// Upper rows of a QWERTY keyboard, accented in `Rows` mode
+const ACCENT_ROWS: &str = "1234567890-=qwertyuiop[]";
+
+/// Velocity of a note played with the (QWERTY-translated) key `key`
+pub fn key_velocity(mode: KeyVelocityMode, velocity: u8, accent: u8, key: char) -> u8 {
+    match mode {
+        KeyVelocityMode::Rows if ACCENT_ROWS.contains(key.to_ascii_lowercase()) => accent,
+        _ => velocity,
+    }
+}
+
+/// Rewrite the velocity of a note-playing action; other actions pass through
+pub fn map_note_velocity(action: Action, f: impl Fn(u8) -> u8) -> Action {
+    match action {
+        Action::Instrument(InstrumentAction::PlayNote(pitch, velocity)) => {
+            Action::Instrument(InstrumentAction::PlayNote(pitch, f(velocity)))
+        }
+        Action::Instrument(InstrumentAction::PlayNotes(pitches, velocity)) => {
+            Action::Instrument(InstrumentAction::PlayNotes(pitches, f(velocity)))
+        }
+        Action::Instrument(InstrumentAction::PlayDrumPadVelocity(pad, velocity)) => {
+            Action::Instrument(InstrumentAction::PlayDrumPadVelocity(pad, f(velocity)))
+        }
+        Action::PianoRoll(PianoRollAction::PlayNote { pitch, velocity, instrument_id, track }) => {
+            Action::PianoRoll(PianoRollAction::PlayNote { pitch, velocity: f(velocity), instrument_id, track })
+        }
+        Action::PianoRoll(PianoRollAction::PlayNotes { pitches, velocity, instrument_id, track }) => {
+            Action::PianoRoll(PianoRollAction::PlayNotes { pitches, velocity: f(velocity), instrument_id, track })
+        }
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curves_keep_endpoints() {
+        for curve in VelocityCurve::ALL {
+            assert_eq!(curve.apply(0), 0);
+            assert_eq!(curve.apply(127), 127);
+            assert!(curve.apply(1) >= 1);
+        }
+        assert!(VelocityCurve::Soft.apply(64) > 64);
+        assert!(VelocityCurve::Hard.apply(64) < 64);
+        assert_eq!(VelocityCurve::Linear.apply(64), 64);
+    }
+
+    #[test]
+    fn row_accents_only_in_rows_mode() {
+        assert_eq!(key_velocity(KeyVelocityMode::Fixed, 90, 120, 'q'), 90);
+        assert_eq!(key_velocity(KeyVelocityMode::Rows, 90, 120, 'q'), 120);
+        assert_eq!(key_velocity(KeyVelocityMode::Rows, 90, 120, '2'), 120);
+        assert_eq!(key_velocity(KeyVelocityMode::Rows, 90, 120, 'z'), 90);
+    }
+
+    #[test]
+    fn only_note_actions_are_rewritten() {
+        let action = map_note_velocity(Action::Instrument(InstrumentAction::PlayNote(60, 100)), |_| 42);
+        assert!(matches!(action, Action::Instrument(InstrumentAction::PlayNote(60, 42))));
+        assert!(matches!(map_note_velocity(Action::None, |_| 42), Action::None));
+    }
+}
@@ -0,0 +1,191 @@
+//! Where the user was in each project: the pane, the selected instrument,
+//! the mixer selection, the piano roll view and the instrument editor's
+//! cursor.
+//!
+//! Kept in `~/.config/imbolc/views.toml`, keyed by project path, so project
+//! files stay free of UI state. A project's view is written when it is
+//! saved, when another project is loaded and on quit, and put back when
+//! the project is loaded.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::panes::{InstrumentEditPane, PianoRollPane};
+use crate::state::{AppState, MixerSelection};
+use crate::ui::PaneManager;
+
+/// Panes worth coming back to; dialogs and browsers are not
+const RESTORABLE: [&str; 11] = [
+    "home", "instrument", "instrument_edit", "piano_roll", "sequencer", "mixer",
+    "track", "tracker", "automation", "routing", "waveform",
+];
+
+/// Piano roll scroll, zoom and cursor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PianoRollView {
+    pub start_tick: u32,
+    pub bottom_pitch: u8,
+    pub zoom: u8,
+    pub cursor_tick: u32,
+    pub cursor_pitch: u8,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectView {
+    pub pane: String,
+    pub instrument: Option<usize>,
+    /// "master", "instrument:<index>" or "bus:<id>"
+    pub mixer: String,
+    pub edit_row: usize,
+    /// Last, since TOML puts tables after plain values
+    pub piano_roll: Option<PianoRollView>,
+}
+
+fn mixer_key(selection: MixerSelection) -> String {
+    match selection {
+        MixerSelection::Master => "master".to_string(),
+        MixerSelection::Instrument(idx) => format!("instrument:{}", idx),
+        MixerSelection::Bus(id) => format!("bus:{}", id),
+    }
+}
+
+fn parse_mixer(key: &str) -> Option<MixerSelection> {
+    match key.split_once(':') {
+        None if key == "master" => Some(MixerSelection::Master),
+        Some(("instrument", idx)) => idx.parse().ok().map(MixerSelection::Instrument),
+        Some(("bus", id)) => id.parse().ok().map(MixerSelection::Bus),
+        _ => None,
+    }
+}
+
+/// The view as it is now
+pub fn capture(panes: &mut PaneManager, state: &AppState) -> ProjectView {
+    let pane = panes.base_id().to_string();
+    let piano_roll = panes.get_pane_mut::<PianoRollPane>("piano_roll").map(|p| p.view());
+    let edit_row = panes.get_pane_mut::<InstrumentEditPane>("instrument_edit").map_or(0, |p| p.cursor_row());
+    ProjectView {
+        pane,
+        instrument: state.instruments.selected,
+        mixer: mixer_key(state.session.mixer.selection),
+        edit_row,
+        piano_roll,
+    }
+}
+
+/// Put back a saved view, skipping whatever no longer fits the project
+pub fn restore(view: &ProjectView, panes: &mut PaneManager, state: &mut AppState) {
+    let count = state.instruments.instruments.len();
+    if count == 0 {
+        return;
+    }
+    if let Some(idx) = view.instrument {
+        state.instruments.selected = Some(idx.min(count - 1));
+    }
+    let mixer = parse_mixer(&view.mixer).filter(|selection| match selection {
+        MixerSelection::Instrument(idx) => *idx < count,
+        MixerSelection::Bus(id) => state.session.mixer.buses.iter().any(|b| b.id == *id),
+        MixerSelection::Master => true,
+    });
+    if let Some(selection) = mixer {
+        state.session.mixer.selection = selection;
+    }
+    if let (Some(piano_roll), Some(pane)) = (view.piano_roll, panes.get_pane_mut::<PianoRollPane>("piano_roll")) {
+        pane.set_view(piano_roll);
+    }
+    if RESTORABLE.contains(&view.pane.as_str()) {
+        panes.switch_to(&view.pane, state);
+    }
+    // Load the editor even if it was already showing, then place its cursor
+    if let (Some(inst), Some(pane)) = (state.instruments.selected_instrument(), panes.get_pane_mut::<InstrumentEditPane>("instrument_edit")) {
+        pane.set_instrument(inst);
+        pane.set_cursor_row(view.edit_row);
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Store {
+    #[serde(default)]
+    projects: BTreeMap<String, ProjectView>,
+}
+
+#[derive(Debug, Default)]
+pub struct ViewStore {
+    store: Store,
+}
+
+impl ViewStore {
+    pub fn load() -> Self {
+        let store = store_path()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|s| toml::from_str(&s).ok())
+            .unwrap_or_default();
+        Self { store }
+    }
+
+    pub fn get(&self, project: &Path) -> Option<&ProjectView> {
+        self.store.projects.get(&project.display().to_string())
+    }
+
+    pub fn set(&mut self, project: &Path, view: ProjectView) {
+        self.store.projects.insert(project.display().to_string(), view);
+    }
+
+    pub fn save(&self) {
+        let Some(path) = store_path() else { return };
+        let result = toml::to_string_pretty(&self.store)
+            .map_err(|e| e.to_string())
+            .and_then(|text| {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                }
+                std::fs::write(&path, text).map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            log::error!("views: could not save: {}", e);
+        }
+    }
+
+    /// Record where the user is in the current project, if it has a path
+    pub fn remember(&mut self, panes: &mut PaneManager, state: &AppState) {
+        if let Some(path) = state.project.path.as_deref() {
+            self.set(path, capture(panes, state));
+            self.save();
+        }
+    }
+}
+
+fn store_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("imbolc").join("views.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mixer_selection_round_trips() {
+        for selection in [MixerSelection::Master, MixerSelection::Instrument(3), MixerSelection::Bus(2)] {
+            assert_eq!(parse_mixer(&mixer_key(selection)), Some(selection));
+        }
+        assert_eq!(parse_mixer("bus:x"), None);
+    }
+
+    #[test]
+    fn view_survives_toml() {
+        let mut store = Store::default();
+        let view = ProjectView {
+            pane: "piano_roll".into(),
+            instrument: Some(1),
+            mixer: "bus:1".into(),
+            edit_row: 4,
+            piano_roll: Some(PianoRollView { start_tick: 1920, bottom_pitch: 36, zoom: 2, cursor_tick: 2400, cursor_pitch: 60 }),
+        };
+        store.projects.insert("/tmp/song.imbolc".into(), view.clone());
+        let text = toml::to_string_pretty(&store).unwrap();
+        let back: Store = toml::from_str(&text).unwrap();
+        assert_eq!(back.projects["/tmp/song.imbolc"], view);
+    }
+}
@@ -0,0 +1,77 @@
+//! Live voice counts per instrument.
+//!
+//! The engine reports an instrument's voice count from its voice chains
+//! whenever it changes, along with how many voices it stole to stay under
+//! the polyphony limit. Stealing stays flagged for a moment so that a
+//! single stolen voice is still seen.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::state::InstrumentId;
+
+/// How long an instrument shows as stealing after its last stolen voice
+const STEAL_HOLD: Duration = Duration::from_millis(1000);
+
+/// What the mixer and instrument list show for one instrument
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VoiceCount {
+    pub voices: usize,
+    pub stealing: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct VoiceActivity {
+    /// Voice count and when a voice was last stolen
+    counts: HashMap<InstrumentId, (usize, Option<Instant>)>,
+}
+
+impl VoiceActivity {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a count reported by the engine
+    pub fn report(&mut self, instrument_id: InstrumentId, voices: usize, stolen: usize, now: Instant) {
+        let entry = self.counts.entry(instrument_id).or_insert((0, None));
+        entry.0 = voices;
+        if stolen > 0 {
+            entry.1 = Some(now);
+        }
+    }
+
+    /// Whether any instrument has a voice sounding
+    pub fn sounding(&self) -> bool {
+        self.counts.values().any(|(voices, _)| *voices > 0)
+    }
+
+    /// Counts to display at `now`
+    pub fn snapshot(&self, now: Instant) -> HashMap<InstrumentId, VoiceCount> {
+        self.counts
+            .iter()
+            .map(|(&id, &(voices, stolen_at))| {
+                let stealing = stolen_at.is_some_and(|at| now.duration_since(at) < STEAL_HOLD);
+                (id, VoiceCount { voices, stealing })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{AppState, SourceType};
+
+    #[test]
+    fn stealing_is_held_briefly() {
+        let id = AppState::new().add_instrument(SourceType::Saw);
+        let mut activity = VoiceActivity::new();
+        let start = Instant::now();
+        activity.report(id, 8, 2, start);
+        activity.report(id, 6, 0, start + Duration::from_millis(100));
+        let counts = activity.snapshot(start + Duration::from_millis(500));
+        assert_eq!(counts[&id], VoiceCount { voices: 6, stealing: true });
+        let counts = activity.snapshot(start + STEAL_HOLD * 2);
+        assert_eq!(counts[&id], VoiceCount { voices: 6, stealing: false });
+    }
+}
@@ -0,0 +1,223 @@
+//! Crash isolation for VST plugins.
+//!
+//! VSTPlugin runs inside scsynth, so a plugin that crashes takes the whole
+//! server down. The guard notices the server dropping out without being
+//! asked to, blames the plugin that came up most recently, and `recover`
+//! disables that plugin and restarts the server. A second crash soon
+//! after a restart is reported but not restarted again, so a bad guess
+//! can't loop.
+
+use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
+
+use crate::action::{Action, AudioDirty, InstrumentAction, InstrumentUpdate, IoFeedback, ServerAction};
+use crate::audio::AudioHandle;
+use crate::global_actions::{dispatch_and_apply, show_status};
+use crate::panes::ServerPane;
+use crate::state::{AppState, EffectId, EffectType, InstrumentId, SourceType};
+use crate::ui::{Frame, PaneManager};
+
+/// How long after a plugin comes up it is still the likely culprit
+const SUSPECT_WINDOW: Duration = Duration::from_secs(30);
+/// Minimum time between automatic restarts
+const RESTART_COOLDOWN: Duration = Duration::from_secs(20);
+
+/// A VST plugin slot: an instrument's source, or one of its effects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Slot {
+    pub instrument: InstrumentId,
+    pub effect: Option<EffectId>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crash {
+    pub suspect: Option<Slot>,
+    /// False when the last automatic restart was too recent
+    pub restart: bool,
+}
+
+pub struct VstGuard {
+    /// Plugins running at the last poll, with when each came up
+    seen: Vec<(Slot, Instant)>,
+    was_up: bool,
+    /// The user stopped, disconnected or restarted the server
+    expect_down: bool,
+    last_restart: Option<Instant>,
+}
+
+impl VstGuard {
+    pub fn new() -> Self {
+        Self { seen: Vec::new(), was_up: false, expect_down: false, last_restart: None }
+    }
+
+    /// The next time the server goes down, it was asked to
+    pub fn expect_stop(&mut self) {
+        self.expect_down = true;
+    }
+
+    /// Call every frame; returns a crash the moment the server is lost
+    pub fn poll(&mut self, state: &AppState, server_up: bool, now: Instant) -> Option<Crash> {
+        let running = vst_slots(state);
+        self.seen.retain(|(slot, _)| running.contains(slot));
+        for slot in running {
+            if !self.seen.iter().any(|(s, _)| *s == slot) {
+                self.seen.push((slot, now));
+            }
+        }
+
+        if server_up {
+            self.was_up = true;
+            self.expect_down = false;
+            return None;
+        }
+        if !std::mem::take(&mut self.was_up) || std::mem::take(&mut self.expect_down) {
+            return None;
+        }
+
+        let recent = self.seen.iter()
+            .filter(|(_, since)| now.duration_since(*since) <= SUSPECT_WINDOW)
+            .max_by_key(|(_, since)| *since)
+            .map(|(slot, _)| *slot);
+        let only = match self.seen.as_slice() {
+            [(slot, _)] => Some(*slot),
+            _ => None,
+        };
+        let restart = self.last_restart.map_or(true, |at| now.duration_since(at) >= RESTART_COOLDOWN);
+        if restart {
+            self.last_restart = Some(now);
+        }
+        Some(Crash { suspect: recent.or(only), restart })
+    }
+}
+
+impl Default for VstGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Active VST instruments and enabled VST effects
+fn vst_slots(state: &AppState) -> Vec<Slot> {
+    let mut slots = Vec::new();
+    for inst in &state.instruments.instruments {
+        if matches!(inst.source, SourceType::Vst(_)) && inst.active {
+            slots.push(Slot { instrument: inst.id, effect: None });
+        }
+        for effect in &inst.effects {
+            if matches!(effect.effect_type, EffectType::Vst(_)) && effect.enabled {
+                slots.push(Slot { instrument: inst.id, effect: Some(effect.id) });
+            }
+        }
+    }
+    slots
+}
+
+/// Plugin name and where it sits, e.g. "Dexed on Keys"
+pub fn describe(state: &AppState, slot: Slot) -> String {
+    let Some(inst) = state.instruments.instrument(slot.instrument) else {
+        return "a VST plugin".to_string();
+    };
+    let plugin_id = match slot.effect {
+        None => match inst.source {
+            SourceType::Vst(id) => Some(id),
+            _ => None,
+        },
+        Some(effect_id) => inst.effect_by_id(effect_id).and_then(|e| match e.effect_type {
+            EffectType::Vst(id) => Some(id),
+            _ => None,
+        }),
+    };
+    let plugin = plugin_id
+        .and_then(|id| state.session.vst_plugins.get(id))
+        .map_or_else(|| "VST plugin".to_string(), |p| p.name.clone());
+    format!("{} on {}", plugin, inst.name)
+}
+
+/// Deactivate the instrument, or switch the effect off, so the plugin
+/// isn't brought back up by the restart
+pub fn disable(state: &AppState, slot: Slot) -> Option<Action> {
+    let inst = state.instruments.instrument(slot.instrument)?;
+    let mut effects = inst.effects.clone();
+    let mut active = inst.active;
+    match slot.effect {
+        None => active = false,
+        Some(effect_id) => effects.iter_mut().find(|e| e.id == effect_id)?.enabled = false,
+    }
+    Some(Action::Instrument(InstrumentAction::Update(Box::new(InstrumentUpdate {
+        id: inst.id,
+        source: inst.source,
+        source_params: inst.source_params.clone(),
+        filter: inst.filter.clone(),
+        eq: inst.eq.clone(),
+        effects,
+        lfo: inst.lfo.clone(),
+        amp_envelope: inst.amp_envelope.clone(),
+        polyphonic: inst.polyphonic,
+        active,
+    }))))
+}
+
+/// Disable the suspect and restart the server with the devices picked in
+/// the server pane, unless the last restart was too recent. Shows and
+/// returns the message saying what happened.
+pub(crate) fn recover(
+    crash: Crash,
+    state: &mut AppState,
+    panes: &mut PaneManager,
+    audio: &mut AudioHandle,
+    app_frame: &mut Frame,
+    pending_audio_dirty: &mut AudioDirty,
+    io_tx: &Sender<IoFeedback>,
+) -> String {
+    let mut message = "Audio server crashed".to_string();
+    if let Some(slot) = crash.suspect {
+        message = format!("Audio server crashed; disabled {}", describe(state, slot));
+        if let Some(action) = disable(state, slot) {
+            dispatch_and_apply(&action, state, panes, audio, app_frame, pending_audio_dirty, io_tx);
+        }
+    }
+    if crash.restart {
+        message.push_str(", restarting");
+        let restart = panes.get_pane_mut::<ServerPane>("server").map(|p| ServerAction::Restart {
+            input_device: p.selected_input_device(),
+            output_device: p.selected_output_device(),
+        });
+        if let Some(restart) = restart {
+            dispatch_and_apply(&Action::Server(restart), state, panes, audio, app_frame, pending_audio_dirty, io_tx);
+        }
+    } else {
+        message.push_str(" again; start it from the server pane (F5)");
+    }
+    log::error!("{}", message);
+    show_status(panes, audio, &message);
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requested_stop_is_not_a_crash() {
+        let state = AppState::new();
+        let mut guard = VstGuard::new();
+        let now = Instant::now();
+        assert_eq!(guard.poll(&state, true, now), None);
+        guard.expect_stop();
+        assert_eq!(guard.poll(&state, false, now), None);
+    }
+
+    #[test]
+    fn restarts_are_rate_limited() {
+        let state = AppState::new();
+        let mut guard = VstGuard::new();
+        let now = Instant::now();
+        guard.poll(&state, true, now);
+        assert_eq!(guard.poll(&state, false, now), Some(Crash { suspect: None, restart: true }));
+        // Staying down is one crash, not many
+        assert_eq!(guard.poll(&state, false, now), None);
+        guard.poll(&state, true, now);
+        let crash = guard.poll(&state, false, now + Duration::from_secs(1));
+        assert_eq!(crash.map(|c| c.restart), Some(false));
+    }
+}
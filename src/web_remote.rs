@@ -0,0 +1,221 @@
+//! Read-only remote view of the transport and mixer over HTTP, for a phone
+//! or tablet on the LAN (`--web[=port]`).
+//!
+//! `/` serves a self-contained page that polls `/state`, a JSON snapshot
+//! the main loop publishes a few times a second. Requests are answered on
+//! a background thread from the latest snapshot, so a slow client never
+//! holds up the UI. Nothing sent by a client changes the session.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::state::AppState;
+
+/// Default port used when `--web` is given without one
+pub const DEFAULT_PORT: u16 = 8470;
+
+/// How often the snapshot is rebuilt
+const PUBLISH_INTERVAL: Duration = Duration::from_millis(100);
+
+const PAGE: &str = include_str!("web_remote.html");
+
+#[derive(Debug, Serialize)]
+struct Snapshot {
+    playing: bool,
+    looping: bool,
+    bpm: f64,
+    time_signature: [u32; 2],
+    /// Playhead in beats from the start
+    beat: f64,
+    recording: bool,
+    master: Channel,
+    master_peak: [f32; 2],
+    instruments: Vec<Channel>,
+    buses: Vec<Channel>,
+}
+
+#[derive(Debug, Serialize)]
+struct Channel {
+    name: String,
+    level: f32,
+    pan: f32,
+    mute: bool,
+    solo: bool,
+    selected: bool,
+}
+
+fn snapshot(state: &AppState) -> Snapshot {
+    use crate::state::MixerSelection;
+
+    let session = &state.session;
+    let mixer = &session.mixer;
+    let piano_roll = &session.piano_roll;
+    let instruments = state.instruments.instruments.iter().enumerate()
+        .map(|(idx, inst)| Channel {
+            name: inst.name.clone(),
+            level: inst.level,
+            pan: inst.pan,
+            mute: inst.mute,
+            solo: inst.solo,
+            selected: matches!(mixer.selection, MixerSelection::Instrument(i) if i == idx),
+        })
+        .collect();
+    let buses = mixer.buses.iter()
+        .map(|bus| Channel {
+            name: bus.name.clone(),
+            level: bus.level,
+            pan: bus.pan,
+            mute: bus.mute,
+            solo: bus.solo,
+            selected: matches!(mixer.selection, MixerSelection::Bus(id) if id == bus.id),
+        })
+        .collect();
+    let viz = &state.audio.visualization;
+    Snapshot {
+        playing: piano_roll.playing,
+        looping: piano_roll.looping,
+        bpm: session.bpm as f64,
+        time_signature: [session.time_signature.0 as u32, session.time_signature.1 as u32],
+        beat: piano_roll.tick_to_beat(state.audio.playhead) as f64,
+        recording: state.recording.recording,
+        master: Channel {
+            name: "Master".to_string(),
+            level: mixer.master_level,
+            pan: 0.0,
+            mute: mixer.master_mute,
+            solo: false,
+            selected: matches!(mixer.selection, MixerSelection::Master),
+        },
+        master_peak: [viz.peak_l, viz.peak_r],
+        instruments,
+        buses,
+    }
+}
+
+/// Response for a request line such as `GET /state HTTP/1.1`
+fn respond(request_line: &str, snapshot: &str) -> (&'static str, &'static str, String) {
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let path = parts.next().unwrap_or("").split('?').next().unwrap_or("");
+    match (method, path) {
+        ("GET", "/") | ("GET", "/index.html") => ("200 OK", "text/html; charset=utf-8", PAGE.to_string()),
+        ("GET", "/state") => ("200 OK", "application/json", snapshot.to_string()),
+        ("GET", _) => ("404 Not Found", "text/plain", "not found\n".to_string()),
+        _ => ("405 Method Not Allowed", "text/plain", "read-only\n".to_string()),
+    }
+}
+
+fn serve(mut stream: TcpStream, latest: &Mutex<String>) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Headers are read and ignored
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+    let snapshot = latest.lock().map(|s| s.clone()).unwrap_or_default();
+    let (status, content_type, body) = respond(&request_line, &snapshot);
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status, content_type, body.len(), body,
+    )?;
+    stream.flush()
+}
+
+pub struct WebRemote {
+    latest: Arc<Mutex<String>>,
+    last_publish: Option<Instant>,
+    port: u16,
+}
+
+impl WebRemote {
+    pub fn start(port: u16) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(("0.0.0.0", port))?;
+        let latest = Arc::new(Mutex::new(String::from("{}")));
+        let shared = Arc::clone(&latest);
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let shared = Arc::clone(&shared);
+                std::thread::spawn(move || {
+                    if let Err(e) = serve(stream, &shared) {
+                        log::debug!("web remote: {}", e);
+                    }
+                });
+            }
+        });
+        Ok(Self { latest, last_publish: None, port })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Rebuild the snapshot clients see, at most every `PUBLISH_INTERVAL`
+    pub fn publish(&mut self, state: &AppState) {
+        if self.last_publish.map_or(false, |t| t.elapsed() < PUBLISH_INTERVAL) {
+            return;
+        }
+        self.last_publish = Some(Instant::now());
+        match serde_json::to_string(&snapshot(state)) {
+            Ok(json) => {
+                if let Ok(mut latest) = self.latest.lock() {
+                    *latest = json;
+                }
+            }
+            Err(e) => log::error!("web remote: {}", e),
+        }
+    }
+}
+
+pub fn from_args(args: &[String]) -> Option<std::io::Result<WebRemote>> {
+    for arg in args {
+        if arg == "--web" {
+            return Some(WebRemote::start(DEFAULT_PORT));
+        }
+        if let Some(port) = arg.strip_prefix("--web=") {
+            return Some(WebRemote::start(port.parse().unwrap_or(DEFAULT_PORT)));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::SourceType;
+
+    #[test]
+    fn snapshot_lists_mixer_channels() {
+        let mut state = AppState::new();
+        state.add_instrument(SourceType::Saw);
+        state.instruments.instruments[0].name = "Bass".to_string();
+        state.instruments.instruments[0].mute = true;
+
+        let snap = snapshot(&state);
+        assert_eq!(snap.instruments.len(), 1);
+        assert_eq!(snap.instruments[0].name, "Bass");
+        assert!(snap.instruments[0].mute);
+        assert_eq!(snap.buses.len(), state.session.mixer.buses.len());
+        assert!(serde_json::to_string(&snap).unwrap().contains("\"playing\":false"));
+    }
+
+    #[test]
+    fn only_reads_are_served() {
+        assert_eq!(respond("GET /state HTTP/1.1", "{}").2, "{}");
+        assert!(respond("GET / HTTP/1.1", "{}").2.contains("<html"));
+        assert_eq!(respond("GET /nope HTTP/1.1", "{}").0, "404 Not Found");
+        assert_eq!(respond("POST /state HTTP/1.1", "{}").0, "405 Method Not Allowed");
+    }
+
+    #[test]
+    fn parses_web_flag() {
+        assert!(from_args(&["imbolc".into(), "song.sqlite".into()]).is_none());
+    }
+}
@@ -0,0 +1,149 @@
+//! Named workspaces: a pane, the keyboard layer and the piano roll's
+//! automation overlay, recalled with one key.
+//!
+//! Alt+1 to Alt+3 switch to Compose, Mix and Perform; Alt+w saves the
+//! current layout into the workspace last switched to. Workspaces are kept
+//! in preferences, so they apply to every project.
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::AudioHandle;
+use crate::global_actions::show_status;
+use crate::panes::{PianoRollPane, PreferencesPane};
+use crate::preferences::Preferences;
+use crate::state::AppState;
+use crate::ui::{LayerStack, PaneManager};
+
+/// Performance keyboard a workspace turns on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Keys {
+    #[default]
+    Off,
+    Piano,
+    Pad,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Workspace {
+    pub name: String,
+    pub pane: String,
+    pub keys: Keys,
+    pub automation_overlay: bool,
+}
+
+impl Default for Workspace {
+    fn default() -> Self {
+        Self { name: String::new(), pane: "instrument".into(), keys: Keys::Off, automation_overlay: false }
+    }
+}
+
+/// Compose, Mix and Perform as first set up
+pub fn defaults() -> Vec<Workspace> {
+    vec![
+        Workspace { name: "Compose".into(), pane: "piano_roll".into(), keys: Keys::Off, automation_overlay: false },
+        Workspace { name: "Mix".into(), pane: "mixer".into(), keys: Keys::Off, automation_overlay: false },
+        Workspace { name: "Perform".into(), pane: "instrument_edit".into(), keys: Keys::Piano, automation_overlay: false },
+    ]
+}
+
+/// The current layout, under `name`
+pub fn capture(name: &str, panes: &mut PaneManager, layer_stack: &LayerStack) -> Workspace {
+    let keys = if layer_stack.has_layer("pad_mode") {
+        Keys::Pad
+    } else if layer_stack.has_layer("piano_mode") {
+        Keys::Piano
+    } else {
+        Keys::Off
+    };
+    let automation_overlay = panes.get_pane_mut::<PianoRollPane>("piano_roll")
+        .is_some_and(|p| p.automation_overlay());
+    Workspace { name: name.to_string(), pane: panes.base_id().to_string(), keys, automation_overlay }
+}
+
+/// Switch to `workspace`'s pane and set its keyboard layer and overlay
+pub fn apply(workspace: &Workspace, panes: &mut PaneManager, layer_stack: &mut LayerStack, state: &AppState) {
+    panes.active_mut().deactivate_performance();
+    layer_stack.pop("piano_mode");
+    layer_stack.pop("pad_mode");
+    panes.switch_to(&workspace.pane, state);
+    layer_stack.set_pane_layer(panes.active().id());
+
+    let pane = panes.active_mut();
+    pane.deactivate_performance();
+    if pane.supports_performance_mode() {
+        match workspace.keys {
+            Keys::Off => {}
+            Keys::Piano => {
+                pane.activate_piano();
+                layer_stack.push("piano_mode");
+            }
+            Keys::Pad => {
+                pane.activate_pad();
+                layer_stack.push("pad_mode");
+            }
+        }
+    }
+    if let Some(piano_roll) = panes.get_pane_mut::<PianoRollPane>("piano_roll") {
+        piano_roll.set_automation_overlay(workspace.automation_overlay);
+    }
+}
+
+/// Switch to workspace `n` (1-based) and say which one
+pub(crate) fn switch(
+    n: u8,
+    current: &mut usize,
+    prefs: &Preferences,
+    panes: &mut PaneManager,
+    layer_stack: &mut LayerStack,
+    state: &AppState,
+    audio: &AudioHandle,
+) {
+    let idx = n as usize - 1;
+    let status = match prefs.workspaces.get(idx) {
+        Some(ws) => {
+            apply(ws, panes, layer_stack, state);
+            *current = idx;
+            format!("Workspace: {}", ws.name)
+        }
+        None => format!("No workspace {}", n),
+    };
+    show_status(panes, audio, &status);
+}
+
+/// Store the current layout in the workspace last switched to
+pub(crate) fn save_current(
+    current: usize,
+    prefs: &mut Preferences,
+    panes: &mut PaneManager,
+    layer_stack: &LayerStack,
+    audio: &AudioHandle,
+) {
+    let Some(name) = prefs.workspaces.get(current).map(|ws| ws.name.clone()) else { return };
+    prefs.workspaces[current] = capture(&name, panes, layer_stack);
+    if let Err(e) = prefs.save() {
+        log::error!("preferences: could not save: {}", e);
+    }
+    if let Some(pane) = panes.get_pane_mut::<PreferencesPane>("preferences") {
+        pane.set_workspaces(prefs.workspaces.clone());
+    }
+    show_status(panes, audio, &format!("Saved workspace: {}", name));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workspaces_survive_toml() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Doc {
+            workspaces: Vec<Workspace>,
+        }
+        let doc = Doc { workspaces: defaults() };
+        let text = toml::to_string_pretty(&doc).unwrap();
+        assert!(text.contains("keys = \"piano\""));
+        assert_eq!(toml::from_str::<Doc>(&text).unwrap(), doc);
+    }
+}